@@ -0,0 +1,242 @@
+use anyhow::Result;
+use async_lock::RwLock;
+use bon::Builder;
+use comfy_quant_base::convert_to_datetime;
+use comfy_quant_config::app_context::AppContext;
+use comfy_quant_node::{
+    node_core::{ExchangeRateManager, NodeExecutable, TradeStats, TradeStatsExt},
+    stats::{AssertionResult, ExecutionReport, PerformanceReport, ResourceUsage},
+    workflow::Workflow,
+};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use sqlx::PgPool;
+use std::{fmt, fs, path::PathBuf, sync::Arc};
+
+#[derive(Builder, Debug)]
+#[builder(on(_, into))]
+pub struct BacktestOptions {
+    workflow: PathBuf,       // 工作流JSON文件
+    from: Option<String>,    // 回测开始时间，不设置则使用工作流中的时间
+    to: Option<String>,      // 回测结束时间，不设置则使用工作流中的时间
+    report: Option<PathBuf>, // 报告输出目录
+    events: Option<PathBuf>, // 事件日志导出文件，.parquet为Parquet格式，其他为Arrow IPC格式
+    #[builder(default = "USDT".to_string())]
+    quote_asset: String, // 计价资产
+}
+
+// 回测结果摘要
+#[derive(Serialize, Debug)]
+pub struct BacktestSummary {
+    pub initial_capital: Decimal,         // 初始资金
+    pub realized_pnl: Decimal,            // 已实现盈亏
+    pub unrealized_pnl: Decimal,          // 未实现盈亏
+    pub total_pnl: Decimal,               // 总盈亏
+    pub total_return: Decimal,            // 总收益率
+    pub annualized_return: Decimal,       // 年化收益率
+    pub time_weighted_return: Decimal,    // 时间加权收益率
+    pub money_weighted_return: Decimal,   // 资金加权收益率(年化)
+    pub max_drawdown: Decimal,            // 最大回撤比例
+    pub total_trades: u64,                // 总交易次数
+    pub win_rate: Decimal,                // 胜率
+    pub performance: PerformanceReport,   // 绩效报告
+    pub running_time: u128,               // 运行持续时间(微妙)
+    pub resource_usage: ResourceUsage,    // 资源消耗
+    pub execution: Vec<ExecutionReport>,  // 各策略交易对的执行质量
+    pub assertions: Vec<AssertionResult>, // 断言节点的检查结果
+}
+
+impl BacktestSummary {
+    async fn try_from_workflow(workflow: &Workflow) -> Result<Self> {
+        Ok(BacktestSummary {
+            initial_capital: workflow.initial_capital().await?,
+            realized_pnl: workflow.realized_pnl().await?,
+            unrealized_pnl: workflow.unrealized_pnl().await?,
+            total_pnl: workflow.total_pnl().await?,
+            total_return: workflow.total_return().await?,
+            annualized_return: workflow.annualized_return().await?,
+            time_weighted_return: workflow.time_weighted_return().await?,
+            money_weighted_return: workflow.money_weighted_return().await?,
+            max_drawdown: workflow.max_drawdown().await,
+            total_trades: workflow.total_trades().await,
+            win_rate: workflow.win_rate().await,
+            performance: workflow.performance_report().await?,
+            running_time: workflow.running_time().await?,
+            resource_usage: workflow.resource_usage()?,
+            execution: workflow.execution_reports().await,
+            assertions: workflow.assertions().await?,
+        })
+    }
+
+    // 所有断言都通过
+    pub fn passed(&self) -> bool {
+        self.assertions.iter().all(|assertion| assertion.passed)
+    }
+}
+
+impl fmt::Display for BacktestSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "initial capital:   {}", self.initial_capital)?;
+        writeln!(f, "realized pnl:      {}", self.realized_pnl)?;
+        writeln!(f, "unrealized pnl:    {}", self.unrealized_pnl)?;
+        writeln!(f, "total pnl:         {}", self.total_pnl)?;
+        writeln!(f, "total return:      {}", self.total_return)?;
+        writeln!(f, "annualized return: {}", self.annualized_return)?;
+        writeln!(f, "twr:               {}", self.time_weighted_return)?;
+        writeln!(f, "irr:               {}", self.money_weighted_return)?;
+        writeln!(f, "max drawdown:      {}", self.max_drawdown)?;
+        writeln!(f, "total trades:      {}", self.total_trades)?;
+        writeln!(f, "win rate:          {}", self.win_rate)?;
+
+        let fmt_opt = |value: Option<Decimal>| value.map_or("-".to_string(), |v| v.to_string());
+        let performance = &self.performance;
+
+        writeln!(f, "volatility:        {}", fmt_opt(performance.volatility))?;
+        writeln!(
+            f,
+            "sharpe ratio:      {}",
+            fmt_opt(performance.sharpe_ratio)
+        )?;
+        writeln!(
+            f,
+            "sortino ratio:     {}",
+            fmt_opt(performance.sortino_ratio)
+        )?;
+        writeln!(
+            f,
+            "calmar ratio:      {}",
+            fmt_opt(performance.calmar_ratio)
+        )?;
+        writeln!(
+            f,
+            "profit factor:     {}",
+            fmt_opt(performance.profit_factor)
+        )?;
+        writeln!(
+            f,
+            "payoff ratio:      {}",
+            fmt_opt(performance.payoff_ratio)
+        )?;
+        writeln!(f, "exposure time:     {}", performance.exposure_time)?;
+        writeln!(
+            f,
+            "avg duration(s):   {}",
+            performance
+                .avg_trade_duration
+                .map_or("-".to_string(), |v| v.to_string())
+        )?;
+        writeln!(f, "running time(us):  {}", self.running_time)?;
+        writeln!(f, "db rows read:      {}", self.resource_usage.db_rows_read)?;
+        writeln!(
+            f,
+            "db rows written:   {}",
+            self.resource_usage.db_rows_written
+        )?;
+        write!(
+            f,
+            "exchange requests: {}",
+            self.resource_usage.exchange_requests
+        )?;
+
+        for report in &self.execution {
+            let fmt_opt = |value: Option<Decimal>| {
+                value.map_or("-".to_string(), |value| value.round_dp(8).to_string())
+            };
+
+            write!(
+                f,
+                "\n{:?} {}: vwap {} twap {} buy vs vwap(bps) {} sell vs vwap(bps) {} arrival cost {} participation {}",
+                report.exchange,
+                report.symbol,
+                fmt_opt(report.vwap),
+                fmt_opt(report.twap),
+                fmt_opt(report.buy_vs_vwap_bps),
+                fmt_opt(report.sell_vs_vwap_bps),
+                report.arrival_cost,
+                fmt_opt(report.participation_rate),
+            )?;
+        }
+
+        for assertion in &self.assertions {
+            write!(f, "\n{}", assertion)?;
+        }
+
+        Ok(())
+    }
+}
+
+// 运行回测，等待数据回放结束后返回结果摘要
+pub async fn run(options: BacktestOptions) -> Result<BacktestSummary> {
+    let json_str = fs::read_to_string(&options.workflow)?;
+    let mut workflow: Workflow = serde_json::from_str(&json_str)?;
+
+    match (&options.from, &options.to) {
+        (Some(from), Some(to)) => {
+            let start_datetime = convert_to_datetime(from)
+                .ok_or_else(|| anyhow::anyhow!("Invalid from datetime: {}", from))?;
+            let end_datetime = convert_to_datetime(to)
+                .ok_or_else(|| anyhow::anyhow!("Invalid to datetime: {}", to))?;
+
+            anyhow::ensure!(
+                start_datetime < end_datetime,
+                "Backtest from datetime must be earlier than to datetime"
+            );
+
+            workflow.set_backtest_time_range(from, to);
+        }
+        (None, None) => {}
+        _ => anyhow::bail!("Backtest from and to must be set together"),
+    }
+
+    let ctx = AppContext::try_new()?;
+    let summary = execute(
+        &mut workflow,
+        Arc::clone(&ctx.db),
+        &options.quote_asset,
+        options.events.is_some(),
+    )
+    .await?;
+
+    if let Some(events) = &options.events {
+        workflow.event_log()?.export(events)?;
+    }
+
+    if let Some(report) = &options.report {
+        fs::create_dir_all(report)?;
+        fs::write(
+            report.join("summary.json"),
+            serde_json::to_string_pretty(&summary)?,
+        )?;
+        fs::write(
+            report.join("workflow.json"),
+            serde_json::to_string(&workflow)?,
+        )?;
+    }
+
+    Ok(summary)
+}
+
+// 初始化并执行工作流，等待结束后返回结果摘要，record_events为true时记录事件日志
+pub(crate) async fn execute(
+    workflow: &mut Workflow,
+    db: Arc<PgPool>,
+    quote_asset: &str,
+    record_events: bool,
+) -> Result<BacktestSummary> {
+    workflow
+        .setup(
+            db,
+            Arc::new(RwLock::new(ExchangeRateManager::default())),
+            quote_asset,
+        )
+        .await?;
+
+    if record_events {
+        workflow.event_log()?.enable();
+    }
+
+    workflow.execute().await?;
+    workflow.wait().await?;
+
+    BacktestSummary::try_from_workflow(workflow).await
+}
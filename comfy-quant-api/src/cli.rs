@@ -0,0 +1,1246 @@
+use crate::{
+    backtest::{self, BacktestOptions},
+    deploy,
+    optimize::{BacktestObjective, Metric, Optimizer, ParamGrid, ParamSpace, ParamSweep},
+    retention::{self, RetentionOptions},
+    risk::{self, RiskOptions},
+    server::{self, AppState, FailoverOptions},
+    walk_forward::WalkForward,
+};
+use anyhow::Result;
+use chrono::Duration;
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use comfy_quant_base::{convert_to_datetime, Credentials, Exchange, KlineInterval, SecretCipher};
+use comfy_quant_config::app_context::AppContext;
+use comfy_quant_database::api_key::{self, CreateApiKeyParams};
+use comfy_quant_node::workflow::Workflow;
+use rust_decimal::Decimal;
+use std::{fs, io, path::PathBuf};
+
+pub fn command() -> Command {
+    Command::new("comfy-quant-api")
+        .subcommand(
+            Command::new("backtest")
+                .about("Run a backtest from a workflow JSON file")
+                .arg(
+                    Arg::new("workflow")
+                        .long("workflow")
+                        .value_name("PATH")
+                        .value_parser(clap::value_parser!(PathBuf))
+                        .required(true)
+                        .help("Workflow JSON file"),
+                )
+                .arg(
+                    Arg::new("from")
+                        .long("from")
+                        .value_name("DATETIME")
+                        .requires("to")
+                        .help("Backtest start datetime, e.g. \"2024-01-01 00:00:00\""),
+                )
+                .arg(
+                    Arg::new("to")
+                        .long("to")
+                        .value_name("DATETIME")
+                        .requires("from")
+                        .help("Backtest end datetime, e.g. \"2024-01-02 00:00:00\""),
+                )
+                .arg(
+                    Arg::new("report")
+                        .long("report")
+                        .value_name("DIR")
+                        .value_parser(clap::value_parser!(PathBuf))
+                        .help("Directory to write the report bundle"),
+                )
+                .arg(
+                    Arg::new("events")
+                        .long("events")
+                        .value_name("PATH")
+                        .value_parser(clap::value_parser!(PathBuf))
+                        .help("File to export the event log, Parquet if it ends with .parquet, otherwise Arrow IPC"),
+                )
+                .arg(
+                    Arg::new("quote-asset")
+                        .long("quote-asset")
+                        .value_name("ASSET")
+                        .default_value("USDT")
+                        .help("Quote asset used to value the portfolio"),
+                ),
+        )
+        .subcommand(
+            Command::new("risk")
+                .about("Print the VaR and exposure summary of workflows")
+                .arg(
+                    Arg::new("workflow-id")
+                        .long("workflow-id")
+                        .value_name("ID")
+                        .action(ArgAction::Append)
+                        .required(true)
+                        .help("Workflow id, repeat to combine workflows of an account"),
+                )
+                .arg(
+                    Arg::new("confidence")
+                        .long("confidence")
+                        .value_name("LEVEL")
+                        .value_parser(clap::value_parser!(Decimal))
+                        .default_value("0.95")
+                        .help("VaR confidence level"),
+                )
+                .arg(
+                    Arg::new("lookback-days")
+                        .long("lookback-days")
+                        .value_name("DAYS")
+                        .value_parser(clap::value_parser!(i64))
+                        .default_value("365")
+                        .help("Days of kline returns used as scenarios"),
+                )
+                .arg(
+                    Arg::new("interval")
+                        .long("interval")
+                        .value_name("INTERVAL")
+                        .default_value("1d")
+                        .help("Kline interval of the returns"),
+                )
+                .arg(
+                    Arg::new("quote-asset")
+                        .long("quote-asset")
+                        .value_name("ASSET")
+                        .default_value("USDT")
+                        .help("Quote asset used to value the portfolio"),
+                ),
+        )
+        .subcommand(
+            Command::new("clone")
+                .about("Duplicate a workflow with fresh node and link ids")
+                .arg(
+                    Arg::new("workflow")
+                        .long("workflow")
+                        .value_name("PATH")
+                        .value_parser(clap::value_parser!(PathBuf))
+                        .required(true)
+                        .help("Workflow JSON file"),
+                )
+                .arg(
+                    Arg::new("output")
+                        .long("output")
+                        .value_name("PATH")
+                        .value_parser(clap::value_parser!(PathBuf))
+                        .help("File to write the cloned workflow, defaults to stdout"),
+                ),
+        )
+        .subcommand(
+            Command::new("optimize")
+                .about("Search workflow parameters with Bayesian optimization over backtests")
+                .arg(
+                    Arg::new("workflow")
+                        .long("workflow")
+                        .value_name("PATH")
+                        .value_parser(clap::value_parser!(PathBuf))
+                        .required(true)
+                        .help("Workflow JSON file"),
+                )
+                .arg(
+                    Arg::new("from")
+                        .long("from")
+                        .value_name("DATETIME")
+                        .required(true)
+                        .help("Backtest start datetime, e.g. \"2024-01-01 00:00:00\""),
+                )
+                .arg(
+                    Arg::new("to")
+                        .long("to")
+                        .value_name("DATETIME")
+                        .required(true)
+                        .help("Backtest end datetime, e.g. \"2024-01-02 00:00:00\""),
+                )
+                .arg(
+                    Arg::new("param")
+                        .long("param")
+                        .value_name("SPEC")
+                        .action(ArgAction::Append)
+                        .required(true)
+                        .help("Parameter to search, NODE_ID:INDEX:LOW:HIGH[:int], repeat for more"),
+                )
+                .arg(
+                    Arg::new("trials")
+                        .long("trials")
+                        .value_name("N")
+                        .value_parser(clap::value_parser!(usize))
+                        .default_value("50")
+                        .help("Number of trials, including resumed ones"),
+                )
+                .arg(
+                    Arg::new("metric")
+                        .long("metric")
+                        .value_name("METRIC")
+                        .value_parser([
+                            "total_return",
+                            "annualized_return",
+                            "twr",
+                            "irr",
+                            "total_pnl",
+                        ])
+                        .default_value("total_return")
+                        .help("Backtest metric to maximize"),
+                )
+                .arg(
+                    Arg::new("history")
+                        .long("history")
+                        .value_name("PATH")
+                        .value_parser(clap::value_parser!(PathBuf))
+                        .help("Trial history file, an existing file resumes the optimization"),
+                )
+                .arg(
+                    Arg::new("seed")
+                        .long("seed")
+                        .value_name("SEED")
+                        .value_parser(clap::value_parser!(u64))
+                        .default_value("0")
+                        .help("Random seed of the sampler"),
+                )
+                .arg(
+                    Arg::new("quote-asset")
+                        .long("quote-asset")
+                        .value_name("ASSET")
+                        .default_value("USDT")
+                        .help("Quote asset used to value the portfolio"),
+                ),
+        )
+        .subcommand(
+            Command::new("sweep")
+                .about("Backtest every combination of a parameter grid in parallel and store the results")
+                .arg(
+                    Arg::new("workflow")
+                        .long("workflow")
+                        .value_name("PATH")
+                        .value_parser(clap::value_parser!(PathBuf))
+                        .required(true)
+                        .help("Workflow JSON file"),
+                )
+                .arg(
+                    Arg::new("from")
+                        .long("from")
+                        .value_name("DATETIME")
+                        .required(true)
+                        .help("Backtest start datetime, e.g. \"2024-01-01 00:00:00\""),
+                )
+                .arg(
+                    Arg::new("to")
+                        .long("to")
+                        .value_name("DATETIME")
+                        .required(true)
+                        .help("Backtest end datetime, e.g. \"2024-01-02 00:00:00\""),
+                )
+                .arg(
+                    Arg::new("param")
+                        .long("param")
+                        .value_name("SPEC")
+                        .action(ArgAction::Append)
+                        .required(true)
+                        .help("Parameter grid, NODE_ID:INDEX:START:END:STEP[:int], repeat for more"),
+                )
+                .arg(
+                    Arg::new("workers")
+                        .long("workers")
+                        .value_name("N")
+                        .value_parser(clap::value_parser!(usize).range(1..))
+                        .help("Number of backtests to run at the same time, defaults to the CPU count"),
+                )
+                .arg(
+                    Arg::new("name")
+                        .long("name")
+                        .value_name("NAME")
+                        .help("Sweep id in optimization_runs, defaults to the workflow content hash"),
+                )
+                .arg(
+                    Arg::new("top")
+                        .long("top")
+                        .value_name("N")
+                        .value_parser(clap::value_parser!(usize))
+                        .default_value("10")
+                        .help("Number of best combinations to print"),
+                )
+                .arg(
+                    Arg::new("quote-asset")
+                        .long("quote-asset")
+                        .value_name("ASSET")
+                        .default_value("USDT")
+                        .help("Quote asset used to value the portfolio"),
+                ),
+        )
+        .subcommand(
+            Command::new("walk-forward")
+                .about("Optimize on rolling in-sample windows and backtest the best parameters out of sample")
+                .arg(
+                    Arg::new("workflow")
+                        .long("workflow")
+                        .value_name("PATH")
+                        .value_parser(clap::value_parser!(PathBuf))
+                        .required(true)
+                        .help("Workflow JSON file"),
+                )
+                .arg(
+                    Arg::new("from")
+                        .long("from")
+                        .value_name("DATETIME")
+                        .required(true)
+                        .help("Backtest start datetime, e.g. \"2024-01-01 00:00:00\""),
+                )
+                .arg(
+                    Arg::new("to")
+                        .long("to")
+                        .value_name("DATETIME")
+                        .required(true)
+                        .help("Backtest end datetime, e.g. \"2024-07-01 00:00:00\""),
+                )
+                .arg(
+                    Arg::new("param")
+                        .long("param")
+                        .value_name("SPEC")
+                        .action(ArgAction::Append)
+                        .required(true)
+                        .help("Parameter grid, NODE_ID:INDEX:START:END:STEP[:int], repeat for more"),
+                )
+                .arg(
+                    Arg::new("in-sample-days")
+                        .long("in-sample-days")
+                        .value_name("DAYS")
+                        .value_parser(clap::value_parser!(i64).range(1..))
+                        .required(true)
+                        .help("Length of each in-sample window"),
+                )
+                .arg(
+                    Arg::new("out-of-sample-days")
+                        .long("out-of-sample-days")
+                        .value_name("DAYS")
+                        .value_parser(clap::value_parser!(i64).range(1..))
+                        .required(true)
+                        .help("Length of each out-of-sample window, also the step between windows"),
+                )
+                .arg(
+                    Arg::new("anchored")
+                        .long("anchored")
+                        .action(ArgAction::SetTrue)
+                        .help("Keep in-sample windows starting at the backtest start instead of rolling"),
+                )
+                .arg(
+                    Arg::new("workers")
+                        .long("workers")
+                        .value_name("N")
+                        .value_parser(clap::value_parser!(usize).range(1..))
+                        .help("Number of backtests to run at the same time, defaults to the CPU count"),
+                )
+                .arg(
+                    Arg::new("name")
+                        .long("name")
+                        .value_name("NAME")
+                        .help("Sweep id in optimization_runs, defaults to the workflow content hash"),
+                )
+                .arg(
+                    Arg::new("quote-asset")
+                        .long("quote-asset")
+                        .value_name("ASSET")
+                        .default_value("USDT")
+                        .help("Quote asset used to value the portfolio"),
+                ),
+        )
+        .subcommand(
+            Command::new("archive")
+                .about("Archive rows older than the retention policy to compressed files, then delete them")
+                .arg(
+                    Arg::new("table")
+                        .long("table")
+                        .value_name("TABLE")
+                        .action(ArgAction::Append)
+                        .help("Table to archive, repeat for more, defaults to all configured tables"),
+                )
+                .arg(
+                    Arg::new("days")
+                        .long("days")
+                        .value_name("DAYS")
+                        .value_parser(clap::value_parser!(u32))
+                        .help("Retention days, overrides the configured value"),
+                )
+                .arg(
+                    Arg::new("dir")
+                        .long("dir")
+                        .value_name("DIR")
+                        .value_parser(clap::value_parser!(PathBuf))
+                        .help("Directory to write the archive files, overrides the configured value"),
+                )
+                .arg(
+                    Arg::new("dry-run")
+                        .long("dry-run")
+                        .action(ArgAction::SetTrue)
+                        .help("Only count the expired rows without archiving or deleting"),
+                ),
+        )
+        .subcommand(
+            Command::new("deploy")
+                .about("Deploy a workflow, re-posting an identical definition returns the existing version")
+                .arg(
+                    Arg::new("workflow")
+                        .long("workflow")
+                        .value_name("PATH")
+                        .value_parser(clap::value_parser!(PathBuf))
+                        .required(true)
+                        .help("Workflow JSON file"),
+                )
+                .arg(
+                    Arg::new("workflow-id")
+                        .long("workflow-id")
+                        .value_name("ID")
+                        .help("Logical workflow id, a changed definition creates a new version of it"),
+                ),
+        )
+        .subcommand(
+            Command::new("api-key")
+                .about("Manage exchange API keys referenced by name from account nodes")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("set")
+                        .about("Encrypt and save an API key, replacing the one with the same name")
+                        .arg(
+                            Arg::new("name")
+                                .long("name")
+                                .value_name("NAME")
+                                .required(true)
+                                .help("Name referenced by account nodes"),
+                        )
+                        .arg(
+                            Arg::new("exchange")
+                                .long("exchange")
+                                .value_name("EXCHANGE")
+                                .value_parser(["binance", "okx", "bybit"])
+                                .default_value("binance")
+                                .help("Exchange of the API key"),
+                        )
+                        .arg(
+                            Arg::new("api-key")
+                                .long("api-key")
+                                .value_name("KEY")
+                                .required(true)
+                                .help("API key"),
+                        )
+                        .arg(
+                            Arg::new("secret-key")
+                                .long("secret-key")
+                                .value_name("SECRET")
+                                .help("Secret key, read from stdin if omitted"),
+                        ),
+                )
+                .subcommand(
+                    Command::new("delete").about("Delete an API key").arg(
+                        Arg::new("name")
+                            .long("name")
+                            .value_name("NAME")
+                            .required(true)
+                            .help("Name of the API key"),
+                    ),
+                )
+                .subcommand(Command::new("list").about("List the names of saved API keys"))
+                .subcommand(
+                    Command::new("gen-master-key")
+                        .about("Generate a master key for the CREDENTIALS_MASTER_KEY environment variable"),
+                ),
+        )
+        .subcommand(
+            Command::new("serve")
+                .about("Run the HTTP API server, resuming workflows that were running before exit")
+                .arg(
+                    Arg::new("addr")
+                        .long("addr")
+                        .value_name("ADDR")
+                        .help("Address to listen on, overrides the configured value"),
+                )
+                .arg(
+                    Arg::new("instance-id")
+                        .long("instance-id")
+                        .value_name("ID")
+                        .help("Stable id of this instance, a restarted instance resumes its own workflows at once"),
+                )
+                .arg(
+                    Arg::new("standby")
+                        .long("standby")
+                        .action(ArgAction::SetTrue)
+                        .help("Take over workflows whose instance stopped sending heartbeats"),
+                )
+                .arg(
+                    Arg::new("heartbeat-secs")
+                        .long("heartbeat-secs")
+                        .value_name("SECS")
+                        .value_parser(clap::value_parser!(u64).range(1..))
+                        .default_value("5")
+                        .help("Interval of workflow lease heartbeats"),
+                )
+                .arg(
+                    Arg::new("takeover-secs")
+                        .long("takeover-secs")
+                        .value_name("SECS")
+                        .value_parser(clap::value_parser!(i64).range(1..))
+                        .default_value("30")
+                        .help("Missed heartbeat time after which a standby takes over a workflow"),
+                ),
+        )
+}
+
+// 运行回测子命令
+pub async fn backtest(args: &ArgMatches) -> Result<()> {
+    let options = BacktestOptions::builder()
+        .workflow(
+            args.get_one::<PathBuf>("workflow")
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("Missing workflow"))?,
+        )
+        .maybe_from(args.get_one::<String>("from").cloned())
+        .maybe_to(args.get_one::<String>("to").cloned())
+        .maybe_report(args.get_one::<PathBuf>("report").cloned())
+        .maybe_events(args.get_one::<PathBuf>("events").cloned())
+        .maybe_quote_asset(args.get_one::<String>("quote-asset").cloned())
+        .build();
+
+    let summary = backtest::run(options).await?;
+
+    println!("{}", summary);
+
+    // 断言失败时以非零状态退出，便于在CI中运行回归测试
+    anyhow::ensure!(summary.passed(), "Backtest assertions failed");
+
+    Ok(())
+}
+
+// 输出风险摘要子命令
+pub async fn risk(args: &ArgMatches) -> Result<()> {
+    let options = RiskOptions::builder()
+        .workflow_ids(
+            args.get_many::<String>("workflow-id")
+                .ok_or_else(|| anyhow::anyhow!("Missing workflow id"))?
+                .cloned()
+                .collect::<Vec<_>>(),
+        )
+        .maybe_confidence(args.get_one::<Decimal>("confidence").cloned())
+        .maybe_lookback_days(args.get_one::<i64>("lookback-days").cloned())
+        .maybe_interval(
+            args.get_one::<String>("interval")
+                .map(|interval| KlineInterval::from(interval.as_str())),
+        )
+        .maybe_quote_asset(args.get_one::<String>("quote-asset").cloned())
+        .build();
+
+    let ctx = AppContext::try_new()?;
+    let summary = risk::daily_summary(&ctx.db, options).await?;
+
+    println!("{}", summary);
+
+    Ok(())
+}
+
+// 复制工作流子命令
+pub fn clone(args: &ArgMatches) -> Result<()> {
+    let path = args
+        .get_one::<PathBuf>("workflow")
+        .ok_or_else(|| anyhow::anyhow!("Missing workflow"))?;
+
+    let workflow: Workflow = serde_json::from_str(&fs::read_to_string(path)?)?;
+    let json_str = serde_json::to_string_pretty(&workflow.clone_with_new_ids())?;
+
+    match args.get_one::<PathBuf>("output") {
+        Some(output) => fs::write(output, json_str)?,
+        None => println!("{}", json_str),
+    }
+
+    Ok(())
+}
+
+// 参数优化子命令
+pub async fn optimize(args: &ArgMatches) -> Result<()> {
+    let path = args
+        .get_one::<PathBuf>("workflow")
+        .ok_or_else(|| anyhow::anyhow!("Missing workflow"))?;
+
+    let datetime = |name: &str| {
+        let value = args
+            .get_one::<String>(name)
+            .ok_or_else(|| anyhow::anyhow!("Missing {}", name))?;
+
+        convert_to_datetime(value)
+            .ok_or_else(|| anyhow::anyhow!("Invalid {} datetime: {}", name, value))
+    };
+
+    let start_datetime = datetime("from")?;
+    let end_datetime = datetime("to")?;
+
+    anyhow::ensure!(
+        start_datetime < end_datetime,
+        "Backtest from datetime must be earlier than to datetime"
+    );
+
+    let space = args
+        .get_many::<String>("param")
+        .ok_or_else(|| anyhow::anyhow!("Missing param"))?
+        .map(|spec| spec.parse::<ParamSpace>())
+        .collect::<Result<Vec<_>>>()?;
+
+    let metric = args
+        .get_one::<String>("metric")
+        .map(|metric| metric.parse::<Metric>())
+        .transpose()?
+        .unwrap_or(Metric::TotalReturn);
+
+    let ctx = AppContext::try_new()?;
+
+    let objective = BacktestObjective::builder()
+        .workflow(fs::read_to_string(path)?)
+        .space(space.clone())
+        .start_datetime(start_datetime)
+        .end_datetime(end_datetime)
+        .db(ctx.db)
+        .metric(metric)
+        .maybe_quote_asset(args.get_one::<String>("quote-asset").cloned())
+        .build();
+
+    let optimizer = Optimizer::builder()
+        .space(space)
+        .n_trials(args.get_one::<usize>("trials").copied().unwrap_or(50))
+        .seed(args.get_one::<u64>("seed").copied().unwrap_or_default())
+        .maybe_history(args.get_one::<PathBuf>("history").cloned())
+        .build();
+
+    let history = optimizer.run(&objective).await?;
+
+    println!("{}", history);
+
+    Ok(())
+}
+
+// 参数扫描子命令
+pub async fn sweep(args: &ArgMatches) -> Result<()> {
+    let path = args
+        .get_one::<PathBuf>("workflow")
+        .ok_or_else(|| anyhow::anyhow!("Missing workflow"))?;
+
+    let datetime = |name: &str| {
+        let value = args
+            .get_one::<String>(name)
+            .ok_or_else(|| anyhow::anyhow!("Missing {}", name))?;
+
+        convert_to_datetime(value)
+            .ok_or_else(|| anyhow::anyhow!("Invalid {} datetime: {}", name, value))
+    };
+
+    let start_datetime = datetime("from")?;
+    let end_datetime = datetime("to")?;
+
+    anyhow::ensure!(
+        start_datetime < end_datetime,
+        "Backtest from datetime must be earlier than to datetime"
+    );
+
+    let grids = args
+        .get_many::<String>("param")
+        .ok_or_else(|| anyhow::anyhow!("Missing param"))?
+        .map(|spec| spec.parse::<ParamGrid>())
+        .collect::<Result<Vec<_>>>()?;
+
+    let ctx = AppContext::try_new()?;
+
+    let sweep = ParamSweep::builder()
+        .workflow(fs::read_to_string(path)?)
+        .grids(grids)
+        .start_datetime(start_datetime)
+        .end_datetime(end_datetime)
+        .db(ctx.db)
+        .maybe_optimization_id(args.get_one::<String>("name").cloned())
+        .maybe_workers(args.get_one::<usize>("workers").copied())
+        .maybe_quote_asset(args.get_one::<String>("quote-asset").cloned())
+        .build();
+
+    let runs = sweep.run().await?;
+    let top = args.get_one::<usize>("top").copied().unwrap_or(10);
+
+    println!("combinations: {}", runs.len());
+
+    for run in runs.iter().take(top) {
+        let fmt_opt = |value: Option<Decimal>| {
+            value.map_or("-".to_string(), |value| value.round_dp(6).to_string())
+        };
+
+        println!(
+            "{} {} total return {} max drawdown {} win rate {}",
+            run.status,
+            run.params,
+            fmt_opt(run.total_return),
+            fmt_opt(run.max_drawdown),
+            fmt_opt(run.win_rate),
+        );
+    }
+
+    Ok(())
+}
+
+// 前推分析子命令
+pub async fn walk_forward(args: &ArgMatches) -> Result<()> {
+    let path = args
+        .get_one::<PathBuf>("workflow")
+        .ok_or_else(|| anyhow::anyhow!("Missing workflow"))?;
+
+    let datetime = |name: &str| {
+        let value = args
+            .get_one::<String>(name)
+            .ok_or_else(|| anyhow::anyhow!("Missing {}", name))?;
+
+        convert_to_datetime(value)
+            .ok_or_else(|| anyhow::anyhow!("Invalid {} datetime: {}", name, value))
+    };
+
+    let days = |name: &str| {
+        args.get_one::<i64>(name)
+            .map(|days| Duration::days(*days))
+            .ok_or_else(|| anyhow::anyhow!("Missing {}", name))
+    };
+
+    let start_datetime = datetime("from")?;
+    let end_datetime = datetime("to")?;
+
+    anyhow::ensure!(
+        start_datetime < end_datetime,
+        "Backtest from datetime must be earlier than to datetime"
+    );
+
+    let grids = args
+        .get_many::<String>("param")
+        .ok_or_else(|| anyhow::anyhow!("Missing param"))?
+        .map(|spec| spec.parse::<ParamGrid>())
+        .collect::<Result<Vec<_>>>()?;
+
+    let ctx = AppContext::try_new()?;
+
+    let walk_forward = WalkForward::builder()
+        .workflow(fs::read_to_string(path)?)
+        .grids(grids)
+        .start_datetime(start_datetime)
+        .end_datetime(end_datetime)
+        .in_sample(days("in-sample-days")?)
+        .out_of_sample(days("out-of-sample-days")?)
+        .anchored(args.get_flag("anchored"))
+        .db(ctx.db)
+        .maybe_optimization_id(args.get_one::<String>("name").cloned())
+        .maybe_workers(args.get_one::<usize>("workers").copied())
+        .maybe_quote_asset(args.get_one::<String>("quote-asset").cloned())
+        .build();
+
+    let report = walk_forward.run().await?;
+
+    println!("{}", report);
+
+    Ok(())
+}
+
+// 归档过期数据子命令
+pub async fn archive(args: &ArgMatches) -> Result<()> {
+    let options = RetentionOptions::builder()
+        .tables(
+            args.get_many::<String>("table")
+                .map(|tables| tables.cloned().collect())
+                .unwrap_or_default(),
+        )
+        .maybe_days(args.get_one::<u32>("days").copied())
+        .maybe_archive_dir(args.get_one::<PathBuf>("dir").cloned())
+        .dry_run(args.get_flag("dry-run"))
+        .build();
+
+    let ctx = AppContext::try_new()?;
+    let summary = retention::run(&ctx.db, &ctx.setting.retention, options).await?;
+
+    println!("{}", summary);
+
+    Ok(())
+}
+
+// 部署工作流子命令
+pub async fn deploy(args: &ArgMatches) -> Result<()> {
+    let path = args
+        .get_one::<PathBuf>("workflow")
+        .ok_or_else(|| anyhow::anyhow!("Missing workflow"))?;
+
+    let ctx = AppContext::try_new()?;
+    let deployment = deploy::deploy(
+        &ctx.db,
+        &fs::read_to_string(path)?,
+        args.get_one::<String>("workflow-id").map(String::as_str),
+    )
+    .await?;
+
+    println!("{}", deployment);
+
+    Ok(())
+}
+
+// API密钥管理子命令，密钥使用 CREDENTIALS_MASTER_KEY 加密后保存
+pub async fn api_key(args: &ArgMatches) -> Result<()> {
+    match args.subcommand() {
+        Some(("set", args)) => {
+            let name = args
+                .get_one::<String>("name")
+                .ok_or_else(|| anyhow::anyhow!("Missing name"))?;
+
+            let key = args
+                .get_one::<String>("api-key")
+                .ok_or_else(|| anyhow::anyhow!("Missing api key"))?;
+
+            // 不在命令行中传入时从标准输入读取，避免留在 shell 历史中
+            let secret = match args.get_one::<String>("secret-key") {
+                Some(secret) => secret.clone(),
+                None => {
+                    let mut secret = String::new();
+                    io::stdin().read_line(&mut secret)?;
+                    secret.trim().to_string()
+                }
+            };
+
+            anyhow::ensure!(!secret.is_empty(), "Missing secret key");
+
+            let exchange = args
+                .get_one::<String>("exchange")
+                .map(|exchange| Exchange::from(exchange.as_str()))
+                .unwrap_or_default();
+
+            let data = CreateApiKeyParams::builder()
+                .name(name)
+                .exchange(exchange)
+                .credentials(Credentials::new(key, secret))
+                .build();
+
+            let ctx = AppContext::try_new()?;
+            let row = api_key::create_or_update(&ctx.db, &SecretCipher::from_env()?, data).await?;
+
+            println!("saved api key {} ({})", row.name, row.exchange);
+        }
+        Some(("delete", args)) => {
+            let name = args
+                .get_one::<String>("name")
+                .ok_or_else(|| anyhow::anyhow!("Missing name"))?;
+
+            let ctx = AppContext::try_new()?;
+            api_key::delete(&ctx.db, name).await?;
+
+            println!("deleted api key {}", name);
+        }
+        Some(("list", _)) => {
+            let ctx = AppContext::try_new()?;
+
+            for row in api_key::list(&ctx.db).await? {
+                println!("{} {} {}", row.name, row.exchange, row.updated_at);
+            }
+        }
+        Some(("gen-master-key", _)) => println!("{}", SecretCipher::generate_key()?),
+        _ => anyhow::bail!("Missing api-key subcommand"),
+    }
+
+    Ok(())
+}
+
+// HTTP服务子命令
+pub async fn serve(args: &ArgMatches) -> Result<()> {
+    let ctx = AppContext::try_new()?;
+    let retention = ctx.setting.retention.clone();
+    let admin_token = ctx.setting.server.admin_token.clone();
+    let addr = args
+        .get_one::<String>("addr")
+        .cloned()
+        .unwrap_or_else(|| ctx.setting.server.addr.clone());
+
+    let failover = FailoverOptions::builder()
+        .maybe_instance_id(args.get_one::<String>("instance-id").cloned())
+        .standby(args.get_flag("standby"))
+        .maybe_heartbeat_secs(args.get_one::<u64>("heartbeat-secs").copied())
+        .maybe_takeover_secs(args.get_one::<i64>("takeover-secs").copied())
+        .build();
+
+    let state = AppState::new(ctx.db)
+        .with_failover(failover)
+        .with_retention(retention)
+        .with_admin_token(admin_token);
+
+    server::serve(&addr, state).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backtest_command() -> Result<()> {
+        let matches = command().try_get_matches_from([
+            "comfy-quant-api",
+            "backtest",
+            "--workflow",
+            "workflow.json",
+            "--from",
+            "2024-01-01 00:00:00",
+            "--to",
+            "2024-01-02 00:00:00",
+            "--report",
+            "out/",
+            "--events",
+            "out/events.parquet",
+        ])?;
+
+        let (name, args) = matches
+            .subcommand()
+            .ok_or_else(|| anyhow::anyhow!("Missing subcommand"))?;
+
+        assert_eq!(name, "backtest");
+        assert_eq!(
+            args.get_one::<PathBuf>("workflow"),
+            Some(&PathBuf::from("workflow.json"))
+        );
+        assert_eq!(
+            args.get_one::<String>("quote-asset"),
+            Some(&"USDT".to_string())
+        );
+        assert_eq!(
+            args.get_one::<PathBuf>("events"),
+            Some(&PathBuf::from("out/events.parquet"))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_backtest_command_requires_range() {
+        let result = command().try_get_matches_from([
+            "comfy-quant-api",
+            "backtest",
+            "--workflow",
+            "workflow.json",
+            "--from",
+            "2024-01-01 00:00:00",
+        ]);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_risk_command() -> Result<()> {
+        let matches = command().try_get_matches_from([
+            "comfy-quant-api",
+            "risk",
+            "--workflow-id",
+            "jEnbRDqQu4UN6y7cgQgp6",
+            "--workflow-id",
+            "wTkYQ3sSPUvqJ3Ft8LnbB",
+            "--confidence",
+            "0.99",
+        ])?;
+
+        let (name, args) = matches
+            .subcommand()
+            .ok_or_else(|| anyhow::anyhow!("Missing subcommand"))?;
+
+        assert_eq!(name, "risk");
+        assert_eq!(args.get_many::<String>("workflow-id").unwrap().count(), 2);
+        assert_eq!(
+            args.get_one::<Decimal>("confidence"),
+            Some(&"0.99".parse()?)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_clone_command() -> Result<()> {
+        let matches = command().try_get_matches_from([
+            "comfy-quant-api",
+            "clone",
+            "--workflow",
+            "workflow.json",
+            "--output",
+            "workflow_copy.json",
+        ])?;
+
+        let (name, args) = matches
+            .subcommand()
+            .ok_or_else(|| anyhow::anyhow!("Missing subcommand"))?;
+
+        assert_eq!(name, "clone");
+        assert_eq!(
+            args.get_one::<PathBuf>("output"),
+            Some(&PathBuf::from("workflow_copy.json"))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_optimize_command() -> Result<()> {
+        let matches = command().try_get_matches_from([
+            "comfy-quant-api",
+            "optimize",
+            "--workflow",
+            "workflow.json",
+            "--from",
+            "2024-01-01 00:00:00",
+            "--to",
+            "2024-01-02 00:00:00",
+            "--param",
+            "3:1:1:1.05",
+            "--param",
+            "3:3:4:20:int",
+            "--history",
+            "trials.json",
+        ])?;
+
+        let (name, args) = matches
+            .subcommand()
+            .ok_or_else(|| anyhow::anyhow!("Missing subcommand"))?;
+
+        assert_eq!(name, "optimize");
+        assert_eq!(args.get_many::<String>("param").unwrap().count(), 2);
+        assert_eq!(args.get_one::<usize>("trials"), Some(&50));
+        assert_eq!(
+            args.get_one::<String>("metric"),
+            Some(&"total_return".to_string())
+        );
+
+        let result = command().try_get_matches_from([
+            "comfy-quant-api",
+            "optimize",
+            "--workflow",
+            "workflow.json",
+            "--from",
+            "2024-01-01 00:00:00",
+            "--to",
+            "2024-01-02 00:00:00",
+        ]);
+        assert!(result.is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sweep_command() -> Result<()> {
+        let matches = command().try_get_matches_from([
+            "comfy-quant-api",
+            "sweep",
+            "--workflow",
+            "workflow.json",
+            "--from",
+            "2024-01-01 00:00:00",
+            "--to",
+            "2024-01-02 00:00:00",
+            "--param",
+            "3:3:5:50:5:int",
+            "--param",
+            "3:1:1:1.05:0.01",
+            "--workers",
+            "4",
+        ])?;
+
+        let (name, args) = matches
+            .subcommand()
+            .ok_or_else(|| anyhow::anyhow!("Missing subcommand"))?;
+
+        assert_eq!(name, "sweep");
+        assert_eq!(args.get_many::<String>("param").unwrap().count(), 2);
+        assert_eq!(args.get_one::<usize>("workers"), Some(&4));
+        assert_eq!(args.get_one::<String>("name"), None);
+
+        let result = command().try_get_matches_from([
+            "comfy-quant-api",
+            "sweep",
+            "--workflow",
+            "workflow.json",
+            "--from",
+            "2024-01-01 00:00:00",
+            "--to",
+            "2024-01-02 00:00:00",
+            "--param",
+            "3:3:5:50:5:int",
+            "--workers",
+            "0",
+        ]);
+        assert!(result.is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_walk_forward_command() -> Result<()> {
+        let matches = command().try_get_matches_from([
+            "comfy-quant-api",
+            "walk-forward",
+            "--workflow",
+            "workflow.json",
+            "--from",
+            "2024-01-01 00:00:00",
+            "--to",
+            "2024-07-01 00:00:00",
+            "--param",
+            "3:3:5:50:5:int",
+            "--in-sample-days",
+            "60",
+            "--out-of-sample-days",
+            "20",
+            "--anchored",
+        ])?;
+
+        let (name, args) = matches
+            .subcommand()
+            .ok_or_else(|| anyhow::anyhow!("Missing subcommand"))?;
+
+        assert_eq!(name, "walk-forward");
+        assert_eq!(args.get_one::<i64>("in-sample-days"), Some(&60));
+        assert_eq!(args.get_one::<i64>("out-of-sample-days"), Some(&20));
+        assert!(args.get_flag("anchored"));
+
+        let result = command().try_get_matches_from([
+            "comfy-quant-api",
+            "walk-forward",
+            "--workflow",
+            "workflow.json",
+            "--from",
+            "2024-01-01 00:00:00",
+            "--to",
+            "2024-07-01 00:00:00",
+            "--param",
+            "3:3:5:50:5:int",
+            "--in-sample-days",
+            "60",
+        ]);
+        assert!(result.is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_archive_command() -> Result<()> {
+        let matches = command().try_get_matches_from([
+            "comfy-quant-api",
+            "archive",
+            "--table",
+            "strategy_journals",
+            "--table",
+            "strategy_spot_positions",
+            "--days",
+            "30",
+            "--dry-run",
+        ])?;
+
+        let (name, args) = matches
+            .subcommand()
+            .ok_or_else(|| anyhow::anyhow!("Missing subcommand"))?;
+
+        assert_eq!(name, "archive");
+        assert_eq!(args.get_many::<String>("table").unwrap().count(), 2);
+        assert_eq!(args.get_one::<u32>("days"), Some(&30));
+        assert!(args.get_flag("dry-run"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_deploy_command() -> Result<()> {
+        let matches = command().try_get_matches_from([
+            "comfy-quant-api",
+            "deploy",
+            "--workflow",
+            "workflow.json",
+            "--workflow-id",
+            "jEnbRDqQu4UN6y7cgQgp6",
+        ])?;
+
+        let (name, args) = matches
+            .subcommand()
+            .ok_or_else(|| anyhow::anyhow!("Missing subcommand"))?;
+
+        assert_eq!(name, "deploy");
+        assert_eq!(
+            args.get_one::<String>("workflow-id"),
+            Some(&"jEnbRDqQu4UN6y7cgQgp6".to_string())
+        );
+
+        let result = command().try_get_matches_from(["comfy-quant-api", "deploy"]);
+        assert!(result.is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_serve_command() -> Result<()> {
+        let matches = command().try_get_matches_from([
+            "comfy-quant-api",
+            "serve",
+            "--addr",
+            "0.0.0.0:8080",
+        ])?;
+
+        let (name, args) = matches
+            .subcommand()
+            .ok_or_else(|| anyhow::anyhow!("Missing subcommand"))?;
+
+        assert_eq!(name, "serve");
+        assert_eq!(
+            args.get_one::<String>("addr"),
+            Some(&"0.0.0.0:8080".to_string())
+        );
+        assert!(!args.get_flag("standby"));
+        assert_eq!(args.get_one::<u64>("heartbeat-secs"), Some(&5));
+
+        let matches = command().try_get_matches_from([
+            "comfy-quant-api",
+            "serve",
+            "--standby",
+            "--instance-id",
+            "vps-2",
+            "--takeover-secs",
+            "60",
+        ])?;
+        let (_, args) = matches
+            .subcommand()
+            .ok_or_else(|| anyhow::anyhow!("Missing subcommand"))?;
+
+        assert!(args.get_flag("standby"));
+        assert_eq!(
+            args.get_one::<String>("instance-id"),
+            Some(&"vps-2".to_string())
+        );
+        assert_eq!(args.get_one::<i64>("takeover-secs"), Some(&60));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_api_key_command() -> Result<()> {
+        let matches = command().try_get_matches_from([
+            "comfy-quant-api",
+            "api-key",
+            "set",
+            "--name",
+            "main",
+            "--api-key",
+            "key",
+        ])?;
+
+        let (_, args) = matches
+            .subcommand()
+            .ok_or_else(|| anyhow::anyhow!("Missing subcommand"))?;
+        let (name, args) = args
+            .subcommand()
+            .ok_or_else(|| anyhow::anyhow!("Missing api-key subcommand"))?;
+
+        assert_eq!(name, "set");
+        assert_eq!(args.get_one::<String>("name"), Some(&"main".to_string()));
+        assert_eq!(
+            args.get_one::<String>("exchange"),
+            Some(&"binance".to_string())
+        );
+        assert!(args.get_one::<String>("secret-key").is_none());
+
+        // 必须指定子命令
+        assert!(command()
+            .try_get_matches_from(["comfy-quant-api", "api-key"])
+            .is_err());
+
+        Ok(())
+    }
+}
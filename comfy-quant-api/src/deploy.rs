@@ -0,0 +1,83 @@
+use anyhow::Result;
+use comfy_quant_base::generate_workflow_id;
+use comfy_quant_database::workflow_deployment::{
+    self, CreateWorkflowDeploymentParams, WorkflowDeployment,
+};
+use comfy_quant_node::workflow::Workflow;
+use sqlx::PgPool;
+use std::fmt;
+
+#[derive(Debug)]
+pub struct Deployment {
+    pub deployment: WorkflowDeployment, // 部署的版本
+    pub created: bool,                  // 是否创建了新版本，相同定义重复部署时为false
+}
+
+impl fmt::Display for Deployment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status = if self.created { "created" } else { "unchanged" };
+
+        write!(
+            f,
+            "workflow {} version {} ({}) {}",
+            self.deployment.workflow_id,
+            self.deployment.version,
+            &self.deployment.content_hash[..12],
+            status
+        )
+    }
+}
+
+// 幂等部署工作流定义：相同内容返回已有版本，内容变化时在同一逻辑工作流下创建新版本。
+// 未指定工作流ID时按内容查找，找不到则创建新的逻辑工作流
+pub async fn deploy(
+    db: &PgPool,
+    definition: &str,
+    workflow_id: Option<&str>,
+) -> Result<Deployment> {
+    let workflow: Workflow = serde_json::from_str(definition)?;
+    let content_hash = workflow.content_hash()?;
+
+    if let Some(deployment) =
+        workflow_deployment::find_by_hash(db, workflow_id, &content_hash).await?
+    {
+        return Ok(Deployment {
+            deployment,
+            created: false,
+        });
+    }
+
+    let workflow_id = workflow_id
+        .map(ToString::to_string)
+        .unwrap_or_else(generate_workflow_id);
+
+    let data = CreateWorkflowDeploymentParams::builder()
+        .workflow_id(&workflow_id)
+        .content_hash(&content_hash)
+        .definition(definition)
+        .build();
+
+    if let Some(deployment) = workflow_deployment::create(db, data).await? {
+        tracing::info!(
+            monotonic_counter.workflow_deployed = 1_u64,
+            workflow_id = %deployment.workflow_id,
+            version = deployment.version,
+            "Workflow deployed"
+        );
+
+        return Ok(Deployment {
+            deployment,
+            created: true,
+        });
+    }
+
+    // 并发部署了相同的定义
+    let deployment = workflow_deployment::find_by_hash(db, Some(&workflow_id), &content_hash)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Workflow deployment not found: {}", workflow_id))?;
+
+    Ok(Deployment {
+        deployment,
+        created: false,
+    })
+}
@@ -0,0 +1,184 @@
+use crate::backtest::{self, BacktestSummary};
+use anyhow::Result;
+use comfy_quant_base::{convert_to_datetime, Exchange, KlineInterval, Market, Symbol};
+use comfy_quant_database::kline::{self, CreateKlineParams};
+use comfy_quant_node::workflow::Workflow;
+use rust_decimal::{prelude::FromPrimitive, Decimal};
+use sqlx::PgPool;
+use std::{f64::consts::PI, fmt, str::FromStr, sync::Arc};
+
+// 示例数据使用虚构的交易对，避免覆盖数据库中真实的K线
+const BASE_ASSET: &str = "DEMO";
+const QUOTE_ASSET: &str = "USDT";
+const START_DATETIME: &str = "2024-01-01 00:00:00";
+const END_DATETIME: &str = "2024-01-01 01:00:00";
+
+const BASE_PRICE: f64 = 100.0; // 中枢价格
+const AMPLITUDE: f64 = 4.0; // 振幅
+const PERIOD_SECS: f64 = 600.0; // 周期(秒)
+const BULK_INSERT_SIZE: usize = 1000; // 每条语句写入的K线数量
+
+const GRID_BACKTEST: &str = r#"{"last_node_id":4,"last_link_id":3,"nodes":[{"id":1,"type":"数据/币安现货行情(回测)","pos":[210,58],"order":0,"mode":0,"outputs":[{"name":"现货交易对","type":"SpotPairInfo","links":[1],"slot_index":0},{"name":"Tick数据流","type":"TickStream","links":[2],"slot_index":1}],"properties":{"type":"data.BacktestSpotTicker","params":["DEMO","USDT","2024-01-01 00:00:00","2024-01-01 01:00:00"]}},{"id":2,"type":"账户/币安账户(回测)","pos":[224,295],"order":1,"mode":0,"outputs":[{"name":"现货账户客户端","type":"SpotClient","links":[3],"slot_index":0}],"properties":{"type":"client.BacktestSpotClient","params":[0.001,[["USDT",1000]]]}},{"id":3,"type":"策略/网格(现货)","pos":[520,93],"order":2,"mode":0,"inputs":[{"name":"现货交易对","type":"SpotPairInfo","link":1},{"name":"现货账户客户端","type":"SpotClient","link":3},{"name":"Tick数据流","type":"TickStream","link":2}],"properties":{"type":"strategy.SpotGrid","params":["arithmetic",96,104,8,1000,"","","",true]}},{"id":4,"type":"测试/断言","pos":[520,420],"order":3,"mode":0,"properties":{"type":"test.Assert","params":["total_trades",1,null]}}],"links":[[1,1,0,3,0,"SpotPairInfo"],[2,1,1,3,2,"TickStream"],[3,2,0,3,1,"SpotClient"]],"groups":[],"config":{},"extra":{},"version":0.4}"#;
+
+const GEOMETRIC_GRID_BACKTEST: &str = r#"{"last_node_id":4,"last_link_id":3,"nodes":[{"id":1,"type":"数据/币安现货行情(回测)","pos":[210,58],"order":0,"mode":0,"outputs":[{"name":"现货交易对","type":"SpotPairInfo","links":[1],"slot_index":0},{"name":"Tick数据流","type":"TickStream","links":[2],"slot_index":1}],"properties":{"type":"data.BacktestSpotTicker","params":["DEMO","USDT","2024-01-01 00:00:00","2024-01-01 01:00:00"]}},{"id":2,"type":"账户/币安账户(回测)","pos":[224,295],"order":1,"mode":0,"outputs":[{"name":"现货账户客户端","type":"SpotClient","links":[3],"slot_index":0}],"properties":{"type":"client.BacktestSpotClient","params":[0.001,[["USDT",1000]]]}},{"id":3,"type":"策略/网格(现货)","pos":[520,93],"order":2,"mode":0,"inputs":[{"name":"现货交易对","type":"SpotPairInfo","link":1},{"name":"现货账户客户端","type":"SpotClient","link":3},{"name":"Tick数据流","type":"TickStream","link":2}],"properties":{"type":"strategy.SpotGrid","params":["geometric",95,105,10,1000,"",90,"",true]}},{"id":4,"type":"测试/断言","pos":[520,420],"order":3,"mode":0,"properties":{"type":"test.Assert","params":["total_trades",1,null]}}],"links":[[1,1,0,3,0,"SpotPairInfo"],[2,1,1,3,2,"TickStream"],[3,2,0,3,1,"SpotClient"]],"groups":[],"config":{},"extra":{},"version":0.4}"#;
+
+// 内置的示例工作流，使用随示例安装的K线数据，无需连接交易所即可回测。
+// 目前只有网格策略节点，定投和均线交叉等示例待对应的策略节点实现后再补充
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fixture {
+    GridBacktest,          // 等差网格回测
+    GeometricGridBacktest, // 等比网格回测，带止损
+}
+
+impl Fixture {
+    pub const ALL: [Fixture; 2] = [Fixture::GridBacktest, Fixture::GeometricGridBacktest];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Fixture::GridBacktest => "grid_backtest",
+            Fixture::GeometricGridBacktest => "geometric_grid_backtest",
+        }
+    }
+
+    // 工作流定义(JSON)
+    pub fn definition(&self) -> &'static str {
+        match self {
+            Fixture::GridBacktest => GRID_BACKTEST,
+            Fixture::GeometricGridBacktest => GEOMETRIC_GRID_BACKTEST,
+        }
+    }
+
+    pub fn workflow(&self) -> Result<Workflow> {
+        Ok(serde_json::from_str(self.definition())?)
+    }
+}
+
+impl FromStr for Fixture {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Fixture::ALL
+            .into_iter()
+            .find(|fixture| fixture.name() == s)
+            .ok_or_else(|| anyhow::anyhow!("Unknown fixture: {}", s))
+    }
+}
+
+impl fmt::Display for Fixture {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+// 示例使用的K线：价格围绕中枢做正弦波动，结果可复现
+pub fn klines() -> Result<Vec<CreateKlineParams>> {
+    let start_datetime = datetime(START_DATETIME)?;
+    let end_datetime = datetime(END_DATETIME)?;
+    let seconds = (end_datetime - start_datetime).num_seconds();
+    let price = |secs: i64| {
+        let price = BASE_PRICE + AMPLITUDE * (2.0 * PI * secs as f64 / PERIOD_SECS).sin();
+        Decimal::from_f64(price)
+            .map(|price| price.round_dp(2))
+            .ok_or_else(|| anyhow::anyhow!("Invalid fixture price: {}", price))
+    };
+
+    (0..=seconds)
+        .map(|secs| {
+            let open_price = price(secs)?;
+            let close_price = price(secs + 1)?;
+
+            Ok(CreateKlineParams::builder()
+                .exchange(Exchange::Binance)
+                .market(Market::Spot)
+                .symbol(symbol())
+                .interval(KlineInterval::OneSecond)
+                .open_time(start_datetime + chrono::Duration::seconds(secs))
+                .open_price(open_price)
+                .high_price(open_price.max(close_price))
+                .low_price(open_price.min(close_price))
+                .close_price(close_price)
+                .volume(Decimal::ONE)
+                .build())
+        })
+        .collect()
+}
+
+// 安装示例所需的K线数据，数据已完整时跳过，返回写入的K线数量
+pub async fn install(db: &PgPool) -> Result<usize> {
+    let klines = klines()?;
+    let count = kline::time_range_klines_count(
+        db,
+        &Exchange::Binance,
+        &Market::Spot,
+        &symbol(),
+        &KlineInterval::OneSecond,
+        &datetime(START_DATETIME)?,
+        &datetime(END_DATETIME)?,
+    )
+    .await?;
+
+    if count == klines.len() {
+        return Ok(0);
+    }
+
+    let total = klines.len();
+    kline::ensure_partitions(db, &datetime(START_DATETIME)?, &datetime(END_DATETIME)?).await?;
+
+    for chunk in klines.chunks(BULK_INSERT_SIZE) {
+        kline::bulk_upsert(db, chunk).await?;
+    }
+
+    tracing::info!("Installed {} fixture klines", total);
+
+    Ok(total)
+}
+
+// 安装数据并运行示例回测，返回结果摘要
+pub async fn run(db: Arc<PgPool>, fixture: Fixture) -> Result<BacktestSummary> {
+    install(&db).await?;
+
+    let mut workflow = fixture.workflow()?;
+    backtest::execute(&mut workflow, db, QUOTE_ASSET, false).await
+}
+
+fn symbol() -> Symbol {
+    format!("{}{}", BASE_ASSET, QUOTE_ASSET).into()
+}
+
+fn datetime(datetime: &str) -> Result<chrono::DateTime<chrono::Utc>> {
+    convert_to_datetime(datetime)
+        .ok_or_else(|| anyhow::anyhow!("Invalid fixture datetime: {}", datetime))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixture_workflows() -> Result<()> {
+        for fixture in Fixture::ALL {
+            fixture.workflow()?.validate()?;
+            assert_eq!(fixture.name().parse::<Fixture>()?, fixture);
+        }
+
+        assert!("dca_backtest".parse::<Fixture>().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fixture_klines() -> Result<()> {
+        let klines = klines()?;
+
+        // 起止时间都包含在内
+        assert_eq!(klines.len(), 3601);
+        assert_eq!(klines[0].open_price, Decimal::from(100));
+        assert_eq!(klines[0].close_price, klines[1].open_price);
+        assert!(klines
+            .iter()
+            .all(|kline| kline.low_price >= Decimal::from(96)
+                && kline.high_price <= Decimal::from(104)));
+
+        Ok(())
+    }
+}
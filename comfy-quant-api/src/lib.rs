@@ -1,2 +1,13 @@
 // comfy-quant-api
-pub mod helper;
+pub mod backtest;
+pub mod cli;
+pub mod deploy;
+pub mod fixtures;
+pub mod net_value;
+pub mod optimize;
+pub mod param_preview;
+pub mod retention;
+pub mod risk;
+pub mod server;
+pub mod walk_forward;
+pub mod workflow_metrics;
@@ -1,25 +1,33 @@
-use comfy_quant_api::helper::init_tracing_subscriber;
+use comfy_quant_api::cli;
+use comfy_quant_config::setting::Setting;
+use comfy_quant_observability::ObservabilityOptions;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    let server_name = "comfy-quant-api".to_string();
-    let _guard = init_tracing_subscriber(server_name)?;
+    let setting = Setting::try_new()?;
+    let options = ObservabilityOptions {
+        service_name: "comfy-quant-api".to_string(),
+        ..setting.observability
+    };
+    let _guard = comfy_quant_observability::init(&options)?;
 
-    foo().await;
+    let matches = cli::command().get_matches();
 
-    tokio::time::sleep(std::time::Duration::from_secs(10)).await;
+    match matches.subcommand() {
+        Some(("backtest", args)) => return cli::backtest(args).await,
+        Some(("risk", args)) => return cli::risk(args).await,
+        Some(("clone", args)) => return cli::clone(args),
+        Some(("optimize", args)) => return cli::optimize(args).await,
+        Some(("sweep", args)) => return cli::sweep(args).await,
+        Some(("walk-forward", args)) => return cli::walk_forward(args).await,
+        Some(("archive", args)) => return cli::archive(args).await,
+        Some(("deploy", args)) => return cli::deploy(args).await,
+        Some(("api-key", args)) => return cli::api_key(args).await,
+        Some(("serve", args)) => return cli::serve(args).await,
+        _ => {}
+    }
 
-    Ok(())
-}
+    cli::command().print_help()?;
 
-#[tracing::instrument]
-async fn foo() {
-    tracing::info!(
-        monotonic_counter.foo = 1_u64,
-        key_1 = "bar",
-        key_2 = 10,
-        "handle foo",
-    );
-
-    tracing::info!(histogram.baz = 10, "histogram example",);
+    Ok(())
 }
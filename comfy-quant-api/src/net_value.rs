@@ -0,0 +1,399 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use comfy_quant_base::{Exchange, KlineInterval, Market, Symbol};
+use comfy_quant_database::{
+    kline, strategy_net_value,
+    strategy_spot_position::{self, StrategySpotPosition},
+    strategy_spot_stats::{self, StrategySpotStats},
+};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use sqlx::PgPool;
+use std::{
+    collections::HashMap,
+    fmt::Write,
+    sync::{Arc, Mutex},
+    time::{self, Instant},
+};
+
+// 重采样结果的默认缓存时间
+const CACHE_TTL: time::Duration = time::Duration::from_secs(60);
+
+// 净值点
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NetValue {
+    pub timestamp: DateTime<Utc>, // 持仓快照时间
+    pub value: Decimal,           // 持仓市值(计价资产)
+    pub net_value: Decimal,       // 净值，持仓市值 / 初始市值
+    pub drawdown: Decimal,        // 相对历史最高净值的回撤
+}
+
+// 策略节点某个交易对的净值序列
+#[derive(Debug, Serialize)]
+pub struct NetValueSeries {
+    pub node_id: i16,              // 策略节点ID
+    pub exchange: Exchange,        // 交易所
+    pub symbol: Symbol,            // 交易对
+    pub net_values: Vec<NetValue>, // 净值序列
+}
+
+// 工作流中策略节点的净值序列，优先读取运行时保存的净值快照，
+// 没有快照时(如早于净值快照功能的工作流)由持仓快照和1分钟K线收盘价计算
+pub async fn list(
+    db: &PgPool,
+    workflow_id: &str,
+    node_id: Option<i16>,
+    start_datetime: &DateTime<Utc>,
+    end_datetime: &DateTime<Utc>,
+) -> Result<Vec<NetValueSeries>> {
+    let interval = KlineInterval::OneMinute;
+    let stats = strategy_spot_stats::list_by_workflow(db, workflow_id).await?;
+    let mut series = vec![];
+
+    for stats in stats
+        .iter()
+        .filter(|stats| node_id.is_none_or(|node_id| stats.node_id == node_id))
+    {
+        let snapshots = strategy_net_value::list(
+            db,
+            workflow_id,
+            stats.node_id,
+            &stats.exchange,
+            &stats.symbol,
+            start_datetime,
+            end_datetime,
+        )
+        .await?;
+
+        if !snapshots.is_empty() {
+            series.push(NetValueSeries {
+                node_id: stats.node_id,
+                exchange: stats.exchange.clone(),
+                symbol: stats.symbol.clone(),
+                net_values: snapshots
+                    .into_iter()
+                    .map(|snapshot| NetValue {
+                        timestamp: snapshot.snapshot_at,
+                        value: snapshot.value,
+                        net_value: snapshot.net_value,
+                        drawdown: snapshot.drawdown,
+                    })
+                    .collect(),
+            });
+            continue;
+        }
+
+        let positions = strategy_spot_position::list(
+            db,
+            workflow_id,
+            stats.node_id,
+            stats.exchange.clone(),
+            &stats.symbol,
+            start_datetime,
+            end_datetime,
+        )
+        .await?;
+
+        // 多取一根K线，保证第一个快照之前有收盘价
+        let closes = kline::list(
+            db,
+            &stats.exchange,
+            &Market::Spot,
+            &stats.symbol,
+            &interval,
+            &(*start_datetime - Duration::minutes(1)),
+            end_datetime,
+        )
+        .await?
+        .into_iter()
+        .map(|kline| (kline.open_time, kline.close_price))
+        .collect::<Vec<_>>();
+
+        series.push(NetValueSeries {
+            node_id: stats.node_id,
+            exchange: stats.exchange.clone(),
+            symbol: stats.symbol.clone(),
+            net_values: net_values(initial_value(stats), &positions, &closes),
+        });
+    }
+
+    Ok(series)
+}
+
+fn initial_value(stats: &StrategySpotStats) -> Decimal {
+    stats.initial_base_balance * stats.initial_price + stats.initial_quote_balance
+}
+
+// 每个持仓快照按快照时间之前最近的收盘价估值，没有收盘价的快照跳过
+pub(crate) fn net_values(
+    initial_value: Decimal,
+    positions: &[StrategySpotPosition],
+    closes: &[(DateTime<Utc>, Decimal)],
+) -> Vec<NetValue> {
+    if initial_value <= Decimal::ZERO {
+        return vec![];
+    }
+
+    let mut max_net_value = Decimal::ZERO;
+
+    positions
+        .iter()
+        .filter_map(|position| {
+            let index = closes.partition_point(|(open_time, _)| *open_time <= position.created_at);
+            let (_, price) = closes.get(index.checked_sub(1)?)?;
+
+            let value = position.base_asset_balance * price + position.quote_asset_balance;
+            let net_value = value / initial_value;
+            max_net_value = max_net_value.max(net_value);
+
+            Some(NetValue {
+                timestamp: position.created_at,
+                value,
+                net_value,
+                drawdown: Decimal::ONE - net_value / max_net_value,
+            })
+        })
+        .collect()
+}
+
+// 按时间间隔重采样，每个区间取最后一个净值点，时间戳对齐到区间开始时间
+pub fn resample(net_values: &[NetValue], interval: &KlineInterval) -> Vec<NetValue> {
+    let secs = interval.to_seconds();
+
+    if secs == 0 {
+        return net_values.to_vec();
+    }
+
+    let mut resampled: Vec<NetValue> = vec![];
+
+    for net_value in net_values {
+        let timestamp = net_value.timestamp.timestamp();
+        let Some(bucket) = DateTime::from_timestamp(timestamp - timestamp.rem_euclid(secs), 0)
+        else {
+            continue;
+        };
+
+        let point = NetValue {
+            timestamp: bucket,
+            ..net_value.clone()
+        };
+
+        match resampled.last_mut() {
+            Some(last) if last.timestamp == bucket => *last = point,
+            _ => resampled.push(point),
+        }
+    }
+
+    resampled
+}
+
+// 净值序列导出为CSV，每行一个净值点
+pub fn to_csv(series: &[NetValueSeries]) -> String {
+    let mut csv = "node_id,exchange,symbol,timestamp,value,net_value,drawdown\n".to_string();
+
+    for series in series {
+        for net_value in &series.net_values {
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{},{},{}",
+                series.node_id,
+                series.exchange,
+                series.symbol,
+                net_value.timestamp.to_rfc3339(),
+                net_value.value,
+                net_value.net_value,
+                net_value.drawdown,
+            );
+        }
+    }
+
+    csv
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    workflow_id: String,
+    node_id: Option<i16>,
+    interval: String,
+    start_datetime: DateTime<Utc>,
+    end_datetime: DateTime<Utc>,
+}
+
+// 按工作流/节点缓存重采样后的净值序列，避免图表轮询时每次都从持仓快照和K线重新计算
+#[derive(Debug)]
+pub struct NetValueCache {
+    ttl: time::Duration,
+    entries: Mutex<HashMap<CacheKey, (Instant, Arc<Vec<NetValueSeries>>)>>,
+}
+
+impl Default for NetValueCache {
+    fn default() -> Self {
+        NetValueCache::new(CACHE_TTL)
+    }
+}
+
+impl NetValueCache {
+    pub fn new(ttl: time::Duration) -> Self {
+        NetValueCache {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // 重采样后的净值序列，缓存未过期时直接返回
+    pub async fn resampled(
+        &self,
+        db: &PgPool,
+        workflow_id: &str,
+        node_id: Option<i16>,
+        interval: &KlineInterval,
+        start_datetime: &DateTime<Utc>,
+        end_datetime: &DateTime<Utc>,
+    ) -> Result<Arc<Vec<NetValueSeries>>> {
+        let key = CacheKey {
+            workflow_id: workflow_id.to_string(),
+            node_id,
+            interval: interval.to_string(),
+            start_datetime: *start_datetime,
+            end_datetime: *end_datetime,
+        };
+
+        if let Some(series) = self.get(&key) {
+            return Ok(series);
+        }
+
+        let series = list(db, workflow_id, node_id, start_datetime, end_datetime)
+            .await?
+            .into_iter()
+            .map(|series| NetValueSeries {
+                net_values: resample(&series.net_values, interval),
+                ..series
+            })
+            .collect::<Vec<_>>();
+        let series = Arc::new(series);
+
+        self.insert(key, Arc::clone(&series));
+
+        Ok(series)
+    }
+
+    fn get(&self, key: &CacheKey) -> Option<Arc<Vec<NetValueSeries>>> {
+        let entries = self.entries.lock().ok()?;
+        let (created_at, series) = entries.get(key)?;
+
+        (created_at.elapsed() < self.ttl).then(|| Arc::clone(series))
+    }
+
+    // 写入时顺便清理过期的缓存
+    fn insert(&self, key: CacheKey, series: Arc<Vec<NetValueSeries>>) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.retain(|_, (created_at, _)| created_at.elapsed() < self.ttl);
+            entries.insert(key, (Instant::now(), series));
+        }
+    }
+
+    // 工作流重新部署或重置后清除它的缓存
+    pub fn invalidate(&self, workflow_id: &str) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.retain(|key, _| key.workflow_id != workflow_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use comfy_quant_base::secs_to_datetime;
+    use rust_decimal_macros::dec;
+
+    fn position(secs: i64, base: Decimal, quote: Decimal) -> Result<StrategySpotPosition> {
+        Ok(StrategySpotPosition {
+            id: 1,
+            workflow_id: "jEnbRDqQu4UN6y7cgQgp6".to_string(),
+            node_id: 1,
+            node_name: "SpotGrid".to_string(),
+            exchange: Exchange::Binance,
+            symbol: "BTCUSDT".into(),
+            base_asset: "BTC".to_string(),
+            quote_asset: "USDT".to_string(),
+            base_asset_balance: base,
+            quote_asset_balance: quote,
+            realized_pnl: Decimal::ZERO,
+            created_at: secs_to_datetime(secs)?,
+        })
+    }
+
+    #[test]
+    fn test_net_values() -> Result<()> {
+        let closes = vec![
+            (secs_to_datetime(60)?, dec!(100)),
+            (secs_to_datetime(120)?, dec!(120)),
+            (secs_to_datetime(180)?, dec!(90)),
+        ];
+        let positions = vec![
+            position(30, dec!(1), dec!(900))?, // 没有收盘价，跳过
+            position(90, dec!(1), dec!(900))?,
+            position(150, dec!(1), dec!(900))?,
+            position(200, dec!(2), dec!(820))?,
+        ];
+
+        let net_values = net_values(dec!(1000), &positions, &closes);
+        assert_eq!(net_values.len(), 3);
+
+        assert_eq!(net_values[0].value, dec!(1000));
+        assert_eq!(net_values[0].net_value, dec!(1));
+        assert_eq!(net_values[1].net_value, dec!(1.02));
+        assert_eq!(net_values[1].drawdown, dec!(0));
+        assert_eq!(net_values[2].value, dec!(1000));
+        assert_eq!(
+            net_values[2].drawdown.round_dp(6),
+            (dec!(1) - dec!(1) / dec!(1.02)).round_dp(6)
+        );
+
+        assert!(super::net_values(dec!(0), &positions, &closes).is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_resample() -> Result<()> {
+        let point = |secs: i64, value: Decimal| -> Result<NetValue> {
+            Ok(NetValue {
+                timestamp: secs_to_datetime(secs)?,
+                value,
+                net_value: value / dec!(1000),
+                drawdown: dec!(0),
+            })
+        };
+        let net_values = vec![
+            point(3600, dec!(1000))?,
+            point(3660, dec!(1010))?,
+            point(7199, dec!(1020))?,
+            point(10800, dec!(990))?,
+        ];
+
+        let resampled = resample(&net_values, &KlineInterval::OneHour);
+        assert_eq!(resampled.len(), 2);
+        assert_eq!(resampled[0].timestamp, secs_to_datetime(3600)?);
+        assert_eq!(resampled[0].value, dec!(1020));
+        assert_eq!(resampled[1].timestamp, secs_to_datetime(10800)?);
+
+        assert_eq!(resample(&net_values, &KlineInterval::OneMinute).len(), 3);
+        assert_eq!(resample(&net_values, &KlineInterval::OneDay).len(), 1);
+
+        let csv = to_csv(&[NetValueSeries {
+            node_id: 1,
+            exchange: Exchange::Binance,
+            symbol: "BTCUSDT".into(),
+            net_values: resampled,
+        }]);
+        let lines = csv.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(
+            lines[1],
+            "1,binance,BTCUSDT,1970-01-01T01:00:00+00:00,1020,1.02,0"
+        );
+
+        Ok(())
+    }
+}
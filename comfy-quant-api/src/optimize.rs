@@ -0,0 +1,1002 @@
+use crate::backtest::{self, BacktestSummary};
+use anyhow::Result;
+use bon::Builder;
+use chrono::{DateTime, Duration, Utc};
+use comfy_quant_database::optimization_run::{
+    self, OptimizationRun, OptimizationRunStatus, SaveOptimizationRunParams,
+};
+use comfy_quant_node::workflow::Workflow;
+use futures::{stream, StreamExt};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::{
+    collections::HashSet,
+    f64::consts::PI,
+    fmt, fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+    thread,
+};
+
+const DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+const MIN_BANDWIDTH: f64 = 0.05; // 归一化后的最小核宽度，防止好样本集中时采样退化
+
+// 待优化的节点参数，按节点ID和参数位置定位
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ParamSpace {
+    pub node_id: u32,  // 节点ID
+    pub index: usize,  // 参数位置
+    pub low: f64,      // 下界
+    pub high: f64,     // 上界
+    pub integer: bool, // 是否为整数参数
+}
+
+impl ParamSpace {
+    // 归一化值映射到参数区间
+    fn denormalize(&self, value: f64) -> f64 {
+        let value = self.low + value.clamp(0., 1.) * (self.high - self.low);
+
+        if self.integer {
+            value.round()
+        } else {
+            value
+        }
+    }
+
+    // 参数值映射到 [0, 1]
+    fn normalize(&self, value: f64) -> f64 {
+        ((value - self.low) / (self.high - self.low)).clamp(0., 1.)
+    }
+
+    fn to_json(&self, value: f64) -> serde_json::Value {
+        if self.integer {
+            (value as i64).into()
+        } else {
+            value.into()
+        }
+    }
+}
+
+// 格式: 节点ID:参数位置:下界:上界[:int]，如 "3:3:4:20:int"
+impl FromStr for ParamSpace {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let parts = s.split(':').collect::<Vec<_>>();
+
+        let (node_id, index, low, high, integer) = match parts.as_slice() {
+            [node_id, index, low, high] => (node_id, index, low, high, false),
+            [node_id, index, low, high, "int"] => (node_id, index, low, high, true),
+            _ => anyhow::bail!(
+                "Invalid param space: {}, expected NODE_ID:INDEX:LOW:HIGH[:int]",
+                s
+            ),
+        };
+
+        let space = ParamSpace {
+            node_id: node_id.parse()?,
+            index: index.parse()?,
+            low: low.parse()?,
+            high: high.parse()?,
+            integer,
+        };
+
+        anyhow::ensure!(
+            space.low.is_finite() && space.high.is_finite() && space.low < space.high,
+            "Invalid param space: {}, low must be less than high",
+            s
+        );
+
+        Ok(space)
+    }
+}
+
+impl fmt::Display for ParamSpace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.node_id, self.index)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TrialState {
+    Complete, // 完成
+    Pruned,   // 提前停止
+    Failed,   // 执行失败
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Trial {
+    pub number: usize,          // 试验序号
+    pub params: Vec<f64>,       // 参数值，与搜索空间一一对应
+    pub state: TrialState,      // 状态
+    pub value: Option<f64>,     // 目标值，提前停止时为最后一个阶段的值
+    pub intermediate: Vec<f64>, // 各预算阶段的目标值
+}
+
+// 试验记录，保存到文件后可在中断后继续优化
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct TrialHistory {
+    pub space: Vec<ParamSpace>, // 搜索空间
+    pub trials: Vec<Trial>,     // 试验
+}
+
+impl TrialHistory {
+    pub fn new(space: Vec<ParamSpace>) -> Self {
+        TrialHistory {
+            space,
+            trials: Vec::new(),
+        }
+    }
+
+    // 读取试验记录，文件不存在时创建新记录
+    pub fn load_or_new(path: impl AsRef<Path>, space: &[ParamSpace]) -> Result<Self> {
+        let path = path.as_ref();
+
+        if !path.exists() {
+            return Ok(TrialHistory::new(space.to_vec()));
+        }
+
+        let history: TrialHistory = serde_json::from_str(&fs::read_to_string(path)?)?;
+
+        anyhow::ensure!(
+            history.space == space,
+            "Search space of {} does not match, remove it to start a new optimization",
+            path.display()
+        );
+
+        Ok(history)
+    }
+
+    // 先写临时文件再替换，避免中断时留下不完整的记录
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let tmp_path = path.with_extension("tmp");
+
+        fs::write(&tmp_path, serde_json::to_string_pretty(self)?)?;
+        fs::rename(&tmp_path, path)?;
+
+        Ok(())
+    }
+
+    // 目标值最大的已完成试验
+    pub fn best(&self) -> Option<&Trial> {
+        self.trials
+            .iter()
+            .filter(|trial| trial.state == TrialState::Complete)
+            .filter_map(|trial| Some((trial, trial.value?)))
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(trial, _)| trial)
+    }
+}
+
+impl fmt::Display for TrialHistory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let count = |state: TrialState| {
+            self.trials
+                .iter()
+                .filter(|trial| trial.state == state)
+                .count()
+        };
+
+        write!(
+            f,
+            "trials: {} complete: {} pruned: {} failed: {}",
+            self.trials.len(),
+            count(TrialState::Complete),
+            count(TrialState::Pruned),
+            count(TrialState::Failed),
+        )?;
+
+        if let Some(best) = self.best() {
+            write!(f, "\nbest trial: #{}", best.number)?;
+            write!(f, "\nbest value: {}", best.value.unwrap_or_default())?;
+
+            for (space, value) in self.space.iter().zip(&best.params) {
+                write!(f, "\n  {} = {}", space, value)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// 优化目标，越大越好
+#[allow(async_fn_in_trait)]
+pub trait Objective {
+    // 用 (0, 1] 比例的预算评估参数，如只回测前一部分时间
+    async fn evaluate(&self, params: &[f64], budget: f64) -> Result<f64>;
+}
+
+// 一维 Parzen 估计，在归一化区间上混合均匀先验和以样本为中心的正态核
+struct ParzenEstimator {
+    mus: Vec<f64>, // 核中心
+    sigma: f64,    // 核宽度
+}
+
+impl ParzenEstimator {
+    fn new(mus: Vec<f64>) -> Self {
+        let sigma = (0.5 * ((mus.len() + 1) as f64).powf(-0.2)).max(MIN_BANDWIDTH);
+
+        ParzenEstimator { mus, sigma }
+    }
+
+    fn pdf(&self, x: f64) -> f64 {
+        let kernels = self
+            .mus
+            .iter()
+            .map(|mu| {
+                let z = (x - mu) / self.sigma;
+                (-0.5 * z * z).exp() / (self.sigma * (2. * PI).sqrt())
+            })
+            .sum::<f64>();
+
+        (1. + kernels) / (self.mus.len() + 1) as f64
+    }
+
+    fn sample(&self, rng: &mut impl Rng) -> f64 {
+        let component = rng.gen_range(0..=self.mus.len());
+
+        match self.mus.get(component) {
+            Some(mu) => (mu + self.sigma * standard_normal(rng)).clamp(0., 1.),
+            None => rng.gen_range(0. ..=1.),
+        }
+    }
+}
+
+// Box-Muller 生成标准正态分布随机数
+fn standard_normal(rng: &mut impl Rng) -> f64 {
+    let u1: f64 = rng.gen_range(f64::EPSILON..1.);
+    let u2: f64 = rng.gen();
+
+    (-2. * u1.ln()).sqrt() * (2. * PI * u2).cos()
+}
+
+// TPE 采样器，按目标值把历史试验分为好、差两组，
+// 选择在好样本分布下概率相对差样本分布最大的候选参数
+#[derive(Builder, Debug, Clone)]
+pub struct TpeSampler {
+    #[builder(default = 10)]
+    n_startup_trials: usize, // 随机采样的试验数量
+    #[builder(default = 0.25)]
+    gamma: f64, // 好样本的比例
+    #[builder(default = 24)]
+    n_candidates: usize, // 每个参数的候选数量
+}
+
+impl TpeSampler {
+    pub fn suggest(&self, history: &TrialHistory, rng: &mut impl Rng) -> Vec<f64> {
+        // 已完成的试验按目标值从大到小排列，提前停止的试验归入差样本
+        let mut completed = history
+            .trials
+            .iter()
+            .filter(|trial| trial.state == TrialState::Complete)
+            .filter_map(|trial| Some((trial.params.as_slice(), trial.value?)))
+            .collect::<Vec<_>>();
+
+        if completed.len() < self.n_startup_trials.max(1) {
+            return history
+                .space
+                .iter()
+                .map(|space| space.denormalize(rng.gen_range(0. ..=1.)))
+                .collect();
+        }
+
+        completed.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+
+        let n_good = ((completed.len() as f64 * self.gamma).ceil() as usize).max(1);
+        let (good, bad) = completed.split_at(n_good);
+        let pruned = history
+            .trials
+            .iter()
+            .filter(|trial| trial.state == TrialState::Pruned)
+            .map(|trial| trial.params.as_slice());
+
+        let good = good.iter().map(|(params, _)| *params).collect::<Vec<_>>();
+        let bad = bad
+            .iter()
+            .map(|(params, _)| *params)
+            .chain(pruned)
+            .collect::<Vec<_>>();
+
+        // 各参数独立建模
+        history
+            .space
+            .iter()
+            .enumerate()
+            .map(|(i, space)| {
+                let estimator = |params: &[&[f64]]| {
+                    ParzenEstimator::new(
+                        params
+                            .iter()
+                            .filter_map(|params| params.get(i))
+                            .map(|value| space.normalize(*value))
+                            .collect(),
+                    )
+                };
+
+                let l = estimator(&good);
+                let g = estimator(&bad);
+
+                let best = (0..self.n_candidates.max(1))
+                    .map(|_| l.sample(rng))
+                    .map(|x| (x, l.pdf(x).ln() - g.pdf(x).ln()))
+                    .max_by(|(_, a), (_, b)| a.total_cmp(b))
+                    .map(|(x, _)| x)
+                    .unwrap_or(0.5);
+
+                space.denormalize(best)
+            })
+            .collect()
+    }
+}
+
+// 中位数剪枝，同一预算阶段的目标值低于已有试验中位数时提前停止
+#[derive(Builder, Debug, Clone)]
+pub struct MedianPruner {
+    #[builder(default = 5)]
+    n_warmup_trials: usize, // 同一阶段至少有这么多试验后才开始剪枝
+}
+
+impl MedianPruner {
+    pub fn should_prune(&self, history: &TrialHistory, step: usize, value: f64) -> bool {
+        let mut values = history
+            .trials
+            .iter()
+            .filter_map(|trial| trial.intermediate.get(step).copied())
+            .collect::<Vec<_>>();
+
+        if values.is_empty() || values.len() < self.n_warmup_trials {
+            return false;
+        }
+
+        values.sort_by(|a, b| a.total_cmp(b));
+
+        let mid = values.len() / 2;
+        let median = if values.len() % 2 == 0 {
+            (values[mid - 1] + values[mid]) / 2.
+        } else {
+            values[mid]
+        };
+
+        value < median
+    }
+}
+
+// 贝叶斯参数优化，循环执行 建议参数 -> 分阶段评估 -> 记录结果
+#[derive(Builder, Debug)]
+pub struct Optimizer {
+    space: Vec<ParamSpace>, // 搜索空间
+    n_trials: usize,        // 试验总数，包括恢复的试验
+    #[builder(default = TpeSampler::builder().build())]
+    sampler: TpeSampler, // 采样器
+    #[builder(default = MedianPruner::builder().build())]
+    pruner: MedianPruner, // 剪枝器
+    #[builder(default = vec![0.25, 0.5, 1.])]
+    budgets: Vec<f64>, // 逐步增加的评估预算，最后一个必须为1
+    #[builder(default)]
+    seed: u64, // 随机数种子
+    history: Option<PathBuf>, // 试验记录文件，每次试验后保存，重新运行时从中恢复
+}
+
+impl Optimizer {
+    pub async fn run(&self, objective: &impl Objective) -> Result<TrialHistory> {
+        anyhow::ensure!(!self.space.is_empty(), "Search space is empty");
+        anyhow::ensure!(
+            self.budgets.last() == Some(&1.)
+                && self.budgets.windows(2).all(|w| w[0] < w[1])
+                && self.budgets.iter().all(|budget| *budget > 0.),
+            "Budgets must be increasing and end with 1"
+        );
+
+        let mut history = match &self.history {
+            Some(path) => TrialHistory::load_or_new(path, &self.space)?,
+            None => TrialHistory::new(self.space.clone()),
+        };
+
+        while history.trials.len() < self.n_trials {
+            let number = history.trials.len();
+
+            // 按试验序号派生种子，恢复后的采样与不中断时一致
+            let mut rng = StdRng::seed_from_u64(self.seed.wrapping_add(number as u64));
+            let params = self.sampler.suggest(&history, &mut rng);
+            let trial = self.run_trial(number, params, &history, objective).await;
+
+            tracing::info!(
+                monotonic_counter.optimizer_trials = 1_u64,
+                state = ?trial.state,
+                "Optimizer trial {}: {:?}, value: {:?}",
+                trial.number,
+                trial.params,
+                trial.value,
+            );
+
+            history.trials.push(trial);
+
+            if let Some(path) = &self.history {
+                history.save(path)?;
+            }
+        }
+
+        Ok(history)
+    }
+
+    async fn run_trial(
+        &self,
+        number: usize,
+        params: Vec<f64>,
+        history: &TrialHistory,
+        objective: &impl Objective,
+    ) -> Trial {
+        let mut intermediate = Vec::new();
+        let mut state = TrialState::Complete;
+
+        for (step, budget) in self.budgets.iter().enumerate() {
+            let value = match objective.evaluate(&params, *budget).await {
+                Ok(value) if value.is_finite() => value,
+                Ok(value) => {
+                    tracing::warn!("Optimizer trial {} invalid value: {}", number, value);
+                    state = TrialState::Failed;
+                    break;
+                }
+                Err(e) => {
+                    tracing::warn!("Optimizer trial {} failed: {}", number, e);
+                    state = TrialState::Failed;
+                    break;
+                }
+            };
+
+            intermediate.push(value);
+
+            if step + 1 < self.budgets.len() && self.pruner.should_prune(history, step, value) {
+                state = TrialState::Pruned;
+                break;
+            }
+        }
+
+        let value = match state {
+            TrialState::Failed => None,
+            _ => intermediate.last().copied(),
+        };
+
+        Trial {
+            number,
+            params,
+            state,
+            value,
+            intermediate,
+        }
+    }
+}
+
+// 回测优化目标
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Metric {
+    TotalReturn,         // 总收益率
+    AnnualizedReturn,    // 年化收益率
+    TimeWeightedReturn,  // 时间加权收益率
+    MoneyWeightedReturn, // 资金加权收益率
+    TotalPnl,            // 总盈亏
+}
+
+impl Metric {
+    fn value(&self, summary: &BacktestSummary) -> Decimal {
+        match self {
+            Metric::TotalReturn => summary.total_return,
+            Metric::AnnualizedReturn => summary.annualized_return,
+            Metric::TimeWeightedReturn => summary.time_weighted_return,
+            Metric::MoneyWeightedReturn => summary.money_weighted_return,
+            Metric::TotalPnl => summary.total_pnl,
+        }
+    }
+}
+
+impl FromStr for Metric {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "total_return" => Ok(Metric::TotalReturn),
+            "annualized_return" => Ok(Metric::AnnualizedReturn),
+            "twr" => Ok(Metric::TimeWeightedReturn),
+            "irr" => Ok(Metric::MoneyWeightedReturn),
+            "total_pnl" => Ok(Metric::TotalPnl),
+            _ => anyhow::bail!("Invalid metric: {}", s),
+        }
+    }
+}
+
+// 以回测结果为目标，预算按回测时间范围的比例截取，从开始时间算起
+#[derive(Builder, Debug)]
+#[builder(on(String, into))]
+pub struct BacktestObjective {
+    workflow: String,              // 工作流JSON
+    space: Vec<ParamSpace>,        // 搜索空间
+    start_datetime: DateTime<Utc>, // 回测开始时间
+    end_datetime: DateTime<Utc>,   // 回测结束时间
+    db: Arc<PgPool>,               // 数据库
+    #[builder(default = Metric::TotalReturn)]
+    metric: Metric, // 优化目标
+    #[builder(default = "USDT".to_string())]
+    quote_asset: String, // 计价资产
+}
+
+impl Objective for BacktestObjective {
+    async fn evaluate(&self, params: &[f64], budget: f64) -> Result<f64> {
+        let secs = (self.end_datetime - self.start_datetime).num_seconds() as f64 * budget;
+        let end_datetime = self.start_datetime + Duration::seconds(secs.round() as i64);
+
+        let params = self
+            .space
+            .iter()
+            .zip(params)
+            .map(|(space, value)| (space.node_id, space.index, space.to_json(*value)));
+
+        let mut workflow =
+            backtest_workflow(&self.workflow, params, &self.start_datetime, &end_datetime)?;
+
+        let summary = backtest::execute(
+            &mut workflow,
+            Arc::clone(&self.db),
+            &self.quote_asset,
+            false,
+        )
+        .await?;
+
+        self.metric
+            .value(&summary)
+            .to_f64()
+            .ok_or_else(|| anyhow::anyhow!("Invalid metric value"))
+    }
+}
+
+// 用参数覆盖工作流模板并设置回测时间范围
+pub(crate) fn backtest_workflow(
+    template: &str,
+    params: impl IntoIterator<Item = (u32, usize, serde_json::Value)>,
+    start_datetime: &DateTime<Utc>,
+    end_datetime: &DateTime<Utc>,
+) -> Result<Workflow> {
+    let mut workflow: Workflow = serde_json::from_str(template)?;
+
+    for (node_id, index, value) in params {
+        workflow.set_node_param(node_id, index, value)?;
+    }
+
+    workflow.set_backtest_time_range(
+        &start_datetime.format(DATETIME_FORMAT).to_string(),
+        &end_datetime.format(DATETIME_FORMAT).to_string(),
+    );
+
+    Ok(workflow)
+}
+
+// 网格扫描的参数取值，从开始值按步长递增到结束值(含)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParamGrid {
+    pub node_id: u32,  // 节点ID
+    pub index: usize,  // 参数位置
+    pub start: f64,    // 开始值
+    pub end: f64,      // 结束值
+    pub step: f64,     // 步长
+    pub integer: bool, // 是否为整数参数
+}
+
+impl ParamGrid {
+    // 所有取值，浮点步长累加的误差按10位小数截断
+    pub fn values(&self) -> Vec<f64> {
+        let n = ((self.end - self.start) / self.step + 1e-9).floor() as usize;
+
+        (0..=n)
+            .map(|i| self.start + i as f64 * self.step)
+            .map(|value| {
+                if self.integer {
+                    value.round()
+                } else {
+                    (value * 1e10).round() / 1e10
+                }
+            })
+            .collect()
+    }
+
+    fn to_json(&self, value: f64) -> serde_json::Value {
+        if self.integer {
+            (value as i64).into()
+        } else {
+            value.into()
+        }
+    }
+}
+
+// 格式: 节点ID:参数位置:开始值:结束值:步长[:int]，如 "3:3:5:50:5:int"
+impl FromStr for ParamGrid {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let parts = s.split(':').collect::<Vec<_>>();
+
+        let (node_id, index, start, end, step, integer) = match parts.as_slice() {
+            [node_id, index, start, end, step] => (node_id, index, start, end, step, false),
+            [node_id, index, start, end, step, "int"] => (node_id, index, start, end, step, true),
+            _ => anyhow::bail!(
+                "Invalid param grid: {}, expected NODE_ID:INDEX:START:END:STEP[:int]",
+                s
+            ),
+        };
+
+        let grid = ParamGrid {
+            node_id: node_id.parse()?,
+            index: index.parse()?,
+            start: start.parse()?,
+            end: end.parse()?,
+            step: step.parse()?,
+            integer,
+        };
+
+        anyhow::ensure!(
+            grid.start.is_finite()
+                && grid.end.is_finite()
+                && grid.start <= grid.end
+                && grid.step.is_finite()
+                && grid.step > 0.,
+            "Invalid param grid: {}, start must not exceed end and step must be positive",
+            s
+        );
+
+        Ok(grid)
+    }
+}
+
+impl fmt::Display for ParamGrid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.node_id, self.index)
+    }
+}
+
+// 解析 optimization_runs 中保存的参数组合，返回 (节点ID, 参数位置, 参数值)
+pub(crate) fn parse_params(params: &str) -> Result<Vec<(u32, usize, serde_json::Value)>> {
+    serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(params)?
+        .into_iter()
+        .map(|(key, value)| {
+            let (node_id, index) = key
+                .split_once(':')
+                .ok_or_else(|| anyhow::anyhow!("Invalid param key: {}", key))?;
+
+            Ok((node_id.parse()?, index.parse()?, value))
+        })
+        .collect()
+}
+
+// 所有参数取值的笛卡尔积
+fn grid_combinations(grids: &[ParamGrid]) -> Vec<Vec<f64>> {
+    grids.iter().fold(vec![vec![]], |combinations, grid| {
+        let values = grid.values();
+
+        combinations
+            .iter()
+            .flat_map(|combination| {
+                values.iter().map(move |value| {
+                    let mut combination = combination.clone();
+                    combination.push(*value);
+                    combination
+                })
+            })
+            .collect()
+    })
+}
+
+// 参数扫描，对网格中的每个参数组合并行回测，结果保存到 optimization_runs 表。
+// 同一扫描ID下已完成的组合不再重复回测，中断后重新运行即可继续
+#[derive(Builder, Debug)]
+#[builder(on(String, into))]
+pub struct ParamSweep {
+    workflow: String,                // 工作流模板JSON
+    grids: Vec<ParamGrid>,           // 参数网格
+    start_datetime: DateTime<Utc>,   // 回测开始时间
+    end_datetime: DateTime<Utc>,     // 回测结束时间
+    db: Arc<PgPool>,                 // 数据库
+    optimization_id: Option<String>, // 扫描ID，默认为工作流模板的内容哈希
+    #[builder(default = thread::available_parallelism().map_or(1, |n| n.get()))]
+    workers: usize, // 同时运行的回测数量
+    #[builder(default = "USDT".to_string())]
+    quote_asset: String, // 计价资产
+}
+
+impl ParamSweep {
+    pub async fn run(&self) -> Result<Vec<OptimizationRun>> {
+        anyhow::ensure!(!self.grids.is_empty(), "Param grid is empty");
+        anyhow::ensure!(self.workers > 0, "Workers must be positive");
+
+        let optimization_id = match &self.optimization_id {
+            Some(optimization_id) => optimization_id.clone(),
+            None => serde_json::from_str::<Workflow>(&self.workflow)?.content_hash()?,
+        };
+
+        let done = optimization_run::list(
+            &self.db,
+            &optimization_id,
+            &self.start_datetime,
+            &self.end_datetime,
+        )
+        .await?
+        .into_iter()
+        .filter(|run| run.status == OptimizationRunStatus::Complete)
+        .map(|run| run.params)
+        .collect::<HashSet<_>>();
+
+        let pending = grid_combinations(&self.grids)
+            .into_iter()
+            .map(|values| self.params_json(&values).map(|params| (values, params)))
+            .filter(|result| !matches!(result, Ok((_, params)) if done.contains(params)))
+            .collect::<Result<Vec<_>>>()?;
+
+        tracing::info!(
+            %optimization_id,
+            "Param sweep: {} combinations pending, {} done",
+            pending.len(),
+            done.len()
+        );
+
+        let mut runs = stream::iter(pending)
+            .map(|(values, params)| async move {
+                let summary = self.backtest(&values).await;
+                (params, summary)
+            })
+            .buffer_unordered(self.workers);
+
+        while let Some((params, summary)) = runs.next().await {
+            let data = SaveOptimizationRunParams::builder()
+                .optimization_id(optimization_id.clone())
+                .params(params.clone())
+                .start_time(self.start_datetime)
+                .end_time(self.end_datetime);
+
+            let data = match summary {
+                Ok(summary) => data
+                    .status(OptimizationRunStatus::Complete)
+                    .total_return(summary.total_return)
+                    .total_pnl(summary.total_pnl)
+                    .max_drawdown(summary.max_drawdown)
+                    .win_rate(summary.win_rate)
+                    .total_trades(summary.total_trades as i64)
+                    .build(),
+                Err(e) => {
+                    tracing::warn!("Param sweep {} failed: {}", params, e);
+
+                    data.status(OptimizationRunStatus::Failed)
+                        .error(e.to_string())
+                        .build()
+                }
+            };
+
+            let run = optimization_run::save(&self.db, data).await?;
+
+            tracing::info!(
+                monotonic_counter.param_sweep_runs = 1_u64,
+                status = %run.status,
+                "Param sweep {}: total return {:?}, max drawdown {:?}, win rate {:?}",
+                run.params,
+                run.total_return,
+                run.max_drawdown,
+                run.win_rate,
+            );
+        }
+
+        optimization_run::list(
+            &self.db,
+            &optimization_id,
+            &self.start_datetime,
+            &self.end_datetime,
+        )
+        .await
+    }
+
+    // 参数组合的JSON，键为 节点ID:参数位置
+    fn params_json(&self, values: &[f64]) -> Result<String> {
+        let params = self
+            .grids
+            .iter()
+            .zip(values)
+            .map(|(grid, value)| (grid.to_string(), grid.to_json(*value)))
+            .collect::<serde_json::Map<_, _>>();
+
+        Ok(serde_json::to_string(&params)?)
+    }
+
+    async fn backtest(&self, values: &[f64]) -> Result<BacktestSummary> {
+        let params = self
+            .grids
+            .iter()
+            .zip(values)
+            .map(|(grid, value)| (grid.node_id, grid.index, grid.to_json(*value)));
+
+        let mut workflow = backtest_workflow(
+            &self.workflow,
+            params,
+            &self.start_datetime,
+            &self.end_datetime,
+        )?;
+
+        backtest::execute(
+            &mut workflow,
+            Arc::clone(&self.db),
+            &self.quote_asset,
+            false,
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 最优点在 (3, 7) 的二次函数，预算只缩放目标值
+    struct Quadratic;
+
+    impl Objective for Quadratic {
+        async fn evaluate(&self, params: &[f64], budget: f64) -> Result<f64> {
+            let (x, y) = (params[0], params[1]);
+            Ok(-((x - 3.).powi(2) + (y - 7.).powi(2)) * budget)
+        }
+    }
+
+    fn space() -> Vec<ParamSpace> {
+        vec![
+            "1:0:-10:10".parse().unwrap(),
+            "1:1:0:20:int".parse().unwrap(),
+        ]
+    }
+
+    #[test]
+    fn test_param_space_from_str() -> Result<()> {
+        let space: ParamSpace = "3:4:10:50:int".parse()?;
+        assert_eq!(space.node_id, 3);
+        assert_eq!(space.index, 4);
+        assert!(space.integer);
+        assert_eq!(space.denormalize(0.51), 30.);
+        assert_eq!(space.to_json(30.), serde_json::json!(30));
+
+        assert!("3:4:50:10".parse::<ParamSpace>().is_err());
+        assert!("3:4:10".parse::<ParamSpace>().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_param_grid_from_str() -> Result<()> {
+        let grid: ParamGrid = "3:3:5:50:5:int".parse()?;
+        assert_eq!(grid.node_id, 3);
+        assert_eq!(grid.values().len(), 10);
+        assert_eq!(grid.to_json(5.), serde_json::json!(5));
+
+        let grid: ParamGrid = "3:1:0.1:0.3:0.1".parse()?;
+        assert_eq!(grid.values(), vec![0.1, 0.2, 0.3]);
+
+        assert!("3:3:50:5:5".parse::<ParamGrid>().is_err());
+        assert!("3:3:5:50:0".parse::<ParamGrid>().is_err());
+        assert!("3:3:5:50".parse::<ParamGrid>().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_params() -> Result<()> {
+        let params = parse_params(r#"{"3:1":1.02,"3:3":10}"#)?;
+
+        assert_eq!(
+            params,
+            vec![
+                (3, 1, serde_json::json!(1.02)),
+                (3, 3, serde_json::json!(10))
+            ]
+        );
+        assert!(parse_params(r#"{"3":10}"#).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_grid_combinations() -> Result<()> {
+        let grids = vec!["3:3:5:15:5:int".parse()?, "3:1:1:2:1".parse()?];
+        let combinations = grid_combinations(&grids);
+
+        assert_eq!(combinations.len(), 6);
+        assert_eq!(combinations[0], vec![5., 1.]);
+        assert_eq!(combinations[5], vec![15., 2.]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_median_pruner() {
+        let pruner = MedianPruner::builder().n_warmup_trials(3).build();
+        let mut history = TrialHistory::new(space());
+
+        for (number, value) in [1., 2., 3.].into_iter().enumerate() {
+            history.trials.push(Trial {
+                number,
+                params: vec![0., 0.],
+                state: TrialState::Complete,
+                value: Some(value),
+                intermediate: vec![value],
+            });
+        }
+
+        assert!(pruner.should_prune(&history, 0, 1.5));
+        assert!(!pruner.should_prune(&history, 0, 2.));
+        // 下一阶段还没有数据
+        assert!(!pruner.should_prune(&history, 1, -100.));
+    }
+
+    #[tokio::test]
+    async fn test_optimizer() -> Result<()> {
+        let optimizer = Optimizer::builder()
+            .space(space())
+            .n_trials(60)
+            .seed(42)
+            .build();
+
+        let history = optimizer.run(&Quadratic).await?;
+        assert_eq!(history.trials.len(), 60);
+        assert!(history
+            .trials
+            .iter()
+            .any(|trial| trial.state == TrialState::Pruned));
+
+        let best = history.best().unwrap();
+        assert!(best.value.unwrap() > -2.);
+        assert!(best.params[1].fract() == 0.);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_optimizer_resume() -> Result<()> {
+        let path = std::env::temp_dir().join(format!("optimizer_{}.json", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let optimizer = |n_trials| {
+            Optimizer::builder()
+                .space(space())
+                .n_trials(n_trials)
+                .seed(7)
+                .history(path.clone())
+                .build()
+        };
+
+        // 中断后继续与一次性运行的结果一致
+        optimizer(15).run(&Quadratic).await?;
+        let resumed = optimizer(30).run(&Quadratic).await?;
+
+        // 搜索空间变化时不能恢复
+        let other_space = vec!["2:0:-10:10".parse()?];
+        assert!(TrialHistory::load_or_new(&path, &other_space).is_err());
+
+        fs::remove_file(&path)?;
+
+        let uninterrupted = Optimizer::builder()
+            .space(space())
+            .n_trials(30)
+            .seed(7)
+            .build()
+            .run(&Quadratic)
+            .await?;
+
+        // JSON读写的浮点数可能有末位误差
+        assert_eq!(resumed.trials.len(), uninterrupted.trials.len());
+        for (a, b) in resumed.trials.iter().zip(&uninterrupted.trials) {
+            assert_eq!(a.state, b.state);
+            assert!(a
+                .params
+                .iter()
+                .zip(&b.params)
+                .all(|(a, b)| (a - b).abs() < 1e-9));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,184 @@
+use crate::backtest::{self, BacktestSummary};
+use anyhow::Result;
+use bon::Builder;
+use chrono::{DateTime, Duration, Utc};
+use comfy_quant_node::workflow::Workflow;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::sync::Arc;
+
+const DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+// 节点参数变更
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ParamChange {
+    pub node_id: u32,             // 节点ID
+    pub index: usize,             // 参数位置
+    pub value: serde_json::Value, // 新的参数值
+}
+
+// 应用参数变更前，以近期数据分别回测当前参数和新参数，比较两者的结果
+#[derive(Builder, Debug)]
+#[builder(on(String, into))]
+pub struct ParamPreview {
+    workflow: String,          // 工作流JSON
+    changes: Vec<ParamChange>, // 参数变更
+    db: Arc<PgPool>,           // 数据库
+    #[builder(default = Duration::hours(24))]
+    lookback: Duration, // 回测的近期时间范围
+    #[builder(default = Utc::now())]
+    end_datetime: DateTime<Utc>, // 回测结束时间
+    #[builder(default = "USDT".to_string())]
+    quote_asset: String, // 计价资产
+}
+
+// 新参数相对当前参数的变化
+#[derive(Serialize, Debug, PartialEq)]
+pub struct SummaryDelta {
+    pub total_pnl: Decimal,             // 总盈亏
+    pub total_return: Decimal,          // 总收益率
+    pub annualized_return: Decimal,     // 年化收益率
+    pub time_weighted_return: Decimal,  // 时间加权收益率
+    pub money_weighted_return: Decimal, // 资金加权收益率(年化)
+}
+
+impl SummaryDelta {
+    fn new(baseline: &BacktestSummary, candidate: &BacktestSummary) -> Self {
+        SummaryDelta {
+            total_pnl: candidate.total_pnl - baseline.total_pnl,
+            total_return: candidate.total_return - baseline.total_return,
+            annualized_return: candidate.annualized_return - baseline.annualized_return,
+            time_weighted_return: candidate.time_weighted_return - baseline.time_weighted_return,
+            money_weighted_return: candidate.money_weighted_return - baseline.money_weighted_return,
+        }
+    }
+}
+
+// 参数变更的回测对比
+#[derive(Serialize, Debug)]
+pub struct ParamPreviewReport {
+    pub from: String,               // 回测开始时间
+    pub to: String,                 // 回测结束时间
+    pub changes: Vec<ParamChange>,  // 参数变更
+    pub baseline: BacktestSummary,  // 当前参数的回测结果
+    pub candidate: BacktestSummary, // 新参数的回测结果
+    pub delta: SummaryDelta,        // 新参数相对当前参数的变化
+}
+
+impl ParamPreview {
+    // 以新参数构建回测工作流，参数无效时返回错误，不执行回测
+    pub fn candidate(&self) -> Result<Workflow> {
+        let mut workflow = self.backtest_workflow()?;
+
+        for change in &self.changes {
+            workflow.set_node_param(change.node_id, change.index, change.value.clone())?;
+        }
+
+        workflow.validate()?;
+
+        Ok(workflow)
+    }
+
+    // 当前参数的回测工作流，实盘行情节点替换为回测行情节点
+    fn backtest_workflow(&self) -> Result<Workflow> {
+        let mut workflow: Workflow = serde_json::from_str(&self.workflow)?;
+        let (from, to) = self.time_range();
+
+        workflow.use_backtest_data(&from, &to)?;
+
+        Ok(workflow)
+    }
+
+    fn time_range(&self) -> (String, String) {
+        let start_datetime = self.end_datetime - self.lookback;
+
+        (
+            start_datetime.format(DATETIME_FORMAT).to_string(),
+            self.end_datetime.format(DATETIME_FORMAT).to_string(),
+        )
+    }
+
+    // 依次回测当前参数和新参数
+    pub async fn run(&self) -> Result<ParamPreviewReport> {
+        anyhow::ensure!(!self.changes.is_empty(), "No param changes");
+        anyhow::ensure!(self.lookback > Duration::zero(), "Invalid lookback");
+
+        let mut candidate = self.candidate()?;
+        let mut baseline = self.backtest_workflow()?;
+
+        let baseline = backtest::execute(
+            &mut baseline,
+            Arc::clone(&self.db),
+            &self.quote_asset,
+            false,
+        )
+        .await?;
+        let candidate = backtest::execute(
+            &mut candidate,
+            Arc::clone(&self.db),
+            &self.quote_asset,
+            false,
+        )
+        .await?;
+        let (from, to) = self.time_range();
+
+        tracing::info!(
+            monotonic_counter.param_preview = 1_u64,
+            changes = self.changes.len(),
+            %from,
+            %to,
+            "Param preview finished"
+        );
+
+        Ok(ParamPreviewReport {
+            from,
+            to,
+            changes: self.changes.clone(),
+            delta: SummaryDelta::new(&baseline, &candidate),
+            baseline,
+            candidate,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::Fixture;
+    use serde_json::json;
+
+    fn preview(changes: Vec<ParamChange>) -> Result<ParamPreview> {
+        Ok(ParamPreview::builder()
+            .workflow(Fixture::GridBacktest.definition())
+            .changes(changes)
+            .db(Arc::new(PgPool::connect_lazy("postgres://localhost/test")?))
+            .lookback(Duration::hours(1))
+            .end_datetime("2024-01-01T01:00:00Z".parse()?)
+            .build())
+    }
+
+    #[tokio::test]
+    async fn test_param_preview_candidate() -> Result<()> {
+        let change = |index, value| ParamChange {
+            node_id: 3,
+            index,
+            value,
+        };
+
+        let workflow = preview(vec![change(3, json!(10))])?.candidate()?;
+        let nodes = serde_json::to_value(workflow.nodes())?;
+
+        assert_eq!(nodes[2]["properties"]["params"][3], json!(10));
+        assert_eq!(
+            nodes[0]["properties"]["params"],
+            json!(["DEMO", "USDT", "2024-01-01 00:00:00", "2024-01-01 01:00:00"])
+        );
+
+        // 无效的参数在回测前返回错误
+        assert!(preview(vec![change(3, json!("ten"))])?.candidate().is_err());
+        assert!(preview(vec![change(99, json!(10))])?.candidate().is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,155 @@
+use anyhow::Result;
+use bon::Builder;
+use chrono::Utc;
+use comfy_quant_config::setting::Retention;
+use comfy_quant_database::retention::{self, ArchiveReport, RetentionPolicy, RetentionTable};
+use sqlx::PgPool;
+use std::{collections::HashMap, fmt, path::PathBuf};
+
+#[derive(Builder, Debug)]
+pub struct RetentionOptions {
+    #[builder(default)]
+    tables: Vec<String>, // 只处理指定的表，为空则处理所有已配置的表
+    days: Option<u32>,            // 覆盖配置中的保留天数
+    archive_dir: Option<PathBuf>, // 覆盖配置中的归档目录
+    #[builder(default)]
+    dry_run: bool, // 只统计过期行数，不归档也不删除
+}
+
+#[derive(Debug)]
+pub struct RetentionSummary {
+    pub reports: Vec<ArchiveReport>, // 每个表的归档结果
+}
+
+impl fmt::Display for RetentionSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.reports.is_empty() {
+            return write!(f, "no retention policy configured");
+        }
+
+        for (i, report) in self.reports.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", report)?;
+        }
+
+        Ok(())
+    }
+}
+
+// 根据配置和命令行参数生成各表的保留策略
+pub fn policies(setting: &Retention, options: &RetentionOptions) -> Result<Vec<RetentionPolicy>> {
+    let configured = setting
+        .tables
+        .iter()
+        .map(|(table, days)| Ok((table.parse::<RetentionTable>()?, *days)))
+        .collect::<Result<HashMap<_, _>>>()?;
+
+    let tables = if options.tables.is_empty() {
+        RetentionTable::ALL
+            .into_iter()
+            .filter(|table| configured.contains_key(table))
+            .collect::<Vec<_>>()
+    } else {
+        options
+            .tables
+            .iter()
+            .map(|table| table.parse::<RetentionTable>())
+            .collect::<Result<Vec<_>>>()?
+    };
+
+    tables
+        .into_iter()
+        .map(|table| {
+            let days = options
+                .days
+                .or_else(|| configured.get(&table).copied())
+                .ok_or_else(|| anyhow::anyhow!("No retention days configured for {}", table))?;
+
+            anyhow::ensure!(
+                days > 0,
+                "Retention days of {} must be greater than 0",
+                table
+            );
+
+            Ok(RetentionPolicy::new(table, days))
+        })
+        .collect()
+}
+
+// 按保留策略归档并删除过期数据
+pub async fn run(
+    db: &PgPool,
+    setting: &Retention,
+    options: RetentionOptions,
+) -> Result<RetentionSummary> {
+    let policies = policies(setting, &options)?;
+    let archive_dir = options
+        .archive_dir
+        .unwrap_or_else(|| setting.archive_dir.clone());
+    let now = Utc::now();
+
+    let mut reports = Vec::with_capacity(policies.len());
+
+    for policy in &policies {
+        let report = retention::archive(db, policy, &archive_dir, now, options.dry_run).await?;
+        reports.push(report);
+    }
+
+    Ok(RetentionSummary { reports })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setting() -> Retention {
+        Retention {
+            archive_dir: PathBuf::from("archive"),
+            tables: HashMap::from([
+                ("strategy_journals".to_string(), 365),
+                ("strategy_spot_positions".to_string(), 90),
+            ]),
+        }
+    }
+
+    #[test]
+    fn test_policies() -> Result<()> {
+        let options = RetentionOptions::builder().build();
+        assert_eq!(
+            policies(&setting(), &options)?,
+            vec![
+                RetentionPolicy::new(RetentionTable::StrategySpotPositions, 90),
+                RetentionPolicy::new(RetentionTable::StrategyJournals, 365),
+            ]
+        );
+
+        let options = RetentionOptions::builder()
+            .tables(vec!["strategy_capital_flows".to_string()])
+            .days(30)
+            .build();
+        assert_eq!(
+            policies(&setting(), &options)?,
+            vec![RetentionPolicy::new(
+                RetentionTable::StrategyCapitalFlows,
+                30
+            )]
+        );
+
+        // 未配置保留天数
+        let options = RetentionOptions::builder()
+            .tables(vec!["strategy_capital_flows".to_string()])
+            .build();
+        assert!(policies(&setting(), &options).is_err());
+
+        // 不支持的表
+        let options = RetentionOptions::builder()
+            .tables(vec!["klines".to_string()])
+            .days(30)
+            .build();
+        assert!(policies(&setting(), &options).is_err());
+
+        Ok(())
+    }
+}
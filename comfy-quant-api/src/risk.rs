@@ -0,0 +1,277 @@
+use anyhow::Result;
+use bon::Builder;
+use chrono::{DateTime, Duration, Utc};
+use comfy_quant_base::{Exchange, KlineInterval, Market};
+use comfy_quant_database::{kline, strategy_spot_position};
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+use rust_decimal_macros::dec;
+use serde::Serialize;
+use sqlx::PgPool;
+use std::{collections::HashMap, fmt};
+
+#[derive(Builder, Debug)]
+#[builder(on(_, into))]
+pub struct RiskOptions {
+    workflow_ids: Vec<String>, // 参与统计的工作流，同一账户的多个工作流合并计算
+    #[builder(default = dec!(0.95))]
+    confidence: Decimal, // 置信度
+    #[builder(default = 365)]
+    lookback_days: i64, // 历史收益率回看天数
+    #[builder(default = KlineInterval::OneDay)]
+    interval: KlineInterval, // 收益率周期
+    #[builder(default = "USDT".to_string())]
+    quote_asset: String, // 计价资产
+}
+
+// 单个资产的敞口
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct AssetExposure {
+    pub exchange: Exchange, // 交易所
+    pub asset: String,      // 资产
+    pub qty: Decimal,       // 持仓数量
+    pub price: Decimal,     // 最新价格
+    pub value: Decimal,     // 市值
+    pub weight: Decimal,    // 占组合市值比例
+}
+
+// 每日风险摘要
+#[derive(Serialize, Debug)]
+pub struct RiskSummary {
+    pub as_of: DateTime<Utc>,          // 统计时间
+    pub quote_asset: String,           // 计价资产
+    pub confidence: Decimal,           // 置信度
+    pub total_value: Decimal,          // 组合总市值
+    pub var: Decimal,                  // 历史模拟法VaR(单周期最大损失)
+    pub expected_shortfall: Decimal,   // 超过VaR部分的平均损失
+    pub observations: usize,           // 历史情景数量
+    pub exposures: Vec<AssetExposure>, // 各资产敞口
+}
+
+impl fmt::Display for RiskSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "as of:              {}", self.as_of)?;
+        writeln!(
+            f,
+            "total value:        {} {}",
+            self.total_value, self.quote_asset
+        )?;
+        writeln!(f, "VaR({}):          {}", self.confidence, self.var)?;
+        writeln!(f, "expected shortfall: {}", self.expected_shortfall)?;
+        writeln!(f, "observations:       {}", self.observations)?;
+
+        for exposure in &self.exposures {
+            write!(
+                f,
+                "\n  {:?} {}: qty {} value {} weight {}",
+                exposure.exchange, exposure.asset, exposure.qty, exposure.value, exposure.weight
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+// 计算工作流组合的风险摘要
+pub async fn daily_summary(db: &PgPool, options: RiskOptions) -> Result<RiskSummary> {
+    anyhow::ensure!(
+        options.confidence > dec!(0) && options.confidence < dec!(1),
+        "Confidence must be between 0 and 1"
+    );
+
+    let as_of = Utc::now();
+    let start_datetime = as_of - Duration::days(options.lookback_days);
+    let positions = strategy_spot_position::list_latest(db, &options.workflow_ids).await?;
+
+    // 按交易所、资产合并持仓
+    let mut holdings: HashMap<(Exchange, String), Decimal> = HashMap::new();
+
+    for position in positions {
+        for (asset, qty) in [
+            (position.base_asset, position.base_asset_balance),
+            (position.quote_asset, position.quote_asset_balance),
+        ] {
+            *holdings
+                .entry((position.exchange.clone(), asset))
+                .or_default() += qty;
+        }
+    }
+
+    let mut holdings = holdings.into_iter().collect::<Vec<_>>();
+    holdings.sort_by(|((_, a), _), ((_, b), _)| a.cmp(b));
+
+    let mut exposures = Vec::with_capacity(holdings.len());
+    let mut returns = Vec::with_capacity(holdings.len());
+
+    for ((exchange, asset), qty) in holdings {
+        if qty.is_zero() {
+            continue;
+        }
+
+        // 计价资产自身没有价格波动
+        if asset == options.quote_asset {
+            exposures.push(exposure(exchange, asset, qty, dec!(1)));
+            continue;
+        }
+
+        let symbol = exchange.symbol(&asset, &options.quote_asset);
+        let klines = kline::list(
+            db,
+            &exchange,
+            &Market::Spot,
+            &symbol,
+            &options.interval,
+            &start_datetime,
+            &as_of,
+        )
+        .await?;
+
+        let closes = klines
+            .iter()
+            .map(|kline| (kline.open_time, kline.close_price))
+            .collect::<Vec<_>>();
+        let price = closes
+            .last()
+            .map(|(_, close)| *close)
+            .ok_or_else(|| anyhow::anyhow!("No klines for {}", symbol))?;
+
+        returns.push(period_returns(&closes));
+        exposures.push(exposure(exchange, asset, qty, price));
+    }
+
+    let total_value = exposures
+        .iter()
+        .map(|exposure| exposure.value)
+        .sum::<Decimal>();
+
+    if total_value > dec!(0) {
+        for exposure in exposures.iter_mut() {
+            exposure.weight = exposure.value / total_value;
+        }
+    }
+
+    // 只有波动资产参与情景计算，与returns一一对应
+    let values = exposures
+        .iter()
+        .filter(|exposure| exposure.asset != options.quote_asset)
+        .map(|exposure| exposure.value)
+        .collect::<Vec<_>>();
+    let pnls = portfolio_pnls(&values, &returns);
+    let (var, expected_shortfall) = historical_var(&pnls, &options.confidence);
+
+    Ok(RiskSummary {
+        as_of,
+        quote_asset: options.quote_asset,
+        confidence: options.confidence,
+        total_value,
+        var,
+        expected_shortfall,
+        observations: pnls.len(),
+        exposures,
+    })
+}
+
+fn exposure(exchange: Exchange, asset: String, qty: Decimal, price: Decimal) -> AssetExposure {
+    AssetExposure {
+        exchange,
+        asset,
+        qty,
+        price,
+        value: qty * price,
+        weight: dec!(0),
+    }
+}
+
+// 相邻收盘价的收益率，以后一根K线的开盘时间为键
+fn period_returns(closes: &[(DateTime<Utc>, Decimal)]) -> HashMap<DateTime<Utc>, Decimal> {
+    closes
+        .windows(2)
+        .filter(|pair| !pair[0].1.is_zero())
+        .map(|pair| (pair[1].0, pair[1].1 / pair[0].1 - dec!(1)))
+        .collect()
+}
+
+// 用当前敞口重演历史收益率，只取所有资产都有数据的时间点
+fn portfolio_pnls(values: &[Decimal], returns: &[HashMap<DateTime<Utc>, Decimal>]) -> Vec<Decimal> {
+    let Some(first) = returns.first() else {
+        return vec![];
+    };
+
+    let mut times = first
+        .keys()
+        .filter(|time| returns.iter().all(|r| r.contains_key(*time)))
+        .collect::<Vec<_>>();
+    times.sort();
+
+    times
+        .into_iter()
+        .map(|time| {
+            values
+                .iter()
+                .zip(returns)
+                .map(|(value, r)| value * r[time])
+                .sum::<Decimal>()
+        })
+        .collect()
+}
+
+// 历史模拟法VaR和预期损失，均以正数表示损失
+fn historical_var(pnls: &[Decimal], confidence: &Decimal) -> (Decimal, Decimal) {
+    if pnls.is_empty() {
+        return (dec!(0), dec!(0));
+    }
+
+    let mut pnls = pnls.to_vec();
+    pnls.sort();
+
+    // 尾部情景数量，至少取一个
+    let tail = ((dec!(1) - confidence) * Decimal::from(pnls.len()))
+        .floor()
+        .max(dec!(1));
+    let tail = tail.to_usize().unwrap_or(1).min(pnls.len());
+    let tail_pnls = &pnls[..tail];
+
+    let var = (-tail_pnls[tail - 1]).max(dec!(0));
+    let expected_shortfall =
+        (-tail_pnls.iter().sum::<Decimal>() / Decimal::from(tail)).max(dec!(0));
+
+    (var, expected_shortfall)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use comfy_quant_base::secs_to_datetime;
+
+    #[test]
+    fn test_historical_var() {
+        let pnls = (1..=100).map(|i| Decimal::from(i - 50)).collect::<Vec<_>>();
+
+        // 最差的5个情景为 -49..-45
+        let (var, expected_shortfall) = historical_var(&pnls, &dec!(0.95));
+        assert_eq!(var, dec!(45));
+        assert_eq!(expected_shortfall, dec!(47));
+
+        let (var, expected_shortfall) = historical_var(&[dec!(10)], &dec!(0.95));
+        assert_eq!(var, dec!(0));
+        assert_eq!(expected_shortfall, dec!(0));
+    }
+
+    #[test]
+    fn test_portfolio_pnls() -> Result<()> {
+        let btc = period_returns(&[
+            (secs_to_datetime(0)?, dec!(100)),
+            (secs_to_datetime(86400)?, dec!(110)),
+            (secs_to_datetime(172800)?, dec!(99)),
+        ]);
+        let eth = period_returns(&[
+            (secs_to_datetime(86400)?, dec!(10)),
+            (secs_to_datetime(172800)?, dec!(12)),
+        ]);
+
+        // 只有第二天两个资产都有收益率
+        let pnls = portfolio_pnls(&[dec!(1000), dec!(500)], &[btc, eth]);
+        assert_eq!(pnls, vec![dec!(0)]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,1524 @@
+use crate::{
+    deploy,
+    net_value::{self, NetValueCache, NetValueSeries},
+    param_preview::{ParamChange, ParamPreview, ParamPreviewReport},
+    retention::{self, RetentionOptions},
+    workflow_metrics,
+};
+use anyhow::{anyhow, Result};
+use async_lock::RwLock;
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Path, Query, Request, State,
+    },
+    http::{header, HeaderMap, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::{get, post, put},
+    Json, Router,
+};
+use bon::Builder;
+use chrono::{DateTime, Utc};
+use comfy_quant_base::{generate_workflow_id, Exchange, KlineInterval, Market, Symbol};
+use comfy_quant_config::setting::Retention;
+use comfy_quant_database::{
+    kline_task::{self, KlineTask},
+    order::{self, OrderFilter, OrderPage},
+    retention::ArchiveReport,
+    strategy_journal::{self, CreateJournalParams, StrategyJournal},
+    strategy_spot_stats::{self, StrategySpotStats},
+    workflow_deployment,
+    workflow_run::{self, WorkflowRunStatus},
+};
+use comfy_quant_node::{
+    node_core::{
+        ExchangeRateManager, NodeExecutable, NodeHealth, NodeMetadata, TradeStatsExt,
+        ValidationError, WorkflowEvent,
+    },
+    nodes::node_registry,
+    stats::{PerformanceReport, StatsAggregate, StatsAggregator},
+    workflow::{Node, QuoteAsset, Workflow},
+};
+use comfy_quant_task::{
+    task_core::control::{self, TaskInfo},
+    tasks::{
+        binance_klines::BinanceKlinesTask, bybit_klines::BybitKlinesTask, okx_klines::OkxKlinesTask,
+    },
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::PgPool;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex as StdMutex},
+    time::{Duration, Instant},
+};
+use tokio::sync::{
+    broadcast::{self, error::RecvError},
+    Mutex,
+};
+
+// 主备故障转移：运行工作流的实例持有租约并定期续期心跳，
+// 备用实例发现心跳超时后获取租约，从最近一次检查点恢复运行
+#[derive(Builder, Debug, Clone)]
+#[builder(on(String, into))]
+pub struct FailoverOptions {
+    #[builder(default = generate_workflow_id())]
+    instance_id: String, // 实例ID，重启后沿用同一ID可以立即恢复自己的工作流
+    #[builder(default = 5)]
+    heartbeat_secs: u64, // 心跳间隔(秒)
+    #[builder(default = 30)]
+    takeover_secs: i64, // 心跳超时多久后由备用实例接管(秒)
+    #[builder(default)]
+    standby: bool, // 是否作为备用实例接管其他实例的工作流
+}
+
+impl Default for FailoverOptions {
+    fn default() -> Self {
+        FailoverOptions::builder().build()
+    }
+}
+
+// HTTP服务的共享状态
+#[derive(Clone)]
+pub struct AppState {
+    db: Arc<PgPool>,                                         // 数据库
+    exchange_rate_manager: Arc<RwLock<ExchangeRateManager>>, // 汇率管理器
+    running: Arc<Mutex<HashMap<String, Workflow>>>,          // 运行中的工作流
+    failover: Arc<FailoverOptions>,                          // 故障转移配置
+    net_values: Arc<NetValueCache>,                          // 重采样净值序列缓存
+    retention: Arc<Retention>,                               // 数据保留策略
+    starting: Arc<StdMutex<HashSet<String>>>,                // 启动中的工作流
+    admin_token: Option<Arc<str>>,                           // 管理接口的访问令牌，为空时不开放
+}
+
+impl AppState {
+    pub fn new(db: Arc<PgPool>) -> Self {
+        AppState {
+            db,
+            exchange_rate_manager: Arc::new(RwLock::new(ExchangeRateManager::default())),
+            running: Arc::new(Mutex::new(HashMap::new())),
+            failover: Arc::new(FailoverOptions::default()),
+            net_values: Arc::new(NetValueCache::default()),
+            retention: Arc::new(Retention::default()),
+            starting: Arc::new(StdMutex::new(HashSet::new())),
+            admin_token: None,
+        }
+    }
+
+    pub fn with_failover(mut self, failover: FailoverOptions) -> Self {
+        self.failover = Arc::new(failover);
+        self
+    }
+
+    pub fn with_retention(mut self, retention: Retention) -> Self {
+        self.retention = Arc::new(retention);
+        self
+    }
+
+    pub fn with_admin_token(mut self, admin_token: Option<String>) -> Self {
+        self.admin_token = admin_token.filter(|token| !token.is_empty()).map(Arc::from);
+        self
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ApiError {
+    #[error("Workflow not found: {0}")]
+    NotFound(String),
+
+    #[error("Task not found: {0}")]
+    TaskNotFound(u64),
+
+    #[error("Journal not found: {0}")]
+    JournalNotFound(i32),
+
+    #[error("Workflow already running: {0}")]
+    Conflict(String),
+
+    #[error("Unauthorized")]
+    Unauthorized,
+
+    #[error("{0}")]
+    BadRequest(String),
+
+    #[error(transparent)]
+    InvalidWorkflow(#[from] ValidationError),
+
+    #[error(transparent)]
+    Internal(#[from] anyhow::Error),
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = match &self {
+            ApiError::NotFound(_) | ApiError::TaskNotFound(_) | ApiError::JournalNotFound(_) => {
+                StatusCode::NOT_FOUND
+            }
+            ApiError::Conflict(_) => StatusCode::CONFLICT,
+            ApiError::Unauthorized => StatusCode::UNAUTHORIZED,
+            ApiError::BadRequest(_) | ApiError::InvalidWorkflow(_) => StatusCode::BAD_REQUEST,
+            ApiError::Internal(e) => {
+                tracing::error!("API internal error: {:?}", e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        };
+
+        // 工作流校验失败时附带逐项问题，前端据此在对应节点上提示
+        let body = match &self {
+            ApiError::InvalidWorkflow(e) => {
+                json!({ "error": self.to_string(), "diagnostics": e.diagnostics })
+            }
+            _ => json!({ "error": self.to_string() }),
+        };
+
+        (status, Json(body)).into_response()
+    }
+}
+
+type ApiResult<T> = Result<Json<T>, ApiError>;
+
+pub fn router(state: AppState) -> Router {
+    let router = Router::new()
+        .route("/nodes", get(list_node_metadata))
+        .route("/workflows", post(create_workflow))
+        .route("/workflows/validate", post(validate_workflow))
+        .route("/workflows/:workflow_id/clone", post(clone_workflow))
+        .route("/workflows/:workflow_id/start", post(start_workflow))
+        .route("/workflows/:workflow_id/stop", post(stop_workflow))
+        .route("/workflows/:workflow_id/nodes", get(list_nodes))
+        .route("/workflows/:workflow_id/stats", get(list_stats))
+        .route("/workflows/:workflow_id/net-values", get(list_net_values))
+        .route(
+            "/workflows/:workflow_id/net-values/export",
+            get(export_net_values),
+        )
+        .route(
+            "/workflows/:workflow_id/performance",
+            get(workflow_performance),
+        )
+        .route("/workflows/:workflow_id/orders", get(list_orders))
+        .route(
+            "/workflows/:workflow_id/journals",
+            get(list_journals).post(create_journal),
+        )
+        .route(
+            "/journals/:journal_id",
+            put(update_journal).delete(delete_journal),
+        )
+        .route("/workflows/:workflow_id/events", get(workflow_events))
+        .route("/workflows/:workflow_id/health", get(workflow_health))
+        .route(
+            "/workflows/:workflow_id/param-preview",
+            post(preview_param_changes),
+        )
+        .route("/tasks", get(list_tasks))
+        .route("/metrics", get(prometheus_metrics))
+        .route("/stats/aggregate", get(stats_aggregate))
+        .route("/stats/aggregate/ws", get(stats_aggregate_events))
+        .route("/tasks/:task_id/cancel", post(cancel_task))
+        .route("/tasks/:task_id/pause", post(pause_task))
+        .route("/tasks/:task_id/resume", post(resume_task));
+
+    // 管理接口可以修改日志级别和删除数据，配置访问令牌后才开放
+    let router = if state.admin_token.is_some() {
+        let admin = Router::new()
+            .route("/admin/log-filter", get(get_log_filter).put(put_log_filter))
+            .route("/admin/archive", post(archive_expired))
+            .route_layer(middleware::from_fn_with_state(
+                state.clone(),
+                require_admin_token,
+            ));
+
+        router.merge(admin)
+    } else {
+        router
+    };
+
+    router.with_state(state)
+}
+
+// 管理接口的请求需携带 Authorization: Bearer <令牌>
+async fn require_admin_token(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if !is_admin(state.admin_token.as_deref(), request.headers()) {
+        return ApiError::Unauthorized.into_response();
+    }
+
+    next.run(request).await
+}
+
+// 逐字节比较全部内容，比较耗时与令牌不同的位置无关
+fn is_admin(admin_token: Option<&str>, headers: &HeaderMap) -> bool {
+    let Some(admin_token) = admin_token else {
+        return false;
+    };
+
+    let token = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .unwrap_or_default();
+
+    token.len() == admin_token.len()
+        && token
+            .bytes()
+            .zip(admin_token.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+// 启动HTTP服务，先恢复进程退出前仍在运行的工作流，其他实例仍持有租约的工作流不恢复
+pub async fn serve(addr: &str, state: AppState) -> Result<()> {
+    comfy_quant_observability::init_metrics()?;
+    workflow_metrics::describe_metrics();
+
+    for run in workflow_run::list_running(&state.db).await? {
+        if let Err(e) = start(&state, &run.workflow_id, None, None).await {
+            tracing::error!("Resume workflow {} failed: {}", run.workflow_id, e);
+        }
+    }
+
+    // 备用实例不续传，避免与主实例重复下载
+    if !state.failover.standby {
+        resume_kline_tasks(&state.db).await;
+    }
+
+    tokio::spawn(keep_alive(state.clone()));
+
+    if state.failover.standby {
+        tokio::spawn(take_over_expired(state.clone()));
+    }
+
+    tracing::info!(
+        instance_id = %state.failover.instance_id,
+        standby = state.failover.standby,
+        "Workflow lease heartbeat started"
+    );
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    tracing::info!("HTTP server listening on {}", addr);
+
+    axum::serve(listener, router(state)).await?;
+
+    Ok(())
+}
+
+// 继续下载进程退出前未完成的K线任务
+async fn resume_kline_tasks(db: &Arc<PgPool>) {
+    let resumed = [
+        BinanceKlinesTask::resume_unfinished(Arc::clone(db)).await,
+        OkxKlinesTask::resume_unfinished(Arc::clone(db)).await,
+        BybitKlinesTask::resume_unfinished(Arc::clone(db)).await,
+    ];
+
+    for result in resumed {
+        match result {
+            Ok(0) => {}
+            Ok(count) => tracing::info!("Resumed {} klines tasks", count),
+            Err(e) => tracing::error!("Resume klines tasks failed: {}", e),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ValidateResponse {
+    content_hash: String, // 工作流定义哈希
+    nodes: Vec<Node>,     // 按执行顺序排列的节点
+}
+
+#[derive(Debug, Deserialize)]
+struct CreateQuery {
+    workflow_id: Option<String>, // 逻辑工作流ID，为空时按内容查找或新建
+}
+
+#[derive(Debug, Serialize)]
+struct CreateResponse {
+    workflow_id: String,  // 逻辑工作流ID
+    version: i32,         // 版本号
+    content_hash: String, // 工作流定义哈希
+    created: bool,        // 是否创建了新版本
+}
+
+#[derive(Debug, Deserialize)]
+struct StartQuery {
+    quote_asset: Option<String>, // 计价资产，为空时沿用工作流中的设置
+    nodes: Option<String>, // 只执行的节点ID，逗号分隔，同时执行其上游依赖节点，为空时执行全部节点
+}
+
+impl StartQuery {
+    fn node_ids(&self) -> Result<Option<Vec<u32>>, ApiError> {
+        let Some(nodes) = self.nodes.as_deref().filter(|nodes| !nodes.is_empty()) else {
+            return Ok(None);
+        };
+
+        nodes
+            .split(',')
+            .map(|node_id| {
+                node_id
+                    .trim()
+                    .parse()
+                    .map_err(|_| ApiError::BadRequest(format!("Invalid node id: {}", node_id)))
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(Some)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct StopQuery {
+    #[serde(default)]
+    cancel_orders: bool, // 是否撤销策略节点的挂单，默认保留挂单，恢复运行时继续跟踪
+}
+
+#[derive(Debug, Serialize)]
+struct WorkflowStatusResponse {
+    workflow_id: String, // 工作流ID
+    status: String,      // 状态
+    resumed: bool,       // 是否从检查点恢复
+}
+
+#[derive(Debug, Serialize)]
+struct WorkflowHealthResponse {
+    workflow_id: String,    // 工作流ID
+    status: String,         // 状态，有节点失败且不再重启时为 failed
+    nodes: Vec<NodeHealth>, // 各节点运行状态
+}
+
+#[derive(Debug, Deserialize)]
+struct ParamPreviewRequest {
+    changes: Vec<ParamChange>,   // 参数变更
+    lookback_hours: Option<i64>, // 回测最近多少小时的数据，默认24小时
+}
+
+#[derive(Debug, Deserialize)]
+struct NetValueQuery {
+    node_id: Option<i16>, // 策略节点ID，为空时返回所有节点
+    from: DateTime<Utc>,  // 开始时间
+    to: DateTime<Utc>,    // 结束时间
+}
+
+#[derive(Debug, Deserialize)]
+struct NetValueExportQuery {
+    node_id: Option<i16>,     // 策略节点ID，为空时返回所有节点
+    from: DateTime<Utc>,      // 开始时间
+    to: DateTime<Utc>,        // 结束时间
+    interval: Option<String>, // 重采样间隔: 1m/1h/1d，默认1h
+    format: Option<String>,   // 导出格式: json/csv，默认json
+}
+
+#[derive(Debug, Deserialize)]
+struct OrderQuery {
+    node_id: Option<i16>,        // 策略节点ID
+    symbol: Option<String>,      // 交易对
+    side: Option<String>,        // 订单方向: BUY/SELL
+    from: Option<DateTime<Utc>>, // 开始时间
+    to: Option<DateTime<Utc>>,   // 结束时间
+    page: Option<u32>,           // 页码，从1开始
+    page_size: Option<u32>,      // 每页数量
+}
+
+impl OrderQuery {
+    fn into_filter(self, workflow_id: String) -> Result<OrderFilter, ApiError> {
+        if let (Some(from), Some(to)) = (&self.from, &self.to) {
+            if from >= to {
+                return Err(ApiError::BadRequest(
+                    "from must be earlier than to".to_string(),
+                ));
+            }
+        }
+
+        let side = self.side.map(|side| side.to_uppercase());
+
+        if let Some(side) = &side {
+            if side != "BUY" && side != "SELL" {
+                return Err(ApiError::BadRequest(format!("Invalid side: {}", side)));
+            }
+        }
+
+        Ok(OrderFilter::builder()
+            .workflow_id(workflow_id)
+            .maybe_node_id(self.node_id)
+            .maybe_symbol(self.symbol.map(|symbol| symbol.to_uppercase()))
+            .maybe_order_side(side)
+            .maybe_start_datetime(self.from)
+            .maybe_end_datetime(self.to)
+            .maybe_page(self.page)
+            .maybe_page_size(self.page_size)
+            .build())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ArchiveRequest {
+    #[serde(default)]
+    tables: Vec<String>, // 只处理指定的表，为空则处理所有已配置的表
+    days: Option<u32>, // 覆盖配置中的保留天数
+    #[serde(default)]
+    dry_run: bool, // 只统计过期行数，不归档也不删除
+}
+
+#[derive(Debug, Serialize)]
+struct ArchiveReportItem {
+    table: String,         // 表
+    cutoff: DateTime<Utc>, // 过期时间
+    rows: u64,             // 过期行数，非试运行时为已归档并删除的行数
+    file: Option<String>,  // 归档文件，试运行或没有过期数据时为空
+    dry_run: bool,         // 是否试运行
+}
+
+impl From<ArchiveReport> for ArchiveReportItem {
+    fn from(report: ArchiveReport) -> Self {
+        ArchiveReportItem {
+            table: report.table.to_string(),
+            cutoff: report.cutoff,
+            rows: report.rows,
+            file: report.file.map(|file| file.display().to_string()),
+            dry_run: report.dry_run,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct LogFilter {
+    filter: String, // 日志过滤规则，如 "info,comfy_quant_node=debug"
+}
+
+#[derive(Debug, Deserialize)]
+struct JournalQuery {
+    node_id: Option<i16>,        // 策略节点ID
+    order_id: Option<String>,    // 关联的订单ID
+    from: Option<DateTime<Utc>>, // 开始时间，与结束时间一起指定时返回与该时间段有交集的日志
+    to: Option<DateTime<Utc>>,   // 结束时间
+}
+
+#[derive(Debug, Deserialize)]
+struct CreateJournalRequest {
+    node_id: i16,                      // 策略节点ID
+    order_id: Option<String>,          // 关联的订单ID
+    start_time: Option<DateTime<Utc>>, // 关联时间段的开始时间
+    end_time: Option<DateTime<Utc>>,   // 关联时间段的结束时间
+    content: String,                   // 日志内容
+    #[serde(default)]
+    tags: Vec<String>, // 标签
+}
+
+#[derive(Debug, Deserialize)]
+struct UpdateJournalRequest {
+    content: String, // 日志内容
+    #[serde(default)]
+    tags: Vec<String>, // 标签
+}
+
+fn parse_workflow(definition: &str) -> Result<Workflow, ApiError> {
+    let workflow: Workflow = serde_json::from_str(definition)
+        .map_err(|e| ApiError::BadRequest(format!("Invalid workflow: {}", e)))?;
+
+    workflow.validate()?;
+
+    Ok(workflow)
+}
+
+// 支持的节点，供前端节点面板使用
+async fn list_node_metadata() -> Json<Vec<NodeMetadata>> {
+    Json(node_registry())
+}
+
+// 校验工作流定义
+async fn validate_workflow(definition: String) -> ApiResult<ValidateResponse> {
+    let workflow = parse_workflow(&definition)?;
+
+    Ok(Json(ValidateResponse {
+        content_hash: workflow.content_hash()?,
+        nodes: workflow.nodes().into_iter().cloned().collect(),
+    }))
+}
+
+// 创建工作流，相同定义重复提交返回已有版本
+async fn create_workflow(
+    State(state): State<AppState>,
+    Query(query): Query<CreateQuery>,
+    definition: String,
+) -> ApiResult<CreateResponse> {
+    parse_workflow(&definition)?;
+
+    let deployment = deploy::deploy(&state.db, &definition, query.workflow_id.as_deref()).await?;
+
+    Ok(Json(CreateResponse {
+        workflow_id: deployment.deployment.workflow_id,
+        version: deployment.deployment.version,
+        content_hash: deployment.deployment.content_hash,
+        created: deployment.created,
+    }))
+}
+
+// 复制工作流的最新版本，节点和连接重新编号后部署为新的逻辑工作流，
+// 不与原工作流共用统计数据。未指定新工作流ID时自动生成
+async fn clone_workflow(
+    State(state): State<AppState>,
+    Path(workflow_id): Path<String>,
+    Query(query): Query<CreateQuery>,
+) -> ApiResult<CreateResponse> {
+    let target_id = query.workflow_id.unwrap_or_else(generate_workflow_id);
+
+    if target_id == workflow_id {
+        return Err(ApiError::BadRequest(
+            "Clone target must differ from the source workflow".to_string(),
+        ));
+    }
+
+    let deployment = workflow_deployment::latest(&state.db, &workflow_id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(workflow_id.clone()))?;
+    let workflow = parse_workflow(&deployment.definition)?;
+    let definition =
+        serde_json::to_string(&workflow.clone_with_new_ids()).map_err(anyhow::Error::from)?;
+
+    let deployment = deploy::deploy(&state.db, &definition, Some(&target_id)).await?;
+
+    Ok(Json(CreateResponse {
+        workflow_id: deployment.deployment.workflow_id,
+        version: deployment.deployment.version,
+        content_hash: deployment.deployment.content_hash,
+        created: deployment.created,
+    }))
+}
+
+// 启动工作流的最新版本，检查点与最新版本一致时从检查点恢复运行时数据；
+// 指定 node_ids 时只执行这些节点及其上游依赖，用于调试大型工作流
+async fn start(
+    state: &AppState,
+    workflow_id: &str,
+    quote_asset: Option<String>,
+    node_ids: Option<Vec<u32>>,
+) -> Result<bool, ApiError> {
+    // 只在检查和登记时持有锁，启动过程中的数据库操作不阻塞其他工作流
+    let _starting = {
+        let running = state.running.lock().await;
+
+        if running.contains_key(workflow_id) {
+            return Err(ApiError::Conflict(workflow_id.to_string()));
+        }
+
+        StartingGuard::try_new(&state.starting, workflow_id)
+            .ok_or_else(|| ApiError::Conflict(workflow_id.to_string()))?
+    };
+
+    // 获取运行租约，其他实例正在运行且心跳正常时拒绝启动
+    let FailoverOptions {
+        instance_id,
+        takeover_secs,
+        ..
+    } = state.failover.as_ref();
+
+    if !workflow_run::claim(&state.db, workflow_id, instance_id, *takeover_secs).await? {
+        return Err(ApiError::Conflict(workflow_id.to_string()));
+    }
+
+    // 启动失败时释放租约，其他实例可以立即接管
+    let (workflow, resumed) = match launch(state, workflow_id, quote_asset, node_ids).await {
+        Ok(launched) => launched,
+        Err(e) => {
+            if let Err(e) = workflow_run::release(&state.db, workflow_id, instance_id).await {
+                tracing::error!("Release workflow {} lease failed: {}", workflow_id, e);
+            }
+
+            return Err(e);
+        }
+    };
+
+    state
+        .running
+        .lock()
+        .await
+        .insert(workflow_id.to_string(), workflow);
+
+    // 重新开始运行时净值从头计算，清除旧的缓存
+    if !resumed {
+        state.net_values.invalidate(workflow_id);
+    }
+
+    Ok(resumed)
+}
+
+// 加载并执行工作流，执行或保存运行状态失败时停止已启动的节点
+async fn launch(
+    state: &AppState,
+    workflow_id: &str,
+    quote_asset: Option<String>,
+    node_ids: Option<Vec<u32>>,
+) -> Result<(Workflow, bool), ApiError> {
+    let deployment = workflow_deployment::latest(&state.db, workflow_id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(workflow_id.to_string()))?;
+
+    let checkpoint = match Workflow::load(&state.db, workflow_id).await? {
+        Some(workflow) if workflow.content_hash()? == deployment.content_hash => Some(workflow),
+        _ => None,
+    };
+    let resumed = checkpoint.is_some();
+
+    let mut workflow = match checkpoint {
+        Some(workflow) => workflow,
+        None => parse_workflow(&deployment.definition)?.with_workflow_id(workflow_id),
+    }
+    .with_owner(&state.failover.instance_id);
+
+    let quote_asset = match quote_asset {
+        Some(quote_asset) => QuoteAsset::from(quote_asset),
+        None => workflow.quote_asset().await,
+    };
+
+    workflow
+        .setup(
+            Arc::clone(&state.db),
+            Arc::clone(&state.exchange_rate_manager),
+            quote_asset,
+        )
+        .await?;
+
+    // 执行前订阅，统计节点启动后的全部事件
+    tokio::spawn(workflow_metrics::record_workflow_events(
+        workflow_id.to_string(),
+        workflow.subscribe_events()?,
+    ));
+
+    let executed = match &node_ids {
+        Some(node_ids) => workflow.execute_subgraph(node_ids).await,
+        None => workflow.execute().await.map_err(anyhow::Error::from),
+    };
+
+    // 未开启定时检查点时也记录运行状态，进程重启后可以恢复
+    let saved = match executed {
+        Ok(()) => workflow.save(WorkflowRunStatus::Running).await,
+        Err(e) => Err(e),
+    };
+
+    if let Err(e) = saved {
+        workflow.stop();
+        return Err(e.into());
+    }
+
+    tracing::info!(
+        monotonic_counter.workflow_started = 1_u64,
+        workflow_id = %workflow_id,
+        version = deployment.version,
+        resumed,
+        "Workflow started"
+    );
+
+    Ok((workflow, resumed))
+}
+
+// 启动中的工作流登记，同一工作流的并发启动只有一个成功，结束时自动注销
+struct StartingGuard<'a> {
+    starting: &'a StdMutex<HashSet<String>>,
+    workflow_id: String,
+}
+
+impl<'a> StartingGuard<'a> {
+    fn try_new(starting: &'a StdMutex<HashSet<String>>, workflow_id: &str) -> Option<Self> {
+        let inserted = starting
+            .lock()
+            .map(|mut starting| starting.insert(workflow_id.to_string()))
+            .unwrap_or_default();
+
+        inserted.then(|| StartingGuard {
+            starting,
+            workflow_id: workflow_id.to_string(),
+        })
+    }
+}
+
+impl Drop for StartingGuard<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut starting) = self.starting.lock() {
+            starting.remove(&self.workflow_id);
+        }
+    }
+}
+
+// 定期续期运行中工作流的租约，租约被其他实例接管时停止本实例的工作流，避免重复下单。
+// 超过接管时间仍未续期成功(如数据库不可用)时，备用实例可能已经接管，同样停止
+async fn keep_alive(state: AppState) {
+    let mut interval =
+        tokio::time::interval(Duration::from_secs(state.failover.heartbeat_secs.max(1)));
+    let takeover = Duration::from_secs(state.failover.takeover_secs.max(0) as u64);
+    let mut renewed_at = HashMap::<String, Instant>::new();
+
+    loop {
+        interval.tick().await;
+
+        let workflow_ids = state
+            .running
+            .lock()
+            .await
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+
+        // 已停止的工作流不再跟踪
+        renewed_at.retain(|workflow_id, _| workflow_ids.contains(workflow_id));
+
+        for workflow_id in workflow_ids {
+            // 启动时获取租约即为一次续期
+            let last_renewed = *renewed_at
+                .entry(workflow_id.clone())
+                .or_insert_with(Instant::now);
+
+            let reason =
+                match workflow_run::heartbeat(&state.db, &workflow_id, &state.failover.instance_id)
+                    .await
+                {
+                    Ok(true) => {
+                        renewed_at.insert(workflow_id, Instant::now());
+                        continue;
+                    }
+                    Ok(false) => "Workflow lease lost, stopped on this instance",
+                    Err(e) if last_renewed.elapsed() >= takeover => {
+                        tracing::warn!("Workflow {} heartbeat failed: {}", workflow_id, e);
+                        "Workflow lease expired, stopped on this instance"
+                    }
+                    // 数据库暂时不可用时保留工作流，租约超时前继续重试
+                    Err(e) => {
+                        tracing::warn!("Workflow {} heartbeat failed: {}", workflow_id, e);
+                        continue;
+                    }
+                };
+
+            renewed_at.remove(&workflow_id);
+
+            let Some(workflow) = state.running.lock().await.remove(&workflow_id) else {
+                continue;
+            };
+
+            workflow.stop();
+
+            tracing::warn!(
+                monotonic_counter.workflow_lease_lost = 1_u64,
+                workflow_id = %workflow_id,
+                "{}",
+                reason
+            );
+        }
+    }
+}
+
+// 备用实例接管心跳超时的工作流，从最近一次检查点恢复运行，策略节点恢复后核对未完结订单
+async fn take_over_expired(state: AppState) {
+    let FailoverOptions {
+        instance_id,
+        heartbeat_secs,
+        takeover_secs,
+        ..
+    } = state.failover.as_ref();
+    let mut interval = tokio::time::interval(Duration::from_secs((*heartbeat_secs).max(1)));
+
+    loop {
+        interval.tick().await;
+
+        let runs = match workflow_run::list_expired(&state.db, instance_id, *takeover_secs).await {
+            Ok(runs) => runs,
+            Err(e) => {
+                tracing::warn!("List expired workflows failed: {}", e);
+                continue;
+            }
+        };
+
+        for run in runs {
+            match start(&state, &run.workflow_id, None, None).await {
+                Ok(resumed) => tracing::warn!(
+                    monotonic_counter.workflow_failover = 1_u64,
+                    workflow_id = %run.workflow_id,
+                    previous_owner = %run.owner,
+                    heartbeat_at = %run.heartbeat_at,
+                    resumed,
+                    "Workflow taken over"
+                ),
+                // 其他备用实例已接管
+                Err(ApiError::Conflict(_)) => {}
+                Err(e) => tracing::error!("Take over workflow {} failed: {}", run.workflow_id, e),
+            }
+        }
+    }
+}
+
+async fn start_workflow(
+    State(state): State<AppState>,
+    Path(workflow_id): Path<String>,
+    Query(query): Query<StartQuery>,
+) -> ApiResult<WorkflowStatusResponse> {
+    let node_ids = query.node_ids()?;
+    let resumed = start(&state, &workflow_id, query.quote_asset, node_ids).await?;
+
+    Ok(Json(WorkflowStatusResponse {
+        workflow_id,
+        status: WorkflowRunStatus::Running.to_string(),
+        resumed,
+    }))
+}
+
+// 停止工作流，等待节点退出并保存检查点，再次启动时从检查点恢复
+async fn stop_workflow(
+    State(state): State<AppState>,
+    Path(workflow_id): Path<String>,
+    Query(query): Query<StopQuery>,
+) -> ApiResult<WorkflowStatusResponse> {
+    let mut workflow = state
+        .running
+        .lock()
+        .await
+        .remove(&workflow_id)
+        .ok_or_else(|| ApiError::NotFound(workflow_id.clone()))?;
+
+    // 收尾失败时仍保存检查点并释放租约，工作流已从运行列表移除，否则会丢失
+    let shutdown = workflow.shutdown(query.cancel_orders).await;
+
+    // 节点失败后停止的工作流保留失败状态
+    let status = workflow.run_status();
+    let saved = workflow.save(status.clone()).await;
+
+    if let Err(e) =
+        workflow_run::release(&state.db, &workflow_id, &state.failover.instance_id).await
+    {
+        tracing::error!("Release workflow {} lease failed: {}", workflow_id, e);
+    }
+
+    if let Err(e) = &shutdown {
+        tracing::error!("Shutdown workflow {} failed: {}", workflow_id, e);
+    }
+
+    saved?;
+    shutdown?;
+
+    Ok(Json(WorkflowStatusResponse {
+        workflow_id,
+        status: status.to_string(),
+        resumed: false,
+    }))
+}
+
+// 以近期数据回测运行中工作流的参数变更，返回与当前参数的对比，不修改运行中的工作流
+async fn preview_param_changes(
+    State(state): State<AppState>,
+    Path(workflow_id): Path<String>,
+    Json(request): Json<ParamPreviewRequest>,
+) -> ApiResult<ParamPreviewReport> {
+    let quote_asset = match state.running.lock().await.get(&workflow_id) {
+        Some(workflow) => workflow.quote_asset().await,
+        None => return Err(ApiError::NotFound(workflow_id)),
+    };
+
+    let lookback_hours = request.lookback_hours.unwrap_or(24);
+
+    if request.changes.is_empty() || !(1..=24 * 30).contains(&lookback_hours) {
+        return Err(ApiError::BadRequest(
+            "changes must not be empty and lookback_hours must be between 1 and 720".to_string(),
+        ));
+    }
+
+    let deployment = workflow_deployment::latest(&state.db, &workflow_id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(workflow_id.clone()))?;
+
+    let preview = ParamPreview::builder()
+        .workflow(deployment.definition)
+        .changes(request.changes)
+        .db(Arc::clone(&state.db))
+        .lookback(chrono::Duration::hours(lookback_hours))
+        .quote_asset(quote_asset.as_ref())
+        .build();
+
+    preview
+        .candidate()
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+
+    Ok(Json(preview.run().await?))
+}
+
+// 工作流最新版本的节点
+async fn list_nodes(
+    State(state): State<AppState>,
+    Path(workflow_id): Path<String>,
+) -> ApiResult<Vec<Node>> {
+    let deployment = workflow_deployment::latest(&state.db, &workflow_id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(workflow_id.clone()))?;
+    let workflow = parse_workflow(&deployment.definition)?;
+
+    Ok(Json(workflow.nodes().into_iter().cloned().collect()))
+}
+
+// 工作流中各策略节点的统计
+async fn list_stats(
+    State(state): State<AppState>,
+    Path(workflow_id): Path<String>,
+) -> ApiResult<Vec<StrategySpotStats>> {
+    let stats = strategy_spot_stats::list_by_workflow(&state.db, &workflow_id).await?;
+
+    Ok(Json(stats))
+}
+
+// 工作流中各策略节点的净值序列
+async fn list_net_values(
+    State(state): State<AppState>,
+    Path(workflow_id): Path<String>,
+    Query(query): Query<NetValueQuery>,
+) -> ApiResult<Vec<NetValueSeries>> {
+    if query.from >= query.to {
+        return Err(ApiError::BadRequest(
+            "from must be earlier than to".to_string(),
+        ));
+    }
+
+    let series = net_value::list(
+        &state.db,
+        &workflow_id,
+        query.node_id,
+        &query.from,
+        &query.to,
+    )
+    .await?;
+
+    Ok(Json(series))
+}
+
+// 按间隔重采样的净值序列，导出为JSON或CSV用于绘图
+async fn export_net_values(
+    State(state): State<AppState>,
+    Path(workflow_id): Path<String>,
+    Query(query): Query<NetValueExportQuery>,
+) -> Result<Response, ApiError> {
+    if query.from >= query.to {
+        return Err(ApiError::BadRequest(
+            "from must be earlier than to".to_string(),
+        ));
+    }
+
+    let interval = match query.interval.as_deref().unwrap_or("1h") {
+        interval @ ("1m" | "1h" | "1d") => KlineInterval::from(interval),
+        interval => {
+            return Err(ApiError::BadRequest(format!(
+                "Invalid interval: {}",
+                interval
+            )))
+        }
+    };
+
+    let series = state
+        .net_values
+        .resampled(
+            &state.db,
+            &workflow_id,
+            query.node_id,
+            &interval,
+            &query.from,
+            &query.to,
+        )
+        .await?;
+
+    match query.format.as_deref().unwrap_or("json") {
+        "json" => Ok(Json(series.as_ref()).into_response()),
+        "csv" => Ok((
+            [(header::CONTENT_TYPE, "text/csv; charset=utf-8")],
+            net_value::to_csv(&series),
+        )
+            .into_response()),
+        format => Err(ApiError::BadRequest(format!("Invalid format: {}", format))),
+    }
+}
+
+// 运行中工作流的绩效报告
+async fn workflow_performance(
+    State(state): State<AppState>,
+    Path(workflow_id): Path<String>,
+) -> ApiResult<PerformanceReport> {
+    let running = state.running.lock().await;
+    let workflow = running
+        .get(&workflow_id)
+        .ok_or_else(|| ApiError::NotFound(workflow_id.clone()))?;
+
+    Ok(Json(workflow.performance_report().await?))
+}
+
+// 工作流的订单历史，支持按节点、交易对、方向和时间过滤并分页
+async fn list_orders(
+    State(state): State<AppState>,
+    Path(workflow_id): Path<String>,
+    Query(query): Query<OrderQuery>,
+) -> ApiResult<OrderPage> {
+    let filter = query.into_filter(workflow_id)?;
+    let page = order::list_orders(&state.db, &filter).await?;
+
+    Ok(Json(page))
+}
+
+// 策略日志，按订单、时间段或策略节点筛选，都未指定时返回工作流的全部日志
+async fn list_journals(
+    State(state): State<AppState>,
+    Path(workflow_id): Path<String>,
+    Query(query): Query<JournalQuery>,
+) -> ApiResult<Vec<StrategyJournal>> {
+    let journals = match query {
+        JournalQuery {
+            order_id: Some(order_id),
+            ..
+        } => strategy_journal::list_by_order(&state.db, &workflow_id, &order_id).await?,
+        JournalQuery {
+            from: Some(from),
+            to: Some(to),
+            ..
+        } => {
+            if from >= to {
+                return Err(ApiError::BadRequest(
+                    "from must be earlier than to".to_string(),
+                ));
+            }
+
+            strategy_journal::list_by_time_range(&state.db, &workflow_id, &from, &to).await?
+        }
+        JournalQuery {
+            node_id: Some(node_id),
+            ..
+        } => strategy_journal::list(&state.db, &workflow_id, node_id).await?,
+        _ => strategy_journal::list_by_workflow(&state.db, &workflow_id).await?,
+    };
+
+    Ok(Json(journals))
+}
+
+// 记录干预原因或当时的行情背景，可关联订单或时间段
+async fn create_journal(
+    State(state): State<AppState>,
+    Path(workflow_id): Path<String>,
+    Json(request): Json<CreateJournalRequest>,
+) -> ApiResult<StrategyJournal> {
+    if let (Some(start_time), Some(end_time)) = (&request.start_time, &request.end_time) {
+        if start_time > end_time {
+            return Err(ApiError::BadRequest(
+                "start_time must be earlier than end_time".to_string(),
+            ));
+        }
+    }
+
+    let data = CreateJournalParams::builder()
+        .workflow_id(workflow_id)
+        .node_id(request.node_id)
+        .maybe_order_id(request.order_id)
+        .maybe_start_time(request.start_time)
+        .maybe_end_time(request.end_time)
+        .content(request.content)
+        .tags(request.tags)
+        .build();
+
+    Ok(Json(strategy_journal::create(&state.db, data).await?))
+}
+
+async fn update_journal(
+    State(state): State<AppState>,
+    Path(journal_id): Path<i32>,
+    Json(request): Json<UpdateJournalRequest>,
+) -> ApiResult<StrategyJournal> {
+    strategy_journal::update(&state.db, journal_id, &request.content, &request.tags)
+        .await?
+        .map(Json)
+        .ok_or_else(|| ApiError::JournalNotFound(journal_id))
+}
+
+async fn delete_journal(
+    State(state): State<AppState>,
+    Path(journal_id): Path<i32>,
+) -> Result<StatusCode, ApiError> {
+    if !strategy_journal::delete(&state.db, journal_id).await? {
+        return Err(ApiError::JournalNotFound(journal_id));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// 推送运行中工作流的运行时事件，工作流停止后关闭连接
+async fn workflow_events(
+    State(state): State<AppState>,
+    Path(workflow_id): Path<String>,
+    ws: WebSocketUpgrade,
+) -> Result<Response, ApiError> {
+    let rx = state
+        .running
+        .lock()
+        .await
+        .get(&workflow_id)
+        .ok_or_else(|| ApiError::NotFound(workflow_id.clone()))?
+        .subscribe_events()?;
+
+    Ok(ws.on_upgrade(move |socket| push_events(socket, workflow_id, rx)))
+}
+
+// 运行中工作流的状态和各节点的运行状态、重启次数
+async fn workflow_health(
+    State(state): State<AppState>,
+    Path(workflow_id): Path<String>,
+) -> ApiResult<WorkflowHealthResponse> {
+    let running = state.running.lock().await;
+    let workflow = running
+        .get(&workflow_id)
+        .ok_or_else(|| ApiError::NotFound(workflow_id.clone()))?;
+
+    Ok(Json(WorkflowHealthResponse {
+        status: workflow.run_status().to_string(),
+        nodes: workflow.node_health(),
+        workflow_id,
+    }))
+}
+
+// Prometheus 指标，连接池和运行中工作流数量在抓取时采样
+async fn prometheus_metrics(State(state): State<AppState>) -> Result<Response, ApiError> {
+    workflow_metrics::record_db_pool(&state.db);
+    workflow_metrics::record_stats_aggregate();
+    metrics::gauge!("workflows_running").set(state.running.lock().await.len() as f64);
+
+    let body = comfy_quant_observability::render_metrics()
+        .ok_or_else(|| anyhow!("Metrics not initialized"))?;
+
+    Ok(([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], body).into_response())
+}
+
+// 所有运行中策略按计价资产汇总的市值、当日盈亏和敞口，由内存中的聚合服务直接返回
+async fn stats_aggregate() -> Json<StatsAggregate> {
+    Json(StatsAggregate::clone(&StatsAggregator::global().current()))
+}
+
+// 推送聚合统计的变化，连接后先推送一次当前结果
+async fn stats_aggregate_events(ws: WebSocketUpgrade) -> Response {
+    ws.on_upgrade(push_stats_aggregate)
+}
+
+// 当前的日志过滤规则
+async fn get_log_filter() -> ApiResult<LogFilter> {
+    let filter = comfy_quant_observability::log_filter()
+        .ok_or_else(|| anyhow!("Observability not initialized"))?;
+
+    Ok(Json(LogFilter { filter }))
+}
+
+// 运行时调整日志过滤规则，排查问题时临时打开某个模块的调试日志，无需重启
+async fn put_log_filter(Json(request): Json<LogFilter>) -> ApiResult<LogFilter> {
+    if comfy_quant_observability::log_filter().is_none() {
+        return Err(anyhow!("Observability not initialized").into());
+    }
+
+    comfy_quant_observability::set_log_filter(&request.filter)
+        .map_err(|e| ApiError::BadRequest(format!("Invalid log filter: {}", e)))?;
+
+    Ok(Json(request))
+}
+
+// 按保留策略归档并删除过期数据，归档目录只能由配置指定
+async fn archive_expired(
+    State(state): State<AppState>,
+    Json(request): Json<ArchiveRequest>,
+) -> ApiResult<Vec<ArchiveReportItem>> {
+    let options = RetentionOptions::builder()
+        .tables(request.tables)
+        .maybe_days(request.days)
+        .dry_run(request.dry_run)
+        .build();
+
+    // 表名或保留天数有误属于请求错误
+    retention::policies(&state.retention, &options)
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+
+    let summary = retention::run(&state.db, &state.retention, options).await?;
+
+    Ok(Json(
+        summary
+            .reports
+            .into_iter()
+            .map(ArchiveReportItem::from)
+            .collect(),
+    ))
+}
+
+#[derive(Debug, Serialize)]
+struct TasksResponse {
+    running: Vec<TaskInfo>,          // 正在执行的后台任务
+    kline_tasks: Vec<KlineTaskItem>, // 未完成的K线下载任务，包括中断后尚未续传的
+}
+
+#[derive(Debug, Serialize)]
+struct KlineTaskItem {
+    id: i32,                               // 任务ID
+    exchange: Exchange,                    // 交易所
+    market: Market,                        // 市场
+    symbol: Symbol,                        // 交易对
+    interval: String,                      // 时间间隔
+    start_time: DateTime<Utc>,             // 开始时间
+    end_time: DateTime<Utc>,               // 结束时间
+    last_open_time: Option<DateTime<Utc>>, // 最后一根已下载K线的开盘时间
+    status: String,                        // 状态
+}
+
+impl From<KlineTask> for KlineTaskItem {
+    fn from(task: KlineTask) -> Self {
+        KlineTaskItem {
+            id: task.id,
+            exchange: task.exchange,
+            market: task.market,
+            symbol: task.symbol,
+            interval: task.interval.to_string(),
+            start_time: task.start_time,
+            end_time: task.end_time,
+            last_open_time: task.last_open_time,
+            status: task.status.to_string(),
+        }
+    }
+}
+
+// 正在执行的后台任务，如K线回填，以及数据库中未完成的K线任务
+async fn list_tasks(State(state): State<AppState>) -> ApiResult<TasksResponse> {
+    let kline_tasks = kline_task::list_unfinished(&state.db)
+        .await?
+        .into_iter()
+        .map(KlineTaskItem::from)
+        .collect();
+
+    Ok(Json(TasksResponse {
+        running: control::registry().list(),
+        kline_tasks,
+    }))
+}
+
+// 取消任务，任务保存进度后退出
+async fn cancel_task(Path(task_id): Path<u64>) -> ApiResult<TaskInfo> {
+    task_action(task_id, |task| task.cancel())
+}
+
+async fn pause_task(Path(task_id): Path<u64>) -> ApiResult<TaskInfo> {
+    task_action(task_id, |task| task.pause())
+}
+
+async fn resume_task(Path(task_id): Path<u64>) -> ApiResult<TaskInfo> {
+    task_action(task_id, |task| task.resume())
+}
+
+fn task_action(task_id: u64, action: impl FnOnce(&control::TaskControl)) -> ApiResult<TaskInfo> {
+    let task = control::registry()
+        .get(task_id)
+        .ok_or(ApiError::TaskNotFound(task_id))?;
+
+    action(&task);
+
+    Ok(Json(task.info()))
+}
+
+async fn push_stats_aggregate(mut socket: WebSocket) {
+    let mut rx = StatsAggregator::global().subscribe();
+    rx.mark_changed();
+
+    loop {
+        tokio::select! {
+            changed = rx.changed() => if changed.is_err() {
+                break;
+            },
+            // 客户端断开或发送关闭帧
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => continue,
+            },
+        }
+
+        let aggregate = StatsAggregate::clone(&rx.borrow_and_update());
+
+        let text = match serde_json::to_string(&aggregate) {
+            Ok(text) => text,
+            Err(e) => {
+                tracing::error!("Serialize stats aggregate failed: {}", e);
+                continue;
+            }
+        };
+
+        if socket.send(Message::Text(text)).await.is_err() {
+            break;
+        }
+    }
+
+    let _ = socket.close().await;
+}
+
+async fn push_events(
+    mut socket: WebSocket,
+    workflow_id: String,
+    mut rx: broadcast::Receiver<WorkflowEvent>,
+) {
+    loop {
+        let event = tokio::select! {
+            event = rx.recv() => event,
+            // 客户端断开或发送关闭帧
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => continue,
+            },
+        };
+
+        let event = match event {
+            Ok(event) => event,
+            Err(RecvError::Lagged(skipped)) => {
+                tracing::warn!(
+                    monotonic_counter.workflow_events_lagged = skipped,
+                    workflow_id = %workflow_id,
+                    "Workflow event subscriber lagged, {} events skipped",
+                    skipped
+                );
+                continue;
+            }
+            Err(RecvError::Closed) => break,
+        };
+
+        let text = match serde_json::to_string(&event) {
+            Ok(text) => text,
+            Err(e) => {
+                tracing::error!("Serialize workflow event failed: {}", e);
+                continue;
+            }
+        };
+
+        if socket.send(Message::Text(text)).await.is_err() {
+            break;
+        }
+    }
+
+    let _ = socket.close().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_error_status() {
+        let status = |error: ApiError| error.into_response().status();
+
+        assert_eq!(
+            status(ApiError::NotFound("jEnbRDqQu4UN6y7cgQgp6".to_string())),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(status(ApiError::TaskNotFound(1)), StatusCode::NOT_FOUND);
+        assert_eq!(status(ApiError::JournalNotFound(1)), StatusCode::NOT_FOUND);
+        assert_eq!(
+            status(ApiError::Conflict("jEnbRDqQu4UN6y7cgQgp6".to_string())),
+            StatusCode::CONFLICT
+        );
+        assert_eq!(status(ApiError::Unauthorized), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            status(ApiError::BadRequest("Invalid workflow".to_string())),
+            StatusCode::BAD_REQUEST
+        );
+        assert_eq!(
+            status(ApiError::InvalidWorkflow(ValidationError {
+                diagnostics: vec![]
+            })),
+            StatusCode::BAD_REQUEST
+        );
+        assert_eq!(
+            status(anyhow::anyhow!("boom").into()),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+    }
+
+    #[test]
+    fn test_is_admin() -> Result<()> {
+        let headers = |value: &str| -> Result<HeaderMap> {
+            let mut headers = HeaderMap::new();
+            headers.insert(header::AUTHORIZATION, value.parse()?);
+            Ok(headers)
+        };
+
+        assert!(is_admin(Some("secret"), &headers("Bearer secret")?));
+        assert!(!is_admin(Some("secret"), &headers("Bearer secreT")?));
+        assert!(!is_admin(Some("secret"), &headers("Bearer secret2")?));
+        assert!(!is_admin(Some("secret"), &headers("secret")?));
+        assert!(!is_admin(Some("secret"), &HeaderMap::new()));
+        // 未配置令牌时管理接口不开放
+        assert!(!is_admin(None, &headers("Bearer ")?));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_archive_request() -> Result<()> {
+        let request: ArchiveRequest = serde_json::from_value(json!({}))?;
+        assert!(request.tables.is_empty());
+        assert_eq!(request.days, None);
+        assert!(!request.dry_run);
+
+        let request: ArchiveRequest = serde_json::from_value(json!({
+            "tables": ["strategy_journals"],
+            "days": 30,
+            "dry_run": true
+        }))?;
+        assert_eq!(request.tables, vec!["strategy_journals".to_string()]);
+        assert_eq!(request.days, Some(30));
+        assert!(request.dry_run);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_order_query_into_filter() -> Result<()> {
+        let query = |query| -> Result<OrderQuery> { Ok(serde_json::from_value(query)?) };
+
+        let filter = query(json!({ "symbol": "btcusdt", "side": "sell", "page": 2 }))?
+            .into_filter("jEnbRDqQu4UN6y7cgQgp6".to_string())
+            .map_err(|e| anyhow::anyhow!(e.to_string()))?;
+        assert_eq!(filter.symbol, Some("BTCUSDT".into()));
+        assert_eq!(filter.order_side.as_deref(), Some("SELL"));
+        assert_eq!(filter.page, 2);
+        assert_eq!(filter.page_size, 50);
+
+        assert!(matches!(
+            query(json!({ "side": "hold" }))?.into_filter("jEnbRDqQu4UN6y7cgQgp6".to_string()),
+            Err(ApiError::BadRequest(_))
+        ));
+        assert!(matches!(
+            query(json!({ "from": "2024-01-02T00:00:00Z", "to": "2024-01-01T00:00:00Z" }))?
+                .into_filter("jEnbRDqQu4UN6y7cgQgp6".to_string()),
+            Err(ApiError::BadRequest(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_start_query_node_ids() -> Result<()> {
+        let query = |query| -> Result<StartQuery> { Ok(serde_json::from_value(query)?) };
+
+        assert_eq!(
+            query(json!({ "nodes": "1, 3,5" }))?
+                .node_ids()
+                .map_err(|e| anyhow::anyhow!(e.to_string()))?,
+            Some(vec![1, 3, 5])
+        );
+        assert!(matches!(
+            query(json!({ "nodes": "" }))?.node_ids(),
+            Ok(None)
+        ));
+        assert!(matches!(query(json!({}))?.node_ids(), Ok(None)));
+        assert!(matches!(
+            query(json!({ "nodes": "1,a" }))?.node_ids(),
+            Err(ApiError::BadRequest(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_workflow() {
+        assert!(matches!(
+            parse_workflow("not a workflow"),
+            Err(ApiError::BadRequest(_))
+        ));
+    }
+}
@@ -0,0 +1,312 @@
+use crate::{
+    backtest::{self, BacktestSummary},
+    optimize::{self, ParamGrid, ParamSweep},
+};
+use anyhow::Result;
+use bon::Builder;
+use chrono::{DateTime, Duration, Utc};
+use comfy_quant_database::optimization_run::OptimizationRunStatus;
+use comfy_quant_node::workflow::Workflow;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use sqlx::PgPool;
+use std::{fmt, sync::Arc};
+
+// 样本内外窗口
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+pub struct Window {
+    pub in_sample_start: DateTime<Utc>,   // 样本内开始时间
+    pub in_sample_end: DateTime<Utc>,     // 样本内结束时间，即样本外开始时间
+    pub out_of_sample_end: DateTime<Utc>, // 样本外结束时间
+}
+
+// 按样本外长度滚动切分时间范围，最后一个样本外窗口不足时截断到结束时间。
+// anchored 为 true 时样本内窗口固定从开始时间起算，随窗口推进逐渐变长
+pub fn split_windows(
+    start_datetime: DateTime<Utc>,
+    end_datetime: DateTime<Utc>,
+    in_sample: Duration,
+    out_of_sample: Duration,
+    anchored: bool,
+) -> Vec<Window> {
+    let mut windows = vec![];
+
+    if in_sample <= Duration::zero() || out_of_sample <= Duration::zero() {
+        return windows;
+    }
+
+    let mut in_sample_start = start_datetime;
+    let mut in_sample_end = start_datetime + in_sample;
+
+    while in_sample_end < end_datetime {
+        windows.push(Window {
+            in_sample_start,
+            in_sample_end,
+            out_of_sample_end: (in_sample_end + out_of_sample).min(end_datetime),
+        });
+
+        if !anchored {
+            in_sample_start += out_of_sample;
+        }
+
+        in_sample_end += out_of_sample;
+    }
+
+    windows
+}
+
+// 单个窗口的结果
+#[derive(Serialize, Debug)]
+pub struct WindowResult {
+    pub window: Window,                 // 窗口
+    pub params: String,                 // 样本内最优的参数组合(JSON)
+    pub in_sample_return: Decimal,      // 样本内总收益率
+    pub out_of_sample: BacktestSummary, // 样本外回测结果
+}
+
+// 前推分析结果
+#[derive(Serialize, Debug)]
+pub struct WalkForwardReport {
+    pub windows: Vec<WindowResult>, // 各窗口结果
+}
+
+impl WalkForwardReport {
+    // 各样本外窗口依次复利的总收益率
+    pub fn compounded_return(&self) -> Decimal {
+        self.windows.iter().fold(Decimal::ONE, |acc, result| {
+            acc * (Decimal::ONE + result.out_of_sample.total_return)
+        }) - Decimal::ONE
+    }
+
+    // 样本外窗口中最大的回撤比例
+    pub fn max_drawdown(&self) -> Decimal {
+        self.windows
+            .iter()
+            .map(|result| result.out_of_sample.max_drawdown)
+            .max()
+            .unwrap_or_default()
+    }
+
+    // 样本外盈利的窗口比例
+    pub fn profitable_ratio(&self) -> Decimal {
+        if self.windows.is_empty() {
+            return Decimal::ZERO;
+        }
+
+        let profitable = self
+            .windows
+            .iter()
+            .filter(|result| result.out_of_sample.total_return > Decimal::ZERO)
+            .count();
+
+        Decimal::from(profitable) / Decimal::from(self.windows.len())
+    }
+
+    // 前推效率，样本外与样本内的日均收益率之比，越接近1说明参数越没有过拟合
+    pub fn efficiency(&self) -> Option<Decimal> {
+        let daily_return = |total_return: Decimal, start: DateTime<Utc>, end: DateTime<Utc>| {
+            let days = Decimal::from((end - start).num_seconds()) / Decimal::from(86400);
+            (!days.is_zero()).then(|| total_return / days)
+        };
+
+        let (in_sample, out_of_sample) = self.windows.iter().try_fold(
+            (Decimal::ZERO, Decimal::ZERO),
+            |(in_sample, out_of_sample), result| {
+                let window = &result.window;
+
+                Some((
+                    in_sample
+                        + daily_return(
+                            result.in_sample_return,
+                            window.in_sample_start,
+                            window.in_sample_end,
+                        )?,
+                    out_of_sample
+                        + daily_return(
+                            result.out_of_sample.total_return,
+                            window.in_sample_end,
+                            window.out_of_sample_end,
+                        )?,
+                ))
+            },
+        )?;
+
+        (!in_sample.is_zero()).then(|| out_of_sample / in_sample)
+    }
+}
+
+impl fmt::Display for WalkForwardReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let fmt_opt = |value: Option<Decimal>| {
+            value.map_or("-".to_string(), |value| value.round_dp(6).to_string())
+        };
+
+        writeln!(f, "windows:           {}", self.windows.len())?;
+        writeln!(
+            f,
+            "compounded return: {}",
+            self.compounded_return().round_dp(6)
+        )?;
+        writeln!(f, "max drawdown:      {}", self.max_drawdown().round_dp(6))?;
+        writeln!(
+            f,
+            "profitable ratio:  {}",
+            self.profitable_ratio().round_dp(6)
+        )?;
+        write!(f, "efficiency:        {}", fmt_opt(self.efficiency()))?;
+
+        for result in &self.windows {
+            write!(
+                f,
+                "\n{} ~ {} | {} ~ {} {} in sample {} out of sample {} max drawdown {}",
+                result.window.in_sample_start,
+                result.window.in_sample_end,
+                result.window.in_sample_end,
+                result.window.out_of_sample_end,
+                result.params,
+                result.in_sample_return.round_dp(6),
+                result.out_of_sample.total_return.round_dp(6),
+                result.out_of_sample.max_drawdown.round_dp(6),
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+// 前推分析，在每个样本内窗口上做参数扫描，用总收益率最高的参数回测紧随其后的样本外窗口
+#[derive(Builder, Debug)]
+#[builder(on(String, into))]
+pub struct WalkForward {
+    workflow: String,                // 工作流模板JSON
+    grids: Vec<ParamGrid>,           // 参数网格
+    start_datetime: DateTime<Utc>,   // 回测开始时间
+    end_datetime: DateTime<Utc>,     // 回测结束时间
+    in_sample: Duration,             // 样本内窗口长度
+    out_of_sample: Duration,         // 样本外窗口长度，也是窗口推进的步长
+    db: Arc<PgPool>,                 // 数据库
+    optimization_id: Option<String>, // 样本内扫描ID，默认为工作流模板的内容哈希
+    workers: Option<usize>,          // 同时运行的回测数量
+    #[builder(default)]
+    anchored: bool, // 样本内窗口是否固定从开始时间起算
+    #[builder(default = "USDT".to_string())]
+    quote_asset: String, // 计价资产
+}
+
+impl WalkForward {
+    pub async fn run(&self) -> Result<WalkForwardReport> {
+        let windows = split_windows(
+            self.start_datetime,
+            self.end_datetime,
+            self.in_sample,
+            self.out_of_sample,
+            self.anchored,
+        );
+
+        anyhow::ensure!(
+            !windows.is_empty(),
+            "Backtest range is too short for the in-sample and out-of-sample windows"
+        );
+
+        // 各窗口的扫描结果按时间范围区分，共用一个扫描ID
+        let optimization_id = match &self.optimization_id {
+            Some(optimization_id) => optimization_id.clone(),
+            None => serde_json::from_str::<Workflow>(&self.workflow)?.content_hash()?,
+        };
+
+        let mut results = Vec::with_capacity(windows.len());
+
+        for window in windows {
+            let runs = ParamSweep::builder()
+                .workflow(self.workflow.clone())
+                .grids(self.grids.clone())
+                .start_datetime(window.in_sample_start)
+                .end_datetime(window.in_sample_end)
+                .db(Arc::clone(&self.db))
+                .optimization_id(optimization_id.clone())
+                .maybe_workers(self.workers)
+                .quote_asset(self.quote_asset.clone())
+                .build()
+                .run()
+                .await?;
+
+            // 扫描结果按总收益率从高到低排列
+            let best = runs
+                .into_iter()
+                .find(|run| run.status == OptimizationRunStatus::Complete)
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "No complete backtest in window {} ~ {}",
+                        window.in_sample_start,
+                        window.in_sample_end
+                    )
+                })?;
+
+            let mut workflow = optimize::backtest_workflow(
+                &self.workflow,
+                optimize::parse_params(&best.params)?,
+                &window.in_sample_end,
+                &window.out_of_sample_end,
+            )?;
+
+            let out_of_sample = backtest::execute(
+                &mut workflow,
+                Arc::clone(&self.db),
+                &self.quote_asset,
+                false,
+            )
+            .await?;
+
+            tracing::info!(
+                "Walk forward window {} ~ {}: params {}, in sample {:?}, out of sample {}",
+                window.in_sample_end,
+                window.out_of_sample_end,
+                best.params,
+                best.total_return,
+                out_of_sample.total_return,
+            );
+
+            results.push(WindowResult {
+                window,
+                params: best.params,
+                in_sample_return: best.total_return.unwrap_or_default(),
+                out_of_sample,
+            });
+        }
+
+        Ok(WalkForwardReport { windows: results })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use comfy_quant_base::secs_to_datetime;
+
+    #[test]
+    fn test_split_windows() -> Result<()> {
+        let start = secs_to_datetime(1704067200)?; // 2024-01-01
+        let end = start + Duration::days(25);
+
+        let windows = split_windows(start, end, Duration::days(10), Duration::days(5), false);
+        assert_eq!(windows.len(), 3);
+        assert_eq!(windows[1].in_sample_start, start + Duration::days(5));
+        assert_eq!(windows[1].in_sample_end, start + Duration::days(15));
+        assert_eq!(windows[2].out_of_sample_end, end);
+
+        // 最后一个样本外窗口截断到结束时间
+        let windows = split_windows(start, end, Duration::days(10), Duration::days(7), false);
+        assert_eq!(windows.len(), 3);
+        assert_eq!(windows[2].in_sample_end, start + Duration::days(24));
+        assert_eq!(windows[2].out_of_sample_end, end);
+
+        let windows = split_windows(start, end, Duration::days(10), Duration::days(5), true);
+        assert!(windows.iter().all(|window| window.in_sample_start == start));
+        assert_eq!(windows[2].in_sample_end, start + Duration::days(20));
+
+        // 时间范围不足一个样本内窗口
+        assert!(split_windows(start, end, Duration::days(30), Duration::days(5), false).is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,302 @@
+use comfy_quant_node::{
+    node_core::WorkflowEvent,
+    stats::{StatsAggregator, StrategySnapshot},
+};
+use metrics::{Label, Unit};
+use rust_decimal::prelude::ToPrimitive;
+use sqlx::PgPool;
+use tokio::sync::broadcast::{self, error::RecvError};
+
+// 注册指标说明，/metrics 输出中显示为 HELP
+pub fn describe_metrics() {
+    metrics::describe_counter!("workflow_ticks_total", "Ticks processed by strategy nodes");
+    metrics::describe_counter!(
+        "workflow_orders_placed_total",
+        "Orders placed by strategy nodes"
+    );
+    metrics::describe_counter!(
+        "workflow_orders_filled_total",
+        "Order fill updates received by strategy nodes"
+    );
+    metrics::describe_counter!(
+        "workflow_errors_total",
+        "Node errors, including nodes exiting with an error"
+    );
+    metrics::describe_counter!(
+        "workflow_risk_triggered_total",
+        "Orders blocked or positions liquidated by risk rules"
+    );
+    metrics::describe_counter!(
+        "workflow_stop_triggered_total",
+        "Stop loss and take profit triggers"
+    );
+    metrics::describe_counter!(
+        "workflow_node_restarts_total",
+        "Node restarts by restart policy or watchdog"
+    );
+    metrics::describe_histogram!(
+        "exchange_request_duration_seconds",
+        Unit::Seconds,
+        "Exchange API request latency, including retries"
+    );
+    metrics::describe_gauge!("workflows_running", "Workflows running on this instance");
+    metrics::describe_gauge!("db_pool_connections", "Open database connections");
+    metrics::describe_gauge!("db_pool_idle_connections", "Idle database connections");
+    metrics::describe_gauge!("db_pool_max_connections", "Maximum database connections");
+    metrics::describe_gauge!(
+        "stats_total_equity",
+        "Total equity of running strategies by quote asset"
+    );
+    metrics::describe_gauge!(
+        "stats_daily_pnl",
+        "PnL of running strategies since the start of the day by quote asset"
+    );
+    metrics::describe_gauge!(
+        "stats_open_exposure",
+        "Open base asset exposure of running strategies by quote asset"
+    );
+}
+
+// 运行时事件对应的计数器名称和标签，不计数的事件返回 None
+fn event_counter(workflow_id: &str, event: &WorkflowEvent) -> Option<(&'static str, Vec<Label>)> {
+    let workflow = Label::new("workflow_id", workflow_id.to_string());
+    let market = |exchange: &str, symbol: &str| {
+        [
+            Label::new("exchange", exchange.to_string()),
+            Label::new("symbol", symbol.to_string()),
+        ]
+    };
+
+    let counter = match event {
+        WorkflowEvent::TickConsumed {
+            exchange, symbol, ..
+        } => {
+            let [exchange, symbol] = market(exchange, symbol);
+            ("workflow_ticks_total", vec![workflow, exchange, symbol])
+        }
+        WorkflowEvent::OrderPlaced {
+            exchange,
+            symbol,
+            side,
+            ..
+        } => {
+            let [exchange, symbol] = market(exchange, symbol);
+            let side = Label::new("side", side.to_lowercase());
+            (
+                "workflow_orders_placed_total",
+                vec![workflow, exchange, symbol, side],
+            )
+        }
+        WorkflowEvent::OrderFilled {
+            exchange,
+            symbol,
+            side,
+            ..
+        } => {
+            let [exchange, symbol] = market(exchange, symbol);
+            let side = Label::new("side", side.to_lowercase());
+            (
+                "workflow_orders_filled_total",
+                vec![workflow, exchange, symbol, side],
+            )
+        }
+        WorkflowEvent::Error { .. } | WorkflowEvent::NodeFinished { error: Some(_), .. } => {
+            ("workflow_errors_total", vec![workflow])
+        }
+        WorkflowEvent::RiskTriggered { rule, .. } => (
+            "workflow_risk_triggered_total",
+            vec![workflow, Label::new("rule", rule.clone())],
+        ),
+        WorkflowEvent::StopTriggered { kind, .. } => (
+            "workflow_stop_triggered_total",
+            vec![workflow, Label::new("kind", kind.clone())],
+        ),
+        WorkflowEvent::NodeRestarting { node_type, .. } => (
+            "workflow_node_restarts_total",
+            vec![workflow, Label::new("node_type", node_type.clone())],
+        ),
+        _ => return None,
+    };
+
+    Some(counter)
+}
+
+// 策略市值更新转换为聚合服务的快照，其他事件返回 None
+fn strategy_snapshot(workflow_id: &str, event: &WorkflowEvent) -> Option<StrategySnapshot> {
+    let WorkflowEvent::EquityUpdated {
+        node_id,
+        exchange,
+        symbol,
+        quote_asset,
+        equity,
+        realized_pnl,
+        unrealized_pnl,
+        exposure,
+        timestamp,
+    } = event
+    else {
+        return None;
+    };
+
+    Some(StrategySnapshot {
+        workflow_id: workflow_id.to_string(),
+        node_id: *node_id as i16,
+        exchange: exchange.as_str().into(),
+        symbol: symbol.as_str().into(),
+        quote_asset: quote_asset.clone(),
+        equity: *equity,
+        realized_pnl: *realized_pnl,
+        unrealized_pnl: *unrealized_pnl,
+        exposure: *exposure,
+        timestamp: *timestamp,
+    })
+}
+
+// 统计运行中工作流的事件并更新统计聚合，工作流停止后事件总线关闭时退出并移除其快照
+pub async fn record_workflow_events(
+    workflow_id: String,
+    mut rx: broadcast::Receiver<WorkflowEvent>,
+) {
+    loop {
+        let event = match rx.recv().await {
+            Ok(event) => event,
+            Err(RecvError::Lagged(skipped)) => {
+                tracing::warn!(
+                    workflow_id = %workflow_id,
+                    "Workflow metrics lagged, {} events skipped",
+                    skipped
+                );
+                continue;
+            }
+            Err(RecvError::Closed) => break,
+        };
+
+        if let Some((name, labels)) = event_counter(&workflow_id, &event) {
+            metrics::counter!(name, labels).increment(1);
+        }
+
+        if let Some(snapshot) = strategy_snapshot(&workflow_id, &event) {
+            StatsAggregator::global().update(snapshot);
+        }
+    }
+
+    StatsAggregator::global().remove_workflow(&workflow_id);
+}
+
+// 所有运行中策略的聚合统计，抓取指标时采样
+pub fn record_stats_aggregate() {
+    let aggregate = StatsAggregator::global().current();
+
+    for (quote_asset, stats) in &aggregate.quote_assets {
+        let labels = vec![Label::new("quote_asset", quote_asset.clone())];
+
+        metrics::gauge!("stats_total_equity", labels.clone())
+            .set(stats.total_equity.to_f64().unwrap_or_default());
+        metrics::gauge!("stats_daily_pnl", labels.clone())
+            .set(stats.daily_pnl.to_f64().unwrap_or_default());
+        metrics::gauge!("stats_open_exposure", labels)
+            .set(stats.open_exposure.to_f64().unwrap_or_default());
+    }
+}
+
+// 数据库连接池状态，抓取指标时采样
+pub fn record_db_pool(db: &PgPool) {
+    metrics::gauge!("db_pool_connections").set(db.size() as f64);
+    metrics::gauge!("db_pool_idle_connections").set(db.num_idle() as f64);
+    metrics::gauge!("db_pool_max_connections").set(db.options().get_max_connections() as f64);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use comfy_quant_base::Exchange;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_event_counter() {
+        let event = WorkflowEvent::OrderPlaced {
+            node_id: 2,
+            exchange: "binance".to_string(),
+            symbol: "BTCUSDT".to_string(),
+            order_id: "1".to_string(),
+            side: "Buy".to_string(),
+            status: "Filled".to_string(),
+            price: "50000".to_string(),
+            qty: "0.01".to_string(),
+            time: 1704067200000,
+        };
+
+        let (name, labels) = event_counter("wf1", &event).unwrap_or_default();
+        assert_eq!(name, "workflow_orders_placed_total");
+        assert_eq!(
+            labels,
+            vec![
+                Label::new("workflow_id", "wf1"),
+                Label::new("exchange", "binance"),
+                Label::new("symbol", "BTCUSDT"),
+                Label::new("side", "buy"),
+            ]
+        );
+
+        let event = WorkflowEvent::NodeFinished {
+            node_id: 2,
+            node_type: "strategy.SpotGrid".to_string(),
+            error: Some("boom".to_string()),
+        };
+        assert_eq!(
+            event_counter("wf1", &event).map(|(name, _)| name),
+            Some("workflow_errors_total")
+        );
+
+        let event = WorkflowEvent::BacktestProgress {
+            node_id: 1,
+            processed: 1,
+            total: 2,
+            percent: 50,
+            eta_secs: None,
+        };
+        assert!(event_counter("wf1", &event).is_none());
+
+        let event = WorkflowEvent::StopTriggered {
+            node_id: 2,
+            exchange: "binance".to_string(),
+            symbol: "BTCUSDT".to_string(),
+            kind: "stop_loss".to_string(),
+            price: dec!(42000),
+        };
+        assert_eq!(
+            event_counter("wf1", &event).map(|(_, labels)| labels),
+            Some(vec![
+                Label::new("workflow_id", "wf1"),
+                Label::new("kind", "stop_loss"),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_strategy_snapshot() {
+        let event = WorkflowEvent::EquityUpdated {
+            node_id: 2,
+            exchange: "binance".to_string(),
+            symbol: "BTCUSDT".to_string(),
+            quote_asset: "USDT".to_string(),
+            equity: dec!(1100),
+            realized_pnl: dec!(60),
+            unrealized_pnl: dec!(40),
+            exposure: dec!(500),
+            timestamp: 1704067200,
+        };
+
+        let snapshot = strategy_snapshot("wf1", &event);
+        assert_eq!(
+            snapshot.map(|snapshot| (snapshot.node_id, snapshot.exchange, snapshot.equity)),
+            Some((2, Exchange::Binance, dec!(1100)))
+        );
+
+        let event = WorkflowEvent::Error {
+            node_id: 2,
+            message: "boom".to_string(),
+        };
+        assert!(strategy_snapshot("wf1", &event).is_none());
+    }
+}
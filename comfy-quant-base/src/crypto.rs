@@ -0,0 +1,151 @@
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use ring::{
+    aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN},
+    rand::{SecureRandom, SystemRandom},
+};
+use std::fmt;
+
+// 主密钥的环境变量，值为base64编码的32字节密钥
+pub const MASTER_KEY_ENV: &str = "CREDENTIALS_MASTER_KEY";
+
+// 交易所API密钥
+#[derive(Clone, PartialEq, Eq)]
+pub struct Credentials {
+    pub api_key: String,
+    pub secret_key: String,
+}
+
+impl Credentials {
+    pub fn new(api_key: impl Into<String>, secret_key: impl Into<String>) -> Self {
+        Credentials {
+            api_key: api_key.into(),
+            secret_key: secret_key.into(),
+        }
+    }
+}
+
+// 日志和错误信息中不输出密钥
+impl fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Credentials")
+            .field("api_key", &"***")
+            .field("secret_key", &"***")
+            .finish()
+    }
+}
+
+// 使用 AES-256-GCM 加密保存的密钥，密文为 base64(随机nonce + 密文 + 认证标签)
+pub struct SecretCipher {
+    key: LessSafeKey,
+    rng: SystemRandom,
+}
+
+impl SecretCipher {
+    pub fn new(key: &[u8]) -> Result<Self> {
+        let key = UnboundKey::new(&AES_256_GCM, key)
+            .map_err(|_| anyhow!("Master key must be {} bytes", AES_256_GCM.key_len()))?;
+
+        Ok(SecretCipher {
+            key: LessSafeKey::new(key),
+            rng: SystemRandom::new(),
+        })
+    }
+
+    pub fn from_base64(key: &str) -> Result<Self> {
+        SecretCipher::new(&STANDARD.decode(key.trim())?)
+    }
+
+    // 从环境变量读取主密钥
+    pub fn from_env() -> Result<Self> {
+        let key = std::env::var(MASTER_KEY_ENV)
+            .map_err(|_| anyhow!("Missing environment variable {}", MASTER_KEY_ENV))?;
+
+        SecretCipher::from_base64(&key)
+    }
+
+    // 生成base64编码的随机主密钥
+    pub fn generate_key() -> Result<String> {
+        let mut key = vec![0; AES_256_GCM.key_len()];
+
+        SystemRandom::new()
+            .fill(&mut key)
+            .map_err(|_| anyhow!("Failed to generate master key"))?;
+
+        Ok(STANDARD.encode(key))
+    }
+
+    pub fn encrypt(&self, plaintext: &str) -> Result<String> {
+        let mut nonce = [0; NONCE_LEN];
+
+        self.rng
+            .fill(&mut nonce)
+            .map_err(|_| anyhow!("Failed to generate nonce"))?;
+
+        let mut sealed = plaintext.as_bytes().to_vec();
+
+        self.key
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::empty(),
+                &mut sealed,
+            )
+            .map_err(|_| anyhow!("Failed to encrypt secret"))?;
+
+        Ok(STANDARD.encode([&nonce[..], &sealed].concat()))
+    }
+
+    pub fn decrypt(&self, ciphertext: &str) -> Result<String> {
+        let data = STANDARD.decode(ciphertext)?;
+
+        anyhow::ensure!(data.len() > NONCE_LEN, "Invalid ciphertext");
+
+        let (nonce, sealed) = data.split_at(NONCE_LEN);
+        let nonce =
+            Nonce::try_assume_unique_for_key(nonce).map_err(|_| anyhow!("Invalid ciphertext"))?;
+        let mut sealed = sealed.to_vec();
+
+        // 主密钥错误或密文被篡改时认证失败
+        let plaintext = self
+            .key
+            .open_in_place(nonce, Aad::empty(), &mut sealed)
+            .map_err(|_| anyhow!("Failed to decrypt secret"))?;
+
+        Ok(String::from_utf8(plaintext.to_vec())?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secret_cipher() -> Result<()> {
+        let key = SecretCipher::generate_key()?;
+        let cipher = SecretCipher::from_base64(&key)?;
+
+        let ciphertext = cipher.encrypt("secret")?;
+
+        assert!(!ciphertext.contains("secret"));
+        assert_eq!(cipher.decrypt(&ciphertext)?, "secret");
+        // 每次加密使用不同的nonce
+        assert_ne!(cipher.encrypt("secret")?, ciphertext);
+
+        // 其他主密钥无法解密
+        let other = SecretCipher::from_base64(&SecretCipher::generate_key()?)?;
+        assert!(other.decrypt(&ciphertext).is_err());
+
+        assert!(SecretCipher::new(b"short").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_credentials_debug() {
+        let credentials = Credentials::new("my-api-key", "my-secret-key");
+        let debug = format!("{:?}", credentials);
+
+        assert!(!debug.contains("my-api-key"));
+        assert!(!debug.contains("my-secret-key"));
+    }
+}
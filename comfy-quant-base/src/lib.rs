@@ -1,7 +1,9 @@
+mod crypto;
 mod serde;
 mod types;
 mod utils;
 
+pub use crypto::*;
 pub use serde::*;
 pub use types::*;
 pub use utils::*;
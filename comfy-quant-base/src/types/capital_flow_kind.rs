@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// 策略资金变动类型
+#[derive(Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum CapitalFlowKind {
+    #[default]
+    Allocation, // 初始投入
+    Deposit,    // 追加资金
+    Withdrawal, // 提取资金
+    Fee,        // 手续费
+    Pnl,        // 已实现盈亏
+}
+
+impl CapitalFlowKind {
+    // 是否为外部资金流入流出，收益率计算时需要剔除
+    pub fn is_external(&self) -> bool {
+        matches!(
+            self,
+            CapitalFlowKind::Allocation | CapitalFlowKind::Deposit | CapitalFlowKind::Withdrawal
+        )
+    }
+}
+
+impl From<&str> for CapitalFlowKind {
+    fn from(value: &str) -> Self {
+        match value {
+            "allocation" => CapitalFlowKind::Allocation,
+            "deposit" => CapitalFlowKind::Deposit,
+            "withdrawal" => CapitalFlowKind::Withdrawal,
+            "fee" => CapitalFlowKind::Fee,
+            "pnl" => CapitalFlowKind::Pnl,
+            _ => CapitalFlowKind::Allocation,
+        }
+    }
+}
+
+impl From<String> for CapitalFlowKind {
+    fn from(value: String) -> Self {
+        value.as_str().into()
+    }
+}
+
+impl AsRef<str> for CapitalFlowKind {
+    fn as_ref(&self) -> &str {
+        match self {
+            CapitalFlowKind::Allocation => "allocation",
+            CapitalFlowKind::Deposit => "deposit",
+            CapitalFlowKind::Withdrawal => "withdrawal",
+            CapitalFlowKind::Fee => "fee",
+            CapitalFlowKind::Pnl => "pnl",
+        }
+    }
+}
+
+impl fmt::Display for CapitalFlowKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_ref())
+    }
+}
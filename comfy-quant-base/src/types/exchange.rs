@@ -7,13 +7,16 @@ use super::Symbol;
 pub enum Exchange {
     #[default]
     Binance,
+    Okx,
+    Bybit,
 }
 
 impl Exchange {
     // 交易对
     pub fn symbol(&self, base_asset: &str, quote_asset: &str) -> Symbol {
         match self {
-            Exchange::Binance => format!("{}{}", base_asset, quote_asset),
+            Exchange::Binance | Exchange::Bybit => format!("{}{}", base_asset, quote_asset),
+            Exchange::Okx => format!("{}-{}", base_asset, quote_asset),
         }
         .to_uppercase()
         .into()
@@ -23,6 +26,8 @@ impl Exchange {
     pub fn allow_quote_assets(&self) -> Vec<String> {
         match self {
             Exchange::Binance => vec!["usdt", "fdusd", "usdc", "tusd", "bnb", "btc", "eth", "dai"],
+            Exchange::Okx => vec!["usdt", "usdc", "btc", "eth", "okb", "dai"],
+            Exchange::Bybit => vec!["usdt", "usdc", "btc", "eth", "dai"],
         }
         .into_iter()
         .map(|s| s.to_uppercase())
@@ -34,6 +39,8 @@ impl From<&str> for Exchange {
     fn from(value: &str) -> Self {
         match value {
             "binance" => Exchange::Binance,
+            "okx" => Exchange::Okx,
+            "bybit" => Exchange::Bybit,
             _ => Exchange::Binance,
         }
     }
@@ -55,6 +62,8 @@ impl AsRef<str> for Exchange {
     fn as_ref(&self) -> &str {
         match self {
             Exchange::Binance => "binance",
+            Exchange::Okx => "okx",
+            Exchange::Bybit => "bybit",
         }
     }
 }
@@ -4,6 +4,12 @@ use strum_macros::{AsRefStr, EnumIter};
 
 #[derive(Debug, Clone, PartialEq, Eq, AsRefStr, EnumIter)]
 pub enum KlineInterval {
+    #[strum(serialize = "100ms")]
+    HundredMilliseconds,
+    #[strum(serialize = "250ms")]
+    TwoHundredFiftyMilliseconds,
+    #[strum(serialize = "500ms")]
+    FiveHundredMilliseconds,
     #[strum(serialize = "1s")]
     OneSecond,
     #[strum(serialize = "1m")]
@@ -36,11 +42,21 @@ pub enum KlineInterval {
     OneWeek,
     #[strum(serialize = "1M")]
     OneMonth,
+    // 按成交笔数聚合的伪周期，K线没有固定时长
+    #[strum(serialize = "100t")]
+    HundredTicks,
+    #[strum(serialize = "500t")]
+    FiveHundredTicks,
+    #[strum(serialize = "1000t")]
+    ThousandTicks,
 }
 
 impl From<&str> for KlineInterval {
     fn from(value: &str) -> Self {
         match value {
+            "100ms" => KlineInterval::HundredMilliseconds,
+            "250ms" => KlineInterval::TwoHundredFiftyMilliseconds,
+            "500ms" => KlineInterval::FiveHundredMilliseconds,
             "1s" => KlineInterval::OneSecond,
             "1m" => KlineInterval::OneMinute,
             "3m" => KlineInterval::ThreeMinutes,
@@ -57,6 +73,9 @@ impl From<&str> for KlineInterval {
             "3d" => KlineInterval::ThreeDays,
             "1w" => KlineInterval::OneWeek,
             "1M" => KlineInterval::OneMonth,
+            "100t" => KlineInterval::HundredTicks,
+            "500t" => KlineInterval::FiveHundredTicks,
+            "1000t" => KlineInterval::ThousandTicks,
             _ => KlineInterval::OneSecond,
         }
     }
@@ -80,6 +99,59 @@ impl From<KlineInterval> for String {
     }
 }
 
+impl KlineInterval {
+    // 时间间隔毫秒数，月按30天计算，按成交笔数聚合的为0
+    pub fn to_millis(&self) -> i64 {
+        match self {
+            KlineInterval::HundredMilliseconds => 100,
+            KlineInterval::TwoHundredFiftyMilliseconds => 250,
+            KlineInterval::FiveHundredMilliseconds => 500,
+            KlineInterval::HundredTicks
+            | KlineInterval::FiveHundredTicks
+            | KlineInterval::ThousandTicks => 0,
+            _ => self.to_seconds() * 1000,
+        }
+    }
+
+    // 时间间隔秒数，月按30天计算，不足1秒和按成交笔数聚合的为0
+    pub fn to_seconds(&self) -> i64 {
+        match self {
+            KlineInterval::HundredMilliseconds
+            | KlineInterval::TwoHundredFiftyMilliseconds
+            | KlineInterval::FiveHundredMilliseconds => 0,
+            KlineInterval::OneSecond => 1,
+            KlineInterval::OneMinute => 60,
+            KlineInterval::ThreeMinutes => 180,
+            KlineInterval::FiveMinutes => 300,
+            KlineInterval::FifteenMinutes => 900,
+            KlineInterval::ThirtyMinutes => 1800,
+            KlineInterval::OneHour => 3600,
+            KlineInterval::TwoHours => 7200,
+            KlineInterval::FourHours => 14400,
+            KlineInterval::SixHours => 21600,
+            KlineInterval::EightHours => 28800,
+            KlineInterval::TwelveHours => 43200,
+            KlineInterval::OneDay => 86400,
+            KlineInterval::ThreeDays => 259200,
+            KlineInterval::OneWeek => 604800,
+            KlineInterval::OneMonth => 2592000,
+            KlineInterval::HundredTicks
+            | KlineInterval::FiveHundredTicks
+            | KlineInterval::ThousandTicks => 0,
+        }
+    }
+
+    // 按成交笔数聚合时每根K线的成交笔数
+    pub fn tick_count(&self) -> Option<u32> {
+        match self {
+            KlineInterval::HundredTicks => Some(100),
+            KlineInterval::FiveHundredTicks => Some(500),
+            KlineInterval::ThousandTicks => Some(1000),
+            _ => None,
+        }
+    }
+}
+
 impl fmt::Display for KlineInterval {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.as_ref())
@@ -89,6 +161,7 @@ impl fmt::Display for KlineInterval {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use strum::IntoEnumIterator;
 
     #[test]
     fn test_kline_interval() {
@@ -100,5 +173,31 @@ mod tests {
 
         let interval3: KlineInterval = "1s".into();
         assert_eq!(interval3, KlineInterval::OneSecond);
+
+        for interval in KlineInterval::iter() {
+            assert_eq!(KlineInterval::from(interval.as_ref()), interval);
+        }
+    }
+
+    #[test]
+    fn test_kline_interval_to_seconds() {
+        assert_eq!(KlineInterval::OneSecond.to_seconds(), 1);
+        assert_eq!(KlineInterval::OneMinute.to_seconds(), 60);
+        assert_eq!(KlineInterval::OneHour.to_seconds(), 3600);
+        assert_eq!(KlineInterval::OneDay.to_seconds(), 86400);
+        assert_eq!(KlineInterval::HundredMilliseconds.to_seconds(), 0);
+        assert_eq!(KlineInterval::ThousandTicks.to_seconds(), 0);
+    }
+
+    #[test]
+    fn test_kline_interval_to_millis() {
+        assert_eq!(KlineInterval::HundredMilliseconds.to_millis(), 100);
+        assert_eq!(KlineInterval::TwoHundredFiftyMilliseconds.to_millis(), 250);
+        assert_eq!(KlineInterval::OneSecond.to_millis(), 1000);
+        assert_eq!(KlineInterval::OneMinute.to_millis(), 60000);
+        assert_eq!(KlineInterval::HundredTicks.to_millis(), 0);
+
+        assert_eq!(KlineInterval::FiveHundredTicks.tick_count(), Some(500));
+        assert_eq!(KlineInterval::FiveHundredMilliseconds.tick_count(), None);
     }
 }
@@ -1,13 +1,17 @@
+mod capital_flow_kind;
 mod exchange;
 mod exchange_market_symbol_key;
 mod exchange_symbol_key;
 mod kline_interval;
 mod market;
 mod symbol;
+mod symbol_alias;
 
+pub use capital_flow_kind::CapitalFlowKind;
 pub use exchange::Exchange;
 pub use exchange_market_symbol_key::ExchangeMarketSymbolKey;
 pub use exchange_symbol_key::ExchangeSymbolKey;
 pub use kline_interval::KlineInterval;
 pub use market::{FuturesMarket, Market};
 pub use symbol::Symbol;
+pub use symbol_alias::{SymbolAlias, SymbolAliases};
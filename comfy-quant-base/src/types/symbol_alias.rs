@@ -0,0 +1,171 @@
+use super::{Exchange, Market, Symbol};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+// 交易对更名记录，old_symbol 在 renamed_at 时刻更名为 new_symbol
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SymbolAlias {
+    pub exchange: Exchange,        // 交易所
+    pub market: Market,            // 市场
+    pub old_symbol: Symbol,        // 旧交易对
+    pub new_symbol: Symbol,        // 新交易对
+    pub renamed_at: DateTime<Utc>, // 更名时间
+}
+
+impl SymbolAlias {
+    pub fn new(
+        exchange: impl Into<Exchange>,
+        market: impl Into<Market>,
+        old_symbol: impl Into<Symbol>,
+        new_symbol: impl Into<Symbol>,
+        renamed_at: DateTime<Utc>,
+    ) -> Self {
+        SymbolAlias {
+            exchange: exchange.into(),
+            market: market.into(),
+            old_symbol: old_symbol.into(),
+            new_symbol: new_symbol.into(),
+            renamed_at,
+        }
+    }
+}
+
+// 交易对更名记录集合，支持多次更名形成的更名链
+#[derive(Debug, Default, Clone)]
+pub struct SymbolAliases(Vec<SymbolAlias>);
+
+impl SymbolAliases {
+    pub fn new(aliases: Vec<SymbolAlias>) -> Self {
+        SymbolAliases(aliases)
+    }
+
+    // 沿更名链找到交易对当前的名称
+    pub fn current(&self, exchange: &Exchange, market: &Market, symbol: &Symbol) -> Symbol {
+        let mut current = symbol.clone();
+        let mut visited = HashSet::from([current.clone()]);
+
+        while let Some(alias) = self
+            .find(exchange, market)
+            .find(|alias| alias.old_symbol == current)
+        {
+            // 防止错误数据形成环
+            if !visited.insert(alias.new_symbol.clone()) {
+                break;
+            }
+
+            current = alias.new_symbol.clone();
+        }
+
+        current
+    }
+
+    // 交易对使用过的所有名称及停用时间，第一个为自身(未停用)
+    pub fn history(
+        &self,
+        exchange: &Exchange,
+        market: &Market,
+        symbol: &Symbol,
+    ) -> Vec<(Symbol, Option<DateTime<Utc>>)> {
+        let mut history = vec![(symbol.clone(), None)];
+        let mut visited = HashSet::from([symbol.clone()]);
+        let mut i = 0;
+
+        while i < history.len() {
+            let (name, _) = history[i].clone();
+
+            for alias in self
+                .find(exchange, market)
+                .filter(|alias| alias.new_symbol == name)
+            {
+                if visited.insert(alias.old_symbol.clone()) {
+                    history.push((alias.old_symbol.clone(), Some(alias.renamed_at)));
+                }
+            }
+
+            i += 1;
+        }
+
+        history
+    }
+
+    fn find<'a>(
+        &'a self,
+        exchange: &'a Exchange,
+        market: &'a Market,
+    ) -> impl Iterator<Item = &'a SymbolAlias> {
+        self.0
+            .iter()
+            .filter(move |alias| &alias.exchange == exchange && &alias.market == market)
+    }
+}
+
+impl From<Vec<SymbolAlias>> for SymbolAliases {
+    fn from(value: Vec<SymbolAlias>) -> Self {
+        SymbolAliases::new(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::secs_to_datetime;
+    use anyhow::Result;
+
+    fn aliases() -> Result<SymbolAliases> {
+        Ok(SymbolAliases::new(vec![
+            SymbolAlias::new(
+                Exchange::Binance,
+                Market::Spot,
+                "MATICUSDT",
+                "POLUSDT",
+                secs_to_datetime(1725926400)?,
+            ),
+            SymbolAlias::new(
+                Exchange::Binance,
+                Market::Spot,
+                "POLUSDT",
+                "POL2USDT",
+                secs_to_datetime(1735689600)?,
+            ),
+        ]))
+    }
+
+    #[test]
+    fn test_symbol_aliases_current() -> Result<()> {
+        let aliases = aliases()?;
+
+        assert_eq!(
+            aliases.current(&Exchange::Binance, &Market::Spot, &"MATICUSDT".into()),
+            "POL2USDT".into()
+        );
+        assert_eq!(
+            aliases.current(&Exchange::Binance, &Market::Spot, &"BTCUSDT".into()),
+            "BTCUSDT".into()
+        );
+        assert_eq!(
+            aliases.current(&Exchange::Binance, &Market::Usdm, &"MATICUSDT".into()),
+            "MATICUSDT".into()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_symbol_aliases_history() -> Result<()> {
+        let aliases = aliases()?;
+
+        let history = aliases.history(&Exchange::Binance, &Market::Spot, &"POL2USDT".into());
+
+        assert_eq!(
+            history,
+            vec![
+                ("POL2USDT".into(), None),
+                ("POLUSDT".into(), Some(secs_to_datetime(1735689600)?)),
+                ("MATICUSDT".into(), Some(secs_to_datetime(1725926400)?)),
+            ]
+        );
+
+        Ok(())
+    }
+}
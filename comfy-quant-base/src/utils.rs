@@ -27,6 +27,11 @@ pub fn generate_workflow_id() -> String {
     nanoid::nanoid!(21, &ALPHABET)
 }
 
+// 生成32位客户端订单ID，只包含字母和数字，满足各交易所的格式要求
+pub fn generate_client_order_id() -> String {
+    nanoid::nanoid!(32, &ALPHABET)
+}
+
 // 将秒转换为UTC时间
 pub fn secs_to_datetime(secs: impl Into<i64>) -> Result<DateTime<Utc>> {
     let datetime = DateTime::<Utc>::from_timestamp(secs.into(), 0)
@@ -44,11 +49,15 @@ pub fn millis_to_datetime(millis: impl Into<i64>) -> Result<DateTime<Utc>> {
     Ok(datetime)
 }
 
-// 计算时间间隔的开始时间
+// 计算时间间隔的开始时间(秒)，不支持不足1秒和按成交笔数聚合的时间间隔
 pub fn calc_interval_start(time: i64, unit: IntervalUnit, interval: u32) -> Result<i64> {
     let time = secs_to_datetime(time).ok();
 
     let start_time = match unit {
+        IntervalUnit::Millisecond => Err(anyhow!(
+            "Millisecond interval requires calc_interval_start_millis"
+        )),
+        IntervalUnit::Tick => Err(anyhow!("Tick interval is not aligned to time")),
         IntervalUnit::Second => calc_interval_start_with_second(time, interval),
         IntervalUnit::Minute => calc_interval_start_with_minute(time, interval),
         IntervalUnit::Hour => calc_interval_start_with_hour(time, interval),
@@ -60,6 +69,20 @@ pub fn calc_interval_start(time: i64, unit: IntervalUnit, interval: u32) -> Resu
     Ok(start_time)
 }
 
+// 计算时间间隔的开始时间(毫秒)
+pub fn calc_interval_start_millis(time: i64, unit: IntervalUnit, interval: u32) -> Result<i64> {
+    match unit {
+        IntervalUnit::Millisecond => {
+            if interval == 0 || 1000 % interval != 0 {
+                anyhow::bail!("Invalid millisecond interval: {}", interval);
+            }
+
+            Ok(time - time.rem_euclid(interval as i64))
+        }
+        _ => Ok(calc_interval_start(time.div_euclid(1000), unit, interval)? * 1000),
+    }
+}
+
 fn calc_interval_start_with_second(time: Option<DateTime<Utc>>, interval: u32) -> Result<i64> {
     let timestamp = time
         .and_then(|t| t.with_second(t.second() / interval * interval))
@@ -139,12 +162,14 @@ fn calc_interval_start_with_month(time: Option<DateTime<Utc>>, interval: u32) ->
 }
 
 pub enum IntervalUnit {
+    Millisecond,
     Second,
     Minute,
     Hour,
     Day,
     Week,
     Month,
+    Tick, // 按成交笔数聚合
 }
 
 impl FromStr for IntervalUnit {
@@ -152,12 +177,14 @@ impl FromStr for IntervalUnit {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
+            "100ms" | "250ms" | "500ms" => Ok(IntervalUnit::Millisecond),
             "1s" => Ok(IntervalUnit::Second),
             "1m" | "3m" | "5m" | "15m" | "30m" => Ok(IntervalUnit::Minute),
             "1h" | "2h" | "4h" | "6h" | "8h" | "12h" => Ok(IntervalUnit::Hour),
             "1d" | "3d" => Ok(IntervalUnit::Day),
             "1w" => Ok(IntervalUnit::Week),
             "1M" => Ok(IntervalUnit::Month),
+            "100t" | "500t" | "1000t" => Ok(IntervalUnit::Tick),
             _ => Err(anyhow!("Invalid interval unit: {}", s)),
         }
     }
@@ -210,6 +237,36 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_get_millis_interval_start() -> Result<()> {
+        let time = TEST_TIME * 1000 + 678;
+
+        let start = calc_interval_start_millis(time, IntervalUnit::Millisecond, 250)?;
+        assert_eq!(start, TEST_TIME * 1000 + 500);
+
+        let start = calc_interval_start_millis(time, IntervalUnit::Minute, 1)?;
+        assert_eq!(start, 1714107660000);
+
+        assert!(calc_interval_start_millis(time, IntervalUnit::Millisecond, 300).is_err());
+        assert!(calc_interval_start_millis(time, IntervalUnit::Tick, 100).is_err());
+        assert!(calc_interval_start(TEST_TIME, IntervalUnit::Millisecond, 100).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_interval_unit_from_str() {
+        assert!(matches!(
+            "250ms".parse::<IntervalUnit>(),
+            Ok(IntervalUnit::Millisecond)
+        ));
+        assert!(matches!(
+            "1000t".parse::<IntervalUnit>(),
+            Ok(IntervalUnit::Tick)
+        ));
+        assert!("2t".parse::<IntervalUnit>().is_err());
+    }
+
     #[test]
     fn test_convert_to_datetime() -> Result<()> {
         let datetime = convert_to_datetime("2024-10-10 15:18:42");
@@ -1,12 +1,23 @@
+use comfy_quant_observability::ObservabilityOptions;
 use config::{Config, ConfigError, Environment, File};
 use serde::Deserialize;
-use std::{env, path::Path};
+use std::{
+    collections::HashMap,
+    env,
+    path::{Path, PathBuf},
+};
 
 #[derive(Debug, Deserialize)]
 #[allow(unused)]
 pub struct Setting {
     pub(crate) debug: bool,
     pub(crate) database: Database,
+    #[serde(default)]
+    pub observability: ObservabilityOptions, // 日志与链路追踪
+    #[serde(default)]
+    pub retention: Retention, // 数据保留策略
+    #[serde(default)]
+    pub server: Server, // HTTP服务
 }
 
 impl Setting {
@@ -46,3 +57,45 @@ impl Setting {
 pub struct Database {
     pub(crate) url: String,
 }
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct Retention {
+    #[serde(default = "default_archive_dir")]
+    pub archive_dir: PathBuf, // 归档文件目录
+    #[serde(default)]
+    pub tables: HashMap<String, u32>, // 表名 -> 保留天数，未配置的表不清理
+}
+
+impl Default for Retention {
+    fn default() -> Self {
+        Retention {
+            archive_dir: default_archive_dir(),
+            tables: HashMap::new(),
+        }
+    }
+}
+
+fn default_archive_dir() -> PathBuf {
+    PathBuf::from("archive")
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct Server {
+    #[serde(default = "default_server_addr")]
+    pub addr: String, // 监听地址
+    #[serde(default)]
+    pub admin_token: Option<String>, // 管理接口的访问令牌，未设置时不开放 /admin 接口
+}
+
+impl Default for Server {
+    fn default() -> Self {
+        Server {
+            addr: default_server_addr(),
+            admin_token: None,
+        }
+    }
+}
+
+fn default_server_addr() -> String {
+    "127.0.0.1:3000".to_string()
+}
@@ -0,0 +1,144 @@
+use anyhow::Result;
+use bon::Builder;
+use chrono::{DateTime, Utc};
+use comfy_quant_base::{Exchange, Symbol};
+use sqlx::{FromRow, PgPool};
+
+// 对所有账户生效的规则使用的账户标识
+pub const ALL_ACCOUNTS: &str = "*";
+
+#[derive(Debug, FromRow)]
+pub struct AccountSymbolRule {
+    pub id: i32,                   // 主键ID
+    pub account: String,           // 账户标识，*表示所有账户
+    pub exchange: Exchange,        // 交易所
+    pub symbol: Symbol,            // 交易对
+    pub rule: String,              // 规则: allow 白名单, deny 黑名单
+    pub reason: Option<String>,    // 原因
+    pub created_at: DateTime<Utc>, // 创建时间
+    pub updated_at: DateTime<Utc>, // 更新时间
+}
+
+impl AccountSymbolRule {
+    pub fn is_deny(&self) -> bool {
+        self.rule == "deny"
+    }
+}
+
+#[derive(Debug, Builder)]
+#[builder(on(_, into))]
+pub struct CreateAccountSymbolRuleParams {
+    pub account: String,        // 账户标识，*表示所有账户
+    pub exchange: Exchange,     // 交易所
+    pub symbol: Symbol,         // 交易对
+    pub rule: String,           // 规则: allow 白名单, deny 黑名单
+    pub reason: Option<String>, // 原因
+}
+
+pub async fn create_or_update(
+    db: &PgPool,
+    data: CreateAccountSymbolRuleParams,
+) -> Result<AccountSymbolRule> {
+    let row = sqlx::query_as!(
+        AccountSymbolRule,
+        r#"
+        INSERT INTO account_symbol_rules (account, exchange, symbol, rule, reason, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5, NOW(), NOW())
+        ON CONFLICT (account, exchange, symbol)
+        DO UPDATE SET
+            rule = EXCLUDED.rule,
+            reason = EXCLUDED.reason,
+            updated_at = NOW()
+        RETURNING *
+        "#,
+        data.account,
+        data.exchange.as_ref(),
+        data.symbol.as_ref(),
+        data.rule,
+        data.reason,
+    )
+    .fetch_one(db)
+    .await?;
+
+    Ok(row)
+}
+
+pub async fn delete(
+    db: &PgPool,
+    account: &str,
+    exchange: &Exchange,
+    symbol: &Symbol,
+) -> Result<()> {
+    sqlx::query!(
+        r#"DELETE FROM account_symbol_rules WHERE account = $1 AND exchange = $2 AND symbol = $3"#,
+        account,
+        exchange.as_ref(),
+        symbol.as_ref(),
+    )
+    .execute(db)
+    .await?;
+
+    Ok(())
+}
+
+// 账户在交易所生效的规则，包括对所有账户生效的规则
+pub async fn list(
+    db: &PgPool,
+    account: &str,
+    exchange: &Exchange,
+) -> Result<Vec<AccountSymbolRule>> {
+    let rows = sqlx::query_as!(
+        AccountSymbolRule,
+        r#"
+        SELECT * FROM account_symbol_rules
+        WHERE account IN ($1, $2) AND exchange = $3
+        ORDER BY id ASC
+        "#,
+        account,
+        ALL_ACCOUNTS,
+        exchange.as_ref(),
+    )
+    .fetch_all(db)
+    .await?;
+
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[sqlx::test(migrator = "crate::MIGRATOR")]
+    async fn test_account_symbol_rules(db: PgPool) -> Result<()> {
+        let data = CreateAccountSymbolRuleParams::builder()
+            .account(ALL_ACCOUNTS)
+            .exchange(Exchange::Binance)
+            .symbol("LUNAUSDT")
+            .rule("deny")
+            .reason("low liquidity")
+            .build();
+        create_or_update(&db, data).await?;
+
+        let data = CreateAccountSymbolRuleParams::builder()
+            .account("account-a")
+            .exchange(Exchange::Binance)
+            .symbol("BTCUSDT")
+            .rule("allow")
+            .build();
+        create_or_update(&db, data).await?;
+
+        let rules = list(&db, "account-a", &Exchange::Binance).await?;
+        assert_eq!(rules.len(), 2);
+        assert!(rules[0].is_deny());
+        assert!(!rules[1].is_deny());
+
+        let rules = list(&db, "account-b", &Exchange::Binance).await?;
+        assert_eq!(rules.len(), 1);
+
+        delete(&db, ALL_ACCOUNTS, &Exchange::Binance, &"LUNAUSDT".into()).await?;
+        let rules = list(&db, "account-b", &Exchange::Binance).await?;
+        assert!(rules.is_empty());
+
+        Ok(())
+    }
+}
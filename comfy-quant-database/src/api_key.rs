@@ -0,0 +1,133 @@
+use anyhow::Result;
+use bon::Builder;
+use chrono::{DateTime, Utc};
+use comfy_quant_base::{Credentials, Exchange, SecretCipher};
+use sqlx::{FromRow, PgPool};
+
+#[derive(Debug, FromRow)]
+pub struct ApiKey {
+    pub id: i32,                   // 主键ID
+    pub name: String,              // 密钥名称，工作流中按名称引用
+    pub exchange: Exchange,        // 交易所
+    pub api_key: String,           // API Key 密文
+    pub secret_key: String,        // Secret Key 密文
+    pub created_at: DateTime<Utc>, // 创建时间
+    pub updated_at: DateTime<Utc>, // 更新时间
+}
+
+impl ApiKey {
+    pub fn decrypt(&self, cipher: &SecretCipher) -> Result<Credentials> {
+        Ok(Credentials::new(
+            cipher.decrypt(&self.api_key)?,
+            cipher.decrypt(&self.secret_key)?,
+        ))
+    }
+}
+
+#[derive(Debug, Builder)]
+#[builder(on(_, into))]
+pub struct CreateApiKeyParams {
+    pub name: String,             // 密钥名称
+    pub exchange: Exchange,       // 交易所
+    pub credentials: Credentials, // 明文密钥，保存前加密
+}
+
+pub async fn create_or_update(
+    db: &PgPool,
+    cipher: &SecretCipher,
+    data: CreateApiKeyParams,
+) -> Result<ApiKey> {
+    let row = sqlx::query_as!(
+        ApiKey,
+        r#"
+        INSERT INTO api_keys (name, exchange, api_key, secret_key, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, NOW(), NOW())
+        ON CONFLICT (name)
+        DO UPDATE SET
+            exchange = EXCLUDED.exchange,
+            api_key = EXCLUDED.api_key,
+            secret_key = EXCLUDED.secret_key,
+            updated_at = NOW()
+        RETURNING *
+        "#,
+        data.name,
+        data.exchange.as_ref(),
+        cipher.encrypt(&data.credentials.api_key)?,
+        cipher.encrypt(&data.credentials.secret_key)?,
+    )
+    .fetch_one(db)
+    .await?;
+
+    Ok(row)
+}
+
+pub async fn get_by_name(db: &PgPool, name: &str) -> Result<Option<ApiKey>> {
+    let row = sqlx::query_as!(ApiKey, r#"SELECT * FROM api_keys WHERE name = $1"#, name)
+        .fetch_optional(db)
+        .await?;
+
+    Ok(row)
+}
+
+pub async fn list(db: &PgPool) -> Result<Vec<ApiKey>> {
+    let rows = sqlx::query_as!(ApiKey, r#"SELECT * FROM api_keys ORDER BY name ASC"#)
+        .fetch_all(db)
+        .await?;
+
+    Ok(rows)
+}
+
+pub async fn delete(db: &PgPool, name: &str) -> Result<()> {
+    sqlx::query!(r#"DELETE FROM api_keys WHERE name = $1"#, name)
+        .execute(db)
+        .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[sqlx::test(migrator = "crate::MIGRATOR")]
+    async fn test_api_keys(db: PgPool) -> Result<()> {
+        let cipher = SecretCipher::from_base64(&SecretCipher::generate_key()?)?;
+
+        let data = CreateApiKeyParams::builder()
+            .name("main")
+            .exchange(Exchange::Binance)
+            .credentials(Credentials::new("api_key", "secret_key"))
+            .build();
+
+        let row = create_or_update(&db, &cipher, data).await?;
+
+        // 数据库中只保存密文
+        assert_ne!(row.api_key, "api_key");
+        assert_ne!(row.secret_key, "secret_key");
+
+        let row = get_by_name(&db, "main")
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("api key not found"))?;
+        assert_eq!(
+            row.decrypt(&cipher)?,
+            Credentials::new("api_key", "secret_key")
+        );
+
+        // 同名密钥更新
+        let data = CreateApiKeyParams::builder()
+            .name("main")
+            .exchange(Exchange::Binance)
+            .credentials(Credentials::new("api_key2", "secret_key2"))
+            .build();
+        create_or_update(&db, &cipher, data).await?;
+
+        let rows = list(&db).await?;
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].decrypt(&cipher)?.api_key, "api_key2");
+
+        delete(&db, "main").await?;
+        assert!(get_by_name(&db, "main").await?.is_none());
+
+        Ok(())
+    }
+}
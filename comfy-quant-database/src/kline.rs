@@ -2,25 +2,68 @@ use anyhow::Result;
 use bon::Builder;
 use chrono::{DateTime, Utc};
 use comfy_quant_base::{Exchange, KlineInterval, Market, Symbol};
-use futures::stream::BoxStream;
+use futures::{stream::BoxStream, StreamExt, TryStreamExt};
 use rust_decimal::Decimal;
 use sqlx::{postgres::PgPool, FromRow};
+use std::{collections::HashMap, fmt};
+
+// K线数据来源
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KlineSource {
+    Backfill, // 历史回填
+    Live,     // 实时采集
+}
+
+impl From<&str> for KlineSource {
+    fn from(value: &str) -> Self {
+        match value {
+            "live" => KlineSource::Live,
+            _ => KlineSource::Backfill,
+        }
+    }
+}
+
+impl From<String> for KlineSource {
+    fn from(value: String) -> Self {
+        value.as_str().into()
+    }
+}
+
+impl AsRef<str> for KlineSource {
+    fn as_ref(&self) -> &str {
+        match self {
+            KlineSource::Backfill => "backfill",
+            KlineSource::Live => "live",
+        }
+    }
+}
+
+impl fmt::Display for KlineSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_ref())
+    }
+}
 
 #[derive(Debug, FromRow)]
 pub struct Kline {
-    pub id: i32,                   // 主键ID
-    pub exchange: Exchange,        // 交易所
-    pub market: Market,            // 市场
-    pub symbol: Symbol,            // 交易对
-    pub interval: KlineInterval,   // 时间间隔
-    pub open_time: DateTime<Utc>,  // 开盘时间
-    pub open_price: Decimal,       // 开盘价格
-    pub high_price: Decimal,       // 最高价格
-    pub low_price: Decimal,        // 最低价格
-    pub close_price: Decimal,      // 收盘价格
-    pub volume: Decimal,           // 成交量
-    pub created_at: DateTime<Utc>, // 创建时间
-    pub updated_at: DateTime<Utc>, // 更新时间
+    pub id: i32,                           // 主键ID
+    pub exchange: Exchange,                // 交易所
+    pub market: Market,                    // 市场
+    pub symbol: Symbol,                    // 交易对
+    pub interval: KlineInterval,           // 时间间隔
+    pub open_time: DateTime<Utc>,          // 开盘时间
+    pub open_price: Decimal,               // 开盘价格
+    pub high_price: Decimal,               // 最高价格
+    pub low_price: Decimal,                // 最低价格
+    pub close_price: Decimal,              // 收盘价格
+    pub volume: Decimal,                   // 成交量
+    pub taker_buy_volume: Decimal,         // 主动买入成交量
+    pub created_at: DateTime<Utc>,         // 创建时间
+    pub updated_at: DateTime<Utc>,         // 更新时间
+    pub source: KlineSource,               // 数据来源
+    pub is_closed: bool,                   // 是否已收盘
+    pub revision: i32,                     // 修订次数，数据每次变化加1
+    pub close_time: Option<DateTime<Utc>>, // 收盘时间，按成交笔数聚合的K线有值
 }
 
 #[derive(Builder)]
@@ -36,6 +79,9 @@ pub struct CreateKlineParams {
     pub low_price: Decimal,       // 最低价格
     pub close_price: Decimal,     // 收盘价格
     pub volume: Decimal,          // 成交量
+    #[builder(default)]
+    pub taker_buy_volume: Decimal, // 主动买入成交量，数据源没有时为0
+    pub close_time: Option<DateTime<Utc>>, // 收盘时间，按成交笔数聚合的K线需要
 }
 
 #[derive(Builder)]
@@ -46,14 +92,16 @@ pub struct UpdateKlineParams {
     pub low_price: Decimal,   // 最低价格
     pub close_price: Decimal, // 收盘价格
     pub volume: Decimal,      // 成交量
+    #[builder(default)]
+    pub taker_buy_volume: Decimal, // 主动买入成交量
 }
 
 pub async fn create(db: &PgPool, data: CreateKlineParams) -> Result<Kline> {
     let kline = sqlx::query_as!(
         Kline,
         r#"
-        INSERT INTO klines (exchange, market, symbol, interval, open_time, open_price, high_price, low_price, close_price, volume, created_at, updated_at)
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, NOW(), NOW())
+        INSERT INTO klines (exchange, market, symbol, interval, open_time, open_price, high_price, low_price, close_price, volume, taker_buy_volume, close_time, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, NOW(), NOW())
         RETURNING *
         "#,
         data.exchange.as_ref(),
@@ -66,6 +114,8 @@ pub async fn create(db: &PgPool, data: CreateKlineParams) -> Result<Kline> {
         data.low_price,
         data.close_price,
         data.volume,
+        data.taker_buy_volume,
+        data.close_time,
     )
     .fetch_one(db)
     .await?;
@@ -77,13 +127,14 @@ pub async fn update(db: &PgPool, data: UpdateKlineParams) -> Result<Kline> {
     let kline = sqlx::query_as!(
         Kline,
         r#"
-        UPDATE klines SET high_price = $1, low_price = $2, close_price = $3, volume = $4, updated_at = NOW() WHERE id = $5
+        UPDATE klines SET high_price = $1, low_price = $2, close_price = $3, volume = $4, taker_buy_volume = $5, updated_at = NOW() WHERE id = $6
         RETURNING *
         "#,
         data.high_price,
         data.low_price,
         data.close_price,
         data.volume,
+        data.taker_buy_volume,
         data.id,
     )
     .fetch_one(db)
@@ -96,8 +147,8 @@ pub async fn create_or_update(db: &PgPool, data: CreateKlineParams) -> Result<Kl
     let kline = sqlx::query_as!(
         Kline,
         r#"
-        INSERT INTO klines (exchange, market, symbol, interval, open_time, open_price, high_price, low_price, close_price, volume, created_at, updated_at)
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, NOW(), NOW())
+        INSERT INTO klines (exchange, market, symbol, interval, open_time, open_price, high_price, low_price, close_price, volume, taker_buy_volume, close_time, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, NOW(), NOW())
         ON CONFLICT (exchange, market, symbol, interval, open_time)
         DO UPDATE SET
             open_price = EXCLUDED.open_price,
@@ -105,6 +156,8 @@ pub async fn create_or_update(db: &PgPool, data: CreateKlineParams) -> Result<Kl
             low_price = EXCLUDED.low_price,
             close_price = EXCLUDED.close_price,
             volume = EXCLUDED.volume,
+            taker_buy_volume = EXCLUDED.taker_buy_volume,
+            close_time = EXCLUDED.close_time,
             updated_at = NOW()
         RETURNING *
         "#,
@@ -118,12 +171,348 @@ pub async fn create_or_update(db: &PgPool, data: CreateKlineParams) -> Result<Kl
         data.low_price,
         data.close_price,
         data.volume,
+        data.taker_buy_volume,
+        data.close_time,
     ).fetch_one(db)
     .await?;
 
     Ok(kline)
 }
 
+// 批量创建或更新K线，一条语句写入整批数据，返回写入的行数。
+// 冲突时与 create_or_update 一样覆盖，同一批中的K线不能重复
+pub async fn bulk_upsert(db: &PgPool, data: &[CreateKlineParams]) -> Result<u64> {
+    if data.is_empty() {
+        return Ok(0);
+    }
+
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO klines (exchange, market, symbol, interval, open_time, open_price, high_price, low_price, close_price, volume, taker_buy_volume, close_time, created_at, updated_at)
+        SELECT exchange, market, symbol, interval, open_time, open_price, high_price, low_price, close_price, volume, taker_buy_volume, close_time, NOW(), NOW()
+        FROM UNNEST($1::varchar[], $2::varchar[], $3::varchar[], $4::varchar[], $5::timestamptz[], $6::numeric[], $7::numeric[], $8::numeric[], $9::numeric[], $10::numeric[], $11::numeric[], $12::timestamptz[])
+            AS t(exchange, market, symbol, interval, open_time, open_price, high_price, low_price, close_price, volume, taker_buy_volume, close_time)
+        ON CONFLICT (exchange, market, symbol, interval, open_time)
+        DO UPDATE SET
+            open_price = EXCLUDED.open_price,
+            high_price = EXCLUDED.high_price,
+            low_price = EXCLUDED.low_price,
+            close_price = EXCLUDED.close_price,
+            volume = EXCLUDED.volume,
+            taker_buy_volume = EXCLUDED.taker_buy_volume,
+            close_time = EXCLUDED.close_time,
+            updated_at = NOW()
+        "#,
+        &data.iter().map(|d| d.exchange.as_ref().to_string()).collect::<Vec<_>>(),
+        &data.iter().map(|d| d.market.as_ref().to_string()).collect::<Vec<_>>(),
+        &data.iter().map(|d| d.symbol.as_ref().to_string()).collect::<Vec<_>>(),
+        &data.iter().map(|d| d.interval.as_ref().to_string()).collect::<Vec<_>>(),
+        &data.iter().map(|d| d.open_time).collect::<Vec<_>>(),
+        &data.iter().map(|d| d.open_price).collect::<Vec<_>>(),
+        &data.iter().map(|d| d.high_price).collect::<Vec<_>>(),
+        &data.iter().map(|d| d.low_price).collect::<Vec<_>>(),
+        &data.iter().map(|d| d.close_price).collect::<Vec<_>>(),
+        &data.iter().map(|d| d.volume).collect::<Vec<_>>(),
+        &data.iter().map(|d| d.taker_buy_volume).collect::<Vec<_>>(),
+        // 收盘时间可以为空，跳过宏对数组元素类型的检查
+        &data.iter().map(|d| d.close_time).collect::<Vec<_>>() as _,
+    )
+    .execute(db)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+#[derive(Builder)]
+#[builder(on(_, into))]
+pub struct UpsertKlineParams {
+    pub exchange: Exchange,       // 交易所
+    pub market: Market,           // 市场
+    pub symbol: Symbol,           // 交易对
+    pub interval: KlineInterval,  // 时间间隔
+    pub open_time: DateTime<Utc>, // 开盘时间
+    pub open_price: Decimal,      // 开盘价格
+    pub high_price: Decimal,      // 最高价格
+    pub low_price: Decimal,       // 最低价格
+    pub close_price: Decimal,     // 收盘价格
+    pub volume: Decimal,          // 成交量
+    #[builder(default)]
+    pub taker_buy_volume: Decimal, // 主动买入成交量，数据源没有时为0
+    pub source: KlineSource,      // 数据来源
+    pub is_closed: bool,          // 是否已收盘
+}
+
+// 写入K线的结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpsertOutcome {
+    Inserted,  // 新增
+    Updated,   // 数据变化，已覆盖
+    Unchanged, // 数据相同，未修改
+    Rejected,  // 未收盘的K线不能覆盖已收盘的K线
+}
+
+// 按冲突规则写入K线：已收盘的数据优先，未收盘的K线不能覆盖已收盘的K线；
+// 数据没有变化时不修改，数据变化时记录来源并增加修订次数
+pub async fn upsert(db: &PgPool, data: UpsertKlineParams) -> Result<(UpsertOutcome, Kline)> {
+    let kline = sqlx::query_as!(
+        Kline,
+        r#"
+        INSERT INTO klines (exchange, market, symbol, interval, open_time, open_price, high_price, low_price, close_price, volume, taker_buy_volume, source, is_closed, revision, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, 1, NOW(), NOW())
+        ON CONFLICT (exchange, market, symbol, interval, open_time)
+        DO UPDATE SET
+            open_price = EXCLUDED.open_price,
+            high_price = EXCLUDED.high_price,
+            low_price = EXCLUDED.low_price,
+            close_price = EXCLUDED.close_price,
+            volume = EXCLUDED.volume,
+            taker_buy_volume = EXCLUDED.taker_buy_volume,
+            source = EXCLUDED.source,
+            is_closed = EXCLUDED.is_closed,
+            revision = klines.revision + 1,
+            updated_at = NOW()
+        WHERE
+            (EXCLUDED.is_closed OR NOT klines.is_closed) AND
+            (klines.open_price, klines.high_price, klines.low_price, klines.close_price, klines.volume, klines.taker_buy_volume, klines.is_closed)
+                IS DISTINCT FROM
+            (EXCLUDED.open_price, EXCLUDED.high_price, EXCLUDED.low_price, EXCLUDED.close_price, EXCLUDED.volume, EXCLUDED.taker_buy_volume, EXCLUDED.is_closed)
+        RETURNING *
+        "#,
+        data.exchange.as_ref(),
+        data.market.as_ref(),
+        data.symbol.as_ref(),
+        data.interval.as_ref(),
+        data.open_time,
+        data.open_price,
+        data.high_price,
+        data.low_price,
+        data.close_price,
+        data.volume,
+        data.taker_buy_volume,
+        data.source.as_ref(),
+        data.is_closed,
+    )
+    .fetch_optional(db)
+    .await?;
+
+    if let Some(kline) = kline {
+        let outcome = if kline.revision == 1 {
+            UpsertOutcome::Inserted
+        } else {
+            UpsertOutcome::Updated
+        };
+
+        return Ok((outcome, kline));
+    }
+
+    // 没有写入，查询已有的K线判断原因
+    let kline = get_kline(
+        db,
+        &data.exchange,
+        &data.market,
+        &data.symbol,
+        &data.interval,
+        &data.open_time,
+    )
+    .await?
+    .ok_or_else(|| anyhow::anyhow!("Kline not found after upsert"))?;
+
+    let outcome = if kline.is_closed && !data.is_closed {
+        UpsertOutcome::Rejected
+    } else {
+        UpsertOutcome::Unchanged
+    };
+
+    Ok((outcome, kline))
+}
+
+// 批量写入K线，冲突规则与 upsert 相同，按输入顺序返回每根K线的结果。
+// 一条语句写入整批数据，同一批中的K线不能重复
+pub async fn upsert_many(
+    db: &PgPool,
+    data: &[UpsertKlineParams],
+) -> Result<Vec<(UpsertOutcome, Kline)>> {
+    if data.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let exchanges = data
+        .iter()
+        .map(|d| d.exchange.as_ref().to_string())
+        .collect::<Vec<_>>();
+    let markets = data
+        .iter()
+        .map(|d| d.market.as_ref().to_string())
+        .collect::<Vec<_>>();
+    let symbols = data
+        .iter()
+        .map(|d| d.symbol.as_ref().to_string())
+        .collect::<Vec<_>>();
+    let intervals = data
+        .iter()
+        .map(|d| d.interval.as_ref().to_string())
+        .collect::<Vec<_>>();
+    let open_times = data.iter().map(|d| d.open_time).collect::<Vec<_>>();
+
+    let written = sqlx::query_as!(
+        Kline,
+        r#"
+        INSERT INTO klines (exchange, market, symbol, interval, open_time, open_price, high_price, low_price, close_price, volume, taker_buy_volume, source, is_closed, revision, created_at, updated_at)
+        SELECT exchange, market, symbol, interval, open_time, open_price, high_price, low_price, close_price, volume, taker_buy_volume, source, is_closed, 1, NOW(), NOW()
+        FROM UNNEST($1::varchar[], $2::varchar[], $3::varchar[], $4::varchar[], $5::timestamptz[], $6::numeric[], $7::numeric[], $8::numeric[], $9::numeric[], $10::numeric[], $11::numeric[], $12::varchar[], $13::bool[])
+            AS t(exchange, market, symbol, interval, open_time, open_price, high_price, low_price, close_price, volume, taker_buy_volume, source, is_closed)
+        ON CONFLICT (exchange, market, symbol, interval, open_time)
+        DO UPDATE SET
+            open_price = EXCLUDED.open_price,
+            high_price = EXCLUDED.high_price,
+            low_price = EXCLUDED.low_price,
+            close_price = EXCLUDED.close_price,
+            volume = EXCLUDED.volume,
+            taker_buy_volume = EXCLUDED.taker_buy_volume,
+            source = EXCLUDED.source,
+            is_closed = EXCLUDED.is_closed,
+            revision = klines.revision + 1,
+            updated_at = NOW()
+        WHERE
+            (EXCLUDED.is_closed OR NOT klines.is_closed) AND
+            (klines.open_price, klines.high_price, klines.low_price, klines.close_price, klines.volume, klines.taker_buy_volume, klines.is_closed)
+                IS DISTINCT FROM
+            (EXCLUDED.open_price, EXCLUDED.high_price, EXCLUDED.low_price, EXCLUDED.close_price, EXCLUDED.volume, EXCLUDED.taker_buy_volume, EXCLUDED.is_closed)
+        RETURNING *
+        "#,
+        &exchanges,
+        &markets,
+        &symbols,
+        &intervals,
+        &open_times,
+        &data.iter().map(|d| d.open_price).collect::<Vec<_>>(),
+        &data.iter().map(|d| d.high_price).collect::<Vec<_>>(),
+        &data.iter().map(|d| d.low_price).collect::<Vec<_>>(),
+        &data.iter().map(|d| d.close_price).collect::<Vec<_>>(),
+        &data.iter().map(|d| d.volume).collect::<Vec<_>>(),
+        &data.iter().map(|d| d.taker_buy_volume).collect::<Vec<_>>(),
+        &data.iter().map(|d| d.source.as_ref().to_string()).collect::<Vec<_>>(),
+        &data.iter().map(|d| d.is_closed).collect::<Vec<_>>(),
+    )
+    .fetch_all(db)
+    .await?;
+
+    // 没有写入的K线，查询已有的K线判断原因
+    let skipped = if written.len() < data.len() {
+        sqlx::query_as!(
+            Kline,
+            r#"
+            SELECT klines.* FROM klines
+            INNER JOIN UNNEST($1::varchar[], $2::varchar[], $3::varchar[], $4::varchar[], $5::timestamptz[])
+                AS t(exchange, market, symbol, interval, open_time)
+            USING (exchange, market, symbol, interval, open_time)
+            "#,
+            &exchanges,
+            &markets,
+            &symbols,
+            &intervals,
+            &open_times,
+        )
+        .fetch_all(db)
+        .await?
+    } else {
+        Vec::new()
+    };
+
+    let key = |exchange: &Exchange,
+               market: &Market,
+               symbol: &Symbol,
+               interval: &KlineInterval,
+               open_time: DateTime<Utc>| {
+        (
+            exchange.as_ref().to_string(),
+            market.as_ref().to_string(),
+            symbol.as_ref().to_string(),
+            interval.as_ref().to_string(),
+            open_time,
+        )
+    };
+    let kline_key = |kline: &Kline| {
+        key(
+            &kline.exchange,
+            &kline.market,
+            &kline.symbol,
+            &kline.interval,
+            kline.open_time,
+        )
+    };
+    let mut written = written
+        .into_iter()
+        .map(|kline| (kline_key(&kline), kline))
+        .collect::<HashMap<_, _>>();
+    let mut skipped = skipped
+        .into_iter()
+        .map(|kline| (kline_key(&kline), kline))
+        .collect::<HashMap<_, _>>();
+
+    data.iter()
+        .map(|d| {
+            let key = key(&d.exchange, &d.market, &d.symbol, &d.interval, d.open_time);
+
+            if let Some(kline) = written.remove(&key) {
+                let outcome = if kline.revision == 1 {
+                    UpsertOutcome::Inserted
+                } else {
+                    UpsertOutcome::Updated
+                };
+
+                return Ok((outcome, kline));
+            }
+
+            let kline = skipped
+                .remove(&key)
+                .ok_or_else(|| anyhow::anyhow!("Kline not found after upsert"))?;
+
+            let outcome = if kline.is_closed && !d.is_closed {
+                UpsertOutcome::Rejected
+            } else {
+                UpsertOutcome::Unchanged
+            };
+
+            Ok((outcome, kline))
+        })
+        .collect()
+}
+
+// 一批K线写入的对账报告
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReconciliationReport {
+    pub inserted: u64,  // 新增
+    pub updated: u64,   // 覆盖
+    pub unchanged: u64, // 未变化
+    pub rejected: u64,  // 拒绝覆盖已收盘K线
+}
+
+impl ReconciliationReport {
+    pub fn record(&mut self, outcome: UpsertOutcome) {
+        match outcome {
+            UpsertOutcome::Inserted => self.inserted += 1,
+            UpsertOutcome::Updated => self.updated += 1,
+            UpsertOutcome::Unchanged => self.unchanged += 1,
+            UpsertOutcome::Rejected => self.rejected += 1,
+        }
+    }
+
+    pub fn total(&self) -> u64 {
+        self.inserted + self.updated + self.unchanged + self.rejected
+    }
+}
+
+impl fmt::Display for ReconciliationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "inserted: {}, updated: {}, unchanged: {}, rejected: {}",
+            self.inserted, self.updated, self.unchanged, self.rejected
+        )
+    }
+}
+
 pub async fn get_by_id(db: &PgPool, id: i32) -> Result<Option<Kline>> {
     let kline = sqlx::query_as!(
         Kline,
@@ -172,17 +561,24 @@ pub async fn list(
     start_datetime: &DateTime<Utc>,
     end_datetime: &DateTime<Utc>,
 ) -> Result<Vec<Kline>> {
-    let result = sqlx::query_as!(
+    let result: Vec<Kline> = sqlx::query_as!(
         Kline,
         r#"
-        SELECT * FROM klines
+        WITH RECURSIVE symbols AS (
+            SELECT $3::VARCHAR AS symbol, NULL::TIMESTAMPTZ AS renamed_at
+            UNION
+            SELECT a.old_symbol, a.renamed_at FROM symbol_aliases a
+                JOIN symbols s ON a.new_symbol = s.symbol
+                WHERE a.exchange = $1 AND a.market = $2
+        )
+        SELECT k.* FROM klines k JOIN symbols s ON k.symbol = s.symbol
             WHERE
-                exchange = $1 AND
-                market = $2 AND
-                symbol = $3 AND
-                interval = $4 AND
-                open_time BETWEEN $5 AND $6
-            ORDER BY open_time ASC
+                k.exchange = $1 AND
+                k.market = $2 AND
+                k.interval = $4 AND
+                k.open_time BETWEEN $5 AND $6 AND
+                (s.renamed_at IS NULL OR k.open_time < s.renamed_at)
+            ORDER BY k.open_time ASC
         "#,
         exchange.as_ref(),
         market.as_ref(),
@@ -192,7 +588,58 @@ pub async fn list(
         end_datetime
     )
     .fetch_all(db)
-    .await?;
+    .await?
+    .into_iter()
+    .map(|kline| with_symbol(kline, symbol))
+    .collect();
+
+    Ok(result)
+}
+
+// 结束时间之前最近的limit根K线，按开盘时间升序返回
+pub async fn list_recent(
+    db: &PgPool,
+    exchange: &Exchange,
+    market: &Market,
+    symbol: &Symbol,
+    interval: &KlineInterval,
+    end_datetime: &DateTime<Utc>,
+    limit: i64,
+) -> Result<Vec<Kline>> {
+    let mut result: Vec<Kline> = sqlx::query_as!(
+        Kline,
+        r#"
+        WITH RECURSIVE symbols AS (
+            SELECT $3::VARCHAR AS symbol, NULL::TIMESTAMPTZ AS renamed_at
+            UNION
+            SELECT a.old_symbol, a.renamed_at FROM symbol_aliases a
+                JOIN symbols s ON a.new_symbol = s.symbol
+                WHERE a.exchange = $1 AND a.market = $2
+        )
+        SELECT k.* FROM klines k JOIN symbols s ON k.symbol = s.symbol
+            WHERE
+                k.exchange = $1 AND
+                k.market = $2 AND
+                k.interval = $4 AND
+                k.open_time < $5 AND
+                (s.renamed_at IS NULL OR k.open_time < s.renamed_at)
+            ORDER BY k.open_time DESC
+            LIMIT $6
+        "#,
+        exchange.as_ref(),
+        market.as_ref(),
+        symbol.as_ref(),
+        interval.as_ref(),
+        end_datetime,
+        limit
+    )
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .map(|kline| with_symbol(kline, symbol))
+    .collect();
+
+    result.reverse();
 
     Ok(result)
 }
@@ -206,10 +653,26 @@ pub fn time_range_klines_stream<'a>(
     start_datetime: &DateTime<Utc>,
     end_datetime: &DateTime<Utc>,
 ) -> BoxStream<'a, Result<Kline, sqlx::Error>> {
+    let alias = symbol.clone();
+
     sqlx::query_as!(
         Kline,
         r#"
-        SELECT * FROM klines WHERE exchange = $1 AND market = $2 AND symbol = $3 AND interval = $4 AND open_time >= $5 AND open_time <= $6 ORDER BY open_time ASC
+        WITH RECURSIVE symbols AS (
+            SELECT $3::VARCHAR AS symbol, NULL::TIMESTAMPTZ AS renamed_at
+            UNION
+            SELECT a.old_symbol, a.renamed_at FROM symbol_aliases a
+                JOIN symbols s ON a.new_symbol = s.symbol
+                WHERE a.exchange = $1 AND a.market = $2
+        )
+        SELECT k.* FROM klines k JOIN symbols s ON k.symbol = s.symbol
+            WHERE
+                k.exchange = $1 AND
+                k.market = $2 AND
+                k.interval = $4 AND
+                k.open_time >= $5 AND k.open_time <= $6 AND
+                (s.renamed_at IS NULL OR k.open_time < s.renamed_at)
+            ORDER BY k.open_time ASC
         "#,
         exchange.as_ref(),
         market.as_ref(),
@@ -219,6 +682,8 @@ pub fn time_range_klines_stream<'a>(
         end_datetime,
     )
     .fetch(db)
+    .map_ok(move |kline| with_symbol(kline, &alias))
+    .boxed()
 }
 
 pub async fn time_range_klines_count(
@@ -232,7 +697,20 @@ pub async fn time_range_klines_count(
 ) -> Result<usize> {
     let count = sqlx::query_scalar!(
         r#"
-        SELECT COUNT(*) FROM klines WHERE exchange = $1 AND market = $2 AND symbol = $3 AND interval = $4 AND open_time >= $5 AND open_time <= $6
+        WITH RECURSIVE symbols AS (
+            SELECT $3::VARCHAR AS symbol, NULL::TIMESTAMPTZ AS renamed_at
+            UNION
+            SELECT a.old_symbol, a.renamed_at FROM symbol_aliases a
+                JOIN symbols s ON a.new_symbol = s.symbol
+                WHERE a.exchange = $1 AND a.market = $2
+        )
+        SELECT COUNT(*) FROM klines k JOIN symbols s ON k.symbol = s.symbol
+            WHERE
+                k.exchange = $1 AND
+                k.market = $2 AND
+                k.interval = $4 AND
+                k.open_time >= $5 AND k.open_time <= $6 AND
+                (s.renamed_at IS NULL OR k.open_time < s.renamed_at)
         "#,
         exchange.as_ref(),
         market.as_ref(),
@@ -247,6 +725,230 @@ pub async fn time_range_klines_count(
     Ok(count.unwrap_or(0) as usize)
 }
 
+// 创建时间范围内各月份的K线分区，写入大量K线前调用，避免数据堆积在默认分区
+pub async fn ensure_partitions(
+    db: &PgPool,
+    start_datetime: &DateTime<Utc>,
+    end_datetime: &DateTime<Utc>,
+) -> Result<()> {
+    sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) FROM (
+            SELECT create_klines_partition(month::DATE)
+            FROM generate_series(
+                date_trunc('month', $1 AT TIME ZONE 'UTC'),
+                date_trunc('month', $2 AT TIME ZONE 'UTC'),
+                INTERVAL '1 month'
+            ) AS month
+        ) t
+        "#,
+        start_datetime,
+        end_datetime,
+    )
+    .fetch_one(db)
+    .await?;
+
+    Ok(())
+}
+
+// 缺失的K线区间，开始和结束均为缺失K线的开盘时间
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KlineGap {
+    pub start_time: DateTime<Utc>, // 第一根缺失K线的开盘时间
+    pub end_time: DateTime<Utc>,   // 最后一根缺失K线的开盘时间
+}
+
+impl KlineGap {
+    // 缺失的K线数量
+    pub fn kline_count(&self, interval: &KlineInterval) -> usize {
+        let millis = interval.to_millis().max(1);
+        ((self.end_time - self.start_time).num_milliseconds() / millis + 1) as usize
+    }
+}
+
+// 查找时间范围内缺失的K线区间，按开始时间正序返回。
+// 与 time_range_klines_count 一样包含交易对更名前的K线
+pub async fn find_gaps(
+    db: &PgPool,
+    exchange: &Exchange,
+    market: &Market,
+    symbol: &Symbol,
+    interval: &KlineInterval,
+    start_datetime: &DateTime<Utc>,
+    end_datetime: &DateTime<Utc>,
+) -> Result<Vec<KlineGap>> {
+    let millis = interval.to_millis();
+
+    if millis == 0 {
+        anyhow::bail!("Kline interval {} has no fixed duration", interval);
+    }
+
+    // 在时间范围前后各补一根虚拟K线，相邻两根K线的间隔超过周期即为缺失
+    let rows = sqlx::query!(
+        r#"
+        WITH RECURSIVE symbols AS (
+            SELECT $3::VARCHAR AS symbol, NULL::TIMESTAMPTZ AS renamed_at
+            UNION
+            SELECT a.old_symbol, a.renamed_at FROM symbol_aliases a
+                JOIN symbols s ON a.new_symbol = s.symbol
+                WHERE a.exchange = $1 AND a.market = $2
+        ),
+        open_times AS (
+            SELECT k.open_time FROM klines k JOIN symbols s ON k.symbol = s.symbol
+                WHERE
+                    k.exchange = $1 AND
+                    k.market = $2 AND
+                    k.interval = $4 AND
+                    k.open_time >= $5 AND k.open_time <= $6 AND
+                    (s.renamed_at IS NULL OR k.open_time < s.renamed_at)
+            UNION
+            SELECT $5 - $7 * INTERVAL '1 millisecond'
+            UNION
+            SELECT $6 + $7 * INTERVAL '1 millisecond'
+        ),
+        gaps AS (
+            SELECT
+                open_time + $7 * INTERVAL '1 millisecond' AS start_time,
+                LEAD(open_time) OVER (ORDER BY open_time) - $7 * INTERVAL '1 millisecond' AS end_time
+            FROM open_times
+        )
+        SELECT start_time AS "start_time!", end_time AS "end_time!" FROM gaps
+            WHERE end_time >= start_time
+            ORDER BY start_time
+        "#,
+        exchange.as_ref(),
+        market.as_ref(),
+        symbol.as_ref(),
+        interval.as_ref(),
+        start_datetime,
+        end_datetime,
+        millis as f64,
+    )
+    .fetch_all(db)
+    .await?;
+
+    let gaps = rows
+        .into_iter()
+        .map(|row| KlineGap {
+            start_time: row.start_time,
+            end_time: row.end_time,
+        })
+        .collect();
+
+    Ok(gaps)
+}
+
+// 由小周期K线聚合出大周期K线，如由1m生成5m、1h、1d，周期按UTC对齐，周线从周一开始。
+// 只返回数据完整的K线，可直接通过 upsert_many 保存
+#[allow(clippy::too_many_arguments)]
+pub async fn aggregate(
+    db: &PgPool,
+    exchange: &Exchange,
+    market: &Market,
+    symbol: &Symbol,
+    from_interval: &KlineInterval,
+    to_interval: &KlineInterval,
+    start_datetime: &DateTime<Utc>,
+    end_datetime: &DateTime<Utc>,
+) -> Result<Vec<UpsertKlineParams>> {
+    let from_seconds = from_interval.to_seconds();
+    let to_seconds = to_interval.to_seconds();
+
+    if from_seconds == 0 || to_seconds <= from_seconds || to_seconds % from_seconds != 0 {
+        anyhow::bail!(
+            "Can not aggregate klines from {} to {}",
+            from_interval,
+            to_interval
+        );
+    }
+
+    let rows = sqlx::query!(
+        r#"
+        WITH RECURSIVE symbols AS (
+            SELECT $3::VARCHAR AS symbol, NULL::TIMESTAMPTZ AS renamed_at
+            UNION
+            SELECT a.old_symbol, a.renamed_at FROM symbol_aliases a
+                JOIN symbols s ON a.new_symbol = s.symbol
+                WHERE a.exchange = $1 AND a.market = $2
+        ),
+        buckets AS (
+            SELECT
+                CASE $6::VARCHAR
+                    WHEN '1w' THEN date_trunc('week', k.open_time AT TIME ZONE 'UTC') AT TIME ZONE 'UTC'
+                    WHEN '1M' THEN date_trunc('month', k.open_time AT TIME ZONE 'UTC') AT TIME ZONE 'UTC'
+                    ELSE to_timestamp(floor(extract(epoch FROM k.open_time) / $7) * $7)
+                END AS bucket_time,
+                k.open_time, k.open_price, k.high_price, k.low_price, k.close_price, k.volume, k.taker_buy_volume
+            FROM klines k JOIN symbols s ON k.symbol = s.symbol
+            WHERE
+                k.exchange = $1 AND
+                k.market = $2 AND
+                k.interval = $4 AND
+                k.open_time >= $8 AND k.open_time <= $9 AND
+                (s.renamed_at IS NULL OR k.open_time < s.renamed_at)
+        )
+        SELECT
+            bucket_time AS "open_time!",
+            (array_agg(open_price ORDER BY open_time))[1] AS "open_price!",
+            MAX(high_price) AS "high_price!",
+            MIN(low_price) AS "low_price!",
+            (array_agg(close_price ORDER BY open_time DESC))[1] AS "close_price!",
+            SUM(volume) AS "volume!",
+            SUM(taker_buy_volume) AS "taker_buy_volume!"
+        FROM buckets
+        GROUP BY bucket_time
+        HAVING COUNT(*) = extract(epoch FROM (
+            CASE $6::VARCHAR
+                WHEN '1w' THEN bucket_time + INTERVAL '1 week'
+                WHEN '1M' THEN bucket_time + INTERVAL '1 month'
+                ELSE bucket_time + $7 * INTERVAL '1 second'
+            END - bucket_time
+        )) / $5
+        ORDER BY bucket_time
+        "#,
+        exchange.as_ref(),
+        market.as_ref(),
+        symbol.as_ref(),
+        from_interval.as_ref(),
+        from_seconds as f64,
+        to_interval.as_ref(),
+        to_seconds as f64,
+        start_datetime,
+        end_datetime,
+    )
+    .fetch_all(db)
+    .await?;
+
+    let klines = rows
+        .into_iter()
+        .map(|row| {
+            UpsertKlineParams::builder()
+                .exchange(exchange.clone())
+                .market(market.clone())
+                .symbol(symbol.clone())
+                .interval(to_interval.clone())
+                .open_time(row.open_time)
+                .open_price(row.open_price)
+                .high_price(row.high_price)
+                .low_price(row.low_price)
+                .close_price(row.close_price)
+                .volume(row.volume)
+                .taker_buy_volume(row.taker_buy_volume)
+                .source(KlineSource::Backfill)
+                .is_closed(true)
+                .build()
+        })
+        .collect();
+
+    Ok(klines)
+}
+
+// 更名前的K线统一使用查询时的交易对名称
+fn with_symbol(mut kline: Kline, symbol: &Symbol) -> Kline {
+    kline.symbol = symbol.clone();
+    kline
+}
+
 // pub async fn listen_for_kline_changes(db: &PgPool) -> Result<(), sqlx::Error> {
 //     sqlx::query("LISTEN kline_change").execute(db).await?;
 
@@ -310,6 +1012,7 @@ mod tests {
         assert_eq!(kline_created.low_price, dec!(10000));
         assert_eq!(kline_created.close_price, dec!(10000));
         assert_eq!(kline_created.volume, dec!(10000));
+        assert_eq!(kline_created.taker_buy_volume, dec!(0));
 
         Ok(())
     }
@@ -379,6 +1082,7 @@ mod tests {
             .low_price(dec!(20000))
             .close_price(dec!(20000))
             .volume(dec!(20000))
+            .taker_buy_volume(dec!(12000))
             .build();
 
         let kline2 = create_or_update(&db, data2).await?;
@@ -389,6 +1093,297 @@ mod tests {
         assert_eq!(kline2.low_price, dec!(20000));
         assert_eq!(kline2.close_price, dec!(20000));
         assert_eq!(kline2.volume, dec!(20000));
+        assert_eq!(kline2.taker_buy_volume, dec!(12000));
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrator = "crate::MIGRATOR")]
+    async fn test_bulk_upsert_klines(db: PgPool) -> Result<()> {
+        let data = |open_time: i64, close_price: Decimal| -> Result<_> {
+            let params = CreateKlineParams::builder()
+                .exchange(Exchange::Binance)
+                .market(Market::Spot)
+                .symbol("BTCUSDT")
+                .interval(KlineInterval::OneMinute)
+                .open_time(secs_to_datetime(open_time)?)
+                .open_price(dec!(10000))
+                .high_price(dec!(10000))
+                .low_price(dec!(10000))
+                .close_price(close_price)
+                .volume(dec!(10000))
+                .build();
+
+            Ok(params)
+        };
+
+        let rows = bulk_upsert(
+            &db,
+            &[
+                data(1721817600, dec!(10000))?,
+                data(1721817660, dec!(10000))?,
+            ],
+        )
+        .await?;
+        assert_eq!(rows, 2);
+
+        // 已存在的K线被覆盖
+        let rows = bulk_upsert(
+            &db,
+            &[
+                data(1721817660, dec!(20000))?,
+                data(1721817720, dec!(20000))?,
+            ],
+        )
+        .await?;
+        assert_eq!(rows, 2);
+
+        let kline = get_kline(
+            &db,
+            &Exchange::Binance,
+            &Market::Spot,
+            &"BTCUSDT".into(),
+            &KlineInterval::OneMinute,
+            &secs_to_datetime(1721817660)?,
+        )
+        .await?
+        .unwrap();
+        assert_eq!(kline.close_price, dec!(20000));
+
+        assert_eq!(bulk_upsert(&db, &[]).await?, 0);
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrator = "crate::MIGRATOR")]
+    async fn test_upsert_kline(db: PgPool) -> Result<()> {
+        let open_time = secs_to_datetime(1721817600)?;
+        let data = |close_price: Decimal, source: KlineSource, is_closed: bool| {
+            UpsertKlineParams::builder()
+                .exchange(Exchange::Binance)
+                .market(Market::Spot)
+                .symbol("BTCUSDT")
+                .interval(KlineInterval::OneMinute)
+                .open_time(open_time)
+                .open_price(dec!(10000))
+                .high_price(dec!(11000))
+                .low_price(dec!(9000))
+                .close_price(close_price)
+                .volume(dec!(100))
+                .source(source)
+                .is_closed(is_closed)
+                .build()
+        };
+        let mut report = ReconciliationReport::default();
+
+        // 实时采集写入未收盘的K线
+        let (outcome, kline) = upsert(&db, data(dec!(10100), KlineSource::Live, false)).await?;
+        report.record(outcome);
+        assert_eq!(outcome, UpsertOutcome::Inserted);
+        assert_eq!(kline.revision, 1);
+        assert!(!kline.is_closed);
+
+        // 回填的收盘K线覆盖未收盘的K线
+        let (outcome, kline) = upsert(&db, data(dec!(10200), KlineSource::Backfill, true)).await?;
+        report.record(outcome);
+        assert_eq!(outcome, UpsertOutcome::Updated);
+        assert_eq!(kline.revision, 2);
+        assert_eq!(kline.source, KlineSource::Backfill);
+        assert_eq!(kline.close_price, dec!(10200));
+
+        // 延迟到达的未收盘K线不能覆盖已收盘的K线
+        let (outcome, kline) = upsert(&db, data(dec!(10150), KlineSource::Live, false)).await?;
+        report.record(outcome);
+        assert_eq!(outcome, UpsertOutcome::Rejected);
+        assert_eq!(kline.close_price, dec!(10200));
+
+        // 相同的收盘K线不修改
+        let (outcome, kline) = upsert(&db, data(dec!(10200), KlineSource::Live, true)).await?;
+        report.record(outcome);
+        assert_eq!(outcome, UpsertOutcome::Unchanged);
+        assert_eq!(kline.revision, 2);
+        assert_eq!(kline.source, KlineSource::Backfill);
+
+        assert_eq!(
+            report,
+            ReconciliationReport {
+                inserted: 1,
+                updated: 1,
+                unchanged: 1,
+                rejected: 1,
+            }
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrator = "crate::MIGRATOR")]
+    async fn test_upsert_many_klines(db: PgPool) -> Result<()> {
+        let data = |open_time: i64, close_price: Decimal, is_closed: bool| -> Result<_> {
+            let params = UpsertKlineParams::builder()
+                .exchange(Exchange::Binance)
+                .market(Market::Spot)
+                .symbol("BTCUSDT")
+                .interval(KlineInterval::OneMinute)
+                .open_time(secs_to_datetime(open_time)?)
+                .open_price(dec!(10000))
+                .high_price(dec!(11000))
+                .low_price(dec!(9000))
+                .close_price(close_price)
+                .volume(dec!(100))
+                .source(KlineSource::Backfill)
+                .is_closed(is_closed)
+                .build();
+
+            Ok(params)
+        };
+
+        let result = upsert_many(
+            &db,
+            &[
+                data(1721817600, dec!(10100), true)?,
+                data(1721817660, dec!(10200), false)?,
+            ],
+        )
+        .await?;
+        assert_eq!(result.len(), 2);
+        assert!(result
+            .iter()
+            .all(|(outcome, _)| *outcome == UpsertOutcome::Inserted));
+
+        // 未收盘的K线不能覆盖已收盘的K线，相同的K线不修改，按输入顺序返回
+        let result = upsert_many(
+            &db,
+            &[
+                data(1721817600, dec!(10150), false)?,
+                data(1721817660, dec!(10200), false)?,
+                data(1721817720, dec!(10300), true)?,
+            ],
+        )
+        .await?;
+        let outcomes = result
+            .iter()
+            .map(|(outcome, _)| *outcome)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            outcomes,
+            vec![
+                UpsertOutcome::Rejected,
+                UpsertOutcome::Unchanged,
+                UpsertOutcome::Inserted
+            ]
+        );
+        assert_eq!(result[0].1.close_price, dec!(10100));
+        assert_eq!(result[2].1.open_time.timestamp(), 1721817720);
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrator = "crate::MIGRATOR")]
+    async fn test_list_recent_klines(db: PgPool) -> Result<()> {
+        for i in 0..5 {
+            let data = CreateKlineParams::builder()
+                .exchange(Exchange::Binance)
+                .market(Market::Spot)
+                .symbol("BTCUSDT")
+                .interval(KlineInterval::OneMinute)
+                .open_time(secs_to_datetime(1721817600 + i * 60)?)
+                .open_price(Decimal::from(i))
+                .high_price(Decimal::from(i))
+                .low_price(Decimal::from(i))
+                .close_price(Decimal::from(i))
+                .volume(dec!(1))
+                .build();
+
+            create(&db, data).await?;
+        }
+
+        let klines = list_recent(
+            &db,
+            &Exchange::Binance,
+            &Market::Spot,
+            &"BTCUSDT".into(),
+            &KlineInterval::OneMinute,
+            &secs_to_datetime(1721817600 + 4 * 60)?,
+            3,
+        )
+        .await?;
+
+        let closes = klines
+            .iter()
+            .map(|kline| kline.close_price)
+            .collect::<Vec<_>>();
+        assert_eq!(closes, vec![dec!(1), dec!(2), dec!(3)]);
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrator = "crate::MIGRATOR")]
+    async fn test_list_klines_with_symbol_alias(db: PgPool) -> Result<()> {
+        let renamed_at = secs_to_datetime(1721817600 + 2 * 60)?;
+
+        // 更名前的K线记录在旧交易对下
+        for (i, symbol) in [
+            (0, "MATICUSDT"),
+            (1, "MATICUSDT"),
+            (2, "MATICUSDT"),
+            (2, "POLUSDT"),
+            (3, "POLUSDT"),
+        ] {
+            let data = CreateKlineParams::builder()
+                .exchange(Exchange::Binance)
+                .market(Market::Spot)
+                .symbol(symbol)
+                .interval(KlineInterval::OneMinute)
+                .open_time(secs_to_datetime(1721817600 + i * 60)?)
+                .open_price(Decimal::from(i))
+                .high_price(Decimal::from(i))
+                .low_price(Decimal::from(i))
+                .close_price(Decimal::from(i))
+                .volume(dec!(1))
+                .build();
+
+            create(&db, data).await?;
+        }
+
+        let data = crate::symbol_alias::CreateSymbolAliasParams::builder()
+            .exchange(Exchange::Binance)
+            .market(Market::Spot)
+            .old_symbol("MATICUSDT")
+            .new_symbol("POLUSDT")
+            .renamed_at(renamed_at)
+            .build();
+        crate::symbol_alias::create_or_update(&db, data).await?;
+
+        let start_datetime = secs_to_datetime(1721817600)?;
+        let end_datetime = secs_to_datetime(1721817600 + 3 * 60)?;
+
+        let klines = list(
+            &db,
+            &Exchange::Binance,
+            &Market::Spot,
+            &"POLUSDT".into(),
+            &KlineInterval::OneMinute,
+            &start_datetime,
+            &end_datetime,
+        )
+        .await?;
+
+        assert_eq!(klines.len(), 4);
+        assert!(klines.iter().all(|kline| kline.symbol == "POLUSDT".into()));
+
+        let count = time_range_klines_count(
+            &db,
+            &Exchange::Binance,
+            &Market::Spot,
+            &"POLUSDT".into(),
+            &KlineInterval::OneMinute,
+            &start_datetime,
+            &end_datetime,
+        )
+        .await?;
+
+        assert_eq!(count, 4);
 
         Ok(())
     }
@@ -474,6 +1469,164 @@ mod tests {
         Ok(())
     }
 
+    #[sqlx::test(migrator = "crate::MIGRATOR")]
+    async fn test_ensure_kline_partitions(db: PgPool) -> Result<()> {
+        let kline = create_kline(&db).await?;
+
+        // 2024-07 的数据在默认分区，创建分区后移入
+        ensure_partitions(
+            &db,
+            &secs_to_datetime(1721817600)?,
+            &secs_to_datetime(1722470400)?,
+        )
+        .await?;
+        ensure_partitions(
+            &db,
+            &secs_to_datetime(1721817600)?,
+            &secs_to_datetime(1721817600)?,
+        )
+        .await?;
+
+        let partitions = sqlx::query_scalar!(
+            r#"
+            SELECT c.relname AS "relname!" FROM pg_inherits i
+                JOIN pg_class c ON c.oid = i.inhrelid
+                WHERE i.inhparent = 'klines'::regclass
+            "#
+        )
+        .fetch_all(&db)
+        .await?;
+        assert!(partitions.contains(&"klines_202407".to_string()));
+        assert!(partitions.contains(&"klines_202408".to_string()));
+
+        let partition = sqlx::query_scalar!(
+            r#"SELECT tableoid::regclass::text AS "partition!" FROM klines WHERE id = $1"#,
+            kline.id
+        )
+        .fetch_one(&db)
+        .await?;
+        assert_eq!(partition, "klines_202407");
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrator = "crate::MIGRATOR")]
+    async fn test_find_kline_gaps(db: PgPool) -> Result<()> {
+        for open_time in [1721817660, 1721817720, 1721817900, 1721818020] {
+            let data = CreateKlineParams::builder()
+                .exchange(Exchange::Binance)
+                .market(Market::Spot)
+                .symbol("BTCUSDT")
+                .interval(KlineInterval::OneMinute)
+                .open_time(secs_to_datetime(open_time)?)
+                .open_price(dec!(10000))
+                .high_price(dec!(10000))
+                .low_price(dec!(10000))
+                .close_price(dec!(10000))
+                .volume(dec!(10000))
+                .build();
+
+            create(&db, data).await?;
+        }
+
+        let gaps = find_gaps(
+            &db,
+            &Exchange::Binance,
+            &Market::Spot,
+            &"BTCUSDT".into(),
+            &KlineInterval::OneMinute,
+            &secs_to_datetime(1721817600)?,
+            &secs_to_datetime(1721818140)?,
+        )
+        .await?;
+
+        let gap = |start_time: i64, end_time: i64| -> Result<KlineGap> {
+            Ok(KlineGap {
+                start_time: secs_to_datetime(start_time)?,
+                end_time: secs_to_datetime(end_time)?,
+            })
+        };
+
+        // 开头、中间和结尾的缺失
+        assert_eq!(
+            gaps,
+            vec![
+                gap(1721817600, 1721817600)?,
+                gap(1721817780, 1721817840)?,
+                gap(1721817960, 1721817960)?,
+                gap(1721818080, 1721818140)?,
+            ]
+        );
+        assert_eq!(gaps[1].kline_count(&KlineInterval::OneMinute), 2);
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrator = "crate::MIGRATOR")]
+    async fn test_aggregate_klines(db: PgPool) -> Result<()> {
+        // 1721817600 为 5m 周期的开始，写入 9 根 1m K线，第二个 5m 周期不完整
+        for i in 0..9 {
+            let price = Decimal::from(10000 + i);
+            let data = CreateKlineParams::builder()
+                .exchange(Exchange::Binance)
+                .market(Market::Spot)
+                .symbol("BTCUSDT")
+                .interval(KlineInterval::OneMinute)
+                .open_time(secs_to_datetime(1721817600 + i * 60)?)
+                .open_price(price)
+                .high_price(price + dec!(5))
+                .low_price(price - dec!(5))
+                .close_price(price + dec!(1))
+                .volume(dec!(10))
+                .taker_buy_volume(dec!(4))
+                .build();
+
+            create(&db, data).await?;
+        }
+
+        let klines = aggregate(
+            &db,
+            &Exchange::Binance,
+            &Market::Spot,
+            &"BTCUSDT".into(),
+            &KlineInterval::OneMinute,
+            &KlineInterval::FiveMinutes,
+            &secs_to_datetime(1721817600)?,
+            &secs_to_datetime(1721818200)?,
+        )
+        .await?;
+
+        assert_eq!(klines.len(), 1);
+        assert_eq!(klines[0].interval, KlineInterval::FiveMinutes);
+        assert_eq!(klines[0].open_time.timestamp(), 1721817600);
+        assert_eq!(klines[0].open_price, dec!(10000));
+        assert_eq!(klines[0].high_price, dec!(10009));
+        assert_eq!(klines[0].low_price, dec!(9995));
+        assert_eq!(klines[0].close_price, dec!(10005));
+        assert_eq!(klines[0].volume, dec!(50));
+        assert_eq!(klines[0].taker_buy_volume, dec!(20));
+
+        let result = upsert_many(&db, &klines).await?;
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].0, UpsertOutcome::Inserted);
+
+        // 不能由大周期聚合出小周期
+        assert!(aggregate(
+            &db,
+            &Exchange::Binance,
+            &Market::Spot,
+            &"BTCUSDT".into(),
+            &KlineInterval::OneHour,
+            &KlineInterval::OneMinute,
+            &secs_to_datetime(1721817600)?,
+            &secs_to_datetime(1721818200)?,
+        )
+        .await
+        .is_err());
+
+        Ok(())
+    }
+
     #[sqlx::test(migrator = "crate::MIGRATOR")]
     async fn test_time_range_klines_count(db: PgPool) -> Result<()> {
         create_kline(&db).await?;
@@ -0,0 +1,163 @@
+use anyhow::Result;
+use bon::Builder;
+use chrono::{DateTime, Utc};
+use comfy_quant_base::{Exchange, KlineInterval, Market, Symbol};
+use sqlx::{postgres::PgPool, FromRow};
+
+#[derive(Debug, FromRow)]
+pub struct KlineSyncState {
+    pub id: i32,                       // 主键ID
+    pub exchange: Exchange,            // 交易所
+    pub market: Market,                // 市场
+    pub symbol: Symbol,                // 交易对
+    pub interval: KlineInterval,       // 时间间隔
+    pub start_time: DateTime<Utc>,     // 已同步区间的开始时间
+    pub last_open_time: DateTime<Utc>, // 最后一根已同步K线的开盘时间
+    pub created_at: DateTime<Utc>,     // 创建时间
+    pub updated_at: DateTime<Utc>,     // 更新时间
+}
+
+impl KlineSyncState {
+    // 已同步的区间覆盖开始时间时，从最后一根已同步的K线继续
+    pub fn resume_from(&self, start_time: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+        (self.start_time <= *start_time && *start_time <= self.last_open_time)
+            .then_some(self.last_open_time)
+    }
+}
+
+#[derive(Builder)]
+#[builder(on(_, into))]
+pub struct SaveKlineSyncStateParams {
+    pub exchange: Exchange,            // 交易所
+    pub market: Market,                // 市场
+    pub symbol: Symbol,                // 交易对
+    pub interval: KlineInterval,       // 时间间隔
+    pub start_time: DateTime<Utc>,     // 本次同步的开始时间
+    pub last_open_time: DateTime<Utc>, // 最后一根已同步K线的开盘时间
+}
+
+pub async fn get(
+    db: &PgPool,
+    exchange: &Exchange,
+    market: &Market,
+    symbol: &Symbol,
+    interval: &KlineInterval,
+) -> Result<Option<KlineSyncState>> {
+    let state = sqlx::query_as!(
+        KlineSyncState,
+        r#"
+        SELECT * FROM kline_sync_states
+        WHERE exchange = $1 AND market = $2 AND symbol = $3 AND interval = $4
+        "#,
+        exchange.as_ref(),
+        market.as_ref(),
+        symbol.as_ref(),
+        interval.as_ref(),
+    )
+    .fetch_optional(db)
+    .await?;
+
+    Ok(state)
+}
+
+// 保存同步进度，本次同步与已同步区间相连时延长区间，否则以本次同步替换
+pub async fn save(db: &PgPool, data: SaveKlineSyncStateParams) -> Result<KlineSyncState> {
+    let state = sqlx::query_as!(
+        KlineSyncState,
+        r#"
+        INSERT INTO kline_sync_states (exchange, market, symbol, interval, start_time, last_open_time, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6, NOW(), NOW())
+        ON CONFLICT (exchange, market, symbol, interval)
+        DO UPDATE SET
+            start_time = CASE
+                WHEN EXCLUDED.start_time BETWEEN kline_sync_states.start_time AND kline_sync_states.last_open_time
+                THEN kline_sync_states.start_time
+                ELSE EXCLUDED.start_time
+            END,
+            last_open_time = CASE
+                WHEN EXCLUDED.start_time BETWEEN kline_sync_states.start_time AND kline_sync_states.last_open_time
+                THEN GREATEST(kline_sync_states.last_open_time, EXCLUDED.last_open_time)
+                ELSE EXCLUDED.last_open_time
+            END,
+            updated_at = NOW()
+        RETURNING *
+        "#,
+        data.exchange.as_ref(),
+        data.market.as_ref(),
+        data.symbol.as_ref(),
+        data.interval.as_ref(),
+        data.start_time,
+        data.last_open_time,
+    )
+    .fetch_one(db)
+    .await?;
+
+    Ok(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use comfy_quant_base::secs_to_datetime;
+
+    async fn save_state(
+        db: &PgPool,
+        start_time: i64,
+        last_open_time: i64,
+    ) -> Result<KlineSyncState> {
+        let data = SaveKlineSyncStateParams::builder()
+            .exchange(Exchange::Binance)
+            .market(Market::Spot)
+            .symbol("BTCUSDT")
+            .interval(KlineInterval::OneMinute)
+            .start_time(secs_to_datetime(start_time)?)
+            .last_open_time(secs_to_datetime(last_open_time)?)
+            .build();
+
+        save(db, data).await
+    }
+
+    #[sqlx::test(migrator = "crate::MIGRATOR")]
+    async fn test_kline_sync_state_save(db: PgPool) -> Result<()> {
+        let state = save_state(&db, 1721817600, 1721860800).await?;
+        assert_eq!(state.start_time.timestamp(), 1721817600);
+        assert_eq!(state.last_open_time.timestamp(), 1721860800);
+
+        // 从进度处续传，延长已同步区间
+        let state = save_state(&db, 1721860800, 1721904000).await?;
+        assert_eq!(state.start_time.timestamp(), 1721817600);
+        assert_eq!(state.last_open_time.timestamp(), 1721904000);
+
+        // 与已同步区间不相连，替换为新区间
+        let state = save_state(&db, 1722000000, 1722003600).await?;
+        assert_eq!(state.start_time.timestamp(), 1722000000);
+        assert_eq!(state.last_open_time.timestamp(), 1722003600);
+
+        let state = get(
+            &db,
+            &Exchange::Binance,
+            &Market::Spot,
+            &"BTCUSDT".into(),
+            &KlineInterval::OneMinute,
+        )
+        .await?
+        .unwrap();
+        assert_eq!(state.id, 1);
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrator = "crate::MIGRATOR")]
+    async fn test_kline_sync_state_resume_from(db: PgPool) -> Result<()> {
+        let state = save_state(&db, 1721817600, 1721860800).await?;
+
+        assert_eq!(
+            state.resume_from(&secs_to_datetime(1721817600)?),
+            Some(state.last_open_time)
+        );
+        assert_eq!(state.resume_from(&secs_to_datetime(1721700000)?), None);
+        assert_eq!(state.resume_from(&secs_to_datetime(1721900000)?), None);
+
+        Ok(())
+    }
+}
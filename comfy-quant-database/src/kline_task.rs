@@ -0,0 +1,217 @@
+use anyhow::Result;
+use bon::Builder;
+use chrono::{DateTime, Utc};
+use comfy_quant_base::{Exchange, KlineInterval, Market, Symbol};
+use sqlx::{postgres::PgPool, FromRow};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KlineTaskStatus {
+    Pending,  // 等待执行
+    Running,  // 执行中
+    Finished, // 已完成
+}
+
+impl From<&str> for KlineTaskStatus {
+    fn from(value: &str) -> Self {
+        match value {
+            "running" => KlineTaskStatus::Running,
+            "finished" => KlineTaskStatus::Finished,
+            _ => KlineTaskStatus::Pending,
+        }
+    }
+}
+
+impl From<String> for KlineTaskStatus {
+    fn from(value: String) -> Self {
+        value.as_str().into()
+    }
+}
+
+impl AsRef<str> for KlineTaskStatus {
+    fn as_ref(&self) -> &str {
+        match self {
+            KlineTaskStatus::Pending => "pending",
+            KlineTaskStatus::Running => "running",
+            KlineTaskStatus::Finished => "finished",
+        }
+    }
+}
+
+impl fmt::Display for KlineTaskStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_ref())
+    }
+}
+
+#[derive(Debug, FromRow)]
+pub struct KlineTask {
+    pub id: i32,                               // 主键ID
+    pub exchange: Exchange,                    // 交易所
+    pub market: Market,                        // 市场
+    pub symbol: Symbol,                        // 交易对
+    pub interval: KlineInterval,               // 时间间隔
+    pub start_time: DateTime<Utc>,             // 开始时间
+    pub end_time: DateTime<Utc>,               // 结束时间
+    pub last_open_time: Option<DateTime<Utc>>, // 最后一根已下载K线的开盘时间
+    pub status: KlineTaskStatus,               // 状态
+    pub created_at: DateTime<Utc>,             // 创建时间
+    pub updated_at: DateTime<Utc>,             // 更新时间
+}
+
+impl KlineTask {
+    // 续传的开始时间，已完成的任务从头开始
+    pub fn resume_from(&self) -> DateTime<Utc> {
+        match (&self.status, self.last_open_time) {
+            (KlineTaskStatus::Finished, _) | (_, None) => self.start_time,
+            (_, Some(last_open_time)) => last_open_time.max(self.start_time),
+        }
+    }
+}
+
+#[derive(Builder)]
+#[builder(on(_, into))]
+pub struct CreateKlineTaskParams {
+    pub exchange: Exchange,        // 交易所
+    pub market: Market,            // 市场
+    pub symbol: Symbol,            // 交易对
+    pub interval: KlineInterval,   // 时间间隔
+    pub start_time: DateTime<Utc>, // 开始时间
+    pub end_time: DateTime<Utc>,   // 结束时间
+}
+
+// 创建任务，如果任务已存在则返回已有任务
+pub async fn create_or_get(db: &PgPool, data: CreateKlineTaskParams) -> Result<KlineTask> {
+    let task = sqlx::query_as!(
+        KlineTask,
+        r#"
+        INSERT INTO kline_tasks (exchange, market, symbol, interval, start_time, end_time, status, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, NOW(), NOW())
+        ON CONFLICT (exchange, market, symbol, interval, start_time, end_time)
+        DO UPDATE SET
+            updated_at = NOW()
+        RETURNING *
+        "#,
+        data.exchange.as_ref(),
+        data.market.as_ref(),
+        data.symbol.as_ref(),
+        data.interval.as_ref(),
+        data.start_time,
+        data.end_time,
+        KlineTaskStatus::Pending.as_ref(),
+    )
+    .fetch_one(db)
+    .await?;
+
+    Ok(task)
+}
+
+// 更新任务状态
+pub async fn update_status(db: &PgPool, id: i32, status: &KlineTaskStatus) -> Result<KlineTask> {
+    let task = sqlx::query_as!(
+        KlineTask,
+        r#"
+        UPDATE kline_tasks SET status = $1, updated_at = NOW() WHERE id = $2
+        RETURNING *
+        "#,
+        status.as_ref(),
+        id,
+    )
+    .fetch_one(db)
+    .await?;
+
+    Ok(task)
+}
+
+// 更新任务进度
+pub async fn update_progress(
+    db: &PgPool,
+    id: i32,
+    last_open_time: &DateTime<Utc>,
+) -> Result<KlineTask> {
+    let task = sqlx::query_as!(
+        KlineTask,
+        r#"
+        UPDATE kline_tasks SET last_open_time = $1, updated_at = NOW() WHERE id = $2
+        RETURNING *
+        "#,
+        last_open_time,
+        id,
+    )
+    .fetch_one(db)
+    .await?;
+
+    Ok(task)
+}
+
+// 未完成的任务(等待执行或执行中)
+pub async fn list_unfinished(db: &PgPool) -> Result<Vec<KlineTask>> {
+    let tasks = sqlx::query_as!(
+        KlineTask,
+        r#"
+        SELECT * FROM kline_tasks WHERE status <> $1 ORDER BY created_at ASC
+        "#,
+        KlineTaskStatus::Finished.as_ref(),
+    )
+    .fetch_all(db)
+    .await?;
+
+    Ok(tasks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use comfy_quant_base::secs_to_datetime;
+
+    async fn create_task(db: &PgPool) -> Result<KlineTask> {
+        let data = CreateKlineTaskParams::builder()
+            .exchange(Exchange::Binance)
+            .market(Market::Spot)
+            .symbol("BTCUSDT")
+            .interval(KlineInterval::OneMinute)
+            .start_time(secs_to_datetime(1721817600)?)
+            .end_time(secs_to_datetime(1721904000)?)
+            .build();
+
+        create_or_get(db, data).await
+    }
+
+    #[sqlx::test(migrator = "crate::MIGRATOR")]
+    async fn test_kline_task_create_or_get(db: PgPool) -> Result<()> {
+        let task = create_task(&db).await?;
+
+        assert_eq!(task.id, 1);
+        assert_eq!(task.status, KlineTaskStatus::Pending);
+        assert_eq!(task.last_open_time, None);
+        assert_eq!(task.resume_from(), task.start_time);
+
+        let task2 = create_task(&db).await?;
+        assert_eq!(task2.id, task.id);
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrator = "crate::MIGRATOR")]
+    async fn test_kline_task_resume(db: PgPool) -> Result<()> {
+        let task = create_task(&db).await?;
+        let last_open_time = secs_to_datetime(1721860800)?;
+
+        update_status(&db, task.id, &KlineTaskStatus::Running).await?;
+        let task = update_progress(&db, task.id, &last_open_time).await?;
+
+        assert_eq!(task.status, KlineTaskStatus::Running);
+        assert_eq!(task.resume_from(), last_open_time);
+
+        let unfinished = list_unfinished(&db).await?;
+        assert_eq!(unfinished.len(), 1);
+
+        let task = update_status(&db, task.id, &KlineTaskStatus::Finished).await?;
+        assert_eq!(task.resume_from(), task.start_time);
+
+        let unfinished = list_unfinished(&db).await?;
+        assert!(unfinished.is_empty());
+
+        Ok(())
+    }
+}
@@ -1,7 +1,20 @@
+pub mod account_symbol_rule;
+pub mod api_key;
 pub mod kline;
+pub mod kline_sync_state;
+pub mod kline_task;
+pub mod optimization_run;
+pub mod order;
+pub mod retention;
 pub mod spot_pairs;
+pub mod strategy_capital_flow;
+pub mod strategy_journal;
+pub mod strategy_net_value;
 pub mod strategy_spot_position;
 pub mod strategy_spot_stats;
+pub mod symbol_alias;
+pub mod workflow_deployment;
+pub mod workflow_run;
 
 pub static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!("../migrations");
 
@@ -0,0 +1,196 @@
+use anyhow::Result;
+use bon::Builder;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use sqlx::{FromRow, PgPool};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OptimizationRunStatus {
+    Complete, // 回测完成
+    Failed,   // 回测失败
+}
+
+impl From<&str> for OptimizationRunStatus {
+    fn from(value: &str) -> Self {
+        match value {
+            "complete" => OptimizationRunStatus::Complete,
+            _ => OptimizationRunStatus::Failed,
+        }
+    }
+}
+
+impl From<String> for OptimizationRunStatus {
+    fn from(value: String) -> Self {
+        value.as_str().into()
+    }
+}
+
+impl AsRef<str> for OptimizationRunStatus {
+    fn as_ref(&self) -> &str {
+        match self {
+            OptimizationRunStatus::Complete => "complete",
+            OptimizationRunStatus::Failed => "failed",
+        }
+    }
+}
+
+impl fmt::Display for OptimizationRunStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_ref())
+    }
+}
+
+#[derive(Debug, FromRow, Clone)]
+pub struct OptimizationRun {
+    pub id: i32,                       // 主键ID
+    pub optimization_id: String,       // 参数扫描ID
+    pub params: String,                // 参数组合(JSON)
+    pub start_time: DateTime<Utc>,     // 回测开始时间
+    pub end_time: DateTime<Utc>,       // 回测结束时间
+    pub status: OptimizationRunStatus, // 状态
+    pub total_return: Option<Decimal>, // 总收益率
+    pub total_pnl: Option<Decimal>,    // 总盈亏
+    pub max_drawdown: Option<Decimal>, // 最大回撤比例
+    pub win_rate: Option<Decimal>,     // 胜率
+    pub total_trades: Option<i64>,     // 总交易次数
+    pub error: Option<String>,         // 回测失败的原因
+    pub created_at: DateTime<Utc>,     // 创建时间
+    pub updated_at: DateTime<Utc>,     // 更新时间
+}
+
+#[derive(Debug, Builder)]
+#[builder(on(String, into))]
+pub struct SaveOptimizationRunParams {
+    pub optimization_id: String,       // 参数扫描ID
+    pub params: String,                // 参数组合(JSON)
+    pub start_time: DateTime<Utc>,     // 回测开始时间
+    pub end_time: DateTime<Utc>,       // 回测结束时间
+    pub status: OptimizationRunStatus, // 状态
+    pub total_return: Option<Decimal>, // 总收益率
+    pub total_pnl: Option<Decimal>,    // 总盈亏
+    pub max_drawdown: Option<Decimal>, // 最大回撤比例
+    pub win_rate: Option<Decimal>,     // 胜率
+    pub total_trades: Option<i64>,     // 总交易次数
+    pub error: Option<String>,         // 回测失败的原因
+}
+
+// 保存参数组合的回测结果，同一扫描中相同参数和时间范围的记录被覆盖
+pub async fn save(db: &PgPool, data: SaveOptimizationRunParams) -> Result<OptimizationRun> {
+    let run = sqlx::query_as!(
+        OptimizationRun,
+        r#"
+        INSERT INTO optimization_runs (optimization_id, params, start_time, end_time, status, total_return, total_pnl, max_drawdown, win_rate, total_trades, error, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, NOW(), NOW())
+        ON CONFLICT (optimization_id, params, start_time, end_time)
+        DO UPDATE SET
+            status = EXCLUDED.status,
+            total_return = EXCLUDED.total_return,
+            total_pnl = EXCLUDED.total_pnl,
+            max_drawdown = EXCLUDED.max_drawdown,
+            win_rate = EXCLUDED.win_rate,
+            total_trades = EXCLUDED.total_trades,
+            error = EXCLUDED.error,
+            updated_at = NOW()
+        RETURNING *
+        "#,
+        data.optimization_id,
+        data.params,
+        data.start_time,
+        data.end_time,
+        data.status.as_ref(),
+        data.total_return,
+        data.total_pnl,
+        data.max_drawdown,
+        data.win_rate,
+        data.total_trades,
+        data.error,
+    )
+    .fetch_one(db)
+    .await?;
+
+    Ok(run)
+}
+
+// 参数扫描在该时间范围内的所有结果，按总收益率从高到低排列，失败的排在最后
+pub async fn list(
+    db: &PgPool,
+    optimization_id: &str,
+    start_time: &DateTime<Utc>,
+    end_time: &DateTime<Utc>,
+) -> Result<Vec<OptimizationRun>> {
+    let runs = sqlx::query_as!(
+        OptimizationRun,
+        r#"
+        SELECT * FROM optimization_runs
+        WHERE optimization_id = $1 AND start_time = $2 AND end_time = $3
+        ORDER BY total_return DESC NULLS LAST, id ASC
+        "#,
+        optimization_id,
+        start_time,
+        end_time,
+    )
+    .fetch_all(db)
+    .await?;
+
+    Ok(runs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use comfy_quant_base::secs_to_datetime;
+    use rust_decimal_macros::dec;
+
+    fn params(
+        params: &str,
+        status: OptimizationRunStatus,
+        total_return: Option<Decimal>,
+    ) -> Result<SaveOptimizationRunParams> {
+        let data = SaveOptimizationRunParams::builder()
+            .optimization_id("grid")
+            .params(params)
+            .start_time(secs_to_datetime(1721817600)?)
+            .end_time(secs_to_datetime(1721904000)?)
+            .status(status)
+            .maybe_total_return(total_return)
+            .maybe_max_drawdown(total_return.map(|_| dec!(0.1)))
+            .maybe_win_rate(total_return.map(|_| dec!(0.5)))
+            .build();
+
+        Ok(data)
+    }
+
+    #[sqlx::test(migrator = "crate::MIGRATOR")]
+    async fn test_optimization_run_save_and_list(db: PgPool) -> Result<()> {
+        use OptimizationRunStatus::*;
+
+        save(&db, params(r#"{"3:3":10}"#, Complete, Some(dec!(0.05)))?).await?;
+        save(&db, params(r#"{"3:3":20}"#, Failed, None)?).await?;
+        save(&db, params(r#"{"3:3":30}"#, Complete, Some(dec!(0.08)))?).await?;
+
+        // 重新运行相同参数时覆盖结果
+        let run = save(&db, params(r#"{"3:3":20}"#, Complete, Some(dec!(0.01)))?).await?;
+        assert_eq!(run.status, Complete);
+        assert_eq!(run.id, 2);
+
+        let runs = list(
+            &db,
+            "grid",
+            &secs_to_datetime(1721817600)?,
+            &secs_to_datetime(1721904000)?,
+        )
+        .await?;
+
+        assert_eq!(
+            runs.iter()
+                .map(|run| run.params.as_str())
+                .collect::<Vec<_>>(),
+            vec![r#"{"3:3":30}"#, r#"{"3:3":10}"#, r#"{"3:3":20}"#]
+        );
+        assert_eq!(runs[0].max_drawdown, Some(dec!(0.1)));
+        assert_eq!(runs[0].win_rate, Some(dec!(0.5)));
+
+        Ok(())
+    }
+}
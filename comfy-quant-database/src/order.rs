@@ -0,0 +1,429 @@
+use anyhow::Result;
+use bon::Builder;
+use chrono::{DateTime, Utc};
+use comfy_quant_base::{Exchange, Symbol};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use sqlx::{postgres::PgPool, FromRow};
+
+const MAX_PAGE_SIZE: u32 = 500; // 每页最多返回的订单数量
+
+#[derive(Debug, FromRow, Serialize)]
+pub struct Order {
+    pub id: i32,                         // 主键ID
+    pub workflow_id: String,             // 工作流ID
+    pub node_id: i16,                    // 策略节点ID
+    pub exchange: Exchange,              // 交易所
+    pub symbol: Symbol,                  // 交易对
+    pub order_id: String,                // 交易所订单ID
+    pub client_order_id: Option<String>, // 用户自己设置的ID
+    pub order_type: String,              // 订单类型
+    pub order_side: String,              // 订单方向
+    pub order_status: String,            // 订单状态
+    pub price: Decimal,                  // 订单价格
+    pub avg_price: Decimal,              // 平均成交价格
+    pub orig_qty: Decimal,               // 原始订单数量
+    pub executed_qty: Decimal,           // 累计成交数量
+    pub cumulative_quote_qty: Decimal,   // 累计成交金额
+    pub order_time: DateTime<Utc>,       // 下单时间
+    pub update_time: DateTime<Utc>,      // 交易所最后更新时间
+    pub created_at: DateTime<Utc>,       // 创建时间
+    pub updated_at: DateTime<Utc>,       // 更新时间
+}
+
+#[derive(Builder, Clone, Debug)]
+#[builder(on(_, into))]
+pub struct CreateOrderParams {
+    pub workflow_id: String,             // 工作流ID
+    pub node_id: i16,                    // 策略节点ID
+    pub exchange: Exchange,              // 交易所
+    pub symbol: Symbol,                  // 交易对
+    pub order_id: String,                // 交易所订单ID
+    pub client_order_id: Option<String>, // 用户自己设置的ID
+    pub order_type: String,              // 订单类型
+    pub order_side: String,              // 订单方向
+    pub order_status: String,            // 订单状态
+    pub price: Decimal,                  // 订单价格
+    pub avg_price: Decimal,              // 平均成交价格
+    pub orig_qty: Decimal,               // 原始订单数量
+    pub executed_qty: Decimal,           // 累计成交数量
+    pub cumulative_quote_qty: Decimal,   // 累计成交金额
+    pub order_time: DateTime<Utc>,       // 下单时间
+    pub update_time: DateTime<Utc>,      // 交易所最后更新时间
+}
+
+// 交易所推送的订单更新
+#[derive(Builder, Clone, Debug)]
+#[builder(on(_, into))]
+pub struct UpdateOrderParams {
+    pub workflow_id: String,        // 工作流ID
+    pub node_id: i16,               // 策略节点ID
+    pub exchange: Exchange,         // 交易所
+    pub order_id: String,           // 交易所订单ID
+    pub order_status: String,       // 订单状态
+    pub executed_qty: Decimal,      // 累计成交数量
+    pub last_quote_qty: Decimal,    // 最近一笔成交的金额
+    pub update_time: DateTime<Utc>, // 交易所更新时间
+}
+
+// 保存订单，同一订单重复保存时更新成交信息，忽略比已保存数据更旧的更新
+pub async fn create(db: &PgPool, data: CreateOrderParams) -> Result<Option<Order>> {
+    let order = sqlx::query_as!(
+        Order,
+        r#"
+        INSERT INTO orders (
+            workflow_id, node_id, exchange, symbol, order_id, client_order_id, order_type, order_side, order_status, price, avg_price, orig_qty, executed_qty, cumulative_quote_qty, order_time, update_time, created_at, updated_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, NOW(), NOW())
+        ON CONFLICT (workflow_id, node_id, exchange, order_id)
+        DO UPDATE SET
+            order_status = EXCLUDED.order_status,
+            avg_price = EXCLUDED.avg_price,
+            executed_qty = EXCLUDED.executed_qty,
+            cumulative_quote_qty = EXCLUDED.cumulative_quote_qty,
+            update_time = EXCLUDED.update_time,
+            updated_at = NOW()
+        WHERE orders.update_time <= EXCLUDED.update_time
+        RETURNING *
+        "#,
+        data.workflow_id,
+        data.node_id,
+        data.exchange.as_ref(),
+        data.symbol.as_ref(),
+        data.order_id,
+        data.client_order_id,
+        data.order_type,
+        data.order_side,
+        data.order_status,
+        data.price,
+        data.avg_price,
+        data.orig_qty,
+        data.executed_qty,
+        data.cumulative_quote_qty,
+        data.order_time,
+        data.update_time,
+    )
+    .fetch_optional(db)
+    .await?;
+
+    Ok(order)
+}
+
+// 按推送的成交更新订单，累计成交数量增加时才累加成交金额，重复推送不会重复计入
+pub async fn update(db: &PgPool, data: UpdateOrderParams) -> Result<Option<Order>> {
+    let order = sqlx::query_as!(
+        Order,
+        r#"
+        UPDATE orders SET
+            order_status = $5,
+            cumulative_quote_qty = CASE
+                WHEN $6 > executed_qty THEN cumulative_quote_qty + $7
+                ELSE cumulative_quote_qty
+            END,
+            avg_price = CASE
+                WHEN $6 > executed_qty THEN (cumulative_quote_qty + $7) / $6
+                ELSE avg_price
+            END,
+            executed_qty = GREATEST(executed_qty, $6),
+            update_time = $8,
+            updated_at = NOW()
+        WHERE
+            workflow_id = $1 AND
+            node_id = $2 AND
+            exchange = $3 AND
+            order_id = $4 AND
+            update_time <= $8
+        RETURNING *
+        "#,
+        data.workflow_id,
+        data.node_id,
+        data.exchange.as_ref(),
+        data.order_id,
+        data.order_status,
+        data.executed_qty,
+        data.last_quote_qty,
+        data.update_time,
+    )
+    .fetch_optional(db)
+    .await?;
+
+    Ok(order)
+}
+
+pub async fn get(
+    db: &PgPool,
+    workflow_id: &str,
+    node_id: i16,
+    exchange: &Exchange,
+    order_id: &str,
+) -> Result<Option<Order>> {
+    let order = sqlx::query_as!(
+        Order,
+        r#"
+        SELECT * FROM orders
+            WHERE
+                workflow_id = $1 AND
+                node_id = $2 AND
+                exchange = $3 AND
+                order_id = $4
+        "#,
+        workflow_id,
+        node_id,
+        exchange.as_ref(),
+        order_id,
+    )
+    .fetch_optional(db)
+    .await?;
+
+    Ok(order)
+}
+
+// 策略节点的订单，按下单时间升序
+pub async fn list(db: &PgPool, workflow_id: &str, node_id: i16) -> Result<Vec<Order>> {
+    let result = sqlx::query_as!(
+        Order,
+        r#"
+        SELECT * FROM orders
+            WHERE
+                workflow_id = $1 AND
+                node_id = $2
+            ORDER BY order_time ASC, id ASC
+        "#,
+        workflow_id,
+        node_id,
+    )
+    .fetch_all(db)
+    .await?;
+
+    Ok(result)
+}
+
+// 订单历史的查询条件，为空的条件不过滤
+#[derive(Builder, Debug, Clone)]
+#[builder(on(String, into), on(Symbol, into))]
+#[allow(clippy::duplicated_attributes)]
+pub struct OrderFilter {
+    pub workflow_id: String,                   // 工作流ID
+    pub node_id: Option<i16>,                  // 策略节点ID
+    pub symbol: Option<Symbol>,                // 交易对
+    pub order_side: Option<String>,            // 订单方向: BUY/SELL
+    pub start_datetime: Option<DateTime<Utc>>, // 下单时间范围的开始(包含)
+    pub end_datetime: Option<DateTime<Utc>>,   // 下单时间范围的结束(不包含)
+    #[builder(default = 1)]
+    pub page: u32,  // 页码，从1开始
+    #[builder(default = 50)]
+    pub page_size: u32, // 每页数量
+}
+
+impl OrderFilter {
+    fn limit(&self) -> i64 {
+        self.page_size.clamp(1, MAX_PAGE_SIZE) as i64
+    }
+
+    fn offset(&self) -> i64 {
+        (self.page.max(1) as i64 - 1) * self.limit()
+    }
+}
+
+// 分页的订单历史
+#[derive(Debug, Serialize)]
+pub struct OrderPage {
+    pub orders: Vec<Order>, // 当前页的订单，按下单时间倒序
+    pub total: i64,         // 符合条件的订单总数
+    pub page: u32,          // 页码
+    pub page_size: u32,     // 每页数量
+}
+
+// 按条件分页查询订单历史，最新的订单在前
+pub async fn list_orders(db: &PgPool, filter: &OrderFilter) -> Result<OrderPage> {
+    let symbol = filter.symbol.as_ref().map(|symbol| symbol.as_ref());
+
+    let orders = sqlx::query_as!(
+        Order,
+        r#"
+        SELECT * FROM orders
+            WHERE
+                workflow_id = $1 AND
+                ($2::SMALLINT IS NULL OR node_id = $2) AND
+                ($3::VARCHAR IS NULL OR symbol = $3) AND
+                ($4::VARCHAR IS NULL OR order_side = $4) AND
+                ($5::TIMESTAMPTZ IS NULL OR order_time >= $5) AND
+                ($6::TIMESTAMPTZ IS NULL OR order_time < $6)
+            ORDER BY order_time DESC, id DESC
+            LIMIT $7 OFFSET $8
+        "#,
+        filter.workflow_id,
+        filter.node_id,
+        symbol,
+        filter.order_side,
+        filter.start_datetime,
+        filter.end_datetime,
+        filter.limit(),
+        filter.offset(),
+    )
+    .fetch_all(db)
+    .await?;
+
+    let total = sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) AS "count!" FROM orders
+            WHERE
+                workflow_id = $1 AND
+                ($2::SMALLINT IS NULL OR node_id = $2) AND
+                ($3::VARCHAR IS NULL OR symbol = $3) AND
+                ($4::VARCHAR IS NULL OR order_side = $4) AND
+                ($5::TIMESTAMPTZ IS NULL OR order_time >= $5) AND
+                ($6::TIMESTAMPTZ IS NULL OR order_time < $6)
+        "#,
+        filter.workflow_id,
+        filter.node_id,
+        symbol,
+        filter.order_side,
+        filter.start_datetime,
+        filter.end_datetime,
+    )
+    .fetch_one(db)
+    .await?;
+
+    Ok(OrderPage {
+        orders,
+        total,
+        page: filter.page.max(1),
+        page_size: filter.limit() as u32,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use comfy_quant_base::millis_to_datetime;
+    use rust_decimal_macros::dec;
+
+    fn order(order_id: &str, order_status: &str, time: i64) -> Result<CreateOrderParams> {
+        Ok(CreateOrderParams::builder()
+            .workflow_id("jEnbRDqQu4UN6y7cgQgp6")
+            .node_id(1_i16)
+            .exchange(Exchange::Binance)
+            .symbol("BTCUSDT")
+            .order_id(order_id)
+            .order_type("LIMIT")
+            .order_side("BUY")
+            .order_status(order_status)
+            .price(dec!(100))
+            .avg_price(dec!(0))
+            .orig_qty(dec!(2))
+            .executed_qty(dec!(0))
+            .cumulative_quote_qty(dec!(0))
+            .order_time(millis_to_datetime(1000)?)
+            .update_time(millis_to_datetime(time)?)
+            .build())
+    }
+
+    #[sqlx::test(migrator = "crate::MIGRATOR")]
+    async fn test_order_create_and_list(db: PgPool) -> Result<()> {
+        create(&db, order("2", "NEW", 1000)?).await?;
+        create(&db, order("1", "NEW", 1000)?).await?;
+
+        // 重复保存时更新状态，旧的数据被忽略
+        let updated = create(&db, order("1", "CANCELED", 2000)?).await?;
+        assert_eq!(
+            updated.map(|order| order.order_status),
+            Some("CANCELED".into())
+        );
+        assert!(create(&db, order("1", "NEW", 1500)?).await?.is_none());
+
+        let orders = list(&db, "jEnbRDqQu4UN6y7cgQgp6", 1).await?;
+        assert_eq!(orders.len(), 2);
+        assert_eq!(orders[0].order_id, "2");
+        assert_eq!(orders[1].order_status, "CANCELED");
+        assert_eq!(orders[1].exchange, Exchange::Binance);
+        assert_eq!(orders[1].symbol, "BTCUSDT".into());
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrator = "crate::MIGRATOR")]
+    async fn test_order_update(db: PgPool) -> Result<()> {
+        create(&db, order("1", "NEW", 1000)?).await?;
+
+        let fill =
+            |status: &str, executed_qty, last_quote_qty, time| -> Result<UpdateOrderParams> {
+                Ok(UpdateOrderParams::builder()
+                    .workflow_id("jEnbRDqQu4UN6y7cgQgp6")
+                    .node_id(1_i16)
+                    .exchange(Exchange::Binance)
+                    .order_id("1")
+                    .order_status(status)
+                    .executed_qty(executed_qty)
+                    .last_quote_qty(last_quote_qty)
+                    .update_time(millis_to_datetime(time)?)
+                    .build())
+            };
+
+        update(&db, fill("PARTIALLY_FILLED", dec!(1), dec!(100), 2000)?).await?;
+        // 重复推送不重复计入成交金额
+        update(&db, fill("PARTIALLY_FILLED", dec!(1), dec!(100), 2000)?).await?;
+        update(&db, fill("FILLED", dec!(2), dec!(98), 3000)?).await?;
+
+        let order = get(&db, "jEnbRDqQu4UN6y7cgQgp6", 1, &Exchange::Binance, "1")
+            .await?
+            .unwrap();
+
+        assert_eq!(order.order_status, "FILLED");
+        assert_eq!(order.executed_qty, dec!(2));
+        assert_eq!(order.cumulative_quote_qty, dec!(198));
+        assert_eq!(order.avg_price, dec!(99));
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrator = "crate::MIGRATOR")]
+    async fn test_list_orders(db: PgPool) -> Result<()> {
+        for (order_id, symbol, side, time) in [
+            ("1", "BTCUSDT", "BUY", 1000),
+            ("2", "BTCUSDT", "SELL", 2000),
+            ("3", "ETHUSDT", "BUY", 3000),
+            ("4", "BTCUSDT", "BUY", 4000),
+        ] {
+            let mut data = order(order_id, "FILLED", time)?;
+            data.symbol = symbol.into();
+            data.order_side = side.into();
+            data.order_time = millis_to_datetime(time)?;
+
+            create(&db, data).await?;
+        }
+
+        let filter = OrderFilter::builder()
+            .workflow_id("jEnbRDqQu4UN6y7cgQgp6")
+            .symbol("BTCUSDT")
+            .page_size(2)
+            .build();
+
+        let page = list_orders(&db, &filter).await?;
+        assert_eq!(page.total, 3);
+        assert_eq!(
+            page.orders
+                .iter()
+                .map(|o| o.order_id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["4", "2"]
+        );
+
+        let page = list_orders(&db, &OrderFilter { page: 2, ..filter }).await?;
+        assert_eq!(page.orders.len(), 1);
+        assert_eq!(page.orders[0].order_id, "1");
+
+        let filter = OrderFilter::builder()
+            .workflow_id("jEnbRDqQu4UN6y7cgQgp6")
+            .order_side("BUY")
+            .start_datetime(millis_to_datetime(2000)?)
+            .end_datetime(millis_to_datetime(4000)?)
+            .build();
+
+        let page = list_orders(&db, &filter).await?;
+        assert_eq!(page.total, 1);
+        assert_eq!(page.orders[0].order_id, "3");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,285 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use flate2::{write::GzEncoder, Compression};
+use futures::StreamExt;
+use sqlx::PgPool;
+use std::{
+    fmt,
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+// 支持数据保留策略的表
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RetentionTable {
+    StrategySpotPositions, // 持仓快照
+    StrategyJournals,      // 策略交易日志
+    StrategyCapitalFlows,  // 策略资金流水
+    StrategyNetValues,     // 策略净值快照
+}
+
+impl RetentionTable {
+    pub const ALL: [RetentionTable; 4] = [
+        RetentionTable::StrategySpotPositions,
+        RetentionTable::StrategyJournals,
+        RetentionTable::StrategyCapitalFlows,
+        RetentionTable::StrategyNetValues,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RetentionTable::StrategySpotPositions => "strategy_spot_positions",
+            RetentionTable::StrategyJournals => "strategy_journals",
+            RetentionTable::StrategyCapitalFlows => "strategy_capital_flows",
+            RetentionTable::StrategyNetValues => "strategy_net_values",
+        }
+    }
+
+    // 判断数据是否过期的时间字段
+    fn time_column(&self) -> &'static str {
+        match self {
+            RetentionTable::StrategyCapitalFlows => "occurred_at",
+            RetentionTable::StrategyNetValues => "snapshot_at",
+            _ => "created_at",
+        }
+    }
+}
+
+impl FromStr for RetentionTable {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        RetentionTable::ALL
+            .into_iter()
+            .find(|table| table.as_str() == s)
+            .ok_or_else(|| anyhow::anyhow!("Unsupported retention table: {}", s))
+    }
+}
+
+impl fmt::Display for RetentionTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+// 数据保留策略，超过保留天数的数据归档后删除
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    pub table: RetentionTable, // 表
+    pub days: u32,             // 保留天数
+}
+
+impl RetentionPolicy {
+    pub fn new(table: RetentionTable, days: u32) -> Self {
+        RetentionPolicy { table, days }
+    }
+
+    // 早于该时间的数据视为过期
+    pub fn cutoff(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        now - Duration::days(self.days as i64)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveReport {
+    pub table: RetentionTable, // 表
+    pub cutoff: DateTime<Utc>, // 过期时间
+    pub rows: u64,             // 过期行数，非试运行时为已归档并删除的行数
+    pub file: Option<PathBuf>, // 归档文件，试运行或没有过期数据时为空
+    pub dry_run: bool,         // 是否试运行
+}
+
+impl fmt::Display for ArchiveReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let action = if self.dry_run {
+            "would archive"
+        } else {
+            "archived"
+        };
+
+        write!(
+            f,
+            "{}: {} {} rows older than {}",
+            self.table, action, self.rows, self.cutoff
+        )?;
+
+        if let Some(file) = &self.file {
+            write!(f, " to {}", file.display())?;
+        }
+
+        Ok(())
+    }
+}
+
+// 统计过期的行数
+pub async fn count_expired(
+    db: &PgPool,
+    table: RetentionTable,
+    cutoff: &DateTime<Utc>,
+) -> Result<i64> {
+    // 表名和字段名来自枚举，不存在注入风险
+    let sql = format!(
+        "SELECT COUNT(*) FROM {} WHERE {} < $1",
+        table.as_str(),
+        table.time_column()
+    );
+
+    let count = sqlx::query_scalar::<_, i64>(&sql)
+        .bind(cutoff)
+        .fetch_one(db)
+        .await?;
+
+    Ok(count)
+}
+
+// 将过期数据归档为gzip压缩的JSON Lines文件，写入成功后删除已归档的行
+pub async fn archive(
+    db: &PgPool,
+    policy: &RetentionPolicy,
+    dir: &Path,
+    now: DateTime<Utc>,
+    dry_run: bool,
+) -> Result<ArchiveReport> {
+    let table = policy.table;
+    let cutoff = policy.cutoff(now);
+
+    let mut report = ArchiveReport {
+        table,
+        cutoff,
+        rows: 0,
+        file: None,
+        dry_run,
+    };
+
+    if dry_run {
+        report.rows = count_expired(db, table, &cutoff).await? as u64;
+        return Ok(report);
+    }
+
+    fs::create_dir_all(dir)?;
+    let path = dir.join(format!("{}-{}.jsonl.gz", table, now.format("%Y%m%d%H%M%S")));
+
+    let sql = format!(
+        "SELECT id, to_jsonb(t)::text FROM {} t WHERE {} < $1 ORDER BY id ASC",
+        table.as_str(),
+        table.time_column()
+    );
+
+    let mut encoder = GzEncoder::new(BufWriter::new(File::create(&path)?), Compression::default());
+    let mut max_id = None;
+
+    {
+        let mut rows = sqlx::query_as::<_, (i32, String)>(&sql)
+            .bind(cutoff)
+            .fetch(db);
+
+        while let Some(row) = rows.next().await {
+            let (id, json) = row?;
+            writeln!(encoder, "{}", json)?;
+            report.rows += 1;
+            max_id = Some(id);
+        }
+    }
+
+    encoder.finish()?.flush()?;
+
+    let Some(max_id) = max_id else {
+        fs::remove_file(&path)?;
+        return Ok(report);
+    };
+
+    // 只删除已写入归档文件的行，归档期间新写入的过期数据留到下次处理
+    let sql = format!(
+        "DELETE FROM {} WHERE {} < $1 AND id <= $2",
+        table.as_str(),
+        table.time_column()
+    );
+
+    sqlx::query(&sql)
+        .bind(cutoff)
+        .bind(max_id)
+        .execute(db)
+        .await?;
+
+    tracing::info!(
+        monotonic_counter.retention_archived_rows = report.rows,
+        table = %table,
+        "Archived {} rows to {}",
+        report.rows,
+        path.display()
+    );
+
+    report.file = Some(path);
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategy_journal::{self, CreateJournalParams};
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    #[test]
+    fn test_retention_table_from_str() -> Result<()> {
+        for table in RetentionTable::ALL {
+            assert_eq!(table.as_str().parse::<RetentionTable>()?, table);
+        }
+
+        assert!("klines".parse::<RetentionTable>().is_err());
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrator = "crate::MIGRATOR")]
+    async fn test_archive(db: PgPool) -> Result<()> {
+        for content in ["old", "new"] {
+            let data = CreateJournalParams::builder()
+                .workflow_id("jEnbRDqQu4UN6y7cgQgp6")
+                .node_id(1_i16)
+                .content(content)
+                .build();
+            strategy_journal::create(&db, data).await?;
+        }
+
+        sqlx::query(
+            "UPDATE strategy_journals SET created_at = NOW() - INTERVAL '40 days' WHERE content = 'old'",
+        )
+        .execute(&db)
+        .await?;
+
+        let policy = RetentionPolicy::new(RetentionTable::StrategyJournals, 30);
+        let dir =
+            std::env::temp_dir().join(format!("comfy-quant-retention-{}", std::process::id()));
+        let now = Utc::now();
+
+        // 试运行不修改数据
+        let report = archive(&db, &policy, &dir, now, true).await?;
+        assert_eq!(report.rows, 1);
+        assert_eq!(report.file, None);
+        assert_eq!(count_expired(&db, policy.table, &report.cutoff).await?, 1);
+
+        let report = archive(&db, &policy, &dir, now, false).await?;
+        assert_eq!(report.rows, 1);
+        assert_eq!(count_expired(&db, policy.table, &report.cutoff).await?, 0);
+
+        let file = report
+            .file
+            .ok_or_else(|| anyhow::anyhow!("Missing archive file"))?;
+        let mut content = String::new();
+        GzDecoder::new(File::open(&file)?).read_to_string(&mut content)?;
+        assert_eq!(content.lines().count(), 1);
+        assert!(content.contains(r#""content": "old""#));
+
+        let journals = strategy_journal::list(&db, "jEnbRDqQu4UN6y7cgQgp6", 1).await?;
+        assert_eq!(journals.len(), 1);
+        assert_eq!(journals[0].content, "new");
+
+        fs::remove_dir_all(&dir)?;
+
+        Ok(())
+    }
+}
@@ -1,43 +1,79 @@
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use comfy_quant_base::{Exchange, Symbol};
+use comfy_quant_exchange::client::spot_client::base::SymbolInformation;
+use rust_decimal::Decimal;
 use sqlx::{FromRow, PgPool};
 
 #[derive(Debug, FromRow)]
 pub struct SpotPair {
-    pub id: i32,                    // 主键ID
-    pub exchange: Exchange,         // 交易所
-    pub symbol: Symbol,             // 交易对
-    pub base_asset: String,         // 基础资产
-    pub quote_asset: String,        // 计价资产
-    pub base_asset_precision: i32,  // 基础资产精度
-    pub quote_asset_precision: i32, // 计价资产精度
-    pub quote_precision: i32,       // 计价精度
-    pub status: String,             // 状态
-    pub created_at: DateTime<Utc>,  // 创建时间
-    pub updated_at: DateTime<Utc>,  // 更新时间
+    pub id: i32,                       // 主键ID
+    pub exchange: Exchange,            // 交易所
+    pub symbol: Symbol,                // 交易对
+    pub base_asset: String,            // 基础资产
+    pub quote_asset: String,           // 计价资产
+    pub base_asset_precision: i32,     // 基础资产精度
+    pub quote_asset_precision: i32,    // 计价资产精度
+    pub quote_precision: i32,          // 计价精度
+    pub status: String,                // 状态
+    pub created_at: DateTime<Utc>,     // 创建时间
+    pub updated_at: DateTime<Utc>,     // 更新时间
+    pub tick_size: Option<Decimal>,    // 价格步长
+    pub step_size: Option<Decimal>,    // 数量步长
+    pub min_notional: Option<Decimal>, // 最小名义价值
 }
 
 pub struct CreateSpotPairParams {
-    pub exchange: Exchange,         // 交易所
-    pub symbol: Symbol,             // 交易对
-    pub base_asset: String,         // 基础资产
-    pub quote_asset: String,        // 计价资产
-    pub base_asset_precision: i32,  // 基础资产精度
-    pub quote_asset_precision: i32, // 计价资产精度
-    pub quote_precision: i32,       // 计价精度
-    pub status: String,             // 状态
+    pub exchange: Exchange,            // 交易所
+    pub symbol: Symbol,                // 交易对
+    pub base_asset: String,            // 基础资产
+    pub quote_asset: String,           // 计价资产
+    pub base_asset_precision: i32,     // 基础资产精度
+    pub quote_asset_precision: i32,    // 计价资产精度
+    pub quote_precision: i32,          // 计价精度
+    pub status: String,                // 状态
+    pub tick_size: Option<Decimal>,    // 价格步长
+    pub step_size: Option<Decimal>,    // 数量步长
+    pub min_notional: Option<Decimal>, // 最小名义价值
+}
+
+impl CreateSpotPairParams {
+    // 从交易所的交易对信息生成缓存数据
+    pub fn from_symbol_info(exchange: Exchange, symbol_info: &SymbolInformation) -> Self {
+        CreateSpotPairParams {
+            exchange,
+            symbol: symbol_info.symbol.clone(),
+            base_asset: symbol_info.base_asset.clone(),
+            quote_asset: symbol_info.quote_asset.clone(),
+            base_asset_precision: symbol_info.base_asset_precision as i32,
+            quote_asset_precision: symbol_info.quote_asset_precision as i32,
+            quote_precision: symbol_info.quote_asset_precision as i32,
+            status: symbol_info
+                .status
+                .clone()
+                .unwrap_or_else(|| "TRADING".to_string()),
+            tick_size: symbol_info.tick_size,
+            step_size: symbol_info.step_size,
+            min_notional: symbol_info.min_notional,
+        }
+    }
 }
 
 pub async fn create_or_update(db: &PgPool, data: CreateSpotPairParams) -> Result<SpotPair> {
     let row = sqlx::query_as!(
         SpotPair,
         r#"
-        INSERT INTO spot_pairs (exchange, symbol, base_asset, quote_asset, base_asset_precision, quote_asset_precision, quote_precision, status, created_at, updated_at)
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, NOW(), NOW())
+        INSERT INTO spot_pairs (exchange, symbol, base_asset, quote_asset, base_asset_precision, quote_asset_precision, quote_precision, status, tick_size, step_size, min_notional, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, NOW(), NOW())
         ON CONFLICT (exchange, symbol)
         DO UPDATE SET
+            base_asset_precision = EXCLUDED.base_asset_precision,
+            quote_asset_precision = EXCLUDED.quote_asset_precision,
+            quote_precision = EXCLUDED.quote_precision,
             status = EXCLUDED.status,
+            tick_size = EXCLUDED.tick_size,
+            step_size = EXCLUDED.step_size,
+            min_notional = EXCLUDED.min_notional,
             updated_at = NOW()
         RETURNING *
         "#,
@@ -49,6 +85,9 @@ pub async fn create_or_update(db: &PgPool, data: CreateSpotPairParams) -> Result
         data.quote_asset_precision,
         data.quote_precision,
         data.status,
+        data.tick_size,
+        data.step_size,
+        data.min_notional,
     )
     .fetch_one(db)
     .await?;
@@ -74,6 +113,7 @@ pub async fn get(db: &PgPool, exchange: &Exchange, symbol: &Symbol) -> Result<Sp
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rust_decimal_macros::dec;
 
     #[sqlx::test(migrator = "crate::MIGRATOR")]
     async fn test_spot_pairs_create_or_update_should_work(db: PgPool) -> Result<()> {
@@ -86,6 +126,9 @@ mod tests {
             quote_asset_precision: 8,
             quote_precision: 8,
             status: "TRADING".into(),
+            tick_size: Some(dec!(0.01)),
+            step_size: Some(dec!(0.00001)),
+            min_notional: Some(dec!(5)),
         };
 
         let spot_pair = create_or_update(&db, data).await?;
@@ -99,6 +142,8 @@ mod tests {
         assert_eq!(spot_pair.quote_asset_precision, 8);
         assert_eq!(spot_pair.quote_precision, 8);
         assert_eq!(spot_pair.status, "TRADING".to_string());
+        assert_eq!(spot_pair.tick_size, Some(dec!(0.01)));
+        assert_eq!(spot_pair.min_notional, Some(dec!(5)));
 
         let data = CreateSpotPairParams {
             exchange: Exchange::Binance,
@@ -109,11 +154,15 @@ mod tests {
             quote_asset_precision: 8,
             quote_precision: 8,
             status: "STOP".into(),
+            tick_size: Some(dec!(0.1)),
+            step_size: Some(dec!(0.00001)),
+            min_notional: Some(dec!(5)),
         };
 
         let spot_pair = create_or_update(&db, data).await?;
 
         assert_eq!(spot_pair.status, "STOP".to_string());
+        assert_eq!(spot_pair.tick_size, Some(dec!(0.1)));
 
         Ok(())
     }
@@ -129,6 +178,9 @@ mod tests {
             quote_asset_precision: 8,
             quote_precision: 8,
             status: "TRADING".into(),
+            tick_size: Some(dec!(0.01)),
+            step_size: Some(dec!(0.00001)),
+            min_notional: Some(dec!(5)),
         };
 
         create_or_update(&db, data).await?;
@@ -0,0 +1,139 @@
+use anyhow::Result;
+use bon::Builder;
+use chrono::{DateTime, Utc};
+use comfy_quant_base::{CapitalFlowKind, Exchange, Symbol};
+use rust_decimal::Decimal;
+use sqlx::{postgres::PgPool, FromRow};
+
+#[derive(Debug, FromRow)]
+pub struct StrategyCapitalFlow {
+    pub id: i32,                    // 主键ID
+    pub workflow_id: String,        // 工作流ID
+    pub node_id: i16,               // 策略节点ID
+    pub node_name: String,          // 策略节点名称
+    pub exchange: Exchange,         // 交易所
+    pub symbol: Symbol,             // 交易对
+    pub asset: String,              // 计价资产
+    pub kind: CapitalFlowKind,      // 变动类型
+    pub amount: Decimal,            // 变动金额，流入为正，流出为负
+    pub occurred_at: DateTime<Utc>, // 发生时间
+    pub created_at: DateTime<Utc>,  // 创建时间
+}
+
+#[derive(Builder, Clone)]
+#[builder(on(_, into))]
+pub struct CreateCapitalFlowParams {
+    pub workflow_id: String,        // 工作流ID
+    pub node_id: i16,               // 策略节点ID
+    pub node_name: String,          // 策略节点名称
+    pub exchange: Exchange,         // 交易所
+    pub symbol: Symbol,             // 交易对
+    pub asset: String,              // 计价资产
+    pub kind: CapitalFlowKind,      // 变动类型
+    pub amount: Decimal,            // 变动金额
+    pub occurred_at: DateTime<Utc>, // 发生时间
+}
+
+pub async fn create(db: &PgPool, data: CreateCapitalFlowParams) -> Result<StrategyCapitalFlow> {
+    let flow = sqlx::query_as!(
+        StrategyCapitalFlow,
+        r#"
+        INSERT INTO strategy_capital_flows (
+            workflow_id, node_id, node_name, exchange, symbol, asset, kind, amount, occurred_at, created_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, NOW())
+        RETURNING *
+        "#,
+        data.workflow_id,
+        data.node_id,
+        data.node_name,
+        data.exchange.as_ref(),
+        data.symbol.as_ref(),
+        data.asset,
+        data.kind.as_ref(),
+        data.amount,
+        data.occurred_at,
+    )
+    .fetch_one(db)
+    .await?;
+
+    Ok(flow)
+}
+
+// 策略节点某交易对的资金流水，按发生时间升序
+pub async fn list(
+    db: &PgPool,
+    workflow_id: &str,
+    node_id: i16,
+    exchange: &Exchange,
+    symbol: &Symbol,
+) -> Result<Vec<StrategyCapitalFlow>> {
+    let result = sqlx::query_as!(
+        StrategyCapitalFlow,
+        r#"
+        SELECT * FROM strategy_capital_flows
+            WHERE
+                workflow_id = $1 AND
+                node_id = $2 AND
+                exchange = $3 AND
+                symbol = $4
+            ORDER BY occurred_at ASC, id ASC
+        "#,
+        workflow_id,
+        node_id,
+        exchange.as_ref(),
+        symbol.as_ref(),
+    )
+    .fetch_all(db)
+    .await?;
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use comfy_quant_base::secs_to_datetime;
+    use rust_decimal_macros::dec;
+
+    #[sqlx::test(migrator = "crate::MIGRATOR")]
+    async fn test_strategy_capital_flow_create_and_list(db: PgPool) -> Result<()> {
+        for (kind, amount, secs) in [
+            (CapitalFlowKind::Deposit, dec!(500), 200),
+            (CapitalFlowKind::Allocation, dec!(1000), 100),
+            (CapitalFlowKind::Withdrawal, dec!(-300), 300),
+        ] {
+            let data = CreateCapitalFlowParams::builder()
+                .workflow_id("jEnbRDqQu4UN6y7cgQgp6")
+                .node_id(1_i16)
+                .node_name("SpotGrid")
+                .exchange(Exchange::Binance)
+                .symbol("BTCUSDT")
+                .asset("USDT")
+                .kind(kind)
+                .amount(amount)
+                .occurred_at(secs_to_datetime(secs)?)
+                .build();
+
+            create(&db, data).await?;
+        }
+
+        let flows = list(
+            &db,
+            "jEnbRDqQu4UN6y7cgQgp6",
+            1,
+            &Exchange::Binance,
+            &"BTCUSDT".into(),
+        )
+        .await?;
+
+        assert_eq!(flows.len(), 3);
+        assert_eq!(flows[0].kind, CapitalFlowKind::Allocation);
+        assert_eq!(flows[0].amount, dec!(1000));
+        assert_eq!(flows[1].kind, CapitalFlowKind::Deposit);
+        assert_eq!(flows[2].kind, CapitalFlowKind::Withdrawal);
+        assert_eq!(flows[2].amount, dec!(-300));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,252 @@
+use anyhow::Result;
+use bon::Builder;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{postgres::PgPool, FromRow};
+
+#[derive(Debug, FromRow, Serialize)]
+pub struct StrategyJournal {
+    pub id: i32,                           // 主键ID
+    pub workflow_id: String,               // 工作流ID
+    pub node_id: i16,                      // 策略节点ID
+    pub order_id: Option<String>,          // 关联的订单ID
+    pub start_time: Option<DateTime<Utc>>, // 关联时间段的开始时间
+    pub end_time: Option<DateTime<Utc>>,   // 关联时间段的结束时间
+    pub content: String,                   // 日志内容
+    pub tags: Vec<String>,                 // 标签
+    pub created_at: DateTime<Utc>,         // 创建时间
+    pub updated_at: DateTime<Utc>,         // 更新时间
+}
+
+#[derive(Builder)]
+#[builder(on(_, into))]
+pub struct CreateJournalParams {
+    pub workflow_id: String,               // 工作流ID
+    pub node_id: i16,                      // 策略节点ID
+    pub order_id: Option<String>,          // 关联的订单ID
+    pub start_time: Option<DateTime<Utc>>, // 关联时间段的开始时间
+    pub end_time: Option<DateTime<Utc>>,   // 关联时间段的结束时间
+    pub content: String,                   // 日志内容
+    #[builder(default)]
+    pub tags: Vec<String>, // 标签
+}
+
+pub async fn create(db: &PgPool, data: CreateJournalParams) -> Result<StrategyJournal> {
+    if let (Some(start_time), Some(end_time)) = (&data.start_time, &data.end_time) {
+        anyhow::ensure!(
+            start_time <= end_time,
+            "Journal start_time must be earlier than end_time"
+        );
+    }
+
+    let journal = sqlx::query_as!(
+        StrategyJournal,
+        r#"
+        INSERT INTO strategy_journals (
+            workflow_id, node_id, order_id, start_time, end_time, content, tags, created_at, updated_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, NOW(), NOW())
+        RETURNING *
+        "#,
+        data.workflow_id,
+        data.node_id,
+        data.order_id,
+        data.start_time,
+        data.end_time,
+        data.content,
+        &data.tags,
+    )
+    .fetch_one(db)
+    .await?;
+
+    Ok(journal)
+}
+
+// 更新日志内容和标签，日志不存在时返回 None
+pub async fn update(
+    db: &PgPool,
+    id: i32,
+    content: &str,
+    tags: &[String],
+) -> Result<Option<StrategyJournal>> {
+    let journal = sqlx::query_as!(
+        StrategyJournal,
+        r#"
+        UPDATE strategy_journals SET content = $1, tags = $2, updated_at = NOW() WHERE id = $3
+        RETURNING *
+        "#,
+        content,
+        tags,
+        id,
+    )
+    .fetch_optional(db)
+    .await?;
+
+    Ok(journal)
+}
+
+// 删除日志，日志不存在时返回false
+pub async fn delete(db: &PgPool, id: i32) -> Result<bool> {
+    let result = sqlx::query!(r#"DELETE FROM strategy_journals WHERE id = $1"#, id)
+        .execute(db)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+// 工作流的所有日志
+pub async fn list_by_workflow(db: &PgPool, workflow_id: &str) -> Result<Vec<StrategyJournal>> {
+    let journals = sqlx::query_as!(
+        StrategyJournal,
+        r#"
+        SELECT * FROM strategy_journals WHERE workflow_id = $1 ORDER BY created_at ASC
+        "#,
+        workflow_id,
+    )
+    .fetch_all(db)
+    .await?;
+
+    Ok(journals)
+}
+
+// 策略节点的所有日志
+pub async fn list(db: &PgPool, workflow_id: &str, node_id: i16) -> Result<Vec<StrategyJournal>> {
+    let journals = sqlx::query_as!(
+        StrategyJournal,
+        r#"
+        SELECT * FROM strategy_journals
+            WHERE workflow_id = $1 AND node_id = $2
+            ORDER BY created_at ASC
+        "#,
+        workflow_id,
+        node_id,
+    )
+    .fetch_all(db)
+    .await?;
+
+    Ok(journals)
+}
+
+// 关联到某个订单的日志
+pub async fn list_by_order(
+    db: &PgPool,
+    workflow_id: &str,
+    order_id: &str,
+) -> Result<Vec<StrategyJournal>> {
+    let journals = sqlx::query_as!(
+        StrategyJournal,
+        r#"
+        SELECT * FROM strategy_journals
+            WHERE workflow_id = $1 AND order_id = $2
+            ORDER BY created_at ASC
+        "#,
+        workflow_id,
+        order_id,
+    )
+    .fetch_all(db)
+    .await?;
+
+    Ok(journals)
+}
+
+// 与时间段有交集的日志
+pub async fn list_by_time_range(
+    db: &PgPool,
+    workflow_id: &str,
+    start_datetime: &DateTime<Utc>,
+    end_datetime: &DateTime<Utc>,
+) -> Result<Vec<StrategyJournal>> {
+    let journals = sqlx::query_as!(
+        StrategyJournal,
+        r#"
+        SELECT * FROM strategy_journals
+            WHERE
+                workflow_id = $1 AND
+                start_time <= $3 AND
+                COALESCE(end_time, start_time) >= $2
+            ORDER BY start_time ASC
+        "#,
+        workflow_id,
+        start_datetime,
+        end_datetime,
+    )
+    .fetch_all(db)
+    .await?;
+
+    Ok(journals)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use comfy_quant_base::secs_to_datetime;
+
+    #[sqlx::test(migrator = "crate::MIGRATOR")]
+    async fn test_strategy_journal_create_and_update(db: PgPool) -> Result<()> {
+        let data = CreateJournalParams::builder()
+            .workflow_id("jEnbRDqQu4UN6y7cgQgp6")
+            .node_id(1_i16)
+            .order_id("12345")
+            .content("手动平仓，避开CPI数据发布")
+            .tags(vec!["intervene".to_string(), "macro".to_string()])
+            .build();
+
+        let journal = create(&db, data).await?;
+
+        assert_eq!(journal.id, 1);
+        assert_eq!(journal.order_id, Some("12345".to_string()));
+        assert_eq!(journal.tags, vec!["intervene", "macro"]);
+
+        let journal = update(&db, journal.id, "手动平仓", &["intervene".to_string()])
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Journal not found"))?;
+        assert_eq!(journal.content, "手动平仓");
+        assert_eq!(journal.tags, vec!["intervene"]);
+        assert!(update(&db, 999, "手动平仓", &[]).await?.is_none());
+
+        let journals = list_by_order(&db, "jEnbRDqQu4UN6y7cgQgp6", "12345").await?;
+        assert_eq!(journals.len(), 1);
+        let journals = list_by_workflow(&db, "jEnbRDqQu4UN6y7cgQgp6").await?;
+        assert_eq!(journals.len(), 1);
+
+        assert!(delete(&db, journal.id).await?);
+        assert!(!delete(&db, journal.id).await?);
+        let journals = list(&db, "jEnbRDqQu4UN6y7cgQgp6", 1).await?;
+        assert!(journals.is_empty());
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrator = "crate::MIGRATOR")]
+    async fn test_strategy_journal_list_by_time_range(db: PgPool) -> Result<()> {
+        let data = CreateJournalParams::builder()
+            .workflow_id("jEnbRDqQu4UN6y7cgQgp6")
+            .node_id(1_i16)
+            .start_time(secs_to_datetime(1000)?)
+            .end_time(secs_to_datetime(2000)?)
+            .content("行情剧烈波动")
+            .build();
+
+        create(&db, data).await?;
+
+        let journals = list_by_time_range(
+            &db,
+            "jEnbRDqQu4UN6y7cgQgp6",
+            &secs_to_datetime(1500)?,
+            &secs_to_datetime(3000)?,
+        )
+        .await?;
+        assert_eq!(journals.len(), 1);
+
+        let journals = list_by_time_range(
+            &db,
+            "jEnbRDqQu4UN6y7cgQgp6",
+            &secs_to_datetime(2500)?,
+            &secs_to_datetime(3000)?,
+        )
+        .await?;
+        assert!(journals.is_empty());
+
+        Ok(())
+    }
+}
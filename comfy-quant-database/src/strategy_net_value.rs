@@ -0,0 +1,158 @@
+use anyhow::Result;
+use bon::Builder;
+use chrono::{DateTime, Utc};
+use comfy_quant_base::{Exchange, Symbol};
+use rust_decimal::Decimal;
+use sqlx::{postgres::PgPool, FromRow};
+
+#[derive(Debug, FromRow)]
+pub struct StrategyNetValue {
+    pub id: i32,                    // 主键ID
+    pub workflow_id: String,        // 工作流ID
+    pub node_id: i16,               // 策略节点ID
+    pub node_name: String,          // 策略节点名称
+    pub exchange: Exchange,         // 交易所
+    pub symbol: Symbol,             // 交易对
+    pub quote_asset: String,        // 计价资产
+    pub value: Decimal,             // 持仓市值(计价资产)
+    pub net_value: Decimal,         // 净值
+    pub drawdown: Decimal,          // 相对历史最高市值的回撤
+    pub snapshot_at: DateTime<Utc>, // 快照时间
+    pub created_at: DateTime<Utc>,  // 创建时间
+}
+
+#[derive(Builder, Clone)]
+#[builder(on(_, into))]
+pub struct SaveNetValueParams {
+    pub workflow_id: String,        // 工作流ID
+    pub node_id: i16,               // 策略节点ID
+    pub node_name: String,          // 策略节点名称
+    pub exchange: Exchange,         // 交易所
+    pub symbol: Symbol,             // 交易对
+    pub quote_asset: String,        // 计价资产
+    pub value: Decimal,             // 持仓市值(计价资产)
+    pub net_value: Decimal,         // 净值
+    pub drawdown: Decimal,          // 相对历史最高市值的回撤
+    pub snapshot_at: DateTime<Utc>, // 快照时间
+}
+
+// 保存净值快照，同一时间的快照被覆盖
+pub async fn save(db: &PgPool, data: SaveNetValueParams) -> Result<StrategyNetValue> {
+    let net_value = sqlx::query_as!(
+        StrategyNetValue,
+        r#"
+        INSERT INTO strategy_net_values (
+            workflow_id, node_id, node_name, exchange, symbol, quote_asset, value, net_value, drawdown, snapshot_at, created_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, NOW())
+        ON CONFLICT (workflow_id, node_id, exchange, symbol, snapshot_at)
+        DO UPDATE SET
+            value = EXCLUDED.value,
+            net_value = EXCLUDED.net_value,
+            drawdown = EXCLUDED.drawdown
+        RETURNING *
+        "#,
+        data.workflow_id,
+        data.node_id,
+        data.node_name,
+        data.exchange.as_ref(),
+        data.symbol.as_ref(),
+        data.quote_asset,
+        data.value,
+        data.net_value,
+        data.drawdown,
+        data.snapshot_at,
+    )
+    .fetch_one(db)
+    .await?;
+
+    Ok(net_value)
+}
+
+// 策略节点某交易对在时间范围内的净值快照，按快照时间升序
+pub async fn list(
+    db: &PgPool,
+    workflow_id: &str,
+    node_id: i16,
+    exchange: &Exchange,
+    symbol: &Symbol,
+    start_datetime: &DateTime<Utc>,
+    end_datetime: &DateTime<Utc>,
+) -> Result<Vec<StrategyNetValue>> {
+    let result = sqlx::query_as!(
+        StrategyNetValue,
+        r#"
+        SELECT * FROM strategy_net_values
+            WHERE
+                workflow_id = $1 AND
+                node_id = $2 AND
+                exchange = $3 AND
+                symbol = $4 AND
+                snapshot_at BETWEEN $5 AND $6
+            ORDER BY snapshot_at ASC
+        "#,
+        workflow_id,
+        node_id,
+        exchange.as_ref(),
+        symbol.as_ref(),
+        start_datetime,
+        end_datetime,
+    )
+    .fetch_all(db)
+    .await?;
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use comfy_quant_base::secs_to_datetime;
+    use rust_decimal_macros::dec;
+
+    fn params(secs: i64, value: Decimal) -> Result<SaveNetValueParams> {
+        let data = SaveNetValueParams::builder()
+            .workflow_id("jEnbRDqQu4UN6y7cgQgp6")
+            .node_id(1_i16)
+            .node_name("SpotGrid")
+            .exchange(Exchange::Binance)
+            .symbol("BTCUSDT")
+            .quote_asset("USDT")
+            .value(value)
+            .net_value(value / dec!(1000))
+            .drawdown(dec!(0))
+            .snapshot_at(secs_to_datetime(secs)?)
+            .build();
+
+        Ok(data)
+    }
+
+    #[sqlx::test(migrator = "crate::MIGRATOR")]
+    async fn test_strategy_net_value_save_and_list(db: PgPool) -> Result<()> {
+        save(&db, params(120, dec!(1020))?).await?;
+        save(&db, params(60, dec!(1000))?).await?;
+        save(&db, params(180, dec!(990))?).await?;
+
+        // 同一时间的快照覆盖
+        let net_value = save(&db, params(120, dec!(1030))?).await?;
+        assert_eq!(net_value.id, 1);
+        assert_eq!(net_value.net_value, dec!(1.03));
+
+        let net_values = list(
+            &db,
+            "jEnbRDqQu4UN6y7cgQgp6",
+            1,
+            &Exchange::Binance,
+            &"BTCUSDT".into(),
+            &secs_to_datetime(0)?,
+            &secs_to_datetime(150)?,
+        )
+        .await?;
+
+        assert_eq!(net_values.len(), 2);
+        assert_eq!(net_values[0].value, dec!(1000));
+        assert_eq!(net_values[1].value, dec!(1030));
+
+        Ok(())
+    }
+}
@@ -21,7 +21,7 @@ pub struct StrategySpotPosition {
     pub created_at: DateTime<Utc>,    // 创建时间
 }
 
-#[derive(Builder)]
+#[derive(Builder, Clone)]
 #[builder(on(_, into))]
 pub struct CreateSpotPositionParams {
     pub workflow_id: String,          // 工作流ID
@@ -97,6 +97,26 @@ pub async fn list(
     Ok(result)
 }
 
+// 多个工作流中每个策略节点、交易对的最新持仓
+pub async fn list_latest(
+    db: &PgPool,
+    workflow_ids: &[String],
+) -> Result<Vec<StrategySpotPosition>> {
+    let result = sqlx::query_as!(
+        StrategySpotPosition,
+        r#"
+        SELECT DISTINCT ON (workflow_id, node_id, exchange, symbol) * FROM strategy_spot_positions
+            WHERE workflow_id = ANY($1)
+            ORDER BY workflow_id, node_id, exchange, symbol, created_at DESC, id DESC
+        "#,
+        workflow_ids,
+    )
+    .fetch_all(db)
+    .await?;
+
+    Ok(result)
+}
+
 #[cfg(test)]
 mod tests {
     use comfy_quant_base::secs_to_datetime;
@@ -180,4 +200,31 @@ mod tests {
 
         Ok(())
     }
+
+    #[sqlx::test(migrator = "crate::MIGRATOR")]
+    async fn test_strategy_spot_position_list_latest(db: PgPool) -> Result<()> {
+        for base_asset_balance in [1, 2] {
+            let data = CreateSpotPositionParams::builder()
+                .workflow_id("jEnbRDqQu4UN6y7cgQgp6")
+                .node_id(1_i16)
+                .node_name("SpotGrid")
+                .exchange(Exchange::Binance)
+                .symbol("BTCUSDT")
+                .base_asset("BTC")
+                .quote_asset("USDT")
+                .base_asset_balance(Decimal::from(base_asset_balance))
+                .quote_asset_balance(Decimal::from(1000))
+                .realized_pnl(Decimal::from(0))
+                .build();
+
+            create(&db, data).await?;
+        }
+
+        let positions = list_latest(&db, &["jEnbRDqQu4UN6y7cgQgp6".to_string()]).await?;
+
+        assert_eq!(positions.len(), 1);
+        assert_eq!(positions[0].base_asset_balance, dec!(2));
+
+        Ok(())
+    }
 }
@@ -4,9 +4,10 @@ use bon::Builder;
 use chrono::{DateTime, Utc};
 use comfy_quant_base::{Exchange, Symbol};
 use rust_decimal::Decimal;
+use serde::Serialize;
 use sqlx::{postgres::PgPool, FromRow};
 
-#[derive(Debug, FromRow)]
+#[derive(Debug, FromRow, Serialize)]
 pub struct StrategySpotStats {
     pub id: i32,                         // 主键ID
     pub workflow_id: String,             // 工作流ID
@@ -37,7 +38,7 @@ pub struct StrategySpotStats {
     pub updated_at: DateTime<Utc>,       // 更新时间
 }
 
-#[derive(Builder)]
+#[derive(Builder, Clone)]
 #[builder(on(_, into))]
 pub struct CreateSpotStatsParams {
     pub workflow_id: String,             // 工作流ID
@@ -253,6 +254,23 @@ pub async fn create_or_update(
     Ok(strategy_spot_stats)
 }
 
+// 工作流中所有策略节点的统计
+pub async fn list_by_workflow(db: &PgPool, workflow_id: &str) -> Result<Vec<StrategySpotStats>> {
+    let result = sqlx::query_as!(
+        StrategySpotStats,
+        r#"
+        SELECT * FROM strategy_spot_stats
+            WHERE workflow_id = $1
+            ORDER BY node_id ASC, exchange ASC, symbol ASC
+        "#,
+        workflow_id,
+    )
+    .fetch_all(db)
+    .await?;
+
+    Ok(result)
+}
+
 #[cfg(test)]
 mod tests {
     use rust_decimal_macros::dec;
@@ -0,0 +1,122 @@
+use anyhow::Result;
+use bon::Builder;
+use chrono::{DateTime, Utc};
+use comfy_quant_base::{Exchange, Market, Symbol, SymbolAliases};
+use sqlx::{FromRow, PgPool};
+
+#[derive(Debug, FromRow)]
+pub struct SymbolAlias {
+    pub id: i32,                   // 主键ID
+    pub exchange: Exchange,        // 交易所
+    pub market: Market,            // 市场
+    pub old_symbol: Symbol,        // 旧交易对
+    pub new_symbol: Symbol,        // 新交易对
+    pub renamed_at: DateTime<Utc>, // 更名时间
+    pub created_at: DateTime<Utc>, // 创建时间
+    pub updated_at: DateTime<Utc>, // 更新时间
+}
+
+impl From<SymbolAlias> for comfy_quant_base::SymbolAlias {
+    fn from(value: SymbolAlias) -> Self {
+        comfy_quant_base::SymbolAlias::new(
+            value.exchange,
+            value.market,
+            value.old_symbol,
+            value.new_symbol,
+            value.renamed_at,
+        )
+    }
+}
+
+#[derive(Debug, Builder)]
+#[builder(on(_, into))]
+pub struct CreateSymbolAliasParams {
+    pub exchange: Exchange,        // 交易所
+    pub market: Market,            // 市场
+    pub old_symbol: Symbol,        // 旧交易对
+    pub new_symbol: Symbol,        // 新交易对
+    pub renamed_at: DateTime<Utc>, // 更名时间
+}
+
+pub async fn create_or_update(db: &PgPool, data: CreateSymbolAliasParams) -> Result<SymbolAlias> {
+    let row = sqlx::query_as!(
+        SymbolAlias,
+        r#"
+        INSERT INTO symbol_aliases (exchange, market, old_symbol, new_symbol, renamed_at, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5, NOW(), NOW())
+        ON CONFLICT (exchange, market, old_symbol)
+        DO UPDATE SET
+            new_symbol = EXCLUDED.new_symbol,
+            renamed_at = EXCLUDED.renamed_at,
+            updated_at = NOW()
+        RETURNING *
+        "#,
+        data.exchange.as_ref(),
+        data.market.as_ref(),
+        data.old_symbol.as_ref(),
+        data.new_symbol.as_ref(),
+        data.renamed_at,
+    )
+    .fetch_one(db)
+    .await?;
+
+    Ok(row)
+}
+
+pub async fn list(db: &PgPool, exchange: &Exchange, market: &Market) -> Result<Vec<SymbolAlias>> {
+    let rows = sqlx::query_as!(
+        SymbolAlias,
+        r#"
+        SELECT * FROM symbol_aliases WHERE exchange = $1 AND market = $2 ORDER BY renamed_at ASC
+        "#,
+        exchange.as_ref(),
+        market.as_ref(),
+    )
+    .fetch_all(db)
+    .await?;
+
+    Ok(rows)
+}
+
+// 交易所市场下的全部更名记录
+pub async fn aliases(db: &PgPool, exchange: &Exchange, market: &Market) -> Result<SymbolAliases> {
+    let aliases = list(db, exchange, market)
+        .await?
+        .into_iter()
+        .map(Into::into)
+        .collect::<Vec<_>>();
+
+    Ok(aliases.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use comfy_quant_base::secs_to_datetime;
+
+    #[sqlx::test(migrator = "crate::MIGRATOR")]
+    async fn test_symbol_alias_create_or_update(db: PgPool) -> Result<()> {
+        let data = CreateSymbolAliasParams::builder()
+            .exchange(Exchange::Binance)
+            .market(Market::Spot)
+            .old_symbol("MATICUSDT")
+            .new_symbol("POLUSDT")
+            .renamed_at(secs_to_datetime(1725926400)?)
+            .build();
+
+        let alias = create_or_update(&db, data).await?;
+
+        assert_eq!(alias.id, 1);
+        assert_eq!(alias.old_symbol, "MATICUSDT".into());
+        assert_eq!(alias.new_symbol, "POLUSDT".into());
+
+        let aliases = aliases(&db, &Exchange::Binance, &Market::Spot).await?;
+
+        assert_eq!(
+            aliases.current(&Exchange::Binance, &Market::Spot, &"MATICUSDT".into()),
+            "POLUSDT".into()
+        );
+
+        Ok(())
+    }
+}
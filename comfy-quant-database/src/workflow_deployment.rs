@@ -0,0 +1,147 @@
+use anyhow::Result;
+use bon::Builder;
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgPool};
+
+#[derive(Debug, FromRow, Clone)]
+pub struct WorkflowDeployment {
+    pub id: i32,                   // 主键ID
+    pub workflow_id: String,       // 逻辑工作流ID
+    pub version: i32,              // 版本号
+    pub content_hash: String,      // 规范化后的工作流定义哈希
+    pub definition: String,        // 工作流定义(JSON)
+    pub created_at: DateTime<Utc>, // 创建时间
+}
+
+#[derive(Debug, Builder)]
+#[builder(on(String, into))]
+pub struct CreateWorkflowDeploymentParams {
+    pub workflow_id: String,  // 逻辑工作流ID
+    pub content_hash: String, // 规范化后的工作流定义哈希
+    pub definition: String,   // 工作流定义(JSON)
+}
+
+// 创建新版本，版本号为该工作流的最大版本号加1。
+// 相同内容已部署过时返回None
+pub async fn create(
+    db: &PgPool,
+    data: CreateWorkflowDeploymentParams,
+) -> Result<Option<WorkflowDeployment>> {
+    let row = sqlx::query_as!(
+        WorkflowDeployment,
+        r#"
+        INSERT INTO workflow_deployments (workflow_id, version, content_hash, definition, created_at)
+        SELECT $1::VARCHAR, COALESCE(MAX(version), 0) + 1, $2, $3, NOW()
+        FROM workflow_deployments WHERE workflow_id = $1
+        ON CONFLICT (workflow_id, content_hash) DO NOTHING
+        RETURNING *
+        "#,
+        data.workflow_id,
+        data.content_hash,
+        data.definition,
+    )
+    .fetch_optional(db)
+    .await?;
+
+    Ok(row)
+}
+
+// 按内容哈希查找已部署的版本，未指定工作流时返回最近部署的一个
+pub async fn find_by_hash(
+    db: &PgPool,
+    workflow_id: Option<&str>,
+    content_hash: &str,
+) -> Result<Option<WorkflowDeployment>> {
+    let row = sqlx::query_as!(
+        WorkflowDeployment,
+        r#"
+        SELECT * FROM workflow_deployments
+        WHERE content_hash = $1 AND ($2::VARCHAR IS NULL OR workflow_id = $2)
+        ORDER BY id DESC
+        LIMIT 1
+        "#,
+        content_hash,
+        workflow_id,
+    )
+    .fetch_optional(db)
+    .await?;
+
+    Ok(row)
+}
+
+// 工作流的最新版本
+pub async fn latest(db: &PgPool, workflow_id: &str) -> Result<Option<WorkflowDeployment>> {
+    let row = sqlx::query_as!(
+        WorkflowDeployment,
+        r#"
+        SELECT * FROM workflow_deployments
+        WHERE workflow_id = $1
+        ORDER BY version DESC
+        LIMIT 1
+        "#,
+        workflow_id,
+    )
+    .fetch_optional(db)
+    .await?;
+
+    Ok(row)
+}
+
+// 工作流的所有版本
+pub async fn list(db: &PgPool, workflow_id: &str) -> Result<Vec<WorkflowDeployment>> {
+    let rows = sqlx::query_as!(
+        WorkflowDeployment,
+        r#"
+        SELECT * FROM workflow_deployments
+        WHERE workflow_id = $1
+        ORDER BY version ASC
+        "#,
+        workflow_id,
+    )
+    .fetch_all(db)
+    .await?;
+
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[sqlx::test(migrator = "crate::MIGRATOR")]
+    async fn test_workflow_deployment(db: PgPool) -> Result<()> {
+        let workflow_id = "jEnbRDqQu4UN6y7cgQgp6";
+        let data = |hash: &str| {
+            CreateWorkflowDeploymentParams::builder()
+                .workflow_id(workflow_id)
+                .content_hash(hash)
+                .definition("{}")
+                .build()
+        };
+
+        let v1 = create(&db, data("hash-1"))
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Deployment not created"))?;
+        assert_eq!(v1.version, 1);
+
+        // 相同内容不会创建新版本
+        assert!(create(&db, data("hash-1")).await?.is_none());
+
+        let v2 = create(&db, data("hash-2"))
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Deployment not created"))?;
+        assert_eq!(v2.version, 2);
+
+        let found = find_by_hash(&db, Some(workflow_id), "hash-1").await?;
+        assert_eq!(found.map(|row| row.version), Some(1));
+        assert!(find_by_hash(&db, None, "hash-3").await?.is_none());
+
+        assert_eq!(
+            latest(&db, workflow_id).await?.map(|row| row.version),
+            Some(2)
+        );
+        assert_eq!(list(&db, workflow_id).await?.len(), 2);
+
+        Ok(())
+    }
+}
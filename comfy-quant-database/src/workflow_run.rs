@@ -0,0 +1,334 @@
+use anyhow::Result;
+use bon::Builder;
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgPool};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WorkflowRunStatus {
+    Running,  // 运行中，进程重启后需要恢复
+    Stopped,  // 已停止
+    Finished, // 节点全部执行完毕
+    Failed,   // 节点失败且不再重启，需人工处理
+}
+
+impl From<&str> for WorkflowRunStatus {
+    fn from(value: &str) -> Self {
+        match value {
+            "stopped" => WorkflowRunStatus::Stopped,
+            "finished" => WorkflowRunStatus::Finished,
+            "failed" => WorkflowRunStatus::Failed,
+            _ => WorkflowRunStatus::Running,
+        }
+    }
+}
+
+impl From<String> for WorkflowRunStatus {
+    fn from(value: String) -> Self {
+        value.as_str().into()
+    }
+}
+
+impl AsRef<str> for WorkflowRunStatus {
+    fn as_ref(&self) -> &str {
+        match self {
+            WorkflowRunStatus::Running => "running",
+            WorkflowRunStatus::Stopped => "stopped",
+            WorkflowRunStatus::Finished => "finished",
+            WorkflowRunStatus::Failed => "failed",
+        }
+    }
+}
+
+impl fmt::Display for WorkflowRunStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_ref())
+    }
+}
+
+#[derive(Debug, FromRow, Clone)]
+pub struct WorkflowRun {
+    pub id: i32,                        // 主键ID
+    pub workflow_id: String,            // 工作流ID
+    pub status: WorkflowRunStatus,      // 状态
+    pub checkpoint: String,             // 最近一次检查点(JSON)
+    pub checkpointed_at: DateTime<Utc>, // 最近一次检查点时间
+    pub owner: String,                  // 运行工作流的实例ID，为空时未启用租约
+    pub heartbeat_at: DateTime<Utc>,    // 最近一次心跳时间
+    pub created_at: DateTime<Utc>,      // 创建时间
+    pub updated_at: DateTime<Utc>,      // 更新时间
+}
+
+#[derive(Debug, Builder)]
+#[builder(on(String, into))]
+pub struct SaveCheckpointParams {
+    pub workflow_id: String,       // 工作流ID
+    pub status: WorkflowRunStatus, // 状态
+    pub checkpoint: String,        // 检查点(JSON)
+    #[builder(default)]
+    pub owner: String, // 运行工作流的实例ID
+}
+
+// 保存检查点，每个工作流只保留最近一次。
+// 运行中的工作流已被其他实例接管时不保存，返回None
+pub async fn save_checkpoint(
+    db: &PgPool,
+    data: SaveCheckpointParams,
+) -> Result<Option<WorkflowRun>> {
+    let run = sqlx::query_as!(
+        WorkflowRun,
+        r#"
+        INSERT INTO workflow_runs (workflow_id, status, checkpoint, checkpointed_at, owner, heartbeat_at, created_at, updated_at)
+        VALUES ($1, $2, $3, NOW(), $4, NOW(), NOW(), NOW())
+        ON CONFLICT (workflow_id)
+        DO UPDATE SET
+            status = EXCLUDED.status,
+            checkpoint = EXCLUDED.checkpoint,
+            checkpointed_at = NOW(),
+            owner = EXCLUDED.owner,
+            heartbeat_at = NOW(),
+            updated_at = NOW()
+        WHERE workflow_runs.owner = EXCLUDED.owner OR workflow_runs.status <> $5
+        RETURNING *
+        "#,
+        data.workflow_id,
+        data.status.as_ref(),
+        data.checkpoint,
+        data.owner,
+        WorkflowRunStatus::Running.as_ref(),
+    )
+    .fetch_optional(db)
+    .await?;
+
+    Ok(run)
+}
+
+// 工作流的最近一次检查点
+pub async fn get(db: &PgPool, workflow_id: &str) -> Result<Option<WorkflowRun>> {
+    let run = sqlx::query_as!(
+        WorkflowRun,
+        r#"
+        SELECT * FROM workflow_runs WHERE workflow_id = $1
+        "#,
+        workflow_id,
+    )
+    .fetch_optional(db)
+    .await?;
+
+    Ok(run)
+}
+
+// 更新运行状态，不修改检查点
+pub async fn update_status(
+    db: &PgPool,
+    workflow_id: &str,
+    status: &WorkflowRunStatus,
+) -> Result<Option<WorkflowRun>> {
+    let run = sqlx::query_as!(
+        WorkflowRun,
+        r#"
+        UPDATE workflow_runs SET status = $1, updated_at = NOW() WHERE workflow_id = $2
+        RETURNING *
+        "#,
+        status.as_ref(),
+        workflow_id,
+    )
+    .fetch_optional(db)
+    .await?;
+
+    Ok(run)
+}
+
+// 运行中的工作流，进程重启后逐个恢复
+pub async fn list_running(db: &PgPool) -> Result<Vec<WorkflowRun>> {
+    let runs = sqlx::query_as!(
+        WorkflowRun,
+        r#"
+        SELECT * FROM workflow_runs WHERE status = $1 ORDER BY created_at ASC
+        "#,
+        WorkflowRunStatus::Running.as_ref(),
+    )
+    .fetch_all(db)
+    .await?;
+
+    Ok(runs)
+}
+
+// 续期运行租约，租约已被其他实例接管或工作流不在运行中时返回false
+pub async fn heartbeat(db: &PgPool, workflow_id: &str, owner: &str) -> Result<bool> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE workflow_runs SET heartbeat_at = NOW()
+        WHERE workflow_id = $1 AND owner = $2 AND status = $3
+        "#,
+        workflow_id,
+        owner,
+        WorkflowRunStatus::Running.as_ref(),
+    )
+    .execute(db)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+// 心跳超时的运行中工作流，由备用实例接管
+pub async fn list_expired(db: &PgPool, owner: &str, timeout_secs: i64) -> Result<Vec<WorkflowRun>> {
+    let runs = sqlx::query_as!(
+        WorkflowRun,
+        r#"
+        SELECT * FROM workflow_runs
+        WHERE status = $1 AND owner <> $2 AND heartbeat_at < NOW() - make_interval(secs => $3)
+        ORDER BY created_at ASC
+        "#,
+        WorkflowRunStatus::Running.as_ref(),
+        owner,
+        timeout_secs as f64,
+    )
+    .fetch_all(db)
+    .await?;
+
+    Ok(runs)
+}
+
+// 获取工作流的运行租约：工作流不在运行中、租约属于自己、未启用租约或心跳超时时可以获取。
+// 多个实例同时接管时只有一个成功
+pub async fn claim(db: &PgPool, workflow_id: &str, owner: &str, timeout_secs: i64) -> Result<bool> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE workflow_runs SET owner = $2, heartbeat_at = NOW(), updated_at = NOW()
+        WHERE workflow_id = $1
+            AND (
+                status <> $3
+                OR owner = $2
+                OR owner = ''
+                OR heartbeat_at < NOW() - make_interval(secs => $4)
+            )
+        "#,
+        workflow_id,
+        owner,
+        WorkflowRunStatus::Running.as_ref(),
+        timeout_secs as f64,
+    )
+    .execute(db)
+    .await?;
+
+    if result.rows_affected() > 0 {
+        return Ok(true);
+    }
+
+    // 从未运行过的工作流没有租约
+    Ok(get(db, workflow_id).await?.is_none())
+}
+
+// 释放自己持有的运行租约，启动失败后其他实例可以立即接管
+pub async fn release(db: &PgPool, workflow_id: &str, owner: &str) -> Result<bool> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE workflow_runs SET owner = '', updated_at = NOW()
+        WHERE workflow_id = $1 AND owner = $2
+        "#,
+        workflow_id,
+        owner,
+    )
+    .execute(db)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[sqlx::test(migrator = "crate::MIGRATOR")]
+    async fn test_workflow_run(db: PgPool) -> Result<()> {
+        let workflow_id = "jEnbRDqQu4UN6y7cgQgp6";
+        let data = |checkpoint: &str| {
+            SaveCheckpointParams::builder()
+                .workflow_id(workflow_id)
+                .status(WorkflowRunStatus::Running)
+                .checkpoint(checkpoint)
+                .build()
+        };
+
+        let run = save_checkpoint(&db, data("{}"))
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Checkpoint not saved"))?;
+        assert_eq!(run.status, WorkflowRunStatus::Running);
+
+        // 同一工作流只保留最近一次检查点
+        let run2 = save_checkpoint(&db, data(r#"{"running_time":1}"#))
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Checkpoint not saved"))?;
+        assert_eq!(run2.id, run.id);
+        assert_eq!(run2.checkpoint, r#"{"running_time":1}"#);
+        assert!(run2.checkpointed_at >= run.checkpointed_at);
+
+        assert_eq!(list_running(&db).await?.len(), 1);
+
+        let run3 = update_status(&db, workflow_id, &WorkflowRunStatus::Stopped).await?;
+        assert_eq!(run3.map(|run| run.status), Some(WorkflowRunStatus::Stopped));
+        assert!(list_running(&db).await?.is_empty());
+
+        let found = get(&db, workflow_id).await?;
+        assert_eq!(
+            found.map(|run| run.checkpoint),
+            Some(r#"{"running_time":1}"#.to_string())
+        );
+        assert!(get(&db, "unknown").await?.is_none());
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrator = "crate::MIGRATOR")]
+    async fn test_workflow_run_lease(db: PgPool) -> Result<()> {
+        let workflow_id = "jEnbRDqQu4UN6y7cgQgp6";
+        let data = |owner: &str| {
+            SaveCheckpointParams::builder()
+                .workflow_id(workflow_id)
+                .status(WorkflowRunStatus::Running)
+                .checkpoint("{}")
+                .owner(owner)
+                .build()
+        };
+
+        // 从未运行过的工作流可以直接获取租约
+        assert!(claim(&db, workflow_id, "primary", 30).await?);
+        assert!(save_checkpoint(&db, data("primary")).await?.is_some());
+        assert!(heartbeat(&db, workflow_id, "primary").await?);
+
+        // 释放后备用实例可以立即接管，之后由主实例重新获取
+        assert!(!release(&db, workflow_id, "standby").await?);
+        assert!(release(&db, workflow_id, "primary").await?);
+        assert!(claim(&db, workflow_id, "standby", 30).await?);
+        assert!(release(&db, workflow_id, "standby").await?);
+        assert!(claim(&db, workflow_id, "primary", 30).await?);
+
+        // 主实例心跳正常时备用实例不能接管
+        assert!(list_expired(&db, "standby", 30).await?.is_empty());
+        assert!(!claim(&db, workflow_id, "standby", 30).await?);
+        assert!(!heartbeat(&db, workflow_id, "standby").await?);
+
+        // 心跳超时后由备用实例接管，主实例的检查点和心跳不再生效
+        sqlx::query!(
+            "UPDATE workflow_runs SET heartbeat_at = NOW() - INTERVAL '1 minute' WHERE workflow_id = $1",
+            workflow_id
+        )
+        .execute(&db)
+        .await?;
+
+        assert_eq!(list_expired(&db, "standby", 30).await?.len(), 1);
+        assert!(claim(&db, workflow_id, "standby", 30).await?);
+        assert!(!heartbeat(&db, workflow_id, "primary").await?);
+        assert!(save_checkpoint(&db, data("primary")).await?.is_none());
+
+        let run = save_checkpoint(&db, data("standby")).await?;
+        assert_eq!(run.map(|run| run.owner), Some("standby".to_string()));
+
+        // 停止后任何实例都可以重新启动
+        update_status(&db, workflow_id, &WorkflowRunStatus::Stopped).await?;
+        assert!(claim(&db, workflow_id, "primary", 30).await?);
+
+        Ok(())
+    }
+}
@@ -19,7 +19,7 @@ async fn main() -> Result<()> {
         .api_key(api_key)
         .secret_key(secret_key)
         // .config(binance::config::Config::testnet())
-        .build();
+        .build()?;
 
     let account = client.get_account().await?;
 
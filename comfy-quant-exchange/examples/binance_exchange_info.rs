@@ -1,9 +1,10 @@
 use comfy_quant_exchange::exchange::binance::BinanceClient;
 
-fn main() -> anyhow::Result<()> {
-    let client = BinanceClient::builder().build();
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let client = BinanceClient::builder().build()?;
 
-    let info = client.spot().get_exchange_info()?;
+    let info = client.spot().get_exchange_info().await?;
 
     dbg!(info);
 
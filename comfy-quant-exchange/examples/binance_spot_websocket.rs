@@ -5,7 +5,7 @@ use futures::StreamExt;
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let config = Config::default().set_ws_endpoint("wss://data-stream.binance.vision/ws");
-    let client = BinanceClient::builder().config(config).build();
+    let client = BinanceClient::builder().config(config).build()?;
     let websocket = client.spot_websocket("btcusdt@aggTrade");
 
     let mut stream = websocket.subscribe().await?;
@@ -0,0 +1,183 @@
+use super::{Announcement, AnnouncementKind};
+use crate::exchange::ConnectionOptions;
+use anyhow::Result;
+use async_stream::stream;
+use bon::bon;
+use chrono::{DateTime, Utc};
+use comfy_quant_base::{millis_to_datetime, Exchange};
+use futures::stream::BoxStream;
+use serde::Deserialize;
+use std::{collections::HashSet, time::Duration};
+use tokio_util::sync::CancellationToken;
+
+const ANNOUNCEMENT_URL: &str =
+    "https://www.binance.com/bapi/composite/v1/public/cms/article/list/query";
+const ARTICLE_URL: &str = "https://www.binance.com/en/support/announcement";
+
+#[derive(Deserialize, Debug)]
+struct ArticleListResponse {
+    data: ArticleListData,
+}
+
+#[derive(Deserialize, Debug)]
+struct ArticleListData {
+    catalogs: Vec<ArticleCatalog>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ArticleCatalog {
+    articles: Vec<Article>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Article {
+    id: u64,
+    code: String,
+    title: String,
+    release_date: i64,
+}
+
+impl TryFrom<Article> for Announcement {
+    type Error = anyhow::Error;
+
+    fn try_from(value: Article) -> Result<Self> {
+        Ok(Announcement {
+            id: value.id.to_string(),
+            exchange: Exchange::Binance,
+            kind: AnnouncementKind::classify(&value.title),
+            assets: Announcement::extract_assets(&value.title),
+            published_at: millis_to_datetime(value.release_date)?,
+            url: Some(format!("{}/{}", ARTICLE_URL, value.code)),
+            title: value.title,
+        })
+    }
+}
+
+// 币安公告，访问公共接口，不需要api_key和secret_key
+#[derive(Debug)]
+pub struct BinanceAnnouncement {
+    http: reqwest::Client,
+    page_size: u32,
+    token: CancellationToken,
+}
+
+#[bon]
+impl BinanceAnnouncement {
+    #[builder]
+    pub fn new(
+        #[builder(default = 20)] page_size: u32, // 每次拉取的公告数量
+        connection: Option<ConnectionOptions>,   // 连接配置(代理)
+    ) -> Result<Self> {
+        let http = connection.unwrap_or_default().http_client()?;
+
+        Ok(BinanceAnnouncement {
+            http,
+            page_size,
+            token: CancellationToken::new(),
+        })
+    }
+
+    // 拉取最新公告，按发布时间升序
+    pub async fn fetch(&self) -> Result<Vec<Announcement>> {
+        Self::fetch_with(&self.http, self.page_size).await
+    }
+
+    async fn fetch_with(http: &reqwest::Client, page_size: u32) -> Result<Vec<Announcement>> {
+        let response = http
+            .get(ANNOUNCEMENT_URL)
+            .query(&[("type", 1), ("pageNo", 1), ("pageSize", page_size)])
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<ArticleListResponse>()
+            .await?;
+
+        let mut announcements = response
+            .data
+            .catalogs
+            .into_iter()
+            .flat_map(|catalog| catalog.articles)
+            .map(Announcement::try_from)
+            .collect::<Result<Vec<_>>>()?;
+
+        announcements.sort_by_key(|announcement| announcement.published_at);
+
+        Ok(announcements)
+    }
+
+    // 轮询公告流，只推送 since 之后发布且未推送过的公告，请求失败时记录日志并在下一轮重试
+    pub fn announcements_stream(
+        &self,
+        since: DateTime<Utc>,
+        poll_interval: Duration,
+    ) -> BoxStream<'static, Announcement> {
+        let http = self.http.clone();
+        let page_size = self.page_size;
+        let token = self.token.clone();
+
+        let announcement_stream = stream! {
+            let mut seen = HashSet::new();
+
+            loop {
+                match Self::fetch_with(&http, page_size).await {
+                    Ok(announcements) => {
+                        for announcement in announcements {
+                            if announcement.published_at <= since
+                                || !seen.insert(announcement.id.clone())
+                            {
+                                continue;
+                            }
+
+                            yield announcement;
+                        }
+                    }
+                    Err(e) => {
+                        tracing::warn!(
+                            monotonic_counter.announcement_fetch_failed = 1_u64,
+                            "Fetch binance announcements failed: {}",
+                            e
+                        );
+                    }
+                }
+
+                tokio::select! {
+                    _ = tokio::time::sleep(poll_interval) => {}
+                    _ = token.cancelled() => break,
+                }
+            }
+        };
+
+        Box::pin(announcement_stream)
+    }
+}
+
+impl Drop for BinanceAnnouncement {
+    fn drop(&mut self) {
+        self.token.cancel();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_article_to_announcement() -> Result<()> {
+        let json_str = r#"{"code":"000000","data":{"catalogs":[{"catalogId":48,"articles":[{"id":123,"code":"abc","title":"Binance Will List Foo (FOO)","type":1,"releaseDate":1735689600000}]}]}}"#;
+        let response: ArticleListResponse = serde_json::from_str(json_str)?;
+        let article = response.data.catalogs.into_iter().next().unwrap().articles;
+        let announcement = Announcement::try_from(article.into_iter().next().unwrap())?;
+
+        assert_eq!(announcement.id, "123");
+        assert_eq!(announcement.kind, AnnouncementKind::NewListing);
+        assert_eq!(announcement.assets, vec!["FOO"]);
+        assert_eq!(announcement.published_at.timestamp(), 1735689600);
+        assert_eq!(
+            announcement.url.as_deref(),
+            Some("https://www.binance.com/en/support/announcement/abc")
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,5 @@
+mod binance_announcement;
+mod types;
+
+pub use binance_announcement::BinanceAnnouncement;
+pub use types::{Announcement, AnnouncementKind};
@@ -0,0 +1,125 @@
+use chrono::{DateTime, Utc};
+use comfy_quant_base::Exchange;
+use serde::{Deserialize, Serialize};
+
+// 公告类型
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum AnnouncementKind {
+    NewListing,  // 新币上线
+    Delisting,   // 下架
+    Maintenance, // 系统维护
+    Other,       // 其他
+}
+
+impl AnnouncementKind {
+    // 根据标题判断公告类型
+    pub fn classify(title: &str) -> Self {
+        let title = title.to_lowercase();
+
+        if title.contains("delist") || title.contains("will remove") {
+            AnnouncementKind::Delisting
+        } else if title.contains("maintenance") || title.contains("suspend") {
+            AnnouncementKind::Maintenance
+        } else if title.contains("will list") || title.contains("listing") {
+            AnnouncementKind::NewListing
+        } else {
+            AnnouncementKind::Other
+        }
+    }
+}
+
+impl AsRef<str> for AnnouncementKind {
+    fn as_ref(&self) -> &str {
+        match self {
+            AnnouncementKind::NewListing => "new_listing",
+            AnnouncementKind::Delisting => "delisting",
+            AnnouncementKind::Maintenance => "maintenance",
+            AnnouncementKind::Other => "other",
+        }
+    }
+}
+
+// 交易所公告
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Announcement {
+    pub id: String,                  // 公告ID
+    pub exchange: Exchange,          // 交易所
+    pub kind: AnnouncementKind,      // 公告类型
+    pub title: String,               // 标题
+    pub assets: Vec<String>,         // 涉及的币种
+    pub published_at: DateTime<Utc>, // 发布时间
+    pub url: Option<String>,         // 公告链接
+}
+
+impl Announcement {
+    // 提取标题中括号内的币种，如 "Binance Will List Foo (FOO) and Bar (BAR)"
+    pub fn extract_assets(title: &str) -> Vec<String> {
+        let mut assets = vec![];
+
+        for part in title.split('(').skip(1) {
+            let Some((inner, _)) = part.split_once(')') else {
+                continue;
+            };
+
+            for asset in inner.split([',', '/', ' ']) {
+                let asset = asset.trim();
+
+                if !asset.is_empty()
+                    && asset.len() <= 12
+                    && asset
+                        .chars()
+                        .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+                    && !assets.iter().any(|a| a == asset)
+                {
+                    assets.push(asset.to_string());
+                }
+            }
+        }
+
+        assets
+    }
+
+    // 公告是否涉及该币种
+    pub fn mentions(&self, asset: &str) -> bool {
+        self.assets.iter().any(|a| a.eq_ignore_ascii_case(asset))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_announcement() {
+        assert_eq!(
+            AnnouncementKind::classify("Binance Will List Foo (FOO) with Seed Tag Applied"),
+            AnnouncementKind::NewListing
+        );
+        assert_eq!(
+            AnnouncementKind::classify("Binance Will Delist BAR, BAZ on 2024-12-30"),
+            AnnouncementKind::Delisting
+        );
+        assert_eq!(
+            AnnouncementKind::classify("Binance Will Perform Scheduled System Maintenance"),
+            AnnouncementKind::Maintenance
+        );
+        assert_eq!(
+            AnnouncementKind::classify("Binance Launchpool Rewards"),
+            AnnouncementKind::Other
+        );
+    }
+
+    #[test]
+    fn test_extract_assets() {
+        assert_eq!(
+            Announcement::extract_assets("Binance Will List Foo (FOO) and Bar Token (BAR)"),
+            vec!["FOO", "BAR"]
+        );
+        assert_eq!(
+            Announcement::extract_assets("Notice of Removal of Spot Trading Pairs (FOO/BTC, BAR)"),
+            vec!["FOO", "BTC", "BAR"]
+        );
+        assert!(Announcement::extract_assets("Binance (Updated) Notice").is_empty());
+    }
+}
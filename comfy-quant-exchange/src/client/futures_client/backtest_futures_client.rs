@@ -0,0 +1,753 @@
+use super::base::{FundingRate, FuturesBalance, FuturesOrder, Position, PositionSide};
+use crate::{
+    client::{
+        futures_client_kind::{FuturesClientExecutable, FuturesClientExecutableExt},
+        spot_client::base::{Order, OrderSide, OrderStatus, OrderType},
+    },
+    store::PriceStore,
+};
+use anyhow::Result;
+use async_lock::RwLock;
+use bon::bon;
+use comfy_quant_base::{Exchange, Market, Symbol};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::{collections::HashMap, sync::Arc};
+use tokio::sync::Mutex;
+
+const FUNDING_INTERVAL_SECS: i64 = 8 * 3600; // 资金费用结算间隔
+const MAINTENANCE_MARGIN_RATE: Decimal = dec!(0.005); // 维持保证金率
+
+// 逐仓持仓
+#[derive(Debug, Clone)]
+struct BacktestPosition {
+    quote_asset: String,  // 保证金资产
+    qty: Decimal,         // 持仓数量
+    entry_price: Decimal, // 开仓均价
+    margin: Decimal,      // 占用保证金
+}
+
+impl BacktestPosition {
+    fn unrealized_pnl(&self, position_side: PositionSide, price: Decimal) -> Decimal {
+        match position_side {
+            PositionSide::Long => (price - self.entry_price) * self.qty,
+            PositionSide::Short => (self.entry_price - price) * self.qty,
+        }
+    }
+
+    // 保证金加未实现盈亏低于按开仓价值计算的维持保证金时强平
+    fn liquidation_price(&self, position_side: PositionSide) -> Decimal {
+        let buffer =
+            (self.margin - self.entry_price * self.qty * MAINTENANCE_MARGIN_RATE) / self.qty;
+
+        match position_side {
+            PositionSide::Long => (self.entry_price - buffer).max(dec!(0)),
+            PositionSide::Short => self.entry_price + buffer,
+        }
+    }
+}
+
+// 未成交的限价单
+#[derive(Debug, Clone)]
+struct OpenOrder {
+    order: FuturesOrder,
+    price: Decimal,    // 限价
+    reserved: Decimal, // 开仓单冻结的保证金
+}
+
+#[derive(Debug)]
+pub struct BacktestFuturesClientData {
+    assets: HashMap<String, Decimal>, // 保证金资产的钱包余额
+    commissions: Option<f64>,
+    default_leverage: u8,                                         // 默认杠杆倍数
+    leverages: HashMap<Symbol, u8>,                               // 交易对的杠杆倍数
+    positions: HashMap<(Symbol, PositionSide), BacktestPosition>, // 持仓
+    open_orders: Vec<OpenOrder>,                                  // 未成交的限价单，按提交顺序撮合
+    order_id: u64,
+    funding_rate: Decimal,     // 每次结算的资金费率，为正时多头向空头支付
+    funding_time: Option<i64>, // 最后一次结算资金费用的时间(秒)
+}
+
+impl BacktestFuturesClientData {
+    fn commission_rate(&self) -> Result<Decimal> {
+        let commission_rate: Decimal = self.commissions.unwrap_or(0.0004).try_into()?;
+
+        Ok(commission_rate)
+    }
+
+    fn leverage(&self, symbol: &Symbol) -> u8 {
+        self.leverages
+            .get(symbol)
+            .copied()
+            .unwrap_or(self.default_leverage)
+    }
+
+    fn wallet(&self, asset: &str) -> Decimal {
+        self.assets.get(asset).copied().unwrap_or_default()
+    }
+
+    fn add_wallet(&mut self, asset: &str, amount: Decimal) {
+        *self.assets.entry(asset.to_string()).or_default() += amount;
+    }
+
+    // 持仓和挂单占用的保证金
+    fn used_margin(&self, asset: &str) -> Decimal {
+        let position_margin = self
+            .positions
+            .values()
+            .filter(|position| position.quote_asset == asset)
+            .map(|position| position.margin)
+            .sum::<Decimal>();
+
+        let order_margin = self
+            .open_orders
+            .iter()
+            .filter(|open| open.order.order.quote_asset.as_deref() == Some(asset))
+            .map(|open| open.reserved)
+            .sum::<Decimal>();
+
+        position_margin + order_margin
+    }
+
+    // 逐仓模式下未实现盈亏不能用于开仓
+    fn available(&self, asset: &str) -> Decimal {
+        (self.wallet(asset) - self.used_margin(asset)).max(dec!(0))
+    }
+
+    fn next_order_id(&mut self) -> String {
+        self.order_id += 1;
+        self.order_id.to_string()
+    }
+
+    // 挂单和未平仓的数量，平仓数量不能超过持仓
+    fn closable_qty(&self, symbol: &Symbol, position_side: PositionSide) -> Decimal {
+        let qty = self
+            .positions
+            .get(&(symbol.clone(), position_side))
+            .map(|position| position.qty)
+            .unwrap_or_default();
+
+        let pending = self
+            .open_orders
+            .iter()
+            .filter(|open| {
+                open.order.reduce_only
+                    && open.order.position_side == position_side
+                    && &open.order.order.symbol == symbol
+            })
+            .map(|open| {
+                open.order
+                    .order
+                    .orig_qty
+                    .parse::<Decimal>()
+                    .unwrap_or_default()
+            })
+            .sum::<Decimal>();
+
+        qty - pending
+    }
+
+    // 开仓，保证金和手续费从可用余额中扣除
+    fn open_position(
+        &mut self,
+        symbol: &Symbol,
+        quote_asset: &str,
+        position_side: PositionSide,
+        qty: Decimal,
+        price: Decimal,
+    ) -> Result<()> {
+        let notional = qty * price;
+        let margin = notional / Decimal::from(self.leverage(symbol));
+        let commission = notional * self.commission_rate()?;
+
+        if self.available(quote_asset) < margin + commission {
+            anyhow::bail!("Insufficient available balance");
+        }
+
+        self.add_wallet(quote_asset, -commission);
+
+        let position = self
+            .positions
+            .entry((symbol.clone(), position_side))
+            .or_insert(BacktestPosition {
+                quote_asset: quote_asset.to_string(),
+                qty: dec!(0),
+                entry_price: dec!(0),
+                margin: dec!(0),
+            });
+
+        position.entry_price =
+            (position.entry_price * position.qty + notional) / (position.qty + qty);
+        position.qty += qty;
+        position.margin += margin;
+
+        Ok(())
+    }
+
+    // 平仓，按比例释放保证金，返回已实现盈亏(不含手续费)
+    fn close_position(
+        &mut self,
+        symbol: &Symbol,
+        position_side: PositionSide,
+        qty: Decimal,
+        price: Decimal,
+    ) -> Result<Decimal> {
+        let commission_rate = self.commission_rate()?;
+        let key = (symbol.clone(), position_side);
+
+        let Some(position) = self.positions.get_mut(&key) else {
+            anyhow::bail!("Position not found");
+        };
+
+        if position.qty < qty {
+            anyhow::bail!("Insufficient position");
+        }
+
+        let realized_pnl = match position_side {
+            PositionSide::Long => (price - position.entry_price) * qty,
+            PositionSide::Short => (position.entry_price - price) * qty,
+        };
+        let released = position.margin * qty / position.qty;
+        let quote_asset = position.quote_asset.clone();
+
+        position.qty -= qty;
+        position.margin -= released;
+
+        if position.qty.is_zero() {
+            self.positions.remove(&key);
+        }
+
+        self.add_wallet(&quote_asset, realized_pnl - qty * price * commission_rate);
+
+        Ok(realized_pnl)
+    }
+
+    // 按结算间隔收取或支付资金费用，多次间隔按当前价格一并结算
+    fn settle_funding(&mut self, now: Option<i64>, price: &impl Fn(&Symbol) -> Option<Decimal>) {
+        let Some(now) = now else {
+            return;
+        };
+
+        let Some(funding_time) = self.funding_time else {
+            self.funding_time = Some(now - now.rem_euclid(FUNDING_INTERVAL_SECS));
+            return;
+        };
+
+        let periods = (now - funding_time) / FUNDING_INTERVAL_SECS;
+
+        if periods <= 0 {
+            return;
+        }
+
+        let payments = self
+            .positions
+            .iter()
+            .filter_map(|((symbol, position_side), position)| {
+                let payment =
+                    position.qty * price(symbol)? * self.funding_rate * Decimal::from(periods);
+
+                let payment = match position_side {
+                    PositionSide::Long => -payment,
+                    PositionSide::Short => payment,
+                };
+
+                Some((position.quote_asset.clone(), payment))
+            })
+            .collect::<Vec<_>>();
+
+        for (asset, payment) in payments {
+            self.add_wallet(&asset, payment);
+        }
+
+        self.funding_time = Some(funding_time + periods * FUNDING_INTERVAL_SECS);
+    }
+
+    // 撮合限价单，价格触及限价时按限价成交
+    fn match_orders(&mut self, price: &impl Fn(&Symbol) -> Option<Decimal>, now: Option<i64>) {
+        let open_orders = std::mem::take(&mut self.open_orders);
+
+        for open in open_orders {
+            let order = &open.order.order;
+            let Some(last_price) = price(&order.symbol) else {
+                self.open_orders.push(open);
+                continue;
+            };
+
+            let crossed = match order.order_side {
+                OrderSide::Buy => last_price <= open.price,
+                OrderSide::Sell => last_price >= open.price,
+            };
+
+            if !crossed {
+                self.open_orders.push(open);
+                continue;
+            }
+
+            let qty = order.orig_qty.parse::<Decimal>().unwrap_or_default();
+            let symbol = order.symbol.clone();
+            let position_side = open.order.position_side;
+
+            // 开仓单先解冻保证金，再按成交价格重新占用
+            let result = if open.order.reduce_only {
+                self.close_position(&symbol, position_side, qty, open.price)
+                    .map(|_| ())
+            } else {
+                let quote_asset = order.quote_asset.clone().unwrap_or_default();
+                self.open_position(&symbol, &quote_asset, position_side, qty, open.price)
+            };
+
+            if let Err(e) = result {
+                tracing::warn!("Backtest futures order {} expired: {}", order.order_id, e);
+                continue;
+            }
+
+            tracing::debug!(
+                "Backtest futures order {} filled at {}, time: {:?}",
+                order.order_id,
+                open.price,
+                now
+            );
+        }
+    }
+
+    // 标记价格触及强平价格时按标记价格强平，损失全部保证金
+    fn liquidate(&mut self, price: &impl Fn(&Symbol) -> Option<Decimal>) {
+        let liquidated = self
+            .positions
+            .iter()
+            .filter(|((symbol, position_side), position)| {
+                let Some(price) = price(symbol) else {
+                    return false;
+                };
+                let liquidation_price = position.liquidation_price(*position_side);
+
+                match position_side {
+                    PositionSide::Long => price <= liquidation_price,
+                    PositionSide::Short => price >= liquidation_price,
+                }
+            })
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+
+        for key in liquidated {
+            if let Some(position) = self.positions.remove(&key) {
+                self.add_wallet(&position.quote_asset, -position.margin);
+
+                tracing::warn!(
+                    monotonic_counter.backtest_futures_liquidated = 1_u64,
+                    symbol = %key.0,
+                    position_side = key.1.as_ref(),
+                    "Backtest futures position liquidated"
+                );
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BacktestFuturesClient {
+    data: Arc<Mutex<BacktestFuturesClientData>>, // 必须使用内部可变性和Sync
+    price_store: Arc<RwLock<PriceStore>>,        // 价格存储
+    market: Market,                              // 价格来源市场
+}
+
+#[bon]
+impl BacktestFuturesClient {
+    #[builder]
+    pub fn new(
+        #[builder(into)] assets: Vec<(String, f64)>,
+        commissions: Option<f64>,
+        price_store: Arc<RwLock<PriceStore>>,
+        #[builder(default = 1)] leverage: u8, // 默认杠杆倍数
+        funding_rate: Option<f64>,            // 每8小时结算的资金费率，默认不收取
+        #[builder(default = Market::Usdm)] market: Market, // 价格来源市场，只有现货行情时可使用现货价格
+    ) -> Self {
+        let assets = assets
+            .into_iter()
+            .filter_map(|(asset, amount)| Some((asset, Decimal::try_from(amount).ok()?)))
+            .collect();
+
+        let data = Arc::new(Mutex::new(BacktestFuturesClientData {
+            assets,
+            commissions,
+            default_leverage: leverage.max(1),
+            leverages: HashMap::new(),
+            positions: HashMap::new(),
+            open_orders: Vec::new(),
+            order_id: 0,
+            funding_rate: funding_rate
+                .and_then(|rate| Decimal::try_from(rate).ok())
+                .unwrap_or_default(),
+            funding_time: None,
+        }));
+
+        BacktestFuturesClient {
+            data,
+            price_store,
+            market,
+        }
+    }
+
+    async fn price(&self, symbol: &Symbol) -> Result<Decimal> {
+        self.price_store
+            .read()
+            .await
+            .price(&Exchange::Binance, &self.market, symbol)
+            .filter(|price| *price > dec!(0))
+            .ok_or_else(|| anyhow::anyhow!("Price not found: {}", symbol))
+    }
+
+    // 每次请求前结算资金费用、撮合挂单并检查强平
+    async fn sync(&self, data: &mut BacktestFuturesClientData) {
+        let price_store = self.price_store.read().await;
+        let price = |symbol: &Symbol| price_store.price(&Exchange::Binance, &self.market, symbol);
+        let now = price_store.timestamp();
+
+        data.settle_funding(now, &price);
+        data.match_orders(&price, now);
+        data.liquidate(&price);
+    }
+
+    async fn timestamp(&self) -> i64 {
+        self.price_store
+            .read()
+            .await
+            .timestamp()
+            .map(|timestamp| timestamp * 1000)
+            .unwrap_or_default()
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn build_order(
+        &self,
+        order_id: String,
+        base_asset: &str,
+        quote_asset: &str,
+        position_side: PositionSide,
+        reduce_only: bool,
+        order_type: OrderType,
+        qty: Decimal,
+        price: Decimal,
+        filled: bool,
+    ) -> FuturesOrder {
+        let time = self.timestamp().await;
+        let order_side = if reduce_only {
+            position_side.close_side()
+        } else {
+            position_side.open_side()
+        };
+        let (executed_qty, order_status) = if filled {
+            (qty, OrderStatus::Filled)
+        } else {
+            (dec!(0), OrderStatus::New)
+        };
+
+        let order = Order::builder()
+            .exchange(Exchange::Binance)
+            .base_asset(base_asset)
+            .quote_asset(quote_asset)
+            .symbol(self.symbol(base_asset, quote_asset))
+            .order_id(order_id)
+            .price(price.to_string())
+            .avg_price(if filled { price } else { dec!(0) }.to_string())
+            .orig_qty(qty.to_string())
+            .executed_qty(executed_qty.to_string())
+            .cumulative_quote_qty((executed_qty * price).to_string())
+            .order_type(order_type)
+            .order_side(order_side)
+            .order_status(order_status)
+            .time(time)
+            .update_time(time)
+            .build();
+
+        FuturesOrder::builder()
+            .order(order)
+            .position_side(position_side)
+            .reduce_only(reduce_only)
+            .build()
+    }
+
+    // 市价单按最新价格全部成交
+    async fn market_order(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        position_side: PositionSide,
+        reduce_only: bool,
+        qty: f64,
+    ) -> Result<FuturesOrder> {
+        let symbol = self.symbol(base_asset, quote_asset);
+        let qty = Decimal::try_from(qty)?;
+        let price = self.price(&symbol).await?;
+        let mut data = self.data.lock().await;
+        self.sync(&mut data).await;
+
+        if qty <= dec!(0) {
+            anyhow::bail!("Invalid quantity");
+        }
+
+        let realized_pnl = if reduce_only {
+            if data.closable_qty(&symbol, position_side) < qty {
+                anyhow::bail!("Insufficient position");
+            }
+
+            data.close_position(&symbol, position_side, qty, price)?
+        } else {
+            data.open_position(&symbol, quote_asset, position_side, qty, price)?;
+            dec!(0)
+        };
+
+        let order_id = data.next_order_id();
+        let mut order = self
+            .build_order(
+                order_id,
+                base_asset,
+                quote_asset,
+                position_side,
+                reduce_only,
+                OrderType::Market,
+                qty,
+                price,
+                true,
+            )
+            .await;
+        order.realized_pnl = realized_pnl;
+
+        Ok(order)
+    }
+
+    // 限价单挂单后立即撮合一次，开仓单冻结保证金
+    async fn limit_order(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        position_side: PositionSide,
+        reduce_only: bool,
+        qty: f64,
+        price: f64,
+    ) -> Result<FuturesOrder> {
+        let symbol = self.symbol(base_asset, quote_asset);
+        let qty = Decimal::try_from(qty)?;
+        let price = Decimal::try_from(price)?;
+        let mut data = self.data.lock().await;
+        self.sync(&mut data).await;
+
+        if qty <= dec!(0) || price <= dec!(0) {
+            anyhow::bail!("Invalid quantity or price");
+        }
+
+        let reserved = if reduce_only {
+            if data.closable_qty(&symbol, position_side) < qty {
+                anyhow::bail!("Insufficient position");
+            }
+
+            dec!(0)
+        } else {
+            let reserved = qty * price / Decimal::from(data.leverage(&symbol));
+
+            if data.available(quote_asset) < reserved {
+                anyhow::bail!("Insufficient available balance");
+            }
+
+            reserved
+        };
+
+        let order_id = data.next_order_id();
+        let order = self
+            .build_order(
+                order_id.clone(),
+                base_asset,
+                quote_asset,
+                position_side,
+                reduce_only,
+                OrderType::Limit,
+                qty,
+                price,
+                false,
+            )
+            .await;
+
+        data.open_orders.push(OpenOrder {
+            order: order.clone(),
+            price,
+            reserved,
+        });
+
+        self.sync(&mut data).await;
+
+        // 立即成交时返回成交后的订单
+        if data
+            .open_orders
+            .iter()
+            .all(|open| open.order.order.order_id != order_id)
+        {
+            let mut filled = order;
+            filled.order.order_status = OrderStatus::Filled;
+            filled.order.executed_qty = filled.order.orig_qty.clone();
+            filled.order.avg_price = filled.order.price.clone();
+            filled.order.cumulative_quote_qty = (qty * price).to_string();
+            return Ok(filled);
+        }
+
+        Ok(order)
+    }
+}
+
+impl FuturesClientExecutable for BacktestFuturesClient {
+    fn exchange(&self) -> Exchange {
+        Exchange::Binance
+    }
+
+    fn market(&self) -> Market {
+        Market::Usdm
+    }
+
+    fn account_id(&self) -> String {
+        "backtest".to_string()
+    }
+
+    async fn get_balance(&self, asset: &str) -> Result<FuturesBalance> {
+        let mut data = self.data.lock().await;
+        self.sync(&mut data).await;
+
+        let mut unrealized_pnl = dec!(0);
+
+        for ((symbol, position_side), position) in &data.positions {
+            if position.quote_asset == asset {
+                let price = self.price(symbol).await?;
+                unrealized_pnl += position.unrealized_pnl(*position_side, price);
+            }
+        }
+
+        Ok(FuturesBalance::builder()
+            .asset(asset)
+            .wallet_balance(data.wallet(asset))
+            .available_balance(data.available(asset))
+            .unrealized_pnl(unrealized_pnl)
+            .build())
+    }
+
+    async fn get_positions(&self, base_asset: &str, quote_asset: &str) -> Result<Vec<Position>> {
+        let symbol = self.symbol(base_asset, quote_asset);
+        let mut data = self.data.lock().await;
+        self.sync(&mut data).await;
+
+        let mut positions = vec![];
+
+        for position_side in [PositionSide::Long, PositionSide::Short] {
+            let Some(position) = data.positions.get(&(symbol.clone(), position_side)) else {
+                continue;
+            };
+            let mark_price = self.price(&symbol).await?;
+
+            positions.push(
+                Position::builder()
+                    .symbol(symbol.clone())
+                    .position_side(position_side)
+                    .qty(position.qty)
+                    .entry_price(position.entry_price)
+                    .mark_price(mark_price)
+                    .leverage(data.leverage(&symbol))
+                    .unrealized_pnl(position.unrealized_pnl(position_side, mark_price))
+                    .liquidation_price(position.liquidation_price(position_side))
+                    .build(),
+            );
+        }
+
+        Ok(positions)
+    }
+
+    // 有持仓时不能调整杠杆，避免重新计算已占用的保证金
+    async fn set_leverage(&self, base_asset: &str, quote_asset: &str, leverage: u8) -> Result<u8> {
+        let symbol = self.symbol(base_asset, quote_asset);
+        let mut data = self.data.lock().await;
+
+        if !(1..=125).contains(&leverage) {
+            anyhow::bail!("Invalid leverage: {}", leverage);
+        }
+
+        if data.positions.keys().any(|(s, _)| s == &symbol) {
+            anyhow::bail!("Cannot change leverage with open positions");
+        }
+
+        data.leverages.insert(symbol, leverage);
+
+        Ok(leverage)
+    }
+
+    async fn get_funding_rate(&self, base_asset: &str, quote_asset: &str) -> Result<FundingRate> {
+        let symbol = self.symbol(base_asset, quote_asset);
+        let mark_price = self.price(&symbol).await?;
+        let mut data = self.data.lock().await;
+        self.sync(&mut data).await;
+
+        let next_funding_time = data
+            .funding_time
+            .map(|funding_time| (funding_time + FUNDING_INTERVAL_SECS) * 1000)
+            .unwrap_or_default();
+
+        Ok(FundingRate::builder()
+            .symbol(symbol)
+            .funding_rate(data.funding_rate)
+            .mark_price(mark_price)
+            .next_funding_time(next_funding_time)
+            .build())
+    }
+
+    async fn market_open(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        position_side: PositionSide,
+        qty: f64,
+    ) -> Result<FuturesOrder> {
+        self.market_order(base_asset, quote_asset, position_side, false, qty)
+            .await
+    }
+
+    async fn market_close(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        position_side: PositionSide,
+        qty: f64,
+    ) -> Result<FuturesOrder> {
+        self.market_order(base_asset, quote_asset, position_side, true, qty)
+            .await
+    }
+
+    async fn limit_open(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        position_side: PositionSide,
+        qty: f64,
+        price: f64,
+    ) -> Result<FuturesOrder> {
+        self.limit_order(base_asset, quote_asset, position_side, false, qty, price)
+            .await
+    }
+
+    async fn limit_close(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        position_side: PositionSide,
+        qty: f64,
+        price: f64,
+    ) -> Result<FuturesOrder> {
+        self.limit_order(base_asset, quote_asset, position_side, true, qty, price)
+            .await
+    }
+
+    async fn cancel_all_orders(&self, base_asset: &str, quote_asset: &str) -> Result<()> {
+        let symbol = self.symbol(base_asset, quote_asset);
+        let mut data = self.data.lock().await;
+        self.sync(&mut data).await;
+
+        data.open_orders
+            .retain(|open| open.order.order.symbol != symbol);
+
+        Ok(())
+    }
+}
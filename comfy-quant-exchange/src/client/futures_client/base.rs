@@ -0,0 +1,248 @@
+use crate::client::spot_client::base::{Order, OrderSide, OrderStatus, OrderType};
+use anyhow::{anyhow, Result};
+use binance::futures::{
+    account::PositionSide as BinancePositionSide,
+    model::{
+        AccountBalance as BinanceAccountBalance, MarkPrice as BinanceMarkPrice,
+        PositionRisk as BinancePositionRisk, Transaction as BinanceTransaction,
+    },
+};
+use bon::Builder;
+use comfy_quant_base::{Exchange, Symbol};
+use rust_decimal::{prelude::FromPrimitive, Decimal};
+use rust_decimal_macros::dec;
+use std::str::FromStr;
+
+#[derive(Builder)]
+#[builder(on(String, into))]
+pub struct BinanceFuturesTransaction {
+    base_asset: String,
+    quote_asset: String,
+    transaction: BinanceTransaction,
+}
+
+// 持仓方向，按双向持仓模式区分多空
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PositionSide {
+    Long,  // 多头
+    Short, // 空头
+}
+
+impl PositionSide {
+    // 开仓的买卖方向
+    pub fn open_side(&self) -> OrderSide {
+        match self {
+            PositionSide::Long => OrderSide::Buy,
+            PositionSide::Short => OrderSide::Sell,
+        }
+    }
+
+    // 平仓的买卖方向
+    pub fn close_side(&self) -> OrderSide {
+        match self {
+            PositionSide::Long => OrderSide::Sell,
+            PositionSide::Short => OrderSide::Buy,
+        }
+    }
+}
+
+impl FromStr for PositionSide {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "LONG" => Ok(PositionSide::Long),
+            "SHORT" => Ok(PositionSide::Short),
+            _ => anyhow::bail!("PositionSide parse failed. value: {}", s),
+        }
+    }
+}
+
+impl AsRef<str> for PositionSide {
+    fn as_ref(&self) -> &str {
+        match self {
+            PositionSide::Long => "LONG",
+            PositionSide::Short => "SHORT",
+        }
+    }
+}
+
+impl From<PositionSide> for BinancePositionSide {
+    fn from(value: PositionSide) -> Self {
+        match value {
+            PositionSide::Long => BinancePositionSide::Long,
+            PositionSide::Short => BinancePositionSide::Short,
+        }
+    }
+}
+
+// 合约账户的保证金资产余额
+#[derive(Builder, Debug, Clone)]
+#[builder(on(String, into))]
+pub struct FuturesBalance {
+    pub asset: String,              // 保证金资产
+    pub wallet_balance: Decimal,    // 钱包余额，包含已实现盈亏和资金费用
+    pub available_balance: Decimal, // 可用于开仓的余额
+    pub unrealized_pnl: Decimal,    // 未实现盈亏
+}
+
+impl FuturesBalance {
+    // 保证金余额 = 钱包余额 + 未实现盈亏
+    pub fn margin_balance(&self) -> Decimal {
+        self.wallet_balance + self.unrealized_pnl
+    }
+}
+
+impl TryFrom<BinanceAccountBalance> for FuturesBalance {
+    type Error = anyhow::Error;
+
+    fn try_from(value: BinanceAccountBalance) -> Result<Self, Self::Error> {
+        Ok(FuturesBalance::builder()
+            .asset(value.asset)
+            .wallet_balance(to_decimal(value.balance, "balance")?)
+            .available_balance(to_decimal(value.available_balance, "available balance")?)
+            .unrealized_pnl(to_decimal(value.cross_unrealized_pnl, "unrealized pnl")?)
+            .build())
+    }
+}
+
+// 合约持仓
+#[derive(Builder, Debug, Clone)]
+#[builder(on(Symbol, into))]
+pub struct Position {
+    pub symbol: Symbol,                     // 交易对
+    pub position_side: PositionSide,        // 持仓方向
+    pub qty: Decimal,                       // 持仓数量，多空都为正数
+    pub entry_price: Decimal,               // 开仓均价
+    pub mark_price: Decimal,                // 标记价格
+    pub leverage: u8,                       // 杠杆倍数
+    pub unrealized_pnl: Decimal,            // 未实现盈亏
+    pub liquidation_price: Option<Decimal>, // 强平价格
+}
+
+impl Position {
+    // 持仓名义价值
+    pub fn notional(&self) -> Decimal {
+        self.qty * self.mark_price
+    }
+}
+
+impl TryFrom<BinancePositionRisk> for Position {
+    type Error = anyhow::Error;
+
+    fn try_from(value: BinancePositionRisk) -> Result<Self, Self::Error> {
+        let qty = to_decimal(value.position_amount, "position amount")?;
+
+        // 单向持仓模式(BOTH)按数量的正负区分多空
+        let position_side = match value.position_side.as_str() {
+            "BOTH" if qty < dec!(0) => PositionSide::Short,
+            "BOTH" => PositionSide::Long,
+            side => side.parse()?,
+        };
+
+        let liquidation_price = to_decimal(value.liquidation_price, "liquidation price")?;
+
+        Ok(Position::builder()
+            .symbol(value.symbol)
+            .position_side(position_side)
+            .qty(qty.abs())
+            .entry_price(to_decimal(value.entry_price, "entry price")?)
+            .mark_price(to_decimal(value.mark_price, "mark price")?)
+            .leverage(value.leverage.parse()?)
+            .unrealized_pnl(to_decimal(value.unrealized_profit, "unrealized profit")?)
+            .maybe_liquidation_price((!liquidation_price.is_zero()).then_some(liquidation_price))
+            .build())
+    }
+}
+
+// 资金费率
+#[derive(Builder, Debug, Clone)]
+#[builder(on(Symbol, into))]
+pub struct FundingRate {
+    pub symbol: Symbol,         // 交易对
+    pub funding_rate: Decimal,  // 资金费率，为正时多头向空头支付
+    pub mark_price: Decimal,    // 标记价格
+    pub next_funding_time: i64, // 下次结算时间(毫秒)
+}
+
+impl TryFrom<BinanceMarkPrice> for FundingRate {
+    type Error = anyhow::Error;
+
+    fn try_from(value: BinanceMarkPrice) -> Result<Self, Self::Error> {
+        Ok(FundingRate::builder()
+            .symbol(value.symbol)
+            .funding_rate(to_decimal(value.last_funding_rate, "funding rate")?)
+            .mark_price(to_decimal(value.mark_price, "mark price")?)
+            .next_funding_time(value.next_funding_time as i64)
+            .build())
+    }
+}
+
+// 合约订单
+#[derive(Builder, Debug, Clone)]
+pub struct FuturesOrder {
+    pub order: Order,                // 订单
+    pub position_side: PositionSide, // 持仓方向
+    pub reduce_only: bool,           // 是否为平仓单
+    #[builder(default)]
+    pub realized_pnl: Decimal, // 平仓的已实现盈亏，不含手续费
+}
+
+impl TryFrom<BinanceFuturesTransaction> for FuturesOrder {
+    type Error = anyhow::Error;
+
+    fn try_from(value: BinanceFuturesTransaction) -> Result<Self, Self::Error> {
+        let tx = value.transaction;
+        let order_side = tx.side.parse::<OrderSide>()?;
+        let position_side = tx.position_side.parse::<PositionSide>()?;
+
+        let order = Order::builder()
+            .exchange(Exchange::Binance)
+            .base_asset(value.base_asset)
+            .quote_asset(value.quote_asset)
+            .symbol(tx.symbol)
+            .order_id(tx.order_id.to_string())
+            .client_order_id(tx.client_order_id)
+            .price(tx.avg_price.to_string())
+            .avg_price(tx.avg_price.to_string())
+            .orig_qty(tx.orig_qty.to_string())
+            .executed_qty(tx.executed_qty.to_string())
+            .cumulative_quote_qty(tx.cum_quote.to_string())
+            .order_type(tx.type_name.parse::<OrderType>()?)
+            .order_side(order_side.clone())
+            .order_status(tx.status.parse::<OrderStatus>()?)
+            .time(tx.update_time as i64)
+            .update_time(tx.update_time as i64)
+            .build();
+
+        Ok(FuturesOrder::builder()
+            .order(order)
+            .position_side(position_side)
+            .reduce_only(order_side == position_side.close_side())
+            .build())
+    }
+}
+
+fn to_decimal(value: f64, field: &str) -> Result<Decimal> {
+    Decimal::from_f64(value)
+        .ok_or_else(|| anyhow!("binance futures {} convert decimal failed", field))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_position_side() -> Result<()> {
+        assert_eq!("LONG".parse::<PositionSide>()?, PositionSide::Long);
+        assert_eq!(PositionSide::Short.as_ref(), "SHORT");
+        assert!("BOTH".parse::<PositionSide>().is_err());
+
+        assert_eq!(PositionSide::Long.open_side(), OrderSide::Buy);
+        assert_eq!(PositionSide::Long.close_side(), OrderSide::Sell);
+        assert_eq!(PositionSide::Short.open_side(), OrderSide::Sell);
+        assert_eq!(PositionSide::Short.close_side(), OrderSide::Buy);
+
+        Ok(())
+    }
+}
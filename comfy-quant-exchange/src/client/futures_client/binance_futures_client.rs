@@ -0,0 +1,198 @@
+use super::base::{
+    BinanceFuturesTransaction, FundingRate, FuturesBalance, FuturesOrder, Position, PositionSide,
+};
+use crate::{
+    client::{
+        futures_client_kind::{FuturesClientExecutable, FuturesClientExecutableExt},
+        spot_client::base::OrderSide,
+    },
+    exchange::{binance::BinanceClient, ConnectionOptions},
+};
+use anyhow::Result;
+use binance::{account::OrderSide as BinanceOrderSide, config::Config};
+use bon::bon;
+use comfy_quant_base::{Exchange, Market};
+
+#[derive(Debug, Clone)]
+pub struct BinanceFuturesClient {
+    client: BinanceClient,
+}
+
+#[bon]
+impl BinanceFuturesClient {
+    #[builder(on(String, into))]
+    pub fn new(
+        api_key: Option<String>,
+        secret_key: Option<String>,
+        config: Option<Config>,
+        connection: Option<ConnectionOptions>,
+    ) -> Result<Self> {
+        let client = BinanceClient::builder()
+            .maybe_api_key(api_key)
+            .maybe_secret_key(secret_key)
+            .maybe_config(config)
+            .maybe_connection(connection)
+            .build()?;
+
+        Ok(BinanceFuturesClient { client })
+    }
+
+    // 双向持仓模式下按持仓方向下单，price为空时下市价单
+    async fn position_order(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        position_side: PositionSide,
+        reduce_only: bool,
+        qty: f64,
+        price: Option<f64>,
+    ) -> Result<FuturesOrder> {
+        let symbol = self.symbol(base_asset, quote_asset);
+        let side = if reduce_only {
+            position_side.close_side()
+        } else {
+            position_side.open_side()
+        };
+        let side = match side {
+            OrderSide::Buy => BinanceOrderSide::Buy,
+            OrderSide::Sell => BinanceOrderSide::Sell,
+        };
+
+        let tx = self
+            .client
+            .futures()
+            .position_order(symbol, side, position_side.into(), qty, price)
+            .await?;
+
+        BinanceFuturesTransaction::builder()
+            .base_asset(base_asset)
+            .quote_asset(quote_asset)
+            .transaction(tx)
+            .build()
+            .try_into()
+    }
+}
+
+impl FuturesClientExecutable for BinanceFuturesClient {
+    fn exchange(&self) -> Exchange {
+        Exchange::Binance
+    }
+
+    fn market(&self) -> Market {
+        Market::Usdm
+    }
+
+    // 以API Key标识账户
+    fn account_id(&self) -> String {
+        self.client.api_key().unwrap_or_default().to_string()
+    }
+
+    async fn get_balance(&self, asset: &str) -> Result<FuturesBalance> {
+        let asset = asset.to_uppercase();
+        self.client.futures().get_asset(asset).await?.try_into()
+    }
+
+    async fn get_positions(&self, base_asset: &str, quote_asset: &str) -> Result<Vec<Position>> {
+        let symbol = self.symbol(base_asset, quote_asset);
+
+        let mut positions = self
+            .client
+            .futures()
+            .get_positions(symbol)
+            .await?
+            .into_iter()
+            .map(Position::try_from)
+            .filter(|position| {
+                position
+                    .as_ref()
+                    .map_or(true, |position| !position.qty.is_zero())
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        positions.sort_by_key(|position| position.position_side != PositionSide::Long);
+
+        Ok(positions)
+    }
+
+    async fn set_leverage(&self, base_asset: &str, quote_asset: &str, leverage: u8) -> Result<u8> {
+        let symbol = self.symbol(base_asset, quote_asset);
+        self.client
+            .futures()
+            .change_leverage(symbol, leverage)
+            .await
+    }
+
+    async fn get_funding_rate(&self, base_asset: &str, quote_asset: &str) -> Result<FundingRate> {
+        let symbol = self.symbol(base_asset, quote_asset);
+        self.client
+            .futures()
+            .get_mark_price(symbol)
+            .await?
+            .try_into()
+    }
+
+    async fn market_open(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        position_side: PositionSide,
+        qty: f64,
+    ) -> Result<FuturesOrder> {
+        self.position_order(base_asset, quote_asset, position_side, false, qty, None)
+            .await
+    }
+
+    async fn market_close(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        position_side: PositionSide,
+        qty: f64,
+    ) -> Result<FuturesOrder> {
+        self.position_order(base_asset, quote_asset, position_side, true, qty, None)
+            .await
+    }
+
+    async fn limit_open(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        position_side: PositionSide,
+        qty: f64,
+        price: f64,
+    ) -> Result<FuturesOrder> {
+        self.position_order(
+            base_asset,
+            quote_asset,
+            position_side,
+            false,
+            qty,
+            Some(price),
+        )
+        .await
+    }
+
+    async fn limit_close(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        position_side: PositionSide,
+        qty: f64,
+        price: f64,
+    ) -> Result<FuturesOrder> {
+        self.position_order(
+            base_asset,
+            quote_asset,
+            position_side,
+            true,
+            qty,
+            Some(price),
+        )
+        .await
+    }
+
+    async fn cancel_all_orders(&self, base_asset: &str, quote_asset: &str) -> Result<()> {
+        let symbol = self.symbol(base_asset, quote_asset);
+        self.client.futures().cancel_all_open_orders(symbol).await
+    }
+}
@@ -0,0 +1,3 @@
+pub mod backtest_futures_client;
+pub mod base;
+pub mod binance_futures_client;
@@ -0,0 +1,256 @@
+use super::futures_client::{
+    backtest_futures_client::BacktestFuturesClient,
+    base::{FundingRate, FuturesBalance, FuturesOrder, Position, PositionSide},
+    binance_futures_client::BinanceFuturesClient,
+};
+use anyhow::Result;
+use comfy_quant_base::{Exchange, Market, Symbol};
+use enum_dispatch::enum_dispatch;
+
+#[enum_dispatch]
+#[allow(async_fn_in_trait)]
+pub trait FuturesClientExecutable {
+    fn exchange(&self) -> Exchange;
+
+    // 合约市场，如U本位合约
+    fn market(&self) -> Market;
+
+    // 账户标识
+    fn account_id(&self) -> String;
+
+    // 获取保证金资产余额
+    async fn get_balance(&self, asset: &str) -> Result<FuturesBalance>;
+
+    // 获取交易对的持仓，没有持仓的方向不返回
+    async fn get_positions(&self, base_asset: &str, quote_asset: &str) -> Result<Vec<Position>>;
+
+    // 调整杠杆倍数，返回调整后的倍数
+    async fn set_leverage(&self, base_asset: &str, quote_asset: &str, leverage: u8) -> Result<u8>;
+
+    // 获取当前资金费率
+    async fn get_funding_rate(&self, base_asset: &str, quote_asset: &str) -> Result<FundingRate>;
+
+    // 市价开仓
+    async fn market_open(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        position_side: PositionSide,
+        qty: f64,
+    ) -> Result<FuturesOrder>;
+
+    // 市价平仓
+    async fn market_close(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        position_side: PositionSide,
+        qty: f64,
+    ) -> Result<FuturesOrder>;
+
+    // 限价开仓
+    async fn limit_open(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        position_side: PositionSide,
+        qty: f64,
+        price: f64,
+    ) -> Result<FuturesOrder>;
+
+    // 限价平仓
+    async fn limit_close(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        position_side: PositionSide,
+        qty: f64,
+        price: f64,
+    ) -> Result<FuturesOrder>;
+
+    // 撤销交易对的所有挂单
+    async fn cancel_all_orders(&self, base_asset: &str, quote_asset: &str) -> Result<()>;
+}
+
+impl<T: ?Sized> FuturesClientExecutableExt for T where T: FuturesClientExecutable {}
+
+pub trait FuturesClientExecutableExt: FuturesClientExecutable {
+    fn symbol(&self, base_asset: &str, quote_asset: &str) -> Symbol {
+        self.exchange().symbol(base_asset, quote_asset)
+    }
+}
+
+#[derive(Debug, Clone)]
+#[enum_dispatch(FuturesClientExecutable)]
+#[allow(clippy::large_enum_variant)]
+pub enum FuturesClientKind {
+    BacktestFuturesClient(BacktestFuturesClient),
+    BinanceFuturesClient(BinanceFuturesClient),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        client::spot_client::base::{OrderSide, OrderStatus, SymbolPrice},
+        store::PriceStore,
+    };
+    use async_lock::RwLock;
+    use rust_decimal_macros::dec;
+    use std::sync::Arc;
+
+    async fn save_price(
+        price_store: &RwLock<PriceStore>,
+        price: rust_decimal::Decimal,
+    ) -> Result<()> {
+        price_store.write().await.save_price(
+            &Exchange::Binance,
+            &Market::Usdm,
+            &SymbolPrice::builder()
+                .symbol("BTCUSDT".into())
+                .price(price)
+                .build(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_futures_client_long_short() -> Result<()> {
+        let price_store = Arc::new(RwLock::new(PriceStore::new()));
+        save_price(&price_store, dec!(100)).await?;
+
+        let client: FuturesClientKind = BacktestFuturesClient::builder()
+            .assets(vec![("USDT".to_string(), 1000.)])
+            .commissions(0.0005)
+            .price_store(Arc::clone(&price_store))
+            .build()
+            .into();
+
+        assert_eq!(client.set_leverage("BTC", "USDT", 5).await?, 5);
+
+        // 开多5个，名义价值500，保证金100，手续费0.25
+        let order = client
+            .market_open("BTC", "USDT", PositionSide::Long, 5.)
+            .await?;
+        assert_eq!(order.order.order_side, OrderSide::Buy);
+        assert!(matches!(order.order.order_status, OrderStatus::Filled));
+        assert!(!order.reduce_only);
+
+        let balance = client.get_balance("USDT").await?;
+        assert_eq!(balance.wallet_balance, dec!(999.75));
+        assert_eq!(balance.available_balance, dec!(899.75));
+
+        // 开空2个
+        client
+            .market_open("BTC", "USDT", PositionSide::Short, 2.)
+            .await?;
+
+        // 价格上涨10%，多头盈利50，空头亏损20
+        save_price(&price_store, dec!(110)).await?;
+
+        let positions = client.get_positions("BTC", "USDT").await?;
+        assert_eq!(positions.len(), 2);
+        assert_eq!(positions[0].position_side, PositionSide::Long);
+        assert_eq!(positions[0].unrealized_pnl, dec!(50));
+        assert_eq!(positions[0].leverage, 5);
+        assert_eq!(positions[1].unrealized_pnl, dec!(-20));
+
+        let balance = client.get_balance("USDT").await?;
+        assert_eq!(balance.unrealized_pnl, dec!(30));
+
+        // 平多3个，已实现盈亏30，手续费0.165
+        let order = client
+            .market_close("BTC", "USDT", PositionSide::Long, 3.)
+            .await?;
+        assert!(order.reduce_only);
+        assert_eq!(order.realized_pnl, dec!(30));
+
+        let positions = client.get_positions("BTC", "USDT").await?;
+        assert_eq!(positions[0].qty, dec!(2));
+
+        // 平仓数量不能超过持仓
+        assert!(client
+            .market_close("BTC", "USDT", PositionSide::Short, 3.)
+            .await
+            .is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_futures_client_limit_order_and_funding() -> Result<()> {
+        let price_store = Arc::new(RwLock::new(PriceStore::new()));
+        save_price(&price_store, dec!(100)).await?;
+        price_store.write().await.save_timestamp(0);
+
+        let client: FuturesClientKind = BacktestFuturesClient::builder()
+            .assets(vec![("USDT".to_string(), 1000.)])
+            .commissions(0.)
+            .funding_rate(0.001)
+            .price_store(Arc::clone(&price_store))
+            .build()
+            .into();
+
+        // 限价开多，价格未触及时冻结保证金
+        let order = client
+            .limit_open("BTC", "USDT", PositionSide::Long, 10., 90.)
+            .await?;
+        assert!(matches!(order.order.order_status, OrderStatus::New));
+        assert_eq!(
+            client.get_balance("USDT").await?.available_balance,
+            dec!(100)
+        );
+        assert!(client.get_positions("BTC", "USDT").await?.is_empty());
+
+        // 价格跌破限价后成交
+        save_price(&price_store, dec!(90)).await?;
+        let positions = client.get_positions("BTC", "USDT").await?;
+        assert_eq!(positions.len(), 1);
+        assert_eq!(positions[0].entry_price, dec!(90));
+
+        // 8小时后结算资金费用，多头支付 900 * 0.001
+        price_store.write().await.save_timestamp(8 * 3600);
+        let balance = client.get_balance("USDT").await?;
+        assert_eq!(balance.wallet_balance, dec!(999.1));
+
+        let funding_rate = client.get_funding_rate("BTC", "USDT").await?;
+        assert_eq!(funding_rate.funding_rate, dec!(0.001));
+        assert_eq!(funding_rate.next_funding_time, 16 * 3600 * 1000);
+
+        // 撤销挂单后解冻保证金
+        client
+            .limit_close("BTC", "USDT", PositionSide::Long, 10., 120.)
+            .await?;
+        client.cancel_all_orders("BTC", "USDT").await?;
+        assert_eq!(client.get_positions("BTC", "USDT").await?[0].qty, dec!(10));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_futures_client_liquidation() -> Result<()> {
+        let price_store = Arc::new(RwLock::new(PriceStore::new()));
+        save_price(&price_store, dec!(100)).await?;
+
+        let client: FuturesClientKind = BacktestFuturesClient::builder()
+            .assets(vec![("USDT".to_string(), 100.)])
+            .commissions(0.)
+            .price_store(Arc::clone(&price_store))
+            .build()
+            .into();
+
+        client.set_leverage("BTC", "USDT", 10).await?;
+        client
+            .market_open("BTC", "USDT", PositionSide::Long, 10.)
+            .await?;
+
+        let positions = client.get_positions("BTC", "USDT").await?;
+        assert_eq!(positions[0].liquidation_price, Some(dec!(90.5)));
+
+        // 价格跌破强平价格后按标记价格强制平仓，损失全部保证金
+        save_price(&price_store, dec!(90)).await?;
+        assert!(client.get_positions("BTC", "USDT").await?.is_empty());
+        assert_eq!(client.get_balance("USDT").await?.wallet_balance, dec!(0));
+
+        Ok(())
+    }
+}
@@ -1,4 +1,6 @@
 mod client_error;
+pub mod futures_client;
+pub mod futures_client_kind;
 pub mod spot_client;
 pub mod spot_client_kind;
 
@@ -1,6 +1,12 @@
-use super::base::{
-    AccountInformation, Balance, Order, OrderSide, OrderStatus, OrderType, SymbolInformation,
-    SymbolPrice,
+use super::{
+    base::{
+        AccountInformation, Balance, MarginAccount, MarginAsset, MarginTransaction, Order,
+        OrderIntent, OrderSide, OrderStatus, OrderType, OrderUpdate, SymbolInformation,
+        SymbolPrice, UserDataEvent, MARGIN_LEVEL_CALL, MARGIN_LEVEL_INITIAL, MARGIN_LEVEL_MAX,
+    },
+    execution_model::ExecutionModel,
+    fee_schedule::FeeSchedule,
+    queue_model::{QueueModel, QueuePosition},
 };
 use crate::{
     client::spot_client_kind::{SpotClientExecutable, SpotclientExecutableExt},
@@ -8,12 +14,28 @@ use crate::{
 };
 use anyhow::Result;
 use async_lock::RwLock;
-use bon::bon;
+use bon::{bon, Builder};
 use comfy_quant_base::{Exchange, Market, Symbol};
-use rust_decimal::Decimal;
+use rust_decimal::{Decimal, RoundingStrategy};
 use rust_decimal_macros::dec;
-use std::{collections::HashMap, sync::Arc};
-use tokio::sync::Mutex;
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    sync::Arc,
+};
+use tokio::sync::{broadcast, Mutex};
+
+const MARGIN_VALUE_ASSET: &str = "USDT"; // 杠杆账户估值币种
+const DEFAULT_MARGIN_DAILY_INTEREST_RATE: Decimal = dec!(0.0002); // 默认借款日利率
+const USER_DATA_CAPACITY: usize = 1024; // 用户数据事件的缓冲数量
+
+// 回测交易对的交易所过滤器，与实盘的 PRICE_FILTER、LOT_SIZE 和 MIN_NOTIONAL 对应
+#[derive(Builder, Debug, Clone, Default)]
+pub struct SymbolFilters {
+    tick_size: Option<Decimal>,    // 价格步长
+    step_size: Option<Decimal>,    // 数量步长
+    min_qty: Option<Decimal>,      // 最小下单数量
+    min_notional: Option<Decimal>, // 最小名义价值
+}
 
 #[derive(Debug)]
 pub struct BacktestSpotClientData {
@@ -21,12 +43,646 @@ pub struct BacktestSpotClientData {
     commissions: Option<f64>,
     order_id: u64,
     order_history: Vec<Order>,
+    client_orders: HashMap<String, Order>, // 按客户端订单ID记录下单时返回的订单
+    open_orders: Vec<Order>,               // 未成交的挂单，按提交顺序撮合
+    reserved: HashMap<String, Decimal>,    // 挂单冻结的资产数量
+    triggered_stops: HashSet<String>,      // 已触发的止损限价单
+    oco_links: HashMap<String, String>,    // OCO订单的另一个订单
+    queue_model: Option<QueueModel>,       // 挂单排队模型，未设置时价格触及限价即成交
+    queues: HashMap<String, QueuePosition>, // 挂单的排队位置
+    execution_model: ExecutionModel,       // 市价单执行模型，默认无滑点和延迟
+    delayed_orders: Vec<(u64, Order)>, // 等待延迟或剩余部分待成交的市价单，(成交的tick序号, 订单)
+    participation: Option<Decimal>,    // 成交量参与率，每个周期最多成交周期成交量的该比例
+    fill_times: HashMap<String, i64>,  // 部分成交订单最近一次成交的时间，每个周期最多成交一次
+    matched_tick: Option<u64>,         // 最近一次在tick上撮合时的tick序号
+    fee_schedule: Option<FeeSchedule>, // 手续费表，设置后按VIP等级计算手续费
+    symbol_commissions: HashMap<Symbol, Decimal>, // 按交易对覆盖的手续费率，如零手续费活动交易对
+    symbol_filters: HashMap<Symbol, SymbolFilters>, // 交易对的交易所过滤器，未设置时不限制
+    vip_level: u8,                     // VIP等级
+    tier_progression: bool,            // 是否随成交额累积升级
+    trade_volume: Decimal,             // 累计成交额，回测中近似为30日成交额
+    margin: bool,                      // 是否开启模拟杠杆账户
+    margin_interest_rates: HashMap<String, Decimal>, // 借款日利率
+    borrowed: HashMap<String, Decimal>, // 借款
+    interest: HashMap<String, Decimal>, // 未还利息
+    interest_time: Option<i64>,        // 最后一次计息时间(秒)
+    tran_id: u64,                      // 借还款交易ID
+    user_data: broadcast::Sender<UserDataEvent>, // 订单后续成交的推送
+}
+
+impl BacktestSpotClientData {
+    // 挂单、吃单手续费率
+    fn commission_rates(&self) -> Result<(Decimal, Decimal)> {
+        // 按手续费表计算
+        if let Some(fee_schedule) = &self.fee_schedule {
+            let tier = if self.tier_progression {
+                fee_schedule.tier_with_progression(self.vip_level, &self.trade_volume)
+            } else {
+                fee_schedule.tier_by_level(self.vip_level)
+            }
+            .ok_or(anyhow::anyhow!("Fee schedule is empty"))?;
+
+            return Ok((tier.maker_rate, tier.taker_rate));
+        }
+
+        let commissions = self.commissions.unwrap_or(0.001);
+        let commission_rate: Decimal = commissions.try_into()?;
+
+        Ok((commission_rate, commission_rate))
+    }
+
+    // 交易对的挂单、吃单手续费率，单独设置的费率优先于账户费率
+    fn symbol_commission_rates(&self, symbol: &Symbol) -> Result<(Decimal, Decimal)> {
+        match self.symbol_commissions.get(symbol) {
+            Some(commission_rate) => Ok((*commission_rate, *commission_rate)),
+            None => self.commission_rates(),
+        }
+    }
+
+    fn free(&self, asset: &str) -> Result<Decimal> {
+        match self.assets.get(asset) {
+            Some(balance) => Ok(balance.free.parse()?),
+            None => Ok(dec!(0)),
+        }
+    }
+
+    fn locked(&self, asset: &str) -> Result<Decimal> {
+        match self.assets.get(asset) {
+            Some(balance) => Ok(balance.locked.parse()?),
+            None => Ok(dec!(0)),
+        }
+    }
+
+    fn balance_mut(&mut self, asset: &str) -> &mut Balance {
+        self.assets.entry(asset.to_string()).or_insert(
+            Balance::builder()
+                .asset(asset)
+                .free("0")
+                .locked("0")
+                .build(),
+        )
+    }
+
+    fn add_free(&mut self, asset: &str, amount: Decimal) -> Result<()> {
+        let free = self.free(asset)?;
+        self.balance_mut(asset).free = (free + amount).to_string();
+
+        Ok(())
+    }
+
+    fn sub_free(&mut self, asset: &str, amount: Decimal) -> Result<()> {
+        let free = self.free(asset)?;
+
+        if free < amount {
+            return Err(anyhow::anyhow!("Insufficient free balance"));
+        }
+
+        self.add_free(asset, -amount)
+    }
+
+    // 冻结挂单占用的资产
+    fn lock(&mut self, asset: &str, amount: Decimal) -> Result<()> {
+        self.sub_free(asset, amount)?;
+
+        let locked = self.locked(asset)?;
+        self.balance_mut(asset).locked = (locked + amount).to_string();
+
+        Ok(())
+    }
+
+    fn unlock(&mut self, asset: &str, amount: Decimal) -> Result<()> {
+        let locked = self.locked(asset)?;
+
+        if locked < amount {
+            return Err(anyhow::anyhow!("Insufficient locked balance"));
+        }
+
+        self.balance_mut(asset).locked = (locked - amount).to_string();
+        self.add_free(asset, amount)
+    }
+
+    // 按成交价格和手续费率结算资产，手续费从到账资产中扣除
+    fn settle(
+        &mut self,
+        base_asset: &str,
+        quote_asset: &str,
+        side: &OrderSide,
+        qty: Decimal,
+        price: Decimal,
+        commission_rate: Decimal,
+    ) -> Result<()> {
+        let quote_qty = qty * price;
+
+        match side {
+            OrderSide::Buy => {
+                self.sub_free(quote_asset, quote_qty)?;
+                self.add_free(base_asset, qty - qty * commission_rate)?;
+            }
+            OrderSide::Sell => {
+                self.sub_free(base_asset, qty)?;
+                self.add_free(quote_asset, quote_qty - quote_qty * commission_rate)?;
+            }
+        }
+
+        self.trade_volume += quote_qty;
+
+        Ok(())
+    }
+
+    // 市价单下单前检查可用余额，买单按最新价格估算所需的计价货币
+    fn ensure_market_balance(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        side: &OrderSide,
+        qty: Decimal,
+        price: Decimal,
+    ) -> Result<()> {
+        let (asset, amount) = match side {
+            OrderSide::Buy => (quote_asset, qty * price),
+            OrderSide::Sell => (base_asset, qty),
+        };
+
+        if self.free(asset)? < amount {
+            return Err(anyhow::anyhow!("Insufficient free balance"));
+        }
+
+        Ok(())
+    }
+
+    // 下一个订单ID
+    fn next_order_id(&self) -> String {
+        (self.order_id + 1).to_string()
+    }
+
+    // 冻结挂单占用的资产后挂单
+    fn open_order(&mut self, order: Order, amount: Decimal) -> Result<()> {
+        let asset = match order.order_side {
+            OrderSide::Buy => order.quote_asset()?,
+            OrderSide::Sell => order.base_asset()?,
+        }
+        .to_string();
+
+        self.lock(&asset, amount)?;
+        self.reserved.insert(order.order_id.clone(), amount);
+        self.order_id += 1;
+        self.open_orders.push(order);
+
+        Ok(())
+    }
+
+    // 解冻挂单占用的资产
+    fn release(&mut self, order: &Order) -> Result<()> {
+        let amount = self.reserved.remove(&order.order_id).unwrap_or_default();
+        self.queues.remove(&order.order_id);
+
+        match order.order_side {
+            OrderSide::Buy => self.unlock(order.quote_asset()?, amount),
+            OrderSide::Sell => self.unlock(order.base_asset()?, amount),
+        }
+    }
+
+    // 部分成交时按成交数量解冻挂单占用的资产
+    fn release_partial(&mut self, order: &Order, qty: Decimal) -> Result<()> {
+        let amount = match order.order_side {
+            OrderSide::Buy => qty * order.price.parse::<Decimal>()?,
+            OrderSide::Sell => qty,
+        };
+        let reserved = self.reserved.entry(order.order_id.clone()).or_default();
+        let amount = amount.min(*reserved);
+        *reserved -= amount;
+
+        match order.order_side {
+            OrderSide::Buy => self.unlock(order.quote_asset()?, amount),
+            OrderSide::Sell => self.unlock(order.base_asset()?, amount),
+        }
+    }
+
+    // 订单本周期可成交的数量。设置成交量参与率时最多成交周期成交量的该比例，
+    // 同一订单每个周期只成交一次；未设置或没有成交量数据时全部成交
+    fn fill_capacity(
+        &self,
+        order_id: &str,
+        remaining: Decimal,
+        volume: Option<Decimal>,
+        update_time: i64,
+    ) -> Decimal {
+        let Some(participation) = self.participation else {
+            return remaining;
+        };
+
+        if self
+            .fill_times
+            .get(order_id)
+            .is_some_and(|fill_time| *fill_time >= update_time)
+        {
+            return dec!(0);
+        }
+
+        let Some(volume) = volume else {
+            return remaining;
+        };
+
+        (volume * participation)
+            .round_dp_with_strategy(8, RoundingStrategy::ToZero)
+            .min(remaining)
+    }
+
+    // 累加订单的成交，更新成交均价和状态，返回是否全部成交
+    fn record_fill(
+        &mut self,
+        order: &mut Order,
+        qty: Decimal,
+        price: Decimal,
+        update_time: i64,
+    ) -> Result<bool> {
+        let orig_qty = order.orig_qty.parse::<Decimal>()?;
+        let executed_qty = order.executed_qty.parse::<Decimal>()? + qty;
+        let cumulative_quote_qty = order.cumulative_quote_qty.parse::<Decimal>()? + qty * price;
+        let filled = executed_qty >= orig_qty;
+
+        order.avg_price = if executed_qty == qty {
+            price
+        } else {
+            cumulative_quote_qty / executed_qty
+        }
+        .to_string();
+        order.executed_qty = executed_qty.to_string();
+        order.cumulative_quote_qty = cumulative_quote_qty.to_string();
+        order.order_status = if filled {
+            OrderStatus::Filled
+        } else {
+            OrderStatus::PartiallyFilled
+        };
+        order.update_time = update_time;
+
+        if filled {
+            self.fill_times.remove(&order.order_id);
+        } else {
+            self.fill_times.insert(order.order_id.clone(), update_time);
+        }
+
+        Ok(filled)
+    }
+
+    // 推送下单之后的成交，没有订阅者时忽略
+    fn push_order_update(
+        &self,
+        order: &Order,
+        last_qty: Decimal,
+        last_price: Decimal,
+        commission_rate: Decimal,
+    ) -> Result<()> {
+        let commission = match order.order_side {
+            OrderSide::Buy => last_qty * commission_rate,
+            OrderSide::Sell => last_qty * last_price * commission_rate,
+        };
+
+        let update = OrderUpdate::builder()
+            .exchange(order.exchange.clone())
+            .symbol(order.symbol.clone())
+            .order_id(order.order_id.clone())
+            .maybe_client_order_id(order.client_order_id.clone())
+            .order_type(order.order_type.clone())
+            .order_side(order.order_side.clone())
+            .order_status(order.order_status.clone())
+            .orig_qty(order.orig_qty.parse::<Decimal>()?)
+            .executed_qty(order.executed_qty.parse::<Decimal>()?)
+            .last_qty(last_qty)
+            .last_price(last_price)
+            .commission(commission)
+            .update_time(order.update_time)
+            .build();
+
+        let _ = self.user_data.send(UserDataEvent::OrderUpdate(update));
+
+        Ok(())
+    }
+
+    // 限价挂单按最近周期的成交量排队，没有成交量数据时不排队
+    fn join_queue(
+        &mut self,
+        order: &Order,
+        volume: Option<Decimal>,
+        volume_seq: u64,
+    ) -> Result<()> {
+        let (Some(queue_model), Some(volume)) = (self.queue_model, volume) else {
+            return Ok(());
+        };
+
+        let qty = order.orig_qty.parse::<Decimal>()?;
+        let position = QueuePosition::new(queue_model, volume, qty, volume_seq);
+        self.queues.insert(order.order_id.clone(), position);
+
+        Ok(())
+    }
+
+    // 挂单按限价成交，按挂单手续费率结算
+    fn fill_limit_order(&mut self, order: Order, qty: Decimal, update_time: i64) -> Result<Order> {
+        let (maker_rate, _) = self.symbol_commission_rates(&order.symbol)?;
+        let price = order.price.parse::<Decimal>()?;
+
+        self.fill_order(order, price, qty, maker_rate, update_time)
+    }
+
+    // 挂单按成交价格成交给定数量并推送成交。全部成交时解冻剩余的冻结资产，
+    // 部分成交时只解冻成交部分
+    fn fill_order(
+        &mut self,
+        mut order: Order,
+        price: Decimal,
+        qty: Decimal,
+        commission_rate: Decimal,
+        update_time: i64,
+    ) -> Result<Order> {
+        let base_asset = order.base_asset()?.to_string();
+        let quote_asset = order.quote_asset()?.to_string();
+        let remaining =
+            order.orig_qty.parse::<Decimal>()? - order.executed_qty.parse::<Decimal>()?;
+        let qty = qty.min(remaining);
+
+        if qty < remaining {
+            self.release_partial(&order, qty)?;
+        } else {
+            self.release(&order)?;
+        }
+
+        self.settle(
+            &base_asset,
+            &quote_asset,
+            &order.order_side,
+            qty,
+            price,
+            commission_rate,
+        )?;
+
+        self.record_fill(&mut order, qty, price, update_time)?;
+        self.push_order_update(&order, qty, price, commission_rate)?;
+
+        Ok(order)
+    }
+
+    // 延迟已满的市价单按最新价格成交并结算，受成交量参与率限制时剩余部分在之后的周期继续成交；
+    // 成交时余额不足的订单不再成交，未成交的拒绝，部分成交的剩余部分过期
+    fn fill_delayed_orders(
+        &mut self,
+        price_of: impl Fn(&Symbol) -> Option<Decimal>,
+        volume_of: impl Fn(&Symbol) -> Option<Decimal>,
+        ticks: u64,
+        update_time: i64,
+    ) -> Result<()> {
+        let delayed_orders = std::mem::take(&mut self.delayed_orders);
+
+        for (due_tick, mut order) in delayed_orders {
+            let price = price_of(&order.symbol).filter(|_| due_tick <= ticks);
+
+            let Some(price) = price else {
+                self.delayed_orders.push((due_tick, order));
+                continue;
+            };
+
+            let volume = volume_of(&order.symbol);
+            let remaining =
+                order.orig_qty.parse::<Decimal>()? - order.executed_qty.parse::<Decimal>()?;
+            let qty = self.fill_capacity(&order.order_id, remaining, volume, update_time);
+
+            if qty <= dec!(0) {
+                self.delayed_orders.push((due_tick, order));
+                continue;
+            }
+
+            let price = self
+                .execution_model
+                .fill_price(&order.order_side, price, qty, volume);
+            let (_, taker_rate) = self.symbol_commission_rates(&order.symbol)?;
+            let base_asset = order.base_asset()?.to_string();
+            let quote_asset = order.quote_asset()?.to_string();
+
+            if let Err(e) =
+                self.ensure_market_balance(&base_asset, &quote_asset, &order.order_side, qty, price)
+            {
+                tracing::warn!(order_id = %order.order_id, "Delayed market order stopped: {}", e);
+
+                order.order_status = if remaining < order.orig_qty.parse::<Decimal>()? {
+                    OrderStatus::Expired
+                } else {
+                    OrderStatus::Rejected
+                };
+                order.update_time = update_time;
+                self.fill_times.remove(&order.order_id);
+                self.push_order_update(&order, dec!(0), price, taker_rate)?;
+                self.order_history.push(order);
+                continue;
+            }
+
+            self.settle(
+                &base_asset,
+                &quote_asset,
+                &order.order_side,
+                qty,
+                price,
+                taker_rate,
+            )?;
+
+            let filled = self.record_fill(&mut order, qty, price, update_time)?;
+            self.push_order_update(&order, qty, price, taker_rate)?;
+
+            if filled {
+                self.order_history.push(order);
+            } else {
+                self.delayed_orders.push((due_tick, order));
+            }
+        }
+
+        Ok(())
+    }
+
+    // 撤销挂单，解冻占用的资产
+    fn cancel_order(&mut self, order_id: &str, update_time: i64) -> Result<Order> {
+        let index = self
+            .open_orders
+            .iter()
+            .position(|order| order.order_id == order_id)
+            .ok_or_else(|| anyhow::anyhow!("Open order not found: {}", order_id))?;
+
+        let mut order = self.open_orders.remove(index);
+        self.release(&order)?;
+        self.triggered_stops.remove(order_id);
+
+        if let Some(other_id) = self.oco_links.remove(order_id) {
+            self.oco_links.remove(&other_id);
+        }
+
+        order.order_status = OrderStatus::Canceled;
+        order.update_time = update_time;
+        self.order_history.push(order.clone());
+
+        Ok(order)
+    }
+
+    // 撤销挂单，OCO订单同时撤销另一个订单
+    fn cancel_order_list(&mut self, order_id: &str, update_time: i64) -> Result<Vec<Order>> {
+        let other_id = self.oco_links.get(order_id).cloned();
+        let mut orders = vec![self.cancel_order(order_id, update_time)?];
+
+        if let Some(other_id) = other_id {
+            orders.push(self.cancel_order(&other_id, update_time)?);
+        }
+
+        Ok(orders)
+    }
+
+    // OCO订单一个成交或触发后，另一个订单冻结的资产转给该订单，返回待撤销的订单
+    fn resolve_oco(&mut self, order_id: &str) -> Option<String> {
+        let other_id = self.oco_links.remove(order_id)?;
+        self.oco_links.remove(&other_id);
+
+        let amount = self.reserved.remove(&other_id).unwrap_or_default();
+        *self.reserved.entry(order_id.to_string()).or_default() += amount;
+
+        Some(other_id)
+    }
+
+    // 最新价格穿过限价的挂单成交。止损限价单在价格触及止损价后才参与撮合，
+    // 触发时已穿过限价则按最新价格吃单成交。设置排队模型时，限价挂单需等价位上的
+    // 累计成交量消耗完前方排队量后才成交。设置成交量参与率时，大额挂单每个周期
+    // 只成交一部分，未成交的部分继续挂单
+    fn match_orders(
+        &mut self,
+        price_of: impl Fn(&Symbol) -> Option<Decimal>,
+        volume_of: impl Fn(&Symbol) -> Option<Decimal>,
+        volume_seq_of: impl Fn(&Symbol) -> u64,
+        update_time: i64,
+    ) -> Result<()> {
+        let open_orders = std::mem::take(&mut self.open_orders);
+        let mut canceled = Vec::new();
+
+        for order in open_orders {
+            let price = price_of(&order.symbol).filter(|_| !canceled.contains(&order.order_id));
+
+            let Some(price) = price else {
+                self.open_orders.push(order);
+                continue;
+            };
+
+            let mut triggered = false;
+
+            if order.order_type == OrderType::StopLossLimit
+                && !self.triggered_stops.contains(&order.order_id)
+            {
+                let stop_price = order
+                    .stop_price
+                    .as_deref()
+                    .unwrap_or_default()
+                    .parse::<Decimal>()?;
+
+                if !is_triggered(&order.order_side, price, stop_price) {
+                    self.open_orders.push(order);
+                    continue;
+                }
+
+                triggered = true;
+                self.triggered_stops.insert(order.order_id.clone());
+                canceled.extend(self.resolve_oco(&order.order_id));
+            }
+
+            let limit_price = order.price.parse::<Decimal>()?;
+
+            if !is_crossed(&order.order_side, price, limit_price) {
+                self.open_orders.push(order);
+                continue;
+            }
+
+            if let Some(position) = self.queues.get_mut(&order.order_id) {
+                let volume = volume_of(&order.symbol).unwrap_or_default();
+
+                if !position.trade(volume, volume_seq_of(&order.symbol)) {
+                    self.open_orders.push(order);
+                    continue;
+                }
+            }
+
+            let remaining =
+                order.orig_qty.parse::<Decimal>()? - order.executed_qty.parse::<Decimal>()?;
+            let qty = self.fill_capacity(
+                &order.order_id,
+                remaining,
+                volume_of(&order.symbol),
+                update_time,
+            );
+
+            if qty <= dec!(0) {
+                self.open_orders.push(order);
+                continue;
+            }
+
+            // OCO订单的一个订单开始成交时撤销另一个订单
+            canceled.extend(self.resolve_oco(&order.order_id));
+
+            let order = if triggered {
+                let (_, taker_rate) = self.symbol_commission_rates(&order.symbol)?;
+                self.fill_order(order, price, qty, taker_rate, update_time)?
+            } else {
+                self.fill_limit_order(order, qty, update_time)?
+            };
+
+            if order.order_status.is_open() {
+                self.open_orders.push(order);
+            } else {
+                self.triggered_stops.remove(&order.order_id);
+                self.order_history.push(order);
+            }
+        }
+
+        for order_id in canceled {
+            self.cancel_order(&order_id, update_time)?;
+        }
+
+        Ok(())
+    }
+
+    fn hourly_interest_rate(&self, asset: &str) -> Decimal {
+        self.margin_interest_rates
+            .get(asset)
+            .cloned()
+            .unwrap_or(DEFAULT_MARGIN_DAILY_INTEREST_RATE)
+            / dec!(24)
+    }
+
+    // 按整小时累计利息，模拟时间取最新价格的时间
+    fn accrue_interest(&mut self, now: Option<i64>) {
+        let (Some(now), Some(interest_time)) = (now, self.interest_time) else {
+            return;
+        };
+
+        let hours = (now - interest_time) / 3600;
+
+        if hours <= 0 {
+            return;
+        }
+
+        let accrued = self
+            .borrowed
+            .iter()
+            .map(|(asset, borrowed)| {
+                let interest = borrowed * self.hourly_interest_rate(asset) * Decimal::from(hours);
+                (asset.clone(), interest)
+            })
+            .collect::<Vec<_>>();
+
+        for (asset, interest) in accrued {
+            *self.interest.entry(asset).or_default() += interest;
+        }
+
+        self.interest_time = Some(interest_time + hours * 3600);
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct BacktestSpotClient {
     data: Arc<Mutex<BacktestSpotClientData>>, // 必须使用内部可变性和Sync
     price_store: Arc<RwLock<PriceStore>>,     // 价格存储
+    user_data: broadcast::Sender<UserDataEvent>, // 订单后续成交的推送，克隆的客户端共享
 }
 
 #[bon]
@@ -37,6 +693,16 @@ impl BacktestSpotClient {
         #[builder(into)] assets: Vec<(String, f64)>,
         commissions: Option<f64>,
         price_store: Arc<RwLock<PriceStore>>,
+        fee_schedule: Option<FeeSchedule>,
+        #[builder(default)] vip_level: u8,
+        #[builder(default)] tier_progression: bool,
+        #[builder(default)] margin: bool, // 开启模拟杠杆账户
+        #[builder(default, into)] margin_interest_rates: Vec<(String, f64)>, // 借款日利率
+        #[builder(default, into)] symbol_commissions: Vec<(String, f64)>, // 按交易对覆盖的手续费率
+        #[builder(default, into)] symbol_filters: Vec<(String, SymbolFilters)>, // 交易对的交易所过滤器
+        queue_model: Option<QueueModel>,                                        // 挂单排队模型
+        execution_model: Option<ExecutionModel>, // 市价单执行模型: 滑点、冲击成本和下单延迟
+        participation_rate: Option<f64>,         // 成交量参与率，每个周期最多成交周期成交量的该比例
     ) -> Self {
         let assets = assets
             .into_iter()
@@ -51,22 +717,497 @@ impl BacktestSpotClient {
             })
             .collect();
 
+        let (user_data, _) = broadcast::channel(USER_DATA_CAPACITY);
+
         let data = Arc::new(Mutex::new(BacktestSpotClientData {
             assets,
             commissions,
             order_id: 0,
             order_history: Vec::new(),
+            client_orders: HashMap::new(),
+            open_orders: Vec::new(),
+            reserved: HashMap::new(),
+            triggered_stops: HashSet::new(),
+            oco_links: HashMap::new(),
+            queue_model,
+            queues: HashMap::new(),
+            execution_model: execution_model.unwrap_or_default(),
+            delayed_orders: Vec::new(),
+            participation: participation_rate
+                .and_then(|rate| Decimal::try_from(rate).ok())
+                .filter(|rate| *rate > dec!(0)),
+            fill_times: HashMap::new(),
+            matched_tick: None,
+            fee_schedule,
+            symbol_commissions: symbol_commissions
+                .into_iter()
+                .filter_map(|(symbol, rate)| {
+                    Some((symbol.to_uppercase().into(), Decimal::try_from(rate).ok()?))
+                })
+                .collect(),
+            symbol_filters: symbol_filters
+                .into_iter()
+                .map(|(symbol, filters)| (symbol.to_uppercase().into(), filters))
+                .collect(),
+            vip_level,
+            tier_progression,
+            trade_volume: Decimal::ZERO,
+            margin,
+            margin_interest_rates: margin_interest_rates
+                .into_iter()
+                .filter_map(|(asset, rate)| Some((asset, Decimal::try_from(rate).ok()?)))
+                .collect(),
+            borrowed: HashMap::new(),
+            interest: HashMap::new(),
+            interest_time: None,
+            tran_id: 0,
+            user_data: user_data.clone(),
         }));
 
-        BacktestSpotClient { data, price_store }
-    }
+        BacktestSpotClient {
+            data,
+            price_store,
+            user_data,
+        }
+    }
+
+    async fn price(&self, symbol: &Symbol) -> Decimal {
+        self.price_store
+            .read()
+            .await
+            .price(&Exchange::Binance, &Market::Spot, symbol)
+            .unwrap_or(dec!(0))
+    }
+
+    async fn volume(&self, symbol: &Symbol) -> Option<Decimal> {
+        self.price_store
+            .read()
+            .await
+            .volume(&Exchange::Binance, &Market::Spot, symbol)
+    }
+
+    async fn volume_seq(&self, symbol: &Symbol) -> u64 {
+        self.price_store
+            .read()
+            .await
+            .volume_seq(&Exchange::Binance, &Market::Spot, symbol)
+    }
+
+    // 数据源每个tick保存价格后调用，按该tick的价格撮合挂单和延迟已满的市价单，
+    // 成交通过用户数据流推送。同一个tick只撮合一次
+    pub async fn on_tick(&self) -> Result<()> {
+        let ticks = self.price_store.read().await.ticks();
+        let mut data = self.data.lock().await;
+
+        if data.matched_tick == Some(ticks) {
+            return Ok(());
+        }
+
+        data.matched_tick = Some(ticks);
+        self.match_orders(&mut data).await
+    }
+
+    // 市价单按最新价格加滑点成交。设置下单延迟时先返回未成交的订单，
+    // 延迟的tick数过后按当时的最新价格成交。设置成交量参与率时超出本周期
+    // 可成交数量的部分在之后的tick继续成交，先返回部分成交的订单
+    async fn market_order(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        qty: Decimal,
+        side: OrderSide,
+    ) -> Result<Order> {
+        let symbol = self.symbol(base_asset, quote_asset);
+        let (market_price, volume, ticks, now) = {
+            let price_store = self.price_store.read().await;
+            (
+                price_store
+                    .price(&Exchange::Binance, &Market::Spot, &symbol)
+                    .unwrap_or(dec!(0)),
+                price_store.volume(&Exchange::Binance, &Market::Spot, &symbol),
+                price_store.ticks(),
+                price_store.timestamp().unwrap_or_default() * 1000,
+            )
+        };
+        let mut data = self.data.lock().await;
+
+        data.ensure_market_balance(base_asset, quote_asset, &side, qty, market_price)?;
+        data.order_id += 1;
+
+        let latency_ticks = data.execution_model.latency_ticks();
+
+        if latency_ticks > 0 {
+            let order = Order::builder()
+                .exchange(Exchange::Binance)
+                .base_asset(base_asset)
+                .quote_asset(quote_asset)
+                .symbol(symbol)
+                .order_id(data.order_id.to_string())
+                .price(market_price.to_string())
+                .avg_price("0")
+                .orig_qty(qty.to_string())
+                .executed_qty("0")
+                .cumulative_quote_qty("0")
+                .order_type(OrderType::Market)
+                .order_side(side)
+                .order_status(OrderStatus::New)
+                .time(now)
+                .update_time(now)
+                .build();
+
+            data.delayed_orders
+                .push((ticks + u64::from(latency_ticks), order.clone()));
+
+            return Ok(order);
+        }
+
+        let order_id = data.order_id.to_string();
+        let fill_qty = data.fill_capacity(&order_id, qty, volume, now);
+
+        if fill_qty < qty {
+            let mut order = Order::builder()
+                .exchange(Exchange::Binance)
+                .base_asset(base_asset)
+                .quote_asset(quote_asset)
+                .symbol(symbol)
+                .order_id(order_id)
+                .price(market_price.to_string())
+                .avg_price("0")
+                .orig_qty(qty.to_string())
+                .executed_qty("0")
+                .cumulative_quote_qty("0")
+                .order_type(OrderType::Market)
+                .order_side(side.clone())
+                .order_status(OrderStatus::New)
+                .time(now)
+                .update_time(now)
+                .build();
+
+            if fill_qty > dec!(0) {
+                let price = data
+                    .execution_model
+                    .fill_price(&side, market_price, fill_qty, volume);
+                let (_, taker_rate) = data.symbol_commission_rates(&order.symbol)?;
+
+                data.settle(base_asset, quote_asset, &side, fill_qty, price, taker_rate)?;
+                data.record_fill(&mut order, fill_qty, price, now)?;
+                data.push_order_update(&order, fill_qty, price, taker_rate)?;
+            }
+
+            data.delayed_orders.push((ticks + 1, order.clone()));
+
+            return Ok(order);
+        }
+
+        let price = data
+            .execution_model
+            .fill_price(&side, market_price, qty, volume);
+        let (_, taker_rate) = data.symbol_commission_rates(&symbol)?;
+
+        data.settle(base_asset, quote_asset, &side, qty, price, taker_rate)?;
+
+        let order = Order::builder()
+            .exchange(Exchange::Binance)
+            .base_asset(base_asset)
+            .quote_asset(quote_asset)
+            .symbol(symbol)
+            .order_id(order_id)
+            .price(price.to_string())
+            .avg_price(price.to_string())
+            .orig_qty(qty.to_string())
+            .executed_qty(qty.to_string())
+            .cumulative_quote_qty((qty * price).to_string())
+            .order_type(OrderType::Market)
+            .order_side(side)
+            .order_status(OrderStatus::Filled)
+            .time(0)
+            .update_time(0)
+            .build();
+
+        data.push_order_update(&order, qty, price, taker_rate)?;
+        data.order_history.push(order.clone());
+
+        Ok(order)
+    }
+
+    // 限价单，价格已穿过限价时按最新价格立即成交，否则冻结资产挂单等待撮合。
+    // 受成交量参与率限制时立即成交一部分，剩余部分挂单
+    async fn limit_order(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        qty: f64,
+        price: f64,
+        side: OrderSide,
+    ) -> Result<Order> {
+        let symbol = self.symbol(base_asset, quote_asset);
+        let qty = Decimal::try_from(qty)?;
+        let limit_price = Decimal::try_from(price)?;
+
+        anyhow::ensure!(
+            qty > dec!(0) && limit_price > dec!(0),
+            "Invalid limit order, qty: {}, price: {}",
+            qty,
+            limit_price
+        );
+
+        let market_price = self.price(&symbol).await;
+        let volume = self.volume(&symbol).await;
+        let volume_seq = self.volume_seq(&symbol).await;
+        let now = self.timestamp().await.unwrap_or_default() * 1000;
+        let mut data = self.data.lock().await;
+
+        self.match_orders(&mut data).await?;
+
+        let crossed = market_price > dec!(0) && is_crossed(&side, market_price, limit_price);
+        let order_id = data.next_order_id();
+        let executed_qty = if crossed {
+            data.fill_capacity(&order_id, qty, volume, now)
+        } else {
+            dec!(0)
+        };
+
+        let (_, taker_rate) = data.symbol_commission_rates(&symbol)?;
+
+        if executed_qty > dec!(0) {
+            data.settle(
+                base_asset,
+                quote_asset,
+                &side,
+                executed_qty,
+                market_price,
+                taker_rate,
+            )?;
+        }
+
+        let (avg_price, order_status) = if executed_qty >= qty {
+            (market_price, OrderStatus::Filled)
+        } else if executed_qty > dec!(0) {
+            (market_price, OrderStatus::PartiallyFilled)
+        } else {
+            (dec!(0), OrderStatus::New)
+        };
+
+        let order = Order::builder()
+            .exchange(Exchange::Binance)
+            .base_asset(base_asset)
+            .quote_asset(quote_asset)
+            .symbol(symbol)
+            .order_id(order_id)
+            .price(limit_price.to_string())
+            .avg_price(avg_price.to_string())
+            .orig_qty(qty.to_string())
+            .executed_qty(executed_qty.to_string())
+            .cumulative_quote_qty((executed_qty * avg_price).to_string())
+            .order_type(OrderType::Limit)
+            .order_side(side)
+            .order_status(order_status)
+            .time(now)
+            .update_time(now)
+            .build();
+
+        if executed_qty > dec!(0) {
+            data.push_order_update(&order, executed_qty, market_price, taker_rate)?;
+        }
+
+        match order.order_status {
+            OrderStatus::Filled => {
+                data.order_id += 1;
+                data.order_history.push(order.clone());
+            }
+            _ => {
+                let amount = match order.order_side {
+                    OrderSide::Buy => (qty - executed_qty) * limit_price,
+                    OrderSide::Sell => qty - executed_qty,
+                };
+
+                data.open_order(order.clone(), amount)?;
+
+                if executed_qty > dec!(0) {
+                    data.fill_times.insert(order.order_id.clone(), now);
+                } else {
+                    data.join_queue(&order, volume, volume_seq)?;
+                }
+            }
+        }
+
+        Ok(order)
+    }
+
+    // 用最新价格撮合挂单，成交延迟已满的市价单
+    async fn match_orders(&self, data: &mut BacktestSpotClientData) -> Result<()> {
+        if data.open_orders.is_empty() && data.delayed_orders.is_empty() {
+            return Ok(());
+        }
+
+        let price_store = self.price_store.read().await;
+        let update_time = price_store.timestamp().unwrap_or_default() * 1000;
+
+        data.fill_delayed_orders(
+            |symbol| price_store.price(&Exchange::Binance, &Market::Spot, symbol),
+            |symbol| price_store.volume(&Exchange::Binance, &Market::Spot, symbol),
+            price_store.ticks(),
+            update_time,
+        )?;
+
+        data.match_orders(
+            |symbol| price_store.price(&Exchange::Binance, &Market::Spot, symbol),
+            |symbol| price_store.volume(&Exchange::Binance, &Market::Spot, symbol),
+            |symbol| price_store.volume_seq(&Exchange::Binance, &Market::Spot, symbol),
+            update_time,
+        )
+    }
+
+    // 计价货币金额按最新价格换算为基础货币数量，保留8位小数
+    async fn quote_to_base_qty(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        quote_qty: f64,
+    ) -> Result<Decimal> {
+        let symbol = self.symbol(base_asset, quote_asset);
+        let quote_qty = Decimal::try_from(quote_qty)?;
+        let price = self.price(&symbol).await;
+
+        anyhow::ensure!(price > dec!(0), "Price not found: {}", symbol);
+
+        Ok((quote_qty / price).round_dp_with_strategy(8, RoundingStrategy::ToZero))
+    }
+
+    async fn timestamp(&self) -> Option<i64> {
+        self.price_store.read().await.timestamp()
+    }
+
+    // 资产按估值币种计价
+    async fn asset_value(&self, asset: &str, amount: Decimal) -> Result<Decimal> {
+        if amount.is_zero() || asset == MARGIN_VALUE_ASSET {
+            return Ok(amount);
+        }
+
+        let symbol = self.symbol(asset, MARGIN_VALUE_ASSET);
+        let price = self
+            .price_store
+            .read()
+            .await
+            .price(&Exchange::Binance, &Market::Spot, &symbol)
+            .ok_or_else(|| anyhow::anyhow!("Price not found: {}", symbol))?;
+
+        Ok(amount * price)
+    }
+
+    async fn margin_account(&self, data: &BacktestSpotClientData) -> Result<MarginAccount> {
+        let names = data
+            .assets
+            .keys()
+            .chain(data.borrowed.keys())
+            .collect::<BTreeSet<_>>();
+
+        let mut assets = Vec::with_capacity(names.len());
+        let mut total_asset_value = dec!(0);
+        let mut total_liability_value = dec!(0);
+
+        for name in names {
+            let (free, locked) = match data.assets.get(name) {
+                Some(balance) => (balance.free.parse()?, balance.locked.parse()?),
+                None => (dec!(0), dec!(0)),
+            };
+            let borrowed = data.borrowed.get(name).cloned().unwrap_or_default();
+            let interest = data.interest.get(name).cloned().unwrap_or_default();
+
+            total_asset_value += self.asset_value(name, free + locked).await?;
+            total_liability_value += self.asset_value(name, borrowed + interest).await?;
+
+            assets.push(
+                MarginAsset::builder()
+                    .asset(name)
+                    .free(free)
+                    .locked(locked)
+                    .borrowed(borrowed)
+                    .interest(interest)
+                    .net_asset(free + locked - borrowed - interest)
+                    .build(),
+            );
+        }
+
+        let margin_level = if total_liability_value.is_zero() {
+            MARGIN_LEVEL_MAX
+        } else {
+            total_asset_value / total_liability_value
+        };
+
+        Ok(MarginAccount::builder()
+            .margin_level(margin_level)
+            .total_asset_value(total_asset_value)
+            .total_liability_value(total_liability_value)
+            .value_asset(MARGIN_VALUE_ASSET)
+            .assets(assets)
+            .build())
+    }
+
+    async fn margin_order(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        qty: f64,
+        side: OrderSide,
+    ) -> Result<Order> {
+        let symbol = self.symbol(base_asset, quote_asset);
+        let qty = Decimal::try_from(qty)?;
+        let price = self.price(&symbol).await;
+        let now = self.timestamp().await;
+        let mut data = self.data.lock().await;
+
+        anyhow::ensure!(data.margin, "Margin mode is not enabled");
+
+        data.accrue_interest(now);
+
+        // 风险率过低时禁止交易
+        let margin_level = self.margin_account(&data).await?.margin_level;
+        anyhow::ensure!(
+            margin_level >= MARGIN_LEVEL_CALL,
+            "Margin level {} is below {}",
+            margin_level,
+            MARGIN_LEVEL_CALL
+        );
+
+        let (_, taker_rate) = data.symbol_commission_rates(&symbol)?;
+        let quote_qty = qty * price;
+
+        match side {
+            OrderSide::Buy => {
+                data.sub_free(quote_asset, quote_qty)?;
+                data.add_free(base_asset, qty - qty * taker_rate)?;
+            }
+            OrderSide::Sell => {
+                data.sub_free(base_asset, qty)?;
+                data.add_free(quote_asset, quote_qty - quote_qty * taker_rate)?;
+            }
+        }
+
+        data.order_id += 1;
+        data.trade_volume += quote_qty;
 
-    async fn price(&self, symbol: &Symbol) -> Decimal {
-        self.price_store
-            .read()
-            .await
-            .price(&Exchange::Binance, &Market::Spot, symbol)
-            .unwrap_or(dec!(0))
+        let order = Order::builder()
+            .exchange(Exchange::Binance)
+            .base_asset(base_asset)
+            .quote_asset(quote_asset)
+            .symbol(symbol)
+            .order_id(data.order_id.to_string())
+            .price(price.to_string())
+            .avg_price(price.to_string())
+            .orig_qty(qty.to_string())
+            .executed_qty(qty.to_string())
+            .cumulative_quote_qty(quote_qty.to_string())
+            .order_type(OrderType::Market)
+            .order_side(side)
+            .order_status(OrderStatus::Filled)
+            .time(0)
+            .update_time(0)
+            .build();
+
+        data.order_history.push(order.clone());
+
+        Ok(order)
     }
 
     async fn add_asset(&mut self, asset: &str, amount: Decimal) -> Result<()> {
@@ -154,14 +1295,22 @@ impl SpotClientExecutable for BacktestSpotClient {
         Exchange::Binance
     }
 
+    fn account_id(&self) -> String {
+        "backtest".to_string()
+    }
+
+    // 挂单、延迟和部分成交的市价单在撮合时成交，成交后推送订单更新
+    fn subscribe_user_data(&self) -> Option<broadcast::Receiver<UserDataEvent>> {
+        Some(self.user_data.subscribe())
+    }
+
     async fn get_account(&self) -> Result<AccountInformation> {
         let data = self.data.lock().await;
-        let commissions = data.commissions.unwrap_or(0.001);
-        let commission_rate = commissions.try_into()?;
+        let (maker_commission_rate, taker_commission_rate) = data.commission_rates()?;
 
         Ok(AccountInformation::builder()
-            .maker_commission_rate(commission_rate)
-            .taker_commission_rate(commission_rate)
+            .maker_commission_rate(maker_commission_rate)
+            .taker_commission_rate(taker_commission_rate)
             .can_trade(true)
             .build())
     }
@@ -171,19 +1320,33 @@ impl SpotClientExecutable for BacktestSpotClient {
         base_asset: &str,
         quote_asset: &str,
     ) -> Result<SymbolInformation> {
-        let symbol = format!("{}{}", base_asset, quote_asset).to_uppercase();
+        let symbol = self.symbol(base_asset, quote_asset);
+        let data = self.data.lock().await;
+        let commission_rate = data.symbol_commissions.get(&symbol).copied();
+        let filters = data
+            .symbol_filters
+            .get(&symbol)
+            .cloned()
+            .unwrap_or_default();
 
         Ok(SymbolInformation::builder()
-            .symbol(symbol.into())
+            .symbol(symbol)
             .base_asset(base_asset)
             .quote_asset(quote_asset)
             .base_asset_precision(3)
             .quote_asset_precision(3)
+            .maybe_tick_size(filters.tick_size)
+            .maybe_step_size(filters.step_size)
+            .maybe_min_qty(filters.min_qty)
+            .maybe_min_notional(filters.min_notional)
+            .maybe_maker_commission_rate(commission_rate)
+            .maybe_taker_commission_rate(commission_rate)
             .build())
     }
 
     async fn get_balance(&self, asset: &str) -> Result<Balance> {
-        let data = self.data.lock().await;
+        let mut data = self.data.lock().await;
+        self.match_orders(&mut data).await?;
 
         match data.assets.get(asset) {
             Some(balance) => Ok(balance.clone()),
@@ -195,17 +1358,32 @@ impl SpotClientExecutable for BacktestSpotClient {
         }
     }
 
+    async fn get_balances(&self) -> Result<Vec<Balance>> {
+        let mut data = self.data.lock().await;
+        self.match_orders(&mut data).await?;
+
+        Ok(data
+            .assets
+            .values()
+            .filter(|balance| !balance.is_empty())
+            .cloned()
+            .collect())
+    }
+
     async fn get_order(
         &self,
         _base_asset: &str,
         _quote_asset: &str,
         order_id: &str,
     ) -> Result<Order> {
-        let data = self.data.lock().await;
+        let mut data = self.data.lock().await;
+        self.match_orders(&mut data).await?;
 
         let order = data
-            .order_history
+            .open_orders
             .iter()
+            .chain(data.delayed_orders.iter().map(|(_, order)| order))
+            .chain(data.order_history.iter())
             .find(|order| order.order_id == order_id)
             .ok_or(anyhow::anyhow!("Order not found"))?
             .clone();
@@ -213,62 +1391,104 @@ impl SpotClientExecutable for BacktestSpotClient {
         Ok(order)
     }
 
-    async fn market_buy(&self, base_asset: &str, quote_asset: &str, qty: f64) -> Result<Order> {
+    async fn get_open_orders(&self, base_asset: &str, quote_asset: &str) -> Result<Vec<Order>> {
         let symbol = self.symbol(base_asset, quote_asset);
-        let qty = Decimal::try_from(qty)?;
-        let price = self.price(&symbol).await;
         let mut data = self.data.lock().await;
+        self.match_orders(&mut data).await?;
 
-        data.order_id += 1;
+        let orders = data
+            .open_orders
+            .iter()
+            .filter(|order| order.symbol == symbol)
+            .cloned()
+            .collect();
 
-        let order = Order::builder()
-            .exchange(Exchange::Binance)
-            .base_asset(base_asset)
-            .quote_asset(quote_asset)
-            .symbol(symbol)
-            .order_id(data.order_id.to_string())
-            .price(price.to_string())
-            .avg_price(price.to_string())
-            .orig_qty(qty.to_string())
-            .executed_qty(qty.to_string())
-            .cumulative_quote_qty((qty * price).to_string())
-            .order_type(OrderType::Market)
-            .order_side(OrderSide::Buy)
-            .order_status(OrderStatus::Filled)
-            .time(0)
-            .update_time(0)
-            .build();
+        Ok(orders)
+    }
 
-        Ok(order)
+    async fn cancel_order(
+        &self,
+        _base_asset: &str,
+        _quote_asset: &str,
+        order_id: &str,
+    ) -> Result<Order> {
+        let now = self.timestamp().await.unwrap_or_default() * 1000;
+        let mut data = self.data.lock().await;
+
+        // 撤单前先撮合，已成交的订单无法撤销
+        self.match_orders(&mut data).await?;
+
+        // OCO订单同时撤销另一个订单
+        let mut orders = data.cancel_order_list(order_id, now)?;
+
+        Ok(orders.remove(0))
     }
 
-    async fn market_sell(&self, base_asset: &str, quote_asset: &str, qty: f64) -> Result<Order> {
+    async fn cancel_all_orders(&self, base_asset: &str, quote_asset: &str) -> Result<Vec<Order>> {
         let symbol = self.symbol(base_asset, quote_asset);
-        let qty = Decimal::try_from(qty)?;
-        let price = self.price(&symbol).await;
+        let now = self.timestamp().await.unwrap_or_default() * 1000;
         let mut data = self.data.lock().await;
+        self.match_orders(&mut data).await?;
 
-        data.order_id += 1;
+        let order_ids = data
+            .open_orders
+            .iter()
+            .filter(|order| order.symbol == symbol)
+            .map(|order| order.order_id.clone())
+            .collect::<Vec<_>>();
 
-        let order = Order::builder()
-            .exchange(Exchange::Binance)
-            .base_asset(base_asset)
-            .quote_asset(quote_asset)
-            .symbol(symbol)
-            .order_id(data.order_id.to_string())
-            .price(price.to_string())
-            .avg_price(price.to_string())
-            .orig_qty(qty.to_string())
-            .executed_qty(qty.to_string())
-            .cumulative_quote_qty((qty * price).to_string())
-            .order_type(OrderType::Market)
-            .order_side(OrderSide::Sell)
-            .order_status(OrderStatus::Filled)
-            .time(0)
-            .update_time(0)
-            .build();
+        let mut orders = Vec::new();
 
-        Ok(order)
+        for order_id in order_ids {
+            // OCO订单的另一个订单已随之撤销
+            if data
+                .open_orders
+                .iter()
+                .any(|order| order.order_id == order_id)
+            {
+                orders.extend(data.cancel_order_list(&order_id, now)?);
+            }
+        }
+
+        Ok(orders)
+    }
+
+    async fn market_buy(&self, base_asset: &str, quote_asset: &str, qty: f64) -> Result<Order> {
+        let qty = Decimal::try_from(qty)?;
+        self.market_order(base_asset, quote_asset, qty, OrderSide::Buy)
+            .await
+    }
+
+    async fn market_sell(&self, base_asset: &str, quote_asset: &str, qty: f64) -> Result<Order> {
+        let qty = Decimal::try_from(qty)?;
+        self.market_order(base_asset, quote_asset, qty, OrderSide::Sell)
+            .await
+    }
+
+    async fn market_buy_quote(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        quote_qty: f64,
+    ) -> Result<Order> {
+        let qty = self
+            .quote_to_base_qty(base_asset, quote_asset, quote_qty)
+            .await?;
+        self.market_order(base_asset, quote_asset, qty, OrderSide::Buy)
+            .await
+    }
+
+    async fn market_sell_quote(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        quote_qty: f64,
+    ) -> Result<Order> {
+        let qty = self
+            .quote_to_base_qty(base_asset, quote_asset, quote_qty)
+            .await?;
+        self.market_order(base_asset, quote_asset, qty, OrderSide::Sell)
+            .await
     }
 
     async fn limit_buy(
@@ -277,62 +1497,204 @@ impl SpotClientExecutable for BacktestSpotClient {
         quote_asset: &str,
         qty: f64,
         price: f64,
+    ) -> Result<Order> {
+        self.limit_order(base_asset, quote_asset, qty, price, OrderSide::Buy)
+            .await
+    }
+
+    async fn limit_sell(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        qty: f64,
+        price: f64,
+    ) -> Result<Order> {
+        self.limit_order(base_asset, quote_asset, qty, price, OrderSide::Sell)
+            .await
+    }
+
+    async fn stop_limit_order(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        side: OrderSide,
+        qty: f64,
+        price: f64,
+        stop_price: f64,
     ) -> Result<Order> {
         let symbol = self.symbol(base_asset, quote_asset);
+        let qty = Decimal::try_from(qty)?;
+        let limit_price = Decimal::try_from(price)?;
+        let stop_price = Decimal::try_from(stop_price)?;
+
+        anyhow::ensure!(
+            qty > dec!(0) && limit_price > dec!(0) && stop_price > dec!(0),
+            "Invalid stop limit order, qty: {}, price: {}, stop price: {}",
+            qty,
+            limit_price,
+            stop_price
+        );
+
+        let market_price = self.price(&symbol).await;
+
+        // 与交易所一致，下单时立即触发的止损单会被拒绝
+        anyhow::ensure!(
+            market_price.is_zero() || !is_triggered(&side, market_price, stop_price),
+            "Stop price {} would trigger immediately, market price: {}",
+            stop_price,
+            market_price
+        );
+
+        let now = self.timestamp().await.unwrap_or_default() * 1000;
         let mut data = self.data.lock().await;
-        data.order_id += 1;
+
+        self.match_orders(&mut data).await?;
 
         let order = Order::builder()
             .exchange(Exchange::Binance)
             .base_asset(base_asset)
             .quote_asset(quote_asset)
             .symbol(symbol)
-            .order_id(data.order_id.to_string())
-            .price(price.to_string())
-            .avg_price(price.to_string())
+            .order_id(data.next_order_id())
+            .price(limit_price.to_string())
+            .avg_price("0")
             .orig_qty(qty.to_string())
             .executed_qty("0")
             .cumulative_quote_qty("0")
-            .order_type(OrderType::Limit)
-            .order_side(OrderSide::Buy)
-            .order_status(OrderStatus::Filled)
-            .time(0)
-            .update_time(0)
+            .order_type(OrderType::StopLossLimit)
+            .order_side(side)
+            .order_status(OrderStatus::New)
+            .stop_price(stop_price.to_string())
+            .time(now)
+            .update_time(now)
             .build();
 
+        let amount = match order.order_side {
+            OrderSide::Buy => qty * limit_price,
+            OrderSide::Sell => qty,
+        };
+
+        data.open_order(order.clone(), amount)?;
+
         Ok(order)
     }
 
-    async fn limit_sell(
+    async fn oco_order(
         &self,
         base_asset: &str,
         quote_asset: &str,
+        side: OrderSide,
         qty: f64,
         price: f64,
-    ) -> Result<Order> {
+        stop_price: f64,
+        stop_limit_price: f64,
+    ) -> Result<Vec<Order>> {
         let symbol = self.symbol(base_asset, quote_asset);
+        let qty = Decimal::try_from(qty)?;
+        let limit_price = Decimal::try_from(price)?;
+        let stop_price = Decimal::try_from(stop_price)?;
+        let stop_limit_price = Decimal::try_from(stop_limit_price)?;
+
+        anyhow::ensure!(
+            qty > dec!(0)
+                && limit_price > dec!(0)
+                && stop_price > dec!(0)
+                && stop_limit_price > dec!(0),
+            "Invalid oco order, qty: {}, price: {}, stop price: {}, stop limit price: {}",
+            qty,
+            limit_price,
+            stop_price,
+            stop_limit_price
+        );
+
+        let market_price = self.price(&symbol).await;
+        let volume = self.volume(&symbol).await;
+        let volume_seq = self.volume_seq(&symbol).await;
+
+        // 卖出: 限价 > 最新价格 > 止损价，买入: 限价 < 最新价格 < 止损价
+        let valid = match side {
+            OrderSide::Sell => {
+                limit_price > stop_price
+                    && (market_price.is_zero()
+                        || (limit_price > market_price && market_price > stop_price))
+            }
+            OrderSide::Buy => {
+                limit_price < stop_price
+                    && (market_price.is_zero()
+                        || (limit_price < market_price && market_price < stop_price))
+            }
+        };
+
+        anyhow::ensure!(
+            valid,
+            "Invalid oco order prices, price: {}, stop price: {}, market price: {}",
+            limit_price,
+            stop_price,
+            market_price
+        );
+
+        let now = self.timestamp().await.unwrap_or_default() * 1000;
         let mut data = self.data.lock().await;
-        data.order_id += 1;
+
+        self.match_orders(&mut data).await?;
+
+        // 两个订单共用冻结资产，按成交金额较大的订单冻结
+        let (limit_amount, stop_amount) = match side {
+            OrderSide::Sell => (qty, dec!(0)),
+            OrderSide::Buy => (
+                qty * limit_price,
+                qty * (stop_limit_price - limit_price).max(dec!(0)),
+            ),
+        };
+
+        let asset = match side {
+            OrderSide::Buy => quote_asset,
+            OrderSide::Sell => base_asset,
+        };
+
+        anyhow::ensure!(
+            data.free(asset)? >= limit_amount + stop_amount,
+            "Insufficient balance: {}",
+            asset
+        );
 
         let order = Order::builder()
             .exchange(Exchange::Binance)
             .base_asset(base_asset)
             .quote_asset(quote_asset)
             .symbol(symbol)
-            .order_id(data.order_id.to_string())
-            .price(price.to_string())
-            .avg_price(price.to_string())
+            .order_id(data.next_order_id())
+            .price(limit_price.to_string())
+            .avg_price("0")
             .orig_qty(qty.to_string())
             .executed_qty("0")
             .cumulative_quote_qty("0")
-            .order_type(OrderType::Limit)
-            .order_side(OrderSide::Sell)
-            .order_status(OrderStatus::Filled)
-            .time(0)
-            .update_time(0)
+            .order_type(OrderType::LimitMaker)
+            .order_side(side.clone())
+            .order_status(OrderStatus::New)
+            .time(now)
+            .update_time(now)
             .build();
 
-        Ok(order)
+        data.open_order(order.clone(), limit_amount)?;
+        data.join_queue(&order, volume, volume_seq)?;
+
+        let stop_order = Order {
+            order_id: data.next_order_id(),
+            price: stop_limit_price.to_string(),
+            order_type: OrderType::StopLossLimit,
+            stop_price: Some(stop_price.to_string()),
+            ..order.clone()
+        };
+
+        data.open_order(stop_order.clone(), stop_amount)?;
+
+        data.oco_links
+            .insert(order.order_id.clone(), stop_order.order_id.clone());
+        data.oco_links
+            .insert(stop_order.order_id.clone(), order.order_id.clone());
+
+        Ok(vec![order, stop_order])
     }
 
     async fn get_price(&self, base_asset: &str, quote_asset: &str) -> Result<SymbolPrice> {
@@ -340,4 +1702,183 @@ impl SpotClientExecutable for BacktestSpotClient {
         let price = self.price(&symbol).await;
         Ok(SymbolPrice::builder().symbol(symbol).price(price).build())
     }
+
+    async fn get_margin_account(&self) -> Result<MarginAccount> {
+        let now = self.timestamp().await;
+        let mut data = self.data.lock().await;
+
+        anyhow::ensure!(data.margin, "Margin mode is not enabled");
+
+        data.accrue_interest(now);
+        self.margin_account(&data).await
+    }
+
+    async fn margin_borrow(&self, asset: &str, qty: f64) -> Result<MarginTransaction> {
+        let qty = Decimal::try_from(qty)?;
+        let now = self.timestamp().await;
+        let mut data = self.data.lock().await;
+
+        anyhow::ensure!(data.margin, "Margin mode is not enabled");
+
+        data.accrue_interest(now);
+
+        // 借款后的风险率不能低于初始风险率
+        let account = self.margin_account(&data).await?;
+        let value = self.asset_value(asset, qty).await?;
+        let margin_level =
+            (account.total_asset_value + value) / (account.total_liability_value + value);
+        anyhow::ensure!(
+            margin_level >= MARGIN_LEVEL_INITIAL,
+            "Margin level {} after borrow is below {}",
+            margin_level,
+            MARGIN_LEVEL_INITIAL
+        );
+
+        // 借款时立即收取第一个小时的利息
+        let interest = qty * data.hourly_interest_rate(asset);
+        data.add_free(asset, qty)?;
+        *data.borrowed.entry(asset.to_string()).or_default() += qty;
+        *data.interest.entry(asset.to_string()).or_default() += interest;
+
+        if data.interest_time.is_none() {
+            data.interest_time = now;
+        }
+
+        data.tran_id += 1;
+
+        Ok(MarginTransaction::builder()
+            .tran_id(data.tran_id.to_string())
+            .asset(asset)
+            .qty(qty)
+            .build())
+    }
+
+    async fn margin_repay(&self, asset: &str, qty: f64) -> Result<MarginTransaction> {
+        let qty = Decimal::try_from(qty)?;
+        let now = self.timestamp().await;
+        let mut data = self.data.lock().await;
+
+        anyhow::ensure!(data.margin, "Margin mode is not enabled");
+
+        data.accrue_interest(now);
+
+        let borrowed = data.borrowed.get(asset).cloned().unwrap_or_default();
+        let interest = data.interest.get(asset).cloned().unwrap_or_default();
+        anyhow::ensure!(
+            qty <= borrowed + interest,
+            "Repay qty {} exceeds liability {}",
+            qty,
+            borrowed + interest
+        );
+
+        data.sub_free(asset, qty)?;
+
+        // 优先偿还利息
+        let repaid_interest = qty.min(interest);
+        data.interest
+            .insert(asset.to_string(), interest - repaid_interest);
+        data.borrowed
+            .insert(asset.to_string(), borrowed - (qty - repaid_interest));
+
+        if data.borrowed.values().all(|borrowed| borrowed.is_zero()) {
+            data.interest_time = None;
+        }
+
+        data.tran_id += 1;
+
+        Ok(MarginTransaction::builder()
+            .tran_id(data.tran_id.to_string())
+            .asset(asset)
+            .qty(qty)
+            .build())
+    }
+
+    async fn margin_buy(&self, base_asset: &str, quote_asset: &str, qty: f64) -> Result<Order> {
+        self.margin_order(base_asset, quote_asset, qty, OrderSide::Buy)
+            .await
+    }
+
+    async fn margin_sell(&self, base_asset: &str, quote_asset: &str, qty: f64) -> Result<Order> {
+        self.margin_order(base_asset, quote_asset, qty, OrderSide::Sell)
+            .await
+    }
+
+    // 与交易所一致，重复的客户端订单ID拒绝下单
+    async fn submit_order(&self, client_order_id: &str, intent: OrderIntent) -> Result<Order> {
+        anyhow::ensure!(
+            !self
+                .data
+                .lock()
+                .await
+                .client_orders
+                .contains_key(client_order_id),
+            "Duplicate client order id: {}",
+            client_order_id
+        );
+
+        let mut order = self.place_intent(intent).await?;
+        order.client_order_id = Some(client_order_id.to_string());
+
+        let mut data = self.data.lock().await;
+        let BacktestSpotClientData {
+            open_orders,
+            delayed_orders,
+            order_history,
+            client_orders,
+            ..
+        } = &mut *data;
+
+        // 挂单和历史订单同样记录客户端订单ID
+        open_orders
+            .iter_mut()
+            .chain(delayed_orders.iter_mut().map(|(_, order)| order))
+            .chain(order_history.iter_mut())
+            .filter(|o| o.order_id == order.order_id)
+            .for_each(|o| o.client_order_id = Some(client_order_id.to_string()));
+        client_orders.insert(client_order_id.to_string(), order.clone());
+
+        Ok(order)
+    }
+
+    async fn get_order_by_client_id(
+        &self,
+        _base_asset: &str,
+        _quote_asset: &str,
+        client_order_id: &str,
+    ) -> Result<Option<Order>> {
+        let mut data = self.data.lock().await;
+        self.match_orders(&mut data).await?;
+
+        let Some(order) = data.client_orders.get(client_order_id) else {
+            return Ok(None);
+        };
+
+        // 优先返回撮合后的最新状态
+        let order = data
+            .open_orders
+            .iter()
+            .chain(data.delayed_orders.iter().map(|(_, order)| order))
+            .chain(data.order_history.iter())
+            .find(|o| o.order_id == order.order_id)
+            .unwrap_or(order)
+            .clone();
+
+        Ok(Some(order))
+    }
+}
+
+// 卖单价格跌到止损价、买单价格涨到止损价时触发
+fn is_triggered(side: &OrderSide, price: Decimal, stop_price: Decimal) -> bool {
+    match side {
+        OrderSide::Buy => price >= stop_price,
+        OrderSide::Sell => price <= stop_price,
+    }
+}
+
+// 买单价格不高于限价、卖单价格不低于限价时成交
+fn is_crossed(side: &OrderSide, price: Decimal, limit_price: Decimal) -> bool {
+    match side {
+        OrderSide::Buy => price <= limit_price,
+        OrderSide::Sell => price >= limit_price,
+    }
 }
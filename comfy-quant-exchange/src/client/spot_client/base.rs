@@ -1,14 +1,23 @@
+use crate::exchange::{
+    binance::{
+        AccountPositionEvent, MarginAccountDetails as BinanceMarginAccountDetails,
+        MarginOrderResult,
+    },
+    bybit::BybitInstrument,
+    okx::{OkxBalanceDetail, OkxInstrument, OkxOrder, OkxTicker, OkxTradeFee},
+};
 use anyhow::{anyhow, Result};
 use binance::model::{
     AccountInformation as BinanceAccountInformation, Balance as BinaceBalance,
-    Filters as BinanceFilters, Symbol as BinaceSymbolInformation,
-    SymbolPrice as BinanceSymbolPrice,
+    Filters as BinanceFilters, OrderTradeEvent as BinanceOrderTradeEvent,
+    Symbol as BinaceSymbolInformation, SymbolPrice as BinanceSymbolPrice,
 };
 use bon::Builder;
 use comfy_quant_base::{Exchange, Symbol};
 use rust_decimal::{prelude::FromPrimitive, Decimal};
 use rust_decimal_macros::dec;
 use std::str::FromStr;
+use strum_macros::IntoStaticStr;
 
 #[derive(Builder)]
 #[builder(on(String, into))]
@@ -26,6 +35,22 @@ pub struct BinanceTransaction {
     transaction: binance::model::Transaction,
 }
 
+#[derive(Builder)]
+#[builder(on(String, into))]
+pub struct BinanceMarginOrder {
+    base_asset: String,
+    quote_asset: String,
+    order: MarginOrderResult,
+}
+
+#[derive(Builder)]
+#[builder(on(String, into))]
+pub struct OkxSpotOrder {
+    base_asset: String,
+    quote_asset: String,
+    order: OkxOrder,
+}
+
 #[derive(Builder, Debug, Clone, Default)]
 pub struct AccountInformation {
     pub maker_commission_rate: Decimal,
@@ -56,6 +81,19 @@ impl TryFrom<BinanceAccountInformation> for AccountInformation {
     }
 }
 
+// OKX 的手续费率为负数表示收取，转换为正数的费率
+impl TryFrom<OkxTradeFee> for AccountInformation {
+    type Error = anyhow::Error;
+
+    fn try_from(value: OkxTradeFee) -> Result<Self, Self::Error> {
+        Ok(AccountInformation::builder()
+            .maker_commission_rate(-value.maker.parse::<Decimal>()?)
+            .taker_commission_rate(-value.taker.parse::<Decimal>()?)
+            .can_trade(true)
+            .build())
+    }
+}
+
 #[derive(Builder, Debug)]
 #[builder(on(String, into))]
 pub struct SymbolInformation {
@@ -65,6 +103,12 @@ pub struct SymbolInformation {
     pub base_asset_precision: u32,
     pub quote_asset_precision: u32,
     pub min_notional: Option<Decimal>,
+    pub tick_size: Option<Decimal>,             // 价格步长
+    pub step_size: Option<Decimal>,             // 数量步长
+    pub min_qty: Option<Decimal>,               // 最小下单数量
+    pub status: Option<String>,                 // 交易状态，如 TRADING
+    pub maker_commission_rate: Option<Decimal>, // 交易对挂单手续费率，如零手续费活动交易对
+    pub taker_commission_rate: Option<Decimal>, // 交易对吃单手续费率
 }
 
 impl From<BinaceSymbolInformation> for SymbolInformation {
@@ -76,6 +120,21 @@ impl From<BinaceSymbolInformation> for SymbolInformation {
             _ => None,
         });
 
+        let tick_size = value.filters.iter().find_map(|filter| match filter {
+            BinanceFilters::PriceFilter { tick_size, .. } => tick_size.parse().ok(),
+            _ => None,
+        });
+
+        let step_size = value.filters.iter().find_map(|filter| match filter {
+            BinanceFilters::LotSize { step_size, .. } => step_size.parse().ok(),
+            _ => None,
+        });
+
+        let min_qty = value.filters.iter().find_map(|filter| match filter {
+            BinanceFilters::LotSize { min_qty, .. } => min_qty.parse().ok(),
+            _ => None,
+        });
+
         SymbolInformation::builder()
             .symbol(value.symbol.into())
             .base_asset(value.base_asset)
@@ -83,10 +142,87 @@ impl From<BinaceSymbolInformation> for SymbolInformation {
             .base_asset_precision(value.base_asset_precision as u32)
             .quote_asset_precision(value.quote_precision as u32)
             .maybe_min_notional(min_notional)
+            .maybe_tick_size(tick_size)
+            .maybe_step_size(step_size)
+            .maybe_min_qty(min_qty)
+            .status(value.status)
             .build()
     }
 }
 
+// OKX 没有最小名义价值，精度按价格和数量步长的小数位数计算
+impl TryFrom<OkxInstrument> for SymbolInformation {
+    type Error = anyhow::Error;
+
+    fn try_from(value: OkxInstrument) -> Result<Self, Self::Error> {
+        let tick_size = value.tick_sz.parse::<Decimal>()?;
+        let step_size = value.lot_sz.parse::<Decimal>()?;
+        let min_qty = value.min_sz.parse::<Decimal>()?;
+        let status = match value.state.as_str() {
+            "live" => "TRADING".to_string(),
+            state => state.to_uppercase(),
+        };
+
+        Ok(SymbolInformation::builder()
+            .symbol(value.inst_id.into())
+            .base_asset(value.base_ccy)
+            .quote_asset(value.quote_ccy)
+            .base_asset_precision(step_size.normalize().scale())
+            .quote_asset_precision(tick_size.normalize().scale())
+            .tick_size(tick_size)
+            .step_size(step_size)
+            .min_qty(min_qty)
+            .status(status)
+            .build())
+    }
+}
+
+// Bybit 的精度以最小单位给出，如 0.000001，最小下单金额作为最小名义价值
+impl TryFrom<BybitInstrument> for SymbolInformation {
+    type Error = anyhow::Error;
+
+    fn try_from(value: BybitInstrument) -> Result<Self, Self::Error> {
+        let tick_size = value.price_filter.tick_size.parse::<Decimal>()?;
+        let step_size = value.lot_size_filter.base_precision.parse::<Decimal>()?;
+        let quote_precision = value.lot_size_filter.quote_precision.parse::<Decimal>()?;
+        let min_notional = value.lot_size_filter.min_order_amt.parse::<Decimal>()?;
+        let min_qty = value.lot_size_filter.min_order_qty.parse::<Decimal>()?;
+
+        Ok(SymbolInformation::builder()
+            .symbol(value.symbol.into())
+            .base_asset(value.base_coin)
+            .quote_asset(value.quote_coin)
+            .base_asset_precision(step_size.normalize().scale())
+            .quote_asset_precision(quote_precision.normalize().scale())
+            .min_notional(min_notional)
+            .tick_size(tick_size)
+            .step_size(step_size)
+            .min_qty(min_qty)
+            .status(value.status.to_uppercase())
+            .build())
+    }
+}
+
+impl SymbolInformation {
+    // 数量按当前价格计算的名义价值低于最小名义价值，无法再下单卖出
+    pub fn is_dust(&self, qty: Decimal, price: Decimal) -> bool {
+        qty > Decimal::ZERO
+            && self
+                .min_notional
+                .is_some_and(|min_notional| qty * price < min_notional)
+    }
+
+    // 挂单、吃单手续费率，交易对未单独设置时使用账户手续费率
+    pub fn commission_rates(&self, account: &AccountInformation) -> (Decimal, Decimal) {
+        (
+            self.maker_commission_rate
+                .unwrap_or(account.maker_commission_rate),
+            self.taker_commission_rate
+                .unwrap_or(account.taker_commission_rate),
+        )
+    }
+}
+
 #[derive(Builder, Debug, Clone)]
 #[builder(on(String, into))]
 pub struct Balance {
@@ -95,6 +231,15 @@ pub struct Balance {
     pub locked: String, // 锁定余额
 }
 
+impl Balance {
+    // 可用和锁定余额都为0，无法解析时视为有余额
+    pub fn is_empty(&self) -> bool {
+        [&self.free, &self.locked]
+            .into_iter()
+            .all(|value| value.parse::<Decimal>().is_ok_and(|value| value.is_zero()))
+    }
+}
+
 impl From<BinaceBalance> for Balance {
     fn from(value: BinaceBalance) -> Self {
         Balance::builder()
@@ -105,7 +250,17 @@ impl From<BinaceBalance> for Balance {
     }
 }
 
-#[derive(Debug, Clone)]
+impl From<OkxBalanceDetail> for Balance {
+    fn from(value: OkxBalanceDetail) -> Self {
+        Balance::builder()
+            .asset(value.ccy)
+            .free(value.avail_bal)
+            .locked(value.frozen_bal)
+            .build()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum OrderStatus {
     New,             // 新订单
     PartiallyFilled, // 部分成交
@@ -113,6 +268,14 @@ pub enum OrderStatus {
     Canceled,        // 已撤销
     PendingCancel,   // 等待撤销
     Rejected,        // 已拒绝
+    Expired,         // 已过期
+}
+
+impl OrderStatus {
+    // 订单是否还可能继续成交
+    pub fn is_open(&self) -> bool {
+        matches!(self, OrderStatus::New | OrderStatus::PartiallyFilled)
+    }
 }
 
 impl FromStr for OrderStatus {
@@ -126,15 +289,32 @@ impl FromStr for OrderStatus {
             "CANCELED" => Ok(OrderStatus::Canceled),
             "PENDING_CANCEL" => Ok(OrderStatus::PendingCancel),
             "REJECTED" => Ok(OrderStatus::Rejected),
+            "EXPIRED" => Ok(OrderStatus::Expired),
             _ => anyhow::bail!("OrderStatus parse failed. value: {}", s),
         }
     }
 }
 
-#[derive(Debug, Clone)]
+impl AsRef<str> for OrderStatus {
+    fn as_ref(&self) -> &str {
+        match self {
+            OrderStatus::New => "NEW",
+            OrderStatus::PartiallyFilled => "PARTIALLY_FILLED",
+            OrderStatus::Filled => "FILLED",
+            OrderStatus::Canceled => "CANCELED",
+            OrderStatus::PendingCancel => "PENDING_CANCEL",
+            OrderStatus::Rejected => "REJECTED",
+            OrderStatus::Expired => "EXPIRED",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum OrderType {
     Market,
     Limit,
+    StopLossLimit, // 止损限价单
+    LimitMaker,    // 只做挂单的限价单，OCO订单的限价部分
 }
 
 impl FromStr for OrderType {
@@ -144,11 +324,24 @@ impl FromStr for OrderType {
         match s {
             "MARKET" => Ok(OrderType::Market),
             "LIMIT" => Ok(OrderType::Limit),
+            "STOP_LOSS_LIMIT" => Ok(OrderType::StopLossLimit),
+            "LIMIT_MAKER" => Ok(OrderType::LimitMaker),
             _ => anyhow::bail!("OrderType parse failed. value: {}", s),
         }
     }
 }
 
+impl AsRef<str> for OrderType {
+    fn as_ref(&self) -> &str {
+        match self {
+            OrderType::Market => "MARKET",
+            OrderType::Limit => "LIMIT",
+            OrderType::StopLossLimit => "STOP_LOSS_LIMIT",
+            OrderType::LimitMaker => "LIMIT_MAKER",
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum OrderSide {
     Buy,
@@ -167,6 +360,15 @@ impl FromStr for OrderSide {
     }
 }
 
+impl AsRef<str> for OrderSide {
+    fn as_ref(&self) -> &str {
+        match self {
+            OrderSide::Buy => "BUY",
+            OrderSide::Sell => "SELL",
+        }
+    }
+}
+
 #[derive(Builder, Debug, Clone)]
 #[builder(on(String, into), on(Exchange, into), on(Symbol, into))]
 #[allow(clippy::duplicated_attributes)]
@@ -187,6 +389,7 @@ pub struct Order {
     pub order_status: OrderStatus,       // 订单状态
     pub time: i64,                       // 订单时间
     pub update_time: i64,                // 最后更新时间
+    pub stop_price: Option<String>,      // 止损触发价格
 }
 
 impl Order {
@@ -232,6 +435,63 @@ impl Order {
     }
 }
 
+impl TryFrom<OkxSpotOrder> for Order {
+    type Error = anyhow::Error;
+
+    fn try_from(value: OkxSpotOrder) -> Result<Self, Self::Error> {
+        let order = value.order;
+        let order_type = match order.ord_type.as_str() {
+            "market" => OrderType::Market,
+            "limit" => OrderType::Limit,
+            "post_only" => OrderType::LimitMaker,
+            ord_type => anyhow::bail!("OKX order type not supported: {}", ord_type),
+        };
+        let order_side = order.side.to_uppercase().parse::<OrderSide>()?;
+        let order_status = match order.state.as_str() {
+            "live" => OrderStatus::New,
+            "partially_filled" => OrderStatus::PartiallyFilled,
+            "filled" => OrderStatus::Filled,
+            "canceled" | "mmp_canceled" => OrderStatus::Canceled,
+            state => anyhow::bail!("OKX order state not supported: {}", state),
+        };
+
+        let parse = |value: &str| -> Result<Decimal> {
+            Ok(if value.is_empty() {
+                dec!(0)
+            } else {
+                value.parse()?
+            })
+        };
+        let executed_qty = parse(&order.acc_fill_sz)?;
+        let avg_price = parse(&order.avg_px)?;
+        // 按计价货币金额下的市价单，委托数量不是基础资产数量，以成交数量代替
+        let orig_qty = if order.tgt_ccy == "quote_ccy" {
+            executed_qty
+        } else {
+            parse(&order.sz)?
+        };
+
+        Ok(Order::builder()
+            .exchange(Exchange::Okx)
+            .base_asset(value.base_asset)
+            .quote_asset(value.quote_asset)
+            .symbol(order.inst_id)
+            .order_id(order.ord_id)
+            .maybe_client_order_id((!order.cl_ord_id.is_empty()).then_some(order.cl_ord_id))
+            .price(parse(&order.px)?.to_string())
+            .avg_price(avg_price.to_string())
+            .orig_qty(orig_qty.to_string())
+            .executed_qty(executed_qty.to_string())
+            .cumulative_quote_qty((avg_price * executed_qty).to_string())
+            .order_type(order_type)
+            .order_side(order_side)
+            .order_status(order_status)
+            .time(order.c_time.parse::<i64>()?)
+            .update_time(order.u_time.parse::<i64>()?)
+            .build())
+    }
+}
+
 impl TryFrom<BinanceOrder> for Order {
     type Error = anyhow::Error;
 
@@ -242,7 +502,9 @@ impl TryFrom<BinanceOrder> for Order {
 
         let amount = value.order.cummulative_quote_qty.parse::<Decimal>()?;
         let qty = value.order.executed_qty.parse::<Decimal>()?;
-        let avg_price = amount / qty;
+        // 未成交的挂单没有成交均价
+        let avg_price = if qty.is_zero() { dec!(0) } else { amount / qty };
+        let stop_price = (value.order.stop_price > 0.).then(|| value.order.stop_price.to_string());
 
         let order = Order::builder()
             .exchange(Exchange::Binance)
@@ -261,6 +523,7 @@ impl TryFrom<BinanceOrder> for Order {
             .order_status(order_status)
             .time(value.order.time as i64)
             .update_time(value.order.update_time as i64)
+            .maybe_stop_price(stop_price)
             .build();
 
         Ok(order)
@@ -281,7 +544,7 @@ impl TryFrom<BinanceTransaction> for Order {
             })?;
         let qty = Decimal::from_f64(value.transaction.executed_qty)
             .ok_or_else(|| anyhow!("binance transaction executed qty convert decimal failed"))?;
-        let avg_price = amount / qty;
+        let avg_price = if qty.is_zero() { dec!(0) } else { amount / qty };
 
         let order = Order::builder()
             .exchange(Exchange::Binance)
@@ -306,6 +569,250 @@ impl TryFrom<BinanceTransaction> for Order {
     }
 }
 
+impl TryFrom<BinanceMarginOrder> for Order {
+    type Error = anyhow::Error;
+
+    fn try_from(value: BinanceMarginOrder) -> Result<Self, Self::Error> {
+        let order_type = value.order.type_name.parse::<OrderType>()?;
+        let order_side = value.order.side.parse::<OrderSide>()?;
+        let order_status = value.order.status.parse::<OrderStatus>()?;
+
+        let amount = value.order.cummulative_quote_qty.parse::<Decimal>()?;
+        let qty = value.order.executed_qty.parse::<Decimal>()?;
+        let avg_price = if qty.is_zero() { dec!(0) } else { amount / qty };
+
+        let order = Order::builder()
+            .exchange(Exchange::Binance)
+            .base_asset(value.base_asset)
+            .quote_asset(value.quote_asset)
+            .symbol(value.order.symbol)
+            .order_id(value.order.order_id.to_string())
+            .client_order_id(value.order.client_order_id)
+            .price(value.order.price)
+            .avg_price(avg_price.to_string())
+            .orig_qty(value.order.orig_qty)
+            .executed_qty(value.order.executed_qty)
+            .cumulative_quote_qty(value.order.cummulative_quote_qty)
+            .order_type(order_type)
+            .order_side(order_side)
+            .order_status(order_status)
+            .time(value.order.transact_time as i64)
+            .update_time(value.order.transact_time as i64)
+            .build();
+
+        Ok(order)
+    }
+}
+
+// 用户数据流推送的订单更新
+#[derive(Builder, Debug, Clone)]
+#[builder(on(String, into), on(Symbol, into))]
+#[allow(clippy::duplicated_attributes)]
+pub struct OrderUpdate {
+    pub exchange: Exchange,              // 交易所
+    pub symbol: Symbol,                  // 交易对
+    pub order_id: String,                // 订单ID
+    pub client_order_id: Option<String>, // 用户自己设置的ID
+    pub order_type: OrderType,           // 订单类型
+    pub order_side: OrderSide,           // 订单方向
+    pub order_status: OrderStatus,       // 订单状态
+    pub orig_qty: Decimal,               // 用户设置的原始订单数量
+    pub executed_qty: Decimal,           // 累计成交数量
+    pub last_qty: Decimal,               // 最近一笔成交数量
+    pub last_price: Decimal,             // 最近一笔成交价格
+    pub commission: Decimal,             // 最近一笔成交的手续费
+    pub update_time: i64,                // 更新时间
+}
+
+impl OrderUpdate {
+    // 已计入 filled_qty 后新增的成交，没有新增成交时返回None
+    pub fn fill(&self, filled_qty: &Decimal, base_asset: &str, quote_asset: &str) -> Option<Order> {
+        let qty = self.executed_qty - filled_qty;
+
+        if qty <= dec!(0) {
+            return None;
+        }
+
+        let order = Order::builder()
+            .exchange(self.exchange.clone())
+            .base_asset(base_asset.to_string())
+            .quote_asset(quote_asset.to_string())
+            .symbol(self.symbol.clone())
+            .order_id(self.order_id.clone())
+            .maybe_client_order_id(self.client_order_id.clone())
+            .price(self.last_price.to_string())
+            .avg_price(self.last_price.to_string())
+            .orig_qty(self.orig_qty.to_string())
+            .executed_qty(qty.to_string())
+            .cumulative_quote_qty((qty * self.last_price).to_string())
+            .order_type(self.order_type.clone())
+            .order_side(self.order_side.clone())
+            .order_status(self.order_status.clone())
+            .time(self.update_time)
+            .update_time(self.update_time)
+            .build();
+
+        Some(order)
+    }
+}
+
+impl TryFrom<BinanceOrderTradeEvent> for OrderUpdate {
+    type Error = anyhow::Error;
+
+    fn try_from(value: BinanceOrderTradeEvent) -> Result<Self, Self::Error> {
+        let order_update = OrderUpdate::builder()
+            .exchange(Exchange::Binance)
+            .symbol(value.symbol)
+            .order_id(value.order_id.to_string())
+            .client_order_id(value.new_client_order_id)
+            .order_type(value.order_type.parse()?)
+            .order_side(value.side.parse()?)
+            .order_status(value.order_status.parse()?)
+            .orig_qty(value.qty.parse()?)
+            .executed_qty(value.accumulated_qty_filled_trades.parse()?)
+            .last_qty(value.qty_last_filled_trade.parse()?)
+            .last_price(value.price_last_filled_trade.parse()?)
+            .commission(value.commission.parse()?)
+            .update_time(value.event_time as i64)
+            .build();
+
+        Ok(order_update)
+    }
+}
+
+// 查询到的订单快照，核对订单时作为一次订单更新处理，最近一笔成交按平均成交价计
+impl TryFrom<&Order> for OrderUpdate {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &Order) -> Result<Self, Self::Error> {
+        let executed_qty = value.executed_qty.parse::<Decimal>()?;
+
+        let order_update = OrderUpdate::builder()
+            .exchange(value.exchange.clone())
+            .symbol(value.symbol.clone())
+            .order_id(value.order_id.clone())
+            .maybe_client_order_id(value.client_order_id.clone())
+            .order_type(value.order_type.clone())
+            .order_side(value.order_side.clone())
+            .order_status(value.order_status.clone())
+            .orig_qty(value.orig_qty.parse()?)
+            .executed_qty(executed_qty)
+            .last_qty(executed_qty)
+            .last_price(value.avg_price.parse()?)
+            .commission(dec!(0))
+            .update_time(value.update_time)
+            .build();
+
+        Ok(order_update)
+    }
+}
+
+// 用户数据流推送的事件
+#[derive(Debug, Clone)]
+pub enum UserDataEvent {
+    OrderUpdate(OrderUpdate),    // 订单更新 executionReport
+    BalanceUpdate(Vec<Balance>), // 账户余额变化 outboundAccountPosition，只包含变化的币种
+}
+
+impl TryFrom<BinanceOrderTradeEvent> for UserDataEvent {
+    type Error = anyhow::Error;
+
+    fn try_from(value: BinanceOrderTradeEvent) -> Result<Self, Self::Error> {
+        Ok(UserDataEvent::OrderUpdate(value.try_into()?))
+    }
+}
+
+impl From<AccountPositionEvent> for UserDataEvent {
+    fn from(value: AccountPositionEvent) -> Self {
+        let balances = value
+            .balances
+            .into_iter()
+            .map(|balance| {
+                Balance::builder()
+                    .asset(balance.asset)
+                    .free(balance.free)
+                    .locked(balance.locked)
+                    .build()
+            })
+            .collect();
+
+        UserDataEvent::BalanceUpdate(balances)
+    }
+}
+
+// 杠杆账户资产
+#[derive(Builder, Debug, Clone)]
+#[builder(on(String, into))]
+pub struct MarginAsset {
+    pub asset: String,      // 币种
+    pub free: Decimal,      // 可用余额
+    pub locked: Decimal,    // 锁定余额
+    pub borrowed: Decimal,  // 借款
+    pub interest: Decimal,  // 未还利息
+    pub net_asset: Decimal, // 净资产
+}
+
+// 杠杆账户信息
+#[derive(Builder, Debug, Clone)]
+#[builder(on(String, into))]
+pub struct MarginAccount {
+    pub margin_level: Decimal,          // 风险率，总资产 / (借款 + 利息)
+    pub total_asset_value: Decimal,     // 总资产估值
+    pub total_liability_value: Decimal, // 总负债估值
+    pub value_asset: String,            // 估值币种
+    pub assets: Vec<MarginAsset>,       // 资产明细
+}
+
+impl MarginAccount {
+    // 风险率低于平仓线
+    pub fn is_liquidatable(&self) -> bool {
+        self.margin_level <= MARGIN_LEVEL_LIQUIDATION
+    }
+}
+
+pub const MARGIN_LEVEL_INITIAL: Decimal = dec!(1.5); // 借款后的最低风险率
+pub const MARGIN_LEVEL_CALL: Decimal = dec!(1.3); // 低于该风险率禁止杠杆交易
+pub const MARGIN_LEVEL_LIQUIDATION: Decimal = dec!(1.1); // 强制平仓风险率
+pub const MARGIN_LEVEL_MAX: Decimal = dec!(999); // 无负债时的风险率
+
+impl TryFrom<BinanceMarginAccountDetails> for MarginAccount {
+    type Error = anyhow::Error;
+
+    fn try_from(value: BinanceMarginAccountDetails) -> Result<Self, Self::Error> {
+        let assets = value
+            .user_assets
+            .into_iter()
+            .map(|asset| {
+                Ok(MarginAsset::builder()
+                    .asset(asset.asset)
+                    .free(asset.free.parse()?)
+                    .locked(asset.locked.parse()?)
+                    .borrowed(asset.borrowed.parse()?)
+                    .interest(asset.interest.parse()?)
+                    .net_asset(asset.net_asset.parse()?)
+                    .build())
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(MarginAccount::builder()
+            .margin_level(value.margin_level.parse()?)
+            .total_asset_value(value.total_asset_of_btc.parse()?)
+            .total_liability_value(value.total_liability_of_btc.parse()?)
+            .value_asset("BTC")
+            .assets(assets)
+            .build())
+    }
+}
+
+// 借款、还款记录
+#[derive(Builder, Debug, Clone)]
+#[builder(on(String, into))]
+pub struct MarginTransaction {
+    pub tran_id: String, // 交易ID
+    pub asset: String,   // 币种
+    pub qty: Decimal,    // 数量
+}
+
 #[derive(Builder, Debug, Clone, PartialEq, Eq)]
 #[builder(on(String, into))]
 pub struct SymbolPrice {
@@ -313,6 +820,17 @@ pub struct SymbolPrice {
     pub price: Decimal,
 }
 
+impl TryFrom<OkxTicker> for SymbolPrice {
+    type Error = anyhow::Error;
+
+    fn try_from(value: OkxTicker) -> Result<Self, Self::Error> {
+        Ok(SymbolPrice::builder()
+            .symbol(value.inst_id.into())
+            .price(value.last.parse::<Decimal>()?)
+            .build())
+    }
+}
+
 impl TryFrom<BinanceSymbolPrice> for SymbolPrice {
     type Error = anyhow::Error;
 
@@ -327,7 +845,170 @@ impl TryFrom<BinanceSymbolPrice> for SymbolPrice {
     }
 }
 
-#[derive(Clone)]
+// 批量下单中的单个订单
+#[derive(Debug, Clone, PartialEq)]
+pub enum OrderIntent {
+    MarketBuy {
+        base_asset: String,
+        quote_asset: String,
+        qty: f64,
+    },
+    MarketSell {
+        base_asset: String,
+        quote_asset: String,
+        qty: f64,
+    },
+    LimitBuy {
+        base_asset: String,
+        quote_asset: String,
+        qty: f64,
+        price: f64,
+    },
+    LimitSell {
+        base_asset: String,
+        quote_asset: String,
+        qty: f64,
+        price: f64,
+    },
+}
+
+impl OrderIntent {
+    pub fn market_buy(
+        base_asset: impl Into<String>,
+        quote_asset: impl Into<String>,
+        qty: f64,
+    ) -> Self {
+        OrderIntent::MarketBuy {
+            base_asset: base_asset.into(),
+            quote_asset: quote_asset.into(),
+            qty,
+        }
+    }
+
+    pub fn market_sell(
+        base_asset: impl Into<String>,
+        quote_asset: impl Into<String>,
+        qty: f64,
+    ) -> Self {
+        OrderIntent::MarketSell {
+            base_asset: base_asset.into(),
+            quote_asset: quote_asset.into(),
+            qty,
+        }
+    }
+
+    pub fn limit_buy(
+        base_asset: impl Into<String>,
+        quote_asset: impl Into<String>,
+        qty: f64,
+        price: f64,
+    ) -> Self {
+        OrderIntent::LimitBuy {
+            base_asset: base_asset.into(),
+            quote_asset: quote_asset.into(),
+            qty,
+            price,
+        }
+    }
+
+    pub fn limit_sell(
+        base_asset: impl Into<String>,
+        quote_asset: impl Into<String>,
+        qty: f64,
+        price: f64,
+    ) -> Self {
+        OrderIntent::LimitSell {
+            base_asset: base_asset.into(),
+            quote_asset: quote_asset.into(),
+            qty,
+            price,
+        }
+    }
+
+    pub fn base_asset(&self) -> &str {
+        match self {
+            OrderIntent::MarketBuy { base_asset, .. }
+            | OrderIntent::MarketSell { base_asset, .. }
+            | OrderIntent::LimitBuy { base_asset, .. }
+            | OrderIntent::LimitSell { base_asset, .. } => base_asset,
+        }
+    }
+
+    pub fn quote_asset(&self) -> &str {
+        match self {
+            OrderIntent::MarketBuy { quote_asset, .. }
+            | OrderIntent::MarketSell { quote_asset, .. }
+            | OrderIntent::LimitBuy { quote_asset, .. }
+            | OrderIntent::LimitSell { quote_asset, .. } => quote_asset,
+        }
+    }
+
+    pub fn side(&self) -> OrderSide {
+        match self {
+            OrderIntent::MarketBuy { .. } | OrderIntent::LimitBuy { .. } => OrderSide::Buy,
+            OrderIntent::MarketSell { .. } | OrderIntent::LimitSell { .. } => OrderSide::Sell,
+        }
+    }
+
+    pub fn qty(&self) -> f64 {
+        match self {
+            OrderIntent::MarketBuy { qty, .. }
+            | OrderIntent::MarketSell { qty, .. }
+            | OrderIntent::LimitBuy { qty, .. }
+            | OrderIntent::LimitSell { qty, .. } => *qty,
+        }
+    }
+
+    // 替换下单数量，用于拆分订单
+    pub fn with_qty(mut self, new_qty: f64) -> Self {
+        match &mut self {
+            OrderIntent::MarketBuy { qty, .. }
+            | OrderIntent::MarketSell { qty, .. }
+            | OrderIntent::LimitBuy { qty, .. }
+            | OrderIntent::LimitSell { qty, .. } => *qty = new_qty,
+        }
+
+        self
+    }
+}
+
+impl TryFrom<SpotClientRequest> for OrderIntent {
+    type Error = anyhow::Error;
+
+    fn try_from(value: SpotClientRequest) -> Result<Self, Self::Error> {
+        let intent = match value {
+            SpotClientRequest::MarketBuy {
+                base_asset,
+                quote_asset,
+                qty,
+            } => OrderIntent::market_buy(base_asset, quote_asset, qty),
+            SpotClientRequest::MarketSell {
+                base_asset,
+                quote_asset,
+                qty,
+            } => OrderIntent::market_sell(base_asset, quote_asset, qty),
+            SpotClientRequest::LimitBuy {
+                base_asset,
+                quote_asset,
+                qty,
+                price,
+            } => OrderIntent::limit_buy(base_asset, quote_asset, qty, price),
+            SpotClientRequest::LimitSell {
+                base_asset,
+                quote_asset,
+                qty,
+                price,
+            } => OrderIntent::limit_sell(base_asset, quote_asset, qty, price),
+            _ => anyhow::bail!("try from SpotClientRequest to OrderIntent failed"),
+        };
+
+        Ok(intent)
+    }
+}
+
+// 请求名称(snake_case)用作指标标签
+#[derive(Clone, IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
 pub enum SpotClientRequest {
     Exchange,
     Symbol {
@@ -342,11 +1023,25 @@ pub enum SpotClientRequest {
     GetBalance {
         asset: String,
     },
+    GetBalances,
     GetOrder {
         base_asset: String,
         quote_asset: String,
         order_id: String,
     },
+    GetOpenOrders {
+        base_asset: String,
+        quote_asset: String,
+    },
+    CancelOrder {
+        base_asset: String,
+        quote_asset: String,
+        order_id: String,
+    },
+    CancelAllOrders {
+        base_asset: String,
+        quote_asset: String,
+    },
     MarketBuy {
         base_asset: String,
         quote_asset: String,
@@ -357,6 +1052,16 @@ pub enum SpotClientRequest {
         quote_asset: String,
         qty: f64,
     },
+    MarketBuyQuote {
+        base_asset: String,
+        quote_asset: String,
+        quote_qty: f64,
+    },
+    MarketSellQuote {
+        base_asset: String,
+        quote_asset: String,
+        quote_qty: f64,
+    },
     LimitBuy {
         base_asset: String,
         quote_asset: String,
@@ -369,13 +1074,137 @@ pub enum SpotClientRequest {
         qty: f64,
         price: f64,
     },
+    StopLimitOrder {
+        base_asset: String,
+        quote_asset: String,
+        side: OrderSide,
+        qty: f64,
+        price: f64,
+        stop_price: f64,
+    },
+    OcoOrder {
+        base_asset: String,
+        quote_asset: String,
+        side: OrderSide,
+        qty: f64,
+        price: f64,
+        stop_price: f64,
+        stop_limit_price: f64,
+    },
     GetPrice {
         base_asset: String,
         quote_asset: String,
     },
+    GetMarginAccount,
+    MarginBorrow {
+        asset: String,
+        qty: f64,
+    },
+    MarginRepay {
+        asset: String,
+        qty: f64,
+    },
+    MarginBuy {
+        base_asset: String,
+        quote_asset: String,
+        qty: f64,
+    },
+    MarginSell {
+        base_asset: String,
+        quote_asset: String,
+        qty: f64,
+    },
+    // 带客户端订单ID下单，用于失败后查询订单是否已提交
+    SubmitOrder {
+        client_order_id: String,
+        intent: OrderIntent,
+    },
+    GetOrderByClientId {
+        base_asset: String,
+        quote_asset: String,
+        client_order_id: String,
+    },
 }
 
 impl SpotClientRequest {
+    // 下单请求的交易对，用于检查交易对黑白名单，查询和撤单请求返回None
+    pub fn trading_pair(&self) -> Option<(&str, &str)> {
+        match self {
+            SpotClientRequest::MarketBuy {
+                base_asset,
+                quote_asset,
+                ..
+            }
+            | SpotClientRequest::MarketSell {
+                base_asset,
+                quote_asset,
+                ..
+            }
+            | SpotClientRequest::MarketBuyQuote {
+                base_asset,
+                quote_asset,
+                ..
+            }
+            | SpotClientRequest::MarketSellQuote {
+                base_asset,
+                quote_asset,
+                ..
+            }
+            | SpotClientRequest::LimitBuy {
+                base_asset,
+                quote_asset,
+                ..
+            }
+            | SpotClientRequest::LimitSell {
+                base_asset,
+                quote_asset,
+                ..
+            }
+            | SpotClientRequest::StopLimitOrder {
+                base_asset,
+                quote_asset,
+                ..
+            }
+            | SpotClientRequest::OcoOrder {
+                base_asset,
+                quote_asset,
+                ..
+            }
+            | SpotClientRequest::MarginBuy {
+                base_asset,
+                quote_asset,
+                ..
+            }
+            | SpotClientRequest::MarginSell {
+                base_asset,
+                quote_asset,
+                ..
+            } => Some((base_asset, quote_asset)),
+            SpotClientRequest::SubmitOrder { intent, .. } => {
+                Some((intent.base_asset(), intent.quote_asset()))
+            }
+            _ => None,
+        }
+    }
+
+    // 下单请求的买卖方向，查询和撤单请求返回None
+    pub fn order_side(&self) -> Option<OrderSide> {
+        match self {
+            SpotClientRequest::MarketBuy { .. }
+            | SpotClientRequest::MarketBuyQuote { .. }
+            | SpotClientRequest::LimitBuy { .. }
+            | SpotClientRequest::MarginBuy { .. } => Some(OrderSide::Buy),
+            SpotClientRequest::MarketSell { .. }
+            | SpotClientRequest::MarketSellQuote { .. }
+            | SpotClientRequest::LimitSell { .. }
+            | SpotClientRequest::MarginSell { .. } => Some(OrderSide::Sell),
+            SpotClientRequest::StopLimitOrder { side, .. }
+            | SpotClientRequest::OcoOrder { side, .. } => Some(side.clone()),
+            SpotClientRequest::SubmitOrder { intent, .. } => Some(intent.side()),
+            _ => None,
+        }
+    }
+
     pub fn exchange() -> Self {
         SpotClientRequest::Exchange
     }
@@ -404,8 +1233,13 @@ pub enum SpotClientResponse {
     AccountInformation(AccountInformation),
     SymbolInformation(SymbolInformation),
     Balance(Balance),
+    Balances(Vec<Balance>),
     Order(Order),
+    OptionalOrder(Option<Order>),
+    Orders(Vec<Order>),
     SymbolPrice(SymbolPrice),
+    MarginAccount(MarginAccount),
+    MarginTransaction(MarginTransaction),
 }
 
 impl From<Exchange> for SpotClientResponse {
@@ -432,12 +1266,30 @@ impl From<Balance> for SpotClientResponse {
     }
 }
 
+impl From<Vec<Balance>> for SpotClientResponse {
+    fn from(value: Vec<Balance>) -> Self {
+        SpotClientResponse::Balances(value)
+    }
+}
+
 impl From<Order> for SpotClientResponse {
     fn from(value: Order) -> Self {
         SpotClientResponse::Order(value)
     }
 }
 
+impl From<Option<Order>> for SpotClientResponse {
+    fn from(value: Option<Order>) -> Self {
+        SpotClientResponse::OptionalOrder(value)
+    }
+}
+
+impl From<Vec<Order>> for SpotClientResponse {
+    fn from(value: Vec<Order>) -> Self {
+        SpotClientResponse::Orders(value)
+    }
+}
+
 impl From<SymbolPrice> for SpotClientResponse {
     fn from(value: SymbolPrice) -> Self {
         SpotClientResponse::SymbolPrice(value)
@@ -492,6 +1344,18 @@ impl TryFrom<SpotClientResponse> for Balance {
     }
 }
 
+impl TryFrom<SpotClientResponse> for Vec<Balance> {
+    type Error = anyhow::Error;
+
+    fn try_from(value: SpotClientResponse) -> Result<Self, Self::Error> {
+        let SpotClientResponse::Balances(balances) = value else {
+            anyhow::bail!("try from SpotClientResponse to Vec<Balance> failed")
+        };
+
+        Ok(balances)
+    }
+}
+
 impl TryFrom<SpotClientResponse> for Order {
     type Error = anyhow::Error;
 
@@ -504,6 +1368,30 @@ impl TryFrom<SpotClientResponse> for Order {
     }
 }
 
+impl TryFrom<SpotClientResponse> for Option<Order> {
+    type Error = anyhow::Error;
+
+    fn try_from(value: SpotClientResponse) -> Result<Self, Self::Error> {
+        let SpotClientResponse::OptionalOrder(order) = value else {
+            anyhow::bail!("try from SpotClientResponse to Option<Order> failed")
+        };
+
+        Ok(order)
+    }
+}
+
+impl TryFrom<SpotClientResponse> for Vec<Order> {
+    type Error = anyhow::Error;
+
+    fn try_from(value: SpotClientResponse) -> Result<Self, Self::Error> {
+        let SpotClientResponse::Orders(orders) = value else {
+            anyhow::bail!("try from SpotClientResponse to Vec<Order> failed")
+        };
+
+        Ok(orders)
+    }
+}
+
 impl TryFrom<SpotClientResponse> for SymbolPrice {
     type Error = anyhow::Error;
 
@@ -515,3 +1403,39 @@ impl TryFrom<SpotClientResponse> for SymbolPrice {
         Ok(symbol_price)
     }
 }
+
+impl From<MarginAccount> for SpotClientResponse {
+    fn from(value: MarginAccount) -> Self {
+        SpotClientResponse::MarginAccount(value)
+    }
+}
+
+impl From<MarginTransaction> for SpotClientResponse {
+    fn from(value: MarginTransaction) -> Self {
+        SpotClientResponse::MarginTransaction(value)
+    }
+}
+
+impl TryFrom<SpotClientResponse> for MarginAccount {
+    type Error = anyhow::Error;
+
+    fn try_from(value: SpotClientResponse) -> Result<Self, Self::Error> {
+        let SpotClientResponse::MarginAccount(margin_account) = value else {
+            anyhow::bail!("try from SpotClientResponse to MarginAccount failed")
+        };
+
+        Ok(margin_account)
+    }
+}
+
+impl TryFrom<SpotClientResponse> for MarginTransaction {
+    type Error = anyhow::Error;
+
+    fn try_from(value: SpotClientResponse) -> Result<Self, Self::Error> {
+        let SpotClientResponse::MarginTransaction(margin_transaction) = value else {
+            anyhow::bail!("try from SpotClientResponse to MarginTransaction failed")
+        };
+
+        Ok(margin_transaction)
+    }
+}
@@ -1,19 +1,29 @@
 use super::base::{
-    AccountInformation, Balance, BinanceOrder, BinanceTransaction, Order, SymbolInformation,
-    SymbolPrice,
+    AccountInformation, Balance, BinanceMarginOrder, BinanceOrder, BinanceTransaction,
+    MarginAccount, MarginTransaction, Order, OrderIntent, OrderSide, OrderStatus, OrderType,
+    SymbolInformation, SymbolPrice, UserDataEvent,
 };
 use crate::{
     client::spot_client_kind::{SpotClientExecutable, SpotclientExecutableExt},
-    exchange::binance::BinanceClient,
+    exchange::{
+        binance::{BinanceClient, UserStreamEvent},
+        ConnectionOptions,
+    },
 };
 use anyhow::Result;
 use binance::config::Config;
 use bon::bon;
 use comfy_quant_base::Exchange;
+use futures::StreamExt;
+use rust_decimal::{prelude::FromPrimitive, Decimal};
+use tokio::sync::broadcast;
+
+const USER_DATA_CAPACITY: usize = 1024; // 用户数据事件的缓冲数量
 
 #[derive(Debug, Clone)]
 pub struct BinanceSpotClient {
     client: BinanceClient,
+    user_data: broadcast::Sender<UserDataEvent>, // 用户数据流推送的事件，克隆的客户端共享
 }
 
 #[bon]
@@ -23,14 +33,42 @@ impl BinanceSpotClient {
         api_key: Option<String>,
         secret_key: Option<String>,
         config: Option<Config>,
-    ) -> Self {
+        connection: Option<ConnectionOptions>,
+    ) -> Result<Self> {
         let client = BinanceClient::builder()
             .maybe_api_key(api_key)
             .maybe_secret_key(secret_key)
             .maybe_config(config)
-            .build();
+            .maybe_connection(connection)
+            .build()?;
+
+        let (user_data, _) = broadcast::channel(USER_DATA_CAPACITY);
 
-        BinanceSpotClient { client }
+        Ok(BinanceSpotClient { client, user_data })
+    }
+
+    // 订阅用户数据流，将订单更新和余额变化转发给所有订阅者，数据流结束时返回错误
+    pub async fn run_user_data_stream(&self) -> Result<()> {
+        let user_stream = self.client.spot_user_stream();
+        let mut stream = user_stream.subscribe().await?;
+
+        while let Some(event) = stream.next().await {
+            let event = match event {
+                UserStreamEvent::OrderTrade(event) => match UserDataEvent::try_from(*event) {
+                    Ok(event) => event,
+                    Err(e) => {
+                        tracing::warn!("Skip binance order update: {}", e);
+                        continue;
+                    }
+                },
+                UserStreamEvent::AccountPosition(event) => event.into(),
+            };
+
+            // 没有订阅者时丢弃
+            let _ = self.user_data.send(event);
+        }
+
+        anyhow::bail!("Binance user data stream closed")
     }
 }
 
@@ -39,8 +77,17 @@ impl SpotClientExecutable for BinanceSpotClient {
         Exchange::Binance
     }
 
+    // 以API Key标识账户
+    fn account_id(&self) -> String {
+        self.client.api_key().unwrap_or_default().to_string()
+    }
+
+    fn subscribe_user_data(&self) -> Option<broadcast::Receiver<UserDataEvent>> {
+        Some(self.user_data.subscribe())
+    }
+
     async fn get_account(&self) -> Result<AccountInformation> {
-        self.client.spot().get_account()?.try_into()
+        self.client.spot().get_account().await?.try_into()
     }
 
     async fn get_symbol_info(
@@ -49,16 +96,32 @@ impl SpotClientExecutable for BinanceSpotClient {
         quote_asset: &str,
     ) -> Result<SymbolInformation> {
         let symbol = self.symbol(base_asset, quote_asset);
-        let symbol_info = self.client.spot().get_symbol_info(symbol)?;
+        let symbol_info = self.client.spot().get_symbol_info(symbol).await?;
         Ok(symbol_info.into())
     }
 
     async fn get_balance(&self, asset: &str) -> Result<Balance> {
         let asset = asset.to_uppercase();
-        let balance = self.client.spot().get_balance(asset)?;
+        let balance = self.client.spot().get_balance(asset).await?;
         Ok(balance.into())
     }
 
+    // 账户信息返回所有币种，过滤掉没有余额的币种
+    async fn get_balances(&self) -> Result<Vec<Balance>> {
+        let balances = self
+            .client
+            .spot()
+            .get_account()
+            .await?
+            .balances
+            .into_iter()
+            .map(Balance::from)
+            .filter(|balance| !balance.is_empty())
+            .collect();
+
+        Ok(balances)
+    }
+
     async fn get_order(
         &self,
         base_asset: &str,
@@ -66,7 +129,11 @@ impl SpotClientExecutable for BinanceSpotClient {
         order_id: &str,
     ) -> Result<Order> {
         let symbol = self.symbol(base_asset, quote_asset);
-        let order = self.client.spot().get_order(symbol, order_id.parse()?)?;
+        let order = self
+            .client
+            .spot()
+            .get_order(symbol, order_id.parse()?)
+            .await?;
 
         BinanceOrder::builder()
             .base_asset(base_asset)
@@ -76,9 +143,65 @@ impl SpotClientExecutable for BinanceSpotClient {
             .try_into()
     }
 
+    async fn get_open_orders(&self, base_asset: &str, quote_asset: &str) -> Result<Vec<Order>> {
+        let symbol = self.symbol(base_asset, quote_asset);
+
+        self.client
+            .spot()
+            .get_open_orders(symbol)
+            .await?
+            .into_iter()
+            .map(|order| {
+                BinanceOrder::builder()
+                    .base_asset(base_asset)
+                    .quote_asset(quote_asset)
+                    .order(order)
+                    .build()
+                    .try_into()
+            })
+            .collect()
+    }
+
+    async fn cancel_order(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        order_id: &str,
+    ) -> Result<Order> {
+        let symbol = self.symbol(base_asset, quote_asset);
+        self.client
+            .spot()
+            .cancel_order(symbol, order_id.parse()?)
+            .await?;
+
+        // 撤单接口只返回订单ID，重新查询撤销后的订单
+        self.get_order(base_asset, quote_asset, order_id).await
+    }
+
+    async fn cancel_all_orders(&self, base_asset: &str, quote_asset: &str) -> Result<Vec<Order>> {
+        let symbol = self.symbol(base_asset, quote_asset);
+        let open_orders = self.get_open_orders(base_asset, quote_asset).await?;
+
+        if open_orders.is_empty() {
+            return Ok(open_orders);
+        }
+
+        self.client.spot().cancel_all_open_orders(symbol).await?;
+
+        let orders = open_orders
+            .into_iter()
+            .map(|order| Order {
+                order_status: OrderStatus::Canceled,
+                ..order
+            })
+            .collect();
+
+        Ok(orders)
+    }
+
     async fn market_buy(&self, base_asset: &str, quote_asset: &str, qty: f64) -> Result<Order> {
         let symbol = self.symbol(base_asset, quote_asset);
-        let tx = self.client.spot().market_buy(symbol, qty)?;
+        let tx = self.client.spot().market_buy(symbol, qty).await?;
 
         BinanceTransaction::builder()
             .base_asset(base_asset)
@@ -90,7 +213,49 @@ impl SpotClientExecutable for BinanceSpotClient {
 
     async fn market_sell(&self, base_asset: &str, quote_asset: &str, qty: f64) -> Result<Order> {
         let symbol = self.symbol(base_asset, quote_asset);
-        let tx = self.client.spot().market_sell(symbol, qty)?;
+        let tx = self.client.spot().market_sell(symbol, qty).await?;
+
+        BinanceTransaction::builder()
+            .base_asset(base_asset)
+            .quote_asset(quote_asset)
+            .transaction(tx)
+            .build()
+            .try_into()
+    }
+
+    async fn market_buy_quote(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        quote_qty: f64,
+    ) -> Result<Order> {
+        let symbol = self.symbol(base_asset, quote_asset);
+        let tx = self
+            .client
+            .spot()
+            .market_buy_quote(symbol, quote_qty)
+            .await?;
+
+        BinanceTransaction::builder()
+            .base_asset(base_asset)
+            .quote_asset(quote_asset)
+            .transaction(tx)
+            .build()
+            .try_into()
+    }
+
+    async fn market_sell_quote(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        quote_qty: f64,
+    ) -> Result<Order> {
+        let symbol = self.symbol(base_asset, quote_asset);
+        let tx = self
+            .client
+            .spot()
+            .market_sell_quote(symbol, quote_qty)
+            .await?;
 
         BinanceTransaction::builder()
             .base_asset(base_asset)
@@ -108,7 +273,7 @@ impl SpotClientExecutable for BinanceSpotClient {
         price: f64,
     ) -> Result<Order> {
         let symbol = self.symbol(base_asset, quote_asset);
-        let tx = self.client.spot().limit_buy(symbol, qty, price)?;
+        let tx = self.client.spot().limit_buy(symbol, qty, price).await?;
 
         BinanceTransaction::builder()
             .base_asset(base_asset)
@@ -126,7 +291,7 @@ impl SpotClientExecutable for BinanceSpotClient {
         price: f64,
     ) -> Result<Order> {
         let symbol = self.symbol(base_asset, quote_asset);
-        let tx = self.client.spot().limit_sell(symbol, qty, price)?;
+        let tx = self.client.spot().limit_sell(symbol, qty, price).await?;
 
         BinanceTransaction::builder()
             .base_asset(base_asset)
@@ -136,8 +301,185 @@ impl SpotClientExecutable for BinanceSpotClient {
             .try_into()
     }
 
+    async fn stop_limit_order(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        side: OrderSide,
+        qty: f64,
+        price: f64,
+        stop_price: f64,
+    ) -> Result<Order> {
+        let symbol = self.symbol(base_asset, quote_asset);
+        let tx = match side {
+            OrderSide::Buy => {
+                self.client
+                    .spot()
+                    .stop_limit_buy(symbol, qty, price, stop_price)
+                    .await?
+            }
+            OrderSide::Sell => {
+                self.client
+                    .spot()
+                    .stop_limit_sell(symbol, qty, price, stop_price)
+                    .await?
+            }
+        };
+
+        // 下单结果不包含止损价格，重新查询订单
+        let order_id = tx.order_id.to_string();
+        self.get_order(base_asset, quote_asset, &order_id).await
+    }
+
+    async fn oco_order(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        side: OrderSide,
+        qty: f64,
+        price: f64,
+        stop_price: f64,
+        stop_limit_price: f64,
+    ) -> Result<Vec<Order>> {
+        let symbol = self.symbol(base_asset, quote_asset);
+        let side = match side {
+            OrderSide::Buy => "BUY",
+            OrderSide::Sell => "SELL",
+        };
+
+        let oco_order = self
+            .client
+            .spot()
+            .oco_order(symbol, side, qty, price, stop_price, stop_limit_price)
+            .await?;
+
+        let mut orders = Vec::with_capacity(oco_order.orders.len());
+
+        for order in oco_order.orders {
+            let order_id = order.order_id.to_string();
+            orders.push(self.get_order(base_asset, quote_asset, &order_id).await?);
+        }
+
+        // 限价单在前
+        orders.sort_by_key(|order| order.order_type != OrderType::LimitMaker);
+
+        Ok(orders)
+    }
+
     async fn get_price(&self, base_asset: &str, quote_asset: &str) -> Result<SymbolPrice> {
         let symbol = self.symbol(base_asset, quote_asset);
-        self.client.spot().get_price(symbol)?.try_into()
+        self.client.spot().get_price(symbol).await?.try_into()
+    }
+
+    async fn get_margin_account(&self) -> Result<MarginAccount> {
+        self.client.margin().get_account().await?.try_into()
     }
+
+    async fn margin_borrow(&self, asset: &str, qty: f64) -> Result<MarginTransaction> {
+        let asset = asset.to_uppercase();
+        let tx = self.client.margin().borrow(&asset, qty).await?;
+
+        margin_transaction(tx.tran_id, asset, qty)
+    }
+
+    async fn margin_repay(&self, asset: &str, qty: f64) -> Result<MarginTransaction> {
+        let asset = asset.to_uppercase();
+        let tx = self.client.margin().repay(&asset, qty).await?;
+
+        margin_transaction(tx.tran_id, asset, qty)
+    }
+
+    async fn margin_buy(&self, base_asset: &str, quote_asset: &str, qty: f64) -> Result<Order> {
+        let symbol = self.symbol(base_asset, quote_asset);
+        let order = self
+            .client
+            .margin()
+            .market_buy(symbol.as_ref(), qty)
+            .await?;
+
+        BinanceMarginOrder::builder()
+            .base_asset(base_asset)
+            .quote_asset(quote_asset)
+            .order(order)
+            .build()
+            .try_into()
+    }
+
+    async fn margin_sell(&self, base_asset: &str, quote_asset: &str, qty: f64) -> Result<Order> {
+        let symbol = self.symbol(base_asset, quote_asset);
+        let order = self
+            .client
+            .margin()
+            .market_sell(symbol.as_ref(), qty)
+            .await?;
+
+        BinanceMarginOrder::builder()
+            .base_asset(base_asset)
+            .quote_asset(quote_asset)
+            .order(order)
+            .build()
+            .try_into()
+    }
+
+    async fn submit_order(&self, client_order_id: &str, intent: OrderIntent) -> Result<Order> {
+        let (base_asset, quote_asset) = (intent.base_asset(), intent.quote_asset());
+        let symbol = self.symbol(base_asset, quote_asset);
+        let (qty, price) = match &intent {
+            OrderIntent::MarketBuy { qty, .. } | OrderIntent::MarketSell { qty, .. } => {
+                (*qty, None)
+            }
+            OrderIntent::LimitBuy { qty, price, .. }
+            | OrderIntent::LimitSell { qty, price, .. } => (*qty, Some(*price)),
+        };
+
+        let tx = self
+            .client
+            .spot()
+            .order_with_client_id(symbol, intent.side().as_ref(), qty, price, client_order_id)
+            .await?;
+
+        BinanceTransaction::builder()
+            .base_asset(base_asset)
+            .quote_asset(quote_asset)
+            .transaction(tx)
+            .build()
+            .try_into()
+    }
+
+    async fn get_order_by_client_id(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        client_order_id: &str,
+    ) -> Result<Option<Order>> {
+        let symbol = self.symbol(base_asset, quote_asset);
+        let Some(order) = self
+            .client
+            .spot()
+            .get_order_by_client_id(symbol, client_order_id)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let order = BinanceOrder::builder()
+            .base_asset(base_asset)
+            .quote_asset(quote_asset)
+            .order(order)
+            .build()
+            .try_into()?;
+
+        Ok(Some(order))
+    }
+}
+
+fn margin_transaction(tran_id: u64, asset: String, qty: f64) -> Result<MarginTransaction> {
+    let qty = Decimal::from_f64(qty)
+        .ok_or_else(|| anyhow::anyhow!("margin transaction qty convert decimal failed"))?;
+
+    Ok(MarginTransaction::builder()
+        .tran_id(tran_id.to_string())
+        .asset(asset)
+        .qty(qty)
+        .build())
 }
@@ -0,0 +1,83 @@
+use super::base::OrderSide;
+use bon::Builder;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+// 市价单执行模型：固定滑点、按成交量估算的冲击成本，以及以tick计的下单延迟
+#[derive(Builder, Debug, Clone, Copy, Default, PartialEq)]
+pub struct ExecutionModel {
+    #[builder(default)]
+    slippage_bps: Decimal, // 固定滑点(基点)
+    #[builder(default)]
+    impact: Decimal, // 冲击系数，滑点额外增加 冲击系数 * 下单数量 / 最近周期成交量
+    #[builder(default)]
+    latency_ticks: u32, // 下单延迟，订单在之后第N个tick的价格成交
+}
+
+impl ExecutionModel {
+    pub fn latency_ticks(&self) -> u32 {
+        self.latency_ticks
+    }
+
+    // 滑点比例，没有成交量数据时只计固定滑点
+    pub fn slippage(&self, qty: Decimal, volume: Option<Decimal>) -> Decimal {
+        let impact = volume
+            .filter(|volume| *volume > dec!(0))
+            .map(|volume| self.impact * qty / volume)
+            .unwrap_or_default();
+
+        self.slippage_bps / dec!(10000) + impact
+    }
+
+    // 成交价格，买入向上、卖出向下偏移
+    pub fn fill_price(
+        &self,
+        side: &OrderSide,
+        price: Decimal,
+        qty: Decimal,
+        volume: Option<Decimal>,
+    ) -> Decimal {
+        let slippage = self.slippage(qty, volume);
+
+        match side {
+            OrderSide::Buy => price * (dec!(1) + slippage),
+            OrderSide::Sell => (price * (dec!(1) - slippage)).max(dec!(0)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_execution_model() {
+        let model = ExecutionModel::default();
+        assert_eq!(
+            model.fill_price(&OrderSide::Buy, dec!(30000), dec!(1), None),
+            dec!(30000)
+        );
+
+        let model = ExecutionModel::builder().slippage_bps(dec!(5)).build();
+        assert_eq!(
+            model.fill_price(&OrderSide::Buy, dec!(30000), dec!(1), Some(dec!(10))),
+            dec!(30015)
+        );
+        assert_eq!(
+            model.fill_price(&OrderSide::Sell, dec!(30000), dec!(1), Some(dec!(10))),
+            dec!(29985)
+        );
+
+        // 下单数量占周期成交量的10%，冲击系数0.01，额外滑点0.1%
+        let model = ExecutionModel::builder()
+            .slippage_bps(dec!(5))
+            .impact(dec!(0.01))
+            .latency_ticks(2)
+            .build();
+        assert_eq!(model.latency_ticks(), 2);
+        assert_eq!(model.slippage(dec!(1), Some(dec!(10))), dec!(0.0015));
+        // 没有成交量数据时只计固定滑点
+        assert_eq!(model.slippage(dec!(1), None), dec!(0.0005));
+        assert_eq!(model.slippage(dec!(1), Some(dec!(0))), dec!(0.0005));
+    }
+}
@@ -0,0 +1,129 @@
+use bon::Builder;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+// 手续费等级
+#[derive(Builder, Debug, Clone, PartialEq)]
+pub struct FeeTier {
+    pub level: u8,               // VIP等级
+    pub min_volume_30d: Decimal, // 30日成交额下限(USDT)
+    pub maker_rate: Decimal,     // 挂单手续费率
+    pub taker_rate: Decimal,     // 吃单手续费率
+}
+
+// 手续费表，按30日成交额分级
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeeSchedule {
+    tiers: Vec<FeeTier>, // 按等级升序
+}
+
+impl FeeSchedule {
+    pub fn new(mut tiers: Vec<FeeTier>) -> Self {
+        tiers.sort_by_key(|tier| tier.level);
+        FeeSchedule { tiers }
+    }
+
+    // 币安现货费率表(未使用BNB抵扣)
+    pub fn binance_spot() -> Self {
+        let tiers = [
+            (0, dec!(0), dec!(0.001), dec!(0.001)),
+            (1, dec!(1000000), dec!(0.0009), dec!(0.001)),
+            (2, dec!(5000000), dec!(0.0008), dec!(0.001)),
+            (3, dec!(20000000), dec!(0.00042), dec!(0.0006)),
+            (4, dec!(100000000), dec!(0.00042), dec!(0.00054)),
+            (5, dec!(150000000), dec!(0.00036), dec!(0.00048)),
+            (6, dec!(400000000), dec!(0.0003), dec!(0.00042)),
+            (7, dec!(800000000), dec!(0.00024), dec!(0.00036)),
+            (8, dec!(2000000000), dec!(0.00018), dec!(0.0003)),
+            (9, dec!(4000000000), dec!(0.00012), dec!(0.00024)),
+        ]
+        .into_iter()
+        .map(|(level, min_volume_30d, maker_rate, taker_rate)| {
+            FeeTier::builder()
+                .level(level)
+                .min_volume_30d(min_volume_30d)
+                .maker_rate(maker_rate)
+                .taker_rate(taker_rate)
+                .build()
+        })
+        .collect();
+
+        FeeSchedule::new(tiers)
+    }
+
+    pub fn tiers(&self) -> &[FeeTier] {
+        &self.tiers
+    }
+
+    // 指定VIP等级的费率，超出范围时取最高等级
+    pub fn tier_by_level(&self, level: u8) -> Option<&FeeTier> {
+        self.tiers
+            .iter()
+            .rev()
+            .find(|tier| tier.level <= level)
+            .or_else(|| self.tiers.first())
+    }
+
+    // 根据30日成交额计算可达到的等级
+    pub fn tier_by_volume(&self, volume_30d: &Decimal) -> Option<&FeeTier> {
+        self.tiers
+            .iter()
+            .rev()
+            .find(|tier| tier.min_volume_30d <= *volume_30d)
+            .or_else(|| self.tiers.first())
+    }
+
+    // 最低VIP等级为level，且随成交额累积升级
+    pub fn tier_with_progression(&self, level: u8, volume_30d: &Decimal) -> Option<&FeeTier> {
+        let by_level = self.tier_by_level(level)?;
+        let by_volume = self.tier_by_volume(volume_30d)?;
+
+        if by_volume.level > by_level.level {
+            Some(by_volume)
+        } else {
+            Some(by_level)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fee_schedule_tier_by_level() {
+        let schedule = FeeSchedule::binance_spot();
+
+        let tier = schedule.tier_by_level(0).unwrap();
+        assert_eq!(tier.maker_rate, dec!(0.001));
+        assert_eq!(tier.taker_rate, dec!(0.001));
+
+        let tier = schedule.tier_by_level(3).unwrap();
+        assert_eq!(tier.maker_rate, dec!(0.00042));
+        assert_eq!(tier.taker_rate, dec!(0.0006));
+
+        let tier = schedule.tier_by_level(20).unwrap();
+        assert_eq!(tier.level, 9);
+    }
+
+    #[test]
+    fn test_fee_schedule_tier_by_volume() {
+        let schedule = FeeSchedule::binance_spot();
+
+        assert_eq!(schedule.tier_by_volume(&dec!(0)).unwrap().level, 0);
+        assert_eq!(schedule.tier_by_volume(&dec!(999999)).unwrap().level, 0);
+        assert_eq!(schedule.tier_by_volume(&dec!(1000000)).unwrap().level, 1);
+        assert_eq!(schedule.tier_by_volume(&dec!(5000000000)).unwrap().level, 9);
+    }
+
+    #[test]
+    fn test_fee_schedule_tier_with_progression() {
+        let schedule = FeeSchedule::binance_spot();
+
+        let tier = schedule.tier_with_progression(2, &dec!(1000000)).unwrap();
+        assert_eq!(tier.level, 2);
+
+        let tier = schedule.tier_with_progression(2, &dec!(20000000)).unwrap();
+        assert_eq!(tier.level, 3);
+    }
+}
@@ -0,0 +1,380 @@
+use super::base::{
+    AccountInformation, Balance, MarginAccount, MarginTransaction, Order, OrderIntent, OrderSide,
+    SpotClientRequest, SpotClientResponse, SymbolInformation, SymbolPrice, UserDataEvent,
+};
+use crate::client::spot_client_kind::{SpotClientExecutable, SpotClientKind};
+use anyhow::Result;
+use comfy_quant_base::Exchange;
+use futures::future::BoxFuture;
+use std::{fmt, sync::Arc};
+use tokio::sync::broadcast;
+use tower::Service;
+
+// 下单前的检查，返回错误时拒绝该订单。inner 为被包装的客户端，可用于查询余额或撤单清仓
+pub trait OrderGuard: fmt::Debug + Send + Sync {
+    fn check<'a>(
+        &'a self,
+        inner: &'a SpotClientKind,
+        req: &'a SpotClientRequest,
+    ) -> BoxFuture<'a, Result<()>>;
+}
+
+// 包装其他客户端，下单请求先经过检查再转发，查询和撤单请求直接转发
+#[derive(Debug, Clone)]
+pub struct GuardedSpotClient {
+    inner: Box<SpotClientKind>,
+    guard: Arc<dyn OrderGuard>,
+}
+
+impl GuardedSpotClient {
+    pub fn new(inner: SpotClientKind, guard: Arc<dyn OrderGuard>) -> Self {
+        GuardedSpotClient {
+            inner: Box::new(inner),
+            guard,
+        }
+    }
+
+    pub fn inner(&self) -> &SpotClientKind {
+        &self.inner
+    }
+
+    // 通过 Service 转发，返回装箱的 future，避免与 SpotClientKind 的 future 互相嵌套
+    async fn forward<T>(&self, req: SpotClientRequest) -> Result<T>
+    where
+        T: TryFrom<SpotClientResponse, Error = anyhow::Error>,
+    {
+        if req.trading_pair().is_some() {
+            self.guard.check(&self.inner, &req).await?;
+        }
+
+        (*self.inner).clone().call(req).await?.try_into()
+    }
+}
+
+impl SpotClientExecutable for GuardedSpotClient {
+    fn exchange(&self) -> Exchange {
+        self.inner.exchange()
+    }
+
+    fn account_id(&self) -> String {
+        self.inner.account_id()
+    }
+
+    fn subscribe_user_data(&self) -> Option<broadcast::Receiver<UserDataEvent>> {
+        self.inner.subscribe_user_data()
+    }
+
+    async fn get_account(&self) -> Result<AccountInformation> {
+        self.forward(SpotClientRequest::GetAccount).await
+    }
+
+    async fn get_symbol_info(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+    ) -> Result<SymbolInformation> {
+        self.forward(SpotClientRequest::get_symbol_info(base_asset, quote_asset))
+            .await
+    }
+
+    async fn get_balance(&self, asset: &str) -> Result<Balance> {
+        self.forward(SpotClientRequest::get_balance(asset)).await
+    }
+
+    async fn get_balances(&self) -> Result<Vec<Balance>> {
+        self.forward(SpotClientRequest::GetBalances).await
+    }
+
+    async fn get_order(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        order_id: &str,
+    ) -> Result<Order> {
+        self.forward(SpotClientRequest::GetOrder {
+            base_asset: base_asset.to_string(),
+            quote_asset: quote_asset.to_string(),
+            order_id: order_id.to_string(),
+        })
+        .await
+    }
+
+    async fn get_open_orders(&self, base_asset: &str, quote_asset: &str) -> Result<Vec<Order>> {
+        self.forward(SpotClientRequest::GetOpenOrders {
+            base_asset: base_asset.to_string(),
+            quote_asset: quote_asset.to_string(),
+        })
+        .await
+    }
+
+    async fn cancel_order(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        order_id: &str,
+    ) -> Result<Order> {
+        self.forward(SpotClientRequest::CancelOrder {
+            base_asset: base_asset.to_string(),
+            quote_asset: quote_asset.to_string(),
+            order_id: order_id.to_string(),
+        })
+        .await
+    }
+
+    async fn cancel_all_orders(&self, base_asset: &str, quote_asset: &str) -> Result<Vec<Order>> {
+        self.forward(SpotClientRequest::CancelAllOrders {
+            base_asset: base_asset.to_string(),
+            quote_asset: quote_asset.to_string(),
+        })
+        .await
+    }
+
+    async fn market_buy(&self, base_asset: &str, quote_asset: &str, qty: f64) -> Result<Order> {
+        self.forward(SpotClientRequest::MarketBuy {
+            base_asset: base_asset.to_string(),
+            quote_asset: quote_asset.to_string(),
+            qty,
+        })
+        .await
+    }
+
+    async fn market_sell(&self, base_asset: &str, quote_asset: &str, qty: f64) -> Result<Order> {
+        self.forward(SpotClientRequest::MarketSell {
+            base_asset: base_asset.to_string(),
+            quote_asset: quote_asset.to_string(),
+            qty,
+        })
+        .await
+    }
+
+    async fn market_buy_quote(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        quote_qty: f64,
+    ) -> Result<Order> {
+        self.forward(SpotClientRequest::MarketBuyQuote {
+            base_asset: base_asset.to_string(),
+            quote_asset: quote_asset.to_string(),
+            quote_qty,
+        })
+        .await
+    }
+
+    async fn market_sell_quote(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        quote_qty: f64,
+    ) -> Result<Order> {
+        self.forward(SpotClientRequest::MarketSellQuote {
+            base_asset: base_asset.to_string(),
+            quote_asset: quote_asset.to_string(),
+            quote_qty,
+        })
+        .await
+    }
+
+    async fn limit_buy(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        qty: f64,
+        price: f64,
+    ) -> Result<Order> {
+        self.forward(SpotClientRequest::LimitBuy {
+            base_asset: base_asset.to_string(),
+            quote_asset: quote_asset.to_string(),
+            qty,
+            price,
+        })
+        .await
+    }
+
+    async fn limit_sell(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        qty: f64,
+        price: f64,
+    ) -> Result<Order> {
+        self.forward(SpotClientRequest::LimitSell {
+            base_asset: base_asset.to_string(),
+            quote_asset: quote_asset.to_string(),
+            qty,
+            price,
+        })
+        .await
+    }
+
+    async fn stop_limit_order(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        side: OrderSide,
+        qty: f64,
+        price: f64,
+        stop_price: f64,
+    ) -> Result<Order> {
+        self.forward(SpotClientRequest::StopLimitOrder {
+            base_asset: base_asset.to_string(),
+            quote_asset: quote_asset.to_string(),
+            side,
+            qty,
+            price,
+            stop_price,
+        })
+        .await
+    }
+
+    async fn oco_order(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        side: OrderSide,
+        qty: f64,
+        price: f64,
+        stop_price: f64,
+        stop_limit_price: f64,
+    ) -> Result<Vec<Order>> {
+        self.forward(SpotClientRequest::OcoOrder {
+            base_asset: base_asset.to_string(),
+            quote_asset: quote_asset.to_string(),
+            side,
+            qty,
+            price,
+            stop_price,
+            stop_limit_price,
+        })
+        .await
+    }
+
+    async fn get_price(&self, base_asset: &str, quote_asset: &str) -> Result<SymbolPrice> {
+        self.forward(SpotClientRequest::GetPrice {
+            base_asset: base_asset.to_string(),
+            quote_asset: quote_asset.to_string(),
+        })
+        .await
+    }
+
+    async fn get_margin_account(&self) -> Result<MarginAccount> {
+        self.forward(SpotClientRequest::GetMarginAccount).await
+    }
+
+    async fn margin_borrow(&self, asset: &str, qty: f64) -> Result<MarginTransaction> {
+        self.forward(SpotClientRequest::MarginBorrow {
+            asset: asset.to_string(),
+            qty,
+        })
+        .await
+    }
+
+    async fn margin_repay(&self, asset: &str, qty: f64) -> Result<MarginTransaction> {
+        self.forward(SpotClientRequest::MarginRepay {
+            asset: asset.to_string(),
+            qty,
+        })
+        .await
+    }
+
+    async fn margin_buy(&self, base_asset: &str, quote_asset: &str, qty: f64) -> Result<Order> {
+        self.forward(SpotClientRequest::MarginBuy {
+            base_asset: base_asset.to_string(),
+            quote_asset: quote_asset.to_string(),
+            qty,
+        })
+        .await
+    }
+
+    async fn margin_sell(&self, base_asset: &str, quote_asset: &str, qty: f64) -> Result<Order> {
+        self.forward(SpotClientRequest::MarginSell {
+            base_asset: base_asset.to_string(),
+            quote_asset: quote_asset.to_string(),
+            qty,
+        })
+        .await
+    }
+
+    async fn submit_order(&self, client_order_id: &str, intent: OrderIntent) -> Result<Order> {
+        self.forward(SpotClientRequest::SubmitOrder {
+            client_order_id: client_order_id.to_string(),
+            intent,
+        })
+        .await
+    }
+
+    async fn get_order_by_client_id(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        client_order_id: &str,
+    ) -> Result<Option<Order>> {
+        self.forward(SpotClientRequest::GetOrderByClientId {
+            base_asset: base_asset.to_string(),
+            quote_asset: quote_asset.to_string(),
+            client_order_id: client_order_id.to_string(),
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{client::spot_client::backtest_spot_client::BacktestSpotClient, store::PriceStore};
+    use async_lock::RwLock;
+    use comfy_quant_base::Market;
+    use rust_decimal_macros::dec;
+
+    // 拒绝所有买单
+    #[derive(Debug)]
+    struct DenyBuy;
+
+    impl OrderGuard for DenyBuy {
+        fn check<'a>(
+            &'a self,
+            _inner: &'a SpotClientKind,
+            req: &'a SpotClientRequest,
+        ) -> BoxFuture<'a, Result<()>> {
+            Box::pin(async move {
+                if req.order_side() == Some(OrderSide::Buy) {
+                    anyhow::bail!("Buy denied");
+                }
+
+                Ok(())
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_guarded_spot_client() -> Result<()> {
+        let price_store = Arc::new(RwLock::new(PriceStore::new()));
+        price_store.write().await.save_price(
+            &Exchange::Binance,
+            &Market::Spot,
+            &SymbolPrice::builder()
+                .symbol("BTCUSDT".into())
+                .price(dec!(50000))
+                .build(),
+        )?;
+
+        let inner: SpotClientKind = BacktestSpotClient::builder()
+            .assets(vec![("BTC".to_string(), 1.), ("USDT".to_string(), 1000.)])
+            .price_store(price_store)
+            .build()
+            .into();
+        let client: SpotClientKind = GuardedSpotClient::new(inner, Arc::new(DenyBuy)).into();
+
+        assert_eq!(client.exchange(), Exchange::Binance);
+        assert!(client.market_buy("BTC", "USDT", 0.01).await.is_err());
+
+        // 卖单和查询请求转发给被包装的客户端
+        let order = client.market_sell("BTC", "USDT", 0.5).await?;
+        assert_eq!(order.symbol, "BTCUSDT".into());
+
+        let balance = client.get_balance("BTC").await?;
+        assert!(balance.free.parse::<f64>()? < 1.);
+
+        Ok(())
+    }
+}
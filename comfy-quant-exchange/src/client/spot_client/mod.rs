@@ -1,3 +1,10 @@
 pub mod backtest_spot_client;
 pub mod base;
 pub mod binance_spot_client;
+pub mod execution_model;
+pub mod fee_schedule;
+pub mod guarded_spot_client;
+pub mod multi_spot_client;
+pub mod okx_spot_client;
+pub mod paper_spot_client;
+pub mod queue_model;
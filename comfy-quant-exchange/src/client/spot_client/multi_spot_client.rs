@@ -0,0 +1,759 @@
+use super::base::{
+    AccountInformation, Balance, MarginAccount, MarginTransaction, Order, OrderIntent, OrderSide,
+    OrderStatus, SpotClientRequest, SpotClientResponse, SymbolInformation, SymbolPrice,
+    UserDataEvent,
+};
+use crate::client::spot_client_kind::{SpotClientExecutable, SpotClientKind};
+use anyhow::{anyhow, Result};
+use comfy_quant_base::Exchange;
+use futures::future::join_all;
+use rust_decimal::{prelude::ToPrimitive, Decimal, RoundingStrategy};
+use std::collections::BTreeMap;
+use tokio::sync::broadcast;
+use tower::Service;
+
+// 多账户客户端：按权重把订单拆分到多个账户，余额为所有账户之和。
+// 拆分后的订单合并为一个订单返回，订单ID为 "账户序号:子订单ID"，多个子订单用逗号连接
+#[derive(Debug, Clone)]
+pub struct MultiSpotClient {
+    clients: Vec<SpotClientKind>, // 子账户
+    weights: Vec<Decimal>,        // 归一化后的权重
+}
+
+impl MultiSpotClient {
+    // 子账户需属于同一交易所，权重必须大于0
+    pub fn try_new(accounts: Vec<(SpotClientKind, Decimal)>) -> Result<Self> {
+        anyhow::ensure!(!accounts.is_empty(), "MultiSpotClient requires accounts");
+        anyhow::ensure!(
+            accounts.iter().all(|(_, weight)| *weight > Decimal::ZERO),
+            "Account weights must be greater than 0"
+        );
+
+        let exchange = accounts[0].0.exchange();
+        anyhow::ensure!(
+            accounts
+                .iter()
+                .all(|(client, _)| client.exchange() == exchange),
+            "Accounts must belong to the same exchange"
+        );
+
+        let total = accounts.iter().map(|(_, weight)| weight).sum::<Decimal>();
+        let (clients, weights) = accounts
+            .into_iter()
+            .map(|(client, weight)| (client, weight / total))
+            .unzip();
+
+        Ok(MultiSpotClient { clients, weights })
+    }
+
+    fn primary(&self) -> &SpotClientKind {
+        &self.clients[0]
+    }
+
+    // 按权重拆分数量，除最后一个账户外向下取整到步长和精度，余数归最后一个账户，
+    // 拆分后为0的账户不下单
+    fn split(&self, qty: f64, step_size: Option<Decimal>, dp: u32) -> Result<Vec<(usize, f64)>> {
+        let qty = Decimal::try_from(qty)?;
+        let last = self.clients.len() - 1;
+        let mut rest = qty;
+        let mut parts = Vec::with_capacity(self.clients.len());
+
+        for (index, weight) in self.weights.iter().enumerate() {
+            let part = if index == last {
+                rest
+            } else {
+                let part = qty * weight;
+                let part = match step_size.filter(|step_size| *step_size > Decimal::ZERO) {
+                    Some(step_size) => (part / step_size).floor() * step_size,
+                    None => part,
+                };
+
+                part.round_dp_with_strategy(dp, RoundingStrategy::ToZero)
+                    .min(rest)
+            };
+
+            rest -= part;
+
+            if part > Decimal::ZERO {
+                parts.push((index, to_f64(part)?));
+            }
+        }
+
+        Ok(parts)
+    }
+
+    // 按基础资产数量拆分
+    async fn split_qty(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        qty: f64,
+    ) -> Result<Vec<(usize, f64)>> {
+        let info = self.get_symbol_info(base_asset, quote_asset).await?;
+        self.split(qty, info.step_size, info.base_asset_precision)
+    }
+
+    // 按计价资产金额拆分
+    async fn split_quote_qty(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        quote_qty: f64,
+    ) -> Result<Vec<(usize, f64)>> {
+        let info = self.get_symbol_info(base_asset, quote_asset).await?;
+        self.split(quote_qty, None, info.quote_asset_precision)
+    }
+
+    // 并发向各账户下单。部分账户失败时返回已成功的订单，全部失败时返回第一个错误
+    async fn fan_out<T, F>(&self, parts: Vec<(usize, f64)>, make_req: F) -> Result<Vec<(usize, T)>>
+    where
+        T: TryFrom<SpotClientResponse, Error = anyhow::Error>,
+        F: Fn(usize, f64) -> SpotClientRequest,
+    {
+        let futures = parts.into_iter().map(|(index, qty)| {
+            let req = make_req(index, qty);
+            async move { (index, call::<T>(&self.clients[index], req).await) }
+        });
+
+        let mut placed = Vec::new();
+        let mut first_error = None;
+
+        for (index, result) in join_all(futures).await {
+            match result {
+                Ok(value) => placed.push((index, value)),
+                Err(e) => {
+                    tracing::warn!(account = index, "MultiSpotClient order failed: {}", e);
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+
+        match first_error {
+            Some(e) if placed.is_empty() => Err(e),
+            _ => Ok(placed),
+        }
+    }
+
+    // 并发查询或撤销子订单
+    async fn for_each_order<F>(&self, order_id: &str, make_req: F) -> Result<Order>
+    where
+        F: Fn(String) -> SpotClientRequest,
+    {
+        let futures = parse_order_id(order_id, self.clients.len())?
+            .into_iter()
+            .map(|(index, order_id)| {
+                let req = make_req(order_id);
+                async move {
+                    call::<Order>(&self.clients[index], req)
+                        .await
+                        .map(|order| (index, order))
+                }
+            });
+
+        let orders = join_all(futures)
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>>>()?;
+
+        merge_orders(orders)
+    }
+
+    // 各账户的订单，订单ID加上账户序号
+    async fn for_each_account<F>(&self, make_req: F) -> Result<Vec<Order>>
+    where
+        F: Fn() -> SpotClientRequest,
+    {
+        let futures = self.clients.iter().enumerate().map(|(index, client)| {
+            let req = make_req();
+            async move {
+                call::<Vec<Order>>(client, req)
+                    .await
+                    .map(|orders| (index, orders))
+            }
+        });
+
+        let mut orders = Vec::new();
+
+        for result in join_all(futures).await {
+            let (index, account_orders) = result?;
+            orders.extend(account_orders.into_iter().map(|mut order| {
+                order.order_id = format!("{}:{}", index, order.order_id);
+                order
+            }));
+        }
+
+        Ok(orders)
+    }
+}
+
+impl SpotClientExecutable for MultiSpotClient {
+    fn exchange(&self) -> Exchange {
+        self.primary().exchange()
+    }
+
+    fn account_id(&self) -> String {
+        self.clients
+            .iter()
+            .map(|client| client.account_id())
+            .collect::<Vec<_>>()
+            .join("+")
+    }
+
+    // 子账户推送的订单ID与合并后的订单ID不一致，不转发推送，策略通过查询订单获取状态
+    fn subscribe_user_data(&self) -> Option<broadcast::Receiver<UserDataEvent>> {
+        None
+    }
+
+    // 手续费率以第一个账户为准，所有账户都可交易时才可交易
+    async fn get_account(&self) -> Result<AccountInformation> {
+        let accounts =
+            join_all(self.clients.iter().map(|client| {
+                call::<AccountInformation>(client, SpotClientRequest::get_account())
+            }))
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>>>()?;
+
+        let can_trade = accounts.iter().all(|account| account.can_trade);
+        let mut account = accounts[0].clone();
+        account.can_trade = can_trade;
+
+        Ok(account)
+    }
+
+    async fn get_symbol_info(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+    ) -> Result<SymbolInformation> {
+        call(
+            self.primary(),
+            SpotClientRequest::get_symbol_info(base_asset, quote_asset),
+        )
+        .await
+    }
+
+    async fn get_balance(&self, asset: &str) -> Result<Balance> {
+        let balances = join_all(
+            self.clients
+                .iter()
+                .map(|client| call::<Balance>(client, SpotClientRequest::get_balance(asset))),
+        )
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>>>()?;
+
+        let mut free = Decimal::ZERO;
+        let mut locked = Decimal::ZERO;
+
+        for balance in balances {
+            free += balance.free.parse::<Decimal>()?;
+            locked += balance.locked.parse::<Decimal>()?;
+        }
+
+        Ok(Balance::builder()
+            .asset(asset)
+            .free(free.to_string())
+            .locked(locked.to_string())
+            .build())
+    }
+
+    // 按资产合并所有账户的余额
+    async fn get_balances(&self) -> Result<Vec<Balance>> {
+        let balances = join_all(
+            self.clients
+                .iter()
+                .map(|client| call::<Vec<Balance>>(client, SpotClientRequest::GetBalances)),
+        )
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>>>()?;
+
+        let mut totals = BTreeMap::<String, (Decimal, Decimal)>::new();
+
+        for balance in balances.into_iter().flatten() {
+            let total = totals.entry(balance.asset).or_default();
+            total.0 += balance.free.parse::<Decimal>()?;
+            total.1 += balance.locked.parse::<Decimal>()?;
+        }
+
+        Ok(totals
+            .into_iter()
+            .map(|(asset, (free, locked))| {
+                Balance::builder()
+                    .asset(asset)
+                    .free(free.to_string())
+                    .locked(locked.to_string())
+                    .build()
+            })
+            .collect())
+    }
+
+    async fn get_order(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        order_id: &str,
+    ) -> Result<Order> {
+        self.for_each_order(order_id, |order_id| SpotClientRequest::GetOrder {
+            base_asset: base_asset.to_string(),
+            quote_asset: quote_asset.to_string(),
+            order_id,
+        })
+        .await
+    }
+
+    async fn get_open_orders(&self, base_asset: &str, quote_asset: &str) -> Result<Vec<Order>> {
+        self.for_each_account(|| SpotClientRequest::GetOpenOrders {
+            base_asset: base_asset.to_string(),
+            quote_asset: quote_asset.to_string(),
+        })
+        .await
+    }
+
+    async fn cancel_order(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        order_id: &str,
+    ) -> Result<Order> {
+        self.for_each_order(order_id, |order_id| SpotClientRequest::CancelOrder {
+            base_asset: base_asset.to_string(),
+            quote_asset: quote_asset.to_string(),
+            order_id,
+        })
+        .await
+    }
+
+    async fn cancel_all_orders(&self, base_asset: &str, quote_asset: &str) -> Result<Vec<Order>> {
+        self.for_each_account(|| SpotClientRequest::CancelAllOrders {
+            base_asset: base_asset.to_string(),
+            quote_asset: quote_asset.to_string(),
+        })
+        .await
+    }
+
+    async fn market_buy(&self, base_asset: &str, quote_asset: &str, qty: f64) -> Result<Order> {
+        let parts = self.split_qty(base_asset, quote_asset, qty).await?;
+        let orders = self
+            .fan_out(parts, |_, qty| SpotClientRequest::MarketBuy {
+                base_asset: base_asset.to_string(),
+                quote_asset: quote_asset.to_string(),
+                qty,
+            })
+            .await?;
+
+        merge_orders(orders)
+    }
+
+    async fn market_sell(&self, base_asset: &str, quote_asset: &str, qty: f64) -> Result<Order> {
+        let parts = self.split_qty(base_asset, quote_asset, qty).await?;
+        let orders = self
+            .fan_out(parts, |_, qty| SpotClientRequest::MarketSell {
+                base_asset: base_asset.to_string(),
+                quote_asset: quote_asset.to_string(),
+                qty,
+            })
+            .await?;
+
+        merge_orders(orders)
+    }
+
+    async fn market_buy_quote(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        quote_qty: f64,
+    ) -> Result<Order> {
+        let parts = self
+            .split_quote_qty(base_asset, quote_asset, quote_qty)
+            .await?;
+        let orders = self
+            .fan_out(parts, |_, quote_qty| SpotClientRequest::MarketBuyQuote {
+                base_asset: base_asset.to_string(),
+                quote_asset: quote_asset.to_string(),
+                quote_qty,
+            })
+            .await?;
+
+        merge_orders(orders)
+    }
+
+    async fn market_sell_quote(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        quote_qty: f64,
+    ) -> Result<Order> {
+        let parts = self
+            .split_quote_qty(base_asset, quote_asset, quote_qty)
+            .await?;
+        let orders = self
+            .fan_out(parts, |_, quote_qty| SpotClientRequest::MarketSellQuote {
+                base_asset: base_asset.to_string(),
+                quote_asset: quote_asset.to_string(),
+                quote_qty,
+            })
+            .await?;
+
+        merge_orders(orders)
+    }
+
+    async fn limit_buy(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        qty: f64,
+        price: f64,
+    ) -> Result<Order> {
+        let parts = self.split_qty(base_asset, quote_asset, qty).await?;
+        let orders = self
+            .fan_out(parts, |_, qty| SpotClientRequest::LimitBuy {
+                base_asset: base_asset.to_string(),
+                quote_asset: quote_asset.to_string(),
+                qty,
+                price,
+            })
+            .await?;
+
+        merge_orders(orders)
+    }
+
+    async fn limit_sell(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        qty: f64,
+        price: f64,
+    ) -> Result<Order> {
+        let parts = self.split_qty(base_asset, quote_asset, qty).await?;
+        let orders = self
+            .fan_out(parts, |_, qty| SpotClientRequest::LimitSell {
+                base_asset: base_asset.to_string(),
+                quote_asset: quote_asset.to_string(),
+                qty,
+                price,
+            })
+            .await?;
+
+        merge_orders(orders)
+    }
+
+    async fn stop_limit_order(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        side: OrderSide,
+        qty: f64,
+        price: f64,
+        stop_price: f64,
+    ) -> Result<Order> {
+        let parts = self.split_qty(base_asset, quote_asset, qty).await?;
+        let orders = self
+            .fan_out(parts, |_, qty| SpotClientRequest::StopLimitOrder {
+                base_asset: base_asset.to_string(),
+                quote_asset: quote_asset.to_string(),
+                side: side.clone(),
+                qty,
+                price,
+                stop_price,
+            })
+            .await?;
+
+        merge_orders(orders)
+    }
+
+    // 各账户的限价单和止损限价单分别合并，返回合并后的两个订单
+    async fn oco_order(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        side: OrderSide,
+        qty: f64,
+        price: f64,
+        stop_price: f64,
+        stop_limit_price: f64,
+    ) -> Result<Vec<Order>> {
+        let parts = self.split_qty(base_asset, quote_asset, qty).await?;
+        let legs = self
+            .fan_out::<Vec<Order>, _>(parts, |_, qty| SpotClientRequest::OcoOrder {
+                base_asset: base_asset.to_string(),
+                quote_asset: quote_asset.to_string(),
+                side: side.clone(),
+                qty,
+                price,
+                stop_price,
+                stop_limit_price,
+            })
+            .await?;
+
+        let leg = |position: usize| {
+            legs.iter()
+                .filter_map(|(index, orders)| {
+                    orders.get(position).map(|order| (*index, order.clone()))
+                })
+                .collect::<Vec<_>>()
+        };
+
+        [leg(0), leg(1)]
+            .into_iter()
+            .filter(|orders| !orders.is_empty())
+            .map(merge_orders)
+            .collect()
+    }
+
+    async fn get_price(&self, base_asset: &str, quote_asset: &str) -> Result<SymbolPrice> {
+        call(
+            self.primary(),
+            SpotClientRequest::GetPrice {
+                base_asset: base_asset.to_string(),
+                quote_asset: quote_asset.to_string(),
+            },
+        )
+        .await
+    }
+
+    async fn get_margin_account(&self) -> Result<MarginAccount> {
+        anyhow::bail!("MultiSpotClient does not support margin trading")
+    }
+
+    async fn margin_borrow(&self, _asset: &str, _qty: f64) -> Result<MarginTransaction> {
+        anyhow::bail!("MultiSpotClient does not support margin trading")
+    }
+
+    async fn margin_repay(&self, _asset: &str, _qty: f64) -> Result<MarginTransaction> {
+        anyhow::bail!("MultiSpotClient does not support margin trading")
+    }
+
+    async fn margin_buy(&self, _base_asset: &str, _quote_asset: &str, _qty: f64) -> Result<Order> {
+        anyhow::bail!("MultiSpotClient does not support margin trading")
+    }
+
+    async fn margin_sell(&self, _base_asset: &str, _quote_asset: &str, _qty: f64) -> Result<Order> {
+        anyhow::bail!("MultiSpotClient does not support margin trading")
+    }
+
+    // 子订单的客户端订单ID为原ID加账户序号，重试时各账户仍使用相同的ID
+    async fn submit_order(&self, client_order_id: &str, intent: OrderIntent) -> Result<Order> {
+        let parts = self
+            .split_qty(intent.base_asset(), intent.quote_asset(), intent.qty())
+            .await?;
+
+        let orders = self
+            .fan_out(parts, |index, qty| SpotClientRequest::SubmitOrder {
+                client_order_id: sub_client_order_id(client_order_id, index),
+                intent: intent.clone().with_qty(qty),
+            })
+            .await?;
+
+        let mut order = merge_orders(orders)?;
+        order.client_order_id = Some(client_order_id.to_string());
+
+        Ok(order)
+    }
+
+    async fn get_order_by_client_id(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        client_order_id: &str,
+    ) -> Result<Option<Order>> {
+        let futures = self.clients.iter().enumerate().map(|(index, client)| {
+            let req = SpotClientRequest::GetOrderByClientId {
+                base_asset: base_asset.to_string(),
+                quote_asset: quote_asset.to_string(),
+                client_order_id: sub_client_order_id(client_order_id, index),
+            };
+            async move {
+                call::<Option<Order>>(client, req)
+                    .await
+                    .map(|order| order.map(|order| (index, order)))
+            }
+        });
+
+        let orders = join_all(futures)
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+
+        if orders.is_empty() {
+            return Ok(None);
+        }
+
+        let mut order = merge_orders(orders)?;
+        order.client_order_id = Some(client_order_id.to_string());
+
+        Ok(Some(order))
+    }
+}
+
+// 通过 Service 调用子账户，返回装箱的 future，避免与 SpotClientKind 的 future 互相嵌套
+async fn call<T>(client: &SpotClientKind, req: SpotClientRequest) -> Result<T>
+where
+    T: TryFrom<SpotClientResponse, Error = anyhow::Error>,
+{
+    client.clone().call(req).await?.try_into()
+}
+
+fn sub_client_order_id(client_order_id: &str, index: usize) -> String {
+    format!("{}{}", client_order_id, index)
+}
+
+// 解析合并后的订单ID，返回账户序号和子订单ID
+fn parse_order_id(order_id: &str, accounts: usize) -> Result<Vec<(usize, String)>> {
+    order_id
+        .split(',')
+        .map(|part| {
+            let (index, order_id) = part
+                .split_once(':')
+                .ok_or_else(|| anyhow!("Invalid MultiSpotClient order id: {}", order_id))?;
+            let index = index.parse::<usize>()?;
+
+            anyhow::ensure!(index < accounts, "Invalid account index: {}", index);
+
+            Ok((index, order_id.to_string()))
+        })
+        .collect()
+}
+
+// 合并子订单，数量和成交额求和，均价按成交额计算
+fn merge_orders(orders: Vec<(usize, Order)>) -> Result<Order> {
+    let (_, first) = orders
+        .first()
+        .ok_or_else(|| anyhow!("No orders to merge"))?;
+    let mut merged = first.clone();
+
+    let sum = |field: fn(&Order) -> &str| {
+        orders.iter().try_fold(Decimal::ZERO, |acc, (_, order)| {
+            Ok::<_, anyhow::Error>(acc + field(order).parse::<Decimal>()?)
+        })
+    };
+
+    let orig_qty = sum(|order| &order.orig_qty)?;
+    let executed_qty = sum(|order| &order.executed_qty)?;
+    let cumulative_quote_qty = sum(|order| &order.cumulative_quote_qty)?;
+
+    merged.order_id = orders
+        .iter()
+        .map(|(index, order)| format!("{}:{}", index, order.order_id))
+        .collect::<Vec<_>>()
+        .join(",");
+    merged.orig_qty = orig_qty.to_string();
+    merged.executed_qty = executed_qty.to_string();
+    merged.cumulative_quote_qty = cumulative_quote_qty.to_string();
+
+    if executed_qty > Decimal::ZERO {
+        merged.avg_price = (cumulative_quote_qty / executed_qty)
+            .normalize()
+            .to_string();
+    }
+
+    // 子订单状态不一致时，有成交为部分成交，否则为新订单
+    let same_status = orders
+        .iter()
+        .all(|(_, order)| order.order_status == first.order_status);
+
+    if !same_status {
+        merged.order_status = if executed_qty > Decimal::ZERO {
+            OrderStatus::PartiallyFilled
+        } else {
+            OrderStatus::New
+        };
+    }
+
+    merged.time = orders
+        .iter()
+        .map(|(_, order)| order.time)
+        .min()
+        .unwrap_or(first.time);
+    merged.update_time = orders
+        .iter()
+        .map(|(_, order)| order.update_time)
+        .max()
+        .unwrap_or(first.update_time);
+
+    Ok(merged)
+}
+
+fn to_f64(value: Decimal) -> Result<f64> {
+    value
+        .to_f64()
+        .ok_or_else(|| anyhow!("Failed to convert {} to f64", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{client::spot_client::backtest_spot_client::BacktestSpotClient, store::PriceStore};
+    use async_lock::RwLock;
+    use comfy_quant_base::Market;
+    use rust_decimal_macros::dec;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_multi_spot_client() -> Result<()> {
+        let price_store = Arc::new(RwLock::new(PriceStore::new()));
+        price_store.write().await.save_price(
+            &Exchange::Binance,
+            &Market::Spot,
+            &SymbolPrice::builder()
+                .symbol("BTCUSDT".into())
+                .price(dec!(50000))
+                .build(),
+        )?;
+
+        let account = || -> SpotClientKind {
+            BacktestSpotClient::builder()
+                .assets(vec![("USDT".to_string(), 100000.)])
+                .commissions(0.)
+                .price_store(Arc::clone(&price_store))
+                .build()
+                .into()
+        };
+        let (a, b) = (account(), account());
+
+        let client = MultiSpotClient::try_new(vec![(a.clone(), dec!(60)), (b.clone(), dec!(40))])?;
+
+        // 按 60/40 拆分到两个账户
+        let order = client.market_buy("BTC", "USDT", 1.).await?;
+        assert_eq!(order.executed_qty.parse::<Decimal>()?, dec!(1));
+        assert_eq!(order.avg_price.parse::<Decimal>()?, dec!(50000));
+        assert!(matches!(order.order_status, OrderStatus::Filled));
+
+        assert_eq!(
+            a.get_balance("BTC").await?.free.parse::<Decimal>()?,
+            dec!(0.6)
+        );
+        assert_eq!(
+            b.get_balance("BTC").await?.free.parse::<Decimal>()?,
+            dec!(0.4)
+        );
+
+        // 余额为所有账户之和
+        let balance = client.get_balance("BTC").await?;
+        assert_eq!(balance.free.parse::<Decimal>()?, dec!(1));
+
+        // 按合并后的订单ID查询
+        let queried = client.get_order("BTC", "USDT", &order.order_id).await?;
+        assert_eq!(queried.executed_qty.parse::<Decimal>()?, dec!(1));
+
+        assert!(client.get_order("BTC", "USDT", "2:1").await.is_err());
+        assert!(MultiSpotClient::try_new(vec![(a, dec!(0))]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_order_id() -> Result<()> {
+        assert_eq!(
+            parse_order_id("0:12,1:34", 2)?,
+            vec![(0, "12".to_string()), (1, "34".to_string())]
+        );
+        assert!(parse_order_id("12", 2).is_err());
+
+        Ok(())
+    }
+}
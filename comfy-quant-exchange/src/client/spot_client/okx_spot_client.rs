@@ -0,0 +1,473 @@
+use super::base::{
+    AccountInformation, Balance, MarginAccount, MarginTransaction, OkxSpotOrder, Order,
+    OrderIntent, OrderSide, OrderStatus, SymbolInformation, SymbolPrice, UserDataEvent,
+};
+use crate::{
+    client::spot_client_kind::{SpotClientExecutable, SpotclientExecutableExt},
+    exchange::{
+        okx::{OkxClient, OkxOrderRequest},
+        ConnectionOptions,
+    },
+};
+use anyhow::{anyhow, Result};
+use bon::bon;
+use comfy_quant_base::Exchange;
+use tokio::sync::broadcast;
+
+const BATCH_ORDERS_LIMIT: usize = 20; // 批量下单接口每次最多提交的订单数量
+
+#[derive(Debug, Clone)]
+pub struct OkxSpotClient {
+    client: OkxClient,
+}
+
+#[bon]
+impl OkxSpotClient {
+    #[builder(on(String, into))]
+    pub fn new(
+        api_key: Option<String>,
+        secret_key: Option<String>,
+        passphrase: Option<String>,
+        #[builder(default)] simulated: bool,
+        connection: Option<ConnectionOptions>,
+    ) -> Result<Self> {
+        let client = OkxClient::builder()
+            .maybe_api_key(api_key)
+            .maybe_secret_key(secret_key)
+            .maybe_passphrase(passphrase)
+            .simulated(simulated)
+            .maybe_connection(connection)
+            .build()?;
+
+        Ok(OkxSpotClient { client })
+    }
+
+    // 现货使用非保证金模式下单，市价单通过 tgt_ccy 指定数量单位
+    fn order_request(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        side: OrderSide,
+        qty: f64,
+        price: Option<f64>,
+        tgt_ccy: Option<&str>,
+    ) -> OkxOrderRequest {
+        let side = match side {
+            OrderSide::Buy => "buy",
+            OrderSide::Sell => "sell",
+        };
+
+        OkxOrderRequest {
+            inst_id: self.symbol(base_asset, quote_asset).to_string(),
+            td_mode: "cash".to_string(),
+            side: side.to_string(),
+            ord_type: if price.is_some() { "limit" } else { "market" }.to_string(),
+            sz: qty.to_string(),
+            px: price.map(|price| price.to_string()),
+            tgt_ccy: tgt_ccy.map(ToString::to_string),
+            cl_ord_id: None,
+        }
+    }
+
+    // 下单接口只返回订单ID，重新查询订单
+    async fn place_order(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        request: OkxOrderRequest,
+    ) -> Result<Order> {
+        let ack = self.client.place_order(&request).await?;
+        self.get_order(base_asset, quote_asset, &ack.ord_id).await
+    }
+
+    fn unsupported<T>(&self, operation: &str) -> Result<T> {
+        anyhow::bail!("OKX spot client does not support {}", operation)
+    }
+}
+
+impl SpotClientExecutable for OkxSpotClient {
+    fn exchange(&self) -> Exchange {
+        Exchange::Okx
+    }
+
+    // 以API Key标识账户
+    fn account_id(&self) -> String {
+        self.client.api_key().unwrap_or_default().to_string()
+    }
+
+    // 暂未接入 OKX 私有频道推送
+    fn subscribe_user_data(&self) -> Option<broadcast::Receiver<UserDataEvent>> {
+        None
+    }
+
+    async fn get_account(&self) -> Result<AccountInformation> {
+        self.client.get_trade_fee(None).await?.try_into()
+    }
+
+    async fn get_symbol_info(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+    ) -> Result<SymbolInformation> {
+        let symbol = self.symbol(base_asset, quote_asset);
+        self.client
+            .get_instrument(symbol.as_ref())
+            .await?
+            .try_into()
+    }
+
+    // 没有余额的币种不返回明细，视为零余额
+    async fn get_balance(&self, asset: &str) -> Result<Balance> {
+        let asset = asset.to_uppercase();
+        let balance = self
+            .client
+            .get_balance(&asset)
+            .await?
+            .details
+            .into_iter()
+            .find(|detail| detail.ccy == asset)
+            .map(Balance::from)
+            .unwrap_or_else(|| {
+                Balance::builder()
+                    .asset(asset)
+                    .free("0")
+                    .locked("0")
+                    .build()
+            });
+
+        Ok(balance)
+    }
+
+    async fn get_balances(&self) -> Result<Vec<Balance>> {
+        let balances = self
+            .client
+            .get_balances()
+            .await?
+            .details
+            .into_iter()
+            .map(Balance::from)
+            .filter(|balance| !balance.is_empty())
+            .collect();
+
+        Ok(balances)
+    }
+
+    async fn get_order(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        order_id: &str,
+    ) -> Result<Order> {
+        let symbol = self.symbol(base_asset, quote_asset);
+        let order = self.client.get_order(symbol.as_ref(), order_id).await?;
+
+        OkxSpotOrder::builder()
+            .base_asset(base_asset)
+            .quote_asset(quote_asset)
+            .order(order)
+            .build()
+            .try_into()
+    }
+
+    async fn get_open_orders(&self, base_asset: &str, quote_asset: &str) -> Result<Vec<Order>> {
+        let symbol = self.symbol(base_asset, quote_asset);
+
+        self.client
+            .get_open_orders(symbol.as_ref())
+            .await?
+            .into_iter()
+            .map(|order| {
+                OkxSpotOrder::builder()
+                    .base_asset(base_asset)
+                    .quote_asset(quote_asset)
+                    .order(order)
+                    .build()
+                    .try_into()
+            })
+            .collect()
+    }
+
+    async fn cancel_order(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        order_id: &str,
+    ) -> Result<Order> {
+        let symbol = self.symbol(base_asset, quote_asset);
+        self.client.cancel_order(symbol.as_ref(), order_id).await?;
+
+        // 撤单接口只返回订单ID，重新查询撤销后的订单
+        self.get_order(base_asset, quote_asset, order_id).await
+    }
+
+    // OKX 没有撤销交易对全部挂单的接口，逐个撤销
+    async fn cancel_all_orders(&self, base_asset: &str, quote_asset: &str) -> Result<Vec<Order>> {
+        let symbol = self.symbol(base_asset, quote_asset);
+        let open_orders = self.get_open_orders(base_asset, quote_asset).await?;
+
+        for order in &open_orders {
+            self.client
+                .cancel_order(symbol.as_ref(), &order.order_id)
+                .await?;
+        }
+
+        let orders = open_orders
+            .into_iter()
+            .map(|order| Order {
+                order_status: OrderStatus::Canceled,
+                ..order
+            })
+            .collect();
+
+        Ok(orders)
+    }
+
+    async fn market_buy(&self, base_asset: &str, quote_asset: &str, qty: f64) -> Result<Order> {
+        let request = self.order_request(
+            base_asset,
+            quote_asset,
+            OrderSide::Buy,
+            qty,
+            None,
+            Some("base_ccy"),
+        );
+        self.place_order(base_asset, quote_asset, request).await
+    }
+
+    async fn market_sell(&self, base_asset: &str, quote_asset: &str, qty: f64) -> Result<Order> {
+        let request = self.order_request(
+            base_asset,
+            quote_asset,
+            OrderSide::Sell,
+            qty,
+            None,
+            Some("base_ccy"),
+        );
+        self.place_order(base_asset, quote_asset, request).await
+    }
+
+    async fn market_buy_quote(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        quote_qty: f64,
+    ) -> Result<Order> {
+        let request = self.order_request(
+            base_asset,
+            quote_asset,
+            OrderSide::Buy,
+            quote_qty,
+            None,
+            Some("quote_ccy"),
+        );
+        self.place_order(base_asset, quote_asset, request).await
+    }
+
+    async fn market_sell_quote(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        quote_qty: f64,
+    ) -> Result<Order> {
+        let request = self.order_request(
+            base_asset,
+            quote_asset,
+            OrderSide::Sell,
+            quote_qty,
+            None,
+            Some("quote_ccy"),
+        );
+        self.place_order(base_asset, quote_asset, request).await
+    }
+
+    async fn limit_buy(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        qty: f64,
+        price: f64,
+    ) -> Result<Order> {
+        let request = self.order_request(
+            base_asset,
+            quote_asset,
+            OrderSide::Buy,
+            qty,
+            Some(price),
+            None,
+        );
+        self.place_order(base_asset, quote_asset, request).await
+    }
+
+    async fn limit_sell(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        qty: f64,
+        price: f64,
+    ) -> Result<Order> {
+        let request = self.order_request(
+            base_asset,
+            quote_asset,
+            OrderSide::Sell,
+            qty,
+            Some(price),
+            None,
+        );
+        self.place_order(base_asset, quote_asset, request).await
+    }
+
+    // 止损和OCO在 OKX 为策略委托，暂不支持
+    async fn stop_limit_order(
+        &self,
+        _base_asset: &str,
+        _quote_asset: &str,
+        _side: OrderSide,
+        _qty: f64,
+        _price: f64,
+        _stop_price: f64,
+    ) -> Result<Order> {
+        self.unsupported("stop limit order")
+    }
+
+    async fn oco_order(
+        &self,
+        _base_asset: &str,
+        _quote_asset: &str,
+        _side: OrderSide,
+        _qty: f64,
+        _price: f64,
+        _stop_price: f64,
+        _stop_limit_price: f64,
+    ) -> Result<Vec<Order>> {
+        self.unsupported("oco order")
+    }
+
+    async fn get_price(&self, base_asset: &str, quote_asset: &str) -> Result<SymbolPrice> {
+        let symbol = self.symbol(base_asset, quote_asset);
+        self.client.get_ticker(symbol.as_ref()).await?.try_into()
+    }
+
+    async fn get_margin_account(&self) -> Result<MarginAccount> {
+        self.unsupported("margin account")
+    }
+
+    async fn margin_borrow(&self, _asset: &str, _qty: f64) -> Result<MarginTransaction> {
+        self.unsupported("margin borrow")
+    }
+
+    async fn margin_repay(&self, _asset: &str, _qty: f64) -> Result<MarginTransaction> {
+        self.unsupported("margin repay")
+    }
+
+    async fn margin_buy(&self, _base_asset: &str, _quote_asset: &str, _qty: f64) -> Result<Order> {
+        self.unsupported("margin buy")
+    }
+
+    async fn margin_sell(&self, _base_asset: &str, _quote_asset: &str, _qty: f64) -> Result<Order> {
+        self.unsupported("margin sell")
+    }
+
+    // 使用批量下单接口，每批最多20个订单
+    async fn submit_batch(&self, intents: Vec<OrderIntent>) -> Vec<Result<Order>> {
+        let mut results = Vec::with_capacity(intents.len());
+
+        for chunk in intents.chunks(BATCH_ORDERS_LIMIT) {
+            let (assets, requests): (Vec<_>, Vec<_>) = chunk
+                .iter()
+                .map(|intent| {
+                    let (base_asset, quote_asset, side, qty, price) = match intent {
+                        OrderIntent::MarketBuy {
+                            base_asset,
+                            quote_asset,
+                            qty,
+                        } => (base_asset, quote_asset, OrderSide::Buy, *qty, None),
+                        OrderIntent::MarketSell {
+                            base_asset,
+                            quote_asset,
+                            qty,
+                        } => (base_asset, quote_asset, OrderSide::Sell, *qty, None),
+                        OrderIntent::LimitBuy {
+                            base_asset,
+                            quote_asset,
+                            qty,
+                            price,
+                        } => (base_asset, quote_asset, OrderSide::Buy, *qty, Some(*price)),
+                        OrderIntent::LimitSell {
+                            base_asset,
+                            quote_asset,
+                            qty,
+                            price,
+                        } => (base_asset, quote_asset, OrderSide::Sell, *qty, Some(*price)),
+                    };
+
+                    let tgt_ccy = price.is_none().then_some("base_ccy");
+                    let request =
+                        self.order_request(base_asset, quote_asset, side, qty, price, tgt_ccy);
+
+                    ((base_asset, quote_asset), request)
+                })
+                .unzip();
+
+            let acks = match self.client.place_batch_orders(&requests).await {
+                Ok(acks) if acks.len() == requests.len() => acks,
+                Ok(acks) => {
+                    let e = anyhow!(
+                        "OKX batch orders returned {} results for {} orders",
+                        acks.len(),
+                        requests.len()
+                    );
+                    results.extend(requests.iter().map(|_| Err(anyhow!("{}", e))));
+                    continue;
+                }
+                Err(e) => {
+                    results.extend(requests.iter().map(|_| Err(anyhow!("{}", e))));
+                    continue;
+                }
+            };
+
+            for ((base_asset, quote_asset), ack) in assets.into_iter().zip(acks) {
+                let result = match ack.check() {
+                    Ok(ack) => self.get_order(base_asset, quote_asset, &ack.ord_id).await,
+                    Err(e) => Err(e),
+                };
+
+                results.push(result);
+            }
+        }
+
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_okx_order_request() -> Result<()> {
+        let client = OkxSpotClient::builder().build()?;
+
+        let request =
+            client.order_request("btc", "usdt", OrderSide::Buy, 100., None, Some("quote_ccy"));
+        let value = serde_json::to_value(&request)?;
+
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "instId": "BTC-USDT",
+                "tdMode": "cash",
+                "side": "buy",
+                "ordType": "market",
+                "sz": "100",
+                "tgtCcy": "quote_ccy",
+            })
+        );
+
+        let request = client.order_request("btc", "usdt", OrderSide::Sell, 0.5, Some(60000.), None);
+        assert_eq!(request.ord_type, "limit");
+        assert_eq!(request.px.as_deref(), Some("60000"));
+        assert_eq!(client.exchange(), Exchange::Okx);
+
+        Ok(())
+    }
+}
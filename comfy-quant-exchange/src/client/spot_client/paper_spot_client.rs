@@ -0,0 +1,289 @@
+use super::{
+    backtest_spot_client::BacktestSpotClient,
+    base::{
+        AccountInformation, Balance, MarginAccount, MarginTransaction, Order, OrderIntent,
+        OrderSide, SymbolInformation, SymbolPrice, UserDataEvent,
+    },
+    binance_spot_client::BinanceSpotClient,
+    fee_schedule::FeeSchedule,
+};
+use crate::{
+    client::spot_client_kind::SpotClientExecutable, exchange::ConnectionOptions, store::PriceStore,
+};
+use anyhow::Result;
+use async_lock::RwLock;
+use bon::bon;
+use comfy_quant_base::Exchange;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+
+// 模拟盘账户：按币安实时行情撮合，资金和订单都是模拟的。
+// 撮合、余额和订单复用回测客户端，价格来自工作流中币安行情节点写入的价格存储，
+// 交易对的过滤器和精度从币安查询，手续费按币安现货费率表和VIP等级计算
+#[derive(Debug, Clone)]
+pub struct PaperSpotClient {
+    inner: BacktestSpotClient, // 模拟撮合和账户
+    market: BinanceSpotClient, // 查询真实的交易对信息，不需要API Key
+}
+
+#[bon]
+impl PaperSpotClient {
+    #[builder]
+    pub fn new(
+        #[builder(into)] assets: Vec<(String, f64)>,
+        price_store: Arc<RwLock<PriceStore>>,
+        #[builder(default)] vip_level: u8,
+        connection: Option<ConnectionOptions>, // 查询交易对信息的代理和接口地址
+    ) -> Result<Self> {
+        let inner = BacktestSpotClient::builder()
+            .assets(assets)
+            .price_store(price_store)
+            .fee_schedule(FeeSchedule::binance_spot())
+            .vip_level(vip_level)
+            .build();
+
+        let market = BinanceSpotClient::builder()
+            .maybe_connection(connection)
+            .build()?;
+
+        Ok(PaperSpotClient { inner, market })
+    }
+
+    // 模拟撮合的回测客户端，行情节点每个tick调用其 on_tick 撮合挂单
+    pub fn simulator(&self) -> &BacktestSpotClient {
+        &self.inner
+    }
+}
+
+impl SpotClientExecutable for PaperSpotClient {
+    fn exchange(&self) -> Exchange {
+        Exchange::Binance
+    }
+
+    fn account_id(&self) -> String {
+        "paper".to_string()
+    }
+
+    fn subscribe_user_data(&self) -> Option<broadcast::Receiver<UserDataEvent>> {
+        self.inner.subscribe_user_data()
+    }
+
+    async fn get_account(&self) -> Result<AccountInformation> {
+        self.inner.get_account().await
+    }
+
+    // 过滤器和精度使用币安的真实数据，与实盘下单规则一致
+    async fn get_symbol_info(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+    ) -> Result<SymbolInformation> {
+        self.market.get_symbol_info(base_asset, quote_asset).await
+    }
+
+    async fn get_balance(&self, asset: &str) -> Result<Balance> {
+        self.inner.get_balance(asset).await
+    }
+
+    async fn get_balances(&self) -> Result<Vec<Balance>> {
+        self.inner.get_balances().await
+    }
+
+    async fn get_order(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        order_id: &str,
+    ) -> Result<Order> {
+        self.inner
+            .get_order(base_asset, quote_asset, order_id)
+            .await
+    }
+
+    async fn get_open_orders(&self, base_asset: &str, quote_asset: &str) -> Result<Vec<Order>> {
+        self.inner.get_open_orders(base_asset, quote_asset).await
+    }
+
+    async fn cancel_order(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        order_id: &str,
+    ) -> Result<Order> {
+        self.inner
+            .cancel_order(base_asset, quote_asset, order_id)
+            .await
+    }
+
+    async fn cancel_all_orders(&self, base_asset: &str, quote_asset: &str) -> Result<Vec<Order>> {
+        self.inner.cancel_all_orders(base_asset, quote_asset).await
+    }
+
+    async fn market_buy(&self, base_asset: &str, quote_asset: &str, qty: f64) -> Result<Order> {
+        self.inner.market_buy(base_asset, quote_asset, qty).await
+    }
+
+    async fn market_sell(&self, base_asset: &str, quote_asset: &str, qty: f64) -> Result<Order> {
+        self.inner.market_sell(base_asset, quote_asset, qty).await
+    }
+
+    async fn market_buy_quote(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        quote_qty: f64,
+    ) -> Result<Order> {
+        self.inner
+            .market_buy_quote(base_asset, quote_asset, quote_qty)
+            .await
+    }
+
+    async fn market_sell_quote(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        quote_qty: f64,
+    ) -> Result<Order> {
+        self.inner
+            .market_sell_quote(base_asset, quote_asset, quote_qty)
+            .await
+    }
+
+    async fn limit_buy(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        qty: f64,
+        price: f64,
+    ) -> Result<Order> {
+        self.inner
+            .limit_buy(base_asset, quote_asset, qty, price)
+            .await
+    }
+
+    async fn limit_sell(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        qty: f64,
+        price: f64,
+    ) -> Result<Order> {
+        self.inner
+            .limit_sell(base_asset, quote_asset, qty, price)
+            .await
+    }
+
+    async fn stop_limit_order(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        side: OrderSide,
+        qty: f64,
+        price: f64,
+        stop_price: f64,
+    ) -> Result<Order> {
+        self.inner
+            .stop_limit_order(base_asset, quote_asset, side, qty, price, stop_price)
+            .await
+    }
+
+    async fn oco_order(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        side: OrderSide,
+        qty: f64,
+        price: f64,
+        stop_price: f64,
+        stop_limit_price: f64,
+    ) -> Result<Vec<Order>> {
+        self.inner
+            .oco_order(
+                base_asset,
+                quote_asset,
+                side,
+                qty,
+                price,
+                stop_price,
+                stop_limit_price,
+            )
+            .await
+    }
+
+    async fn get_price(&self, base_asset: &str, quote_asset: &str) -> Result<SymbolPrice> {
+        self.inner.get_price(base_asset, quote_asset).await
+    }
+
+    async fn get_margin_account(&self) -> Result<MarginAccount> {
+        self.inner.get_margin_account().await
+    }
+
+    async fn margin_borrow(&self, asset: &str, qty: f64) -> Result<MarginTransaction> {
+        self.inner.margin_borrow(asset, qty).await
+    }
+
+    async fn margin_repay(&self, asset: &str, qty: f64) -> Result<MarginTransaction> {
+        self.inner.margin_repay(asset, qty).await
+    }
+
+    async fn margin_buy(&self, base_asset: &str, quote_asset: &str, qty: f64) -> Result<Order> {
+        self.inner.margin_buy(base_asset, quote_asset, qty).await
+    }
+
+    async fn margin_sell(&self, base_asset: &str, quote_asset: &str, qty: f64) -> Result<Order> {
+        self.inner.margin_sell(base_asset, quote_asset, qty).await
+    }
+
+    async fn submit_order(&self, client_order_id: &str, intent: OrderIntent) -> Result<Order> {
+        self.inner.submit_order(client_order_id, intent).await
+    }
+
+    async fn get_order_by_client_id(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        client_order_id: &str,
+    ) -> Result<Option<Order>> {
+        self.inner
+            .get_order_by_client_id(base_asset, quote_asset, client_order_id)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use comfy_quant_base::Market;
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+
+    #[tokio::test]
+    async fn test_paper_spot_client() -> Result<()> {
+        let price_store = Arc::new(RwLock::new(PriceStore::new()));
+        price_store.write().await.save_price(
+            &Exchange::Binance,
+            &Market::Spot,
+            &SymbolPrice::builder()
+                .symbol("BTCUSDT".into())
+                .price(dec!(50000))
+                .build(),
+        )?;
+
+        let client = PaperSpotClient::builder()
+            .assets(vec![("USDT".to_string(), 10000.)])
+            .price_store(price_store)
+            .build()?;
+
+        assert_eq!(client.account_id(), "paper");
+
+        // VIP0 手续费率
+        let account = client.get_account().await?;
+        assert_eq!(account.taker_commission_rate, dec!(0.001));
+
+        // 按价格存储中的实时价格撮合
+        let order = client.market_buy("BTC", "USDT", 0.1).await?;
+        assert_eq!(order.avg_price.parse::<Decimal>()?, dec!(50000));
+
+        Ok(())
+    }
+}
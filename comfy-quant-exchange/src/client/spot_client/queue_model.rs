@@ -0,0 +1,92 @@
+use anyhow::anyhow;
+use rust_decimal::Decimal;
+use std::str::FromStr;
+
+// 挂单排队模型，价格处于或穿过挂单价位后，按累计成交量估算挂单何时轮到成交
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueueModel {
+    Optimistic,  // 乐观: 排在队首，累计成交量达到挂单数量即成交
+    Pessimistic, // 悲观: 排在队尾，需先消耗挂单时估计的排队量
+}
+
+impl QueueModel {
+    // 挂单前方的估计排队量，悲观模式取挂单时最近周期的成交量
+    pub fn queue_ahead(&self, volume: Decimal) -> Decimal {
+        match self {
+            QueueModel::Optimistic => Decimal::ZERO,
+            QueueModel::Pessimistic => volume,
+        }
+    }
+}
+
+impl FromStr for QueueModel {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "optimistic" => Ok(QueueModel::Optimistic),
+            "pessimistic" => Ok(QueueModel::Pessimistic),
+            _ => Err(anyhow!("Invalid queue model: {}", s)),
+        }
+    }
+}
+
+// 挂单在价位上的排队位置
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueuePosition {
+    ahead: Decimal,  // 前方排队量
+    qty: Decimal,    // 挂单数量
+    traded: Decimal, // 挂单后价位上的累计成交量
+    volume_seq: u64, // 最后一次累计的成交量序号，每个周期的成交量只累计一次
+}
+
+impl QueuePosition {
+    pub fn new(model: QueueModel, volume: Decimal, qty: Decimal, volume_seq: u64) -> Self {
+        QueuePosition {
+            ahead: model.queue_ahead(volume),
+            qty,
+            traded: Decimal::ZERO,
+            volume_seq,
+        }
+    }
+
+    // 价格处于或穿过挂单价位时累计周期成交量，返回是否已轮到成交。
+    // volume_seq 为成交量的序号，同一周期的成交量重复撮合时不再累计
+    pub fn trade(&mut self, volume: Decimal, volume_seq: u64) -> bool {
+        if volume_seq > self.volume_seq {
+            self.traded += volume;
+            self.volume_seq = volume_seq;
+        }
+
+        self.is_filled()
+    }
+
+    pub fn is_filled(&self) -> bool {
+        self.traded >= self.ahead + self.qty
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_queue_position() -> anyhow::Result<()> {
+        assert_eq!("optimistic".parse::<QueueModel>()?, QueueModel::Optimistic);
+        assert!("fifo".parse::<QueueModel>().is_err());
+
+        let mut position = QueuePosition::new(QueueModel::Optimistic, dec!(5), dec!(1), 0);
+        assert!(!position.trade(dec!(0.6), 1));
+        // 同一周期只累计一次
+        assert!(!position.trade(dec!(0.6), 1));
+        assert!(position.trade(dec!(0.4), 2));
+
+        let mut position = QueuePosition::new(QueueModel::Pessimistic, dec!(5), dec!(1), 0);
+        assert!(!position.trade(dec!(3), 1));
+        assert!(!position.trade(dec!(2.5), 2));
+        assert!(position.trade(dec!(0.5), 3));
+
+        Ok(())
+    }
+}
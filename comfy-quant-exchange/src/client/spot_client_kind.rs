@@ -1,16 +1,22 @@
 use super::spot_client::{
     backtest_spot_client::BacktestSpotClient,
     base::{
-        AccountInformation, Balance, Order, SpotClientRequest, SpotClientResponse,
-        SymbolInformation, SymbolPrice,
+        AccountInformation, Balance, MarginAccount, MarginTransaction, Order, OrderIntent,
+        OrderSide, SpotClientRequest, SpotClientResponse, SymbolInformation, SymbolPrice,
+        UserDataEvent,
     },
     binance_spot_client::BinanceSpotClient,
+    guarded_spot_client::GuardedSpotClient,
+    multi_spot_client::MultiSpotClient,
+    okx_spot_client::OkxSpotClient,
+    paper_spot_client::PaperSpotClient,
 };
 use anyhow::Result;
 use comfy_quant_base::{Exchange, Symbol};
 use enum_dispatch::enum_dispatch;
 use futures::future::BoxFuture;
 use std::task::{Context, Poll};
+use tokio::sync::broadcast;
 use tower::Service;
 
 #[enum_dispatch]
@@ -18,6 +24,12 @@ use tower::Service;
 pub trait SpotClientExecutable {
     fn exchange(&self) -> Exchange;
 
+    // 账户标识，用于账户级的交易对黑白名单
+    fn account_id(&self) -> String;
+
+    // 订阅交易所推送的订单更新和余额变化，不支持推送时返回None
+    fn subscribe_user_data(&self) -> Option<broadcast::Receiver<UserDataEvent>>;
+
     // 获取账户信息，手续费
     async fn get_account(&self) -> Result<AccountInformation>;
 
@@ -30,16 +42,49 @@ pub trait SpotClientExecutable {
     // 获取账户余额
     async fn get_balance(&self, asset: &str) -> Result<Balance>;
 
+    // 获取账户中所有有余额的资产
+    async fn get_balances(&self) -> Result<Vec<Balance>>;
+
     // 获取订单信息
     async fn get_order(&self, base_asset: &str, quote_asset: &str, order_id: &str)
         -> Result<Order>;
 
+    // 获取交易对的未成交订单
+    async fn get_open_orders(&self, base_asset: &str, quote_asset: &str) -> Result<Vec<Order>>;
+
+    // 撤销订单，返回撤销后的订单
+    async fn cancel_order(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        order_id: &str,
+    ) -> Result<Order>;
+
+    // 撤销交易对的所有挂单，返回被撤销的订单
+    async fn cancel_all_orders(&self, base_asset: &str, quote_asset: &str) -> Result<Vec<Order>>;
+
     // 市价买单
     async fn market_buy(&self, base_asset: &str, quote_asset: &str, qty: f64) -> Result<Order>;
 
     // 市价卖单
     async fn market_sell(&self, base_asset: &str, quote_asset: &str, qty: f64) -> Result<Order>;
 
+    // 按计价货币金额市价买单
+    async fn market_buy_quote(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        quote_qty: f64,
+    ) -> Result<Order>;
+
+    // 按计价货币金额市价卖单
+    async fn market_sell_quote(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        quote_qty: f64,
+    ) -> Result<Order>;
+
     // 限价买单
     async fn limit_buy(
         &self,
@@ -58,16 +103,113 @@ pub trait SpotClientExecutable {
         price: f64,
     ) -> Result<Order>;
 
+    // 止损限价单，最新价格触及止损价后按限价挂单
+    async fn stop_limit_order(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        side: OrderSide,
+        qty: f64,
+        price: f64,
+        stop_price: f64,
+    ) -> Result<Order>;
+
+    // OCO订单，同时挂出限价单和止损限价单，一个成交或触发后另一个自动撤销。
+    // 返回两个订单，限价单在前
+    #[allow(clippy::too_many_arguments)]
+    async fn oco_order(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+        side: OrderSide,
+        qty: f64,
+        price: f64,
+        stop_price: f64,
+        stop_limit_price: f64,
+    ) -> Result<Vec<Order>>;
+
     // 获取价格
     async fn get_price(&self, base_asset: &str, quote_asset: &str) -> Result<SymbolPrice>;
+
+    // 获取杠杆账户信息
+    async fn get_margin_account(&self) -> Result<MarginAccount>;
+
+    // 杠杆借款
+    async fn margin_borrow(&self, asset: &str, qty: f64) -> Result<MarginTransaction>;
+
+    // 杠杆还款，优先偿还利息
+    async fn margin_repay(&self, asset: &str, qty: f64) -> Result<MarginTransaction>;
+
+    // 杠杆市价买单
+    async fn margin_buy(&self, base_asset: &str, quote_asset: &str, qty: f64) -> Result<Order>;
+
+    // 杠杆市价卖单
+    async fn margin_sell(&self, base_asset: &str, quote_asset: &str, qty: f64) -> Result<Order>;
+
+    // 批量下单，按顺序返回每个订单的结果，单个订单失败不影响其他订单
+    // 交易所支持批量接口时可覆盖该实现，默认逐个提交(币安现货没有批量下单接口)
+    async fn submit_batch(&self, intents: Vec<OrderIntent>) -> Vec<Result<Order>> {
+        let mut results = Vec::with_capacity(intents.len());
+
+        for intent in intents {
+            results.push(self.place_intent(intent).await);
+        }
+
+        results
+    }
+
+    // 带客户端订单ID下单，同一ID的订单交易所只接受一次。
+    // 默认忽略该ID直接下单，此时无法按ID查询，失败后不能安全重试
+    async fn submit_order(&self, _client_order_id: &str, intent: OrderIntent) -> Result<Order> {
+        self.place_intent(intent).await
+    }
+
+    // 按客户端订单ID查询订单，订单不存在时返回None
+    async fn get_order_by_client_id(
+        &self,
+        _base_asset: &str,
+        _quote_asset: &str,
+        _client_order_id: &str,
+    ) -> Result<Option<Order>> {
+        anyhow::bail!("{} does not support client order id", self.exchange())
+    }
 }
 
 impl<T: ?Sized> SpotclientExecutableExt for T where T: SpotClientExecutable {}
 
+#[allow(async_fn_in_trait)]
 pub trait SpotclientExecutableExt: SpotClientExecutable {
     fn symbol(&self, base_asset: &str, quote_asset: &str) -> Symbol {
         self.exchange().symbol(base_asset, quote_asset)
     }
+
+    // 按订单意图下单
+    async fn place_intent(&self, intent: OrderIntent) -> Result<Order> {
+        match intent {
+            OrderIntent::MarketBuy {
+                base_asset,
+                quote_asset,
+                qty,
+            } => self.market_buy(&base_asset, &quote_asset, qty).await,
+            OrderIntent::MarketSell {
+                base_asset,
+                quote_asset,
+                qty,
+            } => self.market_sell(&base_asset, &quote_asset, qty).await,
+            OrderIntent::LimitBuy {
+                base_asset,
+                quote_asset,
+                qty,
+                price,
+            } => self.limit_buy(&base_asset, &quote_asset, qty, price).await,
+            OrderIntent::LimitSell {
+                base_asset,
+                quote_asset,
+                qty,
+                price,
+            } => self.limit_sell(&base_asset, &quote_asset, qty, price).await,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -75,6 +217,10 @@ pub trait SpotclientExecutableExt: SpotClientExecutable {
 pub enum SpotClientKind {
     BacktestSpotClient(BacktestSpotClient),
     BinanceSpotClient(BinanceSpotClient),
+    OkxSpotClient(OkxSpotClient),
+    GuardedSpotClient(GuardedSpotClient),
+    PaperSpotClient(PaperSpotClient),
+    MultiSpotClient(MultiSpotClient),
 }
 
 impl Service<SpotClientRequest> for SpotClientKind {
@@ -111,6 +257,7 @@ impl Service<SpotClientRequest> for SpotClientKind {
                     .await?
                     .into(),
                 SpotClientRequest::GetBalance { asset } => client.get_balance(&asset).await?.into(),
+                SpotClientRequest::GetBalances => client.get_balances().await?.into(),
                 SpotClientRequest::GetOrder {
                     base_asset,
                     quote_asset,
@@ -119,6 +266,28 @@ impl Service<SpotClientRequest> for SpotClientKind {
                     .get_order(&base_asset, &quote_asset, &order_id)
                     .await?
                     .into(),
+                SpotClientRequest::GetOpenOrders {
+                    base_asset,
+                    quote_asset,
+                } => client
+                    .get_open_orders(&base_asset, &quote_asset)
+                    .await?
+                    .into(),
+                SpotClientRequest::CancelOrder {
+                    base_asset,
+                    quote_asset,
+                    order_id,
+                } => client
+                    .cancel_order(&base_asset, &quote_asset, &order_id)
+                    .await?
+                    .into(),
+                SpotClientRequest::CancelAllOrders {
+                    base_asset,
+                    quote_asset,
+                } => client
+                    .cancel_all_orders(&base_asset, &quote_asset)
+                    .await?
+                    .into(),
                 SpotClientRequest::MarketBuy {
                     base_asset,
                     quote_asset,
@@ -135,6 +304,22 @@ impl Service<SpotClientRequest> for SpotClientKind {
                     .market_sell(&base_asset, &quote_asset, qty)
                     .await?
                     .into(),
+                SpotClientRequest::MarketBuyQuote {
+                    base_asset,
+                    quote_asset,
+                    quote_qty,
+                } => client
+                    .market_buy_quote(&base_asset, &quote_asset, quote_qty)
+                    .await?
+                    .into(),
+                SpotClientRequest::MarketSellQuote {
+                    base_asset,
+                    quote_asset,
+                    quote_qty,
+                } => client
+                    .market_sell_quote(&base_asset, &quote_asset, quote_qty)
+                    .await?
+                    .into(),
                 SpotClientRequest::LimitBuy {
                     base_asset,
                     quote_asset,
@@ -153,10 +338,76 @@ impl Service<SpotClientRequest> for SpotClientKind {
                     .limit_sell(&base_asset, &quote_asset, qty, price)
                     .await?
                     .into(),
+                SpotClientRequest::StopLimitOrder {
+                    base_asset,
+                    quote_asset,
+                    side,
+                    qty,
+                    price,
+                    stop_price,
+                } => client
+                    .stop_limit_order(&base_asset, &quote_asset, side, qty, price, stop_price)
+                    .await?
+                    .into(),
+                SpotClientRequest::OcoOrder {
+                    base_asset,
+                    quote_asset,
+                    side,
+                    qty,
+                    price,
+                    stop_price,
+                    stop_limit_price,
+                } => client
+                    .oco_order(
+                        &base_asset,
+                        &quote_asset,
+                        side,
+                        qty,
+                        price,
+                        stop_price,
+                        stop_limit_price,
+                    )
+                    .await?
+                    .into(),
                 SpotClientRequest::GetPrice {
                     base_asset,
                     quote_asset,
                 } => client.get_price(&base_asset, &quote_asset).await?.into(),
+                SpotClientRequest::GetMarginAccount => client.get_margin_account().await?.into(),
+                SpotClientRequest::MarginBorrow { asset, qty } => {
+                    client.margin_borrow(&asset, qty).await?.into()
+                }
+                SpotClientRequest::MarginRepay { asset, qty } => {
+                    client.margin_repay(&asset, qty).await?.into()
+                }
+                SpotClientRequest::MarginBuy {
+                    base_asset,
+                    quote_asset,
+                    qty,
+                } => client
+                    .margin_buy(&base_asset, &quote_asset, qty)
+                    .await?
+                    .into(),
+                SpotClientRequest::MarginSell {
+                    base_asset,
+                    quote_asset,
+                    qty,
+                } => client
+                    .margin_sell(&base_asset, &quote_asset, qty)
+                    .await?
+                    .into(),
+                SpotClientRequest::SubmitOrder {
+                    client_order_id,
+                    intent,
+                } => client.submit_order(&client_order_id, intent).await?.into(),
+                SpotClientRequest::GetOrderByClientId {
+                    base_asset,
+                    quote_asset,
+                    client_order_id,
+                } => client
+                    .get_order_by_client_id(&base_asset, &quote_asset, &client_order_id)
+                    .await?
+                    .into(),
             };
 
             Ok(res)
@@ -169,8 +420,18 @@ impl Service<SpotClientRequest> for SpotClientKind {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::store::PriceStore;
+    use crate::{
+        client::spot_client::{
+            base::{OrderStatus, OrderType},
+            execution_model::ExecutionModel,
+            fee_schedule::FeeSchedule,
+            queue_model::QueueModel,
+        },
+        store::PriceStore,
+    };
     use async_lock::RwLock;
+    use comfy_quant_base::Market;
+    use rust_decimal::Decimal;
     use rust_decimal_macros::dec;
     use std::sync::Arc;
 
@@ -184,6 +445,771 @@ mod tests {
         let account = client.get_account().await?;
         assert_eq!(account.maker_commission_rate, dec!(0.001));
         assert_eq!(account.taker_commission_rate, dec!(0.001));
+
+        let mut assets = client
+            .get_balances()
+            .await?
+            .into_iter()
+            .map(|balance| balance.asset)
+            .collect::<Vec<_>>();
+        assets.sort();
+        assert_eq!(assets, vec!["BTC", "USDT"]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_spot_client_fee_schedule() -> Result<()> {
+        let price_store = Arc::new(RwLock::new(PriceStore::new()));
+        price_store.write().await.save_price(
+            &Exchange::Binance,
+            &Market::Spot,
+            &SymbolPrice::builder()
+                .symbol("BTCUSDT".into())
+                .price(dec!(100000))
+                .build(),
+        )?;
+
+        let client: SpotClientKind = BacktestSpotClient::builder()
+            .assets(vec![("USDT".to_string(), 100000000.)])
+            .price_store(price_store)
+            .fee_schedule(FeeSchedule::binance_spot())
+            .vip_level(2)
+            .tier_progression(true)
+            .build()
+            .into();
+
+        let account = client.get_account().await?;
+        assert_eq!(account.maker_commission_rate, dec!(0.0008));
+        assert_eq!(account.taker_commission_rate, dec!(0.001));
+
+        // 成交额达到VIP3
+        client.market_buy("BTC", "USDT", 200.).await?;
+
+        let account = client.get_account().await?;
+        assert_eq!(account.maker_commission_rate, dec!(0.00042));
+        assert_eq!(account.taker_commission_rate, dec!(0.0006));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_spot_client_market_order_quote() -> Result<()> {
+        let price_store = Arc::new(RwLock::new(PriceStore::new()));
+        price_store.write().await.save_price(
+            &Exchange::Binance,
+            &Market::Spot,
+            &SymbolPrice::builder()
+                .symbol("BTCUSDT".into())
+                .price(dec!(30000))
+                .build(),
+        )?;
+
+        let client: SpotClientKind = BacktestSpotClient::builder()
+            .assets(vec![("USDT".to_string(), 1000.)])
+            .price_store(price_store)
+            .build()
+            .into();
+
+        let order = client.market_buy_quote("BTC", "USDT", 100.).await?;
+        assert_eq!(order.base_asset_amount()?, dec!(0.00333333));
+        assert_eq!(order.quote_asset_amount()?, dec!(99.9999));
+
+        let order = client.market_sell_quote("BTC", "USDT", 30.).await?;
+        assert_eq!(order.base_asset_amount()?, dec!(0.001));
+        assert_eq!(order.quote_asset_amount()?, dec!(30));
+
+        // 没有价格时无法换算数量
+        assert!(client.market_buy_quote("ETH", "USDT", 100.).await.is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_spot_client_submit_batch() -> Result<()> {
+        let price_store = Arc::new(RwLock::new(PriceStore::new()));
+        price_store.write().await.save_price(
+            &Exchange::Binance,
+            &Market::Spot,
+            &SymbolPrice::builder()
+                .symbol("BTCUSDT".into())
+                .price(dec!(30000))
+                .build(),
+        )?;
+
+        let client: SpotClientKind = BacktestSpotClient::builder()
+            .assets(vec![("USDT".to_string(), 1000.)])
+            .price_store(price_store)
+            .build()
+            .into();
+
+        let results = client
+            .submit_batch(vec![
+                OrderIntent::market_buy("BTC", "USDT", 0.01),
+                OrderIntent::market_buy("BTC", "USDT", 1.),
+                OrderIntent::market_sell("BTC", "USDT", 0.005),
+            ])
+            .await;
+
+        // 余额不足的订单失败，不影响后续订单
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].as_ref().unwrap().executed_qty, "0.01");
+        assert!(results[1].is_err());
+        assert_eq!(results[2].as_ref().unwrap().executed_qty, "0.005");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_spot_client_limit_order() -> Result<()> {
+        let price_store = Arc::new(RwLock::new(PriceStore::new()));
+        let save_price = |price: Decimal| {
+            let price_store = Arc::clone(&price_store);
+            async move {
+                price_store.write().await.save_price(
+                    &Exchange::Binance,
+                    &Market::Spot,
+                    &SymbolPrice::builder()
+                        .symbol("BTCUSDT".into())
+                        .price(price)
+                        .build(),
+                )
+            }
+        };
+        save_price(dec!(30000)).await?;
+
+        let client: SpotClientKind = BacktestSpotClient::builder()
+            .assets(vec![("USDT".to_string(), 1000.), ("BTC".to_string(), 0.1)])
+            .commissions(0.001)
+            .price_store(Arc::clone(&price_store))
+            .build()
+            .into();
+
+        // 挂单冻结资产
+        let buy = client.limit_buy("BTC", "USDT", 0.01, 29000.).await?;
+        let sell = client.limit_sell("BTC", "USDT", 0.01, 31000.).await?;
+        assert!(matches!(buy.order_status, OrderStatus::New));
+        assert_eq!(buy.executed_qty, "0");
+
+        let usdt = client.get_balance("USDT").await?;
+        assert_eq!(usdt.free.parse::<Decimal>()?, dec!(710));
+        assert_eq!(usdt.locked.parse::<Decimal>()?, dec!(290));
+
+        // 余额不足
+        assert!(client.limit_buy("BTC", "USDT", 1., 29000.).await.is_err());
+
+        // 价格穿过买单限价，按限价成交
+        save_price(dec!(28900)).await?;
+        let buy = client.get_order("BTC", "USDT", &buy.order_id).await?;
+        assert!(matches!(buy.order_status, OrderStatus::Filled));
+        assert_eq!(buy.cumulative_quote_qty.parse::<Decimal>()?, dec!(290));
+
+        let usdt = client.get_balance("USDT").await?;
+        assert_eq!(usdt.free.parse::<Decimal>()?, dec!(710));
+        assert_eq!(usdt.locked.parse::<Decimal>()?, dec!(0));
+
+        let btc = client.get_balance("BTC").await?;
+        assert_eq!(btc.free.parse::<Decimal>()?, dec!(0.09999));
+        assert_eq!(btc.locked.parse::<Decimal>()?, dec!(0.01));
+
+        let sell = client.get_order("BTC", "USDT", &sell.order_id).await?;
+        assert!(matches!(sell.order_status, OrderStatus::New));
+
+        // 限价低于最新价格的卖单立即成交
+        let order = client.limit_sell("BTC", "USDT", 0.01, 28000.).await?;
+        assert!(matches!(order.order_status, OrderStatus::Filled));
+        assert_eq!(order.avg_price.parse::<Decimal>()?, dec!(28900));
+
+        save_price(dec!(31000)).await?;
+        let sell = client.get_order("BTC", "USDT", &sell.order_id).await?;
+        assert!(matches!(sell.order_status, OrderStatus::Filled));
+
+        let usdt = client.get_balance("USDT").await?;
+        assert_eq!(
+            usdt.free.parse::<Decimal>()?,
+            dec!(710) + dec!(289) * dec!(0.999) + dec!(310) * dec!(0.999)
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_spot_client_queue_model() -> Result<()> {
+        let price_store = Arc::new(RwLock::new(PriceStore::new()));
+        let save_tick = |price: Decimal, volume: Decimal, timestamp: i64| {
+            let price_store = Arc::clone(&price_store);
+            async move {
+                let mut price_store = price_store.write().await;
+                let symbol_price = SymbolPrice::builder()
+                    .symbol("BTCUSDT".into())
+                    .price(price)
+                    .build();
+                price_store.save_price(&Exchange::Binance, &Market::Spot, &symbol_price)?;
+                price_store.save_volume(
+                    &Exchange::Binance,
+                    &Market::Spot,
+                    &symbol_price.symbol,
+                    volume,
+                )?;
+                price_store.save_timestamp(timestamp);
+                anyhow::Ok(())
+            }
+        };
+        save_tick(dec!(30000), dec!(2), 0).await?;
+
+        let client = |queue_model| -> SpotClientKind {
+            BacktestSpotClient::builder()
+                .assets(vec![("USDT".to_string(), 100000.)])
+                .commissions(0.)
+                .price_store(Arc::clone(&price_store))
+                .queue_model(queue_model)
+                .build()
+                .into()
+        };
+        let optimistic = client(QueueModel::Optimistic);
+        let pessimistic = client(QueueModel::Pessimistic);
+
+        let optimistic_buy = optimistic.limit_buy("BTC", "USDT", 0.5, 29900.).await?;
+        let pessimistic_buy = pessimistic.limit_buy("BTC", "USDT", 0.5, 29900.).await?;
+
+        // 价格触及限价，成交量不足挂单数量
+        save_tick(dec!(29900), dec!(0.3), 1).await?;
+        let order = optimistic
+            .get_order("BTC", "USDT", &optimistic_buy.order_id)
+            .await?;
+        assert!(matches!(order.order_status, OrderStatus::New));
+
+        // 同一周期的成交量只累计一次
+        let order = optimistic
+            .get_order("BTC", "USDT", &optimistic_buy.order_id)
+            .await?;
+        assert!(matches!(order.order_status, OrderStatus::New));
+
+        save_tick(dec!(29850), dec!(1), 2).await?;
+        let order = optimistic
+            .get_order("BTC", "USDT", &optimistic_buy.order_id)
+            .await?;
+        assert!(matches!(order.order_status, OrderStatus::Filled));
+        assert_eq!(order.avg_price.parse::<Decimal>()?, dec!(29900));
+
+        // 悲观模式需先消耗挂单时估计的排队量
+        let order = pessimistic
+            .get_order("BTC", "USDT", &pessimistic_buy.order_id)
+            .await?;
+        assert!(matches!(order.order_status, OrderStatus::New));
+
+        // 价格回到限价之上时不累计成交量
+        save_tick(dec!(30100), dec!(5), 3).await?;
+        save_tick(dec!(29900), dec!(1.5), 4).await?;
+        let order = pessimistic
+            .get_order("BTC", "USDT", &pessimistic_buy.order_id)
+            .await?;
+        assert!(matches!(order.order_status, OrderStatus::Filled));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_spot_client_execution_model() -> Result<()> {
+        let price_store = Arc::new(RwLock::new(PriceStore::new()));
+        let save_tick = |price: Decimal, volume: Decimal, timestamp: i64| {
+            let price_store = Arc::clone(&price_store);
+            async move {
+                let mut price_store = price_store.write().await;
+                let symbol_price = SymbolPrice::builder()
+                    .symbol("BTCUSDT".into())
+                    .price(price)
+                    .build();
+                price_store.save_price(&Exchange::Binance, &Market::Spot, &symbol_price)?;
+                price_store.save_volume(
+                    &Exchange::Binance,
+                    &Market::Spot,
+                    &symbol_price.symbol,
+                    volume,
+                )?;
+                price_store.save_timestamp(timestamp);
+                anyhow::Ok(())
+            }
+        };
+        save_tick(dec!(30000), dec!(10), 0).await?;
+
+        let client = |execution_model| -> SpotClientKind {
+            BacktestSpotClient::builder()
+                .assets(vec![("USDT".to_string(), 100000.)])
+                .commissions(0.)
+                .price_store(Arc::clone(&price_store))
+                .execution_model(execution_model)
+                .build()
+                .into()
+        };
+
+        // 固定滑点5个基点，下单数量占周期成交量的10%，额外冲击0.1%
+        let slippage = client(
+            ExecutionModel::builder()
+                .slippage_bps(dec!(5))
+                .impact(dec!(0.01))
+                .build(),
+        );
+        let order = slippage.market_buy("BTC", "USDT", 1.).await?;
+        assert!(matches!(order.order_status, OrderStatus::Filled));
+        assert_eq!(order.avg_price.parse::<Decimal>()?, dec!(30045));
+
+        let order = slippage.market_sell("BTC", "USDT", 1.).await?;
+        assert_eq!(order.avg_price.parse::<Decimal>()?, dec!(29955));
+
+        // 延迟2个tick，按之后第2个tick的价格成交
+        let latency = client(ExecutionModel::builder().latency_ticks(2).build());
+        let order = latency.market_buy("BTC", "USDT", 1.).await?;
+        assert!(matches!(order.order_status, OrderStatus::New));
+
+        save_tick(dec!(30100), dec!(10), 1).await?;
+        let pending = latency.get_order("BTC", "USDT", &order.order_id).await?;
+        assert!(matches!(pending.order_status, OrderStatus::New));
+
+        save_tick(dec!(30200), dec!(10), 2).await?;
+        let filled = latency.get_order("BTC", "USDT", &order.order_id).await?;
+        assert!(matches!(filled.order_status, OrderStatus::Filled));
+        assert_eq!(filled.avg_price.parse::<Decimal>()?, dec!(30200));
+        assert_eq!(filled.update_time, 2000);
+        assert_eq!(
+            latency.get_balance("USDT").await?.free.parse::<Decimal>()?,
+            dec!(69800)
+        );
+
+        // 成交时价格上涨导致余额不足，订单被拒绝
+        let order = latency.market_buy("BTC", "USDT", 2.3).await?;
+        save_tick(dec!(30300), dec!(10), 3).await?;
+        save_tick(dec!(30400), dec!(10), 4).await?;
+        let rejected = latency.get_order("BTC", "USDT", &order.order_id).await?;
+        assert!(matches!(rejected.order_status, OrderStatus::Rejected));
+        assert_eq!(
+            latency.get_balance("BTC").await?.free.parse::<Decimal>()?,
+            dec!(1)
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_spot_client_partial_fills() -> Result<()> {
+        let price_store = Arc::new(RwLock::new(PriceStore::new()));
+        let save_tick = |price: Decimal, volume: Decimal, timestamp: i64| {
+            let price_store = Arc::clone(&price_store);
+            async move {
+                let mut price_store = price_store.write().await;
+                let symbol_price = SymbolPrice::builder()
+                    .symbol("BTCUSDT".into())
+                    .price(price)
+                    .build();
+                price_store.save_price(&Exchange::Binance, &Market::Spot, &symbol_price)?;
+                price_store.save_volume(
+                    &Exchange::Binance,
+                    &Market::Spot,
+                    &symbol_price.symbol,
+                    volume,
+                )?;
+                price_store.save_timestamp(timestamp);
+                anyhow::Ok(())
+            }
+        };
+        save_tick(dec!(30000), dec!(10), 0).await?;
+
+        // 每个周期最多成交周期成交量的10%
+        let client: SpotClientKind = BacktestSpotClient::builder()
+            .assets(vec![("USDT".to_string(), 100000.), ("BTC".to_string(), 5.)])
+            .commissions(0.)
+            .price_store(Arc::clone(&price_store))
+            .participation_rate(0.1)
+            .build()
+            .into();
+        let mut user_data = client
+            .subscribe_user_data()
+            .ok_or_else(|| anyhow::anyhow!("missing user data"))?;
+
+        // 市价单分三个周期成交
+        let order = client.market_buy("BTC", "USDT", 2.5).await?;
+        assert!(matches!(order.order_status, OrderStatus::PartiallyFilled));
+        assert_eq!(order.executed_qty.parse::<Decimal>()?, dec!(1));
+
+        // 下单时的成交同样推送
+        let UserDataEvent::OrderUpdate(update) = user_data.try_recv()? else {
+            anyhow::bail!("expected order update");
+        };
+        assert_eq!(update.executed_qty, dec!(1));
+        assert_eq!(update.last_price, dec!(30000));
+
+        // 每一部分成交都结算资产
+        async fn balances(client: &SpotClientKind) -> Result<(Decimal, Decimal)> {
+            let base = client.get_balance("BTC").await?.free.parse::<Decimal>()?;
+            let quote = client.get_balance("USDT").await?.free.parse::<Decimal>()?;
+            Ok((base, quote))
+        }
+
+        assert_eq!(balances(&client).await?, (dec!(6), dec!(70000)));
+
+        // 同一周期不再成交
+        let pending = client.get_order("BTC", "USDT", &order.order_id).await?;
+        assert_eq!(pending.executed_qty.parse::<Decimal>()?, dec!(1));
+
+        save_tick(dec!(30100), dec!(10), 1).await?;
+        let pending = client.get_order("BTC", "USDT", &order.order_id).await?;
+        assert!(matches!(pending.order_status, OrderStatus::PartiallyFilled));
+        assert_eq!(pending.executed_qty.parse::<Decimal>()?, dec!(2));
+
+        let UserDataEvent::OrderUpdate(update) = user_data.try_recv()? else {
+            anyhow::bail!("expected order update");
+        };
+        assert_eq!(update.executed_qty, dec!(2));
+        assert_eq!(update.last_qty, dec!(1));
+        assert_eq!(update.last_price, dec!(30100));
+        assert_eq!(balances(&client).await?, (dec!(7), dec!(39900)));
+
+        save_tick(dec!(30200), dec!(10), 2).await?;
+        let filled = client.get_order("BTC", "USDT", &order.order_id).await?;
+        assert!(matches!(filled.order_status, OrderStatus::Filled));
+        assert_eq!(filled.executed_qty.parse::<Decimal>()?, dec!(2.5));
+        assert_eq!(filled.avg_price.parse::<Decimal>()?, dec!(30080));
+
+        let UserDataEvent::OrderUpdate(update) = user_data.try_recv()? else {
+            anyhow::bail!("expected order update");
+        };
+        assert!(matches!(update.order_status, OrderStatus::Filled));
+        assert_eq!(update.last_qty, dec!(0.5));
+        assert_eq!(balances(&client).await?, (dec!(7.5), dec!(24800)));
+
+        // 限价挂单部分成交后只解冻成交部分
+        let order = client.limit_sell("BTC", "USDT", 3., 30500.).await?;
+        assert!(matches!(order.order_status, OrderStatus::New));
+
+        save_tick(dec!(30600), dec!(20), 3).await?;
+        let pending = client.get_order("BTC", "USDT", &order.order_id).await?;
+        assert!(matches!(pending.order_status, OrderStatus::PartiallyFilled));
+        assert_eq!(pending.executed_qty.parse::<Decimal>()?, dec!(2));
+
+        // 市价单买入的2.5个逐笔到账
+        let balance = client.get_balance("BTC").await?;
+        assert_eq!(balance.free.parse::<Decimal>()?, dec!(4.5));
+        assert_eq!(balance.locked.parse::<Decimal>()?, dec!(1));
+        assert_eq!(client.get_open_orders("BTC", "USDT").await?.len(), 1);
+
+        save_tick(dec!(30600), dec!(20), 4).await?;
+        let filled = client.get_order("BTC", "USDT", &order.order_id).await?;
+        assert!(matches!(filled.order_status, OrderStatus::Filled));
+        assert_eq!(filled.avg_price.parse::<Decimal>()?, dec!(30500));
+
+        let balance = client.get_balance("BTC").await?;
+        assert_eq!(balance.locked.parse::<Decimal>()?, dec!(0));
+        assert_eq!(
+            client.get_balance("USDT").await?.free.parse::<Decimal>()?,
+            dec!(116300)
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_spot_client_on_tick() -> Result<()> {
+        let price_store = Arc::new(RwLock::new(PriceStore::new()));
+        let save_tick = |price: Decimal, timestamp: i64| {
+            let price_store = Arc::clone(&price_store);
+            async move {
+                let mut price_store = price_store.write().await;
+                price_store.save_price(
+                    &Exchange::Binance,
+                    &Market::Spot,
+                    &SymbolPrice::builder()
+                        .symbol("BTCUSDT".into())
+                        .price(price)
+                        .build(),
+                )?;
+                price_store.save_timestamp(timestamp);
+                anyhow::Ok(())
+            }
+        };
+        save_tick(dec!(30000), 0).await?;
+
+        let backtest = BacktestSpotClient::builder()
+            .assets(vec![("USDT".to_string(), 1000.)])
+            .commissions(0.)
+            .price_store(Arc::clone(&price_store))
+            .build();
+        let client: SpotClientKind = backtest.clone().into();
+        let mut user_data = client
+            .subscribe_user_data()
+            .ok_or_else(|| anyhow::anyhow!("missing user data"))?;
+
+        let order = client.limit_buy("BTC", "USDT", 0.01, 29000.).await?;
+
+        // 不调用客户端接口，tick上的撮合同样成交并推送
+        save_tick(dec!(28900), 1).await?;
+        backtest.on_tick().await?;
+
+        let UserDataEvent::OrderUpdate(update) = user_data.try_recv()? else {
+            anyhow::bail!("expected order update");
+        };
+        assert_eq!(update.order_id, order.order_id);
+        assert!(matches!(update.order_status, OrderStatus::Filled));
+        assert_eq!(update.last_price, dec!(29000));
+
+        // 同一个tick不重复撮合
+        backtest.on_tick().await?;
+        assert!(user_data.try_recv().is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_spot_client_symbol_commissions() -> Result<()> {
+        let price_store = Arc::new(RwLock::new(PriceStore::new()));
+        price_store.write().await.save_price(
+            &Exchange::Binance,
+            &Market::Spot,
+            &SymbolPrice::builder()
+                .symbol("BTCFDUSD".into())
+                .price(dec!(30000))
+                .build(),
+        )?;
+
+        let client: SpotClientKind = BacktestSpotClient::builder()
+            .assets(vec![("FDUSD".to_string(), 1000.), ("BTC".to_string(), 0.1)])
+            .commissions(0.001)
+            .symbol_commissions(vec![("btcfdusd".to_string(), 0.)])
+            .price_store(price_store)
+            .build()
+            .into();
+
+        let symbol_info = client.get_symbol_info("BTC", "FDUSD").await?;
+        assert_eq!(symbol_info.taker_commission_rate, Some(dec!(0)));
+
+        let symbol_info = client.get_symbol_info("BTC", "USDT").await?;
+        assert_eq!(symbol_info.taker_commission_rate, None);
+
+        // 零手续费交易对按全部数量到账
+        client.limit_buy("BTC", "FDUSD", 0.01, 30000.).await?;
+        let btc = client.get_balance("BTC").await?;
+        assert_eq!(btc.free.parse::<Decimal>()?, dec!(0.11));
+
+        client.limit_sell("BTC", "FDUSD", 0.11, 30000.).await?;
+        let fdusd = client.get_balance("FDUSD").await?;
+        assert_eq!(fdusd.free.parse::<Decimal>()?, dec!(4000));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_spot_client_cancel_order() -> Result<()> {
+        let price_store = Arc::new(RwLock::new(PriceStore::new()));
+        price_store.write().await.save_price(
+            &Exchange::Binance,
+            &Market::Spot,
+            &SymbolPrice::builder()
+                .symbol("BTCUSDT".into())
+                .price(dec!(30000))
+                .build(),
+        )?;
+
+        let client: SpotClientKind = BacktestSpotClient::builder()
+            .assets(vec![("USDT".to_string(), 1000.), ("BTC".to_string(), 0.1)])
+            .price_store(price_store)
+            .build()
+            .into();
+
+        let buy = client.limit_buy("BTC", "USDT", 0.01, 29000.).await?;
+        client.limit_buy("BTC", "USDT", 0.01, 28000.).await?;
+        client.limit_sell("BTC", "USDT", 0.01, 31000.).await?;
+        client
+            .limit_sell("ETH", "USDT", 1., 3000.)
+            .await
+            .unwrap_err();
+
+        assert_eq!(client.get_open_orders("BTC", "USDT").await?.len(), 3);
+
+        // 撤单后解冻资产
+        let order = client.cancel_order("BTC", "USDT", &buy.order_id).await?;
+        assert!(matches!(order.order_status, OrderStatus::Canceled));
+        assert!(client
+            .cancel_order("BTC", "USDT", &buy.order_id)
+            .await
+            .is_err());
+
+        let order = client.get_order("BTC", "USDT", &buy.order_id).await?;
+        assert!(matches!(order.order_status, OrderStatus::Canceled));
+
+        let usdt = client.get_balance("USDT").await?;
+        assert_eq!(usdt.free.parse::<Decimal>()?, dec!(720));
+        assert_eq!(usdt.locked.parse::<Decimal>()?, dec!(280));
+
+        let orders = client.cancel_all_orders("BTC", "USDT").await?;
+        assert_eq!(orders.len(), 2);
+        assert!(client.get_open_orders("BTC", "USDT").await?.is_empty());
+
+        let usdt = client.get_balance("USDT").await?;
+        assert_eq!(usdt.free.parse::<Decimal>()?, dec!(1000));
+
+        let btc = client.get_balance("BTC").await?;
+        assert_eq!(btc.free.parse::<Decimal>()?, dec!(0.1));
+        assert_eq!(btc.locked.parse::<Decimal>()?, dec!(0));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_spot_client_stop_limit_and_oco() -> Result<()> {
+        let price_store = Arc::new(RwLock::new(PriceStore::new()));
+        let save_price = |price: Decimal| {
+            let price_store = Arc::clone(&price_store);
+            async move {
+                price_store.write().await.save_price(
+                    &Exchange::Binance,
+                    &Market::Spot,
+                    &SymbolPrice::builder()
+                        .symbol("BTCUSDT".into())
+                        .price(price)
+                        .build(),
+                )
+            }
+        };
+        save_price(dec!(30000)).await?;
+
+        let client: SpotClientKind = BacktestSpotClient::builder()
+            .assets(vec![("USDT".to_string(), 1000.), ("BTC".to_string(), 0.1)])
+            .commissions(0.)
+            .price_store(Arc::clone(&price_store))
+            .build()
+            .into();
+
+        // 立即触发的止损单被拒绝
+        assert!(client
+            .stop_limit_order("BTC", "USDT", OrderSide::Sell, 0.01, 30900., 31000.)
+            .await
+            .is_err());
+
+        let stop = client
+            .stop_limit_order("BTC", "USDT", OrderSide::Sell, 0.01, 28900., 29000.)
+            .await?;
+        assert_eq!(stop.order_type, OrderType::StopLossLimit);
+        assert_eq!(stop.stop_price.as_deref(), Some("29000"));
+
+        // OCO卖单的限价需高于最新价格
+        assert!(client
+            .oco_order("BTC", "USDT", OrderSide::Sell, 0.05, 29500., 28000., 27900.)
+            .await
+            .is_err());
+
+        let orders = client
+            .oco_order("BTC", "USDT", OrderSide::Sell, 0.05, 32000., 28000., 27900.)
+            .await?;
+        assert_eq!(orders[0].order_type, OrderType::LimitMaker);
+        assert_eq!(orders[1].order_type, OrderType::StopLossLimit);
+
+        // 两个订单共用冻结的资产
+        let btc = client.get_balance("BTC").await?;
+        assert_eq!(btc.free.parse::<Decimal>()?, dec!(0.04));
+        assert_eq!(btc.locked.parse::<Decimal>()?, dec!(0.06));
+
+        // 触发止损时已穿过限价，按最新价格成交
+        save_price(dec!(28950)).await?;
+        let stop = client.get_order("BTC", "USDT", &stop.order_id).await?;
+        assert!(matches!(stop.order_status, OrderStatus::Filled));
+        assert_eq!(stop.avg_price.parse::<Decimal>()?, dec!(28950));
+        assert_eq!(client.get_open_orders("BTC", "USDT").await?.len(), 2);
+
+        // 止损单触发后撤销限价单
+        save_price(dec!(27950)).await?;
+        let limit = client.get_order("BTC", "USDT", &orders[0].order_id).await?;
+        let stop = client.get_order("BTC", "USDT", &orders[1].order_id).await?;
+        assert!(matches!(limit.order_status, OrderStatus::Canceled));
+        assert!(matches!(stop.order_status, OrderStatus::Filled));
+        assert!(client.get_open_orders("BTC", "USDT").await?.is_empty());
+
+        let btc = client.get_balance("BTC").await?;
+        assert_eq!(btc.free.parse::<Decimal>()?, dec!(0.04));
+        assert_eq!(btc.locked.parse::<Decimal>()?, dec!(0));
+
+        let usdt = client.get_balance("USDT").await?;
+        assert_eq!(usdt.free.parse::<Decimal>()?, dec!(2687));
+
+        // 撤销OCO订单的一个订单，另一个订单同时撤销
+        let orders = client
+            .oco_order("BTC", "USDT", OrderSide::Buy, 0.01, 27000., 29000., 29100.)
+            .await?;
+
+        let usdt = client.get_balance("USDT").await?;
+        assert_eq!(usdt.locked.parse::<Decimal>()?, dec!(291));
+
+        client
+            .cancel_order("BTC", "USDT", &orders[1].order_id)
+            .await?;
+        let limit = client.get_order("BTC", "USDT", &orders[0].order_id).await?;
+        assert!(matches!(limit.order_status, OrderStatus::Canceled));
+
+        let usdt = client.get_balance("USDT").await?;
+        assert_eq!(usdt.free.parse::<Decimal>()?, dec!(2687));
+        assert_eq!(usdt.locked.parse::<Decimal>()?, dec!(0));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_spot_client_margin() -> Result<()> {
+        let price_store = Arc::new(RwLock::new(PriceStore::new()));
+        price_store.write().await.save_price(
+            &Exchange::Binance,
+            &Market::Spot,
+            &SymbolPrice::builder()
+                .symbol("BTCUSDT".into())
+                .price(dec!(100000))
+                .build(),
+        )?;
+        price_store.write().await.save_timestamp(0);
+
+        let client: SpotClientKind = BacktestSpotClient::builder()
+            .assets(vec![("USDT".to_string(), 10000.)])
+            .price_store(Arc::clone(&price_store))
+            .margin(true)
+            .margin_interest_rates(vec![("USDT".to_string(), 0.0024)])
+            .build()
+            .into();
+
+        let account = client.get_margin_account().await?;
+        assert_eq!(account.margin_level, dec!(999));
+
+        // 借款后风险率低于1.5
+        assert!(client.margin_borrow("USDT", 30000.).await.is_err());
+
+        // 借款时收取第一个小时的利息
+        client.margin_borrow("USDT", 10000.).await?;
+        let account = client.get_margin_account().await?;
+        let usdt = account.assets.iter().find(|a| a.asset == "USDT").unwrap();
+        assert_eq!(usdt.free, dec!(20000));
+        assert_eq!(usdt.borrowed, dec!(10000));
+        assert_eq!(usdt.interest, dec!(1));
+
+        let order = client.margin_buy("BTC", "USDT", 0.15).await?;
+        assert_eq!(order.executed_qty, "0.15");
+        assert_eq!(client.get_balance("USDT").await?.free, "5000.00");
+
+        // 满24小时后累计利息
+        price_store.write().await.save_timestamp(86400);
+        let account = client.get_margin_account().await?;
+        let usdt = account.assets.iter().find(|a| a.asset == "USDT").unwrap();
+        assert_eq!(usdt.interest, dec!(25));
+
+        client.margin_sell("BTC", "USDT", 0.14985).await?;
+        client.margin_repay("USDT", 10025.).await?;
+
+        let account = client.get_margin_account().await?;
+        assert_eq!(account.margin_level, dec!(999));
+        assert_eq!(account.total_liability_value, dec!(0));
+
+        // 未开启杠杆
+        let client: SpotClientKind = BacktestSpotClient::builder()
+            .assets(vec![("USDT".to_string(), 10000.)])
+            .price_store(price_store)
+            .build()
+            .into();
+        assert!(client.margin_borrow("USDT", 100.).await.is_err());
+
         Ok(())
     }
 }
@@ -1,12 +1,43 @@
-use super::{Futures, FuturesWebsocket, Spot, SpotWebsocket};
-use binance::{config::Config, futures::websockets::FuturesMarket};
+use super::{
+    rate_limiter::RateLimiter, Futures, FuturesWebsocket, Margin, Spot, SpotUserStream,
+    SpotWebsocket,
+};
+use crate::{client::ClientError, exchange::ConnectionOptions};
+use anyhow::{anyhow, Result};
+use binance::{
+    config::Config,
+    errors::{BinanceContentError, Error as BinanceError, ErrorKind as BinanceErrorKind},
+    futures::websockets::FuturesMarket,
+};
 use bon::bon;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use reqwest::{Method, Url};
+use serde::de::DeserializeOwned;
+use sha2::Sha256;
+
+// 接口所属的市场，决定接口地址和限流器
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Api {
+    Spot,    // 现货和杠杆接口
+    Futures, // U本位合约接口
+}
+
+// 接口的鉴权方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Security {
+    None,   // 公共接口
+    ApiKey, // 只需要 API Key，如 listen key 接口
+    Signed, // 需要 API Key 和签名
+}
 
 #[derive(Debug, Clone)]
 pub struct BinanceClient {
     api_key: Option<String>,
     secret_key: Option<String>,
     config: Option<Config>,
+    connection: ConnectionOptions, // 连接配置
+    http: reqwest::Client,         // HTTP 客户端，按账户代理创建
 }
 
 #[bon]
@@ -16,23 +47,42 @@ impl BinanceClient {
         api_key: Option<String>,
         secret_key: Option<String>,
         config: Option<Config>,
-    ) -> Self {
-        BinanceClient {
+        connection: Option<ConnectionOptions>,
+    ) -> Result<Self> {
+        let connection = connection.unwrap_or_default();
+        let config = connection.binance_config(config);
+        let http = connection.http_client()?;
+
+        Ok(BinanceClient {
             api_key,
             secret_key,
             config,
-        }
+            connection,
+            http,
+        })
     }
 
-    pub fn spot(&self) -> Spot {
+    pub fn api_key(&self) -> Option<&str> {
+        self.api_key.as_deref()
+    }
+
+    pub fn spot(&self) -> Spot<'_> {
         Spot::new(self)
     }
 
-    pub fn spot_websocket(&self, topic: impl Into<String>) -> SpotWebsocket {
+    pub fn spot_websocket(&self, topic: impl Into<String>) -> SpotWebsocket<'_> {
         SpotWebsocket::new(self, topic)
     }
 
-    pub fn futures(&self) -> Futures {
+    pub fn spot_user_stream(&self) -> SpotUserStream<'_> {
+        SpotUserStream::new(self)
+    }
+
+    pub fn margin(&self) -> Margin<'_> {
+        Margin::new(self)
+    }
+
+    pub fn futures(&self) -> Futures<'_> {
         Futures::new(self)
     }
 
@@ -40,7 +90,7 @@ impl BinanceClient {
         &self,
         market: FuturesMarket,
         topic: impl Into<String>,
-    ) -> FuturesWebsocket {
+    ) -> FuturesWebsocket<'_> {
         FuturesWebsocket::new(self, market, topic)
     }
 
@@ -48,18 +98,114 @@ impl BinanceClient {
         &self.config
     }
 
-    pub(crate) fn create_api<T, F1, F2>(&self, new: F1, new_with_config: F2) -> T
-    where
-        F1: FnOnce(Option<String>, Option<String>) -> T,
-        F2: FnOnce(Option<String>, Option<String>, &Config) -> T,
-    {
-        let api_key = self.api_key.clone();
-        let secret_key = self.secret_key.clone();
-
-        self.config
-            .as_ref()
-            .map_or(new(api_key.clone(), secret_key.clone()), |config| {
-                new_with_config(api_key, secret_key, config)
-            })
+    pub fn connection(&self) -> &ConnectionOptions {
+        &self.connection
+    }
+
+    // 按接口权重限流后发送请求，签名接口附加时间戳和签名，参数都放在查询字符串中
+    pub(crate) async fn request<T: DeserializeOwned>(
+        &self,
+        api: Api,
+        weight: u32,
+        method: Method,
+        path: &str,
+        query: &[(&str, String)],
+        security: Security,
+    ) -> Result<T> {
+        let config = self.config.clone().unwrap_or_default();
+        let (endpoint, limiter) = match api {
+            Api::Spot => (config.rest_api_endpoint, RateLimiter::spot()),
+            Api::Futures => (config.futures_rest_api_endpoint, RateLimiter::futures()),
+        };
+
+        let mut url = Url::parse(&format!("{}{}", endpoint.trim_end_matches('/'), path))?;
+
+        if !query.is_empty() {
+            url.query_pairs_mut().extend_pairs(query);
+        }
+
+        if security == Security::Signed {
+            let secret_key = self
+                .secret_key
+                .as_deref()
+                .ok_or_else(|| anyhow!("Binance secret key is required"))?;
+
+            url.query_pairs_mut()
+                .append_pair("recvWindow", &config.recv_window.to_string())
+                .append_pair("timestamp", &Utc::now().timestamp_millis().to_string());
+
+            let signature = sign(secret_key, url.query().unwrap_or_default())?;
+            url.query_pairs_mut().append_pair("signature", &signature);
+        }
+
+        let mut request = self.http.request(method, url);
+
+        if security != Security::None {
+            let api_key = self
+                .api_key
+                .as_deref()
+                .ok_or_else(|| anyhow!("Binance api key is required"))?;
+
+            request = request.header("X-MBX-APIKEY", api_key);
+        }
+
+        limiter.acquire(weight).await;
+
+        let response = request.send().await?;
+        limiter.observe_response(response.status(), response.headers());
+
+        if response.status().is_success() {
+            return Ok(response.json::<T>().await?);
+        }
+
+        let error = response.error_for_status_ref().err();
+        let status = response.status();
+        let body = response.text().await?;
+
+        // 业务错误返回 {"code":-2013,"msg":"Order does not exist."}，保留错误码供调用方判断
+        if let Ok(content) = serde_json::from_str::<BinanceContentError>(&body) {
+            let error = BinanceError::from_kind(BinanceErrorKind::BinanceError(content));
+            return Err(ClientError::BinanceError(error).into());
+        }
+
+        Err(error.map_or_else(
+            || anyhow!("Binance response {}: {}", status, body),
+            Into::into,
+        ))
+    }
+}
+
+// 签名: Hex(HMAC-SHA256(queryString, secretKey))
+fn sign(secret_key: &str, query: &str) -> Result<String> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret_key.as_bytes())
+        .map_err(|e| anyhow!("Binance sign failed: {}", e))?;
+    mac.update(query.as_bytes());
+
+    Ok(mac
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_binance_sign() -> Result<()> {
+        // 币安接口文档中的签名示例
+        let signature = sign(
+            "NhqPtmdSJYdKjVHjA7PZj4Mge3R5YNiP1e3UZjInClVN65XAbvqqM6A7H5fATj0j",
+            "symbol=LTCBTC&side=BUY&type=LIMIT&timeInForce=GTC&quantity=1&price=0.1&recvWindow=5000&timestamp=1499827319559",
+        )?;
+
+        assert_eq!(
+            signature,
+            "c8db56825ae71d6d79447849e617115f4a920fa2acdcab2b053c4b2838bd6b71"
+        );
+
+        Ok(())
     }
 }
@@ -1,17 +1,23 @@
-use super::BinanceClient;
+use super::{
+    client::{Api, Security},
+    spot::{klines_query, parse_klines},
+    BinanceClient,
+};
 use anyhow::{anyhow, Result};
 use binance::{
-    api::Binance,
+    account::OrderSide,
     futures::{
-        account::{FuturesAccount as Account, TimeInForce},
-        general::FuturesGeneral as General,
-        market::FuturesMarket as Market,
+        account::{OrderType, PositionSide, TimeInForce},
         model::{
-            AccountBalance, AccountInformation, ExchangeInformation, OrderBook, Symbol, Transaction,
+            AccountBalance, AccountInformation, ChangeLeverageResponse, ExchangeInformation,
+            MarkPrice, OrderBook, PositionRisk, Symbol, Transaction,
         },
     },
     model::{KlineSummaries, SymbolPrice},
 };
+use reqwest::Method;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
 
 pub struct Futures<'a> {
     client: &'a BinanceClient,
@@ -22,154 +28,252 @@ impl<'a> Futures<'a> {
         Futures { client }
     }
 
-    fn account(&self) -> Account {
+    // 合约接口使用独立的权重额度，下单接口不计IP权重，但仍受限流暂停约束
+    async fn request<T: DeserializeOwned>(
+        &self,
+        weight: u32,
+        method: Method,
+        path: &str,
+        query: &[(&str, String)],
+        security: Security,
+    ) -> Result<T> {
         self.client
-            .create_api(Account::new, Account::new_with_config)
-    }
-
-    fn market(&self) -> Market {
-        self.client.create_api(Market::new, Market::new_with_config)
+            .request(Api::Futures, weight, method, path, query, security)
+            .await
     }
 
-    fn general(&self) -> General {
-        self.client
-            .create_api(General::new, General::new_with_config)
+    async fn order(&self, query: &[(&str, String)]) -> Result<Transaction> {
+        self.request(0, Method::POST, "/fapi/v1/order", query, Security::Signed)
+            .await
     }
 
-    pub fn ping(&self) -> Result<String> {
-        let ping = self.general().ping().map_err(|e| anyhow!(e.to_string()))?;
+    pub async fn ping(&self) -> Result<String> {
+        self.request::<Value>(1, Method::GET, "/fapi/v1/ping", &[], Security::None)
+            .await?;
 
-        Ok(ping)
+        Ok("pong".to_string())
     }
 
-    pub fn get_exchange_info(&self) -> Result<ExchangeInformation> {
-        let exchange_info = self
-            .general()
-            .exchange_info()
-            .map_err(|e| anyhow!(e.to_string()))?;
-
-        Ok(exchange_info)
+    pub async fn get_exchange_info(&self) -> Result<ExchangeInformation> {
+        self.request(1, Method::GET, "/fapi/v1/exchangeInfo", &[], Security::None)
+            .await
     }
 
-    pub fn get_symbol_info(&self, symbol: impl Into<String>) -> Result<Symbol> {
-        let symbol_info = self
-            .general()
-            .get_symbol_info(symbol)
-            .map_err(|e| anyhow!(e.to_string()))?;
+    pub async fn get_symbol_info(&self, symbol: impl Into<String>) -> Result<Symbol> {
+        let symbol = symbol.into().to_uppercase();
 
-        Ok(symbol_info)
+        self.get_exchange_info()
+            .await?
+            .symbols
+            .into_iter()
+            .find(|symbol_info| symbol_info.symbol == symbol)
+            .ok_or_else(|| anyhow!("Symbol not found: {}", symbol))
     }
 
     // 获取账户信息
-    pub fn get_account(&self) -> Result<AccountInformation> {
-        let account_information = self
-            .account()
-            .account_information()
-            .map_err(|e| anyhow!(e.to_string()))?;
-
-        Ok(account_information)
+    pub async fn get_account(&self) -> Result<AccountInformation> {
+        self.request(5, Method::GET, "/fapi/v2/account", &[], Security::Signed)
+            .await
     }
 
     // 获取资产
-    pub fn get_asset(&self, asset: impl Into<String>) -> Result<AccountBalance> {
+    pub async fn get_asset(&self, asset: impl Into<String>) -> Result<AccountBalance> {
         let asset = asset.into();
 
-        let balances = self
-            .account()
-            .account_balance()
-            .map_err(|e| anyhow!(e.to_string()))?;
+        let balances: Vec<AccountBalance> = self
+            .request(5, Method::GET, "/fapi/v2/balance", &[], Security::Signed)
+            .await?;
 
-        let balance = balances
-            .iter()
+        balances
+            .into_iter()
             .find(|b| b.asset == asset)
-            .ok_or_else(|| anyhow!("Asset not found"))?
-            .clone();
-
-        Ok(balance)
+            .ok_or_else(|| anyhow!("Asset not found"))
     }
 
     // 限价买入
-    pub fn limit_buy(
+    pub async fn limit_buy(
         &self,
         symbol: impl Into<String>,  // 交易对
         qty: impl Into<f64>,        // 数量
         price: f64,                 // 价格
         time_in_force: TimeInForce, // 时间限制
     ) -> Result<Transaction> {
-        let transaction = self
-            .account()
-            .limit_buy(symbol, qty, price, time_in_force)
-            .map_err(|e| anyhow!(e.to_string()))?;
-
-        Ok(transaction)
+        self.order(&[
+            ("symbol", symbol.into()),
+            ("side", OrderSide::Buy.to_string()),
+            ("type", OrderType::Limit.to_string()),
+            ("quantity", qty.into().to_string()),
+            ("price", price.to_string()),
+            ("timeInForce", time_in_force.to_string()),
+        ])
+        .await
     }
 
     // 限价卖出
-    pub fn limit_sell(
+    pub async fn limit_sell(
         &self,
         symbol: impl Into<String>,  // 交易对
         qty: impl Into<f64>,        // 数量
         price: f64,                 // 价格
         time_in_force: TimeInForce, // 时间限制
     ) -> Result<Transaction> {
-        let transaction = self
-            .account()
-            .limit_sell(symbol, qty, price, time_in_force)
-            .map_err(|e| anyhow!(e.to_string()))?;
-
-        Ok(transaction)
+        self.order(&[
+            ("symbol", symbol.into()),
+            ("side", OrderSide::Sell.to_string()),
+            ("type", OrderType::Limit.to_string()),
+            ("quantity", qty.into().to_string()),
+            ("price", price.to_string()),
+            ("timeInForce", time_in_force.to_string()),
+        ])
+        .await
     }
 
     // 市价买入
-    pub fn market_buy(
+    pub async fn market_buy(
         &self,
         symbol: impl Into<String>, // 交易对
         qty: impl Into<f64>,       // 数量
     ) -> Result<Transaction> {
-        let transaction = self
-            .account()
-            .market_buy(symbol, qty)
-            .map_err(|e| anyhow!(e.to_string()))?;
-
-        Ok(transaction)
+        self.order(&[
+            ("symbol", symbol.into()),
+            ("side", OrderSide::Buy.to_string()),
+            ("type", OrderType::Market.to_string()),
+            ("quantity", qty.into().to_string()),
+        ])
+        .await
     }
 
     // 市价卖出
-    pub fn market_sell(
+    pub async fn market_sell(
         &self,
         symbol: impl Into<String>, // 交易对
         qty: impl Into<f64>,       // 数量
     ) -> Result<Transaction> {
-        let transaction = self
-            .account()
-            .market_sell(symbol, qty)
-            .map_err(|e| anyhow!(e.to_string()))?;
+        self.order(&[
+            ("symbol", symbol.into()),
+            ("side", OrderSide::Sell.to_string()),
+            ("type", OrderType::Market.to_string()),
+            ("quantity", qty.into().to_string()),
+        ])
+        .await
+    }
 
-        Ok(transaction)
+    // 获取交易对的持仓，双向持仓模式下多空分别返回
+    pub async fn get_positions(&self, symbol: impl Into<String>) -> Result<Vec<PositionRisk>> {
+        let query = [("symbol", symbol.into())];
+
+        self.request(
+            5,
+            Method::GET,
+            "/fapi/v2/positionRisk",
+            &query,
+            Security::Signed,
+        )
+        .await
+    }
+
+    // 调整杠杆倍数，返回调整后的倍数
+    pub async fn change_leverage(&self, symbol: impl Into<String>, leverage: u8) -> Result<u8> {
+        let query = [
+            ("symbol", symbol.into()),
+            ("leverage", leverage.to_string()),
+        ];
+
+        let response: ChangeLeverageResponse = self
+            .request(
+                1,
+                Method::POST,
+                "/fapi/v1/leverage",
+                &query,
+                Security::Signed,
+            )
+            .await?;
+
+        Ok(response.leverage)
+    }
+
+    // 按持仓方向下单，双向持仓模式下开多、平空为买入，开空、平多为卖出
+    pub async fn position_order(
+        &self,
+        symbol: impl Into<String>,   // 交易对
+        side: OrderSide,             // 买卖方向
+        position_side: PositionSide, // 持仓方向
+        qty: f64,                    // 数量
+        price: Option<f64>,          // 价格，为空时下市价单
+    ) -> Result<Transaction> {
+        let mut query = vec![
+            ("symbol", symbol.into()),
+            ("side", side.to_string()),
+            ("positionSide", position_side.to_string()),
+            ("quantity", qty.to_string()),
+        ];
+
+        match price {
+            Some(price) => {
+                query.push(("type", OrderType::Limit.to_string()));
+                query.push(("price", price.to_string()));
+                query.push(("timeInForce", TimeInForce::GTC.to_string()));
+            }
+            None => query.push(("type", OrderType::Market.to_string())),
+        }
+
+        self.order(&query).await
+    }
+
+    // 撤销交易对的所有挂单
+    pub async fn cancel_all_open_orders(&self, symbol: impl Into<String>) -> Result<()> {
+        let query = [("symbol", symbol.into())];
+
+        self.request::<Value>(
+            1,
+            Method::DELETE,
+            "/fapi/v1/allOpenOrders",
+            &query,
+            Security::Signed,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    // 获取标记价格和资金费率
+    pub async fn get_mark_price(&self, symbol: impl Into<String>) -> Result<MarkPrice> {
+        let query = [("symbol", symbol.into())];
+
+        self.request(
+            1,
+            Method::GET,
+            "/fapi/v1/premiumIndex",
+            &query,
+            Security::None,
+        )
+        .await
     }
 
     // 获取价格
-    pub fn get_price(&self, symbol: impl Into<String>) -> Result<SymbolPrice> {
-        let price = self
-            .market()
-            .get_price(symbol)
-            .map_err(|e| anyhow!(e.to_string()))?;
+    pub async fn get_price(&self, symbol: impl Into<String>) -> Result<SymbolPrice> {
+        let query = [("symbol", symbol.into())];
 
-        Ok(price)
+        self.request(
+            1,
+            Method::GET,
+            "/fapi/v1/ticker/price",
+            &query,
+            Security::None,
+        )
+        .await
     }
 
     // 获取深度
-    pub fn get_depth(&self, symbol: impl Into<String>) -> Result<OrderBook> {
-        let order_book = self
-            .market()
-            .get_depth(symbol)
-            .map_err(|e| anyhow!(e.to_string()))?;
+    pub async fn get_depth(&self, symbol: impl Into<String>) -> Result<OrderBook> {
+        let query = [("symbol", symbol.into())];
 
-        Ok(order_book)
+        self.request(10, Method::GET, "/fapi/v1/depth", &query, Security::None)
+            .await
     }
 
     // 获取K线
-    pub fn get_klines(
+    pub async fn get_klines(
         &self,
         symbol: impl Into<String>,          // 交易对
         interval: impl Into<String>,        // 时间间隔
@@ -177,11 +281,18 @@ impl<'a> Futures<'a> {
         start_time: impl Into<Option<u64>>, // 开始时间
         end_time: impl Into<Option<u64>>,   // 结束时间
     ) -> Result<KlineSummaries> {
-        let klines = self
-            .market()
-            .get_klines(symbol, interval, limit, start_time, end_time)
-            .map_err(|e| anyhow!(e.to_string()))?;
+        let query = klines_query(
+            symbol.into(),
+            interval.into(),
+            limit.into(),
+            start_time.into(),
+            end_time.into(),
+        );
+
+        let rows: Vec<Vec<Value>> = self
+            .request(5, Method::GET, "/fapi/v1/klines", &query, Security::None)
+            .await?;
 
-        Ok(klines)
+        parse_klines(&rows)
     }
 }
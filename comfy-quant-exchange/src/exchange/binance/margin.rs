@@ -0,0 +1,102 @@
+use super::{
+    client::{Api, Security},
+    model::{MarginAccountDetails, MarginOrderResult, MarginTransactionId},
+    BinanceClient,
+};
+use anyhow::Result;
+use reqwest::Method;
+
+// 全仓杠杆账户，binance-rs 没有杠杆接口，直接调用签名请求
+#[derive(Clone)]
+pub struct Margin<'a> {
+    client: &'a BinanceClient,
+}
+
+impl<'a> Margin<'a> {
+    pub fn new(client: &'a BinanceClient) -> Self {
+        Margin { client }
+    }
+
+    // 获取杠杆账户详情
+    pub async fn get_account(&self) -> Result<MarginAccountDetails> {
+        self.client
+            .request(
+                Api::Spot,
+                10,
+                Method::GET,
+                "/sapi/v1/margin/account",
+                &[],
+                Security::Signed,
+            )
+            .await
+    }
+
+    // 借款
+    pub async fn borrow(&self, asset: &str, qty: f64) -> Result<MarginTransactionId> {
+        self.borrow_repay(asset, qty, "BORROW").await
+    }
+
+    // 还款
+    pub async fn repay(&self, asset: &str, qty: f64) -> Result<MarginTransactionId> {
+        self.borrow_repay(asset, qty, "REPAY").await
+    }
+
+    // 市价买入
+    pub async fn market_buy(&self, symbol: &str, qty: f64) -> Result<MarginOrderResult> {
+        self.market_order(symbol, "BUY", qty).await
+    }
+
+    // 市价卖出
+    pub async fn market_sell(&self, symbol: &str, qty: f64) -> Result<MarginOrderResult> {
+        self.market_order(symbol, "SELL", qty).await
+    }
+
+    async fn borrow_repay(
+        &self,
+        asset: &str,
+        qty: f64,
+        kind: &str, // BORROW 或 REPAY
+    ) -> Result<MarginTransactionId> {
+        let query = [
+            ("asset", asset.to_string()),
+            ("isIsolated", "FALSE".to_string()),
+            ("amount", qty.to_string()),
+            ("type", kind.to_string()),
+        ];
+
+        self.client
+            .request(
+                Api::Spot,
+                1,
+                Method::POST,
+                "/sapi/v1/margin/borrow-repay",
+                &query,
+                Security::Signed,
+            )
+            .await
+    }
+
+    async fn market_order(&self, symbol: &str, side: &str, qty: f64) -> Result<MarginOrderResult> {
+        // 借款和还款由调用方显式处理，下单不自动借还
+        let query = [
+            ("symbol", symbol.to_string()),
+            ("side", side.to_string()),
+            ("type", "MARKET".to_string()),
+            ("quantity", qty.to_string()),
+            ("newOrderRespType", "FULL".to_string()),
+            ("sideEffectType", "NO_SIDE_EFFECT".to_string()),
+            ("isIsolated", "FALSE".to_string()),
+        ];
+
+        self.client
+            .request(
+                Api::Spot,
+                6,
+                Method::POST,
+                "/sapi/v1/margin/order",
+                &query,
+                Security::Signed,
+            )
+            .await
+    }
+}
@@ -1,11 +1,22 @@
 mod client;
 mod futures;
 mod futures_websocket;
+mod margin;
+mod model;
+mod rate_limiter;
 mod spot;
+mod spot_user_stream;
 mod spot_websocket;
 
 pub use client::BinanceClient;
 pub use futures::Futures;
 pub use futures_websocket::FuturesWebsocket;
+pub use margin::Margin;
+pub use model::{
+    AccountPositionBalance, AccountPositionEvent, MarginAccountDetails, MarginAsset,
+    MarginOrderResult, MarginTransactionId, UserStreamEvent,
+};
+pub use rate_limiter::RateLimiter;
 pub use spot::Spot;
+pub use spot_user_stream::SpotUserStream;
 pub use spot_websocket::SpotWebsocket;
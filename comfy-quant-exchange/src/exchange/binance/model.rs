@@ -0,0 +1,139 @@
+use anyhow::Result;
+use binance::model::OrderTradeEvent;
+use serde::Deserialize;
+
+// 全仓杠杆账户详情，数值字段为字符串
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MarginAccountDetails {
+    pub borrow_enabled: bool,           // 是否允许借款
+    pub trade_enabled: bool,            // 是否允许交易
+    pub margin_level: String,           // 风险率
+    pub total_asset_of_btc: String,     // 总资产(BTC估值)
+    pub total_liability_of_btc: String, // 总负债(BTC估值)
+    pub total_net_asset_of_btc: String, // 净资产(BTC估值)
+    pub user_assets: Vec<MarginAsset>,  // 资产明细
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MarginAsset {
+    pub asset: String,     // 币种
+    pub free: String,      // 可用余额
+    pub locked: String,    // 锁定余额
+    pub borrowed: String,  // 借款
+    pub interest: String,  // 未还利息
+    pub net_asset: String, // 净资产
+}
+
+// 借款、还款结果
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MarginTransactionId {
+    pub tran_id: u64, // 交易ID
+}
+
+// 杠杆下单结果，newOrderRespType 为 FULL
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MarginOrderResult {
+    pub symbol: String,
+    pub order_id: u64,
+    pub client_order_id: String,
+    pub transact_time: u64,
+    pub price: String,
+    pub orig_qty: String,
+    pub executed_qty: String,
+    pub cummulative_quote_qty: String,
+    pub status: String,
+    #[serde(rename = "type")]
+    pub type_name: String,
+    pub side: String,
+}
+
+// 现货用户数据流推送的事件
+#[derive(Debug, Clone)]
+pub enum UserStreamEvent {
+    OrderTrade(Box<OrderTradeEvent>),      // 订单更新 executionReport
+    AccountPosition(AccountPositionEvent), // 余额变化 outboundAccountPosition
+}
+
+impl UserStreamEvent {
+    // 按事件类型解析推送消息，其他事件返回None，listen key 过期时返回错误以便重新连接
+    pub(crate) fn parse(message: &str) -> Result<Option<Self>> {
+        #[derive(Deserialize)]
+        struct Event {
+            e: String,
+        }
+
+        let event = match serde_json::from_str::<Event>(message)?.e.as_str() {
+            "executionReport" => {
+                UserStreamEvent::OrderTrade(Box::new(serde_json::from_str(message)?))
+            }
+            "outboundAccountPosition" => {
+                UserStreamEvent::AccountPosition(serde_json::from_str(message)?)
+            }
+            "listenKeyExpired" => anyhow::bail!("Binance listen key expired"),
+            _ => return Ok(None),
+        };
+
+        Ok(Some(event))
+    }
+}
+
+// 现货账户余额变化，只包含变化的币种。
+// binance-rs 的 AccountUpdateEvent 是合约的 ACCOUNT_UPDATE 格式，无法解析现货的推送
+#[derive(Deserialize, Debug, Clone)]
+pub struct AccountPositionEvent {
+    #[serde(rename = "E")]
+    pub event_time: u64, // 推送时间
+    #[serde(rename = "B")]
+    pub balances: Vec<AccountPositionBalance>, // 变化的余额
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct AccountPositionBalance {
+    #[serde(rename = "a")]
+    pub asset: String, // 币种
+    #[serde(rename = "f")]
+    pub free: String, // 可用余额
+    #[serde(rename = "l")]
+    pub locked: String, // 锁定余额
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+
+    #[test]
+    fn test_margin_order_result() -> Result<()> {
+        let json_str = r#"{"symbol":"BTCUSDT","orderId":28,"clientOrderId":"6gCrw2kRUAF9CvJDGP16IP","transactTime":1507725176595,"price":"0.00000000","origQty":"0.01000000","executedQty":"0.01000000","cummulativeQuoteQty":"600.00000000","status":"FILLED","timeInForce":"GTC","type":"MARKET","side":"BUY","marginBuyBorrowAmount":5,"marginBuyBorrowAsset":"BTC","isIsolated":false,"fills":[]}"#;
+        let order: MarginOrderResult = serde_json::from_str(json_str)?;
+
+        assert_eq!(order.order_id, 28);
+        assert_eq!(order.type_name, "MARKET");
+        assert_eq!(order.cummulative_quote_qty, "600.00000000");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_user_stream_event_parse() -> Result<()> {
+        let json_str = r#"{"e":"outboundAccountPosition","E":1564034571105,"u":1564034571073,"B":[{"a":"ETH","f":"10000.000000","l":"0.000000"}]}"#;
+        let Some(UserStreamEvent::AccountPosition(event)) = UserStreamEvent::parse(json_str)?
+        else {
+            anyhow::bail!("expected account position event");
+        };
+        assert_eq!(event.balances[0].asset, "ETH");
+        assert_eq!(event.balances[0].free, "10000.000000");
+
+        let json_str = r#"{"e":"balanceUpdate","E":1573200697110,"a":"BTC","d":"100.00000000","T":1573200697068}"#;
+        assert!(UserStreamEvent::parse(json_str)?.is_none());
+
+        let json_str = r#"{"e":"listenKeyExpired","E":1576653824250,"listenKey":"OfYGbUzi3PraNagEkdKuFwUHn48brFsItTdsuiIXrucEvD0rhRXZ7I6URWfE8YE8"}"#;
+        assert!(UserStreamEvent::parse(json_str).is_err());
+
+        Ok(())
+    }
+}
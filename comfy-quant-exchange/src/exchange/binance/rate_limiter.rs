@@ -0,0 +1,213 @@
+use reqwest::{header::HeaderMap, StatusCode};
+use std::{
+    sync::{Mutex, OnceLock},
+    time::{Duration, Instant},
+};
+
+const SPOT_WEIGHT_PER_MINUTE: u32 = 6000; // 现货和杠杆接口每分钟的请求权重上限
+const FUTURES_WEIGHT_PER_MINUTE: u32 = 2400; // U本位合约接口每分钟的请求权重上限
+const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(60); // 429/418 没有 Retry-After 时的等待时间
+
+// 币安按IP统计每分钟的请求权重，超限返回429，继续请求会被封禁并返回418。
+// 令牌桶按接口权重扣减，同一个API域名的所有客户端共用一个限流器。
+// 每个响应的 X-MBX-USED-WEIGHT 和 Retry-After 用于校正剩余权重和暂停请求
+#[derive(Debug)]
+pub struct RateLimiter {
+    capacity: u32,
+    state: Mutex<State>,
+}
+
+#[derive(Debug)]
+struct State {
+    tokens: f64,                   // 剩余权重
+    updated_at: Instant,           // 上次补充令牌的时间
+    paused_until: Option<Instant>, // 被限流或封禁后暂停请求直到该时间
+}
+
+impl RateLimiter {
+    pub fn new(capacity: u32) -> Self {
+        RateLimiter {
+            capacity,
+            state: Mutex::new(State {
+                tokens: capacity as f64,
+                updated_at: Instant::now(),
+                paused_until: None,
+            }),
+        }
+    }
+
+    // 现货、杠杆接口共用的限流器
+    pub fn spot() -> &'static RateLimiter {
+        static SPOT: OnceLock<RateLimiter> = OnceLock::new();
+        SPOT.get_or_init(|| RateLimiter::new(SPOT_WEIGHT_PER_MINUTE))
+    }
+
+    // U本位合约接口共用的限流器
+    pub fn futures() -> &'static RateLimiter {
+        static FUTURES: OnceLock<RateLimiter> = OnceLock::new();
+        FUTURES.get_or_init(|| RateLimiter::new(FUTURES_WEIGHT_PER_MINUTE))
+    }
+
+    // 异步等待直到有足够的权重，等待期间不占用运行时的工作线程
+    pub async fn acquire(&self, weight: u32) {
+        while let Some(wait) = self.try_acquire(weight, Instant::now()) {
+            tracing::debug!(weight, "Binance rate limit reached, waiting {:?}", wait);
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    // 剩余权重
+    pub fn available(&self) -> u32 {
+        let mut state = self.lock();
+        self.refill(&mut state, Instant::now());
+        state.tokens as u32
+    }
+
+    // 按交易所返回的已用权重校正剩余权重
+    pub fn update_used_weight(&self, used: u32) {
+        let mut state = self.lock();
+        self.refill(&mut state, Instant::now());
+        state.tokens = state.tokens.min(self.capacity.saturating_sub(used) as f64);
+    }
+
+    // 暂停请求，清空剩余权重
+    pub fn pause(&self, duration: Duration) {
+        let mut state = self.lock();
+        let until = Instant::now() + duration;
+
+        state.tokens = 0.;
+        state.updated_at = until;
+        state.paused_until = Some(state.paused_until.map_or(until, |t| t.max(until)));
+    }
+
+    // 按响应头中的已用权重校正剩余权重，被限流或封禁时按 Retry-After 暂停请求
+    pub(crate) fn observe_response(&self, status: StatusCode, headers: &HeaderMap) {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.parse::<u64>().ok())
+        };
+
+        if let Some(used) = header("x-mbx-used-weight-1m") {
+            self.update_used_weight(used as u32);
+        }
+
+        if matches!(status.as_u16(), 429 | 418) {
+            let retry_after = header("retry-after")
+                .map(Duration::from_secs)
+                .unwrap_or(DEFAULT_RETRY_AFTER);
+
+            tracing::warn!(
+                monotonic_counter.binance_rate_limited = 1_u64,
+                "Binance rate limited, pause requests for {:?}",
+                retry_after
+            );
+
+            self.pause(retry_after);
+        }
+    }
+
+    // 有足够的权重时扣减并返回None，否则返回需要等待的时间
+    fn try_acquire(&self, weight: u32, now: Instant) -> Option<Duration> {
+        let mut state = self.lock();
+
+        if let Some(until) = state.paused_until {
+            if now < until {
+                return Some(until - now);
+            }
+
+            state.paused_until = None;
+        }
+
+        self.refill(&mut state, now);
+
+        // 单个请求的权重超过上限时按上限扣减，避免永远等待
+        let weight = weight.min(self.capacity) as f64;
+
+        if state.tokens >= weight {
+            state.tokens -= weight;
+            return None;
+        }
+
+        let rate = self.capacity as f64 / 60.;
+        Some(Duration::from_secs_f64((weight - state.tokens) / rate))
+    }
+
+    // 按时间匀速补充权重，一分钟补满
+    fn refill(&self, state: &mut State, now: Instant) {
+        let Some(elapsed) = now.checked_duration_since(state.updated_at) else {
+            return;
+        };
+
+        let rate = self.capacity as f64 / 60.;
+        state.tokens = (state.tokens + elapsed.as_secs_f64() * rate).min(self.capacity as f64);
+        state.updated_at = now;
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_rate_limiter_acquire() {
+        let limiter = RateLimiter::new(60);
+        let now = Instant::now();
+
+        assert_eq!(limiter.try_acquire(50, now), None);
+
+        // 剩余10，每秒补充1
+        let wait = limiter.try_acquire(20, now).unwrap();
+        assert_eq!(wait, Duration::from_secs(10));
+
+        assert_eq!(limiter.try_acquire(20, now + Duration::from_secs(10)), None);
+
+        // 交易所返回的已用权重高于本地统计时以交易所为准
+        limiter.update_used_weight(60);
+        assert_eq!(limiter.available(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limiter_acquire_in_runtime() {
+        let limiter = Arc::new(RateLimiter::new(60));
+        limiter.pause(Duration::from_millis(200));
+
+        // 单线程运行时上等待权重时，其他任务照常执行
+        let waiting = tokio::spawn({
+            let limiter = Arc::clone(&limiter);
+            async move {
+                limiter.acquire(1).await;
+                Instant::now()
+            }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let other = tokio::spawn(async { Instant::now() }).await.unwrap();
+
+        let acquired = waiting.await.unwrap();
+        assert!(other < acquired);
+        assert!(acquired - other >= Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_rate_limiter_observe_response() {
+        let limiter = RateLimiter::new(6000);
+        let mut headers = HeaderMap::new();
+        headers.insert("x-mbx-used-weight-1m", HeaderValue::from_static("5990"));
+
+        limiter.observe_response(StatusCode::OK, &headers);
+        assert_eq!(limiter.available(), 10);
+
+        headers.insert("retry-after", HeaderValue::from_static("30"));
+        limiter.observe_response(StatusCode::TOO_MANY_REQUESTS, &headers);
+
+        let wait = limiter.try_acquire(1, Instant::now()).unwrap();
+        assert!(wait > Duration::from_secs(29) && wait <= Duration::from_secs(30));
+    }
+}
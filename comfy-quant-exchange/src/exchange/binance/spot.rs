@@ -1,16 +1,37 @@
-use super::BinanceClient;
+use super::{
+    client::{Api, Security},
+    BinanceClient,
+};
 use crate::client::ClientError;
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use binance::{
-    account::Account,
-    api::Binance,
-    general::General,
-    market::Market,
+    errors::ErrorKind as BinanceErrorKind,
     model::{
-        AccountInformation, Balance, ExchangeInformation, KlineSummaries, Order, OrderBook, Symbol,
-        SymbolPrice, Transaction,
+        AccountInformation, Balance, ExchangeInformation, KlineSummaries, KlineSummary, Order,
+        OrderBook, OrderCanceled, Symbol, SymbolPrice, Transaction,
     },
 };
+use reqwest::Method;
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::Value;
+
+const ORDER_NOT_FOUND: i16 = -2013; // 订单不存在的错误码
+
+// OCO订单的返回结果，只保留订单ID
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct OcoOrder {
+    pub order_list_id: i64,
+    pub orders: Vec<OcoOrderId>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct OcoOrderId {
+    pub symbol: String,
+    pub order_id: u64,
+    pub client_order_id: String,
+}
 
 #[derive(Clone)]
 pub struct Spot<'a> {
@@ -22,153 +43,321 @@ impl<'a> Spot<'a> {
         Spot { client }
     }
 
-    fn account(&self) -> Account {
+    // 按接口权重限流，现货和杠杆接口共用同一个权重额度
+    async fn request<T: DeserializeOwned>(
+        &self,
+        weight: u32,
+        method: Method,
+        path: &str,
+        query: &[(&str, String)],
+        security: Security,
+    ) -> Result<T> {
         self.client
-            .create_api(Account::new, Account::new_with_config)
-    }
-
-    fn market(&self) -> Market {
-        self.client.create_api(Market::new, Market::new_with_config)
+            .request(Api::Spot, weight, method, path, query, security)
+            .await
     }
 
-    fn general(&self) -> General {
-        self.client
-            .create_api(General::new, General::new_with_config)
+    // 下单，参数与 binance-rs 的下单接口一致
+    async fn order(&self, query: &[(&str, String)]) -> Result<Transaction> {
+        self.request(1, Method::POST, "/api/v3/order", query, Security::Signed)
+            .await
     }
 
-    pub fn ping(&self) -> Result<String> {
-        let pong = self.general().ping().map_err(ClientError::BinanceError)?;
+    pub async fn ping(&self) -> Result<String> {
+        self.request::<Value>(1, Method::GET, "/api/v3/ping", &[], Security::None)
+            .await?;
 
-        Ok(pong)
+        Ok("pong".to_string())
     }
 
-    pub fn get_exchange_info(&self) -> Result<ExchangeInformation> {
-        let exchange_info = self
-            .general()
-            .exchange_info()
-            .map_err(ClientError::BinanceError)?;
-
-        Ok(exchange_info)
+    pub async fn get_exchange_info(&self) -> Result<ExchangeInformation> {
+        self.request(20, Method::GET, "/api/v3/exchangeInfo", &[], Security::None)
+            .await
     }
 
-    pub fn get_symbol_info(&self, symbol: impl Into<String>) -> Result<Symbol> {
-        let symbol_info = self
-            .general()
-            .get_symbol_info(symbol)
-            .map_err(ClientError::BinanceError)?;
+    pub async fn get_symbol_info(&self, symbol: impl Into<String>) -> Result<Symbol> {
+        let symbol = symbol.into().to_uppercase();
 
-        Ok(symbol_info)
+        self.get_exchange_info()
+            .await?
+            .symbols
+            .into_iter()
+            .find(|symbol_info| symbol_info.symbol == symbol)
+            .ok_or_else(|| anyhow!("Symbol not found: {}", symbol))
     }
 
     // 获取账户信息
-    pub fn get_account(&self) -> Result<AccountInformation> {
-        let account_information = self
-            .account()
-            .get_account()
-            .map_err(ClientError::BinanceError)?;
-
-        Ok(account_information)
+    pub async fn get_account(&self) -> Result<AccountInformation> {
+        self.request(20, Method::GET, "/api/v3/account", &[], Security::Signed)
+            .await
     }
 
     // 获取账户余额
-    pub fn get_balance(&self, asset: impl Into<String>) -> Result<Balance> {
-        let balance = self
-            .account()
-            .get_balance(asset)
-            .map_err(ClientError::BinanceError)?;
+    pub async fn get_balance(&self, asset: impl Into<String>) -> Result<Balance> {
+        let asset = asset.into();
 
-        Ok(balance)
+        self.get_account()
+            .await?
+            .balances
+            .into_iter()
+            .find(|balance| balance.asset == asset)
+            .ok_or_else(|| anyhow!("Asset not found: {}", asset))
     }
 
     // 限价买入
-    pub fn limit_buy(
+    pub async fn limit_buy(
         &self,
         symbol: impl Into<String>, // 交易对
         qty: impl Into<f64>,       // 数量
         price: f64,                // 价格
     ) -> Result<Transaction> {
-        let transaction = self
-            .account()
-            .limit_buy(symbol, qty, price)
-            .map_err(ClientError::BinanceError)?;
-
-        Ok(transaction)
+        self.order(&limit_order(symbol.into(), "BUY", qty.into(), price))
+            .await
     }
 
     // 限价卖出
-    pub fn limit_sell(
+    pub async fn limit_sell(
         &self,
         symbol: impl Into<String>,
         qty: impl Into<f64>, // 数量
         price: f64,          // 价格
     ) -> Result<Transaction> {
-        let transaction = self
-            .account()
-            .limit_sell(symbol, qty, price)
-            .map_err(ClientError::BinanceError)?;
-
-        Ok(transaction)
+        self.order(&limit_order(symbol.into(), "SELL", qty.into(), price))
+            .await
     }
 
     // 市价买入
-    pub fn market_buy(
+    pub async fn market_buy(
         &self,
         symbol: impl Into<String>, // 交易对
         qty: impl Into<f64>,       // 数量
     ) -> Result<Transaction> {
-        let transaction = self
-            .account()
-            .market_buy(symbol, qty)
-            .map_err(ClientError::BinanceError)?;
-
-        Ok(transaction)
+        self.order(&market_order(symbol.into(), "BUY", "quantity", qty.into()))
+            .await
     }
 
     // 市价卖出
-    pub fn market_sell(
+    pub async fn market_sell(
         &self,
         symbol: impl Into<String>, // 交易对
         qty: impl Into<f64>,       // 数量
     ) -> Result<Transaction> {
-        let transaction = self
-            .account()
-            .market_sell(symbol, qty)
-            .map_err(ClientError::BinanceError)?;
+        self.order(&market_order(symbol.into(), "SELL", "quantity", qty.into()))
+            .await
+    }
 
-        Ok(transaction)
+    // 按计价货币金额市价买入
+    pub async fn market_buy_quote(
+        &self,
+        symbol: impl Into<String>, // 交易对
+        quote_qty: f64,            // 计价货币金额
+    ) -> Result<Transaction> {
+        self.order(&market_order(
+            symbol.into(),
+            "BUY",
+            "quoteOrderQty",
+            quote_qty,
+        ))
+        .await
     }
 
-    pub fn get_order(&self, symbol: impl Into<String>, order_id: u64) -> Result<Order> {
-        let order = self
-            .account()
-            .order_status(symbol, order_id)
-            .map_err(ClientError::BinanceError)?;
+    // 按计价货币金额市价卖出
+    pub async fn market_sell_quote(
+        &self,
+        symbol: impl Into<String>, // 交易对
+        quote_qty: f64,            // 计价货币金额
+    ) -> Result<Transaction> {
+        self.order(&market_order(
+            symbol.into(),
+            "SELL",
+            "quoteOrderQty",
+            quote_qty,
+        ))
+        .await
+    }
+
+    // 止损限价买入
+    pub async fn stop_limit_buy(
+        &self,
+        symbol: impl Into<String>, // 交易对
+        qty: impl Into<f64>,       // 数量
+        price: f64,                // 限价
+        stop_price: f64,           // 止损触发价格
+    ) -> Result<Transaction> {
+        self.order(&stop_limit_order(
+            symbol.into(),
+            "BUY",
+            qty.into(),
+            price,
+            stop_price,
+        ))
+        .await
+    }
+
+    // 止损限价卖出
+    pub async fn stop_limit_sell(
+        &self,
+        symbol: impl Into<String>, // 交易对
+        qty: impl Into<f64>,       // 数量
+        price: f64,                // 限价
+        stop_price: f64,           // 止损触发价格
+    ) -> Result<Transaction> {
+        self.order(&stop_limit_order(
+            symbol.into(),
+            "SELL",
+            qty.into(),
+            price,
+            stop_price,
+        ))
+        .await
+    }
+
+    // OCO订单
+    pub async fn oco_order(
+        &self,
+        symbol: impl Into<String>, // 交易对
+        side: &str,                // 方向，BUY 或 SELL
+        qty: f64,                  // 数量
+        price: f64,                // 限价单价格
+        stop_price: f64,           // 止损触发价格
+        stop_limit_price: f64,     // 止损限价
+    ) -> Result<OcoOrder> {
+        let query = [
+            ("symbol", symbol.into()),
+            ("side", side.to_string()),
+            ("quantity", qty.to_string()),
+            ("price", price.to_string()),
+            ("stopPrice", stop_price.to_string()),
+            ("stopLimitPrice", stop_limit_price.to_string()),
+            ("stopLimitTimeInForce", "GTC".to_string()),
+        ];
+
+        self.request(
+            1,
+            Method::POST,
+            "/api/v3/order/oco",
+            &query,
+            Security::Signed,
+        )
+        .await
+    }
+
+    // 带客户端订单ID下单，限价单传入价格，市价单价格为None
+    pub async fn order_with_client_id(
+        &self,
+        symbol: impl Into<String>, // 交易对
+        side: &str,                // 方向，BUY 或 SELL
+        qty: f64,                  // 数量
+        price: Option<f64>,        // 限价单价格
+        client_order_id: &str,     // 客户端订单ID
+    ) -> Result<Transaction> {
+        let mut query = match price {
+            Some(price) => limit_order(symbol.into(), side, qty, price),
+            None => market_order(symbol.into(), side, "quantity", qty),
+        };
+
+        query.push(("newClientOrderId", client_order_id.to_string()));
+        query.push(("newOrderRespType", "FULL".to_string()));
 
-        Ok(order)
+        self.order(&query).await
+    }
+
+    // 按客户端订单ID查询订单，订单不存在时返回None
+    pub async fn get_order_by_client_id(
+        &self,
+        symbol: impl Into<String>,
+        client_order_id: &str,
+    ) -> Result<Option<Order>> {
+        let query = [
+            ("symbol", symbol.into()),
+            ("origClientOrderId", client_order_id.to_string()),
+        ];
+
+        let result = self
+            .request(4, Method::GET, "/api/v3/order", &query, Security::Signed)
+            .await;
+
+        match result {
+            Ok(order) => Ok(Some(order)),
+            Err(e) if is_order_not_found(&e) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub async fn get_order(&self, symbol: impl Into<String>, order_id: u64) -> Result<Order> {
+        let query = [("symbol", symbol.into()), ("orderId", order_id.to_string())];
+
+        self.request(4, Method::GET, "/api/v3/order", &query, Security::Signed)
+            .await
+    }
+
+    // 获取未成交订单
+    pub async fn get_open_orders(&self, symbol: impl Into<String>) -> Result<Vec<Order>> {
+        let query = [("symbol", symbol.into())];
+
+        self.request(
+            6,
+            Method::GET,
+            "/api/v3/openOrders",
+            &query,
+            Security::Signed,
+        )
+        .await
+    }
+
+    // 撤销订单
+    pub async fn cancel_order(
+        &self,
+        symbol: impl Into<String>,
+        order_id: u64,
+    ) -> Result<OrderCanceled> {
+        let query = [("symbol", symbol.into()), ("orderId", order_id.to_string())];
+
+        self.request(1, Method::DELETE, "/api/v3/order", &query, Security::Signed)
+            .await
+    }
+
+    // 撤销交易对的所有挂单
+    pub async fn cancel_all_open_orders(
+        &self,
+        symbol: impl Into<String>,
+    ) -> Result<Vec<OrderCanceled>> {
+        let query = [("symbol", symbol.into())];
+
+        self.request(
+            1,
+            Method::DELETE,
+            "/api/v3/openOrders",
+            &query,
+            Security::Signed,
+        )
+        .await
     }
 
     // 获取价格
-    pub fn get_price(&self, symbol: impl Into<String>) -> Result<SymbolPrice> {
-        let price = self
-            .market()
-            .get_price(symbol)
-            .map_err(ClientError::BinanceError)?;
+    pub async fn get_price(&self, symbol: impl Into<String>) -> Result<SymbolPrice> {
+        let query = [("symbol", symbol.into())];
 
-        Ok(price)
+        self.request(
+            2,
+            Method::GET,
+            "/api/v3/ticker/price",
+            &query,
+            Security::None,
+        )
+        .await
     }
 
     // 获取深度
-    pub fn get_depth(&self, symbol: impl Into<String>) -> Result<OrderBook> {
-        let order_book = self
-            .market()
-            .get_depth(symbol)
-            .map_err(ClientError::BinanceError)?;
+    pub async fn get_depth(&self, symbol: impl Into<String>) -> Result<OrderBook> {
+        let query = [("symbol", symbol.into())];
 
-        Ok(order_book)
+        self.request(5, Method::GET, "/api/v3/depth", &query, Security::None)
+            .await
     }
 
     // 获取K线
-    pub fn get_klines(
+    pub async fn get_klines(
         &self,
         symbol: impl Into<String>,          // 交易对
         interval: impl Into<String>,        // 时间间隔
@@ -176,11 +365,108 @@ impl<'a> Spot<'a> {
         start_time: impl Into<Option<u64>>, // 开始时间
         end_time: impl Into<Option<u64>>,   // 结束时间
     ) -> Result<KlineSummaries> {
-        let klines = self
-            .market()
-            .get_klines(symbol, interval, limit, start_time, end_time)
-            .map_err(ClientError::BinanceError)?;
+        let query = klines_query(
+            symbol.into(),
+            interval.into(),
+            limit.into(),
+            start_time.into(),
+            end_time.into(),
+        );
+
+        let rows: Vec<Vec<Value>> = self
+            .request(2, Method::GET, "/api/v3/klines", &query, Security::None)
+            .await?;
+
+        parse_klines(&rows)
+    }
+}
+
+fn limit_order(symbol: String, side: &str, qty: f64, price: f64) -> Vec<(&'static str, String)> {
+    vec![
+        ("symbol", symbol),
+        ("side", side.to_string()),
+        ("type", "LIMIT".to_string()),
+        ("quantity", qty.to_string()),
+        ("price", price.to_string()),
+        ("timeInForce", "GTC".to_string()),
+    ]
+}
+
+// 按数量(quantity)或计价货币金额(quoteOrderQty)下市价单
+fn market_order(
+    symbol: String,
+    side: &str,
+    qty_name: &'static str,
+    qty: f64,
+) -> Vec<(&'static str, String)> {
+    vec![
+        ("symbol", symbol),
+        ("side", side.to_string()),
+        ("type", "MARKET".to_string()),
+        (qty_name, qty.to_string()),
+    ]
+}
+
+fn stop_limit_order(
+    symbol: String,
+    side: &str,
+    qty: f64,
+    price: f64,
+    stop_price: f64,
+) -> Vec<(&'static str, String)> {
+    vec![
+        ("symbol", symbol),
+        ("side", side.to_string()),
+        ("type", "STOP_LOSS_LIMIT".to_string()),
+        ("quantity", qty.to_string()),
+        ("price", price.to_string()),
+        ("stopPrice", stop_price.to_string()),
+        ("timeInForce", "GTC".to_string()),
+    ]
+}
+
+// 现货和合约的K线接口参数相同
+pub(crate) fn klines_query(
+    symbol: String,
+    interval: String,
+    limit: Option<u16>,
+    start_time: Option<u64>,
+    end_time: Option<u64>,
+) -> Vec<(&'static str, String)> {
+    let mut query = vec![("symbol", symbol), ("interval", interval)];
+
+    if let Some(limit) = limit {
+        query.push(("limit", limit.to_string()));
+    }
+
+    if let Some(start_time) = start_time {
+        query.push(("startTime", start_time.to_string()));
+    }
+
+    if let Some(end_time) = end_time {
+        query.push(("endTime", end_time.to_string()));
+    }
+
+    query
+}
+
+// K线接口返回数组，按 binance-rs 的方式逐行转换
+pub(crate) fn parse_klines(rows: &[Vec<Value>]) -> Result<KlineSummaries> {
+    let klines = rows
+        .iter()
+        .map(KlineSummary::try_from)
+        .collect::<binance::errors::Result<Vec<_>>>()
+        .map_err(ClientError::BinanceError)?;
+
+    Ok(KlineSummaries::AllKlineSummaries(klines))
+}
 
-        Ok(klines)
+fn is_order_not_found(error: &anyhow::Error) -> bool {
+    match error.downcast_ref::<ClientError>() {
+        Some(ClientError::BinanceError(error)) => matches!(
+            &error.0,
+            BinanceErrorKind::BinanceError(content) if content.code == ORDER_NOT_FOUND
+        ),
+        None => false,
     }
 }
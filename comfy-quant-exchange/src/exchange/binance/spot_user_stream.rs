@@ -0,0 +1,171 @@
+use super::{
+    client::{Api, Security},
+    model::UserStreamEvent,
+    BinanceClient,
+};
+use anyhow::Result;
+use async_stream::stream;
+use binance::model::UserDataStream;
+use futures::stream::BoxStream;
+use reqwest::Method;
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tungstenite::Message;
+
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(30 * 60); // listen key 60分钟过期，每30分钟延长一次
+const RECONNECT_MIN_WAIT: Duration = Duration::from_secs(1); // 重连最短等待时间
+const RECONNECT_MAX_WAIT: Duration = Duration::from_secs(60); // 重连最长等待时间
+
+// 现货账户的用户数据流，推送订单更新(executionReport)和账户余额变化(outboundAccountPosition)
+pub struct SpotUserStream<'a> {
+    client: &'a BinanceClient,
+    keep_running: Arc<AtomicBool>,
+}
+
+impl<'a> SpotUserStream<'a> {
+    pub fn new(client: &'a BinanceClient) -> Self {
+        let keep_running = Arc::new(AtomicBool::new(true));
+
+        SpotUserStream {
+            client,
+            keep_running,
+        }
+    }
+
+    pub async fn subscribe(&self) -> Result<BoxStream<'static, UserStreamEvent>> {
+        let (tx, rx) = flume::unbounded();
+        let client = self.client.clone();
+        let keep_running = self.keep_running.clone();
+
+        // 断线后按指数退避重连，订阅者全部关闭后不再重连
+        tokio::spawn(async move {
+            let mut wait = RECONNECT_MIN_WAIT;
+
+            while keep_running.load(Ordering::Relaxed) && !tx.is_disconnected() {
+                if let Err(e) = run_session(&client, &tx, &keep_running, &mut wait).await {
+                    tracing::error!("{}", e);
+                }
+
+                if !keep_running.load(Ordering::Relaxed) || tx.is_disconnected() {
+                    break;
+                }
+
+                tracing::warn!(
+                    monotonic_counter.binance_user_stream_reconnect = 1_u64,
+                    "Binance user data stream disconnected, reconnecting in {:?}",
+                    wait
+                );
+
+                tokio::time::sleep(wait).await;
+                wait = (wait * 2).min(RECONNECT_MAX_WAIT);
+            }
+        });
+
+        let stream = stream! {
+            while let Ok(event) = rx.recv_async().await {
+                yield event;
+            }
+        };
+
+        Ok(Box::pin(stream))
+    }
+}
+
+impl Drop for SpotUserStream<'_> {
+    fn drop(&mut self) {
+        self.keep_running.store(false, Ordering::Relaxed);
+    }
+}
+
+// 一次连接：申请 listen key，定期延长有效期，直到连接断开
+async fn run_session(
+    client: &BinanceClient,
+    tx: &flume::Sender<UserStreamEvent>,
+    keep_running: &Arc<AtomicBool>,
+    wait: &mut Duration,
+) -> Result<()> {
+    // listen key 过期后需要重新申请，未过期时币安返回原来的 listen key
+    let answer: UserDataStream = client
+        .request(
+            Api::Spot,
+            2,
+            Method::POST,
+            "/api/v3/userDataStream",
+            &[],
+            Security::ApiKey,
+        )
+        .await?;
+
+    let listen_key = answer.listen_key;
+    let config = client.config().clone().unwrap_or_default();
+    let url = format!("{}/{}", config.ws_endpoint, listen_key);
+    let connection = client.connection().clone();
+
+    // tungstenite 是阻塞的，连接和读取都在单独的线程中运行
+    let (mut socket, _) =
+        tokio::task::spawn_blocking(move || connection.connect_websocket(&url)).await??;
+
+    // 连接成功后重置退避时间
+    *wait = RECONNECT_MIN_WAIT;
+
+    let keep_alive = tokio::spawn({
+        let client = client.clone();
+
+        async move {
+            let mut interval = tokio::time::interval(KEEP_ALIVE_INTERVAL);
+            interval.tick().await;
+
+            loop {
+                interval.tick().await;
+
+                let resp = client
+                    .request::<serde_json::Value>(
+                        Api::Spot,
+                        2,
+                        Method::PUT,
+                        "/api/v3/userDataStream",
+                        &[("listenKey", listen_key.clone())],
+                        Security::ApiKey,
+                    )
+                    .await;
+
+                if let Err(e) = resp {
+                    tracing::error!("Binance listen key keep alive failed: {}", e);
+                }
+            }
+        }
+    });
+
+    let tx = tx.clone();
+    let keep_running = keep_running.clone();
+
+    let resp = tokio::task::spawn_blocking(move || -> Result<()> {
+        while keep_running.load(Ordering::Relaxed) && !tx.is_disconnected() {
+            match socket.read()? {
+                // 只转发订单和余额事件
+                Message::Text(message) => match UserStreamEvent::parse(&message)? {
+                    Some(event) => {
+                        let _ = tx.send(event);
+                    }
+                    None => continue,
+                },
+                Message::Close(_) => anyhow::bail!("Binance user data stream closed"),
+                _ => continue,
+            }
+        }
+
+        let _ = socket.close(None);
+
+        Ok(())
+    })
+    .await;
+
+    keep_alive.abort();
+
+    resp?
+}
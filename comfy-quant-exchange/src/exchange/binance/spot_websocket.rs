@@ -3,11 +3,17 @@ use anyhow::Result;
 use async_stream::stream;
 use binance::websockets::{WebSockets, WebsocketEvent};
 use futures::stream::BoxStream;
-use std::sync::{
-    atomic::{AtomicBool, Ordering},
-    Arc,
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
 };
 
+const RECONNECT_MIN_WAIT: Duration = Duration::from_secs(1); // 重连最短等待时间
+const RECONNECT_MAX_WAIT: Duration = Duration::from_secs(60); // 重连最长等待时间
+
 #[allow(unused)]
 pub struct SpotWebsocket<'a> {
     client: &'a BinanceClient,
@@ -27,41 +33,60 @@ impl<'a> SpotWebsocket<'a> {
         }
     }
 
-    pub async fn subscribe(&self) -> Result<BoxStream<WebsocketEvent>> {
+    pub async fn subscribe(&self) -> Result<BoxStream<'_, WebsocketEvent>> {
         let (tx, rx) = flume::unbounded();
         let topic = self.topic.clone();
-        let config = self.client.config().clone();
+        let config = self.client.config().clone().unwrap_or_default();
+        let url = format!("{}/{}", config.ws_endpoint, topic);
+        let connection = self.client.connection().clone();
         let keep_running = self.keep_running.clone();
 
-        tokio::spawn(async move {
+        // 币安 SDK 的 websocket 是阻塞的，在单独的线程中运行，断线后按指数退避重连。
+        // SDK 连接时不支持代理，按账户代理建立连接后交给 SDK 解析推送
+        tokio::task::spawn_blocking(move || {
+            // 回调的错误类型由 SDK 决定
+            #[allow(clippy::result_large_err)]
             let callback = |event| {
                 let _ = tx.send(event);
                 Ok(())
             };
 
-            while keep_running.load(Ordering::Relaxed) {
+            let mut wait = RECONNECT_MIN_WAIT;
+
+            // 订阅者全部关闭后不再重连
+            while keep_running.load(Ordering::Relaxed) && !tx.is_disconnected() {
                 let mut websocket = WebSockets::new(callback);
 
-                let resp = if let Some(config) = &config {
-                    websocket.connect_with_config(&topic, config)
-                } else {
-                    websocket.connect(&topic)
-                };
+                match connection.connect_websocket(&url) {
+                    Ok(socket) => {
+                        websocket.socket = Some(socket);
+
+                        // 连接成功后重置退避时间
+                        wait = RECONNECT_MIN_WAIT;
 
-                if let Err(e) = resp {
-                    tracing::error!("{}", e);
-                    std::thread::sleep(std::time::Duration::from_secs(3));
-                    continue;
+                        if let Err(e) = websocket.event_loop(&keep_running) {
+                            tracing::error!("{}", e);
+                        }
+
+                        let _ = websocket.disconnect();
+                    }
+                    Err(e) => tracing::error!("{}", e),
                 }
 
-                if let Err(e) = websocket.event_loop(&keep_running) {
-                    tracing::error!("{}", e);
+                if !keep_running.load(Ordering::Relaxed) {
+                    break;
                 }
 
-                let _ = websocket.disconnect();
-            }
+                tracing::warn!(
+                    monotonic_counter.binance_spot_websocket_reconnect = 1_u64,
+                    topic = %topic,
+                    "Spot websocket disconnected, reconnecting in {:?}",
+                    wait
+                );
 
-            Ok::<(), anyhow::Error>(())
+                std::thread::sleep(wait);
+                wait = (wait * 2).min(RECONNECT_MAX_WAIT);
+            }
         });
 
         let stream = stream! {
@@ -0,0 +1,158 @@
+use super::model::{BybitInstrument, BybitKline, BybitList, BybitResponse, BybitTicker};
+use crate::exchange::ConnectionOptions;
+use anyhow::{anyhow, Result};
+use bon::bon;
+use reqwest::Url;
+use serde::de::DeserializeOwned;
+
+const REST_ENDPOINT: &str = "https://api.bybit.com";
+
+// Bybit V5 REST 接口，目前只使用现货行情的公共接口
+#[derive(Debug, Clone)]
+pub struct BybitClient {
+    endpoint: String,      // REST 接口地址
+    http: reqwest::Client, // HTTP 客户端，按代理创建
+}
+
+#[bon]
+impl BybitClient {
+    #[builder]
+    pub fn new(connection: Option<ConnectionOptions>) -> Result<Self> {
+        let connection = connection.unwrap_or_default();
+        let endpoint = connection
+            .rest_endpoint
+            .clone()
+            .unwrap_or_else(|| REST_ENDPOINT.to_string())
+            .trim_end_matches('/')
+            .to_string();
+
+        let http = connection.http_client()?;
+
+        Ok(BybitClient { endpoint, http })
+    }
+
+    // 获取交易对信息
+    pub async fn get_instrument(&self, symbol: &str) -> Result<BybitInstrument> {
+        let (list, _) = self
+            .get::<BybitList<BybitInstrument>>(
+                "/v5/market/instruments-info",
+                &[("category", "spot"), ("symbol", symbol)],
+            )
+            .await?;
+
+        list.list
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("Bybit instrument not found: {}", symbol))
+    }
+
+    // 获取最新行情
+    pub async fn get_ticker(&self, symbol: &str) -> Result<BybitTicker> {
+        let (list, time) = self
+            .get::<BybitList<BybitTicker>>(
+                "/v5/market/tickers",
+                &[("category", "spot"), ("symbol", symbol)],
+            )
+            .await?;
+
+        let mut ticker = list
+            .list
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("Bybit ticker not found: {}", symbol))?;
+        ticker.time = time;
+
+        Ok(ticker)
+    }
+
+    // 获取时间范围内的K线(毫秒)，接口按开盘时间倒序返回，这里转为正序
+    pub async fn get_klines(
+        &self,
+        symbol: &str,
+        interval: &str,
+        start_time: i64,
+        end_time: i64,
+        limit: u16,
+    ) -> Result<Vec<BybitKline>> {
+        let (list, _) = self
+            .get::<BybitList<Vec<String>>>(
+                "/v5/market/kline",
+                &[
+                    ("category", "spot"),
+                    ("symbol", symbol),
+                    ("interval", bybit_interval(interval)?),
+                    ("start", &start_time.to_string()),
+                    ("end", &end_time.to_string()),
+                    ("limit", &limit.to_string()),
+                ],
+            )
+            .await?;
+
+        let mut klines = list
+            .list
+            .into_iter()
+            .map(BybitKline::try_from)
+            .collect::<Result<Vec<_>>>()?;
+        klines.reverse();
+
+        Ok(klines)
+    }
+
+    // 返回结果和服务器时间(毫秒)
+    async fn get<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, &str)],
+    ) -> Result<(T, i64)> {
+        let url = Url::parse_with_params(&format!("{}{}", self.endpoint, path), query)?;
+
+        let response = self
+            .http
+            .get(url)
+            .send()
+            .await?
+            .json::<BybitResponse<T>>()
+            .await?;
+        let time = response.time;
+
+        Ok((response.into_result()?, time))
+    }
+}
+
+// 将K线周期转换为 Bybit 的格式，Bybit 不支持秒级K线
+pub fn bybit_interval(interval: &str) -> Result<&'static str> {
+    let interval = match interval {
+        "1m" => "1",
+        "3m" => "3",
+        "5m" => "5",
+        "15m" => "15",
+        "30m" => "30",
+        "1h" => "60",
+        "2h" => "120",
+        "4h" => "240",
+        "6h" => "360",
+        "12h" => "720",
+        "1d" => "D",
+        "1w" => "W",
+        "1M" => "M",
+        _ => anyhow::bail!("Bybit unsupported kline interval: {}", interval),
+    };
+
+    Ok(interval)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bybit_interval() -> Result<()> {
+        assert_eq!(bybit_interval("1m")?, "1");
+        assert_eq!(bybit_interval("4h")?, "240");
+        assert_eq!(bybit_interval("1d")?, "D");
+        assert!(bybit_interval("1s").is_err());
+        assert!(bybit_interval("8h").is_err());
+
+        Ok(())
+    }
+}
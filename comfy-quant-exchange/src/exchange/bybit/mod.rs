@@ -0,0 +1,5 @@
+mod client;
+mod model;
+
+pub use client::{bybit_interval, BybitClient};
+pub use model::{BybitInstrument, BybitKline, BybitLotSizeFilter, BybitPriceFilter, BybitTicker};
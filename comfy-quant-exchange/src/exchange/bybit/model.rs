@@ -0,0 +1,126 @@
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+
+// 接口返回的统一结构，retCode 为 0 时成功
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct BybitResponse<T> {
+    pub ret_code: i64,
+    pub ret_msg: String,
+    pub result: Option<T>,
+    #[serde(default)]
+    pub time: i64, // 服务器时间(毫秒)
+}
+
+impl<T> BybitResponse<T> {
+    pub fn into_result(self) -> Result<T> {
+        if self.ret_code != 0 {
+            anyhow::bail!(
+                "Bybit api error, code: {}, msg: {}",
+                self.ret_code,
+                self.ret_msg
+            );
+        }
+
+        self.result
+            .ok_or_else(|| anyhow!("Bybit api returned empty result"))
+    }
+}
+
+// 列表类接口的返回结果
+#[derive(Deserialize, Debug)]
+pub(super) struct BybitList<T> {
+    #[serde(default = "Vec::new")]
+    pub list: Vec<T>,
+}
+
+// 交易对信息
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BybitInstrument {
+    pub symbol: String,                      // 交易对，如 BTCUSDT
+    pub base_coin: String,                   // 交易货币
+    pub quote_coin: String,                  // 计价货币
+    pub status: String,                      // 状态: Trading/PreLaunch/Delivering/Closed
+    pub lot_size_filter: BybitLotSizeFilter, // 数量限制
+    pub price_filter: BybitPriceFilter,      // 价格限制
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BybitLotSizeFilter {
+    pub base_precision: String,  // 交易货币精度，如 0.000001
+    pub quote_precision: String, // 计价货币精度
+    pub min_order_amt: String,   // 最小下单金额
+    pub min_order_qty: String,   // 最小下单数量
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BybitPriceFilter {
+    pub tick_size: String, // 价格步长
+}
+
+// 行情
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BybitTicker {
+    pub symbol: String,     // 交易对
+    pub last_price: String, // 最新成交价
+    #[serde(skip)]
+    pub time: i64, // 数据时间(毫秒)，取自接口返回的服务器时间
+}
+
+// K线，接口以字符串数组返回
+#[derive(Debug, Clone, PartialEq)]
+pub struct BybitKline {
+    pub open_time: i64,   // 开盘时间(毫秒)
+    pub open: String,     // 开盘价
+    pub high: String,     // 最高价
+    pub low: String,      // 最低价
+    pub close: String,    // 收盘价
+    pub volume: String,   // 交易货币的成交量
+    pub turnover: String, // 计价货币的成交额
+}
+
+impl TryFrom<Vec<String>> for BybitKline {
+    type Error = anyhow::Error;
+
+    fn try_from(value: Vec<String>) -> Result<Self> {
+        let [open_time, open, high, low, close, volume, turnover] = value.as_slice() else {
+            return Err(anyhow!("Bybit kline format error: {:?}", value));
+        };
+
+        Ok(BybitKline {
+            open_time: open_time.parse()?,
+            open: open.clone(),
+            high: high.clone(),
+            low: low.clone(),
+            close: close.clone(),
+            volume: volume.clone(),
+            turnover: turnover.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bybit_response_and_kline() -> Result<()> {
+        let json_str = r#"{"retCode":0,"retMsg":"OK","result":{"category":"spot","symbol":"BTCUSDT","list":[["1670608800000","17071","17073","17027","17055.5","268611","15.74462667"]]},"retExtInfo":{},"time":1672025956592}"#;
+        let response: BybitResponse<BybitList<Vec<String>>> = serde_json::from_str(json_str)?;
+        let kline = BybitKline::try_from(response.into_result()?.list.remove(0))?;
+
+        assert_eq!(kline.open_time, 1670608800000);
+        assert_eq!(kline.close, "17055.5");
+        assert_eq!(kline.volume, "268611");
+
+        let json_str = r#"{"retCode":10001,"retMsg":"Not supported symbols","result":{},"retExtInfo":{},"time":1672025956592}"#;
+        let response: BybitResponse<BybitList<BybitTicker>> = serde_json::from_str(json_str)?;
+        assert!(response.into_result().is_err());
+
+        Ok(())
+    }
+}
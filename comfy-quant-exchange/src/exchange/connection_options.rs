@@ -0,0 +1,164 @@
+use super::proxy::connect_tunnel;
+use anyhow::{anyhow, Result};
+use binance::config::Config;
+use bon::Builder;
+use reqwest::Url;
+use serde::Deserialize;
+use std::net::TcpStream;
+use tungstenite::{handshake::client::Response, stream::MaybeTlsStream, WebSocket};
+
+const PROXY_SCHEMES: [&str; 4] = ["http://", "https://", "socks5://", "socks5h://"];
+const REST_SCHEMES: [&str; 2] = ["http://", "https://"];
+const WS_SCHEMES: [&str; 2] = ["ws://", "wss://"];
+
+// 交易所连接配置，按账户设置代理和自定义接口地址
+#[derive(Builder, Deserialize, Debug, Default, Clone, PartialEq)]
+#[builder(on(String, into))]
+pub struct ConnectionOptions {
+    pub proxy: Option<String>,         // 代理地址，如 "socks5://127.0.0.1:1080"
+    pub rest_endpoint: Option<String>, // 现货 REST 接口地址
+    pub ws_endpoint: Option<String>,   // 现货 websocket 地址
+    pub futures_rest_endpoint: Option<String>, // 合约 REST 接口地址
+    pub futures_ws_endpoint: Option<String>, // 合约 websocket 地址
+}
+
+impl ConnectionOptions {
+    // 检查代理和接口地址的协议
+    pub fn validate(&self) -> Result<()> {
+        check_scheme("proxy", &self.proxy, &PROXY_SCHEMES)?;
+        check_scheme("rest endpoint", &self.rest_endpoint, &REST_SCHEMES)?;
+        check_scheme("ws endpoint", &self.ws_endpoint, &WS_SCHEMES)?;
+        check_scheme(
+            "futures rest endpoint",
+            &self.futures_rest_endpoint,
+            &REST_SCHEMES,
+        )?;
+        check_scheme(
+            "futures ws endpoint",
+            &self.futures_ws_endpoint,
+            &WS_SCHEMES,
+        )?;
+
+        Ok(())
+    }
+
+    fn has_endpoint(&self) -> bool {
+        self.rest_endpoint.is_some()
+            || self.ws_endpoint.is_some()
+            || self.futures_rest_endpoint.is_some()
+            || self.futures_ws_endpoint.is_some()
+    }
+
+    // 将自定义接口地址合并到币安配置，未设置的地址保持原值
+    pub fn binance_config(&self, config: Option<Config>) -> Option<Config> {
+        if !self.has_endpoint() {
+            return config;
+        }
+
+        let mut config = config.unwrap_or_default();
+
+        if let Some(endpoint) = &self.rest_endpoint {
+            config = config.set_rest_api_endpoint(endpoint);
+        }
+
+        if let Some(endpoint) = &self.ws_endpoint {
+            config = config.set_ws_endpoint(endpoint);
+        }
+
+        if let Some(endpoint) = &self.futures_rest_endpoint {
+            config = config.set_futures_rest_api_endpoint(endpoint);
+        }
+
+        if let Some(endpoint) = &self.futures_ws_endpoint {
+            config = config.set_futures_ws_endpoint(endpoint);
+        }
+
+        Some(config)
+    }
+
+    // 按代理配置创建 HTTP 客户端
+    pub fn http_client(&self) -> Result<reqwest::Client> {
+        let mut builder = reqwest::Client::builder();
+
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(reqwest::Proxy::all(proxy)?);
+        }
+
+        Ok(builder.build()?)
+    }
+
+    // 按代理配置连接 websocket，阻塞直到握手完成，需要在阻塞线程中调用
+    pub(crate) fn connect_websocket(
+        &self,
+        url: &str,
+    ) -> Result<(WebSocket<MaybeTlsStream<TcpStream>>, Response)> {
+        let Some(proxy) = &self.proxy else {
+            return Ok(tungstenite::connect(url)?);
+        };
+
+        let target = Url::parse(url)?;
+        let host = target
+            .host_str()
+            .ok_or_else(|| anyhow!("Invalid websocket url: {}", url))?;
+        let port = target
+            .port_or_known_default()
+            .ok_or_else(|| anyhow!("Invalid websocket url: {}", url))?;
+
+        let stream = connect_tunnel(proxy, host, port)?;
+
+        tungstenite::client_tls(url, stream)
+            .map_err(|e| anyhow!("Websocket handshake failed: {}", e))
+    }
+}
+
+fn check_scheme(name: &str, url: &Option<String>, schemes: &[&str]) -> Result<()> {
+    if let Some(url) = url {
+        anyhow::ensure!(
+            schemes.iter().any(|scheme| url.starts_with(scheme)),
+            "Invalid {}: {}, expected one of {:?}",
+            name,
+            url,
+            schemes
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_connection_options() -> Result<()> {
+        let json_str = r#"{"proxy":"socks5://127.0.0.1:1080","rest_endpoint":"https://api1.binance.com","ws_endpoint":"wss://data-stream.binance.vision/ws"}"#;
+        let options: ConnectionOptions = serde_json::from_str(json_str)?;
+        options.validate()?;
+
+        let config = options.binance_config(None).unwrap();
+        assert_eq!(config.rest_api_endpoint, "https://api1.binance.com");
+        assert_eq!(config.ws_endpoint, "wss://data-stream.binance.vision/ws");
+        assert_eq!(
+            config.futures_rest_api_endpoint,
+            Config::default().futures_rest_api_endpoint
+        );
+
+        options.http_client()?;
+
+        // 只设置代理时不修改币安配置
+        let options = ConnectionOptions::builder()
+            .proxy("http://127.0.0.1:8080")
+            .build();
+        assert!(options.binance_config(None).is_none());
+
+        let options = ConnectionOptions::builder().proxy("ftp://proxy").build();
+        assert!(options.validate().is_err());
+
+        let options = ConnectionOptions::builder()
+            .ws_endpoint("https://stream.binance.com")
+            .build();
+        assert!(options.validate().is_err());
+
+        Ok(())
+    }
+}
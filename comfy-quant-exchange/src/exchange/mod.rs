@@ -1 +1,7 @@
 pub mod binance;
+pub mod bybit;
+mod connection_options;
+pub mod okx;
+mod proxy;
+
+pub use connection_options::ConnectionOptions;
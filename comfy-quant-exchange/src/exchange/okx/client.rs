@@ -0,0 +1,383 @@
+use super::model::{
+    OkxBalance, OkxCandle, OkxInstrument, OkxOrder, OkxOrderAck, OkxOrderRequest, OkxResponse,
+    OkxTicker, OkxTradeFee,
+};
+use crate::exchange::ConnectionOptions;
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use bon::bon;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use reqwest::{Method, Url};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::json;
+use sha2::Sha256;
+
+const REST_ENDPOINT: &str = "https://www.okx.com";
+
+// OKX V5 REST 接口，私有接口使用 API Key、Secret Key 和 Passphrase 签名
+#[derive(Debug, Clone)]
+pub struct OkxClient {
+    api_key: Option<String>,
+    secret_key: Option<String>,
+    passphrase: Option<String>,
+    simulated: bool,       // 是否使用模拟盘
+    endpoint: String,      // REST 接口地址
+    http: reqwest::Client, // HTTP 客户端，按账户代理创建
+}
+
+#[bon]
+impl OkxClient {
+    #[builder(on(String, into))]
+    pub fn new(
+        api_key: Option<String>,
+        secret_key: Option<String>,
+        passphrase: Option<String>,
+        #[builder(default)] simulated: bool,
+        connection: Option<ConnectionOptions>,
+    ) -> Result<Self> {
+        let connection = connection.unwrap_or_default();
+        let endpoint = connection
+            .rest_endpoint
+            .clone()
+            .unwrap_or_else(|| REST_ENDPOINT.to_string())
+            .trim_end_matches('/')
+            .to_string();
+
+        let http = connection.http_client()?;
+
+        Ok(OkxClient {
+            api_key,
+            secret_key,
+            passphrase,
+            simulated,
+            endpoint,
+            http,
+        })
+    }
+
+    pub fn api_key(&self) -> Option<&str> {
+        self.api_key.as_deref()
+    }
+
+    // 获取交易产品信息
+    pub async fn get_instrument(&self, inst_id: &str) -> Result<OkxInstrument> {
+        self.public_get::<OkxInstrument>(
+            "/api/v5/public/instruments",
+            &[("instType", "SPOT"), ("instId", inst_id)],
+        )
+        .await?
+        .pop()
+        .ok_or_else(|| anyhow!("OKX instrument not found: {}", inst_id))
+    }
+
+    // 获取手续费率，不指定交易产品时返回账户等级的现货费率
+    pub async fn get_trade_fee(&self, inst_id: Option<&str>) -> Result<OkxTradeFee> {
+        let mut query = vec![("instType", "SPOT")];
+
+        if let Some(inst_id) = inst_id {
+            query.push(("instId", inst_id));
+        }
+
+        self.private_get::<OkxTradeFee>("/api/v5/account/trade-fee", &query)
+            .await?
+            .pop()
+            .ok_or_else(|| anyhow!("OKX trade fee not found"))
+    }
+
+    // 获取交易账户中币种的余额
+    pub async fn get_balance(&self, ccy: &str) -> Result<OkxBalance> {
+        self.private_get::<OkxBalance>("/api/v5/account/balance", &[("ccy", ccy)])
+            .await?
+            .pop()
+            .ok_or_else(|| anyhow!("OKX balance not found: {}", ccy))
+    }
+
+    // 获取交易账户中所有币种的余额
+    pub async fn get_balances(&self) -> Result<OkxBalance> {
+        self.private_get::<OkxBalance>("/api/v5/account/balance", &[])
+            .await?
+            .pop()
+            .ok_or_else(|| anyhow!("OKX balance not found"))
+    }
+
+    pub async fn get_order(&self, inst_id: &str, ord_id: &str) -> Result<OkxOrder> {
+        self.private_get::<OkxOrder>(
+            "/api/v5/trade/order",
+            &[("instId", inst_id), ("ordId", ord_id)],
+        )
+        .await?
+        .pop()
+        .ok_or_else(|| anyhow!("OKX order not found: {}", ord_id))
+    }
+
+    // 获取未成交订单
+    pub async fn get_open_orders(&self, inst_id: &str) -> Result<Vec<OkxOrder>> {
+        self.private_get::<OkxOrder>(
+            "/api/v5/trade/orders-pending",
+            &[("instType", "SPOT"), ("instId", inst_id)],
+        )
+        .await
+    }
+
+    pub async fn place_order(&self, order: &OkxOrderRequest) -> Result<OkxOrderAck> {
+        self.private_post::<OkxOrderAck>("/api/v5/trade/order", order)
+            .await?
+            .pop()
+            .ok_or_else(|| anyhow!("OKX place order returned no data"))?
+            .check()
+    }
+
+    // 批量下单，每次最多20个订单，按顺序返回每个订单的结果
+    pub async fn place_batch_orders(&self, orders: &[OkxOrderRequest]) -> Result<Vec<OkxOrderAck>> {
+        self.private_post::<OkxOrderAck>("/api/v5/trade/batch-orders", orders)
+            .await
+    }
+
+    pub async fn cancel_order(&self, inst_id: &str, ord_id: &str) -> Result<OkxOrderAck> {
+        self.private_post::<OkxOrderAck>(
+            "/api/v5/trade/cancel-order",
+            &json!({ "instId": inst_id, "ordId": ord_id }),
+        )
+        .await?
+        .pop()
+        .ok_or_else(|| anyhow!("OKX cancel order returned no data"))?
+        .check()
+    }
+
+    // 获取最新行情
+    pub async fn get_ticker(&self, inst_id: &str) -> Result<OkxTicker> {
+        self.public_get::<OkxTicker>("/api/v5/market/ticker", &[("instId", inst_id)])
+            .await?
+            .pop()
+            .ok_or_else(|| anyhow!("OKX ticker not found: {}", inst_id))
+    }
+
+    // 获取最近的K线，按开盘时间倒序
+    pub async fn get_candles(
+        &self,
+        inst_id: &str,
+        bar: &str,
+        limit: u32,
+    ) -> Result<Vec<OkxCandle>> {
+        self.public_get::<Vec<String>>(
+            "/api/v5/market/candles",
+            &[
+                ("instId", inst_id),
+                ("bar", bar),
+                ("limit", &limit.to_string()),
+            ],
+        )
+        .await?
+        .into_iter()
+        .map(OkxCandle::try_from)
+        .collect()
+    }
+
+    // 获取时间范围内的历史K线(毫秒)，包含开始和结束时间，按开盘时间正序返回
+    pub async fn get_history_candles(
+        &self,
+        inst_id: &str,
+        bar: &str,
+        start_time: i64,
+        end_time: i64,
+        limit: u32,
+    ) -> Result<Vec<OkxCandle>> {
+        // after 返回早于该时间的数据，before 返回晚于该时间的数据，均不包含边界
+        let mut candles = self
+            .public_get::<Vec<String>>(
+                "/api/v5/market/history-candles",
+                &[
+                    ("instId", inst_id),
+                    ("bar", bar),
+                    ("after", &(end_time + 1).to_string()),
+                    ("before", &(start_time - 1).to_string()),
+                    ("limit", &limit.to_string()),
+                ],
+            )
+            .await?
+            .into_iter()
+            .map(OkxCandle::try_from)
+            .collect::<Result<Vec<_>>>()?;
+        candles.reverse();
+
+        Ok(candles)
+    }
+
+    async fn public_get<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, &str)],
+    ) -> Result<Vec<T>> {
+        self.request(Method::GET, path, query, None, false).await
+    }
+
+    async fn private_get<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, &str)],
+    ) -> Result<Vec<T>> {
+        self.request(Method::GET, path, query, None, true).await
+    }
+
+    async fn private_post<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &(impl Serialize + ?Sized),
+    ) -> Result<Vec<T>> {
+        let body = serde_json::to_string(body)?;
+        self.request(Method::POST, path, &[], Some(body), true)
+            .await
+    }
+
+    async fn request<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(&str, &str)],
+        body: Option<String>,
+        signed: bool,
+    ) -> Result<Vec<T>> {
+        let url = Url::parse_with_params(&format!("{}{}", self.endpoint, path), query)?;
+        // 签名使用包含查询参数的请求路径
+        let request_path = match url.query() {
+            Some(query) if !query.is_empty() => format!("{}?{}", path, query),
+            _ => path.to_string(),
+        };
+        let body = body.unwrap_or_default();
+
+        let mut request = self
+            .http
+            .request(method.clone(), url)
+            .header("Content-Type", "application/json");
+
+        if signed {
+            let (Some(api_key), Some(secret_key), Some(passphrase)) =
+                (&self.api_key, &self.secret_key, &self.passphrase)
+            else {
+                anyhow::bail!("OKX api key, secret key and passphrase are required");
+            };
+
+            let timestamp = Utc::now().format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string();
+            let sign = sign(
+                secret_key,
+                &timestamp,
+                method.as_str(),
+                &request_path,
+                &body,
+            )?;
+
+            request = request
+                .header("OK-ACCESS-KEY", api_key)
+                .header("OK-ACCESS-SIGN", sign)
+                .header("OK-ACCESS-TIMESTAMP", timestamp)
+                .header("OK-ACCESS-PASSPHRASE", passphrase);
+        }
+
+        if self.simulated {
+            request = request.header("x-simulated-trading", "1");
+        }
+
+        if !body.is_empty() {
+            request = request.body(body);
+        }
+
+        let response = request.send().await?.json::<OkxResponse<T>>().await?;
+
+        response.into_data()
+    }
+}
+
+// 将K线周期转换为 OKX 的格式，6小时及以上使用 UTC 时间对齐，与币安一致
+pub fn okx_bar(interval: &str) -> Result<&'static str> {
+    let bar = match interval {
+        "1s" => "1s",
+        "1m" => "1m",
+        "3m" => "3m",
+        "5m" => "5m",
+        "15m" => "15m",
+        "30m" => "30m",
+        "1h" => "1H",
+        "2h" => "2H",
+        "4h" => "4H",
+        "6h" => "6Hutc",
+        "12h" => "12Hutc",
+        "1d" => "1Dutc",
+        "1w" => "1Wutc",
+        "1M" => "1Mutc",
+        _ => anyhow::bail!("OKX unsupported kline interval: {}", interval),
+    };
+
+    Ok(bar)
+}
+
+// 签名: Base64(HMAC-SHA256(timestamp + method + requestPath + body, secretKey))
+fn sign(
+    secret_key: &str,
+    timestamp: &str,
+    method: &str,
+    request_path: &str,
+    body: &str,
+) -> Result<String> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret_key.as_bytes())
+        .map_err(|e| anyhow!("OKX sign failed: {}", e))?;
+    mac.update(format!("{}{}{}{}", timestamp, method, request_path, body).as_bytes());
+
+    Ok(STANDARD.encode(mac.finalize().into_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_okx_sign() -> Result<()> {
+        let sign = sign(
+            "22582BD0CFF14C41EDBF1AB98506286D",
+            "2020-12-08T09:08:57.715Z",
+            "GET",
+            "/api/v5/account/balance?ccy=BTC",
+            "",
+        )?;
+
+        assert_eq!(sign, "HiZhvSfMtWJA3uUIVXV3a/bSXNPCWvYFXoGCVS8V4zY=");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_okx_bar() -> Result<()> {
+        assert_eq!(okx_bar("1s")?, "1s");
+        assert_eq!(okx_bar("1h")?, "1H");
+        assert_eq!(okx_bar("1d")?, "1Dutc");
+        assert!(okx_bar("8h").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_okx_client_endpoint() -> Result<()> {
+        let client = OkxClient::builder()
+            .connection(
+                ConnectionOptions::builder()
+                    .rest_endpoint("https://aws.okx.com/")
+                    .build(),
+            )
+            .build()?;
+
+        assert_eq!(client.endpoint, "https://aws.okx.com");
+        assert_eq!(client.api_key(), None);
+
+        // 代理地址无效时返回错误，不退回到不使用代理的客户端
+        let client = OkxClient::builder()
+            .connection(
+                ConnectionOptions::builder()
+                    .proxy("http://[invalid")
+                    .build(),
+            )
+            .build();
+        assert!(client.is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,8 @@
+mod client;
+mod model;
+
+pub use client::{okx_bar, OkxClient};
+pub use model::{
+    OkxBalance, OkxBalanceDetail, OkxCandle, OkxInstrument, OkxOrder, OkxOrderAck, OkxOrderRequest,
+    OkxTicker, OkxTradeFee,
+};
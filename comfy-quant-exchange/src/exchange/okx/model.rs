@@ -0,0 +1,187 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+// 接口返回的统一结构，code 为 "0" 时成功
+#[derive(Deserialize, Debug)]
+pub(super) struct OkxResponse<T> {
+    pub code: String,
+    pub msg: String,
+    #[serde(default = "Vec::new")]
+    pub data: Vec<T>,
+}
+
+impl<T> OkxResponse<T> {
+    pub fn into_data(self) -> Result<Vec<T>> {
+        if self.code != "0" {
+            anyhow::bail!("OKX api error, code: {}, msg: {}", self.code, self.msg);
+        }
+
+        Ok(self.data)
+    }
+}
+
+// 交易产品
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OkxInstrument {
+    pub inst_id: String,   // 产品ID，如 BTC-USDT
+    pub base_ccy: String,  // 交易货币
+    pub quote_ccy: String, // 计价货币
+    pub tick_sz: String,   // 价格步长
+    pub lot_sz: String,    // 数量步长
+    pub min_sz: String,    // 最小下单数量
+    pub state: String,     // 状态: live/suspend/preopen/test
+}
+
+// 手续费率，负数表示收取手续费，正数表示返佣
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OkxTradeFee {
+    pub maker: String, // 挂单手续费率
+    pub taker: String, // 吃单手续费率
+}
+
+// 交易账户余额
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OkxBalance {
+    #[serde(default)]
+    pub details: Vec<OkxBalanceDetail>, // 各币种余额
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OkxBalanceDetail {
+    pub ccy: String,        // 币种
+    pub avail_bal: String,  // 可用余额
+    pub frozen_bal: String, // 冻结余额
+}
+
+// 下单请求，现货使用非保证金模式(cash)
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OkxOrderRequest {
+    pub inst_id: String,  // 产品ID
+    pub td_mode: String,  // 交易模式
+    pub side: String,     // 订单方向: buy/sell
+    pub ord_type: String, // 订单类型: market/limit/post_only
+    pub sz: String,       // 委托数量
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub px: Option<String>, // 委托价格，限价单需要
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tgt_ccy: Option<String>, // 市价单委托数量的单位: base_ccy/quote_ccy
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cl_ord_id: Option<String>, // 用户自定义订单ID
+}
+
+// 下单、撤单的返回结果
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OkxOrderAck {
+    pub ord_id: String, // 订单ID
+    #[serde(default)]
+    pub cl_ord_id: String, // 用户自定义订单ID
+    pub s_code: String, // 结果代码，"0" 为成功
+    pub s_msg: String,  // 失败原因
+}
+
+impl OkxOrderAck {
+    pub fn check(self) -> Result<Self> {
+        if self.s_code != "0" {
+            anyhow::bail!(
+                "OKX order error, code: {}, msg: {}",
+                self.s_code,
+                self.s_msg
+            );
+        }
+
+        Ok(self)
+    }
+}
+
+// 订单信息
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OkxOrder {
+    pub inst_id: String, // 产品ID
+    pub ord_id: String,  // 订单ID
+    #[serde(default)]
+    pub cl_ord_id: String, // 用户自定义订单ID
+    #[serde(default)]
+    pub px: String, // 委托价格，市价单为空
+    #[serde(default)]
+    pub avg_px: String, // 成交均价，没有成交时为空
+    pub sz: String,      // 委托数量
+    #[serde(default)]
+    pub tgt_ccy: String, // 市价单委托数量的单位
+    pub acc_fill_sz: String, // 累计成交数量
+    pub ord_type: String, // 订单类型
+    pub side: String,    // 订单方向
+    pub state: String,   // 订单状态: live/partially_filled/filled/canceled/mmp_canceled
+    pub c_time: String,  // 创建时间(毫秒)
+    pub u_time: String,  // 更新时间(毫秒)
+}
+
+// 行情
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OkxTicker {
+    pub inst_id: String, // 产品ID
+    pub last: String,    // 最新成交价
+    pub ts: String,      // 数据时间(毫秒)
+}
+
+// K线，接口以字符串数组返回
+#[derive(Debug, Clone, PartialEq)]
+pub struct OkxCandle {
+    pub ts: i64,        // 开盘时间(毫秒)
+    pub open: String,   // 开盘价
+    pub high: String,   // 最高价
+    pub low: String,    // 最低价
+    pub close: String,  // 收盘价
+    pub volume: String, // 交易货币的成交量
+    pub confirm: bool,  // 是否已收盘
+}
+
+impl TryFrom<Vec<String>> for OkxCandle {
+    type Error = anyhow::Error;
+
+    fn try_from(value: Vec<String>) -> Result<Self> {
+        let [ts, open, high, low, close, volume, .., confirm] = value.as_slice() else {
+            return Err(anyhow!("OKX candle format error: {:?}", value));
+        };
+
+        Ok(OkxCandle {
+            ts: ts.parse()?,
+            open: open.clone(),
+            high: high.clone(),
+            low: low.clone(),
+            close: close.clone(),
+            volume: volume.clone(),
+            confirm: confirm == "1",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_okx_response_and_candle() -> Result<()> {
+        let json_str = r#"{"code":"0","msg":"","data":[["1597026383085","3.721","3.743","3.677","3.708","8422410","22698348.04828491","12698348.04828491","1"]]}"#;
+        let response: OkxResponse<Vec<String>> = serde_json::from_str(json_str)?;
+        let candle = OkxCandle::try_from(response.into_data()?.remove(0))?;
+
+        assert_eq!(candle.ts, 1597026383085);
+        assert_eq!(candle.close, "3.708");
+        assert_eq!(candle.volume, "8422410");
+        assert!(candle.confirm);
+
+        let json_str = r#"{"code":"51001","msg":"Instrument ID does not exist","data":[]}"#;
+        let response: OkxResponse<OkxTicker> = serde_json::from_str(json_str)?;
+        assert!(response.into_data().is_err());
+
+        Ok(())
+    }
+}
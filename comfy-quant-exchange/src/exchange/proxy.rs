@@ -0,0 +1,232 @@
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use reqwest::Url;
+use std::{
+    io::{Read, Write},
+    net::{IpAddr, TcpStream, ToSocketAddrs},
+};
+
+const SOCKS_VERSION: u8 = 5;
+const SOCKS_NO_AUTH: u8 = 0;
+const SOCKS_PASSWORD_AUTH: u8 = 2;
+const MAX_RESPONSE_HEADER: usize = 8192; // CONNECT 响应头的最大长度
+
+// 通过代理建立到目标地址的 TCP 隧道，websocket 在隧道上再做 TLS 握手。
+// 支持 http 代理的 CONNECT 方法和 socks5/socks5h 代理，socks5 在本地解析域名，socks5h 由代理解析
+pub(crate) fn connect_tunnel(proxy: &str, host: &str, port: u16) -> Result<TcpStream> {
+    let proxy = Url::parse(proxy)?;
+    let proxy_host = proxy
+        .host_str()
+        .ok_or_else(|| anyhow!("Invalid proxy: {}", proxy))?;
+    let proxy_port = proxy
+        .port_or_known_default()
+        .unwrap_or(match proxy.scheme() {
+            "socks5" | "socks5h" => 1080,
+            _ => 8080,
+        });
+    let auth = (!proxy.username().is_empty()).then(|| {
+        (
+            proxy.username().to_string(),
+            proxy.password().unwrap_or_default().to_string(),
+        )
+    });
+
+    match proxy.scheme() {
+        "http" => {
+            let stream = TcpStream::connect((proxy_host, proxy_port))?;
+            http_connect(stream, host, port, auth)
+        }
+        "socks5" | "socks5h" => {
+            let stream = TcpStream::connect((proxy_host, proxy_port))?;
+            let remote_dns = proxy.scheme() == "socks5h";
+            socks5_connect(stream, host, port, auth, remote_dns)
+        }
+        // https 代理需要先和代理做 TLS 握手，websocket 的阻塞连接不支持
+        scheme => anyhow::bail!("Unsupported websocket proxy scheme: {}", scheme),
+    }
+}
+
+fn http_connect(
+    mut stream: TcpStream,
+    host: &str,
+    port: u16,
+    auth: Option<(String, String)>,
+) -> Result<TcpStream> {
+    let mut request = format!(
+        "CONNECT {host}:{port} HTTP/1.1\r\nHost: {host}:{port}\r\n",
+        host = host,
+        port = port
+    );
+
+    if let Some((username, password)) = auth {
+        let credentials = STANDARD.encode(format!("{}:{}", username, password));
+        request.push_str(&format!("Proxy-Authorization: Basic {}\r\n", credentials));
+    }
+
+    request.push_str("\r\n");
+    stream.write_all(request.as_bytes())?;
+
+    // 逐字节读取响应头，避免读到隧道中的数据
+    let mut response = Vec::new();
+    let mut byte = [0; 1];
+
+    while !response.ends_with(b"\r\n\r\n") {
+        anyhow::ensure!(
+            response.len() < MAX_RESPONSE_HEADER,
+            "Proxy CONNECT response too large"
+        );
+        stream.read_exact(&mut byte)?;
+        response.push(byte[0]);
+    }
+
+    let response = String::from_utf8_lossy(&response);
+    let status_line = response.lines().next().unwrap_or_default();
+
+    anyhow::ensure!(
+        status_line.split_whitespace().nth(1) == Some("200"),
+        "Proxy CONNECT failed: {}",
+        status_line
+    );
+
+    Ok(stream)
+}
+
+fn socks5_connect(
+    mut stream: TcpStream,
+    host: &str,
+    port: u16,
+    auth: Option<(String, String)>,
+    remote_dns: bool,
+) -> Result<TcpStream> {
+    let method = if auth.is_some() {
+        SOCKS_PASSWORD_AUTH
+    } else {
+        SOCKS_NO_AUTH
+    };
+    stream.write_all(&[SOCKS_VERSION, 1, method])?;
+
+    let mut reply = [0; 2];
+    stream.read_exact(&mut reply)?;
+    anyhow::ensure!(
+        reply == [SOCKS_VERSION, method],
+        "Socks5 proxy rejected auth method: {:?}",
+        reply
+    );
+
+    // 用户名密码认证 RFC 1929
+    if let Some((username, password)) = auth {
+        let mut request = vec![1, username.len() as u8];
+        request.extend_from_slice(username.as_bytes());
+        request.push(password.len() as u8);
+        request.extend_from_slice(password.as_bytes());
+        stream.write_all(&request)?;
+
+        stream.read_exact(&mut reply)?;
+        anyhow::ensure!(reply[1] == 0, "Socks5 proxy authentication failed");
+    }
+
+    let mut request = vec![SOCKS_VERSION, 1, 0];
+
+    if remote_dns {
+        request.push(3);
+        request.push(host.len() as u8);
+        request.extend_from_slice(host.as_bytes());
+    } else {
+        let addr = (host, port)
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| anyhow!("Failed to resolve host: {}", host))?;
+
+        match addr.ip() {
+            IpAddr::V4(ip) => {
+                request.push(1);
+                request.extend_from_slice(&ip.octets());
+            }
+            IpAddr::V6(ip) => {
+                request.push(4);
+                request.extend_from_slice(&ip.octets());
+            }
+        }
+    }
+
+    request.extend_from_slice(&port.to_be_bytes());
+    stream.write_all(&request)?;
+
+    let mut reply = [0; 4];
+    stream.read_exact(&mut reply)?;
+    anyhow::ensure!(
+        reply[1] == 0,
+        "Socks5 proxy connect failed with code {}",
+        reply[1]
+    );
+
+    // 读取并丢弃代理绑定的地址和端口
+    let addr_len = match reply[3] {
+        1 => 4,
+        4 => 16,
+        3 => {
+            let mut len = [0; 1];
+            stream.read_exact(&mut len)?;
+            len[0] as usize
+        }
+        atyp => anyhow::bail!("Invalid socks5 address type: {}", atyp),
+    };
+    let mut bound = vec![0; addr_len + 2];
+    stream.read_exact(&mut bound)?;
+
+    Ok(stream)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{net::TcpListener, thread};
+
+    #[test]
+    fn test_connect_tunnel() -> Result<()> {
+        // http 代理：检查 CONNECT 请求和认证头
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let proxy = format!("http://user:pass@{}", listener.local_addr()?);
+
+        let server = thread::spawn(move || -> Result<String> {
+            let (mut stream, _) = listener.accept()?;
+            let mut buf = [0; 1024];
+            let n = stream.read(&mut buf)?;
+            stream.write_all(b"HTTP/1.1 200 Connection established\r\n\r\n")?;
+            Ok(String::from_utf8_lossy(&buf[..n]).to_string())
+        });
+
+        connect_tunnel(&proxy, "stream.binance.com", 9443)?;
+
+        let request = server.join().unwrap()?;
+        assert!(request.starts_with("CONNECT stream.binance.com:9443 HTTP/1.1\r\n"));
+        assert!(request.contains("Proxy-Authorization: Basic dXNlcjpwYXNz\r\n"));
+
+        // socks5h 代理：由代理解析域名
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let proxy = format!("socks5h://{}", listener.local_addr()?);
+
+        let server = thread::spawn(move || -> Result<Vec<u8>> {
+            let (mut stream, _) = listener.accept()?;
+            let mut greeting = [0; 3];
+            stream.read_exact(&mut greeting)?;
+            stream.write_all(&[5, 0])?;
+
+            let mut request = [0; 5 + 18 + 2];
+            stream.read_exact(&mut request)?;
+            stream.write_all(&[5, 0, 0, 1, 127, 0, 0, 1, 0, 80])?;
+            Ok(request.to_vec())
+        });
+
+        connect_tunnel(&proxy, "stream.binance.com", 9443)?;
+
+        let request = server.join().unwrap()?;
+        assert_eq!(&request[..5], &[5, 1, 0, 3, 18]);
+        assert_eq!(&request[5..23], b"stream.binance.com");
+        assert_eq!(&request[23..], &9443_u16.to_be_bytes());
+
+        assert!(connect_tunnel("https://127.0.0.1:8443", "stream.binance.com", 9443).is_err());
+
+        Ok(())
+    }
+}
@@ -1,5 +1,5 @@
 use super::utils::calc_time_range_group;
-use crate::exchange::binance::BinanceClient;
+use crate::exchange::{binance::BinanceClient, ConnectionOptions};
 use anyhow::Result;
 use async_stream::stream;
 use binance::{
@@ -8,7 +8,10 @@ use binance::{
 };
 use bon::bon;
 use comfy_quant_base::{KlineInterval, Market, Symbol};
-use futures::stream::BoxStream;
+use futures::{
+    stream::{self, BoxStream},
+    StreamExt,
+};
 use std::sync::Arc;
 use tokio_util::sync::CancellationToken;
 
@@ -17,21 +20,35 @@ const KLINE_LIMIT: u16 = 1000;
 #[derive(Debug)]
 pub struct BinanceKline {
     client: Arc<BinanceClient>,
+    concurrency: usize, // 并行下载的分段数量
     token: CancellationToken,
 }
 
 #[bon]
 impl BinanceKline {
     #[builder]
-    pub fn new(config: Option<Config>) -> Self {
+    pub fn new(
+        config: Option<Config>,
+        connection: Option<ConnectionOptions>,
+        #[builder(default = 1)] concurrency: usize,
+    ) -> Result<Self> {
         // 访问公共接口，不需要api_key和secret_key
-        let client = Arc::new(BinanceClient::builder().maybe_config(config).build());
+        let client = Arc::new(
+            BinanceClient::builder()
+                .maybe_config(config)
+                .maybe_connection(connection)
+                .build()?,
+        );
         let token = CancellationToken::new();
 
-        BinanceKline { client, token }
+        Ok(BinanceKline {
+            client,
+            concurrency: concurrency.max(1),
+            token,
+        })
     }
 
-    // 获取K线流
+    // 获取K线流，时间范围按每次请求的数量分段，最多同时下载 concurrency 个分段，按时间顺序返回
     pub fn klines_stream(
         &self,
         market: &Market,          // 市场
@@ -39,86 +56,52 @@ impl BinanceKline {
         interval: &KlineInterval, // 时间间隔
         start_time: i64,          // 开始时间
         end_time: i64,            // 结束时间
-    ) -> BoxStream<Result<KlineSummary>> {
+    ) -> BoxStream<'static, Result<KlineSummary>> {
         let client = Arc::clone(&self.client);
-        let (tx, rx) = flume::bounded(1);
-        let (error_tx, error_rx) = flume::bounded(1);
-        let semaphore = Arc::new(async_lock::Semaphore::new(1));
+        let token = self.token.clone();
+        let market = market.clone();
+        let symbol = symbol.clone();
+        let interval = interval.clone();
         let time_range_groups =
             calc_time_range_group(interval.as_ref(), start_time, end_time, KLINE_LIMIT);
-        let cloned_token1 = self.token.clone();
-        let cloned_token2 = self.token.clone();
-
-        // 使用 tokio::spawn 会有问题，所以使用 tokio::task::spawn_blocking
-        // 报错信息: Cannot drop a runtime in a context where blocking is not allowed. This happens when a runtime is dropped from within an asynchronous context.
-        // 原因: reqwest 的 runtime 在异步上下文中被释放了
-        tokio::task::spawn_blocking({
-            let market = market.clone();
-            let symbol = symbol.clone();
-            let interval = interval.clone();
-
-            move || {
-                let result = (move || {
-                    for (start_time, end_time) in time_range_groups {
-                        if cloned_token1.is_cancelled() {
-                            return Ok(());
-                        }
 
-                        let KlineSummaries::AllKlineSummaries(klines) = match market {
-                            Market::Spot => client.spot().get_klines(
-                                &symbol,
-                                &interval,
-                                KLINE_LIMIT,
-                                start_time as u64,
-                                end_time as u64,
-                            )?,
-                            Market::Usdm | Market::Coinm | Market::Vanilla => {
-                                client.futures().get_klines(
-                                    &symbol,
-                                    &interval,
-                                    KLINE_LIMIT,
-                                    start_time as u64,
-                                    end_time as u64,
-                                )?
-                            }
-                        };
-
-                        for kline in klines {
-                            if cloned_token1.is_cancelled() {
-                                return Ok(());
-                            }
-
-                            let guard = semaphore.acquire_arc_blocking();
-                            tx.send((kline, guard))?;
-                        }
+        let mut chunks = stream::iter(time_range_groups)
+            .map(move |(start_time, end_time)| {
+                let client = Arc::clone(&client);
+                let token = token.clone();
+                let market = market.clone();
+                let symbol = symbol.clone();
+                let interval = interval.clone();
+
+                // 请求权重由客户端的限流器统一控制
+                async move {
+                    if token.is_cancelled() {
+                        return Ok(Vec::new());
                     }
 
-                    Ok(())
-                })();
-
-                if let Err(e) = result {
-                    error_tx.send(e)?;
+                    fetch_klines(&client, &market, &symbol, &interval, start_time, end_time).await
                 }
-
-                Ok::<(), anyhow::Error>(())
-            }
-        });
+            })
+            .buffered(self.concurrency);
+        let token = self.token.clone();
 
         let kline_stream = stream! {
-            loop {
-                if cloned_token2.is_cancelled() {
+            while let Some(result) = chunks.next().await {
+                if token.is_cancelled() {
                     break;
                 }
 
-                tokio::select! {
-                    Ok((kline, _guard)) = rx.recv_async() => {
-                        yield Ok(kline);
+                match result {
+                    Ok(klines) => {
+                        for kline in klines {
+                            yield Ok(kline);
+                        }
                     }
-                    Ok(err) = error_rx.recv_async() => {
-                        yield Err(err);
+                    // 出错后停止，避免跳过失败的分段
+                    Err(e) => {
+                        yield Err(e);
                         break;
                     }
-                    else => break,
                 }
             }
         };
@@ -127,9 +110,50 @@ impl BinanceKline {
     }
 }
 
+async fn fetch_klines(
+    client: &BinanceClient,
+    market: &Market,
+    symbol: &Symbol,
+    interval: &KlineInterval,
+    start_time: i64,
+    end_time: i64,
+) -> Result<Vec<KlineSummary>> {
+    let KlineSummaries::AllKlineSummaries(klines) = match market {
+        Market::Spot => {
+            client
+                .spot()
+                .get_klines(
+                    symbol,
+                    interval,
+                    KLINE_LIMIT,
+                    start_time as u64,
+                    end_time as u64,
+                )
+                .await?
+        }
+        Market::Usdm | Market::Coinm | Market::Vanilla => {
+            client
+                .futures()
+                .get_klines(
+                    symbol,
+                    interval,
+                    KLINE_LIMIT,
+                    start_time as u64,
+                    end_time as u64,
+                )
+                .await?
+        }
+    };
+
+    Ok(klines)
+}
+
 impl Default for BinanceKline {
+    // 不设置代理时创建 HTTP 客户端与 reqwest::Client::new() 一样，只在 TLS 初始化失败时出错
     fn default() -> Self {
-        BinanceKline::builder().build()
+        BinanceKline::builder()
+            .build()
+            .expect("create binance kline client")
     }
 }
 
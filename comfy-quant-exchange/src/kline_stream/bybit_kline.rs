@@ -0,0 +1,72 @@
+use super::utils::calc_time_range_group;
+use crate::exchange::{
+    bybit::{BybitClient, BybitKline as BybitKlineSummary},
+    ConnectionOptions,
+};
+use anyhow::{anyhow, Result};
+use async_stream::try_stream;
+use bon::bon;
+use comfy_quant_base::{KlineInterval, Market, Symbol};
+use futures::stream::BoxStream;
+
+const KLINE_LIMIT: u16 = 1000;
+
+// Bybit 现货K线
+#[derive(Debug, Clone)]
+pub struct BybitKline {
+    client: BybitClient,
+}
+
+#[bon]
+impl BybitKline {
+    #[builder]
+    pub fn new(connection: Option<ConnectionOptions>) -> Result<Self> {
+        let client = BybitClient::builder()
+            .maybe_connection(connection)
+            .build()?;
+
+        Ok(BybitKline { client })
+    }
+
+    // 获取K线流，目前只支持现货
+    pub fn klines_stream(
+        &self,
+        market: &Market,          // 市场
+        symbol: &Symbol,          // 交易对
+        interval: &KlineInterval, // 时间间隔
+        start_time: i64,          // 开始时间
+        end_time: i64,            // 结束时间
+    ) -> BoxStream<'static, Result<BybitKlineSummary>> {
+        let client = self.client.clone();
+        let market = market.clone();
+        let symbol = symbol.clone();
+        let interval = interval.clone();
+        let time_range_groups =
+            calc_time_range_group(interval.as_ref(), start_time, end_time, KLINE_LIMIT);
+
+        let kline_stream = try_stream! {
+            if market != Market::Spot {
+                Err::<(), _>(anyhow!("Bybit klines only support spot market: {}", market))?;
+            }
+
+            for (start_time, end_time) in time_range_groups {
+                let klines = client
+                    .get_klines(symbol.as_ref(), interval.as_ref(), start_time, end_time, KLINE_LIMIT)
+                    .await?;
+
+                for kline in klines {
+                    yield kline;
+                }
+            }
+        };
+
+        Box::pin(kline_stream)
+    }
+}
+
+impl Default for BybitKline {
+    // 不设置代理时创建 HTTP 客户端与 reqwest::Client::new() 一样，只在 TLS 初始化失败时出错
+    fn default() -> Self {
+        BybitKline::builder().build().expect("create bybit kline")
+    }
+}
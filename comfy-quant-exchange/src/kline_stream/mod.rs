@@ -1,5 +1,8 @@
 mod binance_kline;
+mod bybit_kline;
 mod utils;
 
 pub use binance_kline::BinanceKline;
+pub use bybit_kline::BybitKline;
+pub(crate) use utils::calc_time_range_group;
 pub use utils::calc_time_range_kline_count;
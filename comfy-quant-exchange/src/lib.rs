@@ -1,6 +1,8 @@
+pub mod announcement;
 pub mod client;
 pub mod exchange;
 pub mod kline_stream;
+pub mod market_data;
 pub mod store;
 
 // pub use exchange::binance::BinanceClient;
@@ -0,0 +1,127 @@
+use super::{MarketDataConnector, MarketKline, MarketTick};
+use crate::{
+    client::spot_client::base::SymbolInformation,
+    exchange::{binance::BinanceClient, ConnectionOptions},
+    kline_stream::BinanceKline,
+};
+use anyhow::Result;
+use async_stream::try_stream;
+use binance::{config::Config, model::KlineSummary, websockets::WebsocketEvent};
+use bon::bon;
+use comfy_quant_base::{Exchange, KlineInterval, Market, Symbol};
+use futures::{stream::BoxStream, StreamExt};
+
+#[derive(Debug)]
+pub struct BinanceConnector {
+    client: BinanceClient,
+    kline: BinanceKline, // 释放时取消未完成的K线下载
+}
+
+#[bon]
+impl BinanceConnector {
+    #[builder]
+    pub fn new(
+        config: Option<Config>,
+        connection: Option<ConnectionOptions>,
+        #[builder(default = 1)] concurrency: usize, // K线分段并行下载的数量
+    ) -> Result<Self> {
+        // 访问公共接口，不需要api_key和secret_key
+        let client = BinanceClient::builder()
+            .maybe_config(config.clone())
+            .maybe_connection(connection.clone())
+            .build()?;
+        let kline = BinanceKline::builder()
+            .maybe_config(config)
+            .maybe_connection(connection)
+            .concurrency(concurrency)
+            .build()?;
+
+        Ok(BinanceConnector { client, kline })
+    }
+}
+
+impl Default for BinanceConnector {
+    // 不设置代理时创建 HTTP 客户端与 reqwest::Client::new() 一样，只在 TLS 初始化失败时出错
+    fn default() -> Self {
+        BinanceConnector::builder()
+            .build()
+            .expect("create binance connector")
+    }
+}
+
+impl MarketDataConnector for BinanceConnector {
+    fn exchange(&self) -> Exchange {
+        Exchange::Binance
+    }
+
+    fn fetch_klines(
+        &self,
+        market: &Market,
+        symbol: &Symbol,
+        interval: &KlineInterval,
+        start_time: i64,
+        end_time: i64,
+    ) -> BoxStream<'static, Result<MarketKline>> {
+        self.kline
+            .klines_stream(market, symbol, interval, start_time, end_time)
+            .map(|kline| kline.and_then(market_kline))
+            .boxed()
+    }
+
+    // 订阅币安1秒K线，每根K线收盘后生成一个tick，与回测时由1秒K线生成的tick一致
+    fn stream_ticks(&self, symbol: &Symbol) -> BoxStream<'static, Result<MarketTick>> {
+        let client = self.client.clone();
+        let symbol = symbol.clone();
+        let topic = format!("{}@kline_1s", symbol.as_ref().to_lowercase());
+
+        let stream = try_stream! {
+            let websocket = client.spot_websocket(topic);
+            let mut events = websocket.subscribe().await?;
+
+            while let Some(event) = events.next().await {
+                let WebsocketEvent::Kline(event) = event else {
+                    continue;
+                };
+
+                // 未收盘的K线会持续推送更新，只使用收盘的K线
+                if !event.kline.is_final_bar {
+                    continue;
+                }
+
+                yield MarketTick::builder()
+                    .timestamp(event.kline.open_time / 1000)
+                    .millis(event.kline.open_time)
+                    .symbol(symbol.clone())
+                    .price(event.kline.close.parse()?)
+                    .volume(event.kline.volume.parse()?)
+                    .taker_buy_volume(event.kline.taker_buy_base_asset_volume.parse()?)
+                    .build();
+            }
+        };
+
+        Box::pin(stream)
+    }
+
+    async fn fetch_exchange_info(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+    ) -> Result<SymbolInformation> {
+        let symbol = self.exchange().symbol(base_asset, quote_asset);
+        let symbol_info = self.client.spot().get_symbol_info(symbol).await?;
+        Ok(symbol_info.into())
+    }
+}
+
+fn market_kline(kline: KlineSummary) -> Result<MarketKline> {
+    Ok(MarketKline::builder()
+        .open_time(kline.open_time)
+        .close_time(kline.close_time)
+        .open(kline.open.parse()?)
+        .high(kline.high.parse()?)
+        .low(kline.low.parse()?)
+        .close(kline.close.parse()?)
+        .volume(kline.volume.parse()?)
+        .taker_buy_volume(kline.taker_buy_base_asset_volume.parse()?)
+        .build())
+}
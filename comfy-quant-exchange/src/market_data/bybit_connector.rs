@@ -0,0 +1,169 @@
+use super::{MarketDataConnector, MarketKline, MarketTick};
+use crate::{
+    client::spot_client::base::SymbolInformation,
+    exchange::{
+        bybit::{bybit_interval, BybitClient, BybitKline as BybitKlineSummary},
+        ConnectionOptions,
+    },
+    kline_stream::BybitKline,
+};
+use anyhow::Result;
+use async_stream::try_stream;
+use bon::bon;
+use comfy_quant_base::{Exchange, KlineInterval, Market, Symbol};
+use futures::{stream::BoxStream, StreamExt};
+use std::time::Duration;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1); // 轮询行情的间隔
+
+#[derive(Debug, Clone)]
+pub struct BybitConnector {
+    client: BybitClient,
+    kline: BybitKline,
+}
+
+#[bon]
+impl BybitConnector {
+    #[builder]
+    pub fn new(connection: Option<ConnectionOptions>) -> Result<Self> {
+        let client = BybitClient::builder()
+            .maybe_connection(connection.clone())
+            .build()?;
+        let kline = BybitKline::builder().maybe_connection(connection).build()?;
+
+        Ok(BybitConnector { client, kline })
+    }
+}
+
+impl Default for BybitConnector {
+    // 不设置代理时创建 HTTP 客户端与 reqwest::Client::new() 一样，只在 TLS 初始化失败时出错
+    fn default() -> Self {
+        BybitConnector::builder()
+            .build()
+            .expect("create bybit connector")
+    }
+}
+
+impl MarketDataConnector for BybitConnector {
+    fn exchange(&self) -> Exchange {
+        Exchange::Bybit
+    }
+
+    // Bybit 不支持秒级K线
+    fn check_interval(&self, interval: &KlineInterval) -> Result<()> {
+        bybit_interval(interval.as_ref()).map(|_| ())
+    }
+
+    // Bybit K线没有收盘时间和主动买入成交量，收盘时间按周期计算
+    fn fetch_klines(
+        &self,
+        market: &Market,
+        symbol: &Symbol,
+        interval: &KlineInterval,
+        start_time: i64,
+        end_time: i64,
+    ) -> BoxStream<'static, Result<MarketKline>> {
+        let interval_millis = interval.to_millis();
+
+        self.kline
+            .klines_stream(market, symbol, interval, start_time, end_time)
+            .map(move |kline| kline.and_then(|kline| market_kline(kline, interval_millis)))
+            .boxed()
+    }
+
+    // Bybit 现货没有秒级K线，每秒轮询最新成交价生成tick，成交量为0
+    fn stream_ticks(&self, symbol: &Symbol) -> BoxStream<'static, Result<MarketTick>> {
+        let client = self.client.clone();
+        let symbol = symbol.clone();
+
+        let stream = try_stream! {
+            let mut interval = tokio::time::interval(POLL_INTERVAL);
+            let mut last_time = 0;
+
+            loop {
+                interval.tick().await;
+
+                let ticker = match client.get_ticker(symbol.as_ref()).await {
+                    Ok(ticker) => ticker,
+                    Err(e) => {
+                        tracing::warn!("Fetch bybit ticker failed: {}", e);
+                        continue;
+                    }
+                };
+
+                // 同一秒内的新成交也推送，按毫秒时间戳去重
+                if ticker.time <= last_time {
+                    continue;
+                }
+
+                last_time = ticker.time;
+
+                yield MarketTick::builder()
+                    .timestamp(ticker.time / 1000)
+                    .millis(ticker.time)
+                    .symbol(symbol.clone())
+                    .price(ticker.last_price.parse()?)
+                    .build();
+            }
+        };
+
+        Box::pin(stream)
+    }
+
+    async fn fetch_exchange_info(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+    ) -> Result<SymbolInformation> {
+        let symbol = self.exchange().symbol(base_asset, quote_asset);
+        self.client
+            .get_instrument(symbol.as_ref())
+            .await?
+            .try_into()
+    }
+}
+
+fn market_kline(kline: BybitKlineSummary, interval_millis: i64) -> Result<MarketKline> {
+    Ok(MarketKline::builder()
+        .open_time(kline.open_time)
+        .close_time(kline.open_time + interval_millis - 1)
+        .open(kline.open.parse()?)
+        .high(kline.high.parse()?)
+        .low(kline.low.parse()?)
+        .close(kline.close.parse()?)
+        .volume(kline.volume.parse()?)
+        .build())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bybit_market_kline() -> Result<()> {
+        let kline = BybitKlineSummary::try_from(
+            [
+                "1670608800000",
+                "17071",
+                "17073",
+                "17027",
+                "17055.5",
+                "268611",
+                "15.7",
+            ]
+            .map(String::from)
+            .to_vec(),
+        )?;
+        let kline = market_kline(kline, KlineInterval::OneMinute.to_millis())?;
+
+        assert_eq!(kline.close_time, 1670608859999);
+        assert_eq!(kline.close.to_string(), "17055.5");
+        assert!(kline.taker_buy_volume.is_zero());
+
+        assert!(BybitConnector::default()
+            .check_interval(&KlineInterval::OneSecond)
+            .is_err());
+
+        Ok(())
+    }
+}
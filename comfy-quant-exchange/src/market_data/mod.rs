@@ -0,0 +1,72 @@
+mod binance_connector;
+mod bybit_connector;
+mod okx_connector;
+
+pub use binance_connector::BinanceConnector;
+pub use bybit_connector::BybitConnector;
+pub use okx_connector::OkxConnector;
+
+use crate::client::spot_client::base::SymbolInformation;
+use anyhow::Result;
+use bon::Builder;
+use comfy_quant_base::{Exchange, KlineInterval, Market, Symbol};
+use futures::stream::BoxStream;
+use rust_decimal::Decimal;
+
+// 交易所K线，价格和数量已转换为 Decimal
+#[derive(Builder, Debug, Clone, PartialEq)]
+pub struct MarketKline {
+    pub open_time: i64,  // 开盘时间(毫秒)
+    pub close_time: i64, // 收盘时间(毫秒)
+    pub open: Decimal,
+    pub high: Decimal,
+    pub low: Decimal,
+    pub close: Decimal,
+    pub volume: Decimal, // 交易货币的成交量
+    #[builder(default)]
+    pub taker_buy_volume: Decimal, // 主动买入成交量，交易所不提供时为0
+}
+
+// 实时行情，每根1秒K线(或轮询周期)收盘后生成一个
+#[derive(Builder, Debug, Clone, PartialEq)]
+pub struct MarketTick {
+    pub timestamp: i64, // 时间(秒)
+    pub symbol: Symbol,
+    pub price: Decimal,
+    #[builder(default)]
+    pub volume: Decimal, // 周期内成交量，交易所不提供时为0
+    #[builder(default)]
+    pub taker_buy_volume: Decimal, // 周期内主动买入成交量，交易所不提供时为0
+    pub millis: Option<i64>, // 毫秒时间戳，同一秒内的tick按此排序
+}
+
+// 行情数据接入，新增交易所只需实现该 trait，K线同步任务和行情节点按交易所复用
+#[allow(async_fn_in_trait)]
+pub trait MarketDataConnector: Send + Sync + 'static {
+    fn exchange(&self) -> Exchange;
+
+    // 检查交易所是否支持该K线周期
+    fn check_interval(&self, _interval: &KlineInterval) -> Result<()> {
+        Ok(())
+    }
+
+    // 按时间范围获取K线(秒)，按开盘时间正序返回
+    fn fetch_klines(
+        &self,
+        market: &Market,
+        symbol: &Symbol,
+        interval: &KlineInterval,
+        start_time: i64,
+        end_time: i64,
+    ) -> BoxStream<'static, Result<MarketKline>>;
+
+    // 订阅现货实时行情，连接断开时流结束
+    fn stream_ticks(&self, symbol: &Symbol) -> BoxStream<'static, Result<MarketTick>>;
+
+    // 获取交易对信息，用于刷新交易对缓存
+    async fn fetch_exchange_info(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+    ) -> Result<SymbolInformation>;
+}
@@ -0,0 +1,164 @@
+use super::{MarketDataConnector, MarketKline, MarketTick};
+use crate::{
+    client::spot_client::base::SymbolInformation,
+    exchange::{
+        okx::{okx_bar, OkxCandle, OkxClient},
+        ConnectionOptions,
+    },
+    kline_stream::calc_time_range_group,
+};
+use anyhow::{anyhow, Result};
+use async_stream::try_stream;
+use bon::bon;
+use comfy_quant_base::{Exchange, KlineInterval, Market, Symbol};
+use futures::stream::BoxStream;
+use std::time::Duration;
+
+const KLINE_LIMIT: u16 = 100; // 历史K线接口每次最多返回的数量
+const POLL_INTERVAL: Duration = Duration::from_secs(1); // 轮询K线的间隔
+const CANDLE_LIMIT: u32 = 5; // 每次轮询拉取的K线数量，覆盖轮询延迟期间收盘的K线
+
+#[derive(Debug, Clone)]
+pub struct OkxConnector {
+    client: OkxClient,
+}
+
+#[bon]
+impl OkxConnector {
+    #[builder]
+    pub fn new(connection: Option<ConnectionOptions>) -> Result<Self> {
+        // 访问公共接口，不需要api_key、secret_key和passphrase
+        let client = OkxClient::builder().maybe_connection(connection).build()?;
+
+        Ok(OkxConnector { client })
+    }
+}
+
+impl Default for OkxConnector {
+    // 不设置代理时创建 HTTP 客户端与 reqwest::Client::new() 一样，只在 TLS 初始化失败时出错
+    fn default() -> Self {
+        OkxConnector::builder()
+            .build()
+            .expect("create okx connector")
+    }
+}
+
+impl MarketDataConnector for OkxConnector {
+    fn exchange(&self) -> Exchange {
+        Exchange::Okx
+    }
+
+    fn check_interval(&self, interval: &KlineInterval) -> Result<()> {
+        okx_bar(interval.as_ref()).map(|_| ())
+    }
+
+    fn fetch_klines(
+        &self,
+        market: &Market,
+        symbol: &Symbol,
+        interval: &KlineInterval,
+        start_time: i64,
+        end_time: i64,
+    ) -> BoxStream<'static, Result<MarketKline>> {
+        let client = self.client.clone();
+        let market = market.clone();
+        let symbol = symbol.clone();
+        let interval_millis = interval.to_millis();
+        let bar = okx_bar(interval.as_ref());
+        let time_range_groups =
+            calc_time_range_group(interval.as_ref(), start_time, end_time, KLINE_LIMIT);
+
+        let stream = try_stream! {
+            if market != Market::Spot {
+                Err::<(), _>(anyhow!("OKX klines only support spot market: {}", market))?;
+            }
+
+            let bar = bar?;
+
+            for (start_time, end_time) in time_range_groups {
+                let candles = client
+                    .get_history_candles(
+                        symbol.as_ref(),
+                        bar,
+                        start_time,
+                        end_time,
+                        KLINE_LIMIT as u32,
+                    )
+                    .await?;
+
+                for candle in candles {
+                    yield market_kline(candle, interval_millis)?;
+                }
+            }
+        };
+
+        Box::pin(stream)
+    }
+
+    // 轮询OKX 1秒K线，每根K线收盘后生成一个tick，与回测时由1秒K线生成的tick一致
+    fn stream_ticks(&self, symbol: &Symbol) -> BoxStream<'static, Result<MarketTick>> {
+        let client = self.client.clone();
+        let symbol = symbol.clone();
+
+        let stream = try_stream! {
+            let mut interval = tokio::time::interval(POLL_INTERVAL);
+            let mut last_ts = 0;
+
+            loop {
+                interval.tick().await;
+
+                let candles = match client.get_candles(symbol.as_ref(), "1s", CANDLE_LIMIT).await {
+                    Ok(candles) => candles,
+                    Err(e) => {
+                        tracing::warn!("Fetch okx candles failed: {}", e);
+                        continue;
+                    }
+                };
+
+                // 接口按开盘时间倒序返回，只使用未推送过的收盘K线
+                for candle in candles.into_iter().rev() {
+                    if !candle.confirm || candle.ts <= last_ts {
+                        continue;
+                    }
+
+                    last_ts = candle.ts;
+
+                    yield MarketTick::builder()
+                        .timestamp(candle.ts / 1000)
+                        .millis(candle.ts)
+                        .symbol(symbol.clone())
+                        .price(candle.close.parse()?)
+                        .volume(candle.volume.parse()?)
+                        .build();
+                }
+            }
+        };
+
+        Box::pin(stream)
+    }
+
+    async fn fetch_exchange_info(
+        &self,
+        base_asset: &str,
+        quote_asset: &str,
+    ) -> Result<SymbolInformation> {
+        let symbol = self.exchange().symbol(base_asset, quote_asset);
+        self.client
+            .get_instrument(symbol.as_ref())
+            .await?
+            .try_into()
+    }
+}
+
+// OKX K线没有收盘时间和主动买入成交量，收盘时间按周期计算
+fn market_kline(candle: OkxCandle, interval_millis: i64) -> Result<MarketKline> {
+    Ok(MarketKline::builder()
+        .open_time(candle.ts)
+        .close_time(candle.ts + interval_millis - 1)
+        .open(candle.open.parse()?)
+        .high(candle.high.parse()?)
+        .low(candle.low.parse()?)
+        .close(candle.close.parse()?)
+        .volume(candle.volume.parse()?)
+        .build())
+}
@@ -1,3 +1,3 @@
 mod price_store;
 
-pub use price_store::PriceStore;
+pub use price_store::{PriceSnapshot, PriceStore};
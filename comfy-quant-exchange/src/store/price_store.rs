@@ -7,9 +7,31 @@ use std::collections::HashMap;
 
 type PriceStoreMap = HashMap<ExchangeMarketSymbolKey, Decimal>;
 
+// 同一时刻多个交易对的价格，用于配对交易、组合策略按一致的价格计算
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PriceSnapshot {
+    pub timestamp: Option<i64>,            // 价格的时间(秒)
+    pub prices: HashMap<Symbol, Decimal>,  // 交易对价格
+    pub volumes: HashMap<Symbol, Decimal>, // 交易对最新周期的成交量
+}
+
+impl PriceSnapshot {
+    pub fn price(&self, symbol: &Symbol) -> Option<Decimal> {
+        self.prices.get(symbol).cloned()
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Default)]
 pub struct PriceStore {
     inner: PriceStoreMap,
+    #[serde(default)]
+    timestamp: Option<i64>, // 最新价格的时间(秒)，回测中作为模拟时间
+    #[serde(default)]
+    volumes: PriceStoreMap, // 最新周期的成交量，回测中用于估算挂单排队
+    #[serde(default)]
+    ticks: u64, // 已保存的tick数，回测中用于模拟下单延迟
+    #[serde(default)]
+    volume_seqs: HashMap<ExchangeMarketSymbolKey, u64>, // 每个交易对保存成交量的次数，回测中每个周期的成交量只消耗一次
 }
 
 impl AsRef<PriceStoreMap> for PriceStore {
@@ -28,6 +50,10 @@ impl PriceStore {
     pub fn new() -> Self {
         PriceStore {
             inner: HashMap::new(),
+            timestamp: None,
+            volumes: HashMap::new(),
+            ticks: 0,
+            volume_seqs: HashMap::new(),
         }
     }
 
@@ -47,6 +73,90 @@ impl PriceStore {
 
         Ok(())
     }
+
+    // 批量保存价格，如同一周期多个交易对的行情
+    pub fn save_prices(
+        &mut self,
+        exchange: &Exchange,
+        market: &Market,
+        symbol_prices: &[SymbolPrice],
+    ) -> Result<()> {
+        for symbol_price in symbol_prices {
+            self.save_price(exchange, market, symbol_price)?;
+        }
+
+        Ok(())
+    }
+
+    // 多个交易对的价格，任一交易对缺少价格时返回None
+    pub fn prices(
+        &self,
+        exchange: &Exchange,
+        market: &Market,
+        symbols: &[Symbol],
+    ) -> Option<HashMap<Symbol, Decimal>> {
+        symbols
+            .iter()
+            .map(|symbol| Some((symbol.clone(), self.price(exchange, market, symbol)?)))
+            .collect()
+    }
+
+    // 交易所市场下所有交易对的价格和成交量快照
+    pub fn snapshot(&self, exchange: &Exchange, market: &Market) -> PriceSnapshot {
+        let select = |map: &PriceStoreMap| {
+            map.iter()
+                .filter(|(key, _)| &key.exchange == exchange && &key.market == market)
+                .map(|(key, value)| (key.symbol.clone(), *value))
+                .collect()
+        };
+
+        PriceSnapshot {
+            timestamp: self.timestamp,
+            prices: select(&self.inner),
+            volumes: select(&self.volumes),
+        }
+    }
+
+    pub fn volume(&self, exchange: &Exchange, market: &Market, symbol: &Symbol) -> Option<Decimal> {
+        let key = ExchangeMarketSymbolKey::try_new(exchange, market, symbol).ok()?;
+        self.volumes.get(&key).cloned()
+    }
+
+    pub fn save_volume(
+        &mut self,
+        exchange: &Exchange,
+        market: &Market,
+        symbol: &Symbol,
+        volume: Decimal,
+    ) -> Result<()> {
+        let key = ExchangeMarketSymbolKey::try_new(exchange, market, symbol)?;
+        *self.volume_seqs.entry(key.clone()).or_default() += 1;
+        self.volumes.insert(key, volume);
+
+        Ok(())
+    }
+
+    // 交易对最新成交量的序号，每保存一次成交量加1，没有成交量数据时为0
+    pub fn volume_seq(&self, exchange: &Exchange, market: &Market, symbol: &Symbol) -> u64 {
+        ExchangeMarketSymbolKey::try_new(exchange, market, symbol)
+            .ok()
+            .and_then(|key| self.volume_seqs.get(&key).copied())
+            .unwrap_or_default()
+    }
+
+    pub fn timestamp(&self) -> Option<i64> {
+        self.timestamp
+    }
+
+    pub fn ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    // 每个tick保存一次时间
+    pub fn save_timestamp(&mut self, timestamp: i64) {
+        self.timestamp = Some(timestamp);
+        self.ticks += 1;
+    }
 }
 
 #[cfg(test)]
@@ -69,5 +179,62 @@ mod tests {
 
         store.save_price(&exchange, &market, &price).unwrap();
         assert_eq!(store.price(&exchange, &market, &symbol), Some(dec!(90000)));
+
+        assert_eq!(store.volume(&exchange, &market, &symbol), None);
+        store
+            .save_volume(&exchange, &market, &symbol, dec!(12.5))
+            .unwrap();
+        assert_eq!(store.volume(&exchange, &market, &symbol), Some(dec!(12.5)));
+        assert_eq!(store.volume_seq(&exchange, &market, &symbol), 1);
+        assert_eq!(store.volume_seq(&exchange, &market, &"ETHUSDT".into()), 0);
+    }
+
+    #[test]
+    fn test_price_snapshot() -> Result<()> {
+        let exchange = Exchange::Binance;
+        let market = Market::Spot;
+        let btc: Symbol = "BTCUSDT".into();
+        let eth: Symbol = "ETHUSDT".into();
+        let mut store = PriceStore::new();
+
+        store.save_prices(
+            &exchange,
+            &market,
+            &[
+                SymbolPrice::builder()
+                    .symbol(btc.clone())
+                    .price(dec!(90000))
+                    .build(),
+                SymbolPrice::builder()
+                    .symbol(eth.clone())
+                    .price(dec!(3000))
+                    .build(),
+            ],
+        )?;
+        store.save_price(
+            &exchange,
+            &Market::Usdm,
+            &SymbolPrice::builder()
+                .symbol(btc.clone())
+                .price(dec!(90100))
+                .build(),
+        )?;
+        store.save_timestamp(60);
+
+        let prices = store
+            .prices(&exchange, &market, &[btc.clone(), eth.clone()])
+            .unwrap_or_default();
+        assert_eq!(prices[&eth], dec!(3000));
+        assert!(store
+            .prices(&exchange, &market, &[btc.clone(), "SOLUSDT".into()])
+            .is_none());
+
+        // 只包含同一交易所市场的交易对
+        let snapshot = store.snapshot(&exchange, &market);
+        assert_eq!(snapshot.timestamp, Some(60));
+        assert_eq!(snapshot.prices.len(), 2);
+        assert_eq!(snapshot.price(&btc), Some(dec!(90000)));
+
+        Ok(())
     }
 }
@@ -0,0 +1,112 @@
+use crate::Indicator;
+use anyhow::Result;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+// 计算真实波幅需要的K线价格
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct Ohlc {
+    pub open: Decimal,
+    pub high: Decimal,
+    pub low: Decimal,
+    pub close: Decimal,
+}
+
+// 平均真实波幅，Wilder平滑，前period个真实波幅的简单平均作为初始值
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Atr {
+    period: usize,
+    prev_close: Option<Decimal>, // 上一根K线的收盘价
+    count: usize,                // 已计入的真实波幅数量，达到period后不再增加
+    sum: Decimal,                // 初始值计算前的真实波幅之和
+    value: Option<Decimal>,      // 当前指标值
+}
+
+impl Atr {
+    pub fn new(period: usize) -> Result<Self> {
+        anyhow::ensure!(period > 0, "ATR period must be greater than 0");
+
+        Ok(Atr {
+            period,
+            prev_close: None,
+            count: 0,
+            sum: Decimal::ZERO,
+            value: None,
+        })
+    }
+
+    pub fn period(&self) -> usize {
+        self.period
+    }
+}
+
+impl Indicator for Atr {
+    type Input = Ohlc;
+    type Output = Decimal;
+
+    fn update(&mut self, input: Ohlc) -> Option<Decimal> {
+        // 第一根K线没有前收盘价，真实波幅为最高价减最低价
+        let true_range = match self.prev_close.replace(input.close) {
+            Some(prev_close) => (input.high - input.low)
+                .max((input.high - prev_close).abs())
+                .max((input.low - prev_close).abs()),
+            None => input.high - input.low,
+        };
+        let period = Decimal::from(self.period);
+
+        match self.value {
+            Some(value) => {
+                self.value = Some((value * (period - Decimal::ONE) + true_range) / period);
+            }
+            None => {
+                self.count += 1;
+                self.sum += true_range;
+
+                if self.count == self.period {
+                    self.value = Some(self.sum / period);
+                }
+            }
+        }
+
+        self.value
+    }
+
+    fn value(&self) -> Option<Decimal> {
+        self.value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn ohlc(high: Decimal, low: Decimal, close: Decimal) -> Ohlc {
+        Ohlc {
+            open: close,
+            high,
+            low,
+            close,
+        }
+    }
+
+    #[test]
+    fn test_atr() -> Result<()> {
+        assert!(Atr::new(0).is_err());
+
+        let mut atr = Atr::new(2)?;
+        assert_eq!(atr.update(ohlc(dec!(11), dec!(9), dec!(10))), None);
+        // 跳空高开，真实波幅为 15 - 10
+        assert_eq!(
+            atr.update(ohlc(dec!(15), dec!(13), dec!(14))),
+            Some(dec!(3.5))
+        );
+        // (3.5 * 1 + 2.5) / 2
+        assert_eq!(
+            atr.update(ohlc(dec!(14), dec!(11.5), dec!(12))),
+            Some(dec!(3))
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,112 @@
+use crate::Indicator;
+use anyhow::Result;
+use rust_decimal::{Decimal, MathematicalOps};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct BollingerOutput {
+    pub middle: Decimal, // 中轨，简单移动平均
+    pub upper: Decimal,  // 上轨
+    pub lower: Decimal,  // 下轨
+}
+
+// 布林带，上下轨为中轨加减multiplier倍总体标准差
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Bollinger {
+    period: usize,
+    multiplier: Decimal,       // 标准差倍数
+    window: VecDeque<Decimal>, // 最近period个输入
+    sum: Decimal,              // 窗口内输入之和
+    sum_sq: Decimal,           // 窗口内输入平方之和
+}
+
+impl Bollinger {
+    pub fn new(period: usize, multiplier: Decimal) -> Result<Self> {
+        anyhow::ensure!(period > 0, "Bollinger period must be greater than 0");
+        anyhow::ensure!(
+            multiplier > Decimal::ZERO,
+            "Bollinger multiplier must be greater than 0"
+        );
+
+        Ok(Bollinger {
+            period,
+            multiplier,
+            window: VecDeque::with_capacity(period + 1),
+            sum: Decimal::ZERO,
+            sum_sq: Decimal::ZERO,
+        })
+    }
+}
+
+impl Indicator for Bollinger {
+    type Input = Decimal;
+    type Output = BollingerOutput;
+
+    fn update(&mut self, input: Decimal) -> Option<BollingerOutput> {
+        self.window.push_back(input);
+        self.sum += input;
+        self.sum_sq += input * input;
+
+        if self.window.len() > self.period {
+            if let Some(first) = self.window.pop_front() {
+                self.sum -= first;
+                self.sum_sq -= first * first;
+            }
+        }
+
+        self.value()
+    }
+
+    fn value(&self) -> Option<BollingerOutput> {
+        if self.window.len() < self.period {
+            return None;
+        }
+
+        let n = Decimal::from(self.period);
+        let middle = self.sum / n;
+        // 累计误差可能使方差略小于0
+        let variance = (self.sum_sq / n - middle * middle).max(Decimal::ZERO);
+        let band = variance.sqrt()? * self.multiplier;
+
+        Some(BollingerOutput {
+            middle,
+            upper: middle + band,
+            lower: middle - band,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_bollinger() -> Result<()> {
+        assert!(Bollinger::new(0, dec!(2)).is_err());
+
+        let mut bollinger = Bollinger::new(2, dec!(2))?;
+        assert_eq!(bollinger.update(dec!(3)), None);
+        // 均值4，总体标准差1
+        assert_eq!(
+            bollinger.update(dec!(5)),
+            Some(BollingerOutput {
+                middle: dec!(4),
+                upper: dec!(6),
+                lower: dec!(2),
+            })
+        );
+        // 窗口为5, 7，均值6，总体标准差1
+        assert_eq!(
+            bollinger.update(dec!(7)),
+            Some(BollingerOutput {
+                middle: dec!(6),
+                upper: dec!(8),
+                lower: dec!(4),
+            })
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,83 @@
+use crate::Indicator;
+use anyhow::Result;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+// 指数移动平均，前period个输入的简单平均作为初始值
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Ema {
+    period: usize,
+    count: usize,           // 已输入数量，达到period后不再增加
+    sum: Decimal,           // 初始值计算前的输入之和
+    value: Option<Decimal>, // 当前指标值
+}
+
+impl Ema {
+    pub fn new(period: usize) -> Result<Self> {
+        anyhow::ensure!(period > 0, "EMA period must be greater than 0");
+
+        Ok(Ema {
+            period,
+            count: 0,
+            sum: Decimal::ZERO,
+            value: None,
+        })
+    }
+
+    pub fn period(&self) -> usize {
+        self.period
+    }
+
+    // 平滑系数 2 / (period + 1)
+    fn alpha(&self) -> Decimal {
+        Decimal::TWO / Decimal::from(self.period + 1)
+    }
+}
+
+impl Indicator for Ema {
+    type Input = Decimal;
+    type Output = Decimal;
+
+    fn update(&mut self, input: Decimal) -> Option<Decimal> {
+        match self.value {
+            Some(value) => {
+                self.value = Some(value + self.alpha() * (input - value));
+            }
+            None => {
+                self.count += 1;
+                self.sum += input;
+
+                if self.count == self.period {
+                    self.value = Some(self.sum / Decimal::from(self.period));
+                }
+            }
+        }
+
+        self.value
+    }
+
+    fn value(&self) -> Option<Decimal> {
+        self.value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_ema() -> Result<()> {
+        assert!(Ema::new(0).is_err());
+
+        let mut ema = Ema::new(3)?;
+        assert_eq!(ema.update(dec!(1)), None);
+        assert_eq!(ema.update(dec!(2)), None);
+        assert_eq!(ema.update(dec!(3)), Some(dec!(2)));
+        // 2 + 0.5 * (6 - 2)
+        assert_eq!(ema.update(dec!(6)), Some(dec!(4)));
+        assert!(ema.is_ready());
+
+        Ok(())
+    }
+}
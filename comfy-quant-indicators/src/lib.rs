@@ -0,0 +1,30 @@
+mod atr;
+mod bollinger;
+mod ema;
+mod macd;
+mod rsi;
+mod sma;
+
+pub use atr::{Atr, Ohlc};
+pub use bollinger::{Bollinger, BollingerOutput};
+pub use ema::Ema;
+pub use macd::{Macd, MacdOutput};
+pub use rsi::Rsi;
+pub use sma::Sma;
+
+// 流式指标，每次输入增量更新，状态可序列化，便于随运行时数据持久化
+pub trait Indicator {
+    type Input;
+    type Output;
+
+    // 输入一个数据，返回更新后的指标值，数据不足时返回None
+    fn update(&mut self, input: Self::Input) -> Option<Self::Output>;
+
+    // 当前指标值，数据不足时返回None
+    fn value(&self) -> Option<Self::Output>;
+
+    // 指标已可用
+    fn is_ready(&self) -> bool {
+        self.value().is_some()
+    }
+}
@@ -0,0 +1,90 @@
+use crate::{Ema, Indicator};
+use anyhow::Result;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct MacdOutput {
+    pub macd: Decimal,      // 快线EMA - 慢线EMA
+    pub signal: Decimal,    // MACD的EMA
+    pub histogram: Decimal, // MACD - 信号线
+}
+
+// 指数平滑异同移动平均
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Macd {
+    fast: Ema,
+    slow: Ema,
+    signal: Ema,
+    value: Option<MacdOutput>, // 当前指标值
+}
+
+impl Macd {
+    pub fn new(fast_period: usize, slow_period: usize, signal_period: usize) -> Result<Self> {
+        anyhow::ensure!(
+            fast_period < slow_period,
+            "MACD fast period must be less than slow period"
+        );
+
+        Ok(Macd {
+            fast: Ema::new(fast_period)?,
+            slow: Ema::new(slow_period)?,
+            signal: Ema::new(signal_period)?,
+            value: None,
+        })
+    }
+}
+
+impl Indicator for Macd {
+    type Input = Decimal;
+    type Output = MacdOutput;
+
+    fn update(&mut self, input: Decimal) -> Option<MacdOutput> {
+        let fast = self.fast.update(input);
+        let slow = self.slow.update(input);
+
+        // 慢线可用后才开始计算信号线
+        if let (Some(fast), Some(slow)) = (fast, slow) {
+            let macd = fast - slow;
+
+            if let Some(signal) = self.signal.update(macd) {
+                self.value = Some(MacdOutput {
+                    macd,
+                    signal,
+                    histogram: macd - signal,
+                });
+            }
+        }
+
+        self.value
+    }
+
+    fn value(&self) -> Option<MacdOutput> {
+        self.value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_macd() -> Result<()> {
+        assert!(Macd::new(26, 12, 9).is_err());
+
+        let mut macd = Macd::new(1, 2, 2)?;
+        assert_eq!(macd.update(dec!(1)), None);
+        // 快线2，慢线1.5，MACD 0.5，信号线数据不足
+        assert_eq!(macd.update(dec!(2)), None);
+        // 快线4，慢线(1.5 + 2 / 3 * 2.5)，MACD 0.8333...
+        let output = macd
+            .update(dec!(4))
+            .ok_or_else(|| anyhow::anyhow!("not ready"))?;
+        assert_eq!(output.macd.round_dp(4), dec!(0.8333));
+        assert_eq!(output.signal.round_dp(4), dec!(0.6667));
+        assert_eq!(output.histogram.round_dp(4), dec!(0.1667));
+
+        Ok(())
+    }
+}
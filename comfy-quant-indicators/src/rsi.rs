@@ -0,0 +1,107 @@
+use crate::Indicator;
+use anyhow::Result;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+// 相对强弱指数，Wilder平滑，前period个涨跌幅的简单平均作为初始值
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Rsi {
+    period: usize,
+    prev: Option<Decimal>, // 上一个输入
+    count: usize,          // 已计入的涨跌幅数量，达到period后不再增加
+    avg_gain: Decimal,     // 平均涨幅，初始值计算前为涨幅之和
+    avg_loss: Decimal,     // 平均跌幅，初始值计算前为跌幅之和
+}
+
+impl Rsi {
+    pub fn new(period: usize) -> Result<Self> {
+        anyhow::ensure!(period > 0, "RSI period must be greater than 0");
+
+        Ok(Rsi {
+            period,
+            prev: None,
+            count: 0,
+            avg_gain: Decimal::ZERO,
+            avg_loss: Decimal::ZERO,
+        })
+    }
+
+    pub fn period(&self) -> usize {
+        self.period
+    }
+}
+
+impl Indicator for Rsi {
+    type Input = Decimal;
+    type Output = Decimal;
+
+    fn update(&mut self, input: Decimal) -> Option<Decimal> {
+        let prev = self.prev.replace(input)?;
+
+        let change = input - prev;
+        let gain = change.max(Decimal::ZERO);
+        let loss = (-change).max(Decimal::ZERO);
+        let period = Decimal::from(self.period);
+
+        if self.count < self.period {
+            self.count += 1;
+            self.avg_gain += gain;
+            self.avg_loss += loss;
+
+            if self.count == self.period {
+                self.avg_gain /= period;
+                self.avg_loss /= period;
+            }
+        } else {
+            self.avg_gain = (self.avg_gain * (period - Decimal::ONE) + gain) / period;
+            self.avg_loss = (self.avg_loss * (period - Decimal::ONE) + loss) / period;
+        }
+
+        self.value()
+    }
+
+    fn value(&self) -> Option<Decimal> {
+        if self.count < self.period {
+            return None;
+        }
+
+        // 没有下跌时为100
+        if self.avg_loss.is_zero() {
+            return Some(Decimal::ONE_HUNDRED);
+        }
+
+        let rs = self.avg_gain / self.avg_loss;
+        Some(Decimal::ONE_HUNDRED - Decimal::ONE_HUNDRED / (Decimal::ONE + rs))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_rsi() -> Result<()> {
+        assert!(Rsi::new(0).is_err());
+
+        let mut rsi = Rsi::new(2)?;
+        assert_eq!(rsi.update(dec!(10)), None);
+        assert_eq!(rsi.update(dec!(12)), None);
+        // 平均涨幅1，平均跌幅0.5，RS = 2
+        assert_eq!(
+            rsi.update(dec!(11)).map(|value| value.round_dp(4)),
+            Some(dec!(66.6667))
+        );
+        // 平均涨幅(1 + 3) / 2 = 2，平均跌幅(0.5 + 0) / 2 = 0.25，RS = 8
+        assert_eq!(
+            rsi.update(dec!(14)).map(|value| value.round_dp(4)),
+            Some(dec!(88.8889))
+        );
+
+        let mut rsi = Rsi::new(1)?;
+        rsi.update(dec!(1));
+        assert_eq!(rsi.update(dec!(2)), Some(dec!(100)));
+
+        Ok(())
+    }
+}
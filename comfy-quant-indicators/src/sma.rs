@@ -0,0 +1,74 @@
+use crate::Indicator;
+use anyhow::Result;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+// 简单移动平均
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Sma {
+    period: usize,
+    window: VecDeque<Decimal>, // 最近period个输入
+    sum: Decimal,              // 窗口内输入之和
+}
+
+impl Sma {
+    pub fn new(period: usize) -> Result<Self> {
+        anyhow::ensure!(period > 0, "SMA period must be greater than 0");
+
+        Ok(Sma {
+            period,
+            window: VecDeque::with_capacity(period + 1),
+            sum: Decimal::ZERO,
+        })
+    }
+
+    pub fn period(&self) -> usize {
+        self.period
+    }
+}
+
+impl Indicator for Sma {
+    type Input = Decimal;
+    type Output = Decimal;
+
+    fn update(&mut self, input: Decimal) -> Option<Decimal> {
+        self.window.push_back(input);
+        self.sum += input;
+
+        if self.window.len() > self.period {
+            if let Some(first) = self.window.pop_front() {
+                self.sum -= first;
+            }
+        }
+
+        self.value()
+    }
+
+    fn value(&self) -> Option<Decimal> {
+        (self.window.len() == self.period).then(|| self.sum / Decimal::from(self.period))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_sma() -> Result<()> {
+        assert!(Sma::new(0).is_err());
+
+        let mut sma = Sma::new(3)?;
+        assert_eq!(sma.update(dec!(1)), None);
+        assert_eq!(sma.update(dec!(2)), None);
+        assert_eq!(sma.update(dec!(3)), Some(dec!(2)));
+        assert_eq!(sma.update(dec!(7)), Some(dec!(4)));
+
+        // 恢复序列化的状态后继续计算
+        let mut restored: Sma = serde_json::from_str(&serde_json::to_string(&sma)?)?;
+        assert_eq!(restored.update(dec!(8)), Some(dec!(6)));
+
+        Ok(())
+    }
+}
@@ -1,3 +1,4 @@
+pub mod migration;
 pub mod node_core;
 pub mod node_io;
 pub mod nodes;
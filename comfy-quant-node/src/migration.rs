@@ -0,0 +1,346 @@
+use anyhow::{bail, Result};
+use serde_json::Value;
+
+// 工作流格式的当前版本。节点类型改名或参数调整不兼容旧的工作流时，
+// 提高版本号并在 MIGRATIONS 末尾加入对应的迁移，已保存的工作流加载时自动升级
+pub const WORKFLOW_VERSION: f32 = 0.5;
+
+// 按版本从低到高排列
+const MIGRATIONS: &[Migration] = &[
+    // 早期编辑器的行情节点
+    Migration {
+        version: 0.4,
+        changes: &[Change::RenameType {
+            from: "ExchangeInfo.binanceSpotTicker",
+            to: "data.BinanceSpotTicker",
+        }],
+    },
+    Migration {
+        version: 0.5,
+        changes: &[
+            // 币安账户不再在参数中保存密钥，改为引用已保存的密钥
+            Change::RewriteParams {
+                prop_type: "client.BinanceSpotClient",
+                rewrite: remove_raw_credentials,
+            },
+            // 回测账户新增滑点、冲击系数、下单延迟和成交量参与率
+            Change::PadParams {
+                prop_type: "client.BacktestSpotClient",
+                len: 10,
+            },
+        ],
+    },
+];
+
+// 升级到 version 版本需要的变更，版本低于 version 的工作流依次应用 changes
+#[derive(Debug, Clone, Copy)]
+pub struct Migration {
+    pub version: f32,
+    pub changes: &'static [Change],
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Change {
+    // 节点类型改名
+    RenameType {
+        from: &'static str,
+        to: &'static str,
+    },
+    // 调整节点参数顺序，order[i] 为新的第 i 个参数在旧参数中的位置，
+    // 旧参数不足时补 null，没有列出的旧参数按原顺序放在最后
+    ReorderParams {
+        prop_type: &'static str,
+        order: &'static [usize],
+    },
+    // 新增的可选参数，参数不足 len 个时补 null
+    PadParams {
+        prop_type: &'static str,
+        len: usize,
+    },
+    // 无法用上面的变更表示的参数调整
+    RewriteParams {
+        prop_type: &'static str,
+        rewrite: fn(&mut Vec<Value>),
+    },
+}
+
+impl Change {
+    fn apply(&self, node: &mut Value) {
+        let Some(properties) = node.get_mut("properties").and_then(Value::as_object_mut) else {
+            return;
+        };
+
+        if let Change::RenameType { from, to } = self {
+            if properties.get("type").and_then(Value::as_str) == Some(from) {
+                properties.insert("type".to_string(), Value::from(*to));
+            }
+
+            return;
+        }
+
+        if properties.get("type").and_then(Value::as_str) != Some(self.prop_type()) {
+            return;
+        }
+
+        let Some(params) = properties.get_mut("params").and_then(Value::as_array_mut) else {
+            return;
+        };
+
+        match self {
+            Change::RenameType { .. } => {}
+            Change::ReorderParams { order, .. } => {
+                let rest = params
+                    .iter()
+                    .enumerate()
+                    .filter(|(index, _)| !order.contains(index))
+                    .map(|(_, param)| param.clone());
+
+                let reordered = order
+                    .iter()
+                    .map(|index| params.get(*index).cloned().unwrap_or(Value::Null))
+                    .chain(rest)
+                    .collect();
+
+                *params = reordered;
+            }
+            Change::PadParams { len, .. } => {
+                if params.len() < *len {
+                    params.resize(*len, Value::Null);
+                }
+            }
+            Change::RewriteParams { rewrite, .. } => rewrite(params),
+        }
+    }
+
+    fn prop_type(&self) -> &'static str {
+        match self {
+            Change::RenameType { from, .. } => from,
+            Change::ReorderParams { prop_type, .. }
+            | Change::PadParams { prop_type, .. }
+            | Change::RewriteParams { prop_type, .. } => prop_type,
+        }
+    }
+}
+
+// 旧版币安账户的参数为 [api_key, secret_key, 代理, REST 地址, websocket 地址]，
+// 第二个参数是密钥而不是代理地址。移除密钥并把密钥引用留空，需重新选择已保存的密钥
+fn remove_raw_credentials(params: &mut Vec<Value>) {
+    let is_raw = params
+        .get(1)
+        .and_then(Value::as_str)
+        .is_some_and(|value| !value.is_empty() && !value.contains("://"));
+
+    if is_raw {
+        params.splice(0..2, [Value::from("")]);
+    }
+}
+
+// 把工作流定义升级到当前版本，没有版本号的按最早的格式处理；
+// 版本高于当前版本的工作流无法识别，返回错误
+pub fn migrate(workflow: Value) -> Result<Value> {
+    apply(workflow, MIGRATIONS, WORKFLOW_VERSION)
+}
+
+fn apply(mut workflow: Value, migrations: &[Migration], current: f32) -> Result<Value> {
+    let version = workflow
+        .get("version")
+        .and_then(Value::as_f64)
+        .map_or(0.0, |version| version as f32);
+
+    if version > current {
+        bail!(
+            "Unsupported workflow version {}, the latest supported version is {}",
+            version,
+            current
+        );
+    }
+
+    if version == current {
+        return Ok(workflow);
+    }
+
+    let changes = migrations
+        .iter()
+        .filter(|migration| migration.version > version)
+        .flat_map(|migration| migration.changes);
+
+    for change in changes {
+        if let Some(nodes) = workflow.get_mut("nodes").and_then(Value::as_array_mut) {
+            nodes.iter_mut().for_each(|node| change.apply(node));
+        }
+    }
+
+    if let Some(workflow) = workflow.as_object_mut() {
+        workflow.insert("version".to_string(), Value::from(current));
+    }
+
+    tracing::info!(from = version, to = current, "Workflow migrated");
+
+    Ok(workflow)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_migrate() -> Result<()> {
+        let workflow = json!({
+            "nodes": [
+                {"id": 1, "properties": {"type": "ExchangeInfo.binanceSpotTicker", "params": ["BTC", "USDT"]}},
+                {"id": 2, "properties": {"type": "strategy.SpotGrid", "params": ["arithmetic", 1, 1.1]}},
+            ],
+            "version": 0.3
+        });
+
+        let workflow = migrate(workflow)?;
+        assert_eq!(
+            workflow["nodes"][0]["properties"]["type"],
+            "data.BinanceSpotTicker"
+        );
+        assert_eq!(
+            workflow["nodes"][1]["properties"]["type"],
+            "strategy.SpotGrid"
+        );
+        assert_eq!(
+            workflow["version"].as_f64().map(|v| v as f32),
+            Some(WORKFLOW_VERSION)
+        );
+
+        // 当前版本不变，更新的版本无法识别
+        let workflow = json!({"nodes": [], "version": 0.5});
+        assert_eq!(migrate(workflow.clone())?, workflow);
+        assert!(migrate(json!({"nodes": [], "version": 9.9})).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_migrate_in_order() -> Result<()> {
+        const MIGRATIONS: &[Migration] = &[
+            Migration {
+                version: 0.5,
+                changes: &[Change::RenameType {
+                    from: "strategy.Grid",
+                    to: "strategy.SpotGrid",
+                }],
+            },
+            Migration {
+                version: 0.6,
+                changes: &[Change::ReorderParams {
+                    prop_type: "strategy.SpotGrid",
+                    order: &[0, 2, 1, 4],
+                }],
+            },
+        ];
+
+        let workflow = json!({
+            "nodes": [{"id": 1, "properties": {"type": "strategy.Grid", "params": ["a", "b", "c", "d"]}}],
+            "version": 0.4
+        });
+
+        let workflow = apply(workflow, MIGRATIONS, 0.6)?;
+        assert_eq!(
+            workflow["nodes"][0]["properties"]["type"],
+            "strategy.SpotGrid"
+        );
+        assert_eq!(
+            workflow["nodes"][0]["properties"]["params"],
+            json!(["a", "c", "b", null, "d"])
+        );
+
+        // 已升级到 0.5 的工作流只调整参数顺序
+        let workflow = json!({
+            "nodes": [
+                {"id": 1, "properties": {"type": "strategy.Grid", "params": ["a", "b"]}},
+                {"id": 2, "properties": {"type": "strategy.SpotGrid", "params": ["a", "b", "c"]}},
+            ],
+            "version": 0.5
+        });
+
+        let workflow = apply(workflow, MIGRATIONS, 0.6)?;
+        assert_eq!(workflow["nodes"][0]["properties"]["type"], "strategy.Grid");
+        assert_eq!(
+            workflow["nodes"][0]["properties"]["params"],
+            json!(["a", "b"])
+        );
+        assert_eq!(
+            workflow["nodes"][1]["properties"]["params"],
+            json!(["a", "c", "b", null])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_migrate_binance_credentials() -> Result<()> {
+        let workflow = json!({
+            "nodes": [
+                {"id": 1, "properties": {"type": "client.BinanceSpotClient", "params": ["api_key", "secret_key", "", "https://api1.binance.com", ""]}},
+                {"id": 2, "properties": {"type": "client.BinanceSpotClient", "params": ["main", "", "https://api1.binance.com"]}},
+                {"id": 3, "properties": {"type": "client.BinanceSpotClient", "params": ["api_key", "secret_key"]}},
+            ],
+            "version": 0.4
+        });
+
+        let workflow = migrate(workflow)?;
+        assert_eq!(
+            workflow["nodes"][0]["properties"]["params"],
+            json!(["", "", "https://api1.binance.com", ""])
+        );
+        // 已是密钥引用的不变
+        assert_eq!(
+            workflow["nodes"][1]["properties"]["params"],
+            json!(["main", "", "https://api1.binance.com"])
+        );
+        assert_eq!(workflow["nodes"][2]["properties"]["params"], json!([""]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_migrate_backtest_client_params() -> Result<()> {
+        let workflow = json!({
+            "nodes": [
+                {"id": 1, "properties": {"type": "client.BacktestSpotClient", "params": [0.001, [["USDT", 1000]]]}},
+                {"id": 2, "properties": {"type": "client.BacktestSpotClient", "params": [0.001, [["USDT", 1000]], 1, true, null, "optimistic"]}},
+            ],
+            "version": 0.4
+        });
+
+        let workflow = migrate(workflow)?;
+        assert_eq!(
+            workflow["nodes"][0]["properties"]["params"],
+            json!([
+                0.001,
+                [["USDT", 1000]],
+                null,
+                null,
+                null,
+                null,
+                null,
+                null,
+                null,
+                null
+            ])
+        );
+        assert_eq!(
+            workflow["nodes"][1]["properties"]["params"],
+            json!([
+                0.001,
+                [["USDT", 1000]],
+                1,
+                true,
+                null,
+                "optimistic",
+                null,
+                null,
+                null,
+                null
+            ])
+        );
+
+        Ok(())
+    }
+}
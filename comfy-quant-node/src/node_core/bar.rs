@@ -0,0 +1,75 @@
+use bon::Builder;
+use comfy_quant_base::{KlineInterval, Symbol};
+use comfy_quant_database::kline::Kline;
+use comfy_quant_indicators::Ohlc;
+use rust_decimal::Decimal;
+
+// K线(OHLCV)
+#[derive(Debug, Clone, Builder, PartialEq)]
+pub struct Bar {
+    pub symbol: Symbol,          // 交易对
+    pub interval: KlineInterval, // 时间间隔
+    pub open_time: i64,          // 开盘时间(秒)
+    pub open: Decimal,           // 开盘价格
+    pub high: Decimal,           // 最高价格
+    pub low: Decimal,            // 最低价格
+    pub close: Decimal,          // 收盘价格
+    pub volume: Decimal,         // 成交量
+    #[builder(default)]
+    pub taker_buy_volume: Decimal, // 主动买入成交量
+    pub open_time_millis: Option<i64>, // 开盘时间(毫秒)，不足1秒和按成交笔数聚合的K线有值
+    pub close_time_millis: Option<i64>, // 收盘时间(毫秒)，按成交笔数聚合的K线为最后一笔成交的时间
+}
+
+impl Bar {
+    // 收盘时间(秒)，即下一根K线的开盘时间；不足1秒的K线向上取整
+    pub fn close_time(&self) -> i64 {
+        match self.interval.to_seconds() {
+            0 => (self.close_millis() + 999).div_euclid(1000),
+            secs => self.open_time + secs,
+        }
+    }
+
+    // 开盘时间(毫秒)
+    pub fn open_millis(&self) -> i64 {
+        self.open_time_millis.unwrap_or(self.open_time * 1000)
+    }
+
+    // 收盘时间(毫秒)
+    pub fn close_millis(&self) -> i64 {
+        self.close_time_millis
+            .unwrap_or_else(|| self.open_millis() + self.interval.to_millis())
+    }
+}
+
+impl From<&Kline> for Bar {
+    fn from(value: &Kline) -> Self {
+        Bar::builder()
+            .symbol(value.symbol.clone())
+            .interval(value.interval.clone())
+            .open_time(value.open_time.timestamp())
+            .open(value.open_price)
+            .high(value.high_price)
+            .low(value.low_price)
+            .close(value.close_price)
+            .volume(value.volume)
+            .taker_buy_volume(value.taker_buy_volume)
+            .maybe_open_time_millis(
+                (value.interval.to_seconds() == 0).then(|| value.open_time.timestamp_millis()),
+            )
+            .maybe_close_time_millis(value.close_time.map(|time| time.timestamp_millis()))
+            .build()
+    }
+}
+
+// 作为ATR等指标的输入
+impl From<&Bar> for Ohlc {
+    fn from(value: &Bar) -> Self {
+        Ohlc {
+            open: value.open,
+            high: value.high,
+            low: value.low,
+            close: value.close,
+        }
+    }
+}
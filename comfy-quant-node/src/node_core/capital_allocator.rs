@@ -0,0 +1,190 @@
+use anyhow::anyhow;
+use bon::Builder;
+use rust_decimal::{Decimal, MathematicalOps};
+use rust_decimal_macros::dec;
+use std::{
+    collections::{BTreeMap, VecDeque},
+    str::FromStr,
+};
+
+// 子策略之间的资金分配方法
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AllocationMethod {
+    EqualWeight, // 等权
+    RiskParity,  // 等风险贡献，忽略相关性时按权益曲线收益率波动率的倒数分配
+    Momentum,    // 动量，按回看期内权益曲线的收益率分配，亏损的子策略只保留最低权重
+}
+
+impl FromStr for AllocationMethod {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "equal_weight" => Ok(AllocationMethod::EqualWeight),
+            "risk_parity" => Ok(AllocationMethod::RiskParity),
+            "momentum" => Ok(AllocationMethod::Momentum),
+            _ => Err(anyhow!("Invalid allocation method: {}", s)),
+        }
+    }
+}
+
+// 多策略资金分配：按子策略权益曲线的近期表现计算各子策略的目标权重。
+// 数据不足时(如刚启动)按等权分配
+#[derive(Builder, Debug, Clone)]
+pub struct CapitalAllocator {
+    method: AllocationMethod, // 分配方法
+    #[builder(default = 3600)]
+    rebalance_secs: i64, // 再分配间隔(秒)
+    #[builder(default = 30)]
+    lookback: usize, // 回看的权益采样数量
+    #[builder(default)]
+    min_weight: Decimal, // 每个子策略的最低权重
+}
+
+impl CapitalAllocator {
+    pub fn rebalance_secs(&self) -> i64 {
+        self.rebalance_secs
+    }
+
+    pub fn lookback(&self) -> usize {
+        self.lookback
+    }
+
+    // 按权益曲线计算目标权重，权重之和为1
+    pub fn weights(&self, curves: &BTreeMap<u32, VecDeque<Decimal>>) -> BTreeMap<u32, Decimal> {
+        if curves.is_empty() {
+            return BTreeMap::new();
+        }
+
+        let scores = match self.method {
+            AllocationMethod::EqualWeight => None,
+            AllocationMethod::RiskParity => curves
+                .iter()
+                .map(|(node_id, curve)| {
+                    let volatility = volatility(curve).filter(|v| *v > dec!(0))?;
+                    Some((*node_id, dec!(1) / volatility))
+                })
+                .collect::<Option<BTreeMap<_, _>>>(),
+            AllocationMethod::Momentum => curves
+                .iter()
+                .map(|(node_id, curve)| {
+                    let (first, last) = (curve.front()?, curve.back()?);
+                    let score = (*first > dec!(0)).then(|| (last / first - dec!(1)).max(dec!(0)));
+                    Some((*node_id, score?))
+                })
+                .collect::<Option<BTreeMap<_, _>>>(),
+        };
+
+        let weights = match scores {
+            Some(scores) if scores.values().any(|score| *score > dec!(0)) => normalize(scores),
+            _ => equal_weights(curves),
+        };
+
+        if self.min_weight <= dec!(0) {
+            return weights;
+        }
+
+        // 低于最低权重的子策略提升到最低权重后重新归一
+        normalize(
+            weights
+                .into_iter()
+                .map(|(node_id, weight)| (node_id, weight.max(self.min_weight)))
+                .collect(),
+        )
+    }
+}
+
+fn equal_weights(curves: &BTreeMap<u32, VecDeque<Decimal>>) -> BTreeMap<u32, Decimal> {
+    let weight = dec!(1) / Decimal::from(curves.len());
+    curves.keys().map(|node_id| (*node_id, weight)).collect()
+}
+
+fn normalize(scores: BTreeMap<u32, Decimal>) -> BTreeMap<u32, Decimal> {
+    let total = scores.values().sum::<Decimal>();
+
+    scores
+        .into_iter()
+        .map(|(node_id, score)| (node_id, score / total))
+        .collect()
+}
+
+// 权益曲线收益率的标准差，至少需要两个收益率
+fn volatility(curve: &VecDeque<Decimal>) -> Option<Decimal> {
+    let returns = curve
+        .iter()
+        .zip(curve.iter().skip(1))
+        .map(|(prev, next)| (*prev > dec!(0)).then(|| next / prev - dec!(1)))
+        .collect::<Option<Vec<_>>>()?;
+
+    if returns.len() < 2 {
+        return None;
+    }
+
+    let len = Decimal::from(returns.len());
+    let mean = returns.iter().sum::<Decimal>() / len;
+    let variance = returns
+        .iter()
+        .map(|r| (r - mean) * (r - mean))
+        .sum::<Decimal>()
+        / len;
+
+    variance.sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn equity_curves(data: &[(u32, &[Decimal])]) -> BTreeMap<u32, VecDeque<Decimal>> {
+        data.iter()
+            .map(|(node_id, curve)| (*node_id, curve.iter().copied().collect()))
+            .collect()
+    }
+
+    #[test]
+    fn test_capital_allocator() -> anyhow::Result<()> {
+        assert_eq!(
+            "risk_parity".parse::<AllocationMethod>()?,
+            AllocationMethod::RiskParity
+        );
+        assert!("kelly".parse::<AllocationMethod>().is_err());
+
+        let curves = equity_curves(&[
+            (1, &[dec!(1000), dec!(1010), dec!(1000), dec!(1010)]),
+            (2, &[dec!(1000), dec!(1020), dec!(1000), dec!(1020)]),
+            (3, &[dec!(1000), dec!(990), dec!(980), dec!(970)]),
+        ]);
+
+        let allocator = |method| CapitalAllocator::builder().method(method).build();
+
+        let weights = allocator(AllocationMethod::EqualWeight).weights(&curves);
+        assert_eq!(weights.values().sum::<Decimal>().round_dp(8), dec!(1));
+        assert_eq!(weights[&1], weights[&3]);
+
+        // 波动越大权重越低
+        let weights = allocator(AllocationMethod::RiskParity).weights(&curves);
+        assert!(weights[&1] > weights[&2]);
+        assert!(weights[&3] > weights[&1]);
+        assert_eq!(weights.values().sum::<Decimal>().round_dp(8), dec!(1));
+
+        // 亏损的子策略不分配资金
+        let weights = allocator(AllocationMethod::Momentum).weights(&curves);
+        assert_eq!(weights[&3], dec!(0));
+        assert!(weights[&2] > weights[&1]);
+
+        // 最低权重
+        let weights = CapitalAllocator::builder()
+            .method(AllocationMethod::Momentum)
+            .min_weight(dec!(0.1))
+            .build()
+            .weights(&curves);
+        assert!(weights[&3] > dec!(0));
+
+        // 数据不足时等权
+        let short = equity_curves(&[(1, &[dec!(1000)]), (2, &[dec!(1000)])]);
+        let weights = allocator(AllocationMethod::RiskParity).weights(&short);
+        assert_eq!(weights[&1], dec!(0.5));
+
+        Ok(())
+    }
+}
@@ -1,14 +1,24 @@
+use super::OrderRules;
 use anyhow::{anyhow, Result};
 use bon::bon;
-use comfy_quant_base::Exchange;
+use comfy_quant_base::{generate_client_order_id, Exchange, Symbol};
+use comfy_quant_database::account_symbol_rule::{self, AccountSymbolRule};
 use comfy_quant_exchange::client::{
     spot_client::base::{
-        AccountInformation, Balance, SpotClientRequest, SpotClientResponse, SymbolInformation,
+        AccountInformation, Balance, Order, OrderIntent, OrderSide, SpotClientRequest,
+        SpotClientResponse, SymbolInformation, SymbolPrice, UserDataEvent,
     },
-    spot_client_kind::SpotClientKind,
+    spot_client_kind::{SpotClientExecutable, SpotClientKind, SpotclientExecutableExt},
 };
 use futures::future;
-use std::{thread::sleep, time::Duration};
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+use sqlx::PgPool;
+use std::{
+    collections::{HashMap, HashSet},
+    thread::sleep,
+    time::{Duration, Instant},
+};
+use tokio::sync::broadcast::{self, error::RecvError};
 use tower::{retry::Policy, util::BoxService, BoxError, Service, ServiceBuilder, ServiceExt};
 
 #[derive(Clone)]
@@ -28,13 +38,19 @@ impl Attempts {
     }
 }
 
-impl<Req, Res, E> Policy<Req, Res, E> for Attempts
-where
-    Req: Clone,
-{
+impl<Res, E> Policy<SpotClientRequest, Res, E> for Attempts {
     type Future = future::Ready<()>;
 
-    fn retry(&mut self, _req: &mut Req, result: &mut Result<Res, E>) -> Option<Self::Future> {
+    fn retry(
+        &mut self,
+        req: &mut SpotClientRequest,
+        result: &mut Result<Res, E>,
+    ) -> Option<Self::Future> {
+        // 下单请求失败时订单可能已经提交，不在这里重试，由 SpotClientService 按客户端订单ID确认后重试
+        if req.trading_pair().is_some() {
+            return None;
+        }
+
         match result {
             Ok(_) => None,
             Err(_) => {
@@ -53,15 +69,81 @@ where
         }
     }
 
-    fn clone_request(&mut self, req: &Req) -> Option<Req> {
+    fn clone_request(&mut self, req: &SpotClientRequest) -> Option<SpotClientRequest> {
         Some(req.clone())
     }
 }
 
+// 账户级的交易对黑白名单，黑名单优先，白名单为空时不限制
+#[derive(Debug, Clone, Default)]
+pub struct SymbolRules {
+    allowed: HashSet<Symbol>, // 白名单
+    denied: HashSet<Symbol>,  // 黑名单
+}
+
+impl SymbolRules {
+    // 加载账户在交易所生效的规则
+    pub async fn load(db: &PgPool, account: &str, exchange: &Exchange) -> Result<Self> {
+        let rules = account_symbol_rule::list(db, account, exchange).await?;
+        Ok(rules.into_iter().collect())
+    }
+
+    pub fn len(&self) -> usize {
+        self.allowed.len() + self.denied.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn check(&self, symbol: &Symbol) -> Result<()> {
+        if self.denied.contains(symbol) {
+            anyhow::bail!("Symbol {} is denied for this account", symbol);
+        }
+
+        if !self.allowed.is_empty() && !self.allowed.contains(symbol) {
+            anyhow::bail!("Symbol {} is not in the allow list of this account", symbol);
+        }
+
+        Ok(())
+    }
+}
+
+impl FromIterator<AccountSymbolRule> for SymbolRules {
+    fn from_iter<T: IntoIterator<Item = AccountSymbolRule>>(iter: T) -> Self {
+        let mut rules = SymbolRules::default();
+
+        for rule in iter {
+            if rule.is_deny() {
+                rules.denied.insert(rule.symbol);
+            } else {
+                rules.allowed.insert(rule.symbol);
+            }
+        }
+
+        rules
+    }
+}
+
+// 下单结果。duplicate 为 true 表示请求失败后按客户端订单ID查到了已提交的订单，
+// 返回该订单而没有重复下单
+#[derive(Debug, Clone)]
+pub struct PlacedOrder {
+    pub order: Order,
+    pub client_order_id: String, // 幂等键，重试时保持不变
+    pub attempts: u64,           // 提交次数
+    pub duplicate: bool,         // 是否检测到已提交的订单
+}
+
 type SpotClientServiceInner = BoxService<SpotClientRequest, SpotClientResponse, BoxError>;
 
 pub struct SpotClientService {
     inner: SpotClientServiceInner,
+    client: SpotClientKind,                   // 用于计算交易对
+    symbol_rules: SymbolRules,                // 交易对黑白名单
+    order_rules: HashMap<Symbol, OrderRules>, // 交易对的下单规则，首次下单时从交易所加载
+    retry_max_retries: u64,                   // 下单失败后的最大重试次数
+    retry_wait_secs: u64,                     // 下单重试的等待时间
 }
 
 impl AsRef<SpotClientServiceInner> for SpotClientService {
@@ -84,6 +166,7 @@ impl SpotClientService {
         retry_max_retries: u64,
         retry_wait_secs: u64,
         timeout_secs: u64,
+        #[builder(default)] symbol_rules: SymbolRules,
     ) -> Self {
         let svc = client.clone();
         let retry_policy = Attempts::builder()
@@ -98,7 +181,28 @@ impl SpotClientService {
             .service(svc)
             .boxed();
 
-        SpotClientService { inner }
+        SpotClientService {
+            inner,
+            client: client.clone(),
+            symbol_rules,
+            order_rules: HashMap::new(),
+            retry_max_retries,
+            retry_wait_secs,
+        }
+    }
+
+    // 检查账户是否允许交易该交易对
+    pub fn ensure_symbol_allowed(&self, base_asset: &str, quote_asset: &str) -> Result<()> {
+        let symbol = self.client.symbol(base_asset, quote_asset);
+
+        self.symbol_rules.check(&symbol).inspect_err(|_| {
+            tracing::warn!(
+                monotonic_counter.spot_symbol_denied = 1_u64,
+                exchange = %self.client.exchange(),
+                symbol = %symbol,
+                "Symbol denied by account rules"
+            )
+        })
     }
 
     pub async fn get_account(&mut self) -> Result<AccountInformation> {
@@ -125,7 +229,308 @@ impl SpotClientService {
         self.ready_call(req).await?.try_into()
     }
 
+    pub async fn market_buy(
+        &mut self,
+        base_asset: &str,
+        quote_asset: &str,
+        qty: f64,
+    ) -> Result<PlacedOrder> {
+        let req = SpotClientRequest::MarketBuy {
+            base_asset: base_asset.to_string(),
+            quote_asset: quote_asset.to_string(),
+            qty,
+        };
+        self.submit_order(req).await
+    }
+
+    pub async fn market_sell(
+        &mut self,
+        base_asset: &str,
+        quote_asset: &str,
+        qty: f64,
+    ) -> Result<PlacedOrder> {
+        let req = SpotClientRequest::MarketSell {
+            base_asset: base_asset.to_string(),
+            quote_asset: quote_asset.to_string(),
+            qty,
+        };
+        self.submit_order(req).await
+    }
+
+    pub async fn limit_buy(
+        &mut self,
+        base_asset: &str,
+        quote_asset: &str,
+        qty: f64,
+        price: f64,
+    ) -> Result<PlacedOrder> {
+        let req = SpotClientRequest::LimitBuy {
+            base_asset: base_asset.to_string(),
+            quote_asset: quote_asset.to_string(),
+            qty,
+            price,
+        };
+        self.submit_order(req).await
+    }
+
+    pub async fn limit_sell(
+        &mut self,
+        base_asset: &str,
+        quote_asset: &str,
+        qty: f64,
+        price: f64,
+    ) -> Result<PlacedOrder> {
+        let req = SpotClientRequest::LimitSell {
+            base_asset: base_asset.to_string(),
+            quote_asset: quote_asset.to_string(),
+            qty,
+            price,
+        };
+        self.submit_order(req).await
+    }
+
+    // 交易对的下单规则，按交易对缓存
+    pub async fn order_rules(&mut self, base_asset: &str, quote_asset: &str) -> Result<OrderRules> {
+        let symbol = self.client.symbol(base_asset, quote_asset);
+
+        if let Some(rules) = self.order_rules.get(&symbol) {
+            return Ok(rules.clone());
+        }
+
+        let req = SpotClientRequest::get_symbol_info(base_asset, quote_asset);
+        let symbol_info: SymbolInformation = self.inner_call(req).await?.try_into()?;
+        let rules = OrderRules::from(&symbol_info);
+        self.order_rules.insert(symbol, rules.clone());
+
+        Ok(rules)
+    }
+
+    // 按交易所过滤器调整下单请求：数量按数量步长、价格按价格步长取整，
+    // 低于最小下单数量或最小名义价值时返回 OrderFilterError，回测和实盘使用相同的规则
+    pub async fn apply_filters(&mut self, req: SpotClientRequest) -> Result<SpotClientRequest> {
+        let Some((base_asset, quote_asset)) = req.trading_pair() else {
+            return Ok(req);
+        };
+        let (base_asset, quote_asset) = (base_asset.to_string(), quote_asset.to_string());
+        let rules = self.order_rules(&base_asset, &quote_asset).await?;
+
+        let req = match req {
+            SpotClientRequest::MarketBuy { qty, .. }
+            | SpotClientRequest::MarketSell { qty, .. }
+            | SpotClientRequest::MarginBuy { qty, .. }
+            | SpotClientRequest::MarginSell { qty, .. } => {
+                let qty = rules.round_qty(Decimal::try_from(qty)?);
+
+                // 市价单按最新价格估算名义价值，没有最小名义价值时不查询价格
+                let price = if rules.min_notional().is_some() {
+                    let req = SpotClientRequest::GetPrice {
+                        base_asset: base_asset.clone(),
+                        quote_asset: quote_asset.clone(),
+                    };
+                    let symbol_price: SymbolPrice = self.inner_call(req).await?.try_into()?;
+                    symbol_price.price
+                } else {
+                    Decimal::ZERO
+                };
+                rules.check(qty, price)?;
+
+                with_qty(req, to_f64(qty)?)
+            }
+            SpotClientRequest::MarketBuyQuote { quote_qty, .. }
+            | SpotClientRequest::MarketSellQuote { quote_qty, .. } => {
+                rules.check_notional(Decimal::try_from(quote_qty)?)?;
+                req
+            }
+            SpotClientRequest::LimitBuy { qty, price, .. } => {
+                let qty = rules.round_qty(Decimal::try_from(qty)?);
+                let price = rules.round_price(Decimal::try_from(price)?, &OrderSide::Buy);
+                rules.check(qty, price)?;
+
+                SpotClientRequest::LimitBuy {
+                    base_asset,
+                    quote_asset,
+                    qty: to_f64(qty)?,
+                    price: to_f64(price)?,
+                }
+            }
+            SpotClientRequest::LimitSell { qty, price, .. } => {
+                let qty = rules.round_qty(Decimal::try_from(qty)?);
+                let price = rules.round_price(Decimal::try_from(price)?, &OrderSide::Sell);
+                rules.check(qty, price)?;
+
+                SpotClientRequest::LimitSell {
+                    base_asset,
+                    quote_asset,
+                    qty: to_f64(qty)?,
+                    price: to_f64(price)?,
+                }
+            }
+            SpotClientRequest::StopLimitOrder {
+                side,
+                qty,
+                price,
+                stop_price,
+                ..
+            } => {
+                let qty = rules.round_qty(Decimal::try_from(qty)?);
+                let price = rules.round_price(Decimal::try_from(price)?, &side);
+                let stop_price = rules.round_price(Decimal::try_from(stop_price)?, &side);
+                rules.check(qty, price)?;
+
+                SpotClientRequest::StopLimitOrder {
+                    base_asset,
+                    quote_asset,
+                    side,
+                    qty: to_f64(qty)?,
+                    price: to_f64(price)?,
+                    stop_price: to_f64(stop_price)?,
+                }
+            }
+            // OCO 的限价单和止损限价单分别检查
+            SpotClientRequest::OcoOrder {
+                side,
+                qty,
+                price,
+                stop_price,
+                stop_limit_price,
+                ..
+            } => {
+                let qty = rules.round_qty(Decimal::try_from(qty)?);
+                let price = rules.round_price(Decimal::try_from(price)?, &side);
+                let stop_price = rules.round_price(Decimal::try_from(stop_price)?, &side);
+                let stop_limit_price =
+                    rules.round_price(Decimal::try_from(stop_limit_price)?, &side);
+                rules.check(qty, price)?;
+                rules.check(qty, stop_limit_price)?;
+
+                SpotClientRequest::OcoOrder {
+                    base_asset,
+                    quote_asset,
+                    side,
+                    qty: to_f64(qty)?,
+                    price: to_f64(price)?,
+                    stop_price: to_f64(stop_price)?,
+                    stop_limit_price: to_f64(stop_limit_price)?,
+                }
+            }
+            req => req,
+        };
+
+        Ok(req)
+    }
+
+    // 生成客户端订单ID作为幂等键后下单。请求失败时订单可能已被交易所接受(如响应超时)，
+    // 先按客户端订单ID查询，查到则返回该订单，确认不存在才用同一ID重试。
+    // 客户端不支持按ID查询时无法确认，不重试
+    async fn submit_order(&mut self, req: SpotClientRequest) -> Result<PlacedOrder> {
+        if let Some((base_asset, quote_asset)) = req.trading_pair() {
+            self.ensure_symbol_allowed(base_asset, quote_asset)?;
+        }
+
+        let intent = OrderIntent::try_from(self.apply_filters(req).await?)?;
+        let (base_asset, quote_asset) = (
+            intent.base_asset().to_string(),
+            intent.quote_asset().to_string(),
+        );
+        let client_order_id = generate_client_order_id();
+        let mut attempts = 0;
+
+        loop {
+            attempts += 1;
+
+            let req = SpotClientRequest::SubmitOrder {
+                client_order_id: client_order_id.clone(),
+                intent: intent.clone(),
+            };
+
+            let err = match self.inner_call(req).await {
+                Ok(res) => {
+                    return Ok(PlacedOrder {
+                        order: res.try_into()?,
+                        client_order_id,
+                        attempts,
+                        duplicate: false,
+                    })
+                }
+                Err(e) => e,
+            };
+
+            let req = SpotClientRequest::GetOrderByClientId {
+                base_asset: base_asset.clone(),
+                quote_asset: quote_asset.clone(),
+                client_order_id: client_order_id.clone(),
+            };
+
+            match self
+                .inner_call(req)
+                .await
+                .and_then(Option::<Order>::try_from)
+            {
+                Ok(Some(order)) => {
+                    tracing::warn!(
+                        monotonic_counter.spot_order_duplicate_detected = 1_u64,
+                        exchange = %self.client.exchange(),
+                        client_order_id = %client_order_id,
+                        "Order already submitted, skip retry: {}",
+                        err
+                    );
+
+                    return Ok(PlacedOrder {
+                        order,
+                        client_order_id,
+                        attempts,
+                        duplicate: true,
+                    });
+                }
+                Ok(None) if attempts <= self.retry_max_retries => {
+                    tracing::warn!(
+                        monotonic_counter.spot_order_retried = 1_u64,
+                        exchange = %self.client.exchange(),
+                        client_order_id = %client_order_id,
+                        "Order not found, retry: {}",
+                        err
+                    );
+
+                    tokio::time::sleep(Duration::from_secs(self.retry_wait_secs)).await;
+                }
+                Ok(None) => return Err(err),
+                Err(e) => {
+                    tracing::warn!(
+                        exchange = %self.client.exchange(),
+                        client_order_id = %client_order_id,
+                        "Query order by client id failed, skip retry: {}",
+                        e
+                    );
+
+                    return Err(err);
+                }
+            }
+        }
+    }
+
     async fn ready_call(&mut self, req: SpotClientRequest) -> Result<SpotClientResponse> {
+        // 下单前检查交易对黑白名单
+        if let Some((base_asset, quote_asset)) = req.trading_pair() {
+            self.ensure_symbol_allowed(base_asset, quote_asset)?;
+        }
+
+        // 下单前按交易所过滤器调整数量和价格
+        let req = self.apply_filters(req).await.inspect_err(|e| {
+            tracing::warn!(
+                monotonic_counter.spot_order_filtered = 1_u64,
+                exchange = %self.client.exchange(),
+                "Apply exchange filters failed: {}",
+                e
+            )
+        })?;
+
+        self.inner_call(req).await
+    }
+
+    async fn inner_call(&mut self, req: SpotClientRequest) -> Result<SpotClientResponse> {
+        let method: &'static str = (&req).into();
+        let started_at = Instant::now();
+
         let res = self
             .as_mut()
             .ready()
@@ -133,8 +538,255 @@ impl SpotClientService {
             .map_err(|e| anyhow!(e))?
             .call(req)
             .await
-            .map_err(|e| anyhow!(e))?;
+            .map_err(|e| anyhow!(e));
+
+        // 交易所请求耗时，包含重试
+        metrics::histogram!(
+            "exchange_request_duration_seconds",
+            "exchange" => self.client.exchange().to_string(),
+            "method" => method,
+            "status" => if res.is_ok() { "ok" } else { "error" }
+        )
+        .record(started_at.elapsed().as_secs_f64());
+
+        res
+    }
+}
+
+// 替换市价单的数量
+fn with_qty(req: SpotClientRequest, qty: f64) -> SpotClientRequest {
+    match req {
+        SpotClientRequest::MarketBuy {
+            base_asset,
+            quote_asset,
+            ..
+        } => SpotClientRequest::MarketBuy {
+            base_asset,
+            quote_asset,
+            qty,
+        },
+        SpotClientRequest::MarketSell {
+            base_asset,
+            quote_asset,
+            ..
+        } => SpotClientRequest::MarketSell {
+            base_asset,
+            quote_asset,
+            qty,
+        },
+        SpotClientRequest::MarginBuy {
+            base_asset,
+            quote_asset,
+            ..
+        } => SpotClientRequest::MarginBuy {
+            base_asset,
+            quote_asset,
+            qty,
+        },
+        SpotClientRequest::MarginSell {
+            base_asset,
+            quote_asset,
+            ..
+        } => SpotClientRequest::MarginSell {
+            base_asset,
+            quote_asset,
+            qty,
+        },
+        req => req,
+    }
+}
+
+fn to_f64(value: Decimal) -> Result<f64> {
+    value
+        .to_f64()
+        .ok_or_else(|| anyhow!("Failed to convert {} to f64", value))
+}
+
+// 等待交易所推送的下一个用户数据事件，客户端不支持推送或推送关闭后永远等待
+pub(crate) async fn next_user_data(
+    rx: &mut Option<broadcast::Receiver<UserDataEvent>>,
+) -> UserDataEvent {
+    while let Some(receiver) = rx.as_mut() {
+        match receiver.recv().await {
+            Ok(event) => return event,
+            Err(RecvError::Lagged(skipped)) => {
+                tracing::warn!(
+                    monotonic_counter.user_data_lagged = skipped,
+                    "User data receiver lagged, skipped {} events",
+                    skipped
+                );
+            }
+            Err(RecvError::Closed) => *rx = None,
+        }
+    }
+
+    future::pending().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node_core::OrderFilterError;
+    use async_lock::RwLock;
+    use comfy_quant_base::Market;
+    use comfy_quant_exchange::{
+        client::spot_client::backtest_spot_client::{BacktestSpotClient, SymbolFilters},
+        store::PriceStore,
+    };
+    use rust_decimal_macros::dec;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_symbol_rules_check() -> Result<()> {
+        let rules = SymbolRules::default();
+        assert!(rules.check(&"BTCUSDT".into()).is_ok());
+
+        let rules = SymbolRules {
+            allowed: HashSet::from(["BTCUSDT".into(), "ETHUSDT".into()]),
+            denied: HashSet::from(["ETHUSDT".into()]),
+        };
+        assert_eq!(rules.len(), 3);
+        assert!(rules.check(&"BTCUSDT".into()).is_ok());
+        // 黑名单优先
+        assert!(rules.check(&"ETHUSDT".into()).is_err());
+        // 不在白名单中
+        assert!(rules.check(&"BNBUSDT".into()).is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_spot_client_service_apply_filters() -> Result<()> {
+        let price_store = Arc::new(RwLock::new(PriceStore::new()));
+        price_store.write().await.save_price(
+            &Exchange::Binance,
+            &Market::Spot,
+            &SymbolPrice::builder()
+                .symbol("BTCUSDT".into())
+                .price(dec!(50000))
+                .build(),
+        )?;
+
+        let client: SpotClientKind = BacktestSpotClient::builder()
+            .assets(vec![("USDT".to_string(), 10000.)])
+            .commissions(0.)
+            .symbol_filters(vec![(
+                "BTCUSDT".to_string(),
+                SymbolFilters::builder()
+                    .tick_size(dec!(0.01))
+                    .step_size(dec!(0.001))
+                    .min_qty(dec!(0.001))
+                    .min_notional(dec!(10))
+                    .build(),
+            )])
+            .price_store(price_store)
+            .build()
+            .into();
+        let mut service = SpotClientService::builder()
+            .client(&client)
+            .retry_max_retries(0)
+            .retry_wait_secs(0)
+            .timeout_secs(10)
+            .build();
+
+        // 数量和价格按步长取整
+        let req = service
+            .apply_filters(SpotClientRequest::LimitBuy {
+                base_asset: "BTC".to_string(),
+                quote_asset: "USDT".to_string(),
+                qty: 0.12345,
+                price: 49999.999,
+            })
+            .await?;
+        let SpotClientRequest::LimitBuy { qty, price, .. } = req else {
+            anyhow::bail!("Unexpected request");
+        };
+        assert_eq!(qty, 0.123);
+        assert_eq!(price, 49999.99);
+
+        // 市价单按最新价格检查最小名义价值
+        let placed = service.market_buy("BTC", "USDT", 0.0019).await?;
+        assert_eq!(placed.order.orig_qty.parse::<Decimal>()?, dec!(0.001));
+
+        let Err(err) = service
+            .apply_filters(SpotClientRequest::MarketBuyQuote {
+                base_asset: "BTC".to_string(),
+                quote_asset: "USDT".to_string(),
+                quote_qty: 5.,
+            })
+            .await
+        else {
+            anyhow::bail!("Order below min notional should be rejected");
+        };
+        assert_eq!(
+            err.downcast_ref::<OrderFilterError>(),
+            Some(&OrderFilterError::BelowMinNotional {
+                notional: dec!(5),
+                min_notional: dec!(10)
+            })
+        );
+
+        let err = service
+            .market_sell("BTC", "USDT", 0.0009)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<OrderFilterError>(),
+            Some(&OrderFilterError::InvalidQuantity)
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_spot_client_service_submit_order() -> Result<()> {
+        let price_store = Arc::new(RwLock::new(PriceStore::new()));
+        price_store.write().await.save_price(
+            &Exchange::Binance,
+            &Market::Spot,
+            &SymbolPrice::builder()
+                .symbol("BTCUSDT".into())
+                .price(dec!(50000))
+                .build(),
+        )?;
+
+        let client: SpotClientKind = BacktestSpotClient::builder()
+            .assets(vec![("USDT".to_string(), 10000.)])
+            .commissions(0.)
+            .price_store(price_store)
+            .build()
+            .into();
+        let mut service = SpotClientService::builder()
+            .client(&client)
+            .retry_max_retries(3)
+            .retry_wait_secs(0)
+            .timeout_secs(10)
+            .build();
+
+        let placed = service.limit_buy("BTC", "USDT", 0.1, 45000.).await?;
+        assert_eq!(placed.attempts, 1);
+        assert!(!placed.duplicate);
+        assert_eq!(placed.client_order_id.len(), 32);
+        assert_eq!(
+            placed.order.client_order_id.as_deref(),
+            Some(placed.client_order_id.as_str())
+        );
+
+        // 按客户端订单ID可以查到已提交的订单
+        let order = client
+            .get_order_by_client_id("BTC", "USDT", &placed.client_order_id)
+            .await?
+            .ok_or_else(|| anyhow!("Order not found"))?;
+        assert_eq!(order.order_id, placed.order.order_id);
+
+        // 同一客户端订单ID不会重复下单
+        let intent = OrderIntent::limit_buy("BTC", "USDT", 0.1, 45000.);
+        assert!(client
+            .submit_order(&placed.client_order_id, intent)
+            .await
+            .is_err());
+        assert_eq!(client.get_open_orders("BTC", "USDT").await?.len(), 1);
 
-        Ok(res)
+        Ok(())
     }
 }
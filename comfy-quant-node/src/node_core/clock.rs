@@ -0,0 +1,96 @@
+use chrono::{DateTime, Utc};
+use std::sync::atomic::{AtomicI64, Ordering};
+
+const NOT_STARTED: i64 = i64::MIN;
+
+// 工作流时钟：回测时由数据源节点按tick时间推进，统计和节点以此为当前时间；
+// 未被推进时(实盘)使用系统时间
+#[derive(Debug)]
+pub struct SimulatedClock {
+    started_at: AtomicI64, // 第一个tick的时间(毫秒)
+    current: AtomicI64,    // 最近一个tick的时间(毫秒)
+}
+
+impl Default for SimulatedClock {
+    fn default() -> Self {
+        SimulatedClock {
+            started_at: AtomicI64::new(NOT_STARTED),
+            current: AtomicI64::new(NOT_STARTED),
+        }
+    }
+}
+
+impl SimulatedClock {
+    // 推进到tick时间，时间不会倒退
+    pub fn advance(&self, timestamp_millis: i64) {
+        let _ = self.started_at.compare_exchange(
+            NOT_STARTED,
+            timestamp_millis,
+            Ordering::Relaxed,
+            Ordering::Relaxed,
+        );
+        self.current.fetch_max(timestamp_millis, Ordering::Relaxed);
+    }
+
+    // 是否由回测数据驱动
+    pub fn is_simulated(&self) -> bool {
+        self.current.load(Ordering::Relaxed) != NOT_STARTED
+    }
+
+    pub fn now(&self) -> DateTime<Utc> {
+        self.simulated(&self.current).unwrap_or_else(Utc::now)
+    }
+
+    // 模拟时间的起点，未被推进时为空
+    pub fn started_at(&self) -> Option<DateTime<Utc>> {
+        self.simulated(&self.started_at)
+    }
+
+    // 模拟时间的跨度(微秒)，未被推进时为空
+    pub fn elapsed_micros(&self) -> Option<u128> {
+        let started_at = self.started_at.load(Ordering::Relaxed);
+        let current = self.current.load(Ordering::Relaxed);
+
+        if started_at == NOT_STARTED || current == NOT_STARTED {
+            return None;
+        }
+
+        Some((current - started_at).max(0) as u128 * 1000)
+    }
+
+    fn simulated(&self, millis: &AtomicI64) -> Option<DateTime<Utc>> {
+        match millis.load(Ordering::Relaxed) {
+            NOT_STARTED => None,
+            millis => DateTime::from_timestamp_millis(millis),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simulated_clock() {
+        let clock = SimulatedClock::default();
+        assert!(!clock.is_simulated());
+        assert_eq!(clock.started_at(), None);
+        assert_eq!(clock.elapsed_micros(), None);
+
+        clock.advance(1_704_067_200_000);
+        clock.advance(1_704_153_600_000);
+        // 时间不会倒退
+        clock.advance(1_704_067_201_000);
+
+        assert!(clock.is_simulated());
+        assert_eq!(
+            clock.started_at(),
+            DateTime::from_timestamp_millis(1_704_067_200_000)
+        );
+        assert_eq!(
+            clock.now(),
+            DateTime::from_timestamp_millis(1_704_153_600_000).unwrap()
+        );
+        assert_eq!(clock.elapsed_micros(), Some(86_400_000_000));
+    }
+}
@@ -0,0 +1,79 @@
+use anyhow::{anyhow, Result};
+use comfy_quant_base::{Credentials, SecretCipher};
+use comfy_quant_database::api_key;
+use sqlx::PgPool;
+use std::str::FromStr;
+
+const ENV_PREFIX: &str = "env:";
+
+// 账户节点参数中的密钥引用，工作流JSON中不保存明文密钥，运行时再解析
+//  "main"         数据库 api_keys 表中名为 main 的加密密钥
+//  "env:BINANCE"  环境变量 BINANCE_API_KEY 和 BINANCE_SECRET_KEY
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum CredentialRef {
+    Stored(String), // 数据库中保存的密钥名称
+    Env(String),    // 环境变量前缀
+}
+
+impl FromStr for CredentialRef {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (credential_ref, name) = match s.strip_prefix(ENV_PREFIX) {
+            Some(prefix) => (CredentialRef::Env(prefix.to_string()), prefix),
+            None => (CredentialRef::Stored(s.to_string()), s),
+        };
+
+        anyhow::ensure!(
+            !name.is_empty() && !name.contains(char::is_whitespace),
+            "Invalid credential reference: {}",
+            s
+        );
+
+        Ok(credential_ref)
+    }
+}
+
+impl CredentialRef {
+    pub(crate) async fn resolve(&self, db: &PgPool) -> Result<Credentials> {
+        match self {
+            CredentialRef::Stored(name) => {
+                let row = api_key::get_by_name(db, name)
+                    .await?
+                    .ok_or_else(|| anyhow!("Credential {} not found", name))?;
+
+                row.decrypt(&SecretCipher::from_env()?)
+            }
+            CredentialRef::Env(prefix) => {
+                let var = |suffix: &str| {
+                    let key = format!("{}_{}", prefix, suffix);
+                    std::env::var(&key).map_err(|_| anyhow!("Missing environment variable {}", key))
+                };
+
+                Ok(Credentials::new(var("API_KEY")?, var("SECRET_KEY")?))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_credential_ref_from_str() -> Result<()> {
+        assert_eq!(
+            "main".parse::<CredentialRef>()?,
+            CredentialRef::Stored("main".to_string())
+        );
+        assert_eq!(
+            "env:BINANCE".parse::<CredentialRef>()?,
+            CredentialRef::Env("BINANCE".to_string())
+        );
+        assert!("".parse::<CredentialRef>().is_err());
+        assert!("env:".parse::<CredentialRef>().is_err());
+        assert!("api key".parse::<CredentialRef>().is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,67 @@
+use super::GraphIssue;
+use serde::Serialize;
+use std::fmt;
+
+// 工作流校验发现的问题，前端据此在对应节点和字段上提示
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Diagnostic {
+    pub node_id: Option<u32>, // 问题所在节点，连接本身的问题为 None
+    pub field: String,        // 问题字段，如 properties.params、inputs[2]
+    pub message: String,      // 问题描述
+}
+
+impl Diagnostic {
+    pub(crate) fn node(node_id: u32, field: impl Into<String>, message: impl Into<String>) -> Self {
+        Diagnostic {
+            node_id: Some(node_id),
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+
+    pub(crate) fn link(message: impl Into<String>) -> Self {
+        Diagnostic {
+            node_id: None,
+            field: "links".to_string(),
+            message: message.into(),
+        }
+    }
+
+    // 连接图的问题，循环依赖在环上的每个节点各报告一次
+    pub(crate) fn from_graph_issue(issue: &GraphIssue) -> Vec<Self> {
+        match issue {
+            GraphIssue::DuplicateNode { node_id } => {
+                vec![Diagnostic::node(*node_id, "id", issue.to_string())]
+            }
+            GraphIssue::DanglingLink { .. } => vec![Diagnostic::link(issue.to_string())],
+            GraphIssue::Cycle { node_ids } => node_ids
+                .iter()
+                .map(|node_id| Diagnostic::node(*node_id, "inputs", issue.to_string()))
+                .collect(),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.node_id {
+            Some(node_id) => write!(f, "node {} {}: {}", node_id, self.field, self.message),
+            None => write!(f, "{}: {}", self.field, self.message),
+        }
+    }
+}
+
+// 工作流校验失败，列出全部问题
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq, Serialize)]
+#[error("Invalid workflow: {}", join_diagnostics(.diagnostics))]
+pub struct ValidationError {
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+fn join_diagnostics(diagnostics: &[Diagnostic]) -> String {
+    diagnostics
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
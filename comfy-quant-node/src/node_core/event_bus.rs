@@ -0,0 +1,324 @@
+use rust_decimal::Decimal;
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+const EVENT_BUS_CAPACITY: usize = 1024; // 订阅者处理过慢时丢弃最早的事件
+
+// 工作流运行时事件，推送给界面展示运行进度
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WorkflowEvent {
+    // 节点开始执行
+    NodeStarted {
+        node_id: u32,      // 节点ID
+        node_type: String, // 节点类型
+    },
+    // 节点执行结束
+    NodeFinished {
+        node_id: u32,          // 节点ID
+        node_type: String,     // 节点类型
+        error: Option<String>, // 执行失败时的错误
+    },
+    // 节点失败后按重启策略等待重启
+    NodeRestarting {
+        node_id: u32,          // 节点ID
+        node_type: String,     // 节点类型
+        attempt: u32,          // 第几次重启
+        delay_ms: u64,         // 重启前等待时间(毫秒)
+        error: Option<String>, // 失败的错误，看门狗重启时为空
+    },
+    // 策略节点处理了一个tick
+    TickConsumed {
+        node_id: u32,     // 节点ID
+        exchange: String, // 交易所
+        symbol: String,   // 交易对
+        timestamp: i64,   // tick时间(秒)
+        price: Decimal,   // 价格
+    },
+    // 策略交易对的最新市值和盈亏，金额以交易对的计价资产计，用于跨工作流的统计聚合
+    EquityUpdated {
+        node_id: u32,            // 节点ID
+        exchange: String,        // 交易所
+        symbol: String,          // 交易对
+        quote_asset: String,     // 计价资产
+        equity: Decimal,         // 持仓市值
+        realized_pnl: Decimal,   // 已实现盈亏
+        unrealized_pnl: Decimal, // 未实现盈亏
+        exposure: Decimal,       // 基础资产敞口市值
+        timestamp: i64,          // tick时间(秒)
+    },
+    // 策略节点下单
+    OrderPlaced {
+        node_id: u32,     // 节点ID
+        exchange: String, // 交易所
+        symbol: String,   // 交易对
+        order_id: String, // 订单ID
+        side: String,     // 方向
+        status: String,   // 订单状态
+        price: String,    // 订单价格
+        qty: String,      // 订单数量
+        time: i64,        // 订单时间(毫秒)
+    },
+    // 订单成交，包括下单即成交和交易所推送的后续成交
+    OrderFilled {
+        node_id: u32,     // 节点ID
+        exchange: String, // 交易所
+        symbol: String,   // 交易对
+        order_id: String, // 订单ID
+        side: String,     // 方向
+        price: String,    // 成交均价
+        qty: String,      // 累计成交数量
+        time: i64,        // 更新时间(毫秒)
+    },
+    // 节点运行中出现的错误，节点继续运行
+    Error {
+        node_id: u32,    // 节点ID
+        message: String, // 错误信息
+    },
+    // 回测数据回放进度
+    BacktestProgress {
+        node_id: u32,          // 节点ID
+        processed: u64,        // 已回放数量
+        total: u64,            // 总数量
+        percent: u8,           // 百分比
+        eta_secs: Option<u64>, // 预计剩余时间(秒)
+    },
+    // 策略统计更新
+    StatsUpdated {
+        node_id: u32,             // 节点ID
+        exchange: String,         // 交易所
+        symbol: String,           // 交易对
+        stats: serde_json::Value, // 统计数据
+    },
+    // 风控规则触发，拒绝下单或清仓
+    RiskTriggered {
+        node_id: u32,     // 风控节点ID
+        exchange: String, // 交易所
+        symbol: String,   // 触发时下单的交易对，定时检查触发时为空
+        rule: String,     // 规则: max_drawdown、daily_loss 或 max_position
+        value: Decimal,   // 触发时的数值
+        limit: Decimal,   // 限额
+        action: String,   // 处理方式: block 或 liquidate
+    },
+    // 策略触发止损或止盈
+    StopTriggered {
+        node_id: u32,     // 节点ID
+        exchange: String, // 交易所
+        symbol: String,   // 交易对
+        kind: String,     // stop_loss 或 take_profit
+        price: Decimal,   // 触发价格
+    },
+}
+
+impl WorkflowEvent {
+    // 记录日志和指标
+    pub(crate) fn log(&self) {
+        match self {
+            WorkflowEvent::NodeStarted { node_id, node_type } => tracing::info!(
+                monotonic_counter.workflow_node_started = 1_u64,
+                node_id,
+                node_type = %node_type,
+                "Node started"
+            ),
+            WorkflowEvent::NodeFinished {
+                node_id,
+                node_type,
+                error: Some(error),
+            } => tracing::error!(
+                monotonic_counter.workflow_node_failed = 1_u64,
+                node_id,
+                node_type = %node_type,
+                "Node failed: {}",
+                error
+            ),
+            WorkflowEvent::NodeFinished {
+                node_id, node_type, ..
+            } => tracing::info!(node_id, node_type = %node_type, "Node finished"),
+            WorkflowEvent::NodeRestarting {
+                node_id,
+                node_type,
+                attempt,
+                delay_ms,
+                error,
+            } => tracing::warn!(
+                monotonic_counter.workflow_node_restarted = 1_u64,
+                node_id,
+                node_type = %node_type,
+                attempt,
+                delay_ms,
+                "Node restarting: {}",
+                error.as_deref().unwrap_or("watchdog timeout")
+            ),
+            WorkflowEvent::TickConsumed { .. } | WorkflowEvent::EquityUpdated { .. } => {}
+            WorkflowEvent::OrderPlaced {
+                node_id,
+                exchange,
+                symbol,
+                order_id,
+                side,
+                status,
+                price,
+                qty,
+                ..
+            } => tracing::info!(
+                monotonic_counter.spot_order_placed = 1_u64,
+                node_id,
+                exchange = %exchange,
+                symbol = %symbol,
+                order_id = %order_id,
+                "Order placed: {} {} @ {} {}",
+                side,
+                qty,
+                price,
+                status
+            ),
+            WorkflowEvent::OrderFilled {
+                node_id,
+                exchange,
+                symbol,
+                order_id,
+                side,
+                price,
+                qty,
+                ..
+            } => tracing::info!(
+                monotonic_counter.spot_order_filled = 1_u64,
+                node_id,
+                exchange = %exchange,
+                symbol = %symbol,
+                order_id = %order_id,
+                "Order filled: {} {} @ {}",
+                side,
+                qty,
+                price
+            ),
+            WorkflowEvent::Error { node_id, message } => tracing::error!(
+                monotonic_counter.workflow_node_error = 1_u64,
+                node_id,
+                "{}",
+                message
+            ),
+            // 每10%记录一次
+            WorkflowEvent::BacktestProgress {
+                node_id,
+                percent,
+                eta_secs,
+                ..
+            } if percent % 10 == 0 => tracing::info!(
+                node_id,
+                "Backtest progress: {}%, eta: {:?}s",
+                percent,
+                eta_secs
+            ),
+            WorkflowEvent::BacktestProgress { .. } => {}
+            WorkflowEvent::StatsUpdated {
+                node_id,
+                exchange,
+                symbol,
+                ..
+            } => tracing::debug!(
+                node_id,
+                exchange = %exchange,
+                symbol = %symbol,
+                "Stats updated"
+            ),
+            WorkflowEvent::RiskTriggered {
+                node_id,
+                exchange,
+                symbol,
+                rule,
+                value,
+                limit,
+                action,
+            } => tracing::warn!(
+                monotonic_counter.risk_triggered = 1_u64,
+                node_id,
+                exchange = %exchange,
+                symbol = %symbol,
+                rule = %rule,
+                "Risk triggered: {} {} exceeds {}, {}",
+                rule,
+                value,
+                limit,
+                action
+            ),
+            WorkflowEvent::StopTriggered {
+                node_id,
+                exchange,
+                symbol,
+                kind,
+                price,
+            } => tracing::warn!(
+                monotonic_counter.strategy_stop_triggered = 1_u64,
+                node_id,
+                exchange = %exchange,
+                symbol = %symbol,
+                "Stop triggered: {} @ {}",
+                kind,
+                price
+            ),
+        }
+    }
+}
+
+// 工作流内的中心事件总线，所有节点发布，API、告警等订阅；
+// 日志在发布时同步记录，不会因为订阅者处理不及时丢失；没有订阅者时事件直接丢弃
+#[derive(Debug)]
+pub struct EventBus {
+    tx: broadcast::Sender<WorkflowEvent>,
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        let (tx, _) = broadcast::channel(EVENT_BUS_CAPACITY);
+        EventBus { tx }
+    }
+}
+
+impl EventBus {
+    pub fn subscribe(&self) -> broadcast::Receiver<WorkflowEvent> {
+        self.tx.subscribe()
+    }
+
+    // 是否有订阅者，构造事件代价较高时先判断
+    pub fn has_subscribers(&self) -> bool {
+        self.tx.receiver_count() > 0
+    }
+
+    pub fn publish(&self, event: WorkflowEvent) {
+        event.log();
+        let _ = self.tx.send(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_event_bus() -> anyhow::Result<()> {
+        let bus = EventBus::default();
+        let started = WorkflowEvent::NodeStarted {
+            node_id: 1,
+            node_type: "strategy.SpotGrid".to_string(),
+        };
+
+        // 没有订阅者时丢弃
+        assert!(!bus.has_subscribers());
+        bus.publish(started.clone());
+
+        let mut rx = bus.subscribe();
+        assert!(bus.has_subscribers());
+        bus.publish(started.clone());
+
+        assert_eq!(rx.recv().await?, started);
+        assert!(rx.try_recv().is_err());
+
+        assert_eq!(
+            serde_json::to_string(&started)?,
+            r#"{"type":"node_started","node_id":1,"node_type":"strategy.SpotGrid"}"#
+        );
+
+        Ok(())
+    }
+}
@@ -1,8 +1,8 @@
-use bon::bon;
+use bon::{bon, Builder};
 use chrono::{DateTime, Utc};
 use rust_decimal::{prelude::ToPrimitive, Decimal, MathematicalOps};
 use rust_decimal_macros::dec;
-use std::collections::{HashMap, HashSet};
+use std::{collections::HashMap, fmt};
 
 #[derive(Debug, Clone, Hash, Eq, PartialEq)]
 pub struct CurrencyPair {
@@ -64,37 +64,68 @@ struct RateSource {
     datetime: DateTime<Utc>,
 }
 
+// 汇率换算的诊断信息：使用的换算路径，以及被跳过的路径和原因
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RateDiagnostic {
+    pub path: Option<Vec<String>>, // 使用的换算路径，如 BTC -> USDT -> CNY，未找到时为空
+    pub age_secs: Option<i64>,     // 路径上最旧的汇率距今的秒数
+    pub skipped: Vec<String>,      // 被跳过的路径及原因
+}
+
+impl fmt::Display for RateDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.path, self.age_secs) {
+            (Some(path), Some(age_secs)) => {
+                write!(f, "path {}, age {}s", path.join(" -> "), age_secs)?
+            }
+            _ => write!(f, "no path found")?,
+        }
+
+        if !self.skipped.is_empty() {
+            write!(f, ", skipped: {}", self.skipped.join("; "))?;
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(Debug)]
 pub struct ExchangeRateManager {
     // 每个币对在不同交易所的原始汇率
     source_rates: HashMap<CurrencyPair, HashMap<String, RateSource>>,
     // 融合后的汇率缓存
     merged_rates: HashMap<CurrencyPair, ExchangeRate>,
-    // 中间货币列表
-    intermediate_currencies: HashSet<String>,
+    // 中间货币候选列表，按顺序尝试：先是配置的货币，再按出现顺序追加汇率中的货币
+    intermediate_currencies: Vec<String>,
+    // 每个币对最近一次换算的诊断信息
+    diagnostics: HashMap<CurrencyPair, RateDiagnostic>,
     // 交易所权重配置
     exchange_weights: HashMap<String, Decimal>,
     // 配置参数
     config: RateManagerConfig,
 }
 
-#[derive(Debug)]
-struct RateManagerConfig {
-    // 异常值阈值(与均值的最大偏差百分比)
+#[derive(Builder, Debug, Clone)]
+pub struct RateManagerConfig {
+    // 异常值阈值(与均值的最大偏差百分比)，默认10%偏差
+    #[builder(default = dec!(0.1))]
     outlier_threshold: Decimal,
-    // 时效性权重衰减系数(每小时)
+    // 时效性权重衰减系数(每小时)，默认每小时衰减10%
+    #[builder(default = dec!(0.9))]
     time_decay_factor: Decimal,
     // 缓存过期时间(秒)
+    #[builder(default = 1)]
     cache_ttl: i64,
+    // 换算路径最多包含的汇率数量，默认2即最多经过一个中间货币
+    #[builder(default = 2)]
+    max_path_len: usize,
+    // 换算路径上最旧的汇率距今的最大秒数，超过时尝试下一条路径，未设置时不限制
+    max_staleness_secs: Option<i64>,
 }
 
 impl Default for RateManagerConfig {
     fn default() -> Self {
-        Self {
-            outlier_threshold: dec!(0.1), // 10%偏差
-            time_decay_factor: dec!(0.9), // 每小时衰减10%
-            cache_ttl: 1,                 // 1秒缓存
-        }
+        Self::builder().build()
     }
 }
 
@@ -107,15 +138,28 @@ impl ExchangeRateManager {
         exchange_weights: Option<Vec<(String, Decimal)>>,
         config: Option<RateManagerConfig>,
     ) -> Self {
-        ExchangeRateManager {
+        let mut manager = ExchangeRateManager {
             source_rates: HashMap::new(),
             merged_rates: HashMap::new(),
-            intermediate_currencies: intermediate_currencies
-                .unwrap_or_default()
-                .into_iter()
-                .collect(),
+            intermediate_currencies: Vec::new(),
+            diagnostics: HashMap::new(),
             exchange_weights: exchange_weights.unwrap_or_default().into_iter().collect(),
             config: config.unwrap_or_default(),
+        };
+
+        for currency in intermediate_currencies.unwrap_or_default() {
+            manager.add_intermediate_currency(currency);
+        }
+
+        manager
+    }
+
+    // 追加中间货币候选，已存在时保持原有顺序
+    fn add_intermediate_currency(&mut self, currency: impl Into<String>) {
+        let currency = currency.into();
+
+        if !self.intermediate_currencies.contains(&currency) {
+            self.intermediate_currencies.push(currency);
         }
     }
 
@@ -148,8 +192,8 @@ impl ExchangeRateManager {
         );
 
         // 添加中间货币
-        self.intermediate_currencies.insert(pair.base.clone());
-        self.intermediate_currencies.insert(pair.quote.clone());
+        self.add_intermediate_currency(&pair.base);
+        self.add_intermediate_currency(&pair.quote);
 
         // 清除受影响的缓存
         self.merged_rates.remove(&pair);
@@ -175,37 +219,138 @@ impl ExchangeRateManager {
             }
         }
 
-        // 1. 尝试获取直接汇率
-        if let Some(merged_rate) = self.calculate_merged_rate(&pair) {
-            self.merged_rates.insert(pair, merged_rate.clone());
-            return Some(merged_rate);
+        // 按路径长度从短到长、中间货币按候选顺序查找，先直接汇率再间接汇率，
+        // 结果与哈希顺序无关
+        let now = Utc::now();
+        let mut diagnostic = RateDiagnostic::default();
+        let mut path = vec![pair.base.clone()];
+        let mut found = None;
+
+        for len in 1..=self.config.max_path_len.max(1) {
+            found = self.find_path(&pair.quote, len, now, &mut path, &mut diagnostic);
+
+            if found.is_some() {
+                break;
+            }
+        }
+
+        let result = found.map(|(path, rates, age_secs)| {
+            let rate = rates.iter().map(|rate| rate.rate).product::<Decimal>();
+            // 来源格式: 来源->中间货币->来源
+            let source = rates
+                .iter()
+                .zip(path.iter().skip(1))
+                .flat_map(|(rate, next)| [rate.source.clone(), next.clone()])
+                .take(rates.len() * 2 - 1)
+                .collect::<Vec<_>>()
+                .join("->");
+            let datetime = rates.iter().map(|rate| rate.datetime).max().unwrap_or(now);
+
+            diagnostic.path = Some(path);
+            diagnostic.age_secs = Some(age_secs);
+
+            ExchangeRate::new(rate, source, datetime)
+        });
+
+        tracing::debug!("Exchange rate {}/{}: {}", pair.base, pair.quote, diagnostic);
+
+        if let Some(merged_rate) = &result {
+            self.merged_rates.insert(pair.clone(), merged_rate.clone());
         }
 
-        // 2. 尝试通过中间货币获取间接汇率
-        for intermediate in &self.intermediate_currencies {
-            let pair1 = CurrencyPair::new(base.as_ref(), intermediate);
-            let pair2 = CurrencyPair::new(intermediate, quote.as_ref());
-
-            if let (Some(rate1), Some(rate2)) = (
-                self.get_rate_internal(&pair1),
-                self.get_rate_internal(&pair2),
-            ) {
-                let merged_rate = ExchangeRate::new(
-                    rate1.rate * rate2.rate,
-                    format!("{}->{}->{}", rate1.source, intermediate, rate2.source),
-                    rate1.datetime.max(rate2.datetime),
-                );
-                self.merged_rates.insert(pair.clone(), merged_rate.clone());
-                return Some(merged_rate);
+        self.diagnostics.insert(pair, diagnostic);
+
+        result
+    }
+
+    // 最近一次换算的诊断信息
+    pub fn diagnostic(
+        &self,
+        base: impl AsRef<str>,
+        quote: impl AsRef<str>,
+    ) -> Option<&RateDiagnostic> {
+        self.diagnostics
+            .get(&CurrencyPair::new(base.as_ref(), quote.as_ref()))
+    }
+
+    // 深度优先查找恰好由 len 个汇率组成的路径，返回路径、各段汇率和路径上最旧汇率的秒数。
+    // 路径的时效取最旧的一段，超过时效的汇率所在的路径都会被跳过
+    fn find_path(
+        &self,
+        quote: &str,
+        len: usize,
+        now: DateTime<Utc>,
+        path: &mut Vec<String>,
+        diagnostic: &mut RateDiagnostic,
+    ) -> Option<(Vec<String>, Vec<ExchangeRate>, i64)> {
+        let from = path.last()?.clone();
+
+        let nexts = if len == 1 {
+            vec![quote.to_string()]
+        } else {
+            self.intermediate_currencies
+                .iter()
+                .filter(|currency| *currency != quote && !path.contains(currency))
+                .cloned()
+                .collect()
+        };
+
+        for next in nexts {
+            let pair = CurrencyPair::new(&from, &next);
+            let Some(rate) = self.calculate_merged_rate(&pair) else {
+                continue;
+            };
+            let age_secs = self.rate_age(&pair, now).unwrap_or_default();
+
+            if let Some(max_staleness_secs) = self.config.max_staleness_secs {
+                if age_secs > max_staleness_secs {
+                    let reason = format!(
+                        "{} -> {} is stale ({}s > {}s)",
+                        from, next, age_secs, max_staleness_secs
+                    );
+
+                    if !diagnostic.skipped.contains(&reason) {
+                        diagnostic.skipped.push(reason);
+                    }
+
+                    continue;
+                }
+            }
+
+            path.push(next);
+
+            let found = if len == 1 {
+                Some((path.clone(), vec![rate], age_secs))
+            } else {
+                self.find_path(quote, len - 1, now, path, diagnostic).map(
+                    |(path, mut rates, age)| {
+                        rates.insert(0, rate);
+                        (path, rates, age.max(age_secs))
+                    },
+                )
+            };
+
+            path.pop();
+
+            if found.is_some() {
+                return found;
             }
         }
 
         None
     }
 
-    // 内部获取汇率方法(不使用缓存)
-    fn get_rate_internal(&self, pair: &CurrencyPair) -> Option<ExchangeRate> {
-        self.calculate_merged_rate(pair)
+    // 币对最新的数据源汇率距今的秒数
+    fn rate_age(&self, pair: &CurrencyPair, now: DateTime<Utc>) -> Option<i64> {
+        self.source_rates
+            .get(pair)?
+            .values()
+            .map(|source| {
+                now.signed_duration_since(source.datetime)
+                    .num_seconds()
+                    .max(0)
+            })
+            .min()
     }
 
     // 计算融合汇率
@@ -342,4 +487,79 @@ mod tests {
         let rate = manager.get_rate("USDT", "CNY").unwrap();
         assert_eq!(rate.rate, dec!(7.17));
     }
+
+    #[test]
+    fn test_conversion_path() {
+        let now = Utc::now();
+        let stale = now - chrono::Duration::hours(2);
+        let config = |max_path_len, max_staleness_secs| {
+            RateManagerConfig::builder()
+                .max_path_len(max_path_len)
+                .maybe_max_staleness_secs(max_staleness_secs)
+                .build()
+        };
+        let manager = |config| {
+            let mut manager = ExchangeRateManager::builder()
+                .intermediate_currencies(vec!["FDUSD".into(), "USDT".into()])
+                .config(config)
+                .build();
+            manager.update_rate("ETH", "BTC", dec!(0.05), "binance", now);
+            manager.update_rate("BTC", "USDT", dec!(50000), "binance", now);
+            manager.update_rate("USDT", "CNY", dec!(7), "binance", stale);
+            manager.update_rate("BTC", "FDUSD", dec!(49000), "binance", now);
+            manager.update_rate("FDUSD", "CNY", dec!(7.1), "binance", now);
+            manager
+        };
+
+        // 按候选顺序优先经过FDUSD
+        let mut default_manager = manager(config(2, None));
+        let rate = default_manager.get_rate("BTC", "CNY").unwrap();
+        assert_eq!(rate.rate, dec!(347900));
+        assert_eq!(rate.source, "merged->FDUSD->merged");
+
+        let diagnostic = default_manager.diagnostic("BTC", "CNY").unwrap();
+        assert_eq!(
+            diagnostic.path,
+            Some(vec![
+                "BTC".to_string(),
+                "FDUSD".to_string(),
+                "CNY".to_string()
+            ])
+        );
+
+        // 路径长度限制
+        assert!(default_manager.get_rate("ETH", "CNY").is_none());
+        assert_eq!(
+            default_manager
+                .diagnostic("ETH", "CNY")
+                .unwrap()
+                .to_string(),
+            "no path found"
+        );
+
+        let mut long_manager = manager(config(3, None));
+        let rate = long_manager.get_rate("ETH", "CNY").unwrap();
+        assert_eq!(rate.rate, dec!(17395));
+        assert_eq!(
+            long_manager.diagnostic("ETH", "CNY").unwrap().path,
+            Some(vec![
+                "ETH".to_string(),
+                "BTC".to_string(),
+                "FDUSD".to_string(),
+                "CNY".to_string()
+            ])
+        );
+
+        // 超过时效的汇率所在路径被跳过
+        let mut fresh_manager = manager(config(2, Some(3600)));
+        assert!(fresh_manager.get_rate("USDT", "CNY").is_none());
+
+        let rate = fresh_manager.get_rate("USDT", "FDUSD").unwrap();
+        assert_eq!(rate.source, "merged->BTC->merged");
+        assert!(fresh_manager
+            .diagnostic("USDT", "CNY")
+            .unwrap()
+            .to_string()
+            .contains("USDT -> CNY is stale"));
+    }
 }
@@ -0,0 +1,235 @@
+use super::{Bar, Tick};
+use comfy_quant_base::{KlineInterval, Symbol};
+use comfy_quant_indicators::{Atr, Indicator, Ohlc};
+use rust_decimal::Decimal;
+use std::collections::VecDeque;
+
+// 最近N根K线的滚动窗口，用数据库中的历史K线初始化，之后由tick或K线增量更新
+#[derive(Debug, Clone)]
+#[allow(unused)]
+pub struct KlinesWindow {
+    symbol: Symbol,          // 交易对
+    interval: KlineInterval, // 时间间隔
+    len: usize,              // 窗口长度
+    bars: VecDeque<Bar>,     // 按开盘时间升序
+}
+
+#[allow(unused)]
+impl KlinesWindow {
+    pub fn new(symbol: Symbol, interval: KlineInterval, len: usize) -> Self {
+        KlinesWindow {
+            symbol,
+            interval,
+            len,
+            bars: VecDeque::with_capacity(len + 1),
+        }
+    }
+
+    pub fn with_bars(mut self, bars: impl IntoIterator<Item = Bar>) -> Self {
+        for bar in bars {
+            self.update_with_bar(bar);
+        }
+
+        self
+    }
+
+    // 同一开盘时间的K线替换最后一根，更早的K线忽略
+    pub fn update_with_bar(&mut self, bar: Bar) {
+        if bar.symbol != self.symbol || bar.interval != self.interval {
+            return;
+        }
+
+        match self.bars.back() {
+            Some(last) if bar.open_millis() < last.open_millis() => return,
+            Some(last) if bar.open_millis() == last.open_millis() => {
+                self.bars.pop_back();
+            }
+            _ => {}
+        }
+
+        self.push(bar);
+    }
+
+    // tick聚合到所在周期的K线
+    pub fn update_with_tick(&mut self, tick: &Tick) {
+        if tick.symbol != self.symbol {
+            return;
+        }
+
+        // 按成交笔数聚合的K线没有固定周期，只能由K线更新
+        let interval_millis = self.interval.to_millis();
+        if interval_millis == 0 {
+            return;
+        }
+
+        let millis = tick.timestamp_millis();
+        let open_millis = millis - millis.rem_euclid(interval_millis);
+
+        match self.bars.back_mut() {
+            Some(last) if open_millis < last.open_millis() => {}
+            Some(last) if open_millis == last.open_millis() => {
+                last.high = last.high.max(tick.price);
+                last.low = last.low.min(tick.price);
+                last.close = tick.price;
+                last.volume += tick.volume;
+                last.taker_buy_volume += tick.taker_buy_volume;
+            }
+            _ => {
+                let bar = Bar::builder()
+                    .symbol(self.symbol.clone())
+                    .interval(self.interval.clone())
+                    .open_time(open_millis.div_euclid(1000))
+                    .maybe_open_time_millis((interval_millis < 1000).then_some(open_millis))
+                    .open(tick.price)
+                    .high(tick.price)
+                    .low(tick.price)
+                    .close(tick.price)
+                    .volume(tick.volume)
+                    .taker_buy_volume(tick.taker_buy_volume)
+                    .build();
+
+                self.push(bar);
+            }
+        }
+    }
+
+    fn push(&mut self, bar: Bar) {
+        self.bars.push_back(bar);
+
+        while self.bars.len() > self.len {
+            self.bars.pop_front();
+        }
+    }
+
+    pub fn bars(&self) -> &VecDeque<Bar> {
+        &self.bars
+    }
+
+    pub fn last(&self) -> Option<&Bar> {
+        self.bars.back()
+    }
+
+    pub fn closes(&self) -> Vec<Decimal> {
+        self.bars.iter().map(|bar| bar.close).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.bars.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bars.is_empty()
+    }
+
+    // 窗口已填满
+    pub fn is_full(&self) -> bool {
+        self.bars.len() >= self.len
+    }
+
+    // 窗口内K线的平均真实波幅(Wilder平滑)，K线数量不足period时返回None
+    pub fn atr(&self, period: usize) -> Option<Decimal> {
+        let mut atr = Atr::new(period).ok()?;
+
+        self.bars
+            .iter()
+            .fold(None, |_, bar| atr.update(Ohlc::from(bar)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn bar(open_time: i64, close: Decimal) -> Bar {
+        Bar::builder()
+            .symbol("BTCUSDT".into())
+            .interval(KlineInterval::OneMinute)
+            .open_time(open_time)
+            .open(close)
+            .high(close)
+            .low(close)
+            .close(close)
+            .volume(dec!(1))
+            .build()
+    }
+
+    fn tick(timestamp: i64, price: Decimal) -> Tick {
+        Tick::builder()
+            .timestamp(timestamp)
+            .symbol("BTCUSDT".into())
+            .price(price)
+            .volume(dec!(1))
+            .build()
+    }
+
+    #[test]
+    fn test_klines_window_update_with_bar() {
+        let mut window = KlinesWindow::new("BTCUSDT".into(), KlineInterval::OneMinute, 2)
+            .with_bars([bar(0, dec!(1)), bar(60, dec!(2))]);
+
+        assert!(window.is_full());
+
+        // 同一根K线更新
+        window.update_with_bar(bar(60, dec!(3)));
+        assert_eq!(window.closes(), vec![dec!(1), dec!(3)]);
+
+        window.update_with_bar(bar(120, dec!(4)));
+        assert_eq!(window.closes(), vec![dec!(3), dec!(4)]);
+
+        // 过期的K线忽略
+        window.update_with_bar(bar(0, dec!(5)));
+        assert_eq!(window.closes(), vec![dec!(3), dec!(4)]);
+    }
+
+    #[test]
+    fn test_klines_window_update_with_tick() {
+        let mut window = KlinesWindow::new("BTCUSDT".into(), KlineInterval::OneMinute, 3)
+            .with_bars([bar(0, dec!(100))]);
+
+        window.update_with_tick(&tick(60, dec!(101)));
+        window.update_with_tick(&tick(90, dec!(99)));
+        window.update_with_tick(&tick(119, dec!(100)));
+
+        let last = window.last().unwrap();
+        assert_eq!(window.len(), 2);
+        assert_eq!(last.open_time, 60);
+        assert_eq!(last.open, dec!(101));
+        assert_eq!(last.high, dec!(101));
+        assert_eq!(last.low, dec!(99));
+        assert_eq!(last.close, dec!(100));
+        assert_eq!(last.volume, dec!(3));
+
+        window.update_with_tick(&tick(120, dec!(102)));
+        assert_eq!(window.closes(), vec![dec!(100), dec!(100), dec!(102)]);
+    }
+
+    #[test]
+    fn test_klines_window_atr() {
+        let mut window = KlinesWindow::new("BTCUSDT".into(), KlineInterval::OneMinute, 10)
+            .with_bars([bar(0, dec!(100))]);
+
+        assert_eq!(window.atr(2), None);
+
+        // 高低价差 4，与前收盘价差 6
+        window.update_with_bar(
+            Bar::builder()
+                .symbol("BTCUSDT".into())
+                .interval(KlineInterval::OneMinute)
+                .open_time(60)
+                .open(dec!(102))
+                .high(dec!(106))
+                .low(dec!(102))
+                .close(dec!(104))
+                .volume(dec!(1))
+                .build(),
+        );
+        window.update_with_bar(bar(120, dec!(102)));
+
+        // 真实波幅依次为 0、6、2，初始值 (0 + 6) / 2，再平滑 (3 * 1 + 2) / 2
+        assert_eq!(window.atr(2), Some(dec!(2.5)));
+        assert_eq!(window.atr(1), Some(dec!(2)));
+        assert_eq!(window.atr(4), None);
+        assert_eq!(window.atr(0), None);
+    }
+}
@@ -1,22 +1,61 @@
+mod bar;
+mod capital_allocator;
 mod client_service;
+mod clock;
+mod credential_ref;
+mod diagnostic;
+mod event_bus;
 mod exchange_rate;
+mod klines_window;
 mod node_context;
+mod node_error;
+mod node_graph;
 mod node_infra;
+mod node_metadata;
 mod port;
+mod position_sizer;
+mod progress;
 mod slot;
 mod slots;
+mod supervisor;
 mod tick;
+mod tick_recorder;
 mod traits;
+mod valuation_policy;
+mod watchdog;
 
+pub(crate) use bar::Bar;
+pub(crate) use client_service::next_user_data;
+pub(crate) use credential_ref::CredentialRef;
+pub(crate) use klines_window::KlinesWindow;
 pub(crate) use node_context::NodeContext;
+pub(crate) use node_graph::NodeGraph;
 pub(crate) use node_infra::NodeInfra;
+pub(crate) use node_metadata::{
+    ANNOUNCEMENT_STREAM, CAPITAL_ALLOCATION, KLINE_STREAM, SPOT_CLIENT, SPOT_PAIR_INFO, TICK_STREAM,
+};
 pub(crate) use port::Port;
+pub(crate) use progress::ProgressTracker;
 pub(crate) use slot::Slot;
+pub(crate) use supervisor::RESTART_RESET_AFTER;
 pub(crate) use tick::Tick;
+pub(crate) use tick_recorder::TickRecorder;
+pub(crate) use watchdog::{Heartbeat, Watchdog};
 
-pub use client_service::SpotClientService;
-pub use exchange_rate::{ExchangeRate, ExchangeRateManager};
+pub use capital_allocator::{AllocationMethod, CapitalAllocator};
+pub use client_service::{PlacedOrder, SpotClientService, SymbolRules};
+pub use clock::SimulatedClock;
+pub use diagnostic::{Diagnostic, ValidationError};
+pub use event_bus::{EventBus, WorkflowEvent};
+pub use exchange_rate::{ExchangeRate, ExchangeRateManager, RateDiagnostic, RateManagerConfig};
+pub use node_error::{ErrorAction, NodeError};
+pub use node_graph::{GraphError, GraphIssue};
+pub use node_metadata::{NodeCategory, NodeMeta, NodeMetadata, PortMetadata};
+pub use position_sizer::{OrderFilterError, OrderRules, PositionSizer, SizingMethod};
+pub use supervisor::{NodeHealth, NodeState, RestartPolicy, Supervisor};
+pub use tick_recorder::{replay_ticks, RecordedTick};
 pub use traits::{
     NodeCore, NodeCoreExt, NodeExecutable, NodeSpotStats, NodeSpotStatsExt, SpotTradeable,
-    TradeStats,
+    TradeStats, TradeStatsExt,
 };
+pub use valuation_policy::ValuationPolicy;
@@ -1,13 +1,25 @@
 use std::sync::Arc;
 
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use comfy_quant_database::strategy_journal::{self, CreateJournalParams};
 use sqlx::PgPool;
 
+use super::{EventBus, SimulatedClock, ValuationPolicy, WorkflowEvent};
+use crate::stats::{Event, EventLog, ResourceMeter, WriteBuffer};
+
 #[derive(Debug, Clone)]
 pub struct NodeContext {
     db: Arc<PgPool>,
     workflow_id: String,
     node_id: i16,
     node_name: String,
+    write_buffer: Arc<WriteBuffer>,         // 数据库写缓冲
+    valuation_policy: Arc<ValuationPolicy>, // 估值策略
+    resource_meter: Arc<ResourceMeter>,     // 资源计数器
+    event_log: Arc<EventLog>,               // 回测事件日志
+    event_bus: Arc<EventBus>,               // 运行时事件总线
+    clock: Arc<SimulatedClock>,             // 工作流时钟
 }
 
 impl NodeContext {
@@ -22,9 +34,51 @@ impl NodeContext {
             workflow_id: workflow_id.into(),
             node_id,
             node_name: node_name.into(),
+            write_buffer: Arc::new(WriteBuffer::default()),
+            valuation_policy: Arc::new(ValuationPolicy::default()),
+            resource_meter: Arc::new(ResourceMeter::default()),
+            event_log: Arc::new(EventLog::default()),
+            event_bus: Arc::new(EventBus::default()),
+            clock: Arc::new(SimulatedClock::default()),
         }
     }
 
+    // 共享工作流的数据库写缓冲
+    pub(crate) fn with_write_buffer(mut self, write_buffer: Arc<WriteBuffer>) -> Self {
+        self.write_buffer = write_buffer;
+        self
+    }
+
+    // 共享工作流的估值策略
+    pub(crate) fn with_valuation_policy(mut self, valuation_policy: Arc<ValuationPolicy>) -> Self {
+        self.valuation_policy = valuation_policy;
+        self
+    }
+
+    // 共享工作流的资源计数器
+    pub(crate) fn with_resource_meter(mut self, resource_meter: Arc<ResourceMeter>) -> Self {
+        self.resource_meter = resource_meter;
+        self
+    }
+
+    // 共享工作流的事件日志
+    pub(crate) fn with_event_log(mut self, event_log: Arc<EventLog>) -> Self {
+        self.event_log = event_log;
+        self
+    }
+
+    // 共享工作流的事件总线
+    pub(crate) fn with_event_bus(mut self, event_bus: Arc<EventBus>) -> Self {
+        self.event_bus = event_bus;
+        self
+    }
+
+    // 共享工作流的时钟
+    pub(crate) fn with_clock(mut self, clock: Arc<SimulatedClock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
     pub fn db(&self) -> &PgPool {
         &self.db
     }
@@ -44,4 +98,54 @@ impl NodeContext {
     pub fn node_name(&self) -> &str {
         &self.node_name
     }
+
+    pub(crate) fn write_buffer(&self) -> &WriteBuffer {
+        &self.write_buffer
+    }
+
+    pub fn valuation_policy(&self) -> &ValuationPolicy {
+        &self.valuation_policy
+    }
+
+    pub(crate) fn resource_meter(&self) -> &ResourceMeter {
+        &self.resource_meter
+    }
+
+    // 当前时间，回测时为模拟时间
+    pub fn now(&self) -> DateTime<Utc> {
+        self.clock.now()
+    }
+
+    pub(crate) fn event_log_enabled(&self) -> bool {
+        self.event_log.is_enabled()
+    }
+
+    // 记录事件，工作流未开启事件日志时忽略
+    pub(crate) fn record_event(&self, event: Event) {
+        self.event_log.record(event);
+    }
+
+    pub(crate) fn has_event_subscribers(&self) -> bool {
+        self.event_bus.has_subscribers()
+    }
+
+    // 发布运行时事件，没有订阅者时丢弃
+    pub(crate) fn publish(&self, event: WorkflowEvent) {
+        self.event_bus.publish(event);
+    }
+
+    // 写入策略日志，记录止损、止盈等策略自行干预的原因，时间为当前时间
+    pub(crate) async fn journal(&self, content: impl Into<String>, tags: &[&str]) -> Result<()> {
+        let data = CreateJournalParams::builder()
+            .workflow_id(self.workflow_id.clone())
+            .node_id(self.node_id)
+            .start_time(self.now())
+            .content(content.into())
+            .tags(tags.iter().map(ToString::to_string).collect::<Vec<_>>())
+            .build();
+
+        strategy_journal::create(&self.db, data).await?;
+
+        Ok(())
+    }
 }
@@ -0,0 +1,161 @@
+use binance::errors::{Error as BinanceError, ErrorKind as BinanceErrorKind};
+use std::error::Error as StdError;
+
+// 币安按错误码可重试的错误: 未知错误、断开连接、请求过多、超时、服务繁忙、时间戳超前
+const BINANCE_RETRYABLE_CODES: &[i16] = &[-1000, -1001, -1003, -1006, -1007, -1008, -1021];
+
+// 节点执行失败的分类，工作流执行器据此决定重试、跳过还是终止运行
+#[derive(thiserror::Error, Debug)]
+pub enum NodeError {
+    // 参数、输入连接或账户配置错误，重试无效
+    #[error("Config error: {0}")]
+    Config(anyhow::Error),
+
+    // 行情、K线等数据缺失或格式错误，只影响当前节点
+    #[error("Data error: {0}")]
+    Data(anyhow::Error),
+
+    // 网络超时、限流等交易所临时错误，稍后重试可能成功
+    #[error("Exchange error (retryable): {0}")]
+    ExchangeRetryable(anyhow::Error),
+
+    // 交易所拒绝请求，如密钥无效、余额不足
+    #[error("Exchange error (fatal): {0}")]
+    ExchangeFatal(anyhow::Error),
+
+    // 节点被取消
+    #[error("Cancelled")]
+    Cancelled,
+
+    // 其他未分类错误
+    #[error(transparent)]
+    Internal(anyhow::Error),
+}
+
+// 执行器对节点错误的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorAction {
+    Retry, // 临时错误，按节点重启策略重新执行
+    Skip,  // 只影响当前节点，按节点重启策略处理，工作流继续运行
+    Abort, // 终止整个工作流
+}
+
+impl NodeError {
+    pub fn action(&self) -> ErrorAction {
+        match self {
+            NodeError::ExchangeRetryable(_) => ErrorAction::Retry,
+            NodeError::Config(_) | NodeError::ExchangeFatal(_) => ErrorAction::Abort,
+            NodeError::Data(_) | NodeError::Cancelled | NodeError::Internal(_) => ErrorAction::Skip,
+        }
+    }
+
+    // 错误分类名称，用于事件和日志
+    pub fn kind(&self) -> &'static str {
+        match self {
+            NodeError::Config(_) => "config",
+            NodeError::Data(_) => "data",
+            NodeError::ExchangeRetryable(_) => "exchange_retryable",
+            NodeError::ExchangeFatal(_) => "exchange_fatal",
+            NodeError::Cancelled => "cancelled",
+            NodeError::Internal(_) => "internal",
+        }
+    }
+}
+
+// 节点内部大多返回 anyhow 错误，按错误链中的具体类型分类；
+// 已经分类的 NodeError 经过 anyhow 传递后原样取回
+impl From<anyhow::Error> for NodeError {
+    fn from(error: anyhow::Error) -> Self {
+        let error = match error.downcast::<NodeError>() {
+            Ok(error) => return error,
+            Err(error) => error,
+        };
+
+        match classify(&error) {
+            Some(Class::Retryable) => NodeError::ExchangeRetryable(error),
+            Some(Class::Fatal) => NodeError::ExchangeFatal(error),
+            Some(Class::Data) => NodeError::Data(error),
+            None => NodeError::Internal(error),
+        }
+    }
+}
+
+impl From<reqwest::Error> for NodeError {
+    fn from(error: reqwest::Error) -> Self {
+        anyhow::Error::from(error).into()
+    }
+}
+
+enum Class {
+    Retryable,
+    Fatal,
+    Data,
+}
+
+fn classify(error: &anyhow::Error) -> Option<Class> {
+    error.chain().find_map(classify_cause)
+}
+
+fn classify_cause(cause: &(dyn StdError + 'static)) -> Option<Class> {
+    if cause.is::<tower::timeout::error::Elapsed>() {
+        return Some(Class::Retryable);
+    }
+
+    if let Some(error) = cause.downcast_ref::<reqwest::Error>() {
+        let retryable = error.is_timeout()
+            || error.is_connect()
+            || error
+                .status()
+                .is_some_and(|status| status.is_server_error() || status.as_u16() == 429);
+
+        return Some(if retryable {
+            Class::Retryable
+        } else {
+            Class::Fatal
+        });
+    }
+
+    if let Some(BinanceError(kind, _)) = cause.downcast_ref::<BinanceError>() {
+        return match kind {
+            BinanceErrorKind::BinanceError(response)
+                if BINANCE_RETRYABLE_CODES.contains(&response.code) =>
+            {
+                Some(Class::Retryable)
+            }
+            BinanceErrorKind::ReqError(_) | BinanceErrorKind::IoError(_) => Some(Class::Retryable),
+            _ => Some(Class::Fatal),
+        };
+    }
+
+    if cause.is::<sqlx::Error>() {
+        return Some(Class::Data);
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+
+    #[test]
+    fn test_node_error_from_anyhow() {
+        // 已分类的错误经过 anyhow 后保持分类
+        let error: NodeError = anyhow::Error::from(NodeError::Config(anyhow!("bad input"))).into();
+        assert!(matches!(error, NodeError::Config(_)));
+        assert_eq!(error.action(), ErrorAction::Abort);
+
+        let error: NodeError = anyhow::Error::from(sqlx::Error::RowNotFound)
+            .context("load klines")
+            .into();
+        assert_eq!(error.kind(), "data");
+        assert_eq!(error.action(), ErrorAction::Skip);
+
+        let error: NodeError = anyhow!("boom").into();
+        assert!(matches!(error, NodeError::Internal(_)));
+        assert_eq!(error.to_string(), "boom");
+
+        assert_eq!(NodeError::Cancelled.action(), ErrorAction::Skip);
+    }
+}
@@ -0,0 +1,241 @@
+use serde::Serialize;
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet},
+    fmt,
+};
+
+// 工作流连接图中的问题
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum GraphIssue {
+    // 多个节点使用同一个ID
+    DuplicateNode { node_id: u32 },
+    // 连接的起点或终点节点不存在
+    DanglingLink { link_id: u32, node_id: u32 },
+    // 节点之间存在循环依赖，node_ids 为环上及环之间的节点
+    Cycle { node_ids: Vec<u32> },
+}
+
+impl fmt::Display for GraphIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GraphIssue::DuplicateNode { node_id } => write!(f, "duplicate node {}", node_id),
+            GraphIssue::DanglingLink { link_id, node_id } => {
+                write!(f, "link {} references missing node {}", link_id, node_id)
+            }
+            GraphIssue::Cycle { node_ids } => write!(f, "cycle through nodes {:?}", node_ids),
+        }
+    }
+}
+
+// 工作流连接图校验失败，列出全部问题
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq, Serialize)]
+#[error("Invalid workflow graph: {}", join_issues(.issues))]
+pub struct GraphError {
+    pub issues: Vec<GraphIssue>,
+}
+
+fn join_issues(issues: &[GraphIssue]) -> String {
+    issues
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+// 节点依赖图，由连接确定执行顺序: 上游节点先于下游节点 setup 和执行
+#[derive(Debug, Default)]
+pub(crate) struct NodeGraph {
+    nodes: Vec<(u32, u32)>,      // (节点ID, 前端给出的顺序)
+    links: Vec<(u32, u32, u32)>, // (连接ID, 起点节点ID, 终点节点ID)
+}
+
+impl NodeGraph {
+    pub(crate) fn new(
+        nodes: impl IntoIterator<Item = (u32, u32)>,
+        links: impl IntoIterator<Item = (u32, u32, u32)>,
+    ) -> Self {
+        NodeGraph {
+            nodes: nodes.into_iter().collect(),
+            links: links.into_iter().collect(),
+        }
+    }
+
+    // 有效的连接: 起点和终点节点都存在
+    fn edges(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        let node_ids = self.nodes.iter().map(|(id, _)| *id).collect::<HashSet<_>>();
+
+        self.links
+            .iter()
+            .filter(move |(_, origin_id, target_id)| {
+                node_ids.contains(origin_id) && node_ids.contains(target_id)
+            })
+            .map(|(_, origin_id, target_id)| (*origin_id, *target_id))
+    }
+
+    // 拓扑排序，同时可执行的节点按 order、ID 排序；
+    // 存在环时无法排序的节点按 order 排在最后，由 check 报告
+    pub(crate) fn sorted(&self) -> Vec<u32> {
+        let order = self.nodes.iter().copied().collect::<HashMap<_, _>>();
+        let (sorted, rest) = self.kahn(&order);
+
+        let mut rest = rest.into_iter().collect::<Vec<_>>();
+        rest.sort_by_key(|id| (order[id], *id));
+
+        sorted.into_iter().chain(rest).collect()
+    }
+
+    // 返回已排序的节点和因环无法排序的节点
+    fn kahn(&self, order: &HashMap<u32, u32>) -> (Vec<u32>, HashSet<u32>) {
+        let mut in_degree = order.keys().map(|id| (*id, 0)).collect::<HashMap<_, _>>();
+        let mut downstream = HashMap::<u32, Vec<u32>>::new();
+
+        for (origin_id, target_id) in self.edges() {
+            *in_degree.entry(target_id).or_default() += 1;
+            downstream.entry(origin_id).or_default().push(target_id);
+        }
+
+        let mut ready = in_degree
+            .iter()
+            .filter(|(_, degree)| **degree == 0)
+            .map(|(id, _)| Reverse((order[id], *id)))
+            .collect::<BinaryHeap<_>>();
+        let mut sorted = Vec::with_capacity(order.len());
+
+        while let Some(Reverse((_, id))) = ready.pop() {
+            sorted.push(id);
+
+            for target_id in downstream.get(&id).into_iter().flatten() {
+                let degree = in_degree.entry(*target_id).or_default();
+                *degree -= 1;
+
+                if *degree == 0 {
+                    ready.push(Reverse((order[target_id], *target_id)));
+                }
+            }
+        }
+
+        let sorted_ids = sorted.iter().copied().collect::<HashSet<_>>();
+        let rest = order
+            .keys()
+            .filter(|id| !sorted_ids.contains(id))
+            .copied()
+            .collect();
+
+        (sorted, rest)
+    }
+
+    // 检查重复节点、悬空连接和循环依赖
+    pub(crate) fn check(&self) -> Result<(), GraphError> {
+        let mut issues = vec![];
+        let mut node_ids = HashSet::new();
+
+        for (node_id, _) in &self.nodes {
+            if !node_ids.insert(*node_id) {
+                issues.push(GraphIssue::DuplicateNode { node_id: *node_id });
+            }
+        }
+
+        for (link_id, origin_id, target_id) in &self.links {
+            for node_id in [origin_id, target_id] {
+                if !node_ids.contains(node_id) {
+                    issues.push(GraphIssue::DanglingLink {
+                        link_id: *link_id,
+                        node_id: *node_id,
+                    });
+                }
+            }
+        }
+
+        let order = self.nodes.iter().copied().collect::<HashMap<_, _>>();
+        let (_, rest) = self.kahn(&order);
+
+        if !rest.is_empty() {
+            issues.push(GraphIssue::Cycle {
+                node_ids: self.cycle_nodes(rest),
+            });
+        }
+
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(GraphError { issues })
+        }
+    }
+
+    // 拓扑排序剩下的节点包含环下游的节点，反向剔除没有下游的节点后只剩环上的节点
+    fn cycle_nodes(&self, mut rest: HashSet<u32>) -> Vec<u32> {
+        loop {
+            let has_downstream = self
+                .edges()
+                .filter(|(origin_id, target_id)| {
+                    rest.contains(origin_id) && rest.contains(target_id)
+                })
+                .map(|(origin_id, _)| origin_id)
+                .collect::<HashSet<_>>();
+
+            if has_downstream.len() == rest.len() {
+                break;
+            }
+
+            rest.retain(|id| has_downstream.contains(id));
+        }
+
+        let mut node_ids = rest.into_iter().collect::<Vec<_>>();
+        node_ids.sort();
+        node_ids
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_node_graph_sorted() {
+        // 前端给出的顺序与连接相反时按连接排序
+        let graph = NodeGraph::new(
+            [(1, 2), (2, 1), (3, 0), (4, 3)],
+            [(1, 1, 2), (2, 2, 3), (3, 1, 3)],
+        );
+        assert_eq!(graph.sorted(), vec![1, 2, 3, 4]);
+        assert!(graph.check().is_ok());
+
+        // 没有依赖关系的节点按 order 排序
+        let graph = NodeGraph::new([(1, 2), (2, 1), (3, 0)], []);
+        assert_eq!(graph.sorted(), vec![3, 2, 1]);
+    }
+
+    #[test]
+    fn test_node_graph_check() {
+        // 1 -> 2 -> 3 -> 2，3 -> 4，5 不存在
+        let graph = NodeGraph::new(
+            [(1, 0), (2, 1), (3, 2), (4, 3)],
+            [(1, 1, 2), (2, 2, 3), (3, 3, 2), (4, 3, 4), (5, 4, 5)],
+        );
+
+        assert_eq!(graph.sorted(), vec![1, 2, 3, 4]);
+        assert_eq!(
+            graph.check(),
+            Err(GraphError {
+                issues: vec![
+                    GraphIssue::DanglingLink {
+                        link_id: 5,
+                        node_id: 5
+                    },
+                    GraphIssue::Cycle {
+                        node_ids: vec![2, 3]
+                    },
+                ]
+            })
+        );
+
+        let graph = NodeGraph::new([(1, 0), (1, 1)], [(1, 1, 1)]);
+        let error = graph.check().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid workflow graph: duplicate node 1; cycle through nodes [1]"
+        );
+    }
+}
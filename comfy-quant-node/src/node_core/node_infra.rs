@@ -1,7 +1,11 @@
-use super::{NodeContext, Port};
-use crate::workflow::{Node, WorkflowContext};
+use super::{Bar, Heartbeat, KlinesWindow, NodeContext, Port};
+use crate::{
+    node_io::SpotPairInfo,
+    workflow::{Node, WorkflowContext},
+};
 use anyhow::{anyhow, Result};
-use comfy_quant_base::{Exchange, Market, Symbol};
+use comfy_quant_base::{Exchange, KlineInterval, Market, Symbol};
+use comfy_quant_database::{kline, spot_pairs};
 use rust_decimal::Decimal;
 use std::sync::Arc;
 
@@ -9,13 +13,19 @@ use std::sync::Arc;
 pub struct NodeInfra {
     port: Port,
     node: Node,
+    heartbeat: Arc<Heartbeat>, // 节点心跳，供看门狗判断节点是否卡住
 }
 
 impl NodeInfra {
     pub fn new(node: Node) -> Self {
         let port = Port::new();
+        let heartbeat = Arc::new(Heartbeat::new());
 
-        Self { port, node }
+        Self {
+            port,
+            node,
+            heartbeat,
+        }
     }
 
     pub(crate) fn port(&self) -> &Port {
@@ -30,6 +40,10 @@ impl NodeInfra {
         &self.node
     }
 
+    pub(crate) fn cloned_heartbeat(&self) -> Arc<Heartbeat> {
+        Arc::clone(&self.heartbeat)
+    }
+
     pub(super) fn workflow_context(&self) -> Result<&Arc<WorkflowContext>> {
         self.node.workflow_context()
     }
@@ -42,7 +56,13 @@ impl NodeInfra {
             context.workflow_id(),
             self.node.id as i16,
             &self.node.properties.prop_type,
-        ))
+        )
+        .with_write_buffer(context.cloned_write_buffer())
+        .with_valuation_policy(context.cloned_valuation_policy())
+        .with_resource_meter(context.cloned_resource_meter())
+        .with_event_log(context.cloned_event_log())
+        .with_event_bus(context.cloned_event_bus())
+        .with_clock(context.cloned_clock()))
     }
 
     pub(super) async fn price(
@@ -58,4 +78,62 @@ impl NodeInfra {
             .price(exchange, market, symbol)
             .ok_or_else(|| anyhow!("price not found"))
     }
+
+    // 交易对信息，按交易对缓存补全精度和下单规则，缓存中没有时只包含资产
+    pub(crate) async fn spot_pair_info(
+        &self,
+        exchange: &Exchange,
+        base_asset: &str,
+        quote_asset: &str,
+    ) -> Result<SpotPairInfo> {
+        let context = self.workflow_context()?;
+        let symbol = exchange.symbol(base_asset, quote_asset);
+        let pair_info = SpotPairInfo::builder()
+            .base_asset(base_asset)
+            .quote_asset(quote_asset)
+            .build();
+
+        match spot_pairs::get(&context.cloned_db(), exchange, &symbol).await {
+            Ok(spot_pair) => {
+                context.cloned_resource_meter().add_db_rows_read(1);
+                Ok(pair_info.with_spot_pair(spot_pair))
+            }
+            Err(e) => {
+                tracing::debug!("Spot pair {} not cached: {}", symbol, e);
+                Ok(pair_info)
+            }
+        }
+    }
+
+    // 用数据库中最近的K线初始化滚动窗口，回测时以模拟时间为准
+    pub(super) async fn klines_window(
+        &self,
+        exchange: &Exchange,
+        symbol: &Symbol,
+        interval: &KlineInterval,
+        len: usize,
+    ) -> Result<KlinesWindow> {
+        let context = self.workflow_context()?;
+        let end_datetime = context.cloned_clock().now();
+
+        let klines = kline::list_recent(
+            &context.cloned_db(),
+            exchange,
+            &Market::Spot,
+            symbol,
+            interval,
+            &end_datetime,
+            len as i64,
+        )
+        .await?;
+
+        context
+            .cloned_resource_meter()
+            .add_db_rows_read(klines.len() as u64);
+
+        let window = KlinesWindow::new(symbol.clone(), interval.clone(), len)
+            .with_bars(klines.iter().map(Bar::from));
+
+        Ok(window)
+    }
 }
@@ -0,0 +1,90 @@
+use serde::Serialize;
+
+// 节点分类，对应前端节点面板的分组
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum NodeCategory {
+    #[serde(rename = "数据")]
+    Data,
+    #[serde(rename = "账户")]
+    Account,
+    #[serde(rename = "策略")]
+    Strategy,
+    #[serde(rename = "风控")]
+    Risk,
+    #[serde(rename = "通知")]
+    Notify,
+    #[serde(rename = "测试")]
+    Test,
+}
+
+// 节点的输入或输出端口
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct PortMetadata {
+    pub name: &'static str, // 端口名称
+    #[serde(rename = "type")]
+    pub port_type: &'static str, // 端口类型，连接两端的类型必须一致
+    pub optional: bool,     // 输入端口是否可以不连接
+}
+
+impl PortMetadata {
+    // 可以不连接的输入端口
+    pub const fn optional(self) -> Self {
+        PortMetadata {
+            optional: true,
+            ..self
+        }
+    }
+}
+
+// 节点元数据，前端节点面板据此展示可用节点
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct NodeMetadata {
+    #[serde(rename = "type")]
+    pub prop_type: &'static str, // 节点类型，对应工作流中的 properties.type
+    pub display_name: &'static str,       // 显示名称
+    pub category: NodeCategory,           // 分类
+    pub inputs: &'static [PortMetadata],  // 输入端口，按槽位顺序
+    pub outputs: &'static [PortMetadata], // 输出端口，按槽位顺序
+    pub icon: &'static str,               // 图标提示
+}
+
+// 由节点实现提供元数据，保证节点面板与后端支持的节点一致
+pub trait NodeMeta {
+    const METADATA: NodeMetadata;
+}
+
+pub(crate) const SPOT_PAIR_INFO: PortMetadata = PortMetadata {
+    name: "现货交易对",
+    port_type: "SpotPairInfo",
+    optional: false,
+};
+
+pub(crate) const TICK_STREAM: PortMetadata = PortMetadata {
+    name: "Tick数据流",
+    port_type: "TickStream",
+    optional: false,
+};
+
+pub(crate) const KLINE_STREAM: PortMetadata = PortMetadata {
+    name: "K线数据流",
+    port_type: "KlineStream",
+    optional: false,
+};
+
+pub(crate) const ANNOUNCEMENT_STREAM: PortMetadata = PortMetadata {
+    name: "公告数据流",
+    port_type: "AnnouncementStream",
+    optional: false,
+};
+
+pub(crate) const SPOT_CLIENT: PortMetadata = PortMetadata {
+    name: "现货账户客户端",
+    port_type: "SpotClient",
+    optional: false,
+};
+
+pub(crate) const CAPITAL_ALLOCATION: PortMetadata = PortMetadata {
+    name: "资金分配",
+    port_type: "CapitalAllocation",
+    optional: false,
+};
@@ -1,4 +1,4 @@
-use super::{slot::Slot, slots::Slots};
+use super::{slot::Slot, slots::Slots, NodeError};
 use anyhow::Result;
 use std::sync::Arc;
 
@@ -33,7 +33,10 @@ impl Port {
             .inputs
             .get::<Arc<Slot<T>>>(index)
             .map(Arc::clone)
-            .ok_or_else(|| anyhow::anyhow!("Input slot {} is not connected", index))?;
+            // 输入未连接属于配置错误
+            .ok_or_else(|| {
+                NodeError::Config(anyhow::anyhow!("Input slot {} is not connected", index))
+            })?;
 
         Ok(slot)
     }
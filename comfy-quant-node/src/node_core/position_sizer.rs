@@ -0,0 +1,308 @@
+use bon::Builder;
+use comfy_quant_exchange::client::spot_client::base::{OrderSide, SymbolInformation};
+use rust_decimal::{Decimal, RoundingStrategy};
+use thiserror::Error;
+
+// 不满足交易所过滤器的订单，实盘会被交易所拒绝
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum OrderFilterError {
+    #[error("Order quantity must be greater than 0")]
+    InvalidQuantity,
+
+    #[error("Order quantity {qty} is below min qty {min_qty}")]
+    BelowMinQty { qty: Decimal, min_qty: Decimal },
+
+    #[error("Order notional {notional} is below min notional {min_notional}")]
+    BelowMinNotional {
+        notional: Decimal,
+        min_notional: Decimal,
+    },
+}
+
+// 交易对的下单规则，对应交易所的 LOT_SIZE、PRICE_FILTER 和 MIN_NOTIONAL 过滤器
+#[derive(Builder, Debug, Clone, Default, PartialEq)]
+pub struct OrderRules {
+    base_asset_precision: u32,     // 基础资产精度
+    tick_size: Option<Decimal>,    // 价格步长
+    step_size: Option<Decimal>,    // 数量步长
+    min_qty: Option<Decimal>,      // 最小下单数量
+    min_notional: Option<Decimal>, // 最小名义价值
+}
+
+impl From<&SymbolInformation> for OrderRules {
+    fn from(value: &SymbolInformation) -> Self {
+        OrderRules::builder()
+            .base_asset_precision(value.base_asset_precision)
+            .maybe_tick_size(value.tick_size)
+            .maybe_step_size(value.step_size)
+            .maybe_min_qty(value.min_qty)
+            .maybe_min_notional(value.min_notional)
+            .build()
+    }
+}
+
+impl OrderRules {
+    // 数量按步长和精度向下取整，下单数量不超过计算出的数量
+    pub fn round_qty(&self, qty: Decimal) -> Decimal {
+        let qty = match self
+            .step_size
+            .filter(|step_size| *step_size > Decimal::ZERO)
+        {
+            Some(step_size) => (qty / step_size).floor() * step_size,
+            None => qty,
+        };
+
+        qty.round_dp_with_strategy(self.base_asset_precision, RoundingStrategy::ToZero)
+    }
+
+    // 价格按步长取整，买单向下、卖单向上，成交价格不差于原价格
+    pub fn round_price(&self, price: Decimal, side: &OrderSide) -> Decimal {
+        let Some(tick_size) = self
+            .tick_size
+            .filter(|tick_size| *tick_size > Decimal::ZERO)
+        else {
+            return price;
+        };
+
+        let ticks = price / tick_size;
+        let ticks = match side {
+            OrderSide::Buy => ticks.floor(),
+            OrderSide::Sell => ticks.ceil(),
+        };
+
+        ticks * tick_size
+    }
+
+    pub fn min_notional(&self) -> Option<Decimal> {
+        self.min_notional
+    }
+
+    // 检查数量是否满足最小下单数量和最小名义价值
+    pub fn check(&self, qty: Decimal, price: Decimal) -> Result<(), OrderFilterError> {
+        if qty <= Decimal::ZERO {
+            return Err(OrderFilterError::InvalidQuantity);
+        }
+
+        if let Some(min_qty) = self.min_qty.filter(|min_qty| qty < *min_qty) {
+            return Err(OrderFilterError::BelowMinQty { qty, min_qty });
+        }
+
+        self.check_notional(qty * price)
+    }
+
+    // 检查名义价值是否满足最小名义价值，用于按计价资产金额下单
+    pub fn check_notional(&self, notional: Decimal) -> Result<(), OrderFilterError> {
+        match self
+            .min_notional
+            .filter(|min_notional| notional < *min_notional)
+        {
+            Some(min_notional) => Err(OrderFilterError::BelowMinNotional {
+                notional,
+                min_notional,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    // 数量满足最小下单数量和最小名义价值
+    pub fn is_tradable(&self, qty: Decimal, price: Decimal) -> bool {
+        self.check(qty, price).is_ok()
+    }
+}
+
+// 仓位计算方法
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SizingMethod {
+    FixedFraction(Decimal), // 权益的固定比例
+    FixedNotional(Decimal), // 固定金额(计价资产)
+    // 凯利公式 p - (1 - p) / b 乘以折扣系数，如半凯利为0.5，结果为负时不下单
+    Kelly {
+        win_rate: Decimal,     // 胜率 p
+        payoff_ratio: Decimal, // 平均盈利与平均亏损之比 b
+        fraction: Decimal,     // 折扣系数
+    },
+    VolatilityTarget(Decimal), // 目标波动率，仓位价值 = 权益 * 目标波动率 / 资产波动率
+}
+
+// 仓位计算，各策略按相同的方法和交易对规则计算下单数量
+#[derive(Builder, Debug, Clone)]
+pub struct PositionSizer {
+    method: SizingMethod, // 计算方法
+    #[builder(default = Decimal::ONE)]
+    max_fraction: Decimal, // 单笔仓位价值占权益的比例上限
+}
+
+impl PositionSizer {
+    // 目标仓位价值(计价资产)。波动率目标法需要资产波动率，缺失或不为正时为0
+    pub fn notional(&self, equity: Decimal, volatility: Option<Decimal>) -> Decimal {
+        if equity <= Decimal::ZERO {
+            return Decimal::ZERO;
+        }
+
+        let notional = match self.method {
+            SizingMethod::FixedFraction(fraction) => equity * fraction,
+            SizingMethod::FixedNotional(notional) => notional,
+            SizingMethod::Kelly {
+                win_rate,
+                payoff_ratio,
+                fraction,
+            } => {
+                if payoff_ratio <= Decimal::ZERO {
+                    return Decimal::ZERO;
+                }
+
+                let kelly = win_rate - (Decimal::ONE - win_rate) / payoff_ratio;
+                equity * kelly * fraction
+            }
+            SizingMethod::VolatilityTarget(target) => match volatility {
+                Some(volatility) if volatility > Decimal::ZERO => equity * target / volatility,
+                _ => Decimal::ZERO,
+            },
+        };
+
+        notional.clamp(Decimal::ZERO, equity * self.max_fraction)
+    }
+
+    // 下单数量，按交易对规则向下取整，不满足最小下单数量或最小名义价值时为0
+    pub fn quantity(
+        &self,
+        equity: Decimal,
+        price: Decimal,
+        volatility: Option<Decimal>,
+        rules: &OrderRules,
+    ) -> Decimal {
+        if price <= Decimal::ZERO {
+            return Decimal::ZERO;
+        }
+
+        let qty = rules.round_qty(self.notional(equity, volatility) / price);
+
+        if rules.is_tradable(qty, price) {
+            qty
+        } else {
+            Decimal::ZERO
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_order_rules() {
+        let rules = OrderRules::builder()
+            .base_asset_precision(8)
+            .step_size(dec!(0.001))
+            .min_qty(dec!(0.01))
+            .min_notional(dec!(10))
+            .build();
+
+        assert_eq!(rules.round_qty(dec!(0.123456)), dec!(0.123));
+        assert!(rules.is_tradable(dec!(0.123), dec!(100)));
+        // 低于最小下单数量
+        assert!(!rules.is_tradable(dec!(0.009), dec!(10000)));
+        // 低于最小名义价值
+        assert!(!rules.is_tradable(dec!(0.05), dec!(100)));
+
+        assert_eq!(
+            rules.check(dec!(0.009), dec!(10000)),
+            Err(OrderFilterError::BelowMinQty {
+                qty: dec!(0.009),
+                min_qty: dec!(0.01)
+            })
+        );
+        assert_eq!(
+            rules.check_notional(dec!(5)),
+            Err(OrderFilterError::BelowMinNotional {
+                notional: dec!(5),
+                min_notional: dec!(10)
+            })
+        );
+        assert_eq!(
+            rules.check(dec!(0), dec!(100)),
+            Err(OrderFilterError::InvalidQuantity)
+        );
+
+        // 没有步长时按精度向下取整
+        let rules = OrderRules::builder().base_asset_precision(2).build();
+        assert_eq!(rules.round_qty(dec!(1.239)), dec!(1.23));
+        // 没有价格步长时价格不变
+        assert_eq!(rules.round_price(dec!(1.239), &OrderSide::Buy), dec!(1.239));
+
+        let rules = OrderRules::builder()
+            .base_asset_precision(8)
+            .tick_size(dec!(0.01))
+            .build();
+        assert_eq!(
+            rules.round_price(dec!(100.456), &OrderSide::Buy),
+            dec!(100.45)
+        );
+        assert_eq!(
+            rules.round_price(dec!(100.451), &OrderSide::Sell),
+            dec!(100.46)
+        );
+        assert_eq!(
+            rules.round_price(dec!(100.45), &OrderSide::Sell),
+            dec!(100.45)
+        );
+    }
+
+    #[test]
+    fn test_position_sizer() {
+        let rules = OrderRules::builder()
+            .base_asset_precision(4)
+            .min_notional(dec!(5))
+            .build();
+
+        let sizer = PositionSizer::builder()
+            .method(SizingMethod::FixedFraction(dec!(0.1)))
+            .build();
+        assert_eq!(sizer.notional(dec!(1000), None), dec!(100));
+        assert_eq!(
+            sizer.quantity(dec!(1000), dec!(30), None, &rules),
+            dec!(3.3333)
+        );
+
+        // 固定金额不超过权益的比例上限
+        let sizer = PositionSizer::builder()
+            .method(SizingMethod::FixedNotional(dec!(500)))
+            .max_fraction(dec!(0.2))
+            .build();
+        assert_eq!(sizer.notional(dec!(1000), None), dec!(200));
+
+        // 胜率0.6，盈亏比2，凯利比例0.4，半凯利0.2
+        let sizer = PositionSizer::builder()
+            .method(SizingMethod::Kelly {
+                win_rate: dec!(0.6),
+                payoff_ratio: dec!(2),
+                fraction: dec!(0.5),
+            })
+            .build();
+        assert_eq!(sizer.notional(dec!(1000), None), dec!(200));
+
+        // 没有优势时不下单
+        let sizer = PositionSizer::builder()
+            .method(SizingMethod::Kelly {
+                win_rate: dec!(0.3),
+                payoff_ratio: dec!(1),
+                fraction: dec!(1),
+            })
+            .build();
+        assert_eq!(sizer.notional(dec!(1000), None), dec!(0));
+
+        // 目标波动率10%，资产波动率50%，仓位为权益的20%
+        let sizer = PositionSizer::builder()
+            .method(SizingMethod::VolatilityTarget(dec!(0.1)))
+            .build();
+        assert_eq!(sizer.notional(dec!(1000), Some(dec!(0.5))), dec!(200));
+        assert_eq!(sizer.notional(dec!(1000), None), dec!(0));
+
+        // 低于最小名义价值时为0
+        assert_eq!(
+            sizer.quantity(dec!(100), dec!(30), Some(dec!(5)), &rules),
+            dec!(0)
+        );
+    }
+}
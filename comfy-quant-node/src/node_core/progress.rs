@@ -0,0 +1,104 @@
+use super::WorkflowEvent;
+use std::time::{Duration, Instant};
+
+// 回测回放进度，百分比变化时才输出事件，避免每根K线都推送
+#[derive(Debug)]
+pub(crate) struct ProgressTracker {
+    node_id: u32,             // 节点ID
+    total: u64,               // 总数量
+    processed: u64,           // 已处理数量
+    last_percent: Option<u8>, // 最近一次输出的百分比
+    started_at: Instant,      // 开始时间，用于估算剩余时间
+}
+
+impl ProgressTracker {
+    pub(crate) fn new(node_id: u32, total: u64) -> Self {
+        ProgressTracker {
+            node_id,
+            total,
+            processed: 0,
+            last_percent: None,
+            started_at: Instant::now(),
+        }
+    }
+
+    // 处理一条数据，百分比变化时返回进度事件
+    pub(crate) fn advance(&mut self) -> Option<WorkflowEvent> {
+        self.advance_with_elapsed(self.started_at.elapsed())
+    }
+
+    fn advance_with_elapsed(&mut self, elapsed: Duration) -> Option<WorkflowEvent> {
+        self.processed += 1;
+
+        let percent = self.percent();
+        if self.last_percent == Some(percent) {
+            return None;
+        }
+
+        self.last_percent = Some(percent);
+
+        // 按已处理的平均速度估算剩余时间
+        let remaining = self.total.saturating_sub(self.processed);
+        let eta_secs = (elapsed * remaining as u32)
+            .checked_div(self.processed as u32)
+            .map(|eta| eta.as_secs());
+
+        Some(WorkflowEvent::BacktestProgress {
+            node_id: self.node_id,
+            processed: self.processed,
+            total: self.total,
+            percent,
+            eta_secs,
+        })
+    }
+
+    fn percent(&self) -> u8 {
+        if self.total == 0 {
+            return 100;
+        }
+
+        (self.processed.min(self.total) * 100 / self.total) as u8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_tracker() {
+        let mut tracker = ProgressTracker::new(1, 200);
+
+        // 第1条为0%，第2条为1%
+        assert!(matches!(
+            tracker.advance_with_elapsed(Duration::from_secs(1)),
+            Some(WorkflowEvent::BacktestProgress { percent: 0, .. })
+        ));
+        assert_eq!(
+            tracker.advance_with_elapsed(Duration::from_secs(2)),
+            Some(WorkflowEvent::BacktestProgress {
+                node_id: 1,
+                processed: 2,
+                total: 200,
+                percent: 1,
+                eta_secs: Some(198),
+            })
+        );
+
+        // 百分比不变时不输出
+        assert_eq!(tracker.advance_with_elapsed(Duration::from_secs(3)), None);
+
+        let events = (3..200)
+            .filter_map(|_| tracker.advance_with_elapsed(Duration::from_secs(200)))
+            .collect::<Vec<_>>();
+        assert_eq!(events.len(), 99);
+        assert!(matches!(
+            events.last(),
+            Some(WorkflowEvent::BacktestProgress {
+                percent: 100,
+                eta_secs: Some(0),
+                ..
+            })
+        ));
+    }
+}
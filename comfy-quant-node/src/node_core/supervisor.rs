@@ -0,0 +1,292 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::Serialize;
+use std::{collections::HashMap, time::Duration};
+
+const DEFAULT_MAX_RETRIES: u32 = 3;
+const DEFAULT_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(300);
+
+// 节点连续运行超过该时间后再失败，重新开始计算重启次数
+pub(crate) const RESTART_RESET_AFTER: Duration = Duration::from_secs(600);
+
+// 节点重启策略，从工作流 config 中读取，node.<节点ID>. 前缀的配置只对该节点生效:
+//      restart_policy: never 不重启; on_failure 失败或 panic 后重启，默认 on_failure
+//      restart_max_retries: 最大连续重启次数，默认3
+//      restart_backoff_secs: 首次重启的等待时间，之后每次翻倍，默认1秒
+// 配置错误和交易所拒绝请求不受策略影响，直接终止工作流
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RestartPolicy {
+    Never,
+    OnFailure { max_retries: u32, backoff: Duration },
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        RestartPolicy::OnFailure {
+            max_retries: DEFAULT_MAX_RETRIES,
+            backoff: DEFAULT_BACKOFF,
+        }
+    }
+}
+
+impl RestartPolicy {
+    pub fn from_config(config: &HashMap<String, String>, node_id: u32) -> Result<Self> {
+        let get = |key: &str| {
+            config
+                .get(&format!("node.{}.{}", node_id, key))
+                .or_else(|| config.get(key))
+                .map(|value| value.trim().to_string())
+        };
+        let parse = |key: &str, default: u64| -> Result<u64> {
+            get(key).map_or(Ok(default), |value| {
+                value
+                    .parse::<u64>()
+                    .map_err(|_| anyhow!("Invalid {} of node {}: {}", key, node_id, value))
+            })
+        };
+
+        match get("restart_policy").as_deref() {
+            Some("never") => Ok(RestartPolicy::Never),
+            Some("on_failure") | None => Ok(RestartPolicy::OnFailure {
+                max_retries: parse("restart_max_retries", DEFAULT_MAX_RETRIES as u64)? as u32,
+                backoff: Duration::from_secs(parse(
+                    "restart_backoff_secs",
+                    DEFAULT_BACKOFF.as_secs(),
+                )?),
+            }),
+            Some(policy) => Err(anyhow!(
+                "Invalid restart_policy of node {}: {}, expected never or on_failure",
+                node_id,
+                policy
+            )),
+        }
+    }
+
+    // 第 attempt 次重启前的等待时间，超过最大重启次数时返回 None
+    pub fn delay(&self, attempt: u32) -> Option<Duration> {
+        match self {
+            RestartPolicy::Never => None,
+            RestartPolicy::OnFailure {
+                max_retries,
+                backoff,
+            } => (1..=*max_retries).contains(&attempt).then(|| {
+                backoff
+                    .saturating_mul(2_u32.saturating_pow(attempt - 1))
+                    .min(MAX_BACKOFF)
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeState {
+    Running,    // 运行中
+    Restarting, // 失败后等待重启
+    Finished,   // 正常结束
+    Failed,     // 失败且不再重启
+    Cancelled,  // 工作流停止
+}
+
+impl NodeState {
+    fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            NodeState::Finished | NodeState::Failed | NodeState::Cancelled
+        )
+    }
+}
+
+// 节点健康状态
+#[derive(Debug, Clone, Serialize)]
+pub struct NodeHealth {
+    pub node_id: u32,               // 节点ID
+    pub node_type: String,          // 节点类型
+    pub state: NodeState,           // 运行状态
+    pub restarts: u32,              // 累计重启次数
+    pub last_error: Option<String>, // 最近一次失败的错误
+    pub updated_at: DateTime<Utc>,  // 状态更新时间
+}
+
+// 记录工作流中各节点的运行状态，执行器在节点启动、重启和结束时更新
+#[derive(Debug, Default)]
+pub struct Supervisor {
+    nodes: DashMap<u32, NodeHealth>,
+}
+
+impl Supervisor {
+    pub fn new() -> Self {
+        Supervisor::default()
+    }
+
+    pub(crate) fn started(&self, node_id: u32, node_type: &str) {
+        self.nodes
+            .entry(node_id)
+            .and_modify(|health| {
+                health.state = NodeState::Running;
+                health.updated_at = Utc::now();
+            })
+            .or_insert_with(|| NodeHealth {
+                node_id,
+                node_type: node_type.to_string(),
+                state: NodeState::Running,
+                restarts: 0,
+                last_error: None,
+                updated_at: Utc::now(),
+            });
+    }
+
+    // 返回累计重启次数
+    pub(crate) fn restarting(&self, node_id: u32, error: Option<String>) -> u32 {
+        let mut restarts = 0;
+
+        self.update(node_id, NodeState::Restarting, error, |health| {
+            health.restarts += 1;
+            restarts = health.restarts;
+        });
+
+        restarts
+    }
+
+    pub(crate) fn finished(&self, node_id: u32) {
+        self.update(node_id, NodeState::Finished, None, |_| {});
+    }
+
+    pub(crate) fn failed(&self, node_id: u32, error: String) {
+        self.update(node_id, NodeState::Failed, Some(error), |_| {});
+    }
+
+    pub(crate) fn cancelled(&self, node_id: u32) {
+        self.update(node_id, NodeState::Cancelled, None, |_| {});
+    }
+
+    fn update(
+        &self,
+        node_id: u32,
+        state: NodeState,
+        error: Option<String>,
+        f: impl FnOnce(&mut NodeHealth),
+    ) {
+        if let Some(mut health) = self.nodes.get_mut(&node_id) {
+            health.state = state;
+            health.updated_at = Utc::now();
+
+            if error.is_some() {
+                health.last_error = error;
+            }
+
+            f(&mut health);
+        }
+    }
+
+    // 各节点健康状态，按节点ID排序
+    pub fn health(&self) -> Vec<NodeHealth> {
+        let mut health = self
+            .nodes
+            .iter()
+            .map(|entry| entry.value().clone())
+            .collect::<Vec<_>>();
+        health.sort_by_key(|health| health.node_id);
+        health
+    }
+
+    // 是否有节点失败且不再重启
+    pub fn has_failed(&self) -> bool {
+        self.nodes
+            .iter()
+            .any(|entry| entry.state == NodeState::Failed)
+    }
+
+    // 节点是否全部结束
+    pub fn all_terminated(&self) -> bool {
+        !self.nodes.is_empty() && self.nodes.iter().all(|entry| entry.state.is_terminal())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restart_policy_from_config() -> Result<()> {
+        let config = HashMap::from([
+            ("restart_max_retries".to_string(), "5".to_string()),
+            ("node.2.restart_policy".to_string(), "never".to_string()),
+            ("node.3.restart_backoff_secs".to_string(), "10".to_string()),
+        ]);
+
+        assert_eq!(
+            RestartPolicy::from_config(&config, 1)?,
+            RestartPolicy::OnFailure {
+                max_retries: 5,
+                backoff: Duration::from_secs(1),
+            }
+        );
+        assert_eq!(
+            RestartPolicy::from_config(&config, 2)?,
+            RestartPolicy::Never
+        );
+        assert_eq!(
+            RestartPolicy::from_config(&config, 3)?,
+            RestartPolicy::OnFailure {
+                max_retries: 5,
+                backoff: Duration::from_secs(10),
+            }
+        );
+        assert_eq!(
+            RestartPolicy::from_config(&HashMap::new(), 1)?,
+            RestartPolicy::default()
+        );
+
+        let config = HashMap::from([("restart_policy".to_string(), "always".to_string())]);
+        assert!(RestartPolicy::from_config(&config, 1).is_err());
+
+        let config = HashMap::from([("restart_max_retries".to_string(), "-1".to_string())]);
+        assert!(RestartPolicy::from_config(&config, 1).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_restart_policy_delay() {
+        let policy = RestartPolicy::default();
+        assert_eq!(policy.delay(0), None);
+        assert_eq!(policy.delay(1), Some(Duration::from_secs(1)));
+        assert_eq!(policy.delay(3), Some(Duration::from_secs(4)));
+        assert_eq!(policy.delay(4), None);
+
+        let policy = RestartPolicy::OnFailure {
+            max_retries: 20,
+            backoff: Duration::from_secs(60),
+        };
+        assert_eq!(policy.delay(10), Some(MAX_BACKOFF));
+
+        assert_eq!(RestartPolicy::Never.delay(1), None);
+    }
+
+    #[test]
+    fn test_supervisor() {
+        let supervisor = Supervisor::new();
+        assert!(!supervisor.all_terminated());
+
+        supervisor.started(1, "data.BacktestSpotTicker");
+        supervisor.started(2, "strategy.SpotGrid");
+        supervisor.restarting(2, Some("timeout".to_string()));
+        supervisor.started(2, "strategy.SpotGrid");
+
+        let health = supervisor.health();
+        assert_eq!(health[1].state, NodeState::Running);
+        assert_eq!(health[1].restarts, 1);
+        assert_eq!(health[1].last_error.as_deref(), Some("timeout"));
+        assert!(!supervisor.has_failed());
+
+        supervisor.finished(1);
+        supervisor.failed(2, "boom".to_string());
+
+        assert!(supervisor.has_failed());
+        assert!(supervisor.all_terminated());
+        assert_eq!(supervisor.health()[1].last_error.as_deref(), Some("boom"));
+    }
+}
@@ -2,12 +2,31 @@ use bon::Builder;
 use comfy_quant_base::Symbol;
 use comfy_quant_exchange::client::spot_client::base::SymbolPrice;
 use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Builder, PartialEq)]
+#[derive(Debug, Clone, Builder, PartialEq, Serialize, Deserialize)]
 pub struct Tick {
     pub timestamp: i64,
     pub symbol: Symbol,
     pub price: Decimal,
+    #[builder(default)]
+    pub volume: Decimal, // 周期内成交量，数据源没有时为0
+    #[builder(default)]
+    pub taker_buy_volume: Decimal, // 周期内主动买入成交量，数据源没有时为0
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub millis: Option<i64>, // 毫秒时间戳，数据源提供时用于亚秒级K线聚合
+}
+
+impl Tick {
+    // 毫秒时间戳，数据源没有时按秒时间戳换算
+    pub fn timestamp_millis(&self) -> i64 {
+        self.millis.unwrap_or(self.timestamp * 1000)
+    }
+
+    // 主动卖出成交量
+    pub fn taker_sell_volume(&self) -> Decimal {
+        (self.volume - self.taker_buy_volume).max(Decimal::ZERO)
+    }
 }
 
 impl From<Tick> for SymbolPrice {
@@ -0,0 +1,187 @@
+use super::Tick;
+use anyhow::Result;
+use comfy_quant_base::{Exchange, Market};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, File},
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+const DEFAULT_BATCH_SIZE: usize = 1000;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RecordedTick {
+    pub exchange: Exchange, // 交易所
+    pub market: Market,     // 市场
+    pub tick: Tick,         // tick
+}
+
+// 实盘tick录制，按运行(工作流ID)分目录，每批写入一个gzip压缩的JSON Lines文件，
+// 事后可以按顺序回放策略实际收到的tick
+#[derive(Debug)]
+pub(crate) struct TickRecorder {
+    dir: PathBuf,              // 本次运行的录制目录
+    node_id: u32,              // 数据源节点ID
+    batch_size: usize,         // 每个文件的tick数量
+    buffer: Vec<RecordedTick>, // 未写入的tick
+}
+
+impl TickRecorder {
+    pub(crate) fn new(dir: impl AsRef<Path>, workflow_id: &str, node_id: u32) -> Self {
+        TickRecorder {
+            dir: dir.as_ref().join(workflow_id),
+            node_id,
+            batch_size: DEFAULT_BATCH_SIZE,
+            buffer: Vec::with_capacity(DEFAULT_BATCH_SIZE),
+        }
+    }
+
+    pub(crate) fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    pub(crate) fn record(
+        &mut self,
+        exchange: &Exchange,
+        market: &Market,
+        tick: &Tick,
+    ) -> Result<()> {
+        self.buffer.push(RecordedTick {
+            exchange: exchange.clone(),
+            market: market.clone(),
+            tick: tick.clone(),
+        });
+
+        if self.buffer.len() >= self.batch_size {
+            self.flush()?;
+        }
+
+        Ok(())
+    }
+
+    // 将缓冲的tick写入新文件，文件名包含首尾tick的时间戳，按文件名排序即为接收顺序
+    pub(crate) fn flush(&mut self) -> Result<()> {
+        let (Some(first), Some(last)) = (self.buffer.first(), self.buffer.last()) else {
+            return Ok(());
+        };
+
+        fs::create_dir_all(&self.dir)?;
+
+        let path = self.dir.join(format!(
+            "{}-{:012}-{:012}.jsonl.gz",
+            self.node_id, first.tick.timestamp, last.tick.timestamp
+        ));
+        let mut encoder =
+            GzEncoder::new(BufWriter::new(File::create(&path)?), Compression::default());
+
+        for tick in &self.buffer {
+            serde_json::to_writer(&mut encoder, tick)?;
+            writeln!(encoder)?;
+        }
+
+        encoder.finish()?.flush()?;
+
+        tracing::info!(
+            monotonic_counter.tick_recorded = self.buffer.len() as u64,
+            "Recorded {} ticks to {}",
+            self.buffer.len(),
+            path.display()
+        );
+
+        self.buffer.clear();
+
+        Ok(())
+    }
+}
+
+impl Drop for TickRecorder {
+    fn drop(&mut self) {
+        if let Err(e) = self.flush() {
+            tracing::error!("Flush tick recorder failed: {}", e);
+        }
+    }
+}
+
+// 按接收顺序读取一次运行中某个节点录制的全部tick
+pub fn replay_ticks(
+    dir: impl AsRef<Path>,
+    workflow_id: &str,
+    node_id: u32,
+) -> Result<Vec<RecordedTick>> {
+    let dir = dir.as_ref().join(workflow_id);
+    let prefix = format!("{}-", node_id);
+
+    let mut paths = fs::read_dir(&dir)?
+        .map(|entry| Ok(entry?.path()))
+        .collect::<Result<Vec<_>>>()?
+        .into_iter()
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(&prefix) && name.ends_with(".jsonl.gz"))
+        })
+        .collect::<Vec<_>>();
+    paths.sort();
+
+    let mut ticks = vec![];
+
+    for path in paths {
+        let reader = BufReader::new(GzDecoder::new(File::open(&path)?));
+
+        for line in reader.lines() {
+            ticks.push(serde_json::from_str(&line?)?);
+        }
+    }
+
+    Ok(ticks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_tick_recorder() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("comfy-quant-ticks-{}", std::process::id()));
+        let workflow_id = "jEnbRDqQu4UN6y7cgQgp6";
+        let tick = |timestamp: i64| {
+            Tick::builder()
+                .timestamp(timestamp)
+                .symbol("BTCUSDT".into())
+                .price(dec!(100.0) + Decimal::from(timestamp))
+                .volume(dec!(1.5))
+                .build()
+        };
+
+        {
+            let mut recorder = TickRecorder::new(&dir, workflow_id, 1).with_batch_size(2);
+
+            for timestamp in 1..=5 {
+                recorder.record(&Exchange::Binance, &Market::Spot, &tick(timestamp))?;
+            }
+
+            // 满批写入两个文件，剩余的在释放时写入
+            assert_eq!(fs::read_dir(dir.join(workflow_id))?.count(), 2);
+        }
+
+        let ticks = replay_ticks(&dir, workflow_id, 1)?;
+        assert_eq!(
+            ticks
+                .iter()
+                .map(|tick| tick.tick.clone())
+                .collect::<Vec<_>>(),
+            (1..=5).map(tick).collect::<Vec<_>>()
+        );
+        assert_eq!(ticks[0].exchange, Exchange::Binance);
+        assert!(replay_ticks(&dir, workflow_id, 2)?.is_empty());
+
+        fs::remove_dir_all(&dir)?;
+
+        Ok(())
+    }
+}
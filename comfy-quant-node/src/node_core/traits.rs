@@ -1,19 +1,19 @@
-use super::{NodeContext, NodeInfra, Tick};
+use super::{Heartbeat, KlinesWindow, NodeContext, NodeError, NodeInfra, Tick, WorkflowEvent};
 use crate::{
     node_core::Port,
-    stats::{SpotStats, SpotStatsData},
+    stats::{Event, EventKind, PerformanceReport, SpotStats, SpotStatsData, TradeRecord},
     workflow::{Node, WorkflowContext},
 };
 use anyhow::Result;
-use comfy_quant_base::{Exchange, Market, Symbol};
+use comfy_quant_base::{Exchange, KlineInterval, Market, Symbol};
 // use chrono::{DateTime, Utc};
-// use comfy_quant_base::KlineInterval;
 use comfy_quant_exchange::client::{
-    spot_client::base::Order,
+    spot_client::base::{Order, OrderIntent, OrderUpdate, UserDataEvent},
     spot_client_kind::{SpotClientExecutable, SpotClientKind, SpotclientExecutableExt},
 };
 use enum_dispatch::enum_dispatch;
 use rust_decimal::{Decimal, MathematicalOps};
+use serde::Serialize;
 use std::sync::Arc;
 
 #[enum_dispatch]
@@ -47,6 +47,30 @@ pub trait NodeCoreExt: NodeCore {
         self.node_infra().node_context()
     }
 
+    fn heartbeat(&self) -> Arc<Heartbeat> {
+        self.node_infra().cloned_heartbeat()
+    }
+
+    // 发布节点运行中的错误，由事件总线记录日志并推送给订阅者；
+    // 尽力而为，没有工作流上下文时只记录日志，不影响节点继续运行
+    fn publish_error(&self, message: impl Into<String>) {
+        let event = WorkflowEvent::Error {
+            node_id: self.node().id,
+            message: message.into(),
+        };
+
+        match self.workflow_context() {
+            Ok(ctx) => ctx.cloned_event_bus().publish(event),
+            Err(_) => event.log(),
+        }
+    }
+
+    // 保存运行时数据，开启检查点时随工作流检查点写入数据库
+    fn save_runtime_store(&self, store: &impl Serialize) -> Result<()> {
+        self.workflow_context()?
+            .save_runtime_store(self.node().id, || Ok(serde_json::to_string(store)?))
+    }
+
     fn connection<U: Send + Sync + 'static>(
         &self,                     // 当前节点
         target: &mut dyn NodeCore, // 目标节点
@@ -67,6 +91,18 @@ pub trait NodeCoreExt: NodeCore {
     ) -> Result<Decimal> {
         self.node_infra().price(exchange, market, symbol).await
     }
+
+    async fn klines_window(
+        &self,
+        exchange: &Exchange,
+        symbol: &Symbol,
+        interval: &KlineInterval,
+        len: usize,
+    ) -> Result<KlinesWindow> {
+        self.node_infra()
+            .klines_window(exchange, symbol, interval, len)
+            .await
+    }
 }
 
 pub trait NodeSpotStats {
@@ -101,6 +137,42 @@ pub trait NodeSpotStatsExt: NodeCore + NodeSpotStats {
             .update_with_tick(&ctx, exchange, symbol, tick)
             .await?;
 
+        ctx.record_event(
+            Event::builder()
+                .time(tick.timestamp * 1000)
+                .kind(EventKind::Tick)
+                .node_id(ctx.node_id())
+                .exchange(exchange.as_ref())
+                .symbol(symbol.as_ref())
+                .price(tick.price)
+                .build(),
+        );
+
+        ctx.publish(WorkflowEvent::TickConsumed {
+            node_id: self.node().id,
+            exchange: exchange.to_string(),
+            symbol: symbol.to_string(),
+            timestamp: tick.timestamp,
+            price: tick.price,
+        });
+
+        // 没有订阅者时不计算市值
+        if ctx.has_event_subscribers() {
+            let data = self.spot_stats_data(exchange, symbol)?;
+
+            ctx.publish(WorkflowEvent::EquityUpdated {
+                node_id: self.node().id,
+                exchange: exchange.to_string(),
+                symbol: symbol.to_string(),
+                quote_asset: data.base.quote_asset.clone(),
+                equity: data.equity(ctx.valuation_policy()),
+                realized_pnl: data.base.realized_pnl,
+                unrealized_pnl: data.base.unrealized_pnl,
+                exposure: data.base_asset_balance * tick.price,
+                timestamp: tick.timestamp,
+            });
+        }
+
         Ok(())
     }
 
@@ -112,10 +184,159 @@ pub trait NodeSpotStatsExt: NodeCore + NodeSpotStats {
     ) -> Result<()> {
         let ctx = self.node_context()?;
 
+        self.spot_stats().save_order(&ctx, order).await?;
+
+        // 未完结的订单等待交易所推送后续成交
+        self.spot_stats_mut().track_order(order)?;
         self.spot_stats_mut()
             .update_with_order(&ctx, exchange, symbol, order)
             .await?;
 
+        self.record_order_events(&ctx, exchange, symbol, order)?;
+
+        ctx.publish(WorkflowEvent::OrderPlaced {
+            node_id: self.node().id,
+            exchange: exchange.to_string(),
+            symbol: symbol.to_string(),
+            order_id: order.order_id.clone(),
+            side: format!("{:?}", order.order_side),
+            status: format!("{:?}", order.order_status),
+            price: order.price.clone(),
+            qty: order.orig_qty.clone(),
+            time: order.time,
+        });
+        self.publish_fill(&ctx, exchange, symbol, order);
+        self.publish_stats(&ctx, exchange, symbol)?;
+
+        Ok(())
+    }
+
+    // 处理交易所推送的用户数据，本节点订单的新增成交计入统计
+    async fn update_spot_stats_with_user_data(&mut self, event: &UserDataEvent) -> Result<()> {
+        let UserDataEvent::OrderUpdate(update) = event else {
+            return Ok(());
+        };
+
+        let ctx = self.node_context()?;
+
+        // 需在 order_fill 之前保存，完结的订单会停止跟踪
+        self.spot_stats().save_order_update(&ctx, update).await?;
+
+        let Some(order) = self.spot_stats_mut().order_fill(update) else {
+            return Ok(());
+        };
+
+        self.spot_stats_mut()
+            .update_with_order(&ctx, &update.exchange, &update.symbol, &order)
+            .await?;
+
+        self.record_order_events(&ctx, &update.exchange, &update.symbol, &order)?;
+        self.publish_fill(&ctx, &update.exchange, &update.symbol, &order);
+        self.publish_stats(&ctx, &update.exchange, &update.symbol)?;
+
+        tracing::info!(
+            monotonic_counter.spot_order_fill_pushed = 1_u64,
+            exchange = %update.exchange,
+            symbol = %update.symbol,
+            order_id = %update.order_id,
+            "Pushed order fill: {}",
+            order.executed_qty
+        );
+
+        Ok(())
+    }
+
+    // 订单有成交时发布成交事件
+    fn publish_fill(&self, ctx: &NodeContext, exchange: &Exchange, symbol: &Symbol, order: &Order) {
+        let executed_qty = order.executed_qty.parse::<Decimal>().unwrap_or_default();
+
+        if executed_qty <= Decimal::ZERO {
+            return;
+        }
+
+        ctx.publish(WorkflowEvent::OrderFilled {
+            node_id: self.node().id,
+            exchange: exchange.to_string(),
+            symbol: symbol.to_string(),
+            order_id: order.order_id.clone(),
+            side: format!("{:?}", order.order_side),
+            price: order.avg_price.clone(),
+            qty: order.executed_qty.clone(),
+            time: order.update_time,
+        });
+    }
+
+    // 发布统计更新事件，没有订阅者时不序列化统计数据
+    fn publish_stats(&self, ctx: &NodeContext, exchange: &Exchange, symbol: &Symbol) -> Result<()> {
+        if !ctx.has_event_subscribers() {
+            return Ok(());
+        }
+
+        ctx.publish(WorkflowEvent::StatsUpdated {
+            node_id: self.node().id,
+            exchange: exchange.to_string(),
+            symbol: symbol.to_string(),
+            stats: serde_json::to_value(self.spot_stats_data(exchange, symbol)?)?,
+        });
+
+        Ok(())
+    }
+
+    // 记录订单、成交和订单更新后的统计快照
+    fn record_order_events(
+        &self,
+        ctx: &NodeContext,
+        exchange: &Exchange,
+        symbol: &Symbol,
+        order: &Order,
+    ) -> Result<()> {
+        if !ctx.event_log_enabled() {
+            return Ok(());
+        }
+
+        let event = |kind: EventKind| {
+            Event::builder()
+                .time(order.update_time)
+                .kind(kind)
+                .node_id(ctx.node_id())
+                .exchange(exchange.as_ref())
+                .symbol(symbol.as_ref())
+        };
+        let side = format!("{:?}", order.order_side);
+        let executed_qty = order.executed_qty.parse::<Decimal>().unwrap_or_default();
+
+        ctx.record_event(
+            event(EventKind::Order)
+                .side(side.clone())
+                .maybe_price(order.price.parse::<Decimal>().ok())
+                .maybe_qty(order.orig_qty.parse::<Decimal>().ok())
+                .detail(format!(
+                    "{} {:?} {:?}",
+                    order.order_id, order.order_type, order.order_status
+                ))
+                .build(),
+        );
+
+        if executed_qty > Decimal::ZERO {
+            ctx.record_event(
+                event(EventKind::Fill)
+                    .side(side)
+                    .maybe_price(order.avg_price.parse::<Decimal>().ok())
+                    .qty(executed_qty)
+                    .detail(order.order_id.clone())
+                    .build(),
+            );
+        }
+
+        let stats_data = self.spot_stats_data(exchange, symbol)?;
+        ctx.record_event(
+            event(EventKind::Stats)
+                .price(stats_data.avg_price)
+                .qty(stats_data.base_asset_balance)
+                .detail(serde_json::to_string(stats_data)?)
+                .build(),
+        );
+
         Ok(())
     }
 }
@@ -125,6 +346,14 @@ impl<T: ?Sized> SpotTradeable for T where T: NodeCore + NodeSpotStats {}
 /// 交易接口
 #[allow(async_fn_in_trait)]
 pub trait SpotTradeable: NodeCore + NodeSpotStats {
+    // 记录交易接口请求次数
+    fn record_exchange_requests(&self, requests: u64) -> Result<()> {
+        self.node_context()?
+            .resource_meter()
+            .add_exchange_requests(requests);
+        Ok(())
+    }
+
     async fn market_buy(
         &mut self,
         client: &SpotClientKind,
@@ -136,6 +365,7 @@ pub trait SpotTradeable: NodeCore + NodeSpotStats {
         let symbol = client.symbol(base_asset, quote_asset);
 
         // 提交交易
+        self.record_exchange_requests(1)?;
         let order = client.market_buy(base_asset, quote_asset, qty).await?;
 
         // 更新统计信息
@@ -156,6 +386,7 @@ pub trait SpotTradeable: NodeCore + NodeSpotStats {
         let symbol = client.symbol(base_asset, quote_asset);
 
         // 提交交易
+        self.record_exchange_requests(1)?;
         let order = client.market_sell(base_asset, quote_asset, qty).await?;
 
         // 更新统计信息
@@ -164,6 +395,209 @@ pub trait SpotTradeable: NodeCore + NodeSpotStats {
 
         Ok(order)
     }
+
+    // 按计价货币金额买入，例如定投100 USDT
+    async fn market_buy_quote(
+        &mut self,
+        client: &SpotClientKind,
+        base_asset: &str,
+        quote_asset: &str,
+        quote_qty: f64,
+    ) -> Result<Order> {
+        let exchange = client.exchange();
+        let symbol = client.symbol(base_asset, quote_asset);
+
+        // 提交交易
+        self.record_exchange_requests(1)?;
+        let order = client
+            .market_buy_quote(base_asset, quote_asset, quote_qty)
+            .await?;
+
+        // 更新统计信息
+        self.update_spot_stats_with_order(&exchange, &symbol, &order)
+            .await?;
+
+        Ok(order)
+    }
+
+    // 按计价货币金额卖出
+    async fn market_sell_quote(
+        &mut self,
+        client: &SpotClientKind,
+        base_asset: &str,
+        quote_asset: &str,
+        quote_qty: f64,
+    ) -> Result<Order> {
+        let exchange = client.exchange();
+        let symbol = client.symbol(base_asset, quote_asset);
+
+        // 提交交易
+        self.record_exchange_requests(1)?;
+        let order = client
+            .market_sell_quote(base_asset, quote_asset, quote_qty)
+            .await?;
+
+        // 更新统计信息
+        self.update_spot_stats_with_order(&exchange, &symbol, &order)
+            .await?;
+
+        Ok(order)
+    }
+
+    // 批量下单，按顺序返回每个订单的结果，成交的订单更新统计信息
+    async fn submit_batch(
+        &mut self,
+        client: &SpotClientKind,
+        intents: Vec<OrderIntent>,
+    ) -> Result<Vec<Result<Order>>> {
+        let exchange = client.exchange();
+        let symbols = intents
+            .iter()
+            .map(|intent| client.symbol(intent.base_asset(), intent.quote_asset()))
+            .collect::<Vec<_>>();
+
+        // 提交交易
+        self.record_exchange_requests(intents.len() as u64)?;
+        let results = client.submit_batch(intents).await;
+
+        // 更新统计信息
+        for (symbol, result) in symbols.iter().zip(&results) {
+            match result {
+                Ok(order) => {
+                    self.update_spot_stats_with_order(&exchange, symbol, order)
+                        .await?
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        monotonic_counter.spot_batch_order_failed = 1_u64,
+                        exchange = %exchange,
+                        symbol = %symbol,
+                        "Batch order failed: {}",
+                        e
+                    );
+                    self.publish_error(format!(
+                        "Batch order {} {} failed: {}",
+                        exchange, symbol, e
+                    ));
+                }
+            }
+        }
+
+        Ok(results)
+    }
+
+    // 清理残余余额：卖出后剩余的基础资产低于最小名义价值时无法再卖出，
+    // 在统计中核销为不可实现，返回核销的数量
+    async fn write_off_dust(
+        &mut self,
+        client: &SpotClientKind,
+        base_asset: &str,
+        quote_asset: &str,
+        price: Decimal,
+    ) -> Result<Option<Decimal>> {
+        let exchange = client.exchange();
+        let symbol = client.symbol(base_asset, quote_asset);
+        self.record_exchange_requests(1)?;
+        let symbol_info = client.get_symbol_info(base_asset, quote_asset).await?;
+
+        let ctx = self.node_context()?;
+        let dust = self
+            .spot_stats_mut()
+            .write_off_dust(&ctx, &exchange, &symbol, price, &symbol_info)
+            .await?;
+
+        if let Some(dust) = dust {
+            tracing::info!(
+                monotonic_counter.spot_dust_write_off = 1_u64,
+                exchange = %exchange,
+                symbol = %symbol,
+                %dust,
+                "Dust balance written off"
+            );
+        }
+
+        Ok(dust)
+    }
+
+    // 从检查点恢复后核对交易对的未完结订单，补记停机期间错过推送的成交
+    async fn reconcile_open_orders(
+        &mut self,
+        client: &SpotClientKind,
+        base_asset: &str,
+        quote_asset: &str,
+    ) -> Result<()> {
+        let order_ids = self.spot_stats().open_order_ids();
+
+        if order_ids.is_empty() {
+            return Ok(());
+        }
+
+        for order_id in &order_ids {
+            self.record_exchange_requests(1)?;
+
+            // 查询失败的订单继续跟踪，等待推送的后续成交
+            let order = match client.get_order(base_asset, quote_asset, order_id).await {
+                Ok(order) => order,
+                Err(e) => {
+                    tracing::warn!("Reconcile order {} failed: {}", order_id, e);
+                    continue;
+                }
+            };
+
+            let update = OrderUpdate::try_from(&order)?;
+            self.update_spot_stats_with_user_data(&UserDataEvent::OrderUpdate(update))
+                .await?;
+        }
+
+        tracing::info!(
+            monotonic_counter.open_orders_reconciled = order_ids.len() as u64,
+            node_id = self.node().id,
+            "Open orders reconciled"
+        );
+
+        Ok(())
+    }
+
+    // 工作流停止时撤销交易对上本节点跟踪的未完结订单，撤销后的订单状态计入统计，返回撤销的数量
+    async fn cancel_open_orders(
+        &mut self,
+        client: &SpotClientKind,
+        base_asset: &str,
+        quote_asset: &str,
+    ) -> Result<usize> {
+        let mut cancelled = 0;
+
+        for order_id in self.spot_stats().open_order_ids() {
+            self.record_exchange_requests(1)?;
+
+            // 撤销失败的订单(如已成交或不属于该交易对)留待恢复时核对
+            let order = match client
+                .cancel_order(base_asset, quote_asset, &order_id)
+                .await
+            {
+                Ok(order) => order,
+                Err(e) => {
+                    tracing::warn!("Cancel order {} failed: {}", order_id, e);
+                    continue;
+                }
+            };
+
+            let update = OrderUpdate::try_from(&order)?;
+            self.update_spot_stats_with_user_data(&UserDataEvent::OrderUpdate(update))
+                .await?;
+            cancelled += 1;
+        }
+
+        if cancelled > 0 {
+            tracing::info!(
+                monotonic_counter.open_orders_cancelled = cancelled as u64,
+                node_id = self.node().id,
+                "Open orders cancelled"
+            );
+        }
+
+        Ok(cancelled)
+    }
 }
 
 // 节点执行
@@ -174,7 +608,13 @@ pub trait NodeExecutable {
         Ok(())
     }
 
-    async fn execute(&mut self) -> Result<()> {
+    // 返回分类的错误，由工作流执行器决定重试、跳过还是终止运行
+    async fn execute(&mut self) -> Result<(), NodeError> {
+        Ok(())
+    }
+
+    // 停止后的收尾，在节点退出执行之后调用，如撤销挂单、保存运行时数据
+    async fn shutdown(&mut self, _cancel_orders: bool) -> Result<()> {
         Ok(())
     }
 }
@@ -212,6 +652,14 @@ pub trait TradeStats {
     async fn unrealized_pnl(&self) -> Result<Decimal>;
     // 运行时间
     async fn running_time(&self) -> Result<u128>;
+    // 时间加权收益率，剔除资金进出的影响
+    async fn time_weighted_return(&self) -> Result<Decimal>;
+    // 资金加权收益率(年化内部收益率)
+    async fn money_weighted_return(&self) -> Result<Decimal>;
+    // 按小时采样的持仓市值序列(时间戳秒, 市值)
+    async fn equity_curve(&self) -> Result<Vec<(i64, Decimal)>>;
+    // 交易记录汇总
+    async fn trade_record(&self) -> Result<TradeRecord>;
     // 资产历史
     // async fn asset_history(
     //     &self,
@@ -284,4 +732,12 @@ pub trait TradeStatsExt: TradeStats {
 
         Ok(annualized)
     }
+
+    // 绩效报告，由净值序列和交易记录计算
+    async fn performance_report(&self) -> Result<PerformanceReport> {
+        let equity_curve = self.equity_curve().await?;
+        let trade_record = self.trade_record().await?;
+
+        Ok(PerformanceReport::new(&equity_curve, &trade_record))
+    }
 }
@@ -0,0 +1,128 @@
+use anyhow::{anyhow, Result};
+use rust_decimal::Decimal;
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+};
+
+// 估值策略，从工作流 config 中读取:
+//      valuation_haircuts: 资产估值折扣，如 "PEPE:0.3,WIF:0.5"，折扣比例在 0~1 之间
+//      valuation_unpriceable: 无可靠价格的资产，如 "FOO,BAR"，估值记为0
+// 避免流动性差的资产以最后一笔成交价虚增持仓市值
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ValuationPolicy {
+    haircuts: HashMap<String, Decimal>, // 资产 -> 折扣比例
+    unpriceable: HashSet<String>,       // 无法估值的资产
+}
+
+impl ValuationPolicy {
+    pub fn new() -> Self {
+        ValuationPolicy::default()
+    }
+
+    pub fn from_config(config: &HashMap<String, String>) -> Result<Self> {
+        let mut policy = ValuationPolicy::new();
+
+        if let Some(haircuts) = config.get("valuation_haircuts") {
+            for item in split_list(haircuts) {
+                let (asset, haircut) = item
+                    .split_once(':')
+                    .ok_or_else(|| anyhow!("Invalid valuation haircut: {}", item))?;
+                let haircut = Decimal::from_str(haircut.trim())?;
+
+                policy = policy.with_haircut(asset.trim(), haircut)?;
+            }
+        }
+
+        if let Some(unpriceable) = config.get("valuation_unpriceable") {
+            for asset in split_list(unpriceable) {
+                policy = policy.with_unpriceable(asset);
+            }
+        }
+
+        Ok(policy)
+    }
+
+    // 设置资产估值折扣
+    pub fn with_haircut(mut self, asset: impl Into<String>, haircut: Decimal) -> Result<Self> {
+        let asset = asset.into();
+
+        anyhow::ensure!(
+            (Decimal::ZERO..=Decimal::ONE).contains(&haircut),
+            "Valuation haircut of {} must be between 0 and 1",
+            asset
+        );
+
+        self.haircuts.insert(asset, haircut);
+
+        Ok(self)
+    }
+
+    // 标记资产无法估值
+    pub fn with_unpriceable(mut self, asset: impl Into<String>) -> Self {
+        self.unpriceable.insert(asset.into());
+        self
+    }
+
+    pub fn is_priceable(&self, asset: &str) -> bool {
+        !self.unpriceable.contains(asset)
+    }
+
+    // 资产估值折扣，无法估值的资产折扣为1
+    pub fn haircut(&self, asset: &str) -> Decimal {
+        if !self.is_priceable(asset) {
+            return Decimal::ONE;
+        }
+
+        self.haircuts.get(asset).copied().unwrap_or_default()
+    }
+
+    // 按估值策略调整资产市值
+    pub fn value(&self, asset: &str, market_value: Decimal) -> Decimal {
+        market_value * (Decimal::ONE - self.haircut(asset))
+    }
+}
+
+fn split_list(value: &str) -> impl Iterator<Item = &str> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_valuation_policy_from_config() -> Result<()> {
+        let config = HashMap::from([
+            (
+                "valuation_haircuts".to_string(),
+                "PEPE:0.3, WIF:0.5".to_string(),
+            ),
+            ("valuation_unpriceable".to_string(), "FOO,".to_string()),
+        ]);
+        let policy = ValuationPolicy::from_config(&config)?;
+
+        assert_eq!(policy.value("PEPE", dec!(100)), dec!(70));
+        assert_eq!(policy.value("WIF", dec!(100)), dec!(50));
+        assert_eq!(policy.value("BTC", dec!(100)), dec!(100));
+        assert_eq!(policy.value("FOO", dec!(100)), dec!(0));
+        assert!(!policy.is_priceable("FOO"));
+
+        assert_eq!(
+            ValuationPolicy::from_config(&HashMap::new())?,
+            ValuationPolicy::new()
+        );
+
+        let config = HashMap::from([("valuation_haircuts".to_string(), "PEPE:1.5".to_string())]);
+        assert!(ValuationPolicy::from_config(&config).is_err());
+
+        let config = HashMap::from([("valuation_haircuts".to_string(), "PEPE".to_string())]);
+        assert!(ValuationPolicy::from_config(&config).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,197 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+// 节点心跳，节点取到输入开始处理时标记忙碌，处理结束后恢复空闲
+// 空闲节点(等待输入)不会被看门狗判定为卡住
+#[derive(Debug)]
+pub struct Heartbeat {
+    started_at: Instant,   // 计时起点
+    busy_since: AtomicU64, // 开始处理的时间(毫秒+1)，0表示空闲
+    beats: AtomicU64,      // 处理次数
+}
+
+impl Default for Heartbeat {
+    fn default() -> Self {
+        Heartbeat {
+            started_at: Instant::now(),
+            busy_since: AtomicU64::new(0),
+            beats: AtomicU64::new(0),
+        }
+    }
+}
+
+impl Heartbeat {
+    pub fn new() -> Self {
+        Heartbeat::default()
+    }
+
+    fn elapsed_millis(&self) -> u64 {
+        self.started_at.elapsed().as_millis() as u64
+    }
+
+    // 开始处理一次输入，返回的守卫释放时恢复空闲
+    pub fn busy(self: &Arc<Self>) -> BusyGuard {
+        let now = self.elapsed_millis();
+
+        self.busy_since.store(now + 1, Ordering::Release);
+        self.beats.fetch_add(1, Ordering::AcqRel);
+
+        BusyGuard(Arc::clone(self))
+    }
+
+    // 本次处理已持续的时间，空闲时为None
+    pub fn busy_for(&self) -> Option<Duration> {
+        match self.busy_since.load(Ordering::Acquire) {
+            0 => None,
+            since => Some(Duration::from_millis(
+                self.elapsed_millis().saturating_sub(since - 1),
+            )),
+        }
+    }
+
+    pub fn beats(&self) -> u64 {
+        self.beats.load(Ordering::Acquire)
+    }
+
+    fn reset(&self) {
+        self.busy_since.store(0, Ordering::Release);
+    }
+}
+
+pub struct BusyGuard(Arc<Heartbeat>);
+
+impl Drop for BusyGuard {
+    fn drop(&mut self) {
+        self.0.reset();
+    }
+}
+
+// 看门狗配置，从工作流 config 中读取:
+//      watchdog_timeout_secs: 节点单次处理超过该时间视为卡住，不设置则不启用
+//      watchdog_restart: 卡住时是否重启节点，默认只告警
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Watchdog {
+    timeout: Duration,
+    restart: bool,
+}
+
+impl Watchdog {
+    pub(crate) fn new(timeout: Duration, restart: bool) -> Self {
+        Watchdog { timeout, restart }
+    }
+
+    pub(crate) fn from_config(config: &HashMap<String, String>) -> Option<Self> {
+        let timeout = config
+            .get("watchdog_timeout_secs")
+            .and_then(|secs| secs.parse::<u64>().ok())
+            .filter(|secs| *secs > 0)?;
+        let restart = config
+            .get("watchdog_restart")
+            .is_some_and(|restart| restart == "true");
+
+        Some(Watchdog::new(Duration::from_secs(timeout), restart))
+    }
+
+    // 检查间隔
+    fn interval(&self) -> Duration {
+        (self.timeout / 4).clamp(Duration::from_millis(10), Duration::from_secs(1))
+    }
+
+    // 监控节点心跳，每次卡住只告警一次；需要重启时返回
+    pub(crate) async fn watch(&self, node_name: &str, heartbeat: &Heartbeat) {
+        let mut alerted_beat = None;
+
+        loop {
+            tokio::time::sleep(self.interval()).await;
+
+            let Some(busy_for) = heartbeat.busy_for() else {
+                continue;
+            };
+
+            if busy_for < self.timeout {
+                continue;
+            }
+
+            let beat = heartbeat.beats();
+
+            if alerted_beat != Some(beat) {
+                alerted_beat = Some(beat);
+
+                tracing::warn!(
+                    monotonic_counter.node_watchdog_timeout = 1_u64,
+                    node = node_name,
+                    busy_ms = busy_for.as_millis() as u64,
+                    "Node stopped making progress"
+                );
+            }
+
+            if self.restart {
+                heartbeat.reset();
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_heartbeat_busy_guard() {
+        let heartbeat = Arc::new(Heartbeat::new());
+        assert_eq!(heartbeat.busy_for(), None);
+
+        {
+            let _busy = heartbeat.busy();
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            assert!(heartbeat.busy_for().unwrap() >= Duration::from_millis(20));
+        }
+
+        assert_eq!(heartbeat.busy_for(), None);
+        assert_eq!(heartbeat.beats(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_watchdog_restart_when_stalled() {
+        let watchdog = Watchdog::new(Duration::from_millis(50), true);
+        let heartbeat = Arc::new(Heartbeat::new());
+
+        // 空闲节点不会触发
+        let idle = tokio::time::timeout(
+            Duration::from_millis(150),
+            watchdog.watch("idle", &heartbeat),
+        )
+        .await;
+        assert!(idle.is_err());
+
+        let _busy = heartbeat.busy();
+        let stalled = tokio::time::timeout(
+            Duration::from_millis(500),
+            watchdog.watch("stalled", &heartbeat),
+        )
+        .await;
+        assert!(stalled.is_ok());
+        assert_eq!(heartbeat.busy_for(), None);
+    }
+
+    #[test]
+    fn test_watchdog_from_config() {
+        let config = HashMap::from([
+            ("watchdog_timeout_secs".to_string(), "30".to_string()),
+            ("watchdog_restart".to_string(), "true".to_string()),
+        ]);
+
+        assert_eq!(
+            Watchdog::from_config(&config),
+            Some(Watchdog::new(Duration::from_secs(30), true))
+        );
+        assert_eq!(Watchdog::from_config(&HashMap::new()), None);
+    }
+}
@@ -0,0 +1,50 @@
+use anyhow::Result;
+use comfy_quant_exchange::announcement::Announcement;
+use flume::{Receiver, Sender};
+use tokio_util::sync::CancellationToken;
+
+// 交易所公告事件流
+#[derive(Debug)]
+pub(crate) struct AnnouncementStream {
+    inner: (Sender<Announcement>, Receiver<Announcement>),
+    token: CancellationToken,
+}
+
+#[allow(unused)]
+impl AnnouncementStream {
+    pub(crate) fn new() -> Self {
+        AnnouncementStream {
+            inner: flume::unbounded(),
+            token: CancellationToken::new(),
+        }
+    }
+
+    pub(crate) async fn send(&self, announcement: &Announcement) -> Result<()> {
+        self.inner.0.send_async(announcement.clone()).await?;
+        Ok(())
+    }
+
+    pub(crate) fn subscribe(&self) -> Receiver<Announcement> {
+        self.inner.1.clone()
+    }
+
+    // 标记数据已发送完毕
+    pub(crate) fn finish(&self) {
+        self.token.cancel();
+    }
+
+    // 接收下一条公告，数据发送完毕且已全部接收后返回None
+    pub(crate) async fn next(&self, rx: &Receiver<Announcement>) -> Option<Announcement> {
+        tokio::select! {
+            biased;
+            announcement = rx.recv_async() => announcement.ok(),
+            _ = self.token.cancelled() => rx.try_recv().ok(),
+        }
+    }
+}
+
+impl Drop for AnnouncementStream {
+    fn drop(&mut self) {
+        self.token.cancel();
+    }
+}
@@ -0,0 +1,115 @@
+use crate::node_core::CapitalAllocator;
+use rust_decimal::Decimal;
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::Mutex,
+};
+
+#[derive(Debug, Default)]
+struct AllocationState {
+    equities: BTreeMap<u32, Decimal>,         // 子策略最新的权益
+    curves: BTreeMap<u32, VecDeque<Decimal>>, // 每次再分配时采样的权益曲线
+    budgets: BTreeMap<u32, Decimal>,          // 子策略的资金预算
+    last_rebalance_at: Option<i64>,           // 上次再分配的时间(秒)
+}
+
+// 多策略资金分配通道，由资金分配节点输出给各子策略：
+// 子策略每个tick上报权益，到达再分配间隔时按权益曲线重新计算各子策略的资金预算
+#[derive(Debug)]
+pub(crate) struct CapitalAllocation {
+    allocator: CapitalAllocator,
+    state: Mutex<AllocationState>,
+}
+
+impl CapitalAllocation {
+    pub(crate) fn new(allocator: CapitalAllocator) -> Self {
+        CapitalAllocation {
+            allocator,
+            state: Mutex::new(AllocationState::default()),
+        }
+    }
+
+    // 上报子策略权益，返回子策略当前的资金预算，尚未分配时为空
+    pub(crate) fn report(&self, node_id: u32, equity: Decimal, timestamp: i64) -> Option<Decimal> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.equities.insert(node_id, equity);
+
+        let last_rebalance_at = *state.last_rebalance_at.get_or_insert(timestamp);
+
+        if timestamp - last_rebalance_at >= self.allocator.rebalance_secs() {
+            self.rebalance(&mut state, timestamp);
+        }
+
+        state.budgets.get(&node_id).copied()
+    }
+
+    // 当前各子策略的资金预算
+    pub(crate) fn budgets(&self) -> BTreeMap<u32, Decimal> {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.budgets.clone()
+    }
+
+    // 采样权益曲线，按子策略的总权益重新分配
+    fn rebalance(&self, state: &mut AllocationState, timestamp: i64) {
+        let lookback = self.allocator.lookback().max(2);
+        let AllocationState {
+            equities, curves, ..
+        } = state;
+
+        for (node_id, equity) in equities.iter() {
+            let curve = curves.entry(*node_id).or_default();
+            curve.push_back(*equity);
+
+            if curve.len() > lookback {
+                curve.pop_front();
+            }
+        }
+
+        let total = state.equities.values().sum::<Decimal>();
+
+        state.budgets = self
+            .allocator
+            .weights(&state.curves)
+            .into_iter()
+            .map(|(node_id, weight)| (node_id, (total * weight).round_dp(8)))
+            .collect();
+        state.last_rebalance_at = Some(timestamp);
+
+        tracing::info!(
+            monotonic_counter.capital_reallocated = 1_u64,
+            strategies = state.budgets.len(),
+            "Capital reallocated: {:?}",
+            state.budgets
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node_core::AllocationMethod;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_capital_allocation() {
+        let allocation = CapitalAllocation::new(
+            CapitalAllocator::builder()
+                .method(AllocationMethod::Momentum)
+                .rebalance_secs(60)
+                .build(),
+        );
+
+        // 未到再分配间隔时没有预算
+        assert_eq!(allocation.report(1, dec!(1000), 0), None);
+        assert_eq!(allocation.report(2, dec!(1000), 0), None);
+
+        // 第一次采样数据不足，等权分配
+        assert_eq!(allocation.report(1, dec!(1100), 60), Some(dec!(1050)));
+        assert_eq!(allocation.report(2, dec!(1000), 61), Some(dec!(1050)));
+
+        // 子策略1的权益上涨，子策略2下跌，资金全部分配给子策略1
+        allocation.report(2, dec!(900), 100);
+        assert_eq!(allocation.report(1, dec!(1200), 120), Some(dec!(2100)));
+        assert_eq!(allocation.budgets()[&2], dec!(0));
+    }
+}
@@ -0,0 +1,191 @@
+use super::KlineStream;
+use crate::node_core::{Bar, Slot};
+use comfy_quant_base::{Exchange, KlineInterval, Market};
+use flume::Receiver;
+use futures::future::{self, FutureExt};
+use std::{collections::VecDeque, sync::Arc};
+
+type ExchangeBar = (Exchange, Market, Bar);
+
+// 多周期K线对齐，以最小周期的K线推进模拟时间，
+// 大周期K线只有在模拟时间到达其收盘时间后才会交付，避免使用未来数据
+#[derive(Debug)]
+pub(crate) struct IntervalAligner {
+    base_interval: KlineInterval, // 驱动模拟时间的最小周期
+    now: Option<i64>,             // 当前模拟时间(秒)
+    pending: Vec<ExchangeBar>,    // 尚未收盘的大周期K线
+}
+
+impl IntervalAligner {
+    pub(crate) fn new(base_interval: KlineInterval) -> Self {
+        IntervalAligner {
+            base_interval,
+            now: None,
+            pending: Vec::new(),
+        }
+    }
+
+    // 推入一根K线，返回可以交付的K线
+    pub(crate) fn push(&mut self, item: ExchangeBar) -> Vec<ExchangeBar> {
+        let bar = &item.2;
+
+        if bar.interval != self.base_interval {
+            if self.now.is_some_and(|now| bar.close_time() <= now) {
+                return vec![item];
+            }
+
+            self.pending.push(item);
+            return vec![];
+        }
+
+        let now = bar.close_time();
+        self.now = Some(now);
+
+        // 已收盘的大周期K线，按收盘时间、周期从小到大交付
+        let (mut ready, pending): (Vec<_>, Vec<_>) = self
+            .pending
+            .drain(..)
+            .partition(|(_, _, bar)| bar.close_time() <= now);
+        self.pending = pending;
+        ready.sort_by_key(|(_, _, bar)| (bar.close_time(), bar.interval.to_seconds()));
+
+        let mut bars = vec![item];
+        bars.extend(ready);
+        bars
+    }
+}
+
+// 同一交易对多个周期的K线流，按模拟时间对齐后交付
+pub(crate) struct AlignedKlines {
+    streams: Vec<(Arc<Slot<KlineStream>>, Receiver<ExchangeBar>)>,
+    aligner: IntervalAligner,
+    ready: VecDeque<ExchangeBar>,
+}
+
+impl AlignedKlines {
+    // base_interval为最小周期
+    pub(crate) fn new(streams: Vec<Arc<Slot<KlineStream>>>, base_interval: KlineInterval) -> Self {
+        let streams = streams
+            .into_iter()
+            .map(|stream| {
+                let rx = stream.subscribe();
+                (stream, rx)
+            })
+            .collect();
+
+        AlignedKlines {
+            streams,
+            aligner: IntervalAligner::new(base_interval),
+            ready: VecDeque::new(),
+        }
+    }
+
+    // 接收下一根已对齐的K线，所有K线流结束后返回None
+    pub(crate) async fn next(&mut self) -> Option<ExchangeBar> {
+        loop {
+            if let Some(item) = self.ready.pop_front() {
+                return Some(item);
+            }
+
+            if self.streams.is_empty() {
+                return None;
+            }
+
+            let futures = self
+                .streams
+                .iter()
+                .map(|(stream, rx)| stream.next(rx).boxed())
+                .collect::<Vec<_>>();
+
+            let (item, index, _) = future::select_all(futures).await;
+
+            match item {
+                Some(item) => self.ready.extend(self.aligner.push(item)),
+                None => {
+                    self.streams.remove(index);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use rust_decimal_macros::dec;
+
+    fn bar(interval: KlineInterval, open_time: i64) -> ExchangeBar {
+        let bar = Bar::builder()
+            .symbol("BTCUSDT".into())
+            .interval(interval)
+            .open_time(open_time)
+            .open(dec!(100))
+            .high(dec!(100))
+            .low(dec!(100))
+            .close(dec!(100))
+            .volume(dec!(1))
+            .build();
+
+        (Exchange::Binance, Market::Spot, bar)
+    }
+
+    fn open_times(bars: &[ExchangeBar]) -> Vec<(i64, i64)> {
+        bars.iter()
+            .map(|(_, _, bar)| (bar.interval.to_seconds(), bar.open_time))
+            .collect()
+    }
+
+    #[test]
+    fn test_interval_aligner() {
+        let mut aligner = IntervalAligner::new(KlineInterval::OneMinute);
+
+        // 1小时K线先到达，尚未收盘
+        assert!(aligner.push(bar(KlineInterval::OneHour, 0)).is_empty());
+
+        let bars = aligner.push(bar(KlineInterval::OneMinute, 0));
+        assert_eq!(open_times(&bars), vec![(60, 0)]);
+
+        // 最后一根1分钟K线收盘时，1小时K线同时收盘
+        let bars = aligner.push(bar(KlineInterval::OneMinute, 3540));
+        assert_eq!(open_times(&bars), vec![(60, 3540), (3600, 0)]);
+
+        // 已收盘的K线直接交付
+        let bars = aligner.push(bar(KlineInterval::OneHour, 0));
+        assert_eq!(open_times(&bars), vec![(3600, 0)]);
+    }
+
+    #[tokio::test]
+    async fn test_aligned_klines() -> Result<()> {
+        let minute_stream = Arc::new(Slot::new(KlineStream::new()));
+        let hour_stream = Arc::new(Slot::new(KlineStream::new()));
+
+        for open_time in [0, 3600] {
+            let (exchange, market, bar) = bar(KlineInterval::OneHour, open_time);
+            hour_stream.send(&exchange, &market, &bar).await?;
+        }
+        hour_stream.finish();
+
+        for open_time in (0..3600).step_by(60) {
+            let (exchange, market, bar) = bar(KlineInterval::OneMinute, open_time);
+            minute_stream.send(&exchange, &market, &bar).await?;
+        }
+        minute_stream.finish();
+
+        let mut aligned = AlignedKlines::new(
+            vec![Arc::clone(&minute_stream), Arc::clone(&hour_stream)],
+            KlineInterval::OneMinute,
+        );
+
+        let mut bars = vec![];
+        while let Some(item) = aligned.next().await {
+            bars.push(item);
+        }
+
+        // 60根1分钟K线 + 1根已收盘的1小时K线，第二根1小时K线未收盘不交付
+        assert_eq!(bars.len(), 61);
+        assert_eq!(open_times(&bars[59..]), vec![(60, 3540), (3600, 0)]);
+
+        Ok(())
+    }
+}
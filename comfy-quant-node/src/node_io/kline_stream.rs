@@ -0,0 +1,89 @@
+use crate::node_core::Bar;
+use anyhow::Result;
+use comfy_quant_base::{Exchange, Market};
+use flume::{Receiver, Sender};
+use tokio_util::sync::CancellationToken;
+
+type ExchangeBar = (Exchange, Market, Bar);
+
+#[derive(Debug)]
+#[allow(unused)]
+pub(crate) struct KlineStream {
+    inner: (Sender<ExchangeBar>, Receiver<ExchangeBar>),
+    token: CancellationToken,
+}
+
+#[allow(unused)]
+impl KlineStream {
+    pub(crate) fn new() -> Self {
+        KlineStream {
+            inner: flume::unbounded(),
+            token: CancellationToken::new(),
+        }
+    }
+
+    pub(crate) async fn send(&self, exchange: &Exchange, market: &Market, bar: &Bar) -> Result<()> {
+        self.inner
+            .0
+            .send_async((exchange.clone(), market.clone(), bar.clone()))
+            .await?;
+        Ok(())
+    }
+
+    pub(crate) fn subscribe(&self) -> Receiver<ExchangeBar> {
+        self.inner.1.clone()
+    }
+
+    // 标记数据已发送完毕
+    pub(crate) fn finish(&self) {
+        self.token.cancel();
+    }
+
+    // 接收下一根K线，数据发送完毕且已全部接收后返回None
+    pub(crate) async fn next(&self, rx: &Receiver<ExchangeBar>) -> Option<ExchangeBar> {
+        tokio::select! {
+            biased;
+            bar = rx.recv_async() => bar.ok(),
+            _ = self.token.cancelled() => rx.try_recv().ok(),
+        }
+    }
+}
+
+impl Drop for KlineStream {
+    fn drop(&mut self) {
+        self.token.cancel();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use comfy_quant_base::KlineInterval;
+    use rust_decimal_macros::dec;
+
+    #[tokio::test]
+    async fn test_kline_stream() -> Result<()> {
+        let kline_stream = KlineStream::new();
+        let bar = Bar::builder()
+            .symbol("BTCUSDT".into())
+            .interval(KlineInterval::OneMinute)
+            .open_time(0)
+            .open(dec!(100))
+            .high(dec!(110))
+            .low(dec!(90))
+            .close(dec!(105))
+            .volume(dec!(1))
+            .build();
+        let exchange = Exchange::Binance;
+        let market = Market::Spot;
+        let rx = kline_stream.subscribe();
+
+        kline_stream.send(&exchange, &market, &bar).await?;
+        kline_stream.finish();
+
+        assert_eq!(kline_stream.next(&rx).await, Some((exchange, market, bar)));
+        assert_eq!(kline_stream.next(&rx).await, None);
+
+        Ok(())
+    }
+}
@@ -1,6 +1,14 @@
+mod announcement_stream;
+mod capital_allocation;
+mod interval_aligner;
+mod kline_stream;
 mod log_kind;
 mod spot_pair_info;
 mod tick_stream;
 
+pub(crate) use announcement_stream::AnnouncementStream;
+pub(crate) use capital_allocation::CapitalAllocation;
+pub(crate) use interval_aligner::AlignedKlines;
+pub(crate) use kline_stream::KlineStream;
 pub(crate) use spot_pair_info::SpotPairInfo;
 pub(crate) use tick_stream::TickStream;
@@ -1,16 +1,80 @@
 use bon::Builder;
+use comfy_quant_database::spot_pairs::SpotPair;
+use rust_decimal::{Decimal, RoundingStrategy};
 
+// 交易对信息，精度和下单规则来自交易对缓存，下游策略统一按此取整
 #[derive(Debug, Builder, Clone)]
 #[builder(on(String, into))]
 #[allow(unused)]
 pub(crate) struct SpotPairInfo {
     pub(crate) base_asset: String,
     pub(crate) quote_asset: String,
+    pub(crate) base_asset_precision: Option<u32>, // 基础资产精度
+    pub(crate) quote_asset_precision: Option<u32>, // 计价资产精度
+    pub(crate) tick_size: Option<Decimal>,        // 价格步长
+    pub(crate) step_size: Option<Decimal>,        // 数量步长
+    pub(crate) min_notional: Option<Decimal>,     // 最小名义价值
+    pub(crate) status: Option<String>,            // 交易状态，如 TRADING
+}
+
+#[allow(unused)]
+impl SpotPairInfo {
+    // 使用缓存的交易对信息补全精度和下单规则
+    pub(crate) fn with_spot_pair(self, spot_pair: SpotPair) -> Self {
+        SpotPairInfo {
+            base_asset_precision: u32::try_from(spot_pair.base_asset_precision).ok(),
+            quote_asset_precision: u32::try_from(spot_pair.quote_asset_precision).ok(),
+            tick_size: spot_pair.tick_size,
+            step_size: spot_pair.step_size,
+            min_notional: spot_pair.min_notional,
+            status: Some(spot_pair.status),
+            ..self
+        }
+    }
+
+    // 价格按价格步长向下取整，未知步长时原样返回
+    pub(crate) fn round_price(&self, price: Decimal) -> Decimal {
+        round_to_step(price, self.tick_size)
+    }
+
+    // 数量按数量步长向下取整，未知步长时按基础资产精度截断
+    pub(crate) fn round_qty(&self, qty: Decimal) -> Decimal {
+        match (self.step_size, self.base_asset_precision) {
+            (Some(step_size), _) => round_to_step(qty, Some(step_size)),
+            (None, Some(precision)) => {
+                qty.round_dp_with_strategy(precision, RoundingStrategy::ToZero)
+            }
+            (None, None) => qty,
+        }
+    }
+
+    // 名义价值是否满足最小名义价值
+    pub(crate) fn meets_min_notional(&self, qty: Decimal, price: Decimal) -> bool {
+        self.min_notional
+            .is_none_or(|min_notional| qty * price >= min_notional)
+    }
+
+    // 交易对是否可交易，未知状态时视为可交易
+    pub(crate) fn is_trading(&self) -> bool {
+        self.status
+            .as_deref()
+            .is_none_or(|status| status == "TRADING")
+    }
+}
+
+fn round_to_step(value: Decimal, step: Option<Decimal>) -> Decimal {
+    match step {
+        Some(step) if step > Decimal::ZERO => (value / step).floor() * step,
+        _ => value,
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::Utc;
+    use comfy_quant_base::Exchange;
+    use rust_decimal_macros::dec;
 
     #[test]
     fn test_exchange_info_builder() {
@@ -21,5 +85,40 @@ mod tests {
 
         assert_eq!(exchange.base_asset, "BTC");
         assert_eq!(exchange.quote_asset, "USDT");
+        assert!(exchange.is_trading());
+        assert_eq!(exchange.round_price(dec!(30000.123)), dec!(30000.123));
+    }
+
+    #[test]
+    fn test_spot_pair_info_rounding() {
+        let spot_pair = SpotPair {
+            id: 1,
+            exchange: Exchange::Binance,
+            symbol: "BTCUSDT".into(),
+            base_asset: "BTC".into(),
+            quote_asset: "USDT".into(),
+            base_asset_precision: 8,
+            quote_asset_precision: 8,
+            quote_precision: 8,
+            status: "BREAK".into(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            tick_size: Some(dec!(0.01)),
+            step_size: Some(dec!(0.00001)),
+            min_notional: Some(dec!(5)),
+        };
+
+        let pair_info = SpotPairInfo::builder()
+            .base_asset("BTC")
+            .quote_asset("USDT")
+            .build()
+            .with_spot_pair(spot_pair);
+
+        assert_eq!(pair_info.base_asset_precision, Some(8));
+        assert_eq!(pair_info.round_price(dec!(30000.129)), dec!(30000.12));
+        assert_eq!(pair_info.round_qty(dec!(0.123456789)), dec!(0.12345));
+        assert!(pair_info.meets_min_notional(dec!(0.001), dec!(30000)));
+        assert!(!pair_info.meets_min_notional(dec!(0.0001), dec!(30000)));
+        assert!(!pair_info.is_trading());
     }
 }
@@ -1,8 +1,16 @@
-use crate::node_core::Tick;
+use crate::node_core::{Tick, TickRecorder};
 use anyhow::Result;
 use bon::Builder;
-use comfy_quant_base::{Exchange, Market};
+use comfy_quant_base::{Exchange, ExchangeMarketSymbolKey, Market, Symbol};
 use flume::{Receiver, Sender};
+use rust_decimal::Decimal;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Mutex,
+    },
+};
 use tokio_util::sync::CancellationToken;
 
 type ExchangeTick = (Exchange, Market, Tick);
@@ -11,6 +19,46 @@ type ExchangeTick = (Exchange, Market, Tick);
 pub(crate) struct TickStream {
     inner: (Sender<ExchangeTick>, Receiver<ExchangeTick>),
     token: CancellationToken,
+    #[builder(default)]
+    last_ticks: Mutex<HashMap<ExchangeMarketSymbolKey, (i64, Decimal)>>, // 每个交易对最后一个tick的毫秒时间戳和价格
+    #[builder(default)]
+    counter: TickStreamCounter, // 计数器
+    recorder: Option<Sender<ExchangeTick>>, // tick录制，记录实际发送给下游的tick
+    #[builder(default)]
+    symbol_subscribers: Mutex<Vec<(HashSet<Symbol>, Sender<ExchangeTick>)>>, // 按交易对过滤的订阅者
+    #[builder(default)]
+    shared_subscribed: AtomicBool, // 是否有订阅者接收共享通道
+}
+
+#[derive(Debug, Default)]
+struct TickStreamCounter {
+    delivered: AtomicU64,    // 已发送
+    duplicated: AtomicU64,   // 重复(已丢弃)
+    out_of_order: AtomicU64, // 乱序(已丢弃)
+    same_second: AtomicU64,  // 与上一个tick时间戳相同但价格不同(已发送)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TickStreamMetrics {
+    pub(crate) delivered: u64,    // 已发送
+    pub(crate) duplicated: u64,   // 重复(已丢弃)
+    pub(crate) out_of_order: u64, // 乱序(已丢弃)
+    pub(crate) same_second: u64,  // 与上一个tick时间戳相同但价格不同(已发送)
+}
+
+// 只接收指定交易对的订阅，每个订阅有独立的通道，发送时按交易对分发，
+// 不影响共享通道和其他订阅者收到的tick
+#[derive(Debug, Clone)]
+pub(crate) struct SymbolReceiver {
+    rx: Receiver<ExchangeTick>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum TickCheck {
+    Accepted,
+    SameSecond, // 没有毫秒时间戳的同一秒内的新成交，照常发送
+    Duplicated,
+    OutOfOrder(i64), // 最后一个tick的毫秒时间戳
 }
 
 impl TickStream {
@@ -18,25 +66,193 @@ impl TickStream {
         TickStream {
             inner: flume::unbounded(),
             token: CancellationToken::new(),
+            last_ticks: Mutex::new(HashMap::new()),
+            counter: TickStreamCounter::default(),
+            recorder: None,
+            symbol_subscribers: Mutex::new(Vec::new()),
+            shared_subscribed: AtomicBool::new(false),
         }
     }
 
+    // 录制写文件是阻塞操作，由单独的线程写入，tick流释放后写完剩余的tick
+    pub(crate) fn with_recorder(mut self, mut recorder: TickRecorder) -> Self {
+        let (tx, rx) = flume::unbounded::<ExchangeTick>();
+
+        tokio::task::spawn_blocking(move || {
+            while let Ok((exchange, market, tick)) = rx.recv() {
+                if let Err(e) = recorder.record(&exchange, &market, &tick) {
+                    tracing::error!("Record tick failed: {}", e);
+                }
+            }
+        });
+
+        self.recorder = Some(tx);
+        self
+    }
+
+    // 发送tick，重复或乱序(如断线重连后)的tick会被丢弃，避免污染下游统计；
+    // 按毫秒时间戳比较，数据源没有毫秒时间戳时同一秒内价格变化的tick照常发送并计数
     pub(crate) async fn send(
         &self,
         exchange: &Exchange,
         market: &Market,
         tick: &Tick,
     ) -> Result<()> {
-        self.inner
-            .0
-            .send_async((exchange.clone(), market.clone(), tick.clone()))
-            .await?;
+        match self.check(exchange, market, tick)? {
+            TickCheck::Accepted => {}
+            TickCheck::SameSecond => {
+                self.counter.same_second.fetch_add(1, Ordering::Relaxed);
+                tracing::debug!(
+                    monotonic_counter.tick_stream_same_second = 1_u64,
+                    "Same-second tick: {}:{}:{} {}",
+                    exchange,
+                    market,
+                    tick.symbol,
+                    tick.timestamp_millis()
+                );
+            }
+            TickCheck::Duplicated => {
+                self.counter.duplicated.fetch_add(1, Ordering::Relaxed);
+                tracing::info!(
+                    monotonic_counter.tick_stream_duplicated = 1_u64,
+                    "Duplicated tick dropped: {}:{}:{} {}",
+                    exchange,
+                    market,
+                    tick.symbol,
+                    tick.timestamp_millis()
+                );
+                return Ok(());
+            }
+            TickCheck::OutOfOrder(last_millis) => {
+                self.counter.out_of_order.fetch_add(1, Ordering::Relaxed);
+                tracing::warn!(
+                    monotonic_counter.tick_stream_out_of_order = 1_u64,
+                    "Out-of-order tick dropped: {}:{}:{} {} < {}",
+                    exchange,
+                    market,
+                    tick.symbol,
+                    tick.timestamp_millis(),
+                    last_millis
+                );
+                return Ok(());
+            }
+        }
+
+        let exchange_tick = (exchange.clone(), market.clone(), tick.clone());
+
+        // 只有按交易对订阅的消费者时不再写入共享通道，避免无人接收的tick堆积
+        if self.fan_out(&exchange_tick)? || self.shared_subscribed.load(Ordering::Relaxed) {
+            self.inner.0.send_async(exchange_tick).await?;
+        }
+
+        self.counter.delivered.fetch_add(1, Ordering::Relaxed);
+
+        // 录制失败不影响行情推送
+        if let Some(recorder) = &self.recorder {
+            if recorder
+                .send((exchange.clone(), market.clone(), tick.clone()))
+                .is_err()
+            {
+                tracing::error!("Record tick failed: recorder stopped");
+            }
+        }
+
         Ok(())
     }
 
+    // 分发给订阅了该交易对的订阅者，移除已关闭的订阅，返回是否没有按交易对的订阅者
+    fn fan_out(&self, exchange_tick: &ExchangeTick) -> Result<bool> {
+        let mut subscribers = self
+            .symbol_subscribers
+            .lock()
+            .map_err(|e| anyhow::anyhow!("TickStream lock poisoned: {}", e))?;
+
+        subscribers.retain(|(symbols, tx)| {
+            !symbols.contains(&exchange_tick.2.symbol) || tx.send(exchange_tick.clone()).is_ok()
+        });
+
+        Ok(subscribers.is_empty())
+    }
+
+    fn check(&self, exchange: &Exchange, market: &Market, tick: &Tick) -> Result<TickCheck> {
+        let key = ExchangeMarketSymbolKey::try_new(exchange, market, &tick.symbol)?;
+        let mut last_ticks = self
+            .last_ticks
+            .lock()
+            .map_err(|e| anyhow::anyhow!("TickStream lock poisoned: {}", e))?;
+
+        let millis = tick.timestamp_millis();
+
+        let check = match last_ticks.get(&key) {
+            Some(&(last, _)) if millis < last => TickCheck::OutOfOrder(last),
+            Some(&(last, price)) if millis == last && tick.price == price => TickCheck::Duplicated,
+            Some(&(last, _)) if millis == last => TickCheck::SameSecond,
+            _ => TickCheck::Accepted,
+        };
+
+        if matches!(check, TickCheck::Accepted | TickCheck::SameSecond) {
+            last_ticks.insert(key, (millis, tick.price));
+        }
+
+        Ok(check)
+    }
+
+    pub(crate) fn metrics(&self) -> TickStreamMetrics {
+        TickStreamMetrics {
+            delivered: self.counter.delivered.load(Ordering::Relaxed),
+            duplicated: self.counter.duplicated.load(Ordering::Relaxed),
+            out_of_order: self.counter.out_of_order.load(Ordering::Relaxed),
+            same_second: self.counter.same_second.load(Ordering::Relaxed),
+        }
+    }
+
+    // 共享通道的订阅，订阅者竞争接收同一个tick
     pub(crate) fn subscribe(&self) -> Receiver<ExchangeTick> {
+        self.shared_subscribed.store(true, Ordering::Relaxed);
         self.inner.1.clone()
     }
+
+    // 按交易对过滤的订阅，用于配对交易、组合等多交易对策略，只接收订阅之后发送的tick
+    pub(crate) fn subscribe_symbols<S: Into<Symbol>>(
+        &self,
+        symbols: impl IntoIterator<Item = S>,
+    ) -> SymbolReceiver {
+        let (tx, rx) = flume::unbounded();
+        let symbols = symbols.into_iter().map(Into::into).collect();
+
+        if let Ok(mut subscribers) = self.symbol_subscribers.lock() {
+            subscribers.push((symbols, tx));
+        }
+
+        SymbolReceiver { rx }
+    }
+
+    // 已发送过tick的交易对
+    pub(crate) fn symbols(&self) -> Vec<Symbol> {
+        self.last_ticks
+            .lock()
+            .map(|last_ticks| last_ticks.keys().map(|key| key.symbol.clone()).collect())
+            .unwrap_or_default()
+    }
+
+    // 标记数据已发送完毕(如回测数据回放结束)
+    pub(crate) fn finish(&self) {
+        self.token.cancel();
+    }
+
+    // 接收下一个tick，数据发送完毕且已全部接收后返回None
+    pub(crate) async fn next(&self, rx: &Receiver<ExchangeTick>) -> Option<ExchangeTick> {
+        tokio::select! {
+            biased;
+            tick = rx.recv_async() => tick.ok(),
+            _ = self.token.cancelled() => rx.try_recv().ok(),
+        }
+    }
+
+    // 接收下一个订阅交易对的tick
+    pub(crate) async fn next_for(&self, rx: &SymbolReceiver) -> Option<ExchangeTick> {
+        self.next(&rx.rx).await
+    }
 }
 
 impl Drop for TickStream {
@@ -48,16 +264,17 @@ impl Drop for TickStream {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::node_core::replay_ticks;
     use rust_decimal_macros::dec;
 
     #[tokio::test]
     async fn test_tick_stream() -> Result<()> {
         let tick_stream = TickStream::new();
-        let tick = Tick {
-            timestamp: 1,
-            symbol: "BTCUSDT".into(),
-            price: dec!(100.0),
-        };
+        let tick = Tick::builder()
+            .timestamp(1)
+            .symbol("BTCUSDT".into())
+            .price(dec!(100.0))
+            .build();
         let exchange = Exchange::Binance;
         let market = Market::Spot;
 
@@ -70,4 +287,231 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_tick_stream_finish() -> Result<()> {
+        let tick_stream = TickStream::new();
+        let tick = Tick::builder()
+            .timestamp(1)
+            .symbol("BTCUSDT".into())
+            .price(dec!(100.0))
+            .build();
+        let exchange = Exchange::Binance;
+        let market = Market::Spot;
+        let rx = tick_stream.subscribe();
+
+        tick_stream.send(&exchange, &market, &tick).await?;
+        tick_stream.finish();
+
+        assert_eq!(tick_stream.next(&rx).await, Some((exchange, market, tick)));
+        assert_eq!(tick_stream.next(&rx).await, None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_tick_stream_recorder() -> Result<()> {
+        let dir =
+            std::env::temp_dir().join(format!("comfy-quant-tick-stream-{}", std::process::id()));
+        let workflow_id = "jEnbRDqQu4UN6y7cgQgp6";
+        let recorder = TickRecorder::new(&dir, workflow_id, 1).with_batch_size(2);
+        let tick_stream = TickStream::new().with_recorder(recorder);
+        let exchange = Exchange::Binance;
+        let market = Market::Spot;
+
+        for timestamp in 1..=3 {
+            let tick = Tick::builder()
+                .timestamp(timestamp)
+                .symbol("BTCUSDT".into())
+                .price(dec!(100.0))
+                .build();
+            tick_stream.send(&exchange, &market, &tick).await?;
+        }
+
+        // 释放后由录制线程写完剩余的tick
+        drop(tick_stream);
+
+        let mut recorded = 0;
+
+        for _ in 0..100 {
+            recorded = replay_ticks(&dir, workflow_id, 1)
+                .map(|ticks| ticks.len())
+                .unwrap_or_default();
+
+            if recorded == 3 {
+                break;
+            }
+
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+
+        assert_eq!(recorded, 3);
+
+        std::fs::remove_dir_all(&dir)?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_tick_stream_drop_duplicated_and_out_of_order() -> Result<()> {
+        let tick_stream = TickStream::new();
+        let exchange = Exchange::Binance;
+        let market = Market::Spot;
+        let tick = |timestamp: i64, price: Decimal| {
+            Tick::builder()
+                .timestamp(timestamp)
+                .symbol("BTCUSDT".into())
+                .price(price)
+                .build()
+        };
+
+        tick_stream
+            .send(&exchange, &market, &tick(1, dec!(100)))
+            .await?;
+        tick_stream
+            .send(&exchange, &market, &tick(2, dec!(100)))
+            .await?;
+        tick_stream
+            .send(&exchange, &market, &tick(2, dec!(100)))
+            .await?;
+        tick_stream
+            .send(&exchange, &market, &tick(1, dec!(100)))
+            .await?;
+        // 同一秒内价格变化的tick照常发送
+        tick_stream
+            .send(&exchange, &market, &tick(2, dec!(101)))
+            .await?;
+        tick_stream
+            .send(&exchange, &market, &tick(3, dec!(101)))
+            .await?;
+
+        let rx = tick_stream.subscribe();
+        let ticks = rx
+            .drain()
+            .map(|(_, _, tick)| (tick.timestamp, tick.price))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ticks,
+            vec![
+                (1, dec!(100)),
+                (2, dec!(100)),
+                (2, dec!(101)),
+                (3, dec!(101))
+            ]
+        );
+
+        assert_eq!(
+            tick_stream.metrics(),
+            TickStreamMetrics {
+                delivered: 4,
+                duplicated: 1,
+                out_of_order: 1,
+                same_second: 1,
+            }
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_tick_stream_compare_millis() -> Result<()> {
+        let tick_stream = TickStream::new();
+        let exchange = Exchange::Binance;
+        let market = Market::Spot;
+        let tick = |millis: i64, price: Decimal| {
+            Tick::builder()
+                .timestamp(millis / 1000)
+                .millis(millis)
+                .symbol("BTCUSDT".into())
+                .price(price)
+                .build()
+        };
+
+        // 同一秒内不同毫秒的tick按顺序发送，更早的毫秒时间戳视为乱序
+        for (millis, price) in [
+            (1000, dec!(100)),
+            (1250, dec!(100)),
+            (1250, dec!(100)),
+            (1100, dec!(101)),
+            (1500, dec!(101)),
+        ] {
+            tick_stream
+                .send(&exchange, &market, &tick(millis, price))
+                .await?;
+        }
+
+        let rx = tick_stream.subscribe();
+        let ticks = rx
+            .drain()
+            .map(|(_, _, tick)| tick.timestamp_millis())
+            .collect::<Vec<_>>();
+        assert_eq!(ticks, vec![1000, 1250, 1500]);
+
+        assert_eq!(
+            tick_stream.metrics(),
+            TickStreamMetrics {
+                delivered: 3,
+                duplicated: 1,
+                out_of_order: 1,
+                same_second: 0,
+            }
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_tick_stream_subscribe_symbols() -> Result<()> {
+        let tick_stream = TickStream::new();
+        let exchange = Exchange::Binance;
+        let market = Market::Spot;
+        let tick = |symbol: &str, timestamp: i64| {
+            Tick::builder()
+                .timestamp(timestamp)
+                .symbol(symbol.into())
+                .price(dec!(100.0))
+                .build()
+        };
+        let rx = tick_stream.subscribe_symbols(["BTCUSDT", "ETHUSDT"]);
+        let rx2 = tick_stream.subscribe_symbols(["BTCUSDT", "SOLUSDT"]);
+
+        // 同一时间不同交易对的tick不算重复
+        tick_stream
+            .send(&exchange, &market, &tick("BTCUSDT", 1))
+            .await?;
+        tick_stream
+            .send(&exchange, &market, &tick("SOLUSDT", 1))
+            .await?;
+        tick_stream
+            .send(&exchange, &market, &tick("ETHUSDT", 1))
+            .await?;
+        tick_stream.finish();
+
+        let mut symbols = tick_stream.symbols();
+        symbols.sort_by(|a, b| a.to_string().cmp(&b.to_string()));
+        assert_eq!(
+            symbols,
+            vec!["BTCUSDT".into(), "ETHUSDT".into(), "SOLUSDT".into()]
+        );
+
+        // 每个订阅者收到各自交易对的全部tick，没有共享通道的订阅者时不写入共享通道
+        let mut received = Vec::new();
+
+        while let Some((_, _, tick)) = tick_stream.next_for(&rx).await {
+            received.push(tick.symbol.to_string());
+        }
+
+        assert_eq!(received, vec!["BTCUSDT", "ETHUSDT"]);
+
+        let mut received = Vec::new();
+
+        while let Some((_, _, tick)) = tick_stream.next_for(&rx2).await {
+            received.push(tick.symbol.to_string());
+        }
+
+        assert_eq!(received, vec!["BTCUSDT", "SOLUSDT"]);
+        assert!(tick_stream.inner.1.is_empty());
+
+        Ok(())
+    }
 }
@@ -1,13 +1,20 @@
 use crate::{
-    node_core::{NodeCore, NodeCoreExt, NodeExecutable, NodeInfra, Slot},
+    node_core::{
+        NodeCategory, NodeCore, NodeCoreExt, NodeExecutable, NodeInfra, NodeMeta, NodeMetadata,
+        Slot, SPOT_CLIENT,
+    },
     workflow::Node,
 };
 use anyhow::Result;
 use bon::Builder;
 use comfy_quant_exchange::client::{
-    spot_client::backtest_spot_client::BacktestSpotClient as Client,
+    spot_client::{
+        backtest_spot_client::BacktestSpotClient as Client, execution_model::ExecutionModel,
+        fee_schedule::FeeSchedule, queue_model::QueueModel,
+    },
     spot_client_kind::SpotClientKind,
 };
+use rust_decimal::Decimal;
 use std::sync::Arc;
 
 // 模拟账户，用于交易系统回测时使用
@@ -20,6 +27,17 @@ pub(crate) struct BacktestSpotClient {
     infra: NodeInfra,
 }
 
+impl NodeMeta for BacktestSpotClient {
+    const METADATA: NodeMetadata = NodeMetadata {
+        prop_type: "client.BacktestSpotClient",
+        display_name: "币安现货账户(回测)",
+        category: NodeCategory::Account,
+        inputs: &[],
+        outputs: &[SPOT_CLIENT],
+        icon: "wallet",
+    };
+}
+
 impl NodeCore for BacktestSpotClient {
     fn node_infra(&self) -> &NodeInfra {
         &self.infra
@@ -47,8 +65,19 @@ impl NodeExecutable for BacktestSpotClient {
             .assets(&self.params.assets[..])
             .commissions(self.params.commissions)
             .price_store(price_store)
+            .maybe_fee_schedule(self.params.vip_level.map(|_| FeeSchedule::binance_spot()))
+            .vip_level(self.params.vip_level.unwrap_or_default())
+            .tier_progression(self.params.tier_progression)
+            .symbol_commissions(&self.params.symbol_commissions[..])
+            .maybe_queue_model(self.params.queue_model)
+            .maybe_execution_model(self.params.execution_model)
+            .maybe_participation_rate(self.params.participation_rate)
             .build();
 
+        // 行情节点每个tick撮合挂单
+        self.workflow_context()?
+            .register_backtest_client(self.node().id, client.clone());
+
         let client_slot = Arc::new(Slot::<SpotClientKind>::new(client.into()));
 
         self.port_mut().set_output(0, client_slot)?;
@@ -78,6 +107,14 @@ impl TryFrom<&BacktestSpotClient> for Node {
 pub(crate) struct Params {
     assets: Vec<(String, f64)>, // 币种，余额
     commissions: f64,           // 手续费
+    vip_level: Option<u8>,      // VIP等级，设置后按交易所手续费表计算手续费
+    #[builder(default)]
+    tier_progression: bool, // 是否随成交额累积升级VIP等级
+    #[builder(default)]
+    symbol_commissions: Vec<(String, f64)>, // 交易对，手续费。覆盖账户手续费，如零手续费活动交易对
+    queue_model: Option<QueueModel>, // 挂单排队模型: optimistic 或 pessimistic，未设置时触及限价即成交
+    execution_model: Option<ExecutionModel>, // 市价单执行模型，未设置时按最新价格立即成交
+    participation_rate: Option<f64>, // 成交量参与率，设置后大额订单按周期成交量分多次部分成交
 }
 
 impl TryFrom<&Node> for Params {
@@ -88,10 +125,24 @@ impl TryFrom<&Node> for Params {
             return Err(BacktestSpotClientError::PropertyTypeMismatch);
         }
 
-        let [commissions, assets] = node.properties.params.as_slice() else {
+        // 手续费、资产为必填参数，其余参数可选
+        let [commissions, assets, optional_params @ ..] = node.properties.params.as_slice() else {
             return Err(BacktestSpotClientError::ParamsFormatError);
         };
 
+        if optional_params.len() > 8 {
+            return Err(BacktestSpotClientError::ParamsFormatError);
+        }
+
+        let vip_level = optional_params.first();
+        let tier_progression = optional_params.get(1);
+        let symbol_commissions = optional_params.get(2);
+        let queue_model = optional_params.get(3);
+        let slippage_bps = optional_params.get(4);
+        let impact = optional_params.get(5);
+        let latency_ticks = optional_params.get(6);
+        let participation_rate = optional_params.get(7);
+
         let commissions = commissions
             .as_f64()
             .ok_or(BacktestSpotClientError::CommissionsError)?;
@@ -108,9 +159,105 @@ impl TryFrom<&Node> for Params {
             })
             .collect::<Vec<(String, f64)>>();
 
+        let vip_level = vip_level
+            .filter(|vip_level| !vip_level.is_null())
+            .map(|vip_level| {
+                vip_level
+                    .as_u64()
+                    .and_then(|vip_level| u8::try_from(vip_level).ok())
+                    .ok_or(BacktestSpotClientError::VipLevelError)
+            })
+            .transpose()?;
+
+        let tier_progression = tier_progression
+            .and_then(|tier_progression| tier_progression.as_bool())
+            .unwrap_or_default();
+
+        let symbol_commissions = symbol_commissions
+            .filter(|symbol_commissions| !symbol_commissions.is_null())
+            .map(|symbol_commissions| {
+                symbol_commissions
+                    .as_array()
+                    .ok_or(BacktestSpotClientError::SymbolCommissionsError)?
+                    .iter()
+                    .map(|symbol_commission| {
+                        let symbol_commission = symbol_commission.as_array()?;
+                        let symbol = symbol_commission.first()?.as_str()?.to_string();
+                        let commission = symbol_commission.get(1)?.as_f64()?;
+                        (commission >= 0.).then_some((symbol, commission))
+                    })
+                    .collect::<Option<Vec<(String, f64)>>>()
+                    .ok_or(BacktestSpotClientError::SymbolCommissionsError)
+            })
+            .transpose()?
+            .unwrap_or_default();
+
+        let queue_model = queue_model
+            .filter(|queue_model| !queue_model.is_null())
+            .map(|queue_model| {
+                queue_model
+                    .as_str()
+                    .and_then(|queue_model| queue_model.parse::<QueueModel>().ok())
+                    .ok_or(BacktestSpotClientError::QueueModelError)
+            })
+            .transpose()?;
+
+        // 滑点(基点)、冲击系数、下单延迟(tick数)，任一设置时启用执行模型
+        let decimal_param = |value: Option<&serde_json::Value>| {
+            value
+                .filter(|value| !value.is_null())
+                .map(|value| {
+                    value
+                        .as_f64()
+                        .filter(|value| *value >= 0.)
+                        .and_then(|value| Decimal::try_from(value).ok())
+                        .ok_or(BacktestSpotClientError::ExecutionModelError)
+                })
+                .transpose()
+        };
+
+        let slippage_bps = decimal_param(slippage_bps)?;
+        let impact = decimal_param(impact)?;
+        let latency_ticks = latency_ticks
+            .filter(|latency_ticks| !latency_ticks.is_null())
+            .map(|latency_ticks| {
+                latency_ticks
+                    .as_u64()
+                    .and_then(|latency_ticks| u32::try_from(latency_ticks).ok())
+                    .ok_or(BacktestSpotClientError::ExecutionModelError)
+            })
+            .transpose()?;
+
+        let execution_model =
+            (slippage_bps.is_some() || impact.is_some() || latency_ticks.is_some()).then(|| {
+                ExecutionModel::builder()
+                    .maybe_slippage_bps(slippage_bps)
+                    .maybe_impact(impact)
+                    .maybe_latency_ticks(latency_ticks)
+                    .build()
+            });
+
+        let participation_rate = participation_rate
+            .filter(|participation_rate| !participation_rate.is_null())
+            .map(|participation_rate| {
+                participation_rate
+                    .as_f64()
+                    .filter(|participation_rate| {
+                        *participation_rate > 0. && *participation_rate <= 1.
+                    })
+                    .ok_or(BacktestSpotClientError::ParticipationRateError)
+            })
+            .transpose()?;
+
         let params = Params::builder()
             .assets(assets)
             .commissions(commissions)
+            .maybe_vip_level(vip_level)
+            .tier_progression(tier_progression)
+            .symbol_commissions(symbol_commissions)
+            .maybe_queue_model(queue_model)
+            .maybe_execution_model(execution_model)
+            .maybe_participation_rate(participation_rate)
             .build();
 
         Ok(params)
@@ -130,6 +277,21 @@ pub enum BacktestSpotClientError {
 
     #[error("Invalid commissions")]
     CommissionsError,
+
+    #[error("Invalid vip level")]
+    VipLevelError,
+
+    #[error("Invalid symbol commissions")]
+    SymbolCommissionsError,
+
+    #[error("Invalid queue model, expected 'optimistic' or 'pessimistic'")]
+    QueueModelError,
+
+    #[error("Invalid execution model, expected non-negative slippage, impact and latency ticks")]
+    ExecutionModelError,
+
+    #[error("Invalid participation rate, expected between 0 and 1")]
+    ParticipationRateError,
 }
 
 #[cfg(test)]
@@ -232,6 +394,121 @@ mod tests {
         assert_eq!(result.unwrap_err().to_string(), "Invalid commissions");
     }
 
+    #[sqlx::test]
+    async fn test_mock_account_vip_level(db: PgPool) -> Result<()> {
+        let json_str = r#"{"id":1,"type":"账户/币安子账户","pos":[199,74],"size":{"0":210,"1":310},"flags":{},"order":0,"mode":0,"inputs":[],"properties":{"type":"client.BacktestSpotClient","params":[0.001, [["USDT", 10000]], 3, true]}}"#;
+
+        let mut node: Node = serde_json::from_str(json_str)?;
+        node.context = Some(default_context(db));
+
+        let mut account = BacktestSpotClient::try_from(node)?;
+        assert_eq!(account.params.vip_level, Some(3));
+        assert!(account.params.tier_progression);
+
+        account.setup().await?;
+
+        let client = account.port().output::<SpotClientKind>(0)?;
+        let account_information = client.get_account().await?;
+        assert_eq!(account_information.maker_commission_rate, dec!(0.00042));
+        assert_eq!(account_information.taker_commission_rate, dec!(0.0006));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mock_account_symbol_commissions() -> Result<()> {
+        let json_str = r#"{"id":1,"type":"账户/币安子账户","pos":[199,74],"size":{"0":210,"1":310},"flags":{},"order":0,"mode":0,"inputs":[],"properties":{"type":"client.BacktestSpotClient","params":[0.001, [["FDUSD", 10000]], null, false, [["BTCFDUSD", 0]]]}}"#;
+
+        let node: Node = serde_json::from_str(json_str)?;
+        let account = BacktestSpotClient::try_from(node)?;
+        assert_eq!(account.params.vip_level, None);
+        assert_eq!(
+            account.params.symbol_commissions,
+            vec![("BTCFDUSD".to_string(), 0.0)]
+        );
+
+        let json_str = r#"{"id":1,"type":"账户/币安子账户","pos":[199,74],"size":{"0":210,"1":310},"flags":{},"order":0,"mode":0,"inputs":[],"properties":{"type":"client.BacktestSpotClient","params":[0.001, [["FDUSD", 10000]], null, false, [["BTCFDUSD", -0.001]]]}}"#;
+
+        let node: Node = serde_json::from_str(json_str)?;
+        let result = BacktestSpotClient::try_from(node);
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Invalid symbol commissions"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mock_account_queue_model() -> Result<()> {
+        let json_str = r#"{"id":1,"type":"账户/币安子账户","pos":[199,74],"size":{"0":210,"1":310},"flags":{},"order":0,"mode":0,"inputs":[],"properties":{"type":"client.BacktestSpotClient","params":[0.001, [["USDT", 10000]], null, false, null, "pessimistic"]}}"#;
+
+        let node: Node = serde_json::from_str(json_str)?;
+        let account = BacktestSpotClient::try_from(node)?;
+        assert_eq!(account.params.queue_model, Some(QueueModel::Pessimistic));
+
+        let json_str = r#"{"id":1,"type":"账户/币安子账户","pos":[199,74],"size":{"0":210,"1":310},"flags":{},"order":0,"mode":0,"inputs":[],"properties":{"type":"client.BacktestSpotClient","params":[0.001, [["USDT", 10000]], null, false, null, "fifo"]}}"#;
+
+        let node: Node = serde_json::from_str(json_str)?;
+        let result = BacktestSpotClient::try_from(node);
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Invalid queue model, expected 'optimistic' or 'pessimistic'"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mock_account_execution_model() -> Result<()> {
+        let json_str = r#"{"id":1,"type":"账户/币安子账户","pos":[199,74],"size":{"0":210,"1":310},"flags":{},"order":0,"mode":0,"inputs":[],"properties":{"type":"client.BacktestSpotClient","params":[0.001, [["USDT", 10000]], null, false, null, null, 5, 0.01, 2]}}"#;
+
+        let node: Node = serde_json::from_str(json_str)?;
+        let account = BacktestSpotClient::try_from(node)?;
+        assert_eq!(
+            account.params.execution_model,
+            Some(
+                ExecutionModel::builder()
+                    .slippage_bps(dec!(5))
+                    .impact(dec!(0.01))
+                    .latency_ticks(2)
+                    .build()
+            )
+        );
+
+        let json_str = r#"{"id":1,"type":"账户/币安子账户","pos":[199,74],"size":{"0":210,"1":310},"flags":{},"order":0,"mode":0,"inputs":[],"properties":{"type":"client.BacktestSpotClient","params":[0.001, [["USDT", 10000]], null, false, null, null]}}"#;
+
+        let node: Node = serde_json::from_str(json_str)?;
+        let account = BacktestSpotClient::try_from(node)?;
+        assert_eq!(account.params.execution_model, None);
+        assert_eq!(account.params.participation_rate, None);
+
+        let json_str = r#"{"id":1,"type":"账户/币安子账户","pos":[199,74],"size":{"0":210,"1":310},"flags":{},"order":0,"mode":0,"inputs":[],"properties":{"type":"client.BacktestSpotClient","params":[0.001, [["USDT", 10000]], null, false, null, null, null, null, null, 0.1]}}"#;
+
+        let node: Node = serde_json::from_str(json_str)?;
+        let account = BacktestSpotClient::try_from(node)?;
+        assert_eq!(account.params.participation_rate, Some(0.1));
+
+        let json_str = r#"{"id":1,"type":"账户/币安子账户","pos":[199,74],"size":{"0":210,"1":310},"flags":{},"order":0,"mode":0,"inputs":[],"properties":{"type":"client.BacktestSpotClient","params":[0.001, [["USDT", 10000]], null, false, null, null, null, null, null, 1.5]}}"#;
+
+        let node: Node = serde_json::from_str(json_str)?;
+        assert_eq!(
+            BacktestSpotClient::try_from(node).unwrap_err().to_string(),
+            "Invalid participation rate, expected between 0 and 1"
+        );
+
+        let json_str = r#"{"id":1,"type":"账户/币安子账户","pos":[199,74],"size":{"0":210,"1":310},"flags":{},"order":0,"mode":0,"inputs":[],"properties":{"type":"client.BacktestSpotClient","params":[0.001, [["USDT", 10000]], null, false, null, null, -5]}}"#;
+
+        let node: Node = serde_json::from_str(json_str)?;
+        let result = BacktestSpotClient::try_from(node);
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Invalid execution model, expected non-negative slippage, impact and latency ticks"
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn test_invalid_assets_format() {
         let json_str = r#"{"id":1,"type":"账户/币安子账户","pos":[199,74],"size":{"0":210,"1":310},"flags":{},"order":0,"mode":0,"inputs":[],"properties":{"type":"client.BacktestSpotClient","params":[0.001, "invalid"]}}"#;
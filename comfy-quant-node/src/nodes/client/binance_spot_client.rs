@@ -1,21 +1,25 @@
 use crate::{
-    node_core::{NodeCore, NodeCoreExt, NodeExecutable, NodeInfra, Slot},
+    node_core::{CredentialRef, NodeCore, NodeCoreExt, NodeError, NodeExecutable, NodeInfra, Slot},
     workflow::Node,
 };
 use anyhow::Result;
 use bon::Builder;
-use comfy_quant_exchange::client::{
-    spot_client::binance_spot_client::BinanceSpotClient as Client, spot_client_kind::SpotClientKind,
+use comfy_quant_exchange::{
+    client::{
+        spot_client::binance_spot_client::BinanceSpotClient as Client,
+        spot_client_kind::SpotClientKind,
+    },
+    exchange::ConnectionOptions,
 };
 use std::sync::Arc;
 
 #[derive(Debug)]
-#[allow(unused)]
 pub(crate) struct BinanceSpotClient {
     params: Params,
     // outputs:
     //      0: SpotClient
     infra: NodeInfra,
+    client: Option<Client>, // setup后创建，执行时订阅用户数据流
 }
 
 impl NodeCore for BinanceSpotClient {
@@ -33,23 +37,42 @@ impl BinanceSpotClient {
         let params = Params::try_from(&node)?;
         let infra = NodeInfra::new(node);
 
-        Ok(BinanceSpotClient { params, infra })
+        Ok(BinanceSpotClient {
+            params,
+            infra,
+            client: None,
+        })
     }
 }
 
 impl NodeExecutable for BinanceSpotClient {
     async fn setup(&mut self) -> Result<()> {
+        // 运行时解析密钥，工作流中只保存密钥引用
+        let db = self.workflow_context()?.cloned_db();
+        let credentials = self.params.credential.resolve(&db).await?;
+
         let client = Client::builder()
-            .api_key(&self.params.api_key)
-            .secret_key(&self.params.secret_key)
-            .build();
+            .api_key(credentials.api_key)
+            .secret_key(credentials.secret_key)
+            .connection(self.params.connection.clone())
+            .build()?;
 
-        let client_slot = Arc::new(Slot::<SpotClientKind>::new(client.into()));
+        let client_slot = Arc::new(Slot::<SpotClientKind>::new(client.clone().into()));
 
         self.port_mut().set_output(0, client_slot)?;
+        self.client = Some(client);
 
         Ok(())
     }
+
+    // 订阅用户数据流，订单成交和余额变化推送给使用该账户的策略节点
+    async fn execute(&mut self) -> Result<(), NodeError> {
+        let Some(client) = &self.client else {
+            return Ok(());
+        };
+
+        Ok(client.run_user_data_stream().await?)
+    }
 }
 
 impl TryFrom<Node> for BinanceSpotClient {
@@ -61,11 +84,11 @@ impl TryFrom<Node> for BinanceSpotClient {
 }
 
 #[derive(Builder, Debug, Clone)]
-#[builder(on(String, into))]
 #[allow(unused)]
 pub(crate) struct Params {
-    api_key: String,
-    secret_key: String,
+    credential: CredentialRef, // 密钥引用
+    #[builder(default)]
+    connection: ConnectionOptions, // 自定义接口地址
 }
 
 impl TryFrom<&Node> for Params {
@@ -76,21 +99,55 @@ impl TryFrom<&Node> for Params {
             return Err(BinanceSpotClientError::PropertyTypeMismatch);
         }
 
-        let [api_key, secret_key] = node.properties.params.as_slice() else {
+        // 可选参数: 代理地址, REST 接口地址, websocket 地址，空字符串表示不设置
+        let [credential, connection @ ..] = node.properties.params.as_slice() else {
             return Err(BinanceSpotClientError::ParamsFormatError);
         };
 
-        let api_key = api_key
-            .as_str()
-            .ok_or(BinanceSpotClientError::ApiKeyError)?;
+        // 旧格式的参数为 [api_key, secret_key, ...]，第二个参数是密钥而不是代理地址，
+        // 不能把 api_key 当作密钥名称使用
+        if connection
+            .first()
+            .and_then(|value| value.as_str())
+            .is_some_and(|value| !value.is_empty() && !value.contains("://"))
+        {
+            return Err(BinanceSpotClientError::RawCredentialError);
+        }
 
-        let secret_key = secret_key
+        if connection.len() > 3 {
+            return Err(BinanceSpotClientError::ParamsFormatError);
+        }
+
+        let credential = credential
             .as_str()
-            .ok_or(BinanceSpotClientError::SecretKeyError)?;
+            .and_then(|credential| credential.parse::<CredentialRef>().ok())
+            .ok_or(BinanceSpotClientError::CredentialError)?;
+
+        let connection_param = |index: usize| -> Result<Option<String>, Self::Error> {
+            match connection.get(index) {
+                Some(value) => {
+                    let value = value
+                        .as_str()
+                        .ok_or(BinanceSpotClientError::ConnectionError)?;
+                    Ok((!value.is_empty()).then(|| value.to_string()))
+                }
+                None => Ok(None),
+            }
+        };
+
+        let connection = ConnectionOptions::builder()
+            .maybe_proxy(connection_param(0)?)
+            .maybe_rest_endpoint(connection_param(1)?)
+            .maybe_ws_endpoint(connection_param(2)?)
+            .build();
+
+        connection
+            .validate()
+            .map_err(|_| BinanceSpotClientError::ConnectionError)?;
 
         let params = Params::builder()
-            .api_key(api_key)
-            .secret_key(secret_key)
+            .credential(credential)
+            .connection(connection)
             .build();
 
         Ok(params)
@@ -105,11 +162,14 @@ pub enum BinanceSpotClientError {
     #[error("Invalid parameters format")]
     ParamsFormatError,
 
-    #[error("Invalid api key")]
-    ApiKeyError,
+    #[error("Invalid credential reference, expected the name of a saved API key or env:<PREFIX>")]
+    CredentialError,
 
-    #[error("Invalid secret key")]
-    SecretKeyError,
+    #[error("API keys in node params are no longer supported, save them with `api-key set` and reference them by name")]
+    RawCredentialError,
+
+    #[error("Invalid proxy or endpoint")]
+    ConnectionError,
 }
 
 #[cfg(test)]
@@ -118,13 +178,67 @@ mod tests {
 
     #[test]
     fn test_try_from_node_to_binance_account() -> Result<()> {
-        let json_str = r#"{"id":1,"type":"账户/币安子账户","pos":[199,74],"size":{"0":210,"1":310},"flags":{},"order":0,"mode":0,"inputs":[],"properties":{"type":"client.BinanceSpotClient","params":["api_secret","secret"]}}"#;
+        let json_str = r#"{"id":1,"type":"账户/币安子账户","pos":[199,74],"size":{"0":210,"1":310},"flags":{},"order":0,"mode":0,"inputs":[],"properties":{"type":"client.BinanceSpotClient","params":["main"]}}"#;
+
+        let node: Node = serde_json::from_str(json_str)?;
+        let account = BinanceSpotClient::try_from(node)?;
+
+        assert_eq!(
+            account.params.credential,
+            CredentialRef::Stored("main".to_string())
+        );
+        assert_eq!(account.params.connection, ConnectionOptions::default());
+
+        let json_str = r#"{"id":1,"type":"账户/币安子账户","pos":[199,74],"size":{"0":210,"1":310},"flags":{},"order":0,"mode":0,"inputs":[],"properties":{"type":"client.BinanceSpotClient","params":["env:BINANCE"]}}"#;
 
         let node: Node = serde_json::from_str(json_str)?;
         let account = BinanceSpotClient::try_from(node)?;
 
-        assert_eq!(account.params.api_key, "api_secret");
-        assert_eq!(account.params.secret_key, "secret");
+        assert_eq!(
+            account.params.credential,
+            CredentialRef::Env("BINANCE".to_string())
+        );
+
+        // 旧格式直接保存了密钥
+        let json_str = r#"{"id":1,"type":"账户/币安子账户","pos":[199,74],"size":{"0":210,"1":310},"flags":{},"order":0,"mode":0,"inputs":[],"properties":{"type":"client.BinanceSpotClient","params":["api_key","secret_key","","",""]}}"#;
+
+        let node: Node = serde_json::from_str(json_str)?;
+        assert!(matches!(
+            Params::try_from(&node),
+            Err(BinanceSpotClientError::RawCredentialError)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_try_from_node_with_connection_options() -> Result<()> {
+        let json_str = r#"{"id":1,"type":"账户/币安子账户","pos":[199,74],"size":{"0":210,"1":310},"flags":{},"order":0,"mode":0,"inputs":[],"properties":{"type":"client.BinanceSpotClient","params":["main","","https://api1.binance.com"]}}"#;
+
+        let node: Node = serde_json::from_str(json_str)?;
+        let account = BinanceSpotClient::try_from(node)?;
+
+        assert_eq!(
+            account.params.connection,
+            ConnectionOptions::builder()
+                .rest_endpoint("https://api1.binance.com")
+                .build()
+        );
+
+        let json_str = r#"{"id":1,"type":"账户/币安子账户","pos":[199,74],"size":{"0":210,"1":310},"flags":{},"order":0,"mode":0,"inputs":[],"properties":{"type":"client.BinanceSpotClient","params":["main","socks5://127.0.0.1:1080"]}}"#;
+
+        let node: Node = serde_json::from_str(json_str)?;
+        let account = BinanceSpotClient::try_from(node)?;
+
+        assert_eq!(
+            account.params.connection.proxy,
+            Some("socks5://127.0.0.1:1080".to_string())
+        );
+
+        let json_str = r#"{"id":1,"type":"账户/币安子账户","pos":[199,74],"size":{"0":210,"1":310},"flags":{},"order":0,"mode":0,"inputs":[],"properties":{"type":"client.BinanceSpotClient","params":["main","","ftp://api.binance.com"]}}"#;
+
+        let node: Node = serde_json::from_str(json_str)?;
+        assert!(BinanceSpotClient::try_from(node).is_err());
 
         Ok(())
     }
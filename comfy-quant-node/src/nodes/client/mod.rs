@@ -1,6 +1,10 @@
 mod backtest_spot_client;
 mod binance_spot_client;
+mod multi_spot_client;
+mod paper_spot_client;
 
 pub(crate) use backtest_spot_client::BacktestSpotClient;
 #[allow(unused)]
 pub(crate) use binance_spot_client::BinanceSpotClient;
+pub(crate) use multi_spot_client::MultiSpotClient;
+pub(crate) use paper_spot_client::PaperSpotClient;
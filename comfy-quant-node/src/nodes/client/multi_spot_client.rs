@@ -0,0 +1,177 @@
+use crate::{
+    node_core::{
+        NodeCategory, NodeCore, NodeCoreExt, NodeExecutable, NodeInfra, NodeMeta, NodeMetadata,
+        Slot, SPOT_CLIENT,
+    },
+    workflow::Node,
+};
+use anyhow::{anyhow, Result};
+use comfy_quant_exchange::client::{
+    spot_client::multi_spot_client::MultiSpotClient as Client, spot_client_kind::SpotClientKind,
+};
+use rust_decimal::Decimal;
+use std::sync::Arc;
+
+// 最多合并的账户数量
+const MAX_ACCOUNTS: usize = 4;
+
+/// 多账户，按权重把策略的订单拆分到多个账户
+/// inputs:
+///      0..4: SpotClient，按权重的顺序连接
+/// outputs:
+///      0: SpotClient
+#[derive(Debug)]
+pub(crate) struct MultiSpotClient {
+    params: Params,   // 参数
+    infra: NodeInfra, // 节点基础设施
+}
+
+impl NodeMeta for MultiSpotClient {
+    const METADATA: NodeMetadata = NodeMetadata {
+        prop_type: "client.MultiSpotClient",
+        display_name: "多账户",
+        category: NodeCategory::Account,
+        inputs: &[SPOT_CLIENT.optional(); MAX_ACCOUNTS],
+        outputs: &[SPOT_CLIENT],
+        icon: "wallet-cards",
+    };
+}
+
+impl NodeCore for MultiSpotClient {
+    fn node_infra(&self) -> &NodeInfra {
+        &self.infra
+    }
+
+    fn node_infra_mut(&mut self) -> &mut NodeInfra {
+        &mut self.infra
+    }
+}
+
+impl MultiSpotClient {
+    pub(crate) fn try_new(node: Node) -> Result<Self> {
+        let params = Params::try_from(&node)?;
+        let infra = NodeInfra::new(node);
+
+        Ok(MultiSpotClient { params, infra })
+    }
+
+    // 合并的账户数量，前 accounts 个输入必须连接
+    pub(crate) fn accounts(&self) -> usize {
+        self.params.weights.len()
+    }
+}
+
+// 上游账户节点需排在多账户节点之前，setup 时输入已连接
+impl NodeExecutable for MultiSpotClient {
+    async fn setup(&mut self) -> Result<()> {
+        let accounts = self
+            .params
+            .weights
+            .iter()
+            .enumerate()
+            .map(|(index, weight)| {
+                let client = self.port().input::<SpotClientKind>(index).map_err(|_| {
+                    anyhow!("MultiSpotClient requires a SpotClient input {}", index)
+                })?;
+
+                Ok(((**client).clone(), *weight))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let client = Client::try_new(accounts)?;
+
+        self.port_mut()
+            .set_output(0, Arc::new(Slot::<SpotClientKind>::new(client.into())))?;
+
+        Ok(())
+    }
+}
+
+impl TryFrom<Node> for MultiSpotClient {
+    type Error = anyhow::Error;
+
+    fn try_from(node: Node) -> Result<Self> {
+        MultiSpotClient::try_new(node)
+    }
+}
+
+impl TryFrom<&MultiSpotClient> for Node {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &MultiSpotClient) -> Result<Self> {
+        Ok(value.node().clone())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Params {
+    weights: Vec<Decimal>, // 各账户的权重，如 [60, 40]
+}
+
+impl TryFrom<&Node> for Params {
+    type Error = MultiSpotClientError;
+
+    fn try_from(node: &Node) -> Result<Self, Self::Error> {
+        if node.properties.prop_type != "client.MultiSpotClient" {
+            return Err(MultiSpotClientError::PropertyTypeMismatch);
+        }
+
+        let [weights] = node.properties.params.as_slice() else {
+            return Err(MultiSpotClientError::ParamsFormatError);
+        };
+
+        let weights = weights
+            .as_array()
+            .ok_or(MultiSpotClientError::WeightsError)?
+            .iter()
+            .map(|weight| {
+                weight
+                    .as_f64()
+                    .and_then(|weight| Decimal::try_from(weight).ok())
+                    .filter(|weight| *weight > Decimal::ZERO)
+            })
+            .collect::<Option<Vec<_>>>()
+            .ok_or(MultiSpotClientError::WeightsError)?;
+
+        if weights.is_empty() || weights.len() > MAX_ACCOUNTS {
+            return Err(MultiSpotClientError::WeightsError);
+        }
+
+        Ok(Params { weights })
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum MultiSpotClientError {
+    #[error("Invalid property type, expected 'client.MultiSpotClient'")]
+    PropertyTypeMismatch,
+
+    #[error("Invalid parameters format")]
+    ParamsFormatError,
+
+    #[error("Invalid weights, expected 1 to 4 positive numbers")]
+    WeightsError,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_try_from_node_to_multi_spot_client() -> Result<()> {
+        let json_str = r#"{"id":3,"type":"账户/多账户","pos":[199,74],"size":{"0":210,"1":310},"flags":{},"order":0,"mode":0,"inputs":[],"properties":{"type":"client.MultiSpotClient","params":[[60, 40]]}}"#;
+
+        let node: Node = serde_json::from_str(json_str)?;
+        let client = MultiSpotClient::try_from(node)?;
+
+        assert_eq!(client.params.weights, vec![dec!(60), dec!(40)]);
+
+        let json_str = r#"{"id":3,"type":"账户/多账户","pos":[199,74],"size":{"0":210,"1":310},"flags":{},"order":0,"mode":0,"inputs":[],"properties":{"type":"client.MultiSpotClient","params":[[60, 0]]}}"#;
+
+        let node: Node = serde_json::from_str(json_str)?;
+        assert!(MultiSpotClient::try_from(node).is_err());
+
+        Ok(())
+    }
+}
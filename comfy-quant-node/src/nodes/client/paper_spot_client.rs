@@ -0,0 +1,237 @@
+use crate::{
+    node_core::{
+        NodeCategory, NodeCore, NodeCoreExt, NodeExecutable, NodeInfra, NodeMeta, NodeMetadata,
+        Slot, SPOT_CLIENT,
+    },
+    workflow::Node,
+};
+use anyhow::Result;
+use bon::Builder;
+use comfy_quant_exchange::{
+    client::{
+        spot_client::paper_spot_client::PaperSpotClient as Client, spot_client_kind::SpotClientKind,
+    },
+    exchange::ConnectionOptions,
+};
+use std::sync::Arc;
+
+// 模拟盘账户，按币安实时行情模拟成交，需要与币安行情节点一起使用
+#[derive(Debug)]
+pub(crate) struct PaperSpotClient {
+    params: Params,
+    // outputs:
+    //      0: SpotClient
+    infra: NodeInfra,
+}
+
+impl NodeMeta for PaperSpotClient {
+    const METADATA: NodeMetadata = NodeMetadata {
+        prop_type: "client.PaperSpotClient",
+        display_name: "币安现货账户(模拟盘)",
+        category: NodeCategory::Account,
+        inputs: &[],
+        outputs: &[SPOT_CLIENT],
+        icon: "wallet",
+    };
+}
+
+impl NodeCore for PaperSpotClient {
+    fn node_infra(&self) -> &NodeInfra {
+        &self.infra
+    }
+
+    fn node_infra_mut(&mut self) -> &mut NodeInfra {
+        &mut self.infra
+    }
+}
+
+impl PaperSpotClient {
+    pub(crate) fn try_new(node: Node) -> Result<Self> {
+        let params = Params::try_from(&node)?;
+        let infra = NodeInfra::new(node);
+
+        Ok(PaperSpotClient { params, infra })
+    }
+}
+
+impl NodeExecutable for PaperSpotClient {
+    async fn setup(&mut self) -> Result<()> {
+        let price_store = self.workflow_context()?.cloned_price_store();
+
+        let client = Client::builder()
+            .assets(&self.params.assets[..])
+            .price_store(price_store)
+            .vip_level(self.params.vip_level)
+            .connection(self.params.connection.clone())
+            .build()?;
+
+        // 行情节点每个tick撮合挂单
+        self.workflow_context()?
+            .register_backtest_client(self.node().id, client.simulator().clone());
+
+        let client_slot = Arc::new(Slot::<SpotClientKind>::new(client.into()));
+
+        self.port_mut().set_output(0, client_slot)?;
+
+        Ok(())
+    }
+}
+
+impl TryFrom<Node> for PaperSpotClient {
+    type Error = anyhow::Error;
+
+    fn try_from(node: Node) -> Result<Self> {
+        PaperSpotClient::try_new(node)
+    }
+}
+
+impl TryFrom<&PaperSpotClient> for Node {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &PaperSpotClient) -> Result<Self> {
+        Ok(value.node().clone())
+    }
+}
+
+#[derive(Builder, Debug, Clone)]
+pub(crate) struct Params {
+    assets: Vec<(String, f64)>, // 币种，模拟余额
+    #[builder(default)]
+    vip_level: u8, // VIP等级，按币安现货费率表计算手续费
+    #[builder(default)]
+    connection: ConnectionOptions, // 查询交易对信息的接口地址
+}
+
+impl TryFrom<&Node> for Params {
+    type Error = PaperSpotClientError;
+
+    fn try_from(node: &Node) -> Result<Self, Self::Error> {
+        if node.properties.prop_type != "client.PaperSpotClient" {
+            return Err(PaperSpotClientError::PropertyTypeMismatch);
+        }
+
+        // 可选参数: VIP等级, 代理地址, REST 接口地址，空字符串表示不设置
+        let [assets, optional_params @ ..] = node.properties.params.as_slice() else {
+            return Err(PaperSpotClientError::ParamsFormatError);
+        };
+
+        if optional_params.len() > 3 {
+            return Err(PaperSpotClientError::ParamsFormatError);
+        }
+
+        let assets = assets
+            .as_array()
+            .ok_or(PaperSpotClientError::AssetsError)?
+            .iter()
+            .map(|asset| {
+                let asset_array = asset.as_array()?;
+                let asset_name = asset_array.first()?.as_str()?.to_string();
+                let asset_balance = asset_array.get(1)?.as_f64()?;
+                (asset_balance >= 0.).then_some((asset_name, asset_balance))
+            })
+            .collect::<Option<Vec<(String, f64)>>>()
+            .ok_or(PaperSpotClientError::AssetsError)?;
+
+        let vip_level = optional_params
+            .first()
+            .filter(|vip_level| !vip_level.is_null())
+            .map(|vip_level| {
+                vip_level
+                    .as_u64()
+                    .and_then(|vip_level| u8::try_from(vip_level).ok())
+                    .ok_or(PaperSpotClientError::VipLevelError)
+            })
+            .transpose()?
+            .unwrap_or_default();
+
+        let connection_param = |index: usize| -> Result<Option<String>, Self::Error> {
+            match optional_params.get(index) {
+                Some(value) if !value.is_null() => {
+                    let value = value
+                        .as_str()
+                        .ok_or(PaperSpotClientError::ConnectionError)?;
+                    Ok((!value.is_empty()).then(|| value.to_string()))
+                }
+                _ => Ok(None),
+            }
+        };
+
+        let connection = ConnectionOptions::builder()
+            .maybe_proxy(connection_param(1)?)
+            .maybe_rest_endpoint(connection_param(2)?)
+            .build();
+
+        connection
+            .validate()
+            .map_err(|_| PaperSpotClientError::ConnectionError)?;
+
+        let params = Params::builder()
+            .assets(assets)
+            .vip_level(vip_level)
+            .connection(connection)
+            .build();
+
+        Ok(params)
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum PaperSpotClientError {
+    #[error("Invalid property type, expected 'client.PaperSpotClient'")]
+    PropertyTypeMismatch,
+
+    #[error("Invalid parameters format")]
+    ParamsFormatError,
+
+    #[error("Invalid assets")]
+    AssetsError,
+
+    #[error("Invalid vip level")]
+    VipLevelError,
+
+    #[error("Invalid proxy or endpoint")]
+    ConnectionError,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_from_node_to_paper_spot_client() -> Result<()> {
+        let json_str = r#"{"id":1,"type":"账户/币安现货账户(模拟盘)","pos":[199,74],"size":{"0":210,"1":310},"flags":{},"order":0,"mode":0,"inputs":[],"properties":{"type":"client.PaperSpotClient","params":[[["BTC", 1], ["USDT", 10000]], 1, "", "https://api1.binance.com"]}}"#;
+
+        let node: Node = serde_json::from_str(json_str)?;
+        let client = PaperSpotClient::try_from(node)?;
+
+        assert_eq!(
+            client.params.assets,
+            vec![("BTC".to_string(), 1.0), ("USDT".to_string(), 10000.0)]
+        );
+        assert_eq!(client.params.vip_level, 1);
+        assert_eq!(
+            client.params.connection,
+            ConnectionOptions::builder()
+                .rest_endpoint("https://api1.binance.com")
+                .build()
+        );
+
+        let json_str = r#"{"id":1,"type":"账户/币安现货账户(模拟盘)","pos":[199,74],"size":{"0":210,"1":310},"flags":{},"order":0,"mode":0,"inputs":[],"properties":{"type":"client.PaperSpotClient","params":[[["USDT", 10000]], 1, "socks5://127.0.0.1:1080"]}}"#;
+
+        let node: Node = serde_json::from_str(json_str)?;
+        let client = PaperSpotClient::try_from(node)?;
+
+        assert_eq!(
+            client.params.connection.proxy,
+            Some("socks5://127.0.0.1:1080".to_string())
+        );
+
+        // 余额不能为负数
+        let json_str = r#"{"id":1,"type":"账户/币安现货账户(模拟盘)","pos":[199,74],"size":{"0":210,"1":310},"flags":{},"order":0,"mode":0,"inputs":[],"properties":{"type":"client.PaperSpotClient","params":[[["USDT", -1]]]}}"#;
+
+        let node: Node = serde_json::from_str(json_str)?;
+        assert!(PaperSpotClient::try_from(node).is_err());
+
+        Ok(())
+    }
+}
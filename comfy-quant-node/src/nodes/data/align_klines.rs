@@ -0,0 +1,179 @@
+use crate::{
+    node_core::{
+        NodeCategory, NodeCore, NodeCoreExt, NodeError, NodeExecutable, NodeInfra, NodeMeta,
+        NodeMetadata, Slot, KLINE_STREAM,
+    },
+    node_io::{AlignedKlines, KlineStream},
+    workflow::Node,
+};
+use anyhow::Result;
+use bon::Builder;
+use comfy_quant_base::KlineInterval;
+use std::sync::Arc;
+
+// 最多对齐的K线周期数量
+const MAX_STREAMS: usize = 4;
+
+/// 多周期K线对齐
+/// inputs:
+///      0: KlineStream 最小周期
+///      1: KlineStream
+///      2: KlineStream(可选)
+///      3: KlineStream(可选)
+/// outputs:
+///      0: KlineStream
+#[derive(Debug)]
+pub(crate) struct AlignKlines {
+    params: Params,   // 参数
+    infra: NodeInfra, // 节点基础设施
+}
+
+impl NodeMeta for AlignKlines {
+    const METADATA: NodeMetadata = NodeMetadata {
+        prop_type: "data.AlignKlines",
+        display_name: "多周期K线对齐",
+        category: NodeCategory::Data,
+        inputs: &[
+            KLINE_STREAM,
+            KLINE_STREAM,
+            KLINE_STREAM.optional(),
+            KLINE_STREAM.optional(),
+        ],
+        outputs: &[KLINE_STREAM],
+        icon: "layers",
+    };
+}
+
+impl NodeCore for AlignKlines {
+    fn node_infra(&self) -> &NodeInfra {
+        &self.infra
+    }
+
+    fn node_infra_mut(&mut self) -> &mut NodeInfra {
+        &mut self.infra
+    }
+}
+
+impl AlignKlines {
+    pub(crate) fn try_new(node: Node) -> Result<Self> {
+        let params = Params::try_from(&node)?;
+        let infra = NodeInfra::new(node);
+
+        Ok(AlignKlines { params, infra })
+    }
+
+    // 按最小周期推进模拟时间，大周期K线收盘后才交付给下游
+    async fn align(&self) -> Result<()> {
+        let streams = (0..MAX_STREAMS)
+            .filter_map(|slot| self.port().input::<KlineStream>(slot).ok())
+            .collect::<Vec<_>>();
+        let kline_stream = self.port().output::<KlineStream>(0)?;
+        let mut aligned = AlignedKlines::new(streams, self.params.base_interval.clone());
+        let heartbeat = self.heartbeat();
+
+        while let Some((exchange, market, bar)) = aligned.next().await {
+            let _busy = heartbeat.busy();
+
+            kline_stream.send(&exchange, &market, &bar).await?;
+        }
+
+        Ok(())
+    }
+}
+
+impl NodeExecutable for AlignKlines {
+    async fn setup(&mut self) -> Result<()> {
+        let kline_stream_slot = Arc::new(Slot::<KlineStream>::new(KlineStream::new()));
+        self.port_mut().set_output(0, kline_stream_slot)?;
+
+        Ok(())
+    }
+
+    async fn execute(&mut self) -> Result<(), NodeError> {
+        let result = self.align().await;
+
+        // 对齐结束，通知下游节点
+        self.port().output::<KlineStream>(0)?.finish();
+
+        Ok(result?)
+    }
+}
+
+impl TryFrom<Node> for AlignKlines {
+    type Error = anyhow::Error;
+
+    fn try_from(node: Node) -> Result<Self> {
+        AlignKlines::try_new(node)
+    }
+}
+
+impl TryFrom<&AlignKlines> for Node {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &AlignKlines) -> Result<Self> {
+        Ok(value.node().clone())
+    }
+}
+
+#[derive(Builder, Debug, Clone)]
+pub(crate) struct Params {
+    base_interval: KlineInterval, // 最小周期，驱动模拟时间
+}
+
+impl TryFrom<&Node> for Params {
+    type Error = AlignKlinesError;
+
+    fn try_from(node: &Node) -> Result<Self, Self::Error> {
+        if node.properties.prop_type != "data.AlignKlines" {
+            return Err(AlignKlinesError::PropertyTypeMismatch);
+        }
+
+        let [base_interval] = node.properties.params.as_slice() else {
+            return Err(AlignKlinesError::ParamsFormatError);
+        };
+
+        // 未知的时间间隔会被解析为1s，这里严格校验
+        let base_interval = base_interval
+            .as_str()
+            .filter(|interval| KlineInterval::from(*interval).as_ref() == *interval)
+            .map(KlineInterval::from)
+            .ok_or(AlignKlinesError::IntervalError)?;
+
+        let params = Params::builder().base_interval(base_interval).build();
+
+        Ok(params)
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum AlignKlinesError {
+    #[error("Invalid property type, expected 'data.AlignKlines'")]
+    PropertyTypeMismatch,
+
+    #[error("Invalid parameters format")]
+    ParamsFormatError,
+
+    #[error("Invalid kline interval")]
+    IntervalError,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_from_node_to_align_klines() -> Result<()> {
+        let json_str = r#"{"id":3,"type":"数据/多周期K线对齐","pos":[199,74],"size":{"0":210,"1":310},"flags":{},"order":0,"mode":0,"inputs":[{"name":"K线数据流","type":"KlineStream","link":1},{"name":"K线数据流","type":"KlineStream","link":2}],"properties":{"type":"data.AlignKlines","params":["1m"]}}"#;
+
+        let node: Node = serde_json::from_str(json_str)?;
+        let align_klines = AlignKlines::try_from(node)?;
+
+        assert_eq!(align_klines.params.base_interval, KlineInterval::OneMinute);
+
+        let json_str = r#"{"id":3,"type":"数据/多周期K线对齐","pos":[199,74],"size":{"0":210,"1":310},"flags":{},"order":0,"mode":0,"inputs":[],"properties":{"type":"data.AlignKlines","params":["7m"]}}"#;
+        let node: Node = serde_json::from_str(json_str)?;
+        assert!(AlignKlines::try_from(node).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,343 @@
+use super::backtest_spot_ticker::sync_binance_klines;
+use crate::{
+    node_core::{
+        NodeCategory, NodeCore, NodeCoreExt, NodeError, NodeExecutable, NodeInfra, NodeMeta,
+        NodeMetadata, ProgressTracker, Slot, Tick, TICK_STREAM,
+    },
+    node_io::TickStream,
+    workflow::Node,
+};
+use anyhow::Result;
+use bon::Builder;
+use chrono::{DateTime, Utc};
+use comfy_quant_base::{convert_to_datetime, Exchange, KlineInterval, Market, Symbol};
+use comfy_quant_database::kline;
+use futures::StreamExt;
+use std::sync::Arc;
+
+/// 多交易对回测行情数据，按时间合并多个交易对的K线
+/// outputs:
+///      0: TickStream
+#[derive(Debug)]
+#[allow(unused)]
+pub(crate) struct BacktestMultiSpotTicker {
+    params: Params,          // 参数
+    infra: NodeInfra,        // 节点基础设施
+    exchange: Exchange,      // 交易所
+    market: Market,          // 市场
+    interval: KlineInterval, // 时间间隔
+}
+
+impl NodeMeta for BacktestMultiSpotTicker {
+    const METADATA: NodeMetadata = NodeMetadata {
+        prop_type: "data.BacktestMultiSpotTicker",
+        display_name: "币安现货多交易对行情(回测)",
+        category: NodeCategory::Data,
+        inputs: &[],
+        outputs: &[TICK_STREAM],
+        icon: "chart-candlestick",
+    };
+}
+
+impl NodeCore for BacktestMultiSpotTicker {
+    fn node_infra(&self) -> &NodeInfra {
+        &self.infra
+    }
+
+    fn node_infra_mut(&mut self) -> &mut NodeInfra {
+        &mut self.infra
+    }
+}
+
+impl BacktestMultiSpotTicker {
+    pub(crate) fn try_new(node: Node) -> Result<Self> {
+        let params = Params::try_from(&node)?;
+        let infra = NodeInfra::new(node);
+
+        Ok(BacktestMultiSpotTicker {
+            params,
+            infra,
+            exchange: Exchange::Binance,
+            market: Market::Spot,
+            interval: KlineInterval::OneSecond,
+        })
+    }
+
+    fn symbols(&self) -> Vec<Symbol> {
+        self.params
+            .pairs
+            .iter()
+            .map(|(base_asset, quote_asset)| {
+                format!("{}{}", base_asset, quote_asset)
+                    .to_uppercase()
+                    .into()
+            })
+            .collect()
+    }
+
+    async fn feed_ticks(&self) -> Result<()> {
+        let tick_stream = self.port().output::<TickStream>(0)?;
+        let symbols = self.symbols();
+        let start_timestamp = self.params.start_datetime.timestamp();
+        let end_timestamp = self.params.end_datetime.timestamp();
+        let ctx = self.node_context()?;
+        let mut total = 0;
+
+        for symbol in &symbols {
+            sync_binance_klines(
+                ctx.cloned_db(),
+                &self.market,
+                symbol,
+                &self.interval,
+                start_timestamp,
+                end_timestamp,
+            )
+            .await?;
+
+            total += kline::time_range_klines_count(
+                ctx.db(),
+                &self.exchange,
+                &self.market,
+                symbol,
+                &self.interval,
+                &self.params.start_datetime,
+                &self.params.end_datetime,
+            )
+            .await?;
+        }
+
+        // 回放进度通过事件总线推送
+        let mut progress = ProgressTracker::new(self.node().id, total as u64);
+
+        let mut klines_streams = symbols
+            .iter()
+            .map(|symbol| {
+                kline::time_range_klines_stream(
+                    ctx.db(),
+                    &self.exchange,
+                    &self.market,
+                    symbol,
+                    &self.interval,
+                    &self.params.start_datetime,
+                    &self.params.end_datetime,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        // 每个交易对待发送的下一根K线
+        let mut heads = Vec::with_capacity(klines_streams.len());
+
+        for klines_stream in &mut klines_streams {
+            heads.push(klines_stream.next().await.and_then(Result::ok));
+        }
+
+        let price_store = self.workflow_context()?.cloned_price_store();
+        let clock = self.workflow_context()?.cloned_clock();
+        let heartbeat = self.heartbeat();
+
+        // 同一时间的K线作为一组，先全部写入价格存储再发送，策略收到tick时各交易对的价格一致
+        while let Some(open_time) = heads.iter().flatten().map(|kline| kline.open_time).min() {
+            let _busy = heartbeat.busy();
+            let mut ticks = Vec::new();
+
+            for ((symbol, head), klines_stream) in symbols
+                .iter()
+                .zip(heads.iter_mut())
+                .zip(klines_streams.iter_mut())
+            {
+                let Some(kline) = head.take_if(|kline| kline.open_time == open_time) else {
+                    continue;
+                };
+
+                ticks.push(
+                    Tick::builder()
+                        .timestamp(kline.open_time.timestamp())
+                        .millis(kline.open_time.timestamp_millis())
+                        .symbol(symbol.clone())
+                        .price(kline.close_price)
+                        .volume(kline.volume)
+                        .taker_buy_volume(kline.taker_buy_volume)
+                        .build(),
+                );
+
+                *head = klines_stream.next().await.and_then(Result::ok);
+            }
+
+            ctx.resource_meter().add_db_rows_read(ticks.len() as u64);
+
+            {
+                let mut price_store = price_store.write().await;
+
+                for tick in &ticks {
+                    price_store.save_price(&self.exchange, &self.market, &tick.clone().into())?;
+                    price_store.save_volume(
+                        &self.exchange,
+                        &self.market,
+                        &tick.symbol,
+                        tick.volume,
+                    )?;
+                }
+
+                price_store.save_timestamp(open_time.timestamp());
+            }
+
+            // 以tick时间推进模拟时钟
+            clock.advance(open_time.timestamp_millis());
+
+            // 按该tick的价格撮合模拟账户的挂单，策略收到tick前完成成交
+            self.workflow_context()?.match_backtest_orders().await?;
+
+            for tick in &ticks {
+                tick_stream.send(&self.exchange, &self.market, tick).await?;
+
+                if let Some(event) = progress.advance() {
+                    ctx.publish(event);
+                }
+            }
+        }
+
+        tracing::info!("Tick stream metrics: {:?}", tick_stream.metrics());
+
+        Ok(())
+    }
+}
+
+impl NodeExecutable for BacktestMultiSpotTicker {
+    async fn setup(&mut self) -> Result<()> {
+        let tick_stream_slot = Arc::new(Slot::<TickStream>::new(TickStream::new()));
+
+        self.port_mut().set_output(0, tick_stream_slot)?;
+
+        Ok(())
+    }
+
+    async fn execute(&mut self) -> Result<(), NodeError> {
+        let result = self.feed_ticks().await;
+
+        // 回放结束，通知下游节点
+        self.port().output::<TickStream>(0)?.finish();
+
+        Ok(result?)
+    }
+}
+
+impl TryFrom<Node> for BacktestMultiSpotTicker {
+    type Error = anyhow::Error;
+
+    fn try_from(node: Node) -> Result<Self> {
+        BacktestMultiSpotTicker::try_new(node)
+    }
+}
+
+impl TryFrom<&BacktestMultiSpotTicker> for Node {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &BacktestMultiSpotTicker) -> Result<Self> {
+        Ok(value.node().clone())
+    }
+}
+
+#[derive(Builder, Debug, Clone)]
+pub(crate) struct Params {
+    pairs: Vec<(String, String)>, // 交易对: 基础货币，计价货币
+    start_datetime: DateTime<Utc>,
+    end_datetime: DateTime<Utc>,
+}
+
+impl TryFrom<&Node> for Params {
+    type Error = BacktestMultiSpotTickerError;
+
+    fn try_from(node: &Node) -> Result<Self, Self::Error> {
+        if node.properties.prop_type != "data.BacktestMultiSpotTicker" {
+            return Err(BacktestMultiSpotTickerError::PropertyTypeMismatch);
+        }
+
+        let [pairs, start_datetime, end_datetime] = node.properties.params.as_slice() else {
+            return Err(BacktestMultiSpotTickerError::ParamsFormatError);
+        };
+
+        let pairs = pairs
+            .as_array()
+            .ok_or(BacktestMultiSpotTickerError::PairsError)?
+            .iter()
+            .map(|pair| {
+                let pair = pair.as_array()?;
+                let base_asset = pair.first()?.as_str()?.to_string();
+                let quote_asset = pair.get(1)?.as_str()?.to_string();
+                Some((base_asset, quote_asset))
+            })
+            .collect::<Option<Vec<(String, String)>>>()
+            .filter(|pairs| !pairs.is_empty())
+            .ok_or(BacktestMultiSpotTickerError::PairsError)?;
+
+        let start_datetime = start_datetime
+            .as_str()
+            .and_then(convert_to_datetime)
+            .ok_or(BacktestMultiSpotTickerError::StartDatetimeError)?;
+
+        let end_datetime = end_datetime
+            .as_str()
+            .and_then(convert_to_datetime)
+            .ok_or(BacktestMultiSpotTickerError::EndDatetimeError)?;
+
+        let params = Params::builder()
+            .pairs(pairs)
+            .start_datetime(start_datetime)
+            .end_datetime(end_datetime)
+            .build();
+
+        Ok(params)
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum BacktestMultiSpotTickerError {
+    #[error("Invalid property type, expected 'data.BacktestMultiSpotTicker'")]
+    PropertyTypeMismatch,
+
+    #[error("Invalid parameters format")]
+    ParamsFormatError,
+
+    #[error("Invalid pairs, expected a non-empty list of [base asset, quote asset]")]
+    PairsError,
+
+    #[error("Invalid start datetime")]
+    StartDatetimeError,
+
+    #[error("Invalid end datetime")]
+    EndDatetimeError,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_from_node_to_backtest_multi_spot_ticker() -> Result<()> {
+        let json_str = r#"{"id":1,"type":"数据/币安现货多交易对行情","pos":[199,74],"size":{"0":210,"1":310},"flags":{},"order":0,"mode":0,"inputs":[],"properties":{"type":"data.BacktestMultiSpotTicker","params":[[["BTC","USDT"],["eth","usdt"]],"2024-10-10 15:18:42","2024-10-10 16:18:42"]}}"#;
+
+        let node: Node = serde_json::from_str(json_str)?;
+        let ticker = BacktestMultiSpotTicker::try_from(node)?;
+
+        assert_eq!(
+            ticker.symbols(),
+            vec![Symbol::from("BTCUSDT"), Symbol::from("ETHUSDT")]
+        );
+        assert_eq!(
+            ticker.params.start_datetime,
+            convert_to_datetime("2024-10-10 15:18:42").unwrap()
+        );
+
+        let json_str = r#"{"id":1,"type":"数据/币安现货多交易对行情","pos":[199,74],"size":{"0":210,"1":310},"flags":{},"order":0,"mode":0,"inputs":[],"properties":{"type":"data.BacktestMultiSpotTicker","params":[[],"2024-10-10 15:18:42","2024-10-10 16:18:42"]}}"#;
+
+        let node: Node = serde_json::from_str(json_str)?;
+        assert_eq!(
+            BacktestMultiSpotTicker::try_from(node)
+                .unwrap_err()
+                .to_string(),
+            "Invalid pairs, expected a non-empty list of [base asset, quote asset]"
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,278 @@
+use super::backtest_spot_ticker::sync_binance_klines;
+use crate::{
+    node_core::{
+        Bar, NodeCategory, NodeCore, NodeCoreExt, NodeError, NodeExecutable, NodeInfra, NodeMeta,
+        NodeMetadata, Slot, KLINE_STREAM, SPOT_PAIR_INFO,
+    },
+    node_io::{KlineStream, SpotPairInfo},
+    workflow::Node,
+};
+use anyhow::Result;
+use bon::Builder;
+use chrono::{DateTime, Utc};
+use comfy_quant_base::{convert_to_datetime, Exchange, KlineInterval, Market, Symbol};
+use comfy_quant_database::kline;
+use comfy_quant_exchange::client::spot_client::base::SymbolPrice;
+use futures::StreamExt;
+use std::sync::Arc;
+
+/// 回测K线数据
+/// outputs:
+///      0: SpotPairInfo
+///      1: KlineStream
+#[derive(Debug)]
+#[allow(unused)]
+pub(crate) struct BacktestSpotKlines {
+    params: Params,     // 参数
+    infra: NodeInfra,   // 节点基础设施
+    exchange: Exchange, // 交易所
+    market: Market,     // 市场
+}
+
+impl NodeMeta for BacktestSpotKlines {
+    const METADATA: NodeMetadata = NodeMetadata {
+        prop_type: "data.BacktestSpotKlines",
+        display_name: "币安现货K线(回测)",
+        category: NodeCategory::Data,
+        inputs: &[],
+        outputs: &[SPOT_PAIR_INFO, KLINE_STREAM],
+        icon: "candlestick",
+    };
+}
+
+impl NodeCore for BacktestSpotKlines {
+    fn node_infra(&self) -> &NodeInfra {
+        &self.infra
+    }
+
+    fn node_infra_mut(&mut self) -> &mut NodeInfra {
+        &mut self.infra
+    }
+}
+
+impl BacktestSpotKlines {
+    pub(crate) fn try_new(node: Node) -> Result<Self> {
+        let params = Params::try_from(&node)?;
+        let infra = NodeInfra::new(node);
+
+        Ok(BacktestSpotKlines {
+            params,
+            infra,
+            exchange: Exchange::Binance,
+            market: Market::Spot,
+        })
+    }
+
+    async fn feed_klines(&self) -> Result<()> {
+        let kline_stream = self.port().output::<KlineStream>(1)?;
+        let symbol: Symbol = format!("{}{}", self.params.base_asset, self.params.quote_asset)
+            .to_uppercase()
+            .into();
+        let ctx = self.node_context()?;
+
+        sync_binance_klines(
+            ctx.cloned_db(),
+            &self.market,
+            &symbol,
+            &self.params.interval,
+            self.params.start_datetime.timestamp(),
+            self.params.end_datetime.timestamp(),
+        )
+        .await?;
+
+        let mut klines_stream = kline::time_range_klines_stream(
+            ctx.db(),
+            &self.exchange,
+            &self.market,
+            &symbol,
+            &self.params.interval,
+            &self.params.start_datetime,
+            &self.params.end_datetime,
+        );
+
+        let price_store = self.workflow_context()?.cloned_price_store();
+        let clock = self.workflow_context()?.cloned_clock();
+        let heartbeat = self.heartbeat();
+
+        while let Some(Ok(kline)) = klines_stream.next().await {
+            let _busy = heartbeat.busy();
+            ctx.resource_meter().add_db_rows_read(1);
+
+            let bar = Bar::from(&kline);
+
+            // K线收盘后才可见，以收盘时间作为模拟时间，避免使用未来数据
+            {
+                let mut price_store = price_store.write().await;
+                let symbol_price = SymbolPrice::builder()
+                    .symbol(symbol.clone())
+                    .price(bar.close)
+                    .build();
+                price_store.save_price(&self.exchange, &self.market, &symbol_price)?;
+                price_store.save_volume(&self.exchange, &self.market, &symbol, bar.volume)?;
+                price_store.save_timestamp(bar.close_time());
+            }
+
+            clock.advance(bar.close_millis());
+
+            kline_stream
+                .send(&self.exchange, &self.market, &bar)
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+impl NodeExecutable for BacktestSpotKlines {
+    async fn setup(&mut self) -> Result<()> {
+        let pair_info = self
+            .node_infra()
+            .spot_pair_info(
+                &self.exchange,
+                &self.params.base_asset,
+                &self.params.quote_asset,
+            )
+            .await?;
+
+        let pair_info_slot = Arc::new(Slot::<SpotPairInfo>::new(pair_info));
+        let kline_stream_slot = Arc::new(Slot::<KlineStream>::new(KlineStream::new()));
+
+        self.port_mut().set_output(0, pair_info_slot)?;
+        self.port_mut().set_output(1, kline_stream_slot)?;
+
+        Ok(())
+    }
+
+    async fn execute(&mut self) -> Result<(), NodeError> {
+        let result = self.feed_klines().await;
+
+        // 回放结束，通知下游节点
+        self.port().output::<KlineStream>(1)?.finish();
+
+        Ok(result?)
+    }
+}
+
+impl TryFrom<Node> for BacktestSpotKlines {
+    type Error = anyhow::Error;
+
+    fn try_from(node: Node) -> Result<Self> {
+        BacktestSpotKlines::try_new(node)
+    }
+}
+
+impl TryFrom<&BacktestSpotKlines> for Node {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &BacktestSpotKlines) -> Result<Self> {
+        Ok(value.node().clone())
+    }
+}
+
+#[derive(Builder, Debug, Clone)]
+#[builder(on(String, into))]
+pub(crate) struct Params {
+    base_asset: String,
+    quote_asset: String,
+    start_datetime: DateTime<Utc>,
+    end_datetime: DateTime<Utc>,
+    interval: KlineInterval,
+}
+
+impl TryFrom<&Node> for Params {
+    type Error = BacktestSpotKlinesError;
+
+    fn try_from(node: &Node) -> Result<Self, Self::Error> {
+        if node.properties.prop_type != "data.BacktestSpotKlines" {
+            return Err(BacktestSpotKlinesError::PropertyTypeMismatch);
+        }
+
+        let [base_asset, quote_asset, start_datetime, end_datetime, interval] =
+            node.properties.params.as_slice()
+        else {
+            return Err(BacktestSpotKlinesError::ParamsFormatError);
+        };
+
+        let base_asset = base_asset
+            .as_str()
+            .ok_or(BacktestSpotKlinesError::BaseAssetError)?;
+
+        let quote_asset = quote_asset
+            .as_str()
+            .ok_or(BacktestSpotKlinesError::QuoteAssetError)?;
+
+        let start_datetime = start_datetime
+            .as_str()
+            .and_then(convert_to_datetime)
+            .ok_or(BacktestSpotKlinesError::StartDatetimeError)?;
+
+        let end_datetime = end_datetime
+            .as_str()
+            .and_then(convert_to_datetime)
+            .ok_or(BacktestSpotKlinesError::EndDatetimeError)?;
+
+        // 未知的时间间隔会被解析为1s，这里严格校验
+        let interval = interval
+            .as_str()
+            .filter(|interval| KlineInterval::from(*interval).as_ref() == *interval)
+            .map(KlineInterval::from)
+            .ok_or(BacktestSpotKlinesError::IntervalError)?;
+
+        let params = Params::builder()
+            .base_asset(base_asset)
+            .quote_asset(quote_asset)
+            .start_datetime(start_datetime)
+            .end_datetime(end_datetime)
+            .interval(interval)
+            .build();
+
+        Ok(params)
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum BacktestSpotKlinesError {
+    #[error("Invalid property type, expected 'data.BacktestSpotKlines'")]
+    PropertyTypeMismatch,
+
+    #[error("Invalid parameters format")]
+    ParamsFormatError,
+
+    #[error("Invalid base asset")]
+    BaseAssetError,
+
+    #[error("Invalid quote asset")]
+    QuoteAssetError,
+
+    #[error("Invalid start datetime")]
+    StartDatetimeError,
+
+    #[error("Invalid end datetime")]
+    EndDatetimeError,
+
+    #[error("Invalid kline interval")]
+    IntervalError,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_from_node_to_backtest_spot_klines() -> Result<()> {
+        let json_str = r#"{"id":1,"type":"数据/币安现货K线","pos":[199,74],"size":{"0":210,"1":310},"flags":{},"order":0,"mode":0,"inputs":[],"properties":{"type":"data.BacktestSpotKlines","params":["BTC","USDT","2024-10-10 15:18:42","2024-10-10 16:18:42","1h"]}}"#;
+
+        let node: Node = serde_json::from_str(json_str)?;
+        let backtest_spot_klines = BacktestSpotKlines::try_from(node)?;
+
+        assert_eq!(backtest_spot_klines.params.base_asset, "BTC");
+        assert_eq!(backtest_spot_klines.params.quote_asset, "USDT");
+        assert_eq!(backtest_spot_klines.params.interval, KlineInterval::OneHour);
+
+        let json_str = r#"{"id":1,"type":"数据/币安现货K线","pos":[199,74],"size":{"0":210,"1":310},"flags":{},"order":0,"mode":0,"inputs":[],"properties":{"type":"data.BacktestSpotKlines","params":["BTC","USDT","2024-10-10 15:18:42","2024-10-10 16:18:42","7m"]}}"#;
+        let node: Node = serde_json::from_str(json_str)?;
+        assert!(BacktestSpotKlines::try_from(node).is_err());
+
+        Ok(())
+    }
+}
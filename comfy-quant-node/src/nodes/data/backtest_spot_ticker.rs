@@ -1,5 +1,8 @@
 use crate::{
-    node_core::{NodeCore, NodeCoreExt, NodeExecutable, NodeInfra, Slot, Tick},
+    node_core::{
+        NodeCategory, NodeCore, NodeCoreExt, NodeError, NodeExecutable, NodeInfra, NodeMeta,
+        NodeMetadata, ProgressTracker, Slot, Tick, SPOT_PAIR_INFO, TICK_STREAM,
+    },
     node_io::{SpotPairInfo, TickStream},
     workflow::Node,
 };
@@ -13,6 +16,7 @@ use comfy_quant_task::{
     tasks::binance_klines::BinanceKlinesTask,
 };
 use futures::StreamExt;
+use sqlx::PgPool;
 use std::sync::Arc;
 
 /// 回测行情数据
@@ -29,6 +33,17 @@ pub(crate) struct BacktestSpotTicker {
     interval: KlineInterval, // 时间间隔
 }
 
+impl NodeMeta for BacktestSpotTicker {
+    const METADATA: NodeMetadata = NodeMetadata {
+        prop_type: "data.BacktestSpotTicker",
+        display_name: "币安现货行情(回测)",
+        category: NodeCategory::Data,
+        inputs: &[],
+        outputs: &[SPOT_PAIR_INFO, TICK_STREAM],
+        icon: "chart-line",
+    };
+}
+
 impl NodeCore for BacktestSpotTicker {
     fn node_infra(&self) -> &NodeInfra {
         &self.infra
@@ -62,36 +77,51 @@ impl BacktestSpotTicker {
         let end_timestamp = self.params.end_datetime.timestamp();
         let ctx = self.node_context()?;
 
-        // 等待数据同步完成，如果出错，重试3次
-        'retry: for i in 0..3 {
-            let task = BinanceKlinesTask::builder()
-                .db(ctx.cloned_db())
-                .market(self.market.clone())
-                .symbol(symbol.clone())
-                .interval(self.interval.clone())
-                .start_timestamp(start_timestamp)
-                .end_timestamp(end_timestamp)
-                .build()?;
-
-            let mut task_result = task.execute().await?;
-
-            tracing::info!("Binance klines task start");
-
-            while let Some(Ok(status)) = task_result.next().await {
-                match status {
-                    TaskStatus::Finished => {
-                        tracing::info!("Binance klines task finished");
-                        break 'retry;
-                    }
-                    TaskStatus::Failed(err) => {
-                        tracing::error!("{} Binance klines task failed: {}", i + 1, err);
-                        continue 'retry;
-                    }
-                    _ => {}
-                }
-            }
+        sync_binance_klines(
+            ctx.cloned_db(),
+            &self.market,
+            &symbol,
+            &self.interval,
+            start_timestamp,
+            end_timestamp,
+        )
+        .await?;
+
+        // 交易所停机等原因缺失的K线在回放中会被跳过，记录下来便于排查
+        let gaps = kline::find_gaps(
+            ctx.db(),
+            &self.exchange,
+            &self.market,
+            &symbol,
+            &self.interval,
+            &self.params.start_datetime,
+            &self.params.end_datetime,
+        )
+        .await?;
+
+        if let Some(gap) = gaps.first() {
+            tracing::warn!(
+                %symbol,
+                "Backtest klines have {} gaps, first gap: {} - {}",
+                gaps.len(),
+                gap.start_time,
+                gap.end_time
+            );
         }
 
+        // 回放进度通过事件总线推送
+        let total = kline::time_range_klines_count(
+            ctx.db(),
+            &self.exchange,
+            &self.market,
+            &symbol,
+            &self.interval,
+            &self.params.start_datetime,
+            &self.params.end_datetime,
+        )
+        .await?;
+        let mut progress = ProgressTracker::new(self.node().id, total as u64);
+
         let mut klines_stream = kline::time_range_klines_stream(
             ctx.db(),
             &self.exchange,
@@ -103,35 +133,102 @@ impl BacktestSpotTicker {
         );
 
         let price_store = self.workflow_context()?.cloned_price_store();
+        let clock = self.workflow_context()?.cloned_clock();
+        let heartbeat = self.heartbeat();
 
         while let Some(Ok(kline)) = klines_stream.next().await {
+            let _busy = heartbeat.busy();
+            ctx.resource_meter().add_db_rows_read(1);
+
             let tick = Tick::builder()
                 .timestamp(kline.open_time.timestamp())
+                .millis(kline.open_time.timestamp_millis())
                 .symbol(symbol.clone())
                 .price(kline.close_price)
+                .volume(kline.volume)
+                .taker_buy_volume(kline.taker_buy_volume)
                 .build();
 
-            price_store.write().await.save_price(
-                &self.exchange,
-                &self.market,
-                &tick.clone().into(),
-            )?;
+            {
+                let mut price_store = price_store.write().await;
+                price_store.save_price(&self.exchange, &self.market, &tick.clone().into())?;
+                price_store.save_volume(&self.exchange, &self.market, &symbol, tick.volume)?;
+                price_store.save_timestamp(tick.timestamp);
+            }
+
+            // 以tick时间推进模拟时钟
+            clock.advance(tick.timestamp_millis());
+
+            // 按该tick的价格撮合模拟账户的挂单，策略收到tick前完成成交
+            self.workflow_context()?.match_backtest_orders().await?;
 
             tick_stream
                 .send(&self.exchange, &self.market, &tick)
                 .await?;
+
+            if let Some(event) = progress.advance() {
+                ctx.publish(event);
+            }
         }
 
+        tracing::info!("Tick stream metrics: {:?}", tick_stream.metrics());
+
         Ok(())
     }
 }
 
+// 等待币安K线数据同步完成，如果出错，重试3次
+pub(super) async fn sync_binance_klines(
+    db: Arc<PgPool>,
+    market: &Market,
+    symbol: &Symbol,
+    interval: &KlineInterval,
+    start_timestamp: i64,
+    end_timestamp: i64,
+) -> Result<()> {
+    'retry: for i in 0..3 {
+        let task = BinanceKlinesTask::builder()
+            .db(Arc::clone(&db))
+            .market(market.clone())
+            .symbol(symbol.clone())
+            .interval(interval.clone())
+            .start_timestamp(start_timestamp)
+            .end_timestamp(end_timestamp)
+            .build()?;
+
+        let mut task_result = task.execute().await?;
+
+        tracing::info!("Binance klines task start");
+
+        while let Some(Ok(status)) = task_result.next().await {
+            match status {
+                TaskStatus::Finished => {
+                    tracing::info!("Binance klines task finished");
+                    break 'retry;
+                }
+                TaskStatus::Failed(err) => {
+                    tracing::error!("{} Binance klines task failed: {}", i + 1, err);
+                    continue 'retry;
+                }
+                TaskStatus::Cancelled => anyhow::bail!("Binance klines task cancelled"),
+                _ => {}
+            }
+        }
+    }
+
+    Ok(())
+}
+
 impl NodeExecutable for BacktestSpotTicker {
     async fn setup(&mut self) -> Result<()> {
-        let pair_info = SpotPairInfo::builder()
-            .base_asset(&self.params.base_asset)
-            .quote_asset(&self.params.quote_asset)
-            .build();
+        let pair_info = self
+            .node_infra()
+            .spot_pair_info(
+                &self.exchange,
+                &self.params.base_asset,
+                &self.params.quote_asset,
+            )
+            .await?;
         let tick_stream = TickStream::new();
 
         let pair_info_slot = Arc::new(Slot::<SpotPairInfo>::new(pair_info));
@@ -143,9 +240,13 @@ impl NodeExecutable for BacktestSpotTicker {
         Ok(())
     }
 
-    async fn execute(&mut self) -> Result<()> {
-        self.feed_ticks().await?;
-        Ok(())
+    async fn execute(&mut self) -> Result<(), NodeError> {
+        let result = self.feed_ticks().await;
+
+        // 回放结束，通知下游节点
+        self.port().output::<TickStream>(1)?.finish();
+
+        Ok(result?)
     }
 }
 
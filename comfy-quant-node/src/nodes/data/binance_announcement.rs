@@ -0,0 +1,173 @@
+use crate::{
+    node_core::{
+        NodeCategory, NodeCore, NodeCoreExt, NodeError, NodeExecutable, NodeInfra, NodeMeta,
+        NodeMetadata, Slot, ANNOUNCEMENT_STREAM,
+    },
+    node_io::AnnouncementStream,
+    workflow::Node,
+};
+use anyhow::Result;
+use bon::Builder;
+use chrono::Utc;
+use comfy_quant_exchange::announcement::BinanceAnnouncement as BinanceAnnouncementClient;
+use futures::StreamExt;
+use std::{sync::Arc, time::Duration};
+
+/// 币安公告(新币上线、下架、维护)
+/// outputs:
+///      0: AnnouncementStream
+#[derive(Debug)]
+pub(crate) struct BinanceAnnouncement {
+    params: Params,
+    infra: NodeInfra,
+}
+
+impl NodeMeta for BinanceAnnouncement {
+    const METADATA: NodeMetadata = NodeMetadata {
+        prop_type: "data.BinanceAnnouncement",
+        display_name: "币安公告",
+        category: NodeCategory::Data,
+        inputs: &[],
+        outputs: &[ANNOUNCEMENT_STREAM],
+        icon: "megaphone",
+    };
+}
+
+impl NodeCore for BinanceAnnouncement {
+    fn node_infra(&self) -> &NodeInfra {
+        &self.infra
+    }
+
+    fn node_infra_mut(&mut self) -> &mut NodeInfra {
+        &mut self.infra
+    }
+}
+
+impl BinanceAnnouncement {
+    pub(crate) fn try_new(node: Node) -> Result<Self> {
+        let params = Params::try_from(&node)?;
+        let infra = NodeInfra::new(node);
+
+        Ok(BinanceAnnouncement { params, infra })
+    }
+
+    async fn feed_announcements(&self) -> Result<()> {
+        let announcement_stream = self.port().output::<AnnouncementStream>(0)?;
+        let client = BinanceAnnouncementClient::builder().build()?;
+        let poll_interval = Duration::from_secs(self.params.poll_secs);
+        let heartbeat = self.heartbeat();
+
+        // 只推送节点启动后发布的公告
+        let mut stream = client.announcements_stream(Utc::now(), poll_interval);
+
+        while let Some(announcement) = stream.next().await {
+            let _busy = heartbeat.busy();
+
+            tracing::info!(
+                monotonic_counter.announcement_received = 1_u64,
+                kind = announcement.kind.as_ref(),
+                "Binance announcement: {}",
+                announcement.title
+            );
+
+            announcement_stream.send(&announcement).await?;
+        }
+
+        Ok(())
+    }
+}
+
+impl NodeExecutable for BinanceAnnouncement {
+    async fn setup(&mut self) -> Result<()> {
+        let announcement_stream = AnnouncementStream::new();
+        let announcement_stream_slot =
+            Arc::new(Slot::<AnnouncementStream>::new(announcement_stream));
+
+        self.port_mut().set_output(0, announcement_stream_slot)?;
+
+        Ok(())
+    }
+
+    async fn execute(&mut self) -> Result<(), NodeError> {
+        let result = self.feed_announcements().await;
+
+        self.port().output::<AnnouncementStream>(0)?.finish();
+
+        Ok(result?)
+    }
+}
+
+impl TryFrom<Node> for BinanceAnnouncement {
+    type Error = anyhow::Error;
+
+    fn try_from(node: Node) -> Result<Self> {
+        BinanceAnnouncement::try_new(node)
+    }
+}
+
+impl TryFrom<&BinanceAnnouncement> for Node {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &BinanceAnnouncement) -> Result<Self> {
+        Ok(value.node().clone())
+    }
+}
+
+#[derive(Builder, Debug, Clone)]
+pub(crate) struct Params {
+    poll_secs: u64, // 轮询间隔(秒)
+}
+
+impl TryFrom<&Node> for Params {
+    type Error = BinanceAnnouncementError;
+
+    fn try_from(node: &Node) -> Result<Self, Self::Error> {
+        if node.properties.prop_type != "data.BinanceAnnouncement" {
+            return Err(BinanceAnnouncementError::PropertyTypeMismatch);
+        }
+
+        let [poll_secs] = node.properties.params.as_slice() else {
+            return Err(BinanceAnnouncementError::ParamsFormatError);
+        };
+
+        let poll_secs = poll_secs
+            .as_u64()
+            .filter(|secs| *secs > 0)
+            .ok_or(BinanceAnnouncementError::PollSecsError)?;
+
+        Ok(Params::builder().poll_secs(poll_secs).build())
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum BinanceAnnouncementError {
+    #[error("Invalid property type, expected 'data.BinanceAnnouncement'")]
+    PropertyTypeMismatch,
+
+    #[error("Invalid parameters format")]
+    ParamsFormatError,
+
+    #[error("Invalid poll secs, must be a positive integer")]
+    PollSecsError,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_from_node_to_binance_announcement() -> Result<()> {
+        let json_str = r#"{"id":1,"type":"数据/币安公告","pos":[199,74],"size":{"0":210,"1":310},"flags":{},"order":0,"mode":0,"inputs":[],"properties":{"type":"data.BinanceAnnouncement","params":[60]}}"#;
+
+        let node: Node = serde_json::from_str(json_str)?;
+        let binance_announcement = BinanceAnnouncement::try_from(node)?;
+
+        assert_eq!(binance_announcement.params.poll_secs, 60);
+
+        let json_str = r#"{"id":1,"type":"数据/币安公告","pos":[199,74],"size":{"0":210,"1":310},"flags":{},"order":0,"mode":0,"inputs":[],"properties":{"type":"data.BinanceAnnouncement","params":[0]}}"#;
+        let node: Node = serde_json::from_str(json_str)?;
+        assert!(BinanceAnnouncement::try_from(node).is_err());
+
+        Ok(())
+    }
+}
@@ -1,10 +1,17 @@
 use crate::{
-    node_core::{NodeCore, NodeCoreExt, NodeExecutable, NodeInfra, Slot},
-    node_io::SpotPairInfo,
+    node_core::{
+        NodeCategory, NodeCore, NodeCoreExt, NodeError, NodeExecutable, NodeInfra, NodeMeta,
+        NodeMetadata, Slot, Tick, SPOT_PAIR_INFO, TICK_STREAM,
+    },
+    node_io::{SpotPairInfo, TickStream},
     workflow::Node,
 };
 use anyhow::Result;
 use bon::Builder;
+use comfy_quant_base::{Exchange, Market};
+use comfy_quant_database::spot_pairs::{self, CreateSpotPairParams};
+use comfy_quant_exchange::market_data::{BinanceConnector, MarketDataConnector};
+use futures::StreamExt;
 use std::sync::Arc;
 
 /// 币安现货行情
@@ -12,10 +19,22 @@ use std::sync::Arc;
 ///      0: SpotPairInfo
 ///      1: TickStream
 #[derive(Debug)]
-#[allow(unused)]
 pub(crate) struct BinanceSpotTicker {
-    params: Params,
-    infra: NodeInfra,
+    params: Params,     // 参数
+    infra: NodeInfra,   // 节点基础设施
+    exchange: Exchange, // 交易所
+    market: Market,     // 市场
+}
+
+impl NodeMeta for BinanceSpotTicker {
+    const METADATA: NodeMetadata = NodeMetadata {
+        prop_type: "data.BinanceSpotTicker",
+        display_name: "币安现货行情",
+        category: NodeCategory::Data,
+        inputs: &[],
+        outputs: &[SPOT_PAIR_INFO, TICK_STREAM],
+        icon: "chart-line",
+    };
 }
 
 impl NodeCore for BinanceSpotTicker {
@@ -33,58 +52,109 @@ impl BinanceSpotTicker {
         let params = Params::try_from(&node)?;
         let infra = NodeInfra::new(node);
 
-        Ok(BinanceSpotTicker { params, infra })
+        Ok(BinanceSpotTicker {
+            params,
+            infra,
+            exchange: Exchange::Binance,
+            market: Market::Spot,
+        })
     }
 
-    async fn feed_ticks(&self) -> Result<()> {
-        // let slot = self.port.output::<Tick>(1)?;
-
-        // let symbol = format!(
-        //     "{}{}@ticker",
-        //     self.params.base_asset.to_lowercase(),
-        //     self.params.quote_asset.to_lowercase()
-        // );
-
-        // todo: 从数据库推送中获取行情
-        // tokio::spawn(async move {
-        //     loop {
-        //         let tick = Tick::builder()
-        //             .timestamp(Utc::now().timestamp())
-        //             .price(0.)
-        //             .build();
+    // 从交易所刷新交易对缓存，失败时沿用已缓存的交易对信息
+    async fn refresh_spot_pair(&self) -> Result<()> {
+        let ctx = self.node_context()?;
+        let symbol_info = BinanceConnector::default()
+            .fetch_exchange_info(&self.params.base_asset, &self.params.quote_asset)
+            .await?;
 
-        //         slot.send(tick).await?;
+        spot_pairs::create_or_update(
+            ctx.db(),
+            CreateSpotPairParams::from_symbol_info(Exchange::Binance, &symbol_info),
+        )
+        .await?;
+        ctx.resource_meter().add_db_rows_written(1);
 
-        //         tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
-        //     }
+        Ok(())
+    }
 
-        //     #[allow(unreachable_code)]
-        //     Ok::<(), anyhow::Error>(())
-        // });
+    // 每根1秒K线收盘后推送一个tick，与回测时由1秒K线生成的tick一致
+    async fn feed_ticks(&self) -> Result<()> {
+        let tick_stream = self.port().output::<TickStream>(1)?;
+        let symbol = self
+            .exchange
+            .symbol(&self.params.base_asset, &self.params.quote_asset);
+
+        let mut ticks = BinanceConnector::default().stream_ticks(&symbol);
+        let price_store = self.workflow_context()?.cloned_price_store();
+        let heartbeat = self.heartbeat();
+
+        while let Some(market_tick) = ticks.next().await {
+            let market_tick = market_tick?;
+            let _busy = heartbeat.busy();
+
+            let tick = Tick::builder()
+                .timestamp(market_tick.timestamp)
+                .symbol(market_tick.symbol)
+                .price(market_tick.price)
+                .volume(market_tick.volume)
+                .taker_buy_volume(market_tick.taker_buy_volume)
+                .maybe_millis(market_tick.millis)
+                .build();
+
+            {
+                let mut price_store = price_store.write().await;
+                price_store.save_price(&self.exchange, &self.market, &tick.clone().into())?;
+                price_store.save_volume(&self.exchange, &self.market, &symbol, tick.volume)?;
+                price_store.save_timestamp(tick.timestamp);
+            }
+
+            // 按该tick的价格撮合模拟盘账户的挂单，撮合失败不影响行情推送
+            if let Err(e) = self.workflow_context()?.match_backtest_orders().await {
+                tracing::error!("Match paper orders failed: {}", e);
+            }
+
+            tick_stream
+                .send(&self.exchange, &self.market, &tick)
+                .await?;
+        }
 
-        Ok(())
+        anyhow::bail!("Binance spot tick stream closed: {}", symbol)
     }
 }
 
 impl NodeExecutable for BinanceSpotTicker {
     async fn setup(&mut self) -> Result<()> {
-        let pair_info = SpotPairInfo::builder()
-            .base_asset(&self.params.base_asset)
-            .quote_asset(&self.params.quote_asset)
-            .build();
+        if let Err(e) = self.refresh_spot_pair().await {
+            tracing::warn!("Refresh spot pair failed: {}", e);
+        }
+
+        let pair_info = self
+            .node_infra()
+            .spot_pair_info(
+                &self.exchange,
+                &self.params.base_asset,
+                &self.params.quote_asset,
+            )
+            .await?;
 
         let pair_info_slot = Arc::new(Slot::<SpotPairInfo>::new(pair_info));
-        // let output_slot1 = Slot::<Tick>::builder().channel_capacity(1024).build();
+        // 开启录制时保存发送给下游的每个tick，用于事后回放
+        let mut tick_stream = TickStream::new();
+
+        if let Some(recorder) = self.workflow_context()?.tick_recorder(self.node().id) {
+            tick_stream = tick_stream.with_recorder(recorder);
+        }
+
+        let tick_stream_slot = Arc::new(Slot::<TickStream>::new(tick_stream));
 
         self.port_mut().set_output(0, pair_info_slot)?;
-        // port.set_output(1, output_slot1)?;
+        self.port_mut().set_output(1, tick_stream_slot)?;
 
         Ok(())
     }
 
-    async fn execute(&mut self) -> Result<()> {
-        self.feed_ticks().await?;
-        Ok(())
+    async fn execute(&mut self) -> Result<(), NodeError> {
+        Ok(self.feed_ticks().await?)
     }
 }
 
@@ -96,6 +166,14 @@ impl TryFrom<Node> for BinanceSpotTicker {
     }
 }
 
+impl TryFrom<&BinanceSpotTicker> for Node {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &BinanceSpotTicker) -> Result<Self> {
+        Ok(value.node().clone())
+    }
+}
+
 #[derive(Builder, Debug, Clone)]
 #[builder(on(String, into))]
 pub(crate) struct Params {
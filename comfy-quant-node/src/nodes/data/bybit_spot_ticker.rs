@@ -0,0 +1,369 @@
+use crate::{
+    node_core::{
+        NodeCategory, NodeCore, NodeCoreExt, NodeError, NodeExecutable, NodeInfra, NodeMeta,
+        NodeMetadata, ProgressTracker, Slot, Tick, SPOT_PAIR_INFO, TICK_STREAM,
+    },
+    node_io::{SpotPairInfo, TickStream},
+    workflow::Node,
+};
+use anyhow::Result;
+use bon::Builder;
+use chrono::{DateTime, Utc};
+use comfy_quant_base::{convert_to_datetime, Exchange, KlineInterval, Market, Symbol};
+use comfy_quant_database::{
+    kline,
+    spot_pairs::{self, CreateSpotPairParams},
+};
+use comfy_quant_exchange::market_data::{BybitConnector, MarketDataConnector};
+use comfy_quant_task::{
+    task_core::{status::TaskStatus, traits::Executable as _},
+    tasks::bybit_klines::BybitKlinesTask,
+};
+use futures::StreamExt;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+/// Bybit现货行情(回测)，Bybit 没有秒级K线，以1分钟K线的收盘价生成tick
+/// outputs:
+///      0: SpotPairInfo
+///      1: TickStream
+#[derive(Debug)]
+pub(crate) struct BybitSpotTicker {
+    params: Params,          // 参数
+    infra: NodeInfra,        // 节点基础设施
+    exchange: Exchange,      // 交易所
+    market: Market,          // 市场
+    interval: KlineInterval, // 时间间隔
+}
+
+impl NodeMeta for BybitSpotTicker {
+    const METADATA: NodeMetadata = NodeMetadata {
+        prop_type: "data.BybitSpotTicker",
+        display_name: "Bybit现货行情(回测)",
+        category: NodeCategory::Data,
+        inputs: &[],
+        outputs: &[SPOT_PAIR_INFO, TICK_STREAM],
+        icon: "chart-line",
+    };
+}
+
+impl NodeCore for BybitSpotTicker {
+    fn node_infra(&self) -> &NodeInfra {
+        &self.infra
+    }
+
+    fn node_infra_mut(&mut self) -> &mut NodeInfra {
+        &mut self.infra
+    }
+}
+
+impl BybitSpotTicker {
+    pub(crate) fn try_new(node: Node) -> Result<Self> {
+        let params = Params::try_from(&node)?;
+        let infra = NodeInfra::new(node);
+
+        Ok(BybitSpotTicker {
+            params,
+            infra,
+            exchange: Exchange::Bybit,
+            market: Market::Spot,
+            interval: KlineInterval::OneMinute,
+        })
+    }
+
+    // 从交易所刷新交易对缓存，失败时沿用已缓存的交易对信息
+    async fn refresh_spot_pair(&self) -> Result<()> {
+        let ctx = self.node_context()?;
+        let symbol_info = BybitConnector::default()
+            .fetch_exchange_info(&self.params.base_asset, &self.params.quote_asset)
+            .await?;
+
+        spot_pairs::create_or_update(
+            ctx.db(),
+            CreateSpotPairParams::from_symbol_info(Exchange::Bybit, &symbol_info),
+        )
+        .await?;
+        ctx.resource_meter().add_db_rows_written(1);
+
+        Ok(())
+    }
+
+    async fn feed_ticks(&self) -> Result<()> {
+        let tick_stream = self.port().output::<TickStream>(1)?;
+        let symbol = self
+            .exchange
+            .symbol(&self.params.base_asset, &self.params.quote_asset);
+        let start_timestamp = self.params.start_datetime.timestamp();
+        let end_timestamp = self.params.end_datetime.timestamp();
+        let ctx = self.node_context()?;
+
+        sync_bybit_klines(
+            ctx.cloned_db(),
+            &self.market,
+            &symbol,
+            &self.interval,
+            start_timestamp,
+            end_timestamp,
+        )
+        .await?;
+
+        // 回放进度通过事件总线推送
+        let total = kline::time_range_klines_count(
+            ctx.db(),
+            &self.exchange,
+            &self.market,
+            &symbol,
+            &self.interval,
+            &self.params.start_datetime,
+            &self.params.end_datetime,
+        )
+        .await?;
+        let mut progress = ProgressTracker::new(self.node().id, total as u64);
+
+        let mut klines_stream = kline::time_range_klines_stream(
+            ctx.db(),
+            &self.exchange,
+            &self.market,
+            &symbol,
+            &self.interval,
+            &self.params.start_datetime,
+            &self.params.end_datetime,
+        );
+
+        let price_store = self.workflow_context()?.cloned_price_store();
+        let clock = self.workflow_context()?.cloned_clock();
+        let heartbeat = self.heartbeat();
+
+        while let Some(Ok(kline)) = klines_stream.next().await {
+            let _busy = heartbeat.busy();
+            ctx.resource_meter().add_db_rows_read(1);
+
+            // 收盘价在K线收盘后才可见，以收盘时间作为tick时间，避免使用未来数据
+            let tick = Tick::builder()
+                .timestamp(kline.open_time.timestamp() + self.interval.to_seconds())
+                .millis(kline.open_time.timestamp_millis() + self.interval.to_millis())
+                .symbol(symbol.clone())
+                .price(kline.close_price)
+                .volume(kline.volume)
+                .taker_buy_volume(kline.taker_buy_volume)
+                .build();
+
+            {
+                let mut price_store = price_store.write().await;
+                price_store.save_price(&self.exchange, &self.market, &tick.clone().into())?;
+                price_store.save_volume(&self.exchange, &self.market, &symbol, tick.volume)?;
+                price_store.save_timestamp(tick.timestamp);
+            }
+
+            // 以tick时间推进模拟时钟
+            clock.advance(tick.timestamp_millis());
+
+            // 按该tick的价格撮合模拟账户的挂单，策略收到tick前完成成交
+            self.workflow_context()?.match_backtest_orders().await?;
+
+            tick_stream
+                .send(&self.exchange, &self.market, &tick)
+                .await?;
+
+            if let Some(event) = progress.advance() {
+                ctx.publish(event);
+            }
+        }
+
+        tracing::info!("Tick stream metrics: {:?}", tick_stream.metrics());
+
+        Ok(())
+    }
+}
+
+// 等待Bybit K线数据同步完成，如果出错，重试3次
+async fn sync_bybit_klines(
+    db: Arc<PgPool>,
+    market: &Market,
+    symbol: &Symbol,
+    interval: &KlineInterval,
+    start_timestamp: i64,
+    end_timestamp: i64,
+) -> Result<()> {
+    'retry: for i in 0..3 {
+        let task = BybitKlinesTask::builder()
+            .db(Arc::clone(&db))
+            .market(market.clone())
+            .symbol(symbol.clone())
+            .interval(interval.clone())
+            .start_timestamp(start_timestamp)
+            .end_timestamp(end_timestamp)
+            .build()?;
+
+        let mut task_result = task.execute().await?;
+
+        tracing::info!("Bybit klines task start");
+
+        while let Some(Ok(status)) = task_result.next().await {
+            match status {
+                TaskStatus::Finished => {
+                    tracing::info!("Bybit klines task finished");
+                    break 'retry;
+                }
+                TaskStatus::Failed(err) => {
+                    tracing::error!("{} Bybit klines task failed: {}", i + 1, err);
+                    continue 'retry;
+                }
+                TaskStatus::Cancelled => anyhow::bail!("Bybit klines task cancelled"),
+                _ => {}
+            }
+        }
+    }
+
+    Ok(())
+}
+
+impl NodeExecutable for BybitSpotTicker {
+    async fn setup(&mut self) -> Result<()> {
+        if let Err(e) = self.refresh_spot_pair().await {
+            tracing::warn!("Refresh spot pair failed: {}", e);
+        }
+
+        let pair_info = self
+            .node_infra()
+            .spot_pair_info(
+                &self.exchange,
+                &self.params.base_asset,
+                &self.params.quote_asset,
+            )
+            .await?;
+        let tick_stream = TickStream::new();
+
+        let pair_info_slot = Arc::new(Slot::<SpotPairInfo>::new(pair_info));
+        let tick_stream_slot = Arc::new(Slot::<TickStream>::new(tick_stream));
+
+        self.port_mut().set_output(0, pair_info_slot)?;
+        self.port_mut().set_output(1, tick_stream_slot)?;
+
+        Ok(())
+    }
+
+    async fn execute(&mut self) -> Result<(), NodeError> {
+        let result = self.feed_ticks().await;
+
+        // 回放结束，通知下游节点
+        self.port().output::<TickStream>(1)?.finish();
+
+        Ok(result?)
+    }
+}
+
+impl TryFrom<Node> for BybitSpotTicker {
+    type Error = anyhow::Error;
+
+    fn try_from(node: Node) -> Result<Self> {
+        BybitSpotTicker::try_new(node)
+    }
+}
+
+impl TryFrom<&BybitSpotTicker> for Node {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &BybitSpotTicker) -> Result<Self> {
+        Ok(value.node().clone())
+    }
+}
+
+#[derive(Builder, Debug, Clone)]
+#[builder(on(String, into))]
+pub(crate) struct Params {
+    base_asset: String,
+    quote_asset: String,
+    start_datetime: DateTime<Utc>,
+    end_datetime: DateTime<Utc>,
+}
+
+impl TryFrom<&Node> for Params {
+    type Error = BybitSpotTickerError;
+
+    fn try_from(node: &Node) -> Result<Self, Self::Error> {
+        if node.properties.prop_type != "data.BybitSpotTicker" {
+            return Err(BybitSpotTickerError::PropertyTypeMismatch);
+        }
+
+        let [base_asset, quote_asset, start_datetime, end_datetime] =
+            node.properties.params.as_slice()
+        else {
+            return Err(BybitSpotTickerError::ParamsFormatError);
+        };
+
+        let base_asset = base_asset
+            .as_str()
+            .ok_or(BybitSpotTickerError::BaseAssetError)?;
+
+        let quote_asset = quote_asset
+            .as_str()
+            .ok_or(BybitSpotTickerError::QuoteAssetError)?;
+
+        let start_datetime = start_datetime
+            .as_str()
+            .and_then(convert_to_datetime)
+            .ok_or(BybitSpotTickerError::StartDatetimeError)?;
+
+        let end_datetime = end_datetime
+            .as_str()
+            .and_then(convert_to_datetime)
+            .ok_or(BybitSpotTickerError::EndDatetimeError)?;
+
+        let params = Params::builder()
+            .base_asset(base_asset)
+            .quote_asset(quote_asset)
+            .start_datetime(start_datetime)
+            .end_datetime(end_datetime)
+            .build();
+
+        Ok(params)
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum BybitSpotTickerError {
+    #[error("Invalid property type, expected 'data.BybitSpotTicker'")]
+    PropertyTypeMismatch,
+
+    #[error("Invalid parameters format")]
+    ParamsFormatError,
+
+    #[error("Invalid base asset")]
+    BaseAssetError,
+
+    #[error("Invalid quote asset")]
+    QuoteAssetError,
+
+    #[error("Invalid start datetime")]
+    StartDatetimeError,
+
+    #[error("Invalid end datetime")]
+    EndDatetimeError,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_from_node_to_bybit_spot_ticker() -> Result<()> {
+        let json_str = r#"{"id":1,"type":"数据/Bybit现货行情(回测)","pos":[199,74],"size":{"0":210,"1":310},"flags":{},"order":0,"mode":0,"inputs":[],"properties":{"type":"data.BybitSpotTicker","params":["BTC","USDT","2024-10-10 15:18:42","2024-10-10 16:18:42"]}}"#;
+
+        let node: Node = serde_json::from_str(json_str)?;
+        let bybit_spot_ticker = BybitSpotTicker::try_from(node)?;
+
+        assert_eq!(bybit_spot_ticker.params.base_asset, "BTC");
+        assert_eq!(bybit_spot_ticker.params.quote_asset, "USDT");
+        assert_eq!(
+            bybit_spot_ticker.params.start_datetime,
+            convert_to_datetime("2024-10-10 15:18:42").unwrap()
+        );
+        assert_eq!(
+            bybit_spot_ticker.params.end_datetime,
+            convert_to_datetime("2024-10-10 16:18:42").unwrap()
+        );
+
+        Ok(())
+    }
+}
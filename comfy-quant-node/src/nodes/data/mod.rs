@@ -1,6 +1,19 @@
+mod align_klines;
+mod backtest_multi_spot_ticker;
+mod backtest_spot_klines;
 mod backtest_spot_ticker;
+mod binance_announcement;
 mod binance_spot_ticker;
+mod bybit_spot_ticker;
+mod okx_spot_ticker;
+mod tick_to_kline;
 
+pub(crate) use align_klines::AlignKlines;
+pub(crate) use backtest_multi_spot_ticker::BacktestMultiSpotTicker;
+pub(crate) use backtest_spot_klines::BacktestSpotKlines;
 pub(crate) use backtest_spot_ticker::BacktestSpotTicker;
-#[allow(unused)]
+pub(crate) use binance_announcement::BinanceAnnouncement;
 pub(crate) use binance_spot_ticker::BinanceSpotTicker;
+pub(crate) use bybit_spot_ticker::BybitSpotTicker;
+pub(crate) use okx_spot_ticker::OkxSpotTicker;
+pub(crate) use tick_to_kline::TickToKline;
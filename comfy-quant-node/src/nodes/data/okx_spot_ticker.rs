@@ -0,0 +1,243 @@
+use crate::{
+    node_core::{
+        NodeCategory, NodeCore, NodeCoreExt, NodeError, NodeExecutable, NodeInfra, NodeMeta,
+        NodeMetadata, Slot, Tick, SPOT_PAIR_INFO, TICK_STREAM,
+    },
+    node_io::{SpotPairInfo, TickStream},
+    workflow::Node,
+};
+use anyhow::Result;
+use bon::Builder;
+use comfy_quant_base::{Exchange, Market};
+use comfy_quant_database::spot_pairs::{self, CreateSpotPairParams};
+use comfy_quant_exchange::market_data::{MarketDataConnector, OkxConnector};
+use futures::StreamExt;
+use std::sync::Arc;
+
+/// OKX现货行情
+/// outputs:
+///      0: SpotPairInfo
+///      1: TickStream
+#[derive(Debug)]
+pub(crate) struct OkxSpotTicker {
+    params: Params,     // 参数
+    infra: NodeInfra,   // 节点基础设施
+    exchange: Exchange, // 交易所
+    market: Market,     // 市场
+}
+
+impl NodeMeta for OkxSpotTicker {
+    const METADATA: NodeMetadata = NodeMetadata {
+        prop_type: "data.OkxSpotTicker",
+        display_name: "OKX现货行情",
+        category: NodeCategory::Data,
+        inputs: &[],
+        outputs: &[SPOT_PAIR_INFO, TICK_STREAM],
+        icon: "chart-line",
+    };
+}
+
+impl NodeCore for OkxSpotTicker {
+    fn node_infra(&self) -> &NodeInfra {
+        &self.infra
+    }
+
+    fn node_infra_mut(&mut self) -> &mut NodeInfra {
+        &mut self.infra
+    }
+}
+
+impl OkxSpotTicker {
+    pub(crate) fn try_new(node: Node) -> Result<Self> {
+        let params = Params::try_from(&node)?;
+        let infra = NodeInfra::new(node);
+
+        Ok(OkxSpotTicker {
+            params,
+            infra,
+            exchange: Exchange::Okx,
+            market: Market::Spot,
+        })
+    }
+
+    // 从交易所刷新交易对缓存，失败时沿用已缓存的交易对信息
+    async fn refresh_spot_pair(&self) -> Result<()> {
+        let ctx = self.node_context()?;
+        let symbol_info = OkxConnector::default()
+            .fetch_exchange_info(&self.params.base_asset, &self.params.quote_asset)
+            .await?;
+
+        spot_pairs::create_or_update(
+            ctx.db(),
+            CreateSpotPairParams::from_symbol_info(Exchange::Okx, &symbol_info),
+        )
+        .await?;
+        ctx.resource_meter().add_db_rows_written(1);
+
+        Ok(())
+    }
+
+    // 每根1秒K线收盘后推送一个tick，与回测时由1秒K线生成的tick一致
+    async fn feed_ticks(&self) -> Result<()> {
+        let tick_stream = self.port().output::<TickStream>(1)?;
+        let symbol = self
+            .exchange
+            .symbol(&self.params.base_asset, &self.params.quote_asset);
+
+        let mut ticks = OkxConnector::default().stream_ticks(&symbol);
+        let price_store = self.workflow_context()?.cloned_price_store();
+        let heartbeat = self.heartbeat();
+
+        while let Some(market_tick) = ticks.next().await {
+            let market_tick = market_tick?;
+            let _busy = heartbeat.busy();
+
+            let tick = Tick::builder()
+                .timestamp(market_tick.timestamp)
+                .symbol(market_tick.symbol)
+                .price(market_tick.price)
+                .volume(market_tick.volume)
+                .taker_buy_volume(market_tick.taker_buy_volume)
+                .maybe_millis(market_tick.millis)
+                .build();
+
+            {
+                let mut price_store = price_store.write().await;
+                price_store.save_price(&self.exchange, &self.market, &tick.clone().into())?;
+                price_store.save_volume(&self.exchange, &self.market, &symbol, tick.volume)?;
+                price_store.save_timestamp(tick.timestamp);
+            }
+
+            // 按该tick的价格撮合模拟盘账户的挂单，撮合失败不影响行情推送
+            if let Err(e) = self.workflow_context()?.match_backtest_orders().await {
+                tracing::error!("Match paper orders failed: {}", e);
+            }
+
+            tick_stream
+                .send(&self.exchange, &self.market, &tick)
+                .await?;
+        }
+
+        anyhow::bail!("OKX spot tick stream closed: {}", symbol)
+    }
+}
+
+impl NodeExecutable for OkxSpotTicker {
+    async fn setup(&mut self) -> Result<()> {
+        if let Err(e) = self.refresh_spot_pair().await {
+            tracing::warn!("Refresh spot pair failed: {}", e);
+        }
+
+        let pair_info = self
+            .node_infra()
+            .spot_pair_info(
+                &self.exchange,
+                &self.params.base_asset,
+                &self.params.quote_asset,
+            )
+            .await?;
+
+        let pair_info_slot = Arc::new(Slot::<SpotPairInfo>::new(pair_info));
+        // 开启录制时保存发送给下游的每个tick，用于事后回放
+        let mut tick_stream = TickStream::new();
+
+        if let Some(recorder) = self.workflow_context()?.tick_recorder(self.node().id) {
+            tick_stream = tick_stream.with_recorder(recorder);
+        }
+
+        let tick_stream_slot = Arc::new(Slot::<TickStream>::new(tick_stream));
+
+        self.port_mut().set_output(0, pair_info_slot)?;
+        self.port_mut().set_output(1, tick_stream_slot)?;
+
+        Ok(())
+    }
+
+    async fn execute(&mut self) -> Result<(), NodeError> {
+        Ok(self.feed_ticks().await?)
+    }
+}
+
+impl TryFrom<Node> for OkxSpotTicker {
+    type Error = anyhow::Error;
+
+    fn try_from(node: Node) -> Result<Self> {
+        OkxSpotTicker::try_new(node)
+    }
+}
+
+impl TryFrom<&OkxSpotTicker> for Node {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &OkxSpotTicker) -> Result<Self> {
+        Ok(value.node().clone())
+    }
+}
+
+#[derive(Builder, Debug, Clone)]
+#[builder(on(String, into))]
+pub(crate) struct Params {
+    base_asset: String,
+    quote_asset: String,
+}
+
+impl TryFrom<&Node> for Params {
+    type Error = OkxSpotTickerError;
+
+    fn try_from(node: &Node) -> Result<Self, Self::Error> {
+        if node.properties.prop_type != "data.OkxSpotTicker" {
+            return Err(OkxSpotTickerError::PropertyTypeMismatch);
+        }
+
+        let [base_asset, quote_asset] = node.properties.params.as_slice() else {
+            return Err(OkxSpotTickerError::ParamsFormatError);
+        };
+
+        let base_asset = base_asset
+            .as_str()
+            .ok_or(OkxSpotTickerError::BaseAssetError)?;
+
+        let quote_asset = quote_asset
+            .as_str()
+            .ok_or(OkxSpotTickerError::QuoteAssetError)?;
+
+        let params = Params::builder()
+            .base_asset(base_asset)
+            .quote_asset(quote_asset)
+            .build();
+
+        Ok(params)
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum OkxSpotTickerError {
+    #[error("Invalid property type, expected 'data.OkxSpotTicker'")]
+    PropertyTypeMismatch,
+
+    #[error("Invalid parameters format")]
+    ParamsFormatError,
+
+    #[error("Invalid base asset")]
+    BaseAssetError,
+
+    #[error("Invalid quote asset")]
+    QuoteAssetError,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_from_node_to_okx_spot_ticker() -> Result<()> {
+        let json_str = r#"{"id":1,"type":"数据/OKX现货行情","pos":[199,74],"size":{"0":210,"1":310},"flags":{},"order":0,"mode":0,"inputs":[],"properties":{"type":"data.OkxSpotTicker","params":["BTC","USDT"]}}"#;
+
+        let node: Node = serde_json::from_str(json_str)?;
+        let okx_spot_ticker = OkxSpotTicker::try_from(node)?;
+
+        assert_eq!(okx_spot_ticker.params.base_asset, "BTC");
+        assert_eq!(okx_spot_ticker.params.quote_asset, "USDT");
+        Ok(())
+    }
+}
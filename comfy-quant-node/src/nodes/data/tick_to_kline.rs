@@ -0,0 +1,439 @@
+use crate::{
+    node_core::{
+        Bar, NodeCategory, NodeCore, NodeCoreExt, NodeError, NodeExecutable, NodeInfra, NodeMeta,
+        NodeMetadata, Slot, Tick, KLINE_STREAM, TICK_STREAM,
+    },
+    node_io::{KlineStream, TickStream},
+    workflow::Node,
+};
+use anyhow::Result;
+use bon::Builder;
+use comfy_quant_base::{
+    calc_interval_start_millis, Exchange, IntervalUnit, KlineInterval, Market, Symbol,
+};
+use std::{collections::HashMap, sync::Arc};
+
+/// Tick聚合K线
+/// inputs:
+///      0: TickStream
+/// outputs:
+///      0: KlineStream
+#[derive(Debug)]
+pub(crate) struct TickToKline {
+    params: Params,   // 参数
+    infra: NodeInfra, // 节点基础设施
+}
+
+impl NodeMeta for TickToKline {
+    const METADATA: NodeMetadata = NodeMetadata {
+        prop_type: "data.TickToKline",
+        display_name: "Tick聚合K线",
+        category: NodeCategory::Data,
+        inputs: &[TICK_STREAM],
+        outputs: &[KLINE_STREAM],
+        icon: "merge",
+    };
+}
+
+impl NodeCore for TickToKline {
+    fn node_infra(&self) -> &NodeInfra {
+        &self.infra
+    }
+
+    fn node_infra_mut(&mut self) -> &mut NodeInfra {
+        &mut self.infra
+    }
+}
+
+impl TickToKline {
+    pub(crate) fn try_new(node: Node) -> Result<Self> {
+        let params = Params::try_from(&node)?;
+        let infra = NodeInfra::new(node);
+
+        Ok(TickToKline { params, infra })
+    }
+
+    async fn aggregate(&self) -> Result<()> {
+        let tick_stream = self.port().input::<TickStream>(0)?;
+        let kline_stream = self.port().output::<KlineStream>(0)?;
+        let rx = tick_stream.subscribe();
+        let mut aggregator = KlineAggregator::new(self.params.interval.clone());
+        let heartbeat = self.heartbeat();
+
+        while let Some((exchange, market, tick)) = tick_stream.next(&rx).await {
+            let _busy = heartbeat.busy();
+
+            if let Some(bar) = aggregator.push(&exchange, &market, &tick)? {
+                kline_stream.send(&exchange, &market, &bar).await?;
+            }
+        }
+
+        // 数据结束，输出最后一根K线
+        for (exchange, market, bar) in aggregator.flush() {
+            kline_stream.send(&exchange, &market, &bar).await?;
+        }
+
+        Ok(())
+    }
+}
+
+impl NodeExecutable for TickToKline {
+    async fn setup(&mut self) -> Result<()> {
+        let kline_stream_slot = Arc::new(Slot::<KlineStream>::new(KlineStream::new()));
+        self.port_mut().set_output(0, kline_stream_slot)?;
+
+        Ok(())
+    }
+
+    async fn execute(&mut self) -> Result<(), NodeError> {
+        let result = self.aggregate().await;
+
+        // 聚合结束，通知下游节点
+        self.port().output::<KlineStream>(0)?.finish();
+
+        Ok(result?)
+    }
+}
+
+impl TryFrom<Node> for TickToKline {
+    type Error = anyhow::Error;
+
+    fn try_from(node: Node) -> Result<Self> {
+        TickToKline::try_new(node)
+    }
+}
+
+impl TryFrom<&TickToKline> for Node {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &TickToKline) -> Result<Self> {
+        Ok(value.node().clone())
+    }
+}
+
+type BarKey = (Exchange, Market, Symbol);
+
+// 按交易对将tick聚合为K线，tick进入下一周期时输出上一根K线；
+// 按成交笔数聚合时，成交笔数达到后立即输出
+#[derive(Debug)]
+struct KlineAggregator {
+    interval: KlineInterval,           // K线周期
+    bars: HashMap<BarKey, (Bar, u32)>, // 每个交易对尚未收盘的K线及其成交笔数
+    order: Vec<BarKey>,                // 交易对首次出现的顺序，保证输出稳定
+}
+
+impl KlineAggregator {
+    fn new(interval: KlineInterval) -> Self {
+        KlineAggregator {
+            interval,
+            bars: HashMap::new(),
+            order: Vec::new(),
+        }
+    }
+
+    // 推入一个tick，返回已收盘的K线
+    fn push(&mut self, exchange: &Exchange, market: &Market, tick: &Tick) -> Result<Option<Bar>> {
+        let key = (exchange.clone(), market.clone(), tick.symbol.clone());
+
+        if let Some(tick_count) = self.interval.tick_count() {
+            return Ok(self.push_by_count(key, tick, tick_count));
+        }
+
+        let open_millis = interval_start(tick.timestamp_millis(), &self.interval)?;
+
+        let Some((bar, _)) = self.bars.get_mut(&key) else {
+            if !self.order.contains(&key) {
+                self.order.push(key.clone());
+            }
+            let bar = new_bar(&self.interval, open_millis, tick);
+            self.bars.insert(key, (bar, 1));
+            return Ok(None);
+        };
+
+        // 上游已丢弃乱序tick，这里只是防御
+        if open_millis < bar.open_millis() {
+            return Ok(None);
+        }
+
+        if open_millis == bar.open_millis() {
+            update_bar(bar, tick);
+            return Ok(None);
+        }
+
+        let bar = std::mem::replace(bar, new_bar(&self.interval, open_millis, tick));
+
+        Ok(Some(bar))
+    }
+
+    // 按成交笔数聚合，以第一笔成交的时间作为开盘时间
+    fn push_by_count(&mut self, key: BarKey, tick: &Tick, tick_count: u32) -> Option<Bar> {
+        let Some((bar, count)) = self.bars.get_mut(&key) else {
+            if !self.order.contains(&key) {
+                self.order.push(key.clone());
+            }
+            let bar = new_bar(&self.interval, tick.timestamp_millis(), tick);
+            self.bars.insert(key.clone(), (bar, 1));
+            return self.close_full(&key, tick_count);
+        };
+
+        update_bar(bar, tick);
+        *count += 1;
+
+        self.close_full(&key, tick_count)
+    }
+
+    // 成交笔数达到后取出K线，下一笔成交开始新K线
+    fn close_full(&mut self, key: &BarKey, tick_count: u32) -> Option<Bar> {
+        match self.bars.get(key) {
+            Some((_, count)) if *count >= tick_count => self.bars.remove(key).map(|(bar, _)| bar),
+            _ => None,
+        }
+    }
+
+    // 取出所有尚未收盘的K线
+    fn flush(&mut self) -> Vec<(Exchange, Market, Bar)> {
+        self.order
+            .drain(..)
+            .filter_map(|key| {
+                let (bar, _) = self.bars.remove(&key)?;
+                let (exchange, market, _) = key;
+                Some((exchange, market, bar))
+            })
+            .collect()
+    }
+}
+
+// 以tick开始一根新K线
+fn new_bar(interval: &KlineInterval, open_millis: i64, tick: &Tick) -> Bar {
+    let tick_based = interval.tick_count().is_some();
+
+    Bar::builder()
+        .symbol(tick.symbol.clone())
+        .interval(interval.clone())
+        .open_time(open_millis.div_euclid(1000))
+        .maybe_open_time_millis((interval.to_seconds() == 0).then_some(open_millis))
+        .maybe_close_time_millis(tick_based.then(|| tick.timestamp_millis()))
+        .open(tick.price)
+        .high(tick.price)
+        .low(tick.price)
+        .close(tick.price)
+        .volume(tick.volume)
+        .taker_buy_volume(tick.taker_buy_volume)
+        .build()
+}
+
+// tick计入未收盘的K线
+fn update_bar(bar: &mut Bar, tick: &Tick) {
+    bar.high = bar.high.max(tick.price);
+    bar.low = bar.low.min(tick.price);
+    bar.close = tick.price;
+    bar.volume += tick.volume;
+    bar.taker_buy_volume += tick.taker_buy_volume;
+
+    if bar.close_time_millis.is_some() {
+        bar.close_time_millis = Some(tick.timestamp_millis());
+    }
+}
+
+// 计算tick所在K线的开盘时间(毫秒)
+fn interval_start(millis: i64, interval: &KlineInterval) -> Result<i64> {
+    let unit = interval.as_ref().parse::<IntervalUnit>()?;
+    let count = interval
+        .as_ref()
+        .trim_end_matches(char::is_alphabetic)
+        .parse::<u32>()?;
+
+    calc_interval_start_millis(millis, unit, count)
+}
+
+#[derive(Builder, Debug, Clone)]
+pub(crate) struct Params {
+    interval: KlineInterval, // 聚合后的K线周期
+}
+
+impl TryFrom<&Node> for Params {
+    type Error = TickToKlineError;
+
+    fn try_from(node: &Node) -> Result<Self, Self::Error> {
+        if node.properties.prop_type != "data.TickToKline" {
+            return Err(TickToKlineError::PropertyTypeMismatch);
+        }
+
+        let [interval] = node.properties.params.as_slice() else {
+            return Err(TickToKlineError::ParamsFormatError);
+        };
+
+        // 未知的时间间隔会被解析为1s，这里严格校验
+        let interval = interval
+            .as_str()
+            .filter(|interval| KlineInterval::from(*interval).as_ref() == *interval)
+            .map(KlineInterval::from)
+            .ok_or(TickToKlineError::IntervalError)?;
+
+        let params = Params::builder().interval(interval).build();
+
+        Ok(params)
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum TickToKlineError {
+    #[error("Invalid property type, expected 'data.TickToKline'")]
+    PropertyTypeMismatch,
+
+    #[error("Invalid parameters format")]
+    ParamsFormatError,
+
+    #[error("Invalid kline interval")]
+    IntervalError,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_try_from_node_to_tick_to_kline() -> Result<()> {
+        let json_str = r#"{"id":2,"type":"数据/Tick聚合K线","pos":[199,74],"size":{"0":210,"1":310},"flags":{},"order":0,"mode":0,"inputs":[{"name":"Tick数据流","type":"TickStream","link":1}],"properties":{"type":"data.TickToKline","params":["5m"]}}"#;
+
+        let node: Node = serde_json::from_str(json_str)?;
+        let tick_to_kline = TickToKline::try_from(node)?;
+
+        assert_eq!(tick_to_kline.params.interval, KlineInterval::FiveMinutes);
+
+        let json_str = r#"{"id":2,"type":"数据/Tick聚合K线","pos":[199,74],"size":{"0":210,"1":310},"flags":{},"order":0,"mode":0,"inputs":[{"name":"Tick数据流","type":"TickStream","link":1}],"properties":{"type":"data.TickToKline","params":["7m"]}}"#;
+        let node: Node = serde_json::from_str(json_str)?;
+        assert!(TickToKline::try_from(node).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_kline_aggregator() -> Result<()> {
+        let exchange = Exchange::Binance;
+        let market = Market::Spot;
+        let tick = |timestamp: i64, price: Decimal| {
+            Tick::builder()
+                .timestamp(timestamp)
+                .symbol("BTCUSDT".into())
+                .price(price)
+                .volume(dec!(1))
+                .build()
+        };
+        let mut aggregator = KlineAggregator::new(KlineInterval::OneMinute);
+
+        // 2024-01-01 00:00:00
+        let start = 1704067200;
+        assert_eq!(
+            aggregator.push(&exchange, &market, &tick(start, dec!(100)))?,
+            None
+        );
+        assert_eq!(
+            aggregator.push(&exchange, &market, &tick(start + 20, dec!(110)))?,
+            None
+        );
+        assert_eq!(
+            aggregator.push(&exchange, &market, &tick(start + 40, dec!(90)))?,
+            None
+        );
+        assert_eq!(
+            aggregator.push(&exchange, &market, &tick(start + 59, dec!(105)))?,
+            None
+        );
+
+        // 进入下一分钟，输出上一根K线
+        let bar = aggregator
+            .push(&exchange, &market, &tick(start + 61, dec!(106)))?
+            .ok_or_else(|| anyhow::anyhow!("bar not closed"))?;
+        assert_eq!(bar.open_time, start);
+        assert_eq!(bar.close_time(), start + 60);
+        assert_eq!(
+            (bar.open, bar.high, bar.low, bar.close, bar.volume),
+            (dec!(100), dec!(110), dec!(90), dec!(105), dec!(4))
+        );
+
+        let bars = aggregator.flush();
+        assert_eq!(bars.len(), 1);
+        assert_eq!(bars[0].2.open_time, start + 60);
+        assert_eq!(bars[0].2.close, dec!(106));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_kline_aggregator_sub_second() -> Result<()> {
+        let exchange = Exchange::Binance;
+        let market = Market::Spot;
+        // 2024-01-01 00:00:00
+        let start = 1704067200000;
+        let tick = |millis: i64, price: Decimal| {
+            Tick::builder()
+                .timestamp(millis / 1000)
+                .millis(millis)
+                .symbol("BTCUSDT".into())
+                .price(price)
+                .volume(dec!(1))
+                .build()
+        };
+        let mut aggregator = KlineAggregator::new(KlineInterval::TwoHundredFiftyMilliseconds);
+
+        assert_eq!(
+            aggregator.push(&exchange, &market, &tick(start + 10, dec!(100)))?,
+            None
+        );
+        assert_eq!(
+            aggregator.push(&exchange, &market, &tick(start + 200, dec!(101)))?,
+            None
+        );
+
+        let bar = aggregator
+            .push(&exchange, &market, &tick(start + 260, dec!(102)))?
+            .ok_or_else(|| anyhow::anyhow!("bar not closed"))?;
+        assert_eq!(bar.open_millis(), start);
+        assert_eq!(bar.close_millis(), start + 250);
+        assert_eq!(bar.close, dec!(101));
+
+        let bars = aggregator.flush();
+        assert_eq!(bars[0].2.open_millis(), start + 250);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_kline_aggregator_by_tick_count() -> Result<()> {
+        let exchange = Exchange::Binance;
+        let market = Market::Spot;
+        let tick = |millis: i64, price: Decimal| {
+            Tick::builder()
+                .timestamp(millis / 1000)
+                .millis(millis)
+                .symbol("BTCUSDT".into())
+                .price(price)
+                .volume(dec!(1))
+                .build()
+        };
+        let mut aggregator = KlineAggregator::new(KlineInterval::HundredTicks);
+
+        for i in 0..99 {
+            assert_eq!(
+                aggregator.push(&exchange, &market, &tick(1000 + i * 7, dec!(100)))?,
+                None
+            );
+        }
+
+        // 第100笔成交时立即收盘
+        let bar = aggregator
+            .push(&exchange, &market, &tick(5000, dec!(105)))?
+            .ok_or_else(|| anyhow::anyhow!("bar not closed"))?;
+        assert_eq!(bar.open_millis(), 1000);
+        assert_eq!(bar.close_millis(), 5000);
+        assert_eq!(bar.volume, dec!(100));
+        assert_eq!(bar.high, dec!(105));
+
+        assert!(aggregator.flush().is_empty());
+
+        Ok(())
+    }
+}
@@ -1,4 +1,9 @@
 pub(crate) mod client;
 pub(crate) mod data;
 pub(crate) mod node_kind;
+pub(crate) mod notify;
+pub(crate) mod risk;
 pub(crate) mod strategy;
+pub(crate) mod test;
+
+pub use node_kind::node_registry;
@@ -1,7 +1,19 @@
-use super::client::BacktestSpotClient;
+use super::client::{BacktestSpotClient, MultiSpotClient, PaperSpotClient};
 use crate::{
-    node_core::{NodeCore, NodeExecutable, NodeInfra, TradeStats},
-    nodes::{data::BacktestSpotTicker, strategy::SpotGrid},
+    node_core::{
+        NodeCore, NodeExecutable, NodeInfra, NodeMeta, NodeMetadata, NodeSpotStats, TradeStats,
+    },
+    nodes::{
+        data::{
+            AlignKlines, BacktestMultiSpotTicker, BacktestSpotKlines, BacktestSpotTicker,
+            BinanceAnnouncement, BinanceSpotTicker, BybitSpotTicker, OkxSpotTicker, TickToKline,
+        },
+        notify::{Telegram, Webhook},
+        risk::RiskGuard,
+        strategy::{SpotGrid, StrategyAllocator, TriangularArb},
+        test::Assert,
+    },
+    stats::{ExecutionReport, TradeRecord},
     workflow::Node,
 };
 use anyhow::Result;
@@ -13,28 +25,177 @@ use std::fmt;
 pub(crate) enum NodeKind {
     // data
     BacktestSpotTicker(BacktestSpotTicker),
+    BacktestMultiSpotTicker(BacktestMultiSpotTicker),
+    BacktestSpotKlines(BacktestSpotKlines),
+    BinanceSpotTicker(BinanceSpotTicker),
+    OkxSpotTicker(OkxSpotTicker),
+    BybitSpotTicker(BybitSpotTicker),
+    BinanceAnnouncement(BinanceAnnouncement),
+    TickToKline(TickToKline),
+    AlignKlines(AlignKlines),
 
     // client
     BacktestSpotClient(BacktestSpotClient),
+    PaperSpotClient(PaperSpotClient),
+    MultiSpotClient(MultiSpotClient),
+
+    // risk
+    RiskGuard(RiskGuard),
 
     // strategy
     SpotGrid(SpotGrid),
+    StrategyAllocator(StrategyAllocator),
+    TriangularArb(TriangularArb),
+
+    // notify
+    Webhook(Webhook),
+    Telegram(Telegram),
+
+    // test
+    Assert(Assert),
 }
 
 impl NodeKind {
     fn struct_name(&self) -> &str {
         match self {
             NodeKind::BacktestSpotTicker(_) => "BacktestSpotTicker",
+            NodeKind::BacktestMultiSpotTicker(_) => "BacktestMultiSpotTicker",
+            NodeKind::BacktestSpotKlines(_) => "BacktestSpotKlines",
+            NodeKind::BinanceSpotTicker(_) => "BinanceSpotTicker",
+            NodeKind::OkxSpotTicker(_) => "OkxSpotTicker",
+            NodeKind::BybitSpotTicker(_) => "BybitSpotTicker",
+            NodeKind::BinanceAnnouncement(_) => "BinanceAnnouncement",
+            NodeKind::TickToKline(_) => "TickToKline",
+            NodeKind::AlignKlines(_) => "AlignKlines",
             NodeKind::BacktestSpotClient(_) => "BacktestSpotClient",
+            NodeKind::PaperSpotClient(_) => "PaperSpotClient",
+            NodeKind::MultiSpotClient(_) => "MultiSpotClient",
+            NodeKind::RiskGuard(_) => "RiskGuard",
             NodeKind::SpotGrid(_) => "SpotGrid",
+            NodeKind::StrategyAllocator(_) => "StrategyAllocator",
+            NodeKind::TriangularArb(_) => "TriangularArb",
+            NodeKind::Webhook(_) => "Webhook",
+            NodeKind::Telegram(_) => "Telegram",
+            NodeKind::Assert(_) => "Assert",
+        }
+    }
+
+    // 节点元数据
+    pub(crate) fn metadata(&self) -> NodeMetadata {
+        match self {
+            NodeKind::BacktestSpotTicker(_) => BacktestSpotTicker::METADATA,
+            NodeKind::BacktestMultiSpotTicker(_) => BacktestMultiSpotTicker::METADATA,
+            NodeKind::BacktestSpotKlines(_) => BacktestSpotKlines::METADATA,
+            NodeKind::BinanceSpotTicker(_) => BinanceSpotTicker::METADATA,
+            NodeKind::OkxSpotTicker(_) => OkxSpotTicker::METADATA,
+            NodeKind::BybitSpotTicker(_) => BybitSpotTicker::METADATA,
+            NodeKind::BinanceAnnouncement(_) => BinanceAnnouncement::METADATA,
+            NodeKind::TickToKline(_) => TickToKline::METADATA,
+            NodeKind::AlignKlines(_) => AlignKlines::METADATA,
+            NodeKind::BacktestSpotClient(_) => BacktestSpotClient::METADATA,
+            NodeKind::PaperSpotClient(_) => PaperSpotClient::METADATA,
+            NodeKind::MultiSpotClient(_) => MultiSpotClient::METADATA,
+            NodeKind::RiskGuard(_) => RiskGuard::METADATA,
+            NodeKind::SpotGrid(_) => SpotGrid::METADATA,
+            NodeKind::StrategyAllocator(_) => StrategyAllocator::METADATA,
+            NodeKind::TriangularArb(_) => TriangularArb::METADATA,
+            NodeKind::Webhook(_) => Webhook::METADATA,
+            NodeKind::Telegram(_) => Telegram::METADATA,
+            NodeKind::Assert(_) => Assert::METADATA,
+        }
+    }
+
+    // 必须连接的输入槽位，元数据中没有标记为可选的输入；多账户节点按权重个数确定
+    pub(crate) fn required_inputs(&self) -> Vec<usize> {
+        match self {
+            NodeKind::MultiSpotClient(client) => (0..client.accounts()).collect(),
+            _ => self
+                .metadata()
+                .inputs
+                .iter()
+                .enumerate()
+                .filter(|(_, port)| !port.optional)
+                .map(|(slot, _)| slot)
+                .collect(),
+        }
+    }
+
+    // 策略节点的执行质量报告
+    pub(crate) fn execution_reports(&self) -> Vec<ExecutionReport> {
+        match self {
+            NodeKind::SpotGrid(spot_grid) => spot_grid.spot_stats().execution_reports(),
+            NodeKind::TriangularArb(arb) => arb.spot_stats().execution_reports(),
+            _ => vec![],
+        }
+    }
+
+    // 策略节点的总交易次数
+    pub(crate) fn total_trades(&self) -> u64 {
+        match self {
+            NodeKind::SpotGrid(spot_grid) => spot_grid.spot_stats().total_trades(),
+            NodeKind::TriangularArb(arb) => arb.spot_stats().total_trades(),
+            _ => 0,
+        }
+    }
+
+    // 策略节点的卖出交易次数
+    pub(crate) fn sell_trades(&self) -> u64 {
+        match self {
+            NodeKind::SpotGrid(spot_grid) => spot_grid.spot_stats().sell_trades(),
+            NodeKind::TriangularArb(arb) => arb.spot_stats().sell_trades(),
+            _ => 0,
+        }
+    }
+
+    // 策略节点的盈利交易次数
+    pub(crate) fn win_trades(&self) -> u64 {
+        match self {
+            NodeKind::SpotGrid(spot_grid) => spot_grid.spot_stats().win_trades(),
+            NodeKind::TriangularArb(arb) => arb.spot_stats().win_trades(),
+            _ => 0,
+        }
+    }
+
+    // 策略节点的最大回撤比例
+    pub(crate) fn max_drawdown(&self) -> Decimal {
+        match self {
+            NodeKind::SpotGrid(spot_grid) => spot_grid.spot_stats().max_drawdown(),
+            NodeKind::TriangularArb(arb) => arb.spot_stats().max_drawdown(),
+            _ => Decimal::ZERO,
         }
     }
 }
 
+// 工作流支持的所有节点，供前端节点面板使用；新增节点时需同时加入 NodeKind
+pub fn node_registry() -> Vec<NodeMetadata> {
+    vec![
+        BacktestSpotTicker::METADATA,
+        BacktestMultiSpotTicker::METADATA,
+        BacktestSpotKlines::METADATA,
+        BinanceSpotTicker::METADATA,
+        OkxSpotTicker::METADATA,
+        BybitSpotTicker::METADATA,
+        BinanceAnnouncement::METADATA,
+        TickToKline::METADATA,
+        AlignKlines::METADATA,
+        BacktestSpotClient::METADATA,
+        PaperSpotClient::METADATA,
+        MultiSpotClient::METADATA,
+        RiskGuard::METADATA,
+        SpotGrid::METADATA,
+        StrategyAllocator::METADATA,
+        TriangularArb::METADATA,
+        Webhook::METADATA,
+        Telegram::METADATA,
+        Assert::METADATA,
+    ]
+}
+
 impl TradeStats for NodeKind {
     async fn initial_capital(&self) -> Result<Decimal> {
         match self {
             NodeKind::SpotGrid(spot_grid) => spot_grid.initial_capital().await,
+            NodeKind::TriangularArb(arb) => arb.initial_capital().await,
             _ => Ok(Decimal::ZERO),
         }
     }
@@ -42,6 +203,7 @@ impl TradeStats for NodeKind {
     async fn realized_pnl(&self) -> Result<Decimal> {
         match self {
             NodeKind::SpotGrid(spot_grid) => spot_grid.realized_pnl().await,
+            NodeKind::TriangularArb(arb) => arb.realized_pnl().await,
             _ => Ok(Decimal::ZERO),
         }
     }
@@ -49,6 +211,7 @@ impl TradeStats for NodeKind {
     async fn unrealized_pnl(&self) -> Result<Decimal> {
         match self {
             NodeKind::SpotGrid(spot_grid) => spot_grid.unrealized_pnl().await,
+            NodeKind::TriangularArb(arb) => arb.unrealized_pnl().await,
             _ => Ok(Decimal::ZERO),
         }
     }
@@ -56,9 +219,42 @@ impl TradeStats for NodeKind {
     async fn running_time(&self) -> Result<u128> {
         match self {
             NodeKind::SpotGrid(spot_grid) => spot_grid.running_time().await,
+            NodeKind::TriangularArb(arb) => arb.running_time().await,
             _ => Ok(0),
         }
     }
+
+    async fn time_weighted_return(&self) -> Result<Decimal> {
+        match self {
+            NodeKind::SpotGrid(spot_grid) => spot_grid.time_weighted_return().await,
+            NodeKind::TriangularArb(arb) => arb.time_weighted_return().await,
+            _ => Ok(Decimal::ZERO),
+        }
+    }
+
+    async fn money_weighted_return(&self) -> Result<Decimal> {
+        match self {
+            NodeKind::SpotGrid(spot_grid) => spot_grid.money_weighted_return().await,
+            NodeKind::TriangularArb(arb) => arb.money_weighted_return().await,
+            _ => Ok(Decimal::ZERO),
+        }
+    }
+
+    async fn equity_curve(&self) -> Result<Vec<(i64, Decimal)>> {
+        match self {
+            NodeKind::SpotGrid(spot_grid) => spot_grid.equity_curve().await,
+            NodeKind::TriangularArb(arb) => arb.equity_curve().await,
+            _ => Ok(vec![]),
+        }
+    }
+
+    async fn trade_record(&self) -> Result<TradeRecord> {
+        match self {
+            NodeKind::SpotGrid(spot_grid) => spot_grid.trade_record().await,
+            NodeKind::TriangularArb(arb) => arb.trade_record().await,
+            _ => Ok(TradeRecord::default()),
+        }
+    }
 }
 
 impl fmt::Debug for NodeKind {
@@ -73,8 +269,24 @@ impl TryFrom<Node> for NodeKind {
     fn try_from(node: Node) -> Result<Self> {
         let node_kind = match node.properties.prop_type.as_str() {
             "data.BacktestSpotTicker" => BacktestSpotTicker::try_from(node)?.into(),
+            "data.BacktestMultiSpotTicker" => BacktestMultiSpotTicker::try_from(node)?.into(),
+            "data.BacktestSpotKlines" => BacktestSpotKlines::try_from(node)?.into(),
+            "data.BinanceSpotTicker" => BinanceSpotTicker::try_from(node)?.into(),
+            "data.OkxSpotTicker" => OkxSpotTicker::try_from(node)?.into(),
+            "data.BybitSpotTicker" => BybitSpotTicker::try_from(node)?.into(),
+            "data.BinanceAnnouncement" => BinanceAnnouncement::try_from(node)?.into(),
+            "data.TickToKline" => TickToKline::try_from(node)?.into(),
+            "data.AlignKlines" => AlignKlines::try_from(node)?.into(),
             "client.BacktestSpotClient" => BacktestSpotClient::try_from(node)?.into(),
+            "client.PaperSpotClient" => PaperSpotClient::try_from(node)?.into(),
+            "client.MultiSpotClient" => MultiSpotClient::try_from(node)?.into(),
+            "risk.RiskGuard" => RiskGuard::try_from(node)?.into(),
             "strategy.SpotGrid" => SpotGrid::try_from(node)?.into(),
+            "strategy.StrategyAllocator" => StrategyAllocator::try_from(node)?.into(),
+            "strategy.TriangularArb" => TriangularArb::try_from(node)?.into(),
+            "notify.Webhook" => Webhook::try_from(node)?.into(),
+            "notify.Telegram" => Telegram::try_from(node)?.into(),
+            "test.Assert" => Assert::try_from(node)?.into(),
             prop_type => anyhow::bail!("Invalid node type: {}", prop_type),
         };
 
@@ -88,8 +300,24 @@ impl TryFrom<&NodeKind> for Node {
     fn try_from(node_kind: &NodeKind) -> Result<Self> {
         match node_kind {
             NodeKind::BacktestSpotTicker(node) => node.try_into(),
+            NodeKind::BacktestMultiSpotTicker(node) => node.try_into(),
+            NodeKind::BacktestSpotKlines(node) => node.try_into(),
+            NodeKind::BinanceSpotTicker(node) => node.try_into(),
+            NodeKind::OkxSpotTicker(node) => node.try_into(),
+            NodeKind::BybitSpotTicker(node) => node.try_into(),
+            NodeKind::BinanceAnnouncement(node) => node.try_into(),
+            NodeKind::TickToKline(node) => node.try_into(),
+            NodeKind::AlignKlines(node) => node.try_into(),
             NodeKind::BacktestSpotClient(node) => node.try_into(),
+            NodeKind::PaperSpotClient(node) => node.try_into(),
+            NodeKind::MultiSpotClient(node) => node.try_into(),
+            NodeKind::RiskGuard(node) => node.try_into(),
             NodeKind::SpotGrid(node) => node.try_into(),
+            NodeKind::StrategyAllocator(node) => node.try_into(),
+            NodeKind::TriangularArb(node) => node.try_into(),
+            NodeKind::Webhook(node) => node.try_into(),
+            NodeKind::Telegram(node) => node.try_into(),
+            NodeKind::Assert(node) => node.try_into(),
         }
     }
 }
@@ -104,6 +332,8 @@ mod tests {
         let node: Node = serde_json::from_str(json_str)?;
         let node_kind = NodeKind::try_from(node)?;
 
+        assert_eq!(node_kind.metadata(), BacktestSpotTicker::METADATA);
+
         match node_kind {
             NodeKind::BacktestSpotTicker(_) => {}
             _ => assert!(false),
@@ -111,4 +341,43 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_node_registry() -> Result<()> {
+        let registry = node_registry();
+
+        for metadata in &registry {
+            // 注册的节点类型都能被解析，参数错误而不是类型不支持
+            let json_str = format!(
+                r#"{{"id":1,"type":"{}","pos":[0,0],"order":0,"mode":0,"properties":{{"type":"{}","params":[]}}}}"#,
+                metadata.display_name, metadata.prop_type
+            );
+            let node: Node = serde_json::from_str(&json_str)?;
+            let error = NodeKind::try_from(node)
+                .err()
+                .map(|e| e.to_string())
+                .unwrap_or_default();
+
+            assert!(!error.starts_with("Invalid node type"), "{}", error);
+        }
+
+        let mut prop_types = registry.iter().map(|m| m.prop_type).collect::<Vec<_>>();
+        prop_types.sort();
+        prop_types.dedup();
+        assert_eq!(prop_types.len(), registry.len());
+
+        assert_eq!(
+            serde_json::to_value(TickToKline::METADATA)?,
+            serde_json::json!({
+                "type": "data.TickToKline",
+                "display_name": "Tick聚合K线",
+                "category": "数据",
+                "inputs": [{"name": "Tick数据流", "type": "TickStream", "optional": false}],
+                "outputs": [{"name": "K线数据流", "type": "KlineStream", "optional": false}],
+                "icon": "merge"
+            })
+        );
+
+        Ok(())
+    }
 }
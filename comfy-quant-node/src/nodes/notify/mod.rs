@@ -0,0 +1,6 @@
+mod notifier;
+mod telegram;
+mod webhook;
+
+pub(crate) use telegram::Telegram;
+pub(crate) use webhook::Webhook;
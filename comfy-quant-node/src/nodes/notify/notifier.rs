@@ -0,0 +1,243 @@
+use crate::node_core::WorkflowEvent;
+use serde_json::Value;
+use std::{collections::HashSet, str::FromStr, time::Duration};
+use tokio::sync::broadcast::{self, error::RecvError};
+
+// 通知节点类型前缀，通知节点之间互不等待
+const NOTIFY_PREFIX: &str = "notify.";
+
+// 推送请求超时时间
+pub(crate) const SEND_TIMEOUT: Duration = Duration::from_secs(10);
+
+// 可订阅的告警事件
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum NotifyEvent {
+    OrderFilled,   // 订单成交
+    StopTriggered, // 止损、止盈触发
+    RiskTriggered, // 风控触发
+    Error,         // 节点运行出错或异常退出
+}
+
+impl NotifyEvent {
+    const ALL: [NotifyEvent; 4] = [
+        NotifyEvent::OrderFilled,
+        NotifyEvent::StopTriggered,
+        NotifyEvent::RiskTriggered,
+        NotifyEvent::Error,
+    ];
+
+    fn of(event: &WorkflowEvent) -> Option<Self> {
+        match event {
+            WorkflowEvent::OrderFilled { .. } => Some(NotifyEvent::OrderFilled),
+            WorkflowEvent::StopTriggered { .. } => Some(NotifyEvent::StopTriggered),
+            WorkflowEvent::RiskTriggered { .. } => Some(NotifyEvent::RiskTriggered),
+            WorkflowEvent::Error { .. } | WorkflowEvent::NodeFinished { error: Some(_), .. } => {
+                Some(NotifyEvent::Error)
+            }
+            _ => None,
+        }
+    }
+
+    // 未配置模板时使用的默认消息
+    fn default_template(event: &WorkflowEvent) -> &'static str {
+        match event {
+            WorkflowEvent::OrderFilled { .. } => {
+                "[{exchange}] {symbol} 订单成交: {side} {qty} @ {price}"
+            }
+            WorkflowEvent::StopTriggered { .. } => "[{exchange}] {symbol} 触发{kind} @ {price}",
+            WorkflowEvent::RiskTriggered { .. } => {
+                "[{exchange}] {symbol} 风控触发: {rule} {value} 超过 {limit}, {action}"
+            }
+            WorkflowEvent::NodeFinished { .. } => "节点 {node_id}({node_type}) 异常退出: {error}",
+            _ => "节点 {node_id}: {message}",
+        }
+    }
+
+    // 解析事件列表，空列表订阅全部事件
+    pub(crate) fn parse_list(value: &Value) -> Option<Vec<Self>> {
+        let events = value
+            .as_array()?
+            .iter()
+            .map(|event| event.as_str()?.parse().ok())
+            .collect::<Option<Vec<_>>>()?;
+
+        if events.is_empty() {
+            return Some(NotifyEvent::ALL.to_vec());
+        }
+
+        Some(events)
+    }
+}
+
+impl FromStr for NotifyEvent {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "order_filled" => Ok(NotifyEvent::OrderFilled),
+            "stop_triggered" => Ok(NotifyEvent::StopTriggered),
+            "risk_triggered" => Ok(NotifyEvent::RiskTriggered),
+            "error" => Ok(NotifyEvent::Error),
+            _ => anyhow::bail!("Invalid notify event: {}", s),
+        }
+    }
+}
+
+// 渲染消息，模板中的 {字段} 替换为事件的同名字段，未知字段原样保留
+pub(crate) fn render(template: Option<&str>, event: &WorkflowEvent) -> String {
+    let template = template.unwrap_or_else(|| NotifyEvent::default_template(event));
+    let fields = serde_json::to_value(event).unwrap_or_default();
+    let mut message = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        message.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let Some(end) = rest.find('}') else {
+            break;
+        };
+
+        match fields.get(&rest[1..end]) {
+            Some(Value::String(value)) => message.push_str(value),
+            Some(Value::Null) => {}
+            Some(value) => message.push_str(&value.to_string()),
+            None => message.push_str(&rest[..=end]),
+        }
+
+        rest = &rest[end + 1..];
+    }
+
+    message.push_str(rest);
+    message
+}
+
+// 订阅事件总线，过滤出需要通知的事件
+#[derive(Debug)]
+pub(crate) struct EventListener {
+    rx: broadcast::Receiver<WorkflowEvent>, // 事件订阅
+    events: Vec<NotifyEvent>,               // 需要通知的事件
+    running: HashSet<u32>,                  // 运行中的其他节点
+    finished: bool,                         // 其他节点已全部结束
+}
+
+impl EventListener {
+    // 需在节点执行前订阅，否则会错过其他节点的启动事件
+    pub(crate) fn new(rx: broadcast::Receiver<WorkflowEvent>, events: Vec<NotifyEvent>) -> Self {
+        EventListener {
+            rx,
+            events,
+            running: HashSet::new(),
+            finished: false,
+        }
+    }
+
+    // 下一个需要通知的事件，除通知节点外的节点全部结束后返回 None
+    pub(crate) async fn next(&mut self) -> Option<WorkflowEvent> {
+        while !self.finished {
+            let event = match self.rx.recv().await {
+                Ok(event) => event,
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!("Notify listener lagged, {} events skipped", skipped);
+                    continue;
+                }
+                Err(RecvError::Closed) => return None,
+            };
+
+            match &event {
+                WorkflowEvent::NodeStarted { node_id, node_type }
+                    if !node_type.starts_with(NOTIFY_PREFIX) =>
+                {
+                    self.running.insert(*node_id);
+                }
+                WorkflowEvent::NodeFinished { node_id, .. } if self.running.remove(node_id) => {
+                    self.finished = self.running.is_empty();
+                }
+                _ => {}
+            }
+
+            if NotifyEvent::of(&event).is_some_and(|kind| self.events.contains(&kind)) {
+                return Some(event);
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node_core::EventBus;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_render() {
+        let event = WorkflowEvent::StopTriggered {
+            node_id: 2,
+            exchange: "binance".to_string(),
+            symbol: "BTCUSDT".to_string(),
+            kind: "stop_loss".to_string(),
+            price: dec!(42000.5),
+        };
+
+        assert_eq!(
+            render(None, &event),
+            "[binance] BTCUSDT 触发stop_loss @ 42000.5"
+        );
+        assert_eq!(
+            render(Some("节点{node_id} {symbol} {unknown} {kind"), &event),
+            "节点2 BTCUSDT {unknown} {kind"
+        );
+    }
+
+    #[test]
+    fn test_parse_list() {
+        assert_eq!(
+            NotifyEvent::parse_list(&serde_json::json!(["stop_triggered", "error"])),
+            Some(vec![NotifyEvent::StopTriggered, NotifyEvent::Error])
+        );
+        assert_eq!(
+            NotifyEvent::parse_list(&serde_json::json!([])),
+            Some(NotifyEvent::ALL.to_vec())
+        );
+        assert_eq!(NotifyEvent::parse_list(&serde_json::json!(["tick"])), None);
+    }
+
+    #[tokio::test]
+    async fn test_event_listener() -> anyhow::Result<()> {
+        let bus = EventBus::default();
+        let mut listener = EventListener::new(bus.subscribe(), vec![NotifyEvent::Error]);
+
+        bus.publish(WorkflowEvent::NodeStarted {
+            node_id: 1,
+            node_type: "strategy.SpotGrid".to_string(),
+        });
+        bus.publish(WorkflowEvent::NodeStarted {
+            node_id: 2,
+            node_type: "notify.Webhook".to_string(),
+        });
+        bus.publish(WorkflowEvent::Error {
+            node_id: 1,
+            message: "order failed".to_string(),
+        });
+        bus.publish(WorkflowEvent::NodeFinished {
+            node_id: 1,
+            node_type: "strategy.SpotGrid".to_string(),
+            error: Some("exit".to_string()),
+        });
+
+        assert!(matches!(
+            listener.next().await,
+            Some(WorkflowEvent::Error { node_id: 1, .. })
+        ));
+        // 最后一个节点异常退出仍会通知，之后结束
+        assert!(matches!(
+            listener.next().await,
+            Some(WorkflowEvent::NodeFinished { node_id: 1, .. })
+        ));
+        assert!(listener.next().await.is_none());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,246 @@
+use super::notifier::{render, EventListener, NotifyEvent, SEND_TIMEOUT};
+use crate::{
+    node_core::{
+        NodeCategory, NodeCore, NodeCoreExt, NodeError, NodeExecutable, NodeInfra, NodeMeta,
+        NodeMetadata, WorkflowEvent,
+    },
+    workflow::Node,
+};
+use anyhow::{anyhow, Result};
+use serde_json::json;
+
+const TELEGRAM_API: &str = "https://api.telegram.org";
+const DEFAULT_TOKEN_ENV: &str = "TELEGRAM_BOT_TOKEN";
+
+/// Telegram 通知，订阅成交、止损和错误等事件，通过机器人发送到指定会话
+/// 机器人 token 从环境变量读取，不保存在工作流中
+#[derive(Debug)]
+pub(crate) struct Telegram {
+    params: Params,                  // 参数
+    infra: NodeInfra,                // 节点基础设施
+    listener: Option<EventListener>, // 事件订阅，setup 时创建
+    token: Option<String>,           // 机器人 token，setup 时读取
+}
+
+impl NodeMeta for Telegram {
+    const METADATA: NodeMetadata = NodeMetadata {
+        prop_type: "notify.Telegram",
+        display_name: "Telegram通知",
+        category: NodeCategory::Notify,
+        inputs: &[],
+        outputs: &[],
+        icon: "send",
+    };
+}
+
+impl NodeCore for Telegram {
+    fn node_infra(&self) -> &NodeInfra {
+        &self.infra
+    }
+
+    fn node_infra_mut(&mut self) -> &mut NodeInfra {
+        &mut self.infra
+    }
+}
+
+impl Telegram {
+    pub(crate) fn try_new(node: Node) -> Result<Self> {
+        let params = Params::try_from(&node)?;
+        let infra = NodeInfra::new(node);
+
+        Ok(Telegram {
+            params,
+            infra,
+            listener: None,
+            token: None,
+        })
+    }
+
+    async fn send(
+        &self,
+        client: &reqwest::Client,
+        token: &str,
+        event: &WorkflowEvent,
+    ) -> Result<()> {
+        let text = render(self.params.template.as_deref(), event);
+
+        client
+            .post(format!("{}/bot{}/sendMessage", TELEGRAM_API, token))
+            .json(&json!({ "chat_id": self.params.chat_id, "text": text }))
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+}
+
+impl NodeExecutable for Telegram {
+    async fn setup(&mut self) -> Result<()> {
+        let token = std::env::var(&self.params.token_env)
+            .map_err(|_| anyhow!("Missing environment variable {}", self.params.token_env))?;
+        let rx = self.workflow_context()?.cloned_event_bus().subscribe();
+
+        self.token = Some(token);
+        self.listener = Some(EventListener::new(rx, self.params.events.clone()));
+
+        Ok(())
+    }
+
+    async fn execute(&mut self) -> Result<(), NodeError> {
+        let mut listener = self
+            .listener
+            .take()
+            .ok_or_else(|| NodeError::Config(anyhow!("Telegram listener not initialized")))?;
+        let token = self
+            .token
+            .clone()
+            .ok_or_else(|| NodeError::Config(anyhow!("Telegram bot token not initialized")))?;
+        let client = reqwest::Client::builder().timeout(SEND_TIMEOUT).build()?;
+
+        // 推送失败只记录日志，不发布错误事件，避免告警循环
+        while let Some(event) = listener.next().await {
+            if let Err(e) = self.send(&client, &token, &event).await {
+                // reqwest 的错误信息包含带 token 的地址，需去掉
+                tracing::warn!(
+                    node_id = self.node().id,
+                    "Telegram delivery failed: {}",
+                    e.to_string().replace(&token, "***")
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl TryFrom<Node> for Telegram {
+    type Error = anyhow::Error;
+
+    fn try_from(node: Node) -> Result<Self> {
+        Telegram::try_new(node)
+    }
+}
+
+impl TryFrom<&Telegram> for Node {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &Telegram) -> Result<Self> {
+        Ok(value.node().clone())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Params {
+    chat_id: String,          // 会话ID，用户、群组ID或 @频道名
+    events: Vec<NotifyEvent>, // 订阅的事件，为空时订阅全部
+    template: Option<String>, // 消息模板，如 "{symbol} 触发{kind} @ {price}"
+    token_env: String,        // 保存机器人 token 的环境变量，默认 TELEGRAM_BOT_TOKEN
+}
+
+impl TryFrom<&Node> for Params {
+    type Error = TelegramError;
+
+    fn try_from(node: &Node) -> Result<Self, Self::Error> {
+        if node.properties.prop_type != "notify.Telegram" {
+            return Err(TelegramError::PropertyTypeMismatch);
+        }
+
+        let (chat_id, events, template, token_env) = match node.properties.params.as_slice() {
+            [chat_id, events] => (chat_id, events, None, None),
+            [chat_id, events, template] => (chat_id, events, Some(template), None),
+            [chat_id, events, template, token_env] => {
+                (chat_id, events, Some(template), Some(token_env))
+            }
+            _ => return Err(TelegramError::ParamsFormatError),
+        };
+
+        // 会话ID可以是数字或字符串
+        let chat_id = match chat_id {
+            serde_json::Value::Number(chat_id) => chat_id.to_string(),
+            serde_json::Value::String(chat_id) if !chat_id.is_empty() => chat_id.clone(),
+            _ => return Err(TelegramError::ChatIdError),
+        };
+
+        let events = NotifyEvent::parse_list(events).ok_or(TelegramError::EventsError)?;
+
+        let template = template
+            .map(|template| template.as_str().ok_or(TelegramError::TemplateError))
+            .transpose()?
+            .filter(|template| !template.is_empty())
+            .map(String::from);
+
+        let token_env = token_env
+            .map(|token_env| token_env.as_str().ok_or(TelegramError::TokenEnvError))
+            .transpose()?
+            .filter(|token_env| !token_env.is_empty())
+            .unwrap_or(DEFAULT_TOKEN_ENV)
+            .to_string();
+
+        Ok(Params {
+            chat_id,
+            events,
+            template,
+            token_env,
+        })
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum TelegramError {
+    #[error("Invalid property type, expected 'notify.Telegram'")]
+    PropertyTypeMismatch,
+
+    #[error("Invalid parameters format")]
+    ParamsFormatError,
+
+    #[error("Invalid chat id")]
+    ChatIdError,
+
+    #[error("Invalid events, expected order_filled, stop_triggered, risk_triggered or error")]
+    EventsError,
+
+    #[error("Invalid template, must be a string")]
+    TemplateError,
+
+    #[error("Invalid token env, must be a string")]
+    TokenEnvError,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_from_node_to_telegram() -> Result<()> {
+        let json_str = r#"{"id":6,"type":"通知/Telegram通知","pos":[199,74],"size":{"0":210,"1":310},"flags":{},"order":0,"mode":0,"inputs":[],"properties":{"type":"notify.Telegram","params":[-1001234567890,[]]}}"#;
+
+        let node: Node = serde_json::from_str(json_str)?;
+        let telegram = Telegram::try_from(node)?;
+
+        assert_eq!(telegram.params.chat_id, "-1001234567890");
+        assert_eq!(telegram.params.events.len(), 4);
+        assert_eq!(telegram.params.template, None);
+        assert_eq!(telegram.params.token_env, "TELEGRAM_BOT_TOKEN");
+
+        let json_str = r#"{"id":6,"type":"通知/Telegram通知","pos":[199,74],"size":{"0":210,"1":310},"flags":{},"order":0,"mode":0,"inputs":[],"properties":{"type":"notify.Telegram","params":["@alerts",["order_filled","error"],"","GRID_BOT_TOKEN"]}}"#;
+
+        let node: Node = serde_json::from_str(json_str)?;
+        let telegram = Telegram::try_from(node)?;
+
+        assert_eq!(telegram.params.chat_id, "@alerts");
+        assert_eq!(
+            telegram.params.events,
+            vec![NotifyEvent::OrderFilled, NotifyEvent::Error]
+        );
+        assert_eq!(telegram.params.template, None);
+        assert_eq!(telegram.params.token_env, "GRID_BOT_TOKEN");
+
+        let json_str = r#"{"id":6,"type":"通知/Telegram通知","pos":[199,74],"size":{"0":210,"1":310},"flags":{},"order":0,"mode":0,"inputs":[],"properties":{"type":"notify.Telegram","params":["@alerts",["filled"]]}}"#;
+
+        let node: Node = serde_json::from_str(json_str)?;
+        assert!(Telegram::try_from(node).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,193 @@
+use super::notifier::{render, EventListener, NotifyEvent, SEND_TIMEOUT};
+use crate::{
+    node_core::{
+        NodeCategory, NodeCore, NodeCoreExt, NodeError, NodeExecutable, NodeInfra, NodeMeta,
+        NodeMetadata, WorkflowEvent,
+    },
+    workflow::Node,
+};
+use anyhow::{anyhow, Result};
+use serde_json::json;
+
+/// Webhook 通知，订阅成交、止损和错误等事件，POST 到指定地址
+/// 请求体: {"text": 渲染后的消息, "event": 原始事件}
+#[derive(Debug)]
+pub(crate) struct Webhook {
+    params: Params,                  // 参数
+    infra: NodeInfra,                // 节点基础设施
+    listener: Option<EventListener>, // 事件订阅，setup 时创建
+}
+
+impl NodeMeta for Webhook {
+    const METADATA: NodeMetadata = NodeMetadata {
+        prop_type: "notify.Webhook",
+        display_name: "Webhook通知",
+        category: NodeCategory::Notify,
+        inputs: &[],
+        outputs: &[],
+        icon: "webhook",
+    };
+}
+
+impl NodeCore for Webhook {
+    fn node_infra(&self) -> &NodeInfra {
+        &self.infra
+    }
+
+    fn node_infra_mut(&mut self) -> &mut NodeInfra {
+        &mut self.infra
+    }
+}
+
+impl Webhook {
+    pub(crate) fn try_new(node: Node) -> Result<Self> {
+        let params = Params::try_from(&node)?;
+        let infra = NodeInfra::new(node);
+
+        Ok(Webhook {
+            params,
+            infra,
+            listener: None,
+        })
+    }
+
+    async fn send(&self, client: &reqwest::Client, event: &WorkflowEvent) -> Result<()> {
+        let text = render(self.params.template.as_deref(), event);
+
+        client
+            .post(&self.params.url)
+            .json(&json!({ "text": text, "event": event }))
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+}
+
+impl NodeExecutable for Webhook {
+    async fn setup(&mut self) -> Result<()> {
+        let rx = self.workflow_context()?.cloned_event_bus().subscribe();
+        self.listener = Some(EventListener::new(rx, self.params.events.clone()));
+
+        Ok(())
+    }
+
+    async fn execute(&mut self) -> Result<(), NodeError> {
+        let mut listener = self
+            .listener
+            .take()
+            .ok_or_else(|| NodeError::Config(anyhow!("Webhook listener not initialized")))?;
+        let client = reqwest::Client::builder().timeout(SEND_TIMEOUT).build()?;
+
+        // 推送失败只记录日志，不发布错误事件，避免告警循环
+        while let Some(event) = listener.next().await {
+            if let Err(e) = self.send(&client, &event).await {
+                tracing::warn!(node_id = self.node().id, "Webhook delivery failed: {}", e);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl TryFrom<Node> for Webhook {
+    type Error = anyhow::Error;
+
+    fn try_from(node: Node) -> Result<Self> {
+        Webhook::try_new(node)
+    }
+}
+
+impl TryFrom<&Webhook> for Node {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &Webhook) -> Result<Self> {
+        Ok(value.node().clone())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Params {
+    url: String,              // 推送地址
+    events: Vec<NotifyEvent>, // 订阅的事件，为空时订阅全部
+    template: Option<String>, // 消息模板，如 "{symbol} 触发{kind} @ {price}"
+}
+
+impl TryFrom<&Node> for Params {
+    type Error = WebhookError;
+
+    fn try_from(node: &Node) -> Result<Self, Self::Error> {
+        if node.properties.prop_type != "notify.Webhook" {
+            return Err(WebhookError::PropertyTypeMismatch);
+        }
+
+        let (url, events, template) = match node.properties.params.as_slice() {
+            [url, events] => (url, events, None),
+            [url, events, template] => (url, events, Some(template)),
+            _ => return Err(WebhookError::ParamsFormatError),
+        };
+
+        let url = url
+            .as_str()
+            .filter(|url| url.starts_with("http://") || url.starts_with("https://"))
+            .ok_or(WebhookError::UrlError)?
+            .to_string();
+
+        let events = NotifyEvent::parse_list(events).ok_or(WebhookError::EventsError)?;
+
+        let template = template
+            .map(|template| template.as_str().ok_or(WebhookError::TemplateError))
+            .transpose()?
+            .filter(|template| !template.is_empty())
+            .map(String::from);
+
+        Ok(Params {
+            url,
+            events,
+            template,
+        })
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum WebhookError {
+    #[error("Invalid property type, expected 'notify.Webhook'")]
+    PropertyTypeMismatch,
+
+    #[error("Invalid parameters format")]
+    ParamsFormatError,
+
+    #[error("Invalid url, expected an http(s) address")]
+    UrlError,
+
+    #[error("Invalid events, expected order_filled, stop_triggered, risk_triggered or error")]
+    EventsError,
+
+    #[error("Invalid template, must be a string")]
+    TemplateError,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_from_node_to_webhook() -> Result<()> {
+        let json_str = r#"{"id":5,"type":"通知/Webhook通知","pos":[199,74],"size":{"0":210,"1":310},"flags":{},"order":0,"mode":0,"inputs":[],"properties":{"type":"notify.Webhook","params":["https://example.com/hook",["stop_triggered"],"{symbol} {kind}"]}}"#;
+
+        let node: Node = serde_json::from_str(json_str)?;
+        let webhook = Webhook::try_from(node)?;
+
+        assert_eq!(webhook.params.url, "https://example.com/hook");
+        assert_eq!(webhook.params.events, vec![NotifyEvent::StopTriggered]);
+        assert_eq!(webhook.params.template.as_deref(), Some("{symbol} {kind}"));
+
+        let json_str = r#"{"id":5,"type":"通知/Webhook通知","pos":[199,74],"size":{"0":210,"1":310},"flags":{},"order":0,"mode":0,"inputs":[],"properties":{"type":"notify.Webhook","params":["example.com",[]]}}"#;
+
+        let node: Node = serde_json::from_str(json_str)?;
+        assert!(Webhook::try_from(node).is_err());
+
+        Ok(())
+    }
+}
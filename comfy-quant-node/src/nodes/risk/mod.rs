@@ -0,0 +1,3 @@
+mod risk_guard;
+
+pub(crate) use risk_guard::RiskGuard;
@@ -0,0 +1,902 @@
+use crate::{
+    node_core::{
+        EventBus, NodeCategory, NodeCore, NodeCoreExt, NodeError, NodeExecutable, NodeInfra,
+        NodeMeta, NodeMetadata, SimulatedClock, Slot, WorkflowEvent, SPOT_CLIENT,
+    },
+    workflow::Node,
+};
+use anyhow::{anyhow, Result};
+use async_lock::RwLock;
+use bon::Builder;
+use chrono::NaiveDate;
+use comfy_quant_base::Market;
+use comfy_quant_exchange::{
+    client::{
+        spot_client::{
+            base::{Balance, OrderIntent, OrderSide, SpotClientRequest},
+            guarded_spot_client::{GuardedSpotClient, OrderGuard},
+        },
+        spot_client_kind::{SpotClientExecutable, SpotClientKind, SpotclientExecutableExt},
+    },
+    store::PriceStore,
+};
+use futures::future::BoxFuture;
+use rust_decimal::Decimal;
+use std::{
+    collections::BTreeSet,
+    fmt,
+    str::FromStr,
+    sync::{Arc, Mutex, MutexGuard},
+    time::Duration,
+};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(1); // 实盘定时检查权益的间隔
+
+/// 风控，包装上游账户客户端，下单前检查回撤、单日亏损和持仓限额；
+/// 实盘时另按价格变化定时检查回撤和单日亏损，没有下单也能及时清仓
+/// inputs:
+///      0: SpotClient
+/// outputs:
+///      0: SpotClient
+#[derive(Debug)]
+pub(crate) struct RiskGuard {
+    params: Params,            // 参数
+    infra: NodeInfra,          // 节点基础设施
+    guard: Option<Arc<Guard>>, // 下单检查，setup 时创建，执行时定时检查权益
+}
+
+impl NodeMeta for RiskGuard {
+    const METADATA: NodeMetadata = NodeMetadata {
+        prop_type: "risk.RiskGuard",
+        display_name: "风控",
+        category: NodeCategory::Risk,
+        inputs: &[SPOT_CLIENT],
+        outputs: &[SPOT_CLIENT],
+        icon: "shield",
+    };
+}
+
+impl NodeCore for RiskGuard {
+    fn node_infra(&self) -> &NodeInfra {
+        &self.infra
+    }
+
+    fn node_infra_mut(&mut self) -> &mut NodeInfra {
+        &mut self.infra
+    }
+}
+
+impl RiskGuard {
+    pub(crate) fn try_new(node: Node) -> Result<Self> {
+        let params = Params::try_from(&node)?;
+        let infra = NodeInfra::new(node);
+
+        Ok(RiskGuard {
+            params,
+            infra,
+            guard: None,
+        })
+    }
+
+    // 价格有变化时检查权益。回测时由下单前的检查负责，时钟被tick推进后结束，
+    // 避免回放结束后节点一直运行
+    async fn watch(&self) -> Result<()> {
+        let guard = self
+            .guard
+            .clone()
+            .ok_or_else(|| anyhow!("RiskGuard is not set up"))?;
+        let client = self.port().input::<SpotClientKind>(0)?;
+        let ctx = self.workflow_context()?;
+        let price_store = ctx.cloned_price_store();
+        let clock = ctx.cloned_clock();
+        let heartbeat = self.heartbeat();
+        let mut last_ticks = price_store.read().await.ticks();
+
+        loop {
+            tokio::time::sleep(CHECK_INTERVAL).await;
+
+            if clock.is_simulated() {
+                return Ok(());
+            }
+
+            let ticks = price_store.read().await.ticks();
+
+            if ticks == last_ticks {
+                continue;
+            }
+
+            last_ticks = ticks;
+            let _busy = heartbeat.busy();
+
+            if let Err(e) = guard.check_equity(&client, None).await {
+                tracing::warn!(
+                    node_id = self.node().id,
+                    "RiskGuard check equity failed: {}",
+                    e
+                );
+            }
+        }
+    }
+}
+
+// 上游账户节点需排在风控节点之前，setup 时输入已连接
+impl NodeExecutable for RiskGuard {
+    async fn setup(&mut self) -> Result<()> {
+        let client = self
+            .port()
+            .input::<SpotClientKind>(0)
+            .map_err(|_| anyhow!("RiskGuard requires a SpotClient input from a preceding node"))?;
+        let ctx = self.workflow_context()?;
+
+        let guard = Arc::new(Guard {
+            node_id: self.node().id,
+            params: self.params.clone(),
+            price_store: ctx.cloned_price_store(),
+            clock: ctx.cloned_clock(),
+            event_bus: ctx.cloned_event_bus(),
+            state: Mutex::new(GuardState::default()),
+        });
+        guard.snapshot(&client).await?;
+
+        let client = GuardedSpotClient::new((**client).clone(), Arc::clone(&guard));
+
+        self.port_mut()
+            .set_output(0, Arc::new(Slot::<SpotClientKind>::new(client.into())))?;
+        self.guard = Some(guard);
+
+        Ok(())
+    }
+
+    async fn execute(&mut self) -> Result<(), NodeError> {
+        Ok(self.watch().await?)
+    }
+}
+
+impl TryFrom<Node> for RiskGuard {
+    type Error = anyhow::Error;
+
+    fn try_from(node: Node) -> Result<Self> {
+        RiskGuard::try_new(node)
+    }
+}
+
+impl TryFrom<&RiskGuard> for Node {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &RiskGuard) -> Result<Self> {
+        Ok(value.node().clone())
+    }
+}
+
+// 触发回撤或单日亏损限额后的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum RiskAction {
+    #[default]
+    Block, // 拒绝新的买单，卖单仍可减仓
+    Liquidate, // 撤销挂单并市价卖出持仓，之后拒绝所有新的订单
+}
+
+impl FromStr for RiskAction {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "block" => Ok(RiskAction::Block),
+            "liquidate" => Ok(RiskAction::Liquidate),
+            _ => Err(anyhow!("Invalid risk action: {}", s)),
+        }
+    }
+}
+
+impl AsRef<str> for RiskAction {
+    fn as_ref(&self) -> &str {
+        match self {
+            RiskAction::Block => "block",
+            RiskAction::Liquidate => "liquidate",
+        }
+    }
+}
+
+// 风控规则
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RiskRule {
+    MaxDrawdown, // 最大回撤
+    DailyLoss,   // 单日亏损
+    MaxPosition, // 单个交易对的持仓价值
+}
+
+impl fmt::Display for RiskRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rule = match self {
+            RiskRule::MaxDrawdown => "max_drawdown",
+            RiskRule::DailyLoss => "daily_loss",
+            RiskRule::MaxPosition => "max_position",
+        };
+
+        write!(f, "{}", rule)
+    }
+}
+
+// 触发的规则
+#[derive(Debug, Clone, PartialEq)]
+struct Breach {
+    rule: RiskRule, // 规则
+    value: Decimal, // 触发时的数值
+    limit: Decimal, // 限额
+}
+
+#[derive(Debug, Default)]
+struct GuardState {
+    assets: BTreeSet<String>,  // 启动时持有和下过单的基础资产，计入权益
+    peak_equity: Decimal,      // 权益峰值
+    day: Option<NaiveDate>,    // 当前日期(UTC)
+    day_start_equity: Decimal, // 当日第一次检查时的权益
+    tripped: Option<RiskRule>, // 已触发的回撤或单日亏损规则
+}
+
+impl GuardState {
+    // 用最新权益更新峰值和当日起始权益，返回新触发的规则。
+    // 回撤触发后一直生效，单日亏损触发后在下一个自然日解除
+    fn update(&mut self, params: &Params, equity: Decimal, today: NaiveDate) -> Option<Breach> {
+        if self.day != Some(today) {
+            self.day = Some(today);
+            self.day_start_equity = equity;
+
+            if self.tripped == Some(RiskRule::DailyLoss) {
+                self.tripped = None;
+            }
+        }
+
+        self.peak_equity = self.peak_equity.max(equity);
+
+        if self.tripped.is_some() {
+            return None;
+        }
+
+        let loss_ratio = |base: Decimal| {
+            (base > Decimal::ZERO).then(|| ((base - equity) / base).max(Decimal::ZERO))
+        };
+
+        let breach = [
+            (
+                RiskRule::MaxDrawdown,
+                params.max_drawdown,
+                loss_ratio(self.peak_equity),
+            ),
+            (
+                RiskRule::DailyLoss,
+                params.daily_loss,
+                loss_ratio(self.day_start_equity),
+            ),
+        ]
+        .into_iter()
+        .find_map(|(rule, limit, value)| match (limit, value) {
+            (Some(limit), Some(value)) if value >= limit => Some(Breach { rule, value, limit }),
+            _ => None,
+        })?;
+
+        self.tripped = Some(breach.rule);
+
+        Some(breach)
+    }
+}
+
+// 下单前的风控检查，只检查计价资产与参数一致的交易对
+#[derive(Debug)]
+struct Guard {
+    node_id: u32,                         // 风控节点ID
+    params: Params,                       // 参数
+    price_store: Arc<RwLock<PriceStore>>, // 最新价格
+    clock: Arc<SimulatedClock>,           // 工作流时钟，按该时间划分自然日
+    event_bus: Arc<EventBus>,             // 事件总线
+    state: Mutex<GuardState>,             // 权益峰值和触发状态
+}
+
+impl OrderGuard for Guard {
+    fn check<'a>(
+        &'a self,
+        inner: &'a SpotClientKind,
+        req: &'a SpotClientRequest,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(self.check_order(inner, req))
+    }
+}
+
+impl Guard {
+    fn state(&self) -> Result<MutexGuard<'_, GuardState>> {
+        self.state
+            .lock()
+            .map_err(|_| anyhow!("RiskGuard state lock poisoned"))
+    }
+
+    async fn check_order(&self, inner: &SpotClientKind, req: &SpotClientRequest) -> Result<()> {
+        let (Some((base_asset, quote_asset)), Some(side)) = (req.trading_pair(), req.order_side())
+        else {
+            return Ok(());
+        };
+
+        if quote_asset != self.params.quote_asset {
+            return Ok(());
+        }
+
+        self.state()?.assets.insert(base_asset.to_string());
+
+        let tripped = self.check_equity(inner, Some(base_asset)).await?;
+
+        // 清仓后卖单也拒绝，避免与清仓订单重复卖出
+        if let Some(rule) = tripped {
+            if side == OrderSide::Buy || self.params.action == RiskAction::Liquidate {
+                anyhow::bail!("Order rejected by risk guard: {} triggered", rule);
+            }
+        }
+
+        // 卖单减少持仓，不受持仓限额限制
+        if side == OrderSide::Sell {
+            return Ok(());
+        }
+
+        let Some(limit) = self.params.max_position else {
+            return Ok(());
+        };
+
+        let price = self.price(inner, base_asset).await?;
+        let holding = balance_total(&inner.get_balance(base_asset).await?)? * price;
+        let position = holding + buy_value(req, price)?;
+
+        if position > limit {
+            let breach = Breach {
+                rule: RiskRule::MaxPosition,
+                value: position,
+                limit,
+            };
+            self.publish(inner, Some(base_asset), &breach, RiskAction::Block);
+
+            anyhow::bail!(
+                "Order rejected by risk guard: position {} exceeds {}",
+                position,
+                limit
+            );
+        }
+
+        Ok(())
+    }
+
+    // 记录启动时账户中所有有余额的资产，没有下单的持仓也计入权益
+    async fn snapshot(&self, inner: &SpotClientKind) -> Result<()> {
+        let assets = inner
+            .get_balances()
+            .await?
+            .into_iter()
+            .map(|balance| balance.asset)
+            .filter(|asset| *asset != self.params.quote_asset)
+            .collect::<Vec<_>>();
+
+        self.state()?.assets.extend(assets);
+
+        Ok(())
+    }
+
+    // 用最新权益检查回撤和单日亏损，新触发时上报并按设置清仓，返回已触发的规则。
+    // base_asset 为触发检查的订单的基础资产，定时检查时为空
+    async fn check_equity(
+        &self,
+        inner: &SpotClientKind,
+        base_asset: Option<&str>,
+    ) -> Result<Option<RiskRule>> {
+        let equity = self.equity(inner).await?;
+        let today = self.clock.now().date_naive();
+        let (breach, tripped) = {
+            let mut state = self.state()?;
+            let breach = state.update(&self.params, equity, today);
+            (breach, state.tripped)
+        };
+
+        if let Some(breach) = breach {
+            self.publish(inner, base_asset, &breach, self.params.action);
+
+            if self.params.action == RiskAction::Liquidate {
+                self.liquidate(inner).await?;
+            }
+        }
+
+        Ok(tripped)
+    }
+
+    // 计价资产余额与启动时持有、下过单的基础资产市值之和
+    async fn equity(&self, inner: &SpotClientKind) -> Result<Decimal> {
+        let assets = self.state()?.assets.clone();
+        let mut equity = balance_total(&inner.get_balance(&self.params.quote_asset).await?)?;
+
+        for asset in assets {
+            let amount = balance_total(&inner.get_balance(&asset).await?)?;
+
+            if !amount.is_zero() {
+                equity += amount * self.price(inner, &asset).await?;
+            }
+        }
+
+        Ok(equity)
+    }
+
+    // 优先使用数据节点推送的最新价格，没有时向交易所查询
+    async fn price(&self, inner: &SpotClientKind, base_asset: &str) -> Result<Decimal> {
+        let symbol = inner.symbol(base_asset, &self.params.quote_asset);
+        let price = self
+            .price_store
+            .read()
+            .await
+            .price(&inner.exchange(), &Market::Spot, &symbol);
+
+        match price {
+            Some(price) => Ok(price),
+            None => Ok(inner
+                .get_price(base_asset, &self.params.quote_asset)
+                .await?
+                .price),
+        }
+    }
+
+    // 撤销挂单并市价卖出计入权益的基础资产。清仓订单不经过策略节点，不计入策略统计
+    async fn liquidate(&self, inner: &SpotClientKind) -> Result<()> {
+        let assets = self.state()?.assets.clone();
+        let quote_asset = &self.params.quote_asset;
+
+        for asset in assets {
+            let result = async {
+                inner.cancel_all_orders(&asset, quote_asset).await?;
+
+                let free = inner.get_balance(&asset).await?.free.parse::<f64>()?;
+
+                if free > 0. {
+                    inner.market_sell(&asset, quote_asset, free).await?;
+                }
+
+                Ok::<_, anyhow::Error>(())
+            }
+            .await;
+
+            if let Err(e) = result {
+                tracing::warn!(
+                    node_id = self.node_id,
+                    asset = %asset,
+                    "RiskGuard liquidate failed: {}",
+                    e
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    fn publish(
+        &self,
+        inner: &SpotClientKind,
+        base_asset: Option<&str>,
+        breach: &Breach,
+        action: RiskAction,
+    ) {
+        let symbol = base_asset
+            .map(|base_asset| {
+                inner
+                    .symbol(base_asset, &self.params.quote_asset)
+                    .to_string()
+            })
+            .unwrap_or_default();
+
+        self.event_bus.publish(WorkflowEvent::RiskTriggered {
+            node_id: self.node_id,
+            exchange: inner.exchange().to_string(),
+            symbol,
+            rule: breach.rule.to_string(),
+            value: breach.value,
+            limit: breach.limit,
+            action: action.as_ref().to_string(),
+        });
+    }
+}
+
+// 可用与锁定余额之和
+fn balance_total(balance: &Balance) -> Result<Decimal> {
+    Ok(balance.free.parse::<Decimal>()? + balance.locked.parse::<Decimal>()?)
+}
+
+// 买单增加的持仓价值，市价单按最新价格估算
+fn buy_value(req: &SpotClientRequest, price: Decimal) -> Result<Decimal> {
+    let value = match req {
+        SpotClientRequest::MarketBuy { qty, .. } | SpotClientRequest::MarginBuy { qty, .. } => {
+            Decimal::try_from(*qty)? * price
+        }
+        SpotClientRequest::MarketBuyQuote { quote_qty, .. } => Decimal::try_from(*quote_qty)?,
+        SpotClientRequest::LimitBuy {
+            qty,
+            price: limit_price,
+            ..
+        }
+        | SpotClientRequest::StopLimitOrder {
+            qty,
+            price: limit_price,
+            ..
+        }
+        | SpotClientRequest::OcoOrder {
+            qty,
+            price: limit_price,
+            ..
+        } => Decimal::try_from(*qty)? * Decimal::try_from(*limit_price)?,
+        SpotClientRequest::SubmitOrder { intent, .. } => match intent {
+            OrderIntent::MarketBuy { qty, .. } => Decimal::try_from(*qty)? * price,
+            OrderIntent::LimitBuy {
+                qty,
+                price: limit_price,
+                ..
+            } => Decimal::try_from(*qty)? * Decimal::try_from(*limit_price)?,
+            _ => Decimal::ZERO,
+        },
+        _ => Decimal::ZERO,
+    };
+
+    Ok(value)
+}
+
+#[derive(Builder, Debug, Clone)]
+#[builder(on(String, into))]
+pub(crate) struct Params {
+    quote_asset: String,           // 计价资产，权益和持仓价值按该资产计算
+    max_drawdown: Option<Decimal>, // 权益从峰值回撤的比例上限
+    daily_loss: Option<Decimal>,   // 权益较当日起始的亏损比例上限
+    max_position: Option<Decimal>, // 单个交易对的持仓价值上限
+    #[builder(default)]
+    action: RiskAction, // 触发回撤或单日亏损后的处理方式: block 或 liquidate
+}
+
+impl TryFrom<&Node> for Params {
+    type Error = RiskGuardError;
+
+    fn try_from(node: &Node) -> Result<Self, Self::Error> {
+        if node.properties.prop_type != "risk.RiskGuard" {
+            return Err(RiskGuardError::PropertyTypeMismatch);
+        }
+
+        // 计价资产为必填参数，限额和处理方式可选，至少设置一个限额
+        let [quote_asset, optional_params @ ..] = node.properties.params.as_slice() else {
+            return Err(RiskGuardError::ParamsFormatError);
+        };
+
+        if optional_params.len() > 4 {
+            return Err(RiskGuardError::ParamsFormatError);
+        }
+
+        let quote_asset = quote_asset
+            .as_str()
+            .filter(|quote_asset| !quote_asset.is_empty())
+            .ok_or(RiskGuardError::QuoteAssetError)?;
+
+        let decimal_param = |index: usize,
+                             valid: fn(&Decimal) -> bool,
+                             error: RiskGuardError|
+         -> Result<Option<Decimal>, RiskGuardError> {
+            match optional_params.get(index) {
+                Some(value) if !value.is_null() => value
+                    .as_f64()
+                    .and_then(|value| Decimal::try_from(value).ok())
+                    .filter(valid)
+                    .map(Some)
+                    .ok_or(error),
+                _ => Ok(None),
+            }
+        };
+
+        let is_ratio = |value: &Decimal| *value > Decimal::ZERO && *value <= Decimal::ONE;
+
+        let max_drawdown = decimal_param(0, is_ratio, RiskGuardError::MaxDrawdownError)?;
+        let daily_loss = decimal_param(1, is_ratio, RiskGuardError::DailyLossError)?;
+        let max_position = decimal_param(
+            2,
+            |value| *value > Decimal::ZERO,
+            RiskGuardError::MaxPositionError,
+        )?;
+
+        if max_drawdown.is_none() && daily_loss.is_none() && max_position.is_none() {
+            return Err(RiskGuardError::NoLimitError);
+        }
+
+        let action = optional_params
+            .get(3)
+            .filter(|action| !action.is_null())
+            .map(|action| {
+                action
+                    .as_str()
+                    .and_then(|action| action.parse::<RiskAction>().ok())
+                    .ok_or(RiskGuardError::ActionError)
+            })
+            .transpose()?;
+
+        let params = Params::builder()
+            .quote_asset(quote_asset)
+            .maybe_max_drawdown(max_drawdown)
+            .maybe_daily_loss(daily_loss)
+            .maybe_max_position(max_position)
+            .maybe_action(action)
+            .build();
+
+        Ok(params)
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum RiskGuardError {
+    #[error("Invalid property type, expected 'risk.RiskGuard'")]
+    PropertyTypeMismatch,
+
+    #[error("Invalid parameters format")]
+    ParamsFormatError,
+
+    #[error("Invalid quote asset")]
+    QuoteAssetError,
+
+    #[error("Invalid max drawdown, expected between 0 and 1")]
+    MaxDrawdownError,
+
+    #[error("Invalid daily loss, expected between 0 and 1")]
+    DailyLossError,
+
+    #[error("Invalid max position, expected greater than 0")]
+    MaxPositionError,
+
+    #[error("At least one of max drawdown, daily loss or max position is required")]
+    NoLimitError,
+
+    #[error("Invalid risk action, expected 'block' or 'liquidate'")]
+    ActionError,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use comfy_quant_base::Exchange;
+    use comfy_quant_exchange::client::spot_client::{
+        backtest_spot_client::BacktestSpotClient, base::SymbolPrice,
+    };
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_try_from_node_to_risk_guard() -> Result<()> {
+        let json_str = r#"{"id":3,"type":"风控/风控","pos":[199,74],"size":{"0":210,"1":310},"flags":{},"order":1,"mode":0,"inputs":[{"name":"现货账户客户端","type":"SpotClient","link":1}],"outputs":[{"name":"现货账户客户端","type":"SpotClient","links":[2],"slot_index":0}],"properties":{"type":"risk.RiskGuard","params":["USDT", 0.2, null, 5000, "liquidate"]}}"#;
+
+        let node: Node = serde_json::from_str(json_str)?;
+        let guard = RiskGuard::try_from(node)?;
+
+        assert_eq!(guard.params.quote_asset, "USDT");
+        assert_eq!(guard.params.max_drawdown, Some(dec!(0.2)));
+        assert_eq!(guard.params.daily_loss, None);
+        assert_eq!(guard.params.max_position, Some(dec!(5000)));
+        assert_eq!(guard.params.action, RiskAction::Liquidate);
+
+        let json_str = r#"{"id":3,"type":"风控/风控","pos":[199,74],"size":{"0":210,"1":310},"flags":{},"order":1,"mode":0,"properties":{"type":"risk.RiskGuard","params":["USDT"]}}"#;
+
+        let node: Node = serde_json::from_str(json_str)?;
+        assert_eq!(
+            RiskGuard::try_from(node).unwrap_err().to_string(),
+            "At least one of max drawdown, daily loss or max position is required"
+        );
+
+        let json_str = r#"{"id":3,"type":"风控/风控","pos":[199,74],"size":{"0":210,"1":310},"flags":{},"order":1,"mode":0,"properties":{"type":"risk.RiskGuard","params":["USDT", 1.5]}}"#;
+
+        let node: Node = serde_json::from_str(json_str)?;
+        assert_eq!(
+            RiskGuard::try_from(node).unwrap_err().to_string(),
+            "Invalid max drawdown, expected between 0 and 1"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_guard_state_update() -> Result<()> {
+        let params = Params::builder()
+            .quote_asset("USDT")
+            .max_drawdown(dec!(0.2))
+            .daily_loss(dec!(0.05))
+            .build();
+        let day1 = NaiveDate::from_ymd_opt(2024, 1, 1).ok_or(anyhow!("Invalid date"))?;
+        let day2 = NaiveDate::from_ymd_opt(2024, 1, 2).ok_or(anyhow!("Invalid date"))?;
+
+        let mut state = GuardState::default();
+        assert_eq!(state.update(&params, dec!(1000), day1), None);
+        assert_eq!(state.update(&params, dec!(1100), day1), None);
+
+        // 当日从1000亏到940，超过5%
+        let breach = state.update(&params, dec!(940), day1);
+        assert_eq!(
+            breach,
+            Some(Breach {
+                rule: RiskRule::DailyLoss,
+                value: dec!(0.06),
+                limit: dec!(0.05),
+            })
+        );
+        assert_eq!(state.tripped, Some(RiskRule::DailyLoss));
+        // 已触发时不重复上报
+        assert_eq!(state.update(&params, dec!(930), day1), None);
+
+        // 下一个自然日解除单日亏损，回撤从峰值1100计算
+        assert_eq!(state.update(&params, dec!(900), day2), None);
+        assert_eq!(state.tripped, None);
+
+        let breach = state
+            .update(&params, dec!(880), day2)
+            .map(|breach| breach.rule);
+        assert_eq!(breach, Some(RiskRule::MaxDrawdown));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_risk_guard_check_order() -> Result<()> {
+        let price_store = Arc::new(RwLock::new(PriceStore::new()));
+        let save_price = |price: Decimal| {
+            let price_store = Arc::clone(&price_store);
+            async move {
+                price_store.write().await.save_price(
+                    &Exchange::Binance,
+                    &Market::Spot,
+                    &SymbolPrice::builder()
+                        .symbol("BTCUSDT".into())
+                        .price(price)
+                        .build(),
+                )
+            }
+        };
+        save_price(dec!(50000)).await?;
+
+        let inner: SpotClientKind = BacktestSpotClient::builder()
+            .assets(vec![("USDT".to_string(), 1000.)])
+            .commissions(0.)
+            .price_store(Arc::clone(&price_store))
+            .build()
+            .into();
+        let event_bus = Arc::new(EventBus::default());
+        let mut events = event_bus.subscribe();
+        let guard = Guard {
+            node_id: 3,
+            params: Params::builder()
+                .quote_asset("USDT")
+                .max_drawdown(dec!(0.2))
+                .max_position(dec!(600))
+                .build(),
+            price_store: Arc::clone(&price_store),
+            clock: Arc::new(SimulatedClock::default()),
+            event_bus,
+            state: Mutex::new(GuardState::default()),
+        };
+        let client: SpotClientKind = GuardedSpotClient::new(inner, Arc::new(guard)).into();
+
+        client.market_buy("BTC", "USDT", 0.01).await?;
+
+        // 持仓500，再买200超过600的限额
+        assert!(client.market_buy_quote("BTC", "USDT", 200.).await.is_err());
+        assert!(matches!(
+            events.recv().await?,
+            WorkflowEvent::RiskTriggered { ref rule, .. } if rule == "max_position"
+        ));
+
+        // 价格腰斩，权益从1000回撤到750，拒绝买单，卖单仍可下
+        save_price(dec!(25000)).await?;
+        assert!(client.market_buy("BTC", "USDT", 0.001).await.is_err());
+        assert!(matches!(
+            events.recv().await?,
+            WorkflowEvent::RiskTriggered { ref rule, .. } if rule == "max_drawdown"
+        ));
+        client.market_sell("BTC", "USDT", 0.01).await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_risk_guard_check_equity() -> Result<()> {
+        let price_store = Arc::new(RwLock::new(PriceStore::new()));
+        let save_price = |price: Decimal| {
+            let price_store = Arc::clone(&price_store);
+            async move {
+                price_store.write().await.save_price(
+                    &Exchange::Binance,
+                    &Market::Spot,
+                    &SymbolPrice::builder()
+                        .symbol("BTCUSDT".into())
+                        .price(price)
+                        .build(),
+                )
+            }
+        };
+        save_price(dec!(50000)).await?;
+
+        let inner: SpotClientKind = BacktestSpotClient::builder()
+            .assets(vec![("USDT".to_string(), 1000.)])
+            .commissions(0.)
+            .price_store(Arc::clone(&price_store))
+            .build()
+            .into();
+        let event_bus = Arc::new(EventBus::default());
+        let mut events = event_bus.subscribe();
+        let guard = Arc::new(Guard {
+            node_id: 3,
+            params: Params::builder()
+                .quote_asset("USDT")
+                .max_drawdown(dec!(0.2))
+                .action(RiskAction::Liquidate)
+                .build(),
+            price_store: Arc::clone(&price_store),
+            clock: Arc::new(SimulatedClock::default()),
+            event_bus,
+            state: Mutex::new(GuardState::default()),
+        });
+        let client: SpotClientKind =
+            GuardedSpotClient::new(inner.clone(), Arc::clone(&guard)).into();
+
+        client.market_buy("BTC", "USDT", 0.01).await?;
+        assert_eq!(guard.check_equity(&inner, None).await?, None);
+
+        // 没有新的订单，定时检查发现回撤后清仓
+        save_price(dec!(25000)).await?;
+        assert_eq!(
+            guard.check_equity(&inner, None).await?,
+            Some(RiskRule::MaxDrawdown)
+        );
+        assert!(matches!(
+            events.recv().await?,
+            WorkflowEvent::RiskTriggered { ref rule, ref symbol, .. }
+                if rule == "max_drawdown" && symbol.is_empty()
+        ));
+        assert_eq!(inner.get_balance("BTC").await?.free.parse::<f64>()?, 0.);
+
+        // 清仓后买单和卖单都被拒绝
+        for result in [
+            client.market_buy("BTC", "USDT", 0.001).await,
+            client.market_sell("BTC", "USDT", 0.001).await,
+        ] {
+            assert!(result
+                .unwrap_err()
+                .to_string()
+                .starts_with("Order rejected by risk guard"));
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_risk_guard_snapshot() -> Result<()> {
+        let price_store = Arc::new(RwLock::new(PriceStore::new()));
+        price_store.write().await.save_price(
+            &Exchange::Binance,
+            &Market::Spot,
+            &SymbolPrice::builder()
+                .symbol("BTCUSDT".into())
+                .price(dec!(50000))
+                .build(),
+        )?;
+
+        // 启动前已持有的BTC计入权益
+        let inner: SpotClientKind = BacktestSpotClient::builder()
+            .assets(vec![("BTC".to_string(), 0.01), ("USDT".to_string(), 500.)])
+            .commissions(0.)
+            .price_store(Arc::clone(&price_store))
+            .build()
+            .into();
+        let guard = Guard {
+            node_id: 3,
+            params: Params::builder()
+                .quote_asset("USDT")
+                .max_drawdown(dec!(0.2))
+                .build(),
+            price_store: Arc::clone(&price_store),
+            clock: Arc::new(SimulatedClock::default()),
+            event_bus: Arc::new(EventBus::default()),
+            state: Mutex::new(GuardState::default()),
+        };
+
+        guard.snapshot(&inner).await?;
+        assert_eq!(guard.state()?.assets, BTreeSet::from(["BTC".to_string()]));
+        assert_eq!(guard.equity(&inner).await?, dec!(1000));
+
+        Ok(())
+    }
+}
@@ -1,3 +1,7 @@
 mod spot_grid;
+mod strategy_allocator;
+mod triangular_arb;
 
 pub(crate) use spot_grid::SpotGrid;
+pub(crate) use strategy_allocator::StrategyAllocator;
+pub(crate) use triangular_arb::TriangularArb;
@@ -1,22 +1,24 @@
 use crate::{
     node_core::{
-        NodeCore, NodeCoreExt, NodeExecutable, NodeInfra, NodeSpotStats, NodeSpotStatsExt,
-        SpotClientService, SpotTradeable, TradeStats,
+        next_user_data, KlinesWindow, NodeCategory, NodeCore, NodeCoreExt, NodeError,
+        NodeExecutable, NodeInfra, NodeMeta, NodeMetadata, NodeSpotStats, NodeSpotStatsExt,
+        SpotClientService, SpotTradeable, SymbolRules, Tick, TradeStats, WorkflowEvent,
+        CAPITAL_ALLOCATION, SPOT_CLIENT, SPOT_PAIR_INFO, TICK_STREAM,
     },
-    node_io::{SpotPairInfo, TickStream},
-    stats::SpotStats,
+    node_io::{CapitalAllocation, SpotPairInfo, TickStream},
+    stats::{Event, EventKind, SpotStats, TradeRecord},
     workflow::Node,
 };
 use anyhow::{anyhow, Result};
 use bon::{bon, Builder};
-use comfy_quant_base::{Exchange, Market, Symbol};
+use comfy_quant_base::{Exchange, KlineInterval, Market, Symbol};
 use comfy_quant_exchange::client::{
     spot_client::base::{Order, OrderSide},
     spot_client_kind::{SpotClientExecutable, SpotClientKind, SpotclientExecutableExt},
 };
 use rust_decimal::{
     prelude::{FromPrimitive, ToPrimitive},
-    Decimal, MathematicalOps,
+    Decimal, MathematicalOps, RoundingStrategy,
 };
 use rust_decimal_macros::dec;
 use serde::{Deserialize, Serialize};
@@ -25,19 +27,43 @@ use std::{
     sync::atomic::{AtomicBool, Ordering},
 };
 
+// 自适应网格保留的重算记录数量
+const MAX_RECALCULATIONS: usize = 100;
+
+// 资金预算与当前权益的偏离超过该比例时才调整投资金额
+const REALLOCATE_THRESHOLD: Decimal = dec!(0.01);
+
 /// 网格交易
 /// inputs:
 ///     0: SpotPairInfo
 ///     1: SpotClientKind
 ///     2: TickStream
+///     3: CapitalAllocation (可选，由多策略资金分配节点调整投资金额)
 #[derive(Debug)]
 #[allow(unused)]
 pub(crate) struct SpotGrid {
     params: Params,
     store: RuntimeStore,
+    window: Option<KlinesWindow>, // 自适应网格计算ATR的K线窗口，不持久化
     infra: NodeInfra,
 }
 
+impl NodeMeta for SpotGrid {
+    const METADATA: NodeMetadata = NodeMetadata {
+        prop_type: "strategy.SpotGrid",
+        display_name: "网格(现货)",
+        category: NodeCategory::Strategy,
+        inputs: &[
+            SPOT_PAIR_INFO,
+            SPOT_CLIENT,
+            TICK_STREAM,
+            CAPITAL_ALLOCATION.optional(),
+        ],
+        outputs: &[],
+        icon: "grid",
+    };
+}
+
 impl NodeCore for SpotGrid {
     fn node_infra(&self) -> &NodeInfra {
         &self.infra
@@ -67,6 +93,7 @@ impl SpotGrid {
         Ok(Self {
             params,
             store,
+            window: None,
             infra,
         })
     }
@@ -77,14 +104,12 @@ impl SpotGrid {
         client: &SpotClientKind,
         tick_stream: &TickStream,
     ) -> Result<()> {
-        // 获取初始化价格
-        let (_, _, tick) = tick_stream.subscribe().recv_async().await?;
-        let initial_price = tick.price;
-
-        // 如果已经初始化，则跳过
-        if self.store.initialized {
-            return Ok(());
-        }
+        // 加载账户级的交易对黑白名单，恢复运行时同样需要检查
+        let ctx = self.node_context()?;
+        let symbol_rules =
+            SymbolRules::load(ctx.db(), &client.account_id(), &client.exchange()).await?;
+        ctx.resource_meter()
+            .add_db_rows_read(symbol_rules.len() as u64);
 
         // 创建客户端服务
         let mut spot_client_service = SpotClientService::builder()
@@ -92,8 +117,26 @@ impl SpotGrid {
             .retry_max_retries(3)
             .retry_wait_secs(3)
             .timeout_secs(10)
+            .symbol_rules(symbol_rules)
             .build();
 
+        // 检查交易对是否允许交易
+        spot_client_service.ensure_symbol_allowed(&pair_info.base_asset, &pair_info.quote_asset)?;
+
+        // 获取初始化价格
+        let (_, _, initial_tick) = tick_stream.subscribe().recv_async().await?;
+
+        // 自适应网格需要K线窗口计算ATR，恢复运行时也需要重新加载
+        if self.params.mode == Mode::Adaptive {
+            self.init_klines_window(client, pair_info, &initial_tick)
+                .await;
+        }
+
+        // 如果已经初始化，则跳过
+        if self.store.initialized {
+            return Ok(());
+        }
+
         // 获取账户信息
         let account = spot_client_service.get_account().await?;
 
@@ -135,34 +178,69 @@ impl SpotGrid {
                 &symbol,
                 &dec!(0),
                 &self.params.investment,
-                &initial_price,
+                &initial_tick,
             )
             .await?;
 
-        // 计算网格价格
-        let grid_prices = calc_grid_prices(
-            self.params.mode,
+        // 计算网格价格，自适应网格在ATR不可用时按等差网格创建
+        let atr = self.atr();
+        let grid_prices = match atr {
+            Some(atr) => calc_adaptive_grid_prices(
+                self.params.lower_price,
+                self.params.upper_price,
+                atr * self.params.adaptive.atr_multiplier,
+                self.params.grid_rows,
+                symbol_info.quote_asset_precision,
+            ),
+            None => calc_grid_prices(
+                self.params.mode,
+                self.params.lower_price,
+                self.params.upper_price,
+                self.params.grid_rows,
+                symbol_info.quote_asset_precision,
+            ),
+        };
+
+        // 交易对单独设置的手续费优先，零手续费交易对卖出时不扣除手续费
+        let (_, commission_rate) = symbol_info.commission_rates(&account);
+
+        // 每格利润率预览
+        let profit_rate = calculate_grid_profit(
+            if atr.is_some() {
+                Mode::Arithmetic
+            } else {
+                self.params.mode
+            },
             self.params.lower_price,
             self.params.upper_price,
-            self.params.grid_rows,
-            symbol_info.quote_asset_precision,
+            commission_rate,
+            grid_prices.len().saturating_sub(1) as u64,
+        );
+        tracing::info!(
+            "SpotGrid profit rate per grid: {:?}, commission rate: {}",
+            profit_rate,
+            commission_rate
         );
 
         // 获取当前价格
         let (_, _, tick) = tick_stream.subscribe().recv_async().await?;
 
         // 创建网格
-        let grid = Grid::builder()
+        let mut grid = Grid::builder()
             .exchange(exchange)
             .investment(self.params.investment)
             .grid_prices(grid_prices)
             .current_price(tick.price)
             .base_asset_precision(symbol_info.base_asset_precision)
             .quote_asset_precision(symbol_info.quote_asset_precision)
-            .commission_rate(account.taker_commission_rate)
+            .commission_rate(commission_rate)
             .trading_config((&self.params).into())
             .build();
 
+        if let Some(atr) = atr {
+            grid.record_recalculation(tick.timestamp, atr);
+        }
+
         self.store.grid = Some(grid);
 
         // 初始化完成
@@ -171,6 +249,139 @@ impl SpotGrid {
         Ok(())
     }
 
+    // 用历史K线初始化ATR窗口，加载失败时只用之后的tick聚合
+    async fn init_klines_window(
+        &mut self,
+        client: &SpotClientKind,
+        pair_info: &SpotPairInfo,
+        initial_tick: &Tick,
+    ) {
+        let adaptive = &self.params.adaptive;
+        let exchange = client.exchange();
+        let symbol = client.symbol(&pair_info.base_asset, &pair_info.quote_asset);
+        let interval = KlineInterval::from(adaptive.atr_interval.as_str());
+        let len = adaptive.atr_period + 1;
+
+        let mut window = match self.klines_window(&exchange, &symbol, &interval, len).await {
+            Ok(window) => window,
+            Err(e) => {
+                tracing::warn!("SpotGrid load klines window failed: {}", e);
+                KlinesWindow::new(symbol, interval, len)
+            }
+        };
+
+        window.update_with_tick(initial_tick);
+        self.window = Some(window);
+    }
+
+    fn atr(&self) -> Option<Decimal> {
+        self.window
+            .as_ref()?
+            .atr(self.params.adaptive.atr_period)
+            .filter(|atr| *atr > Decimal::ZERO)
+    }
+
+    // 按最新的ATR重新计算网格间距，只在没有持仓且未锁定时重建网格
+    fn recalculate_adaptive_grid(&mut self, tick: &Tick) -> Result<()> {
+        let Some(window) = self.window.as_mut() else {
+            return Ok(());
+        };
+
+        window.update_with_tick(tick);
+
+        let recalc_secs = self.params.adaptive.recalc_secs;
+        let grid = self.grid()?;
+
+        if tick.timestamp - grid.adaptive.last_recalc_at < recalc_secs {
+            return Ok(());
+        }
+
+        if grid.has_position() || grid.locked.load(Ordering::Relaxed) {
+            tracing::debug!("SpotGrid adaptive recalculation deferred, grid has position");
+            return Ok(());
+        }
+
+        let Some(atr) = self.atr() else {
+            return Ok(());
+        };
+
+        let grid_prices = calc_adaptive_grid_prices(
+            self.params.lower_price,
+            self.params.upper_price,
+            atr * self.params.adaptive.atr_multiplier,
+            self.params.grid_rows,
+            self.grid()?.spec.quote_asset_precision,
+        );
+
+        let grid = self.grid_mut()?;
+
+        if grid.grid_prices() != grid_prices {
+            grid.rebuild(grid_prices, tick.price);
+        }
+
+        let recalculation = grid.record_recalculation(tick.timestamp, atr);
+
+        tracing::info!(
+            monotonic_counter.spot_grid_recalculated = 1_u64,
+            atr = %recalculation.atr,
+            spacing = %recalculation.spacing,
+            rows = recalculation.rows,
+            "SpotGrid adaptive spacing recalculated"
+        );
+
+        Ok(())
+    }
+
+    // 上报权益并领取资金分配节点的预算，只在网格没有持仓时按预算追加或提取资金并重建网格
+    async fn reallocate(&mut self, allocation: &CapitalAllocation, tick: &Tick) -> Result<()> {
+        let ctx = self.node_context()?;
+        let (exchange, _, symbol) = self.exchange_pair_symbol()?;
+        let data = self.spot_stats_data(&exchange, &symbol)?;
+        let equity = data.quote_asset_balance
+            + ctx
+                .valuation_policy()
+                .value(&data.base.base_asset, data.base_asset_balance * tick.price);
+
+        let Some(budget) = allocation.report(self.node().id, equity, tick.timestamp) else {
+            return Ok(());
+        };
+
+        let grid = self.grid()?;
+
+        if grid.has_position() || grid.locked.load(Ordering::Relaxed) || equity <= dec!(0) {
+            return Ok(());
+        }
+
+        let amount = budget - equity;
+
+        if (amount / equity).abs() < REALLOCATE_THRESHOLD {
+            return Ok(());
+        }
+
+        if amount > dec!(0) {
+            self.store
+                .stats
+                .deposit(&ctx, &exchange, &symbol, amount)
+                .await?;
+        } else {
+            self.store
+                .stats
+                .withdraw(&ctx, &exchange, &symbol, -amount)
+                .await?;
+        }
+
+        self.grid_mut()?.reallocate(budget, tick.price);
+
+        tracing::info!(
+            monotonic_counter.spot_grid_reallocated = 1_u64,
+            equity = %equity,
+            budget = %budget,
+            "SpotGrid investment reallocated"
+        );
+
+        Ok(())
+    }
+
     fn grid(&self) -> Result<&Grid> {
         self.store
             .grid
@@ -196,26 +407,140 @@ impl SpotGrid {
 
         Ok((exchange, pair_info, symbol))
     }
+
+    // 记录交易信号到回测事件日志
+    fn record_signal(&self, tick: &Tick, signal: &TradeSignal) -> Result<()> {
+        let ctx = self.node_context()?;
+
+        if !ctx.event_log_enabled() {
+            return Ok(());
+        }
+
+        let (exchange, _, symbol) = self.exchange_pair_symbol()?;
+        let (side, qty) = match signal {
+            TradeSignal::Buy { quantity, .. } => (Some("Buy"), Some(*quantity)),
+            TradeSignal::Sell { quantity, .. } => (Some("Sell"), Some(*quantity)),
+            TradeSignal::StopLoss { sell_all_on_stop } => {
+                (sell_all_on_stop.then_some("Sell"), None)
+            }
+            TradeSignal::TakeProfit => (Some("Sell"), None),
+        };
+
+        ctx.record_event(
+            Event::builder()
+                .time(tick.timestamp * 1000)
+                .kind(EventKind::Signal)
+                .node_id(ctx.node_id())
+                .exchange(exchange.as_ref())
+                .symbol(symbol.as_ref())
+                .maybe_side(side.map(String::from))
+                .price(tick.price)
+                .maybe_qty(qty)
+                .detail(format!("{:?}", signal))
+                .build(),
+        );
+
+        Ok(())
+    }
+
+    // 发布止损、止盈触发事件，供告警节点通知，同时写入策略日志，日志写入失败不影响平仓
+    async fn publish_stop(&self, kind: &str, price: Decimal) -> Result<()> {
+        let (exchange, _, symbol) = self.exchange_pair_symbol()?;
+
+        self.workflow_context()?
+            .cloned_event_bus()
+            .publish(WorkflowEvent::StopTriggered {
+                node_id: self.node().id,
+                exchange: exchange.to_string(),
+                symbol: symbol.to_string(),
+                kind: kind.to_string(),
+                price,
+            });
+
+        let content = format!("{} {} triggered at {}", symbol, kind, price);
+
+        if let Err(e) = self.node_context()?.journal(content, &[kind]).await {
+            tracing::error!("SpotGrid write journal failed: {}", e);
+        }
+
+        Ok(())
+    }
 }
 
 // 节点执行
 impl NodeExecutable for SpotGrid {
-    async fn execute(&mut self) -> Result<()> {
+    async fn execute(&mut self) -> Result<(), NodeError> {
+        Ok(self.run().await?)
+    }
+
+    async fn shutdown(&mut self, cancel_orders: bool) -> Result<()> {
+        if cancel_orders {
+            let pair_info = self.port().input::<SpotPairInfo>(0)?;
+            let client = self.port().input::<SpotClientKind>(1)?;
+
+            self.cancel_open_orders(&client, &pair_info.base_asset, &pair_info.quote_asset)
+                .await?;
+        }
+
+        self.save_runtime_store(&self.store)
+    }
+}
+
+impl SpotGrid {
+    async fn run(&mut self) -> Result<()> {
         // 获取输入
         let pair_info = self.port().input::<SpotPairInfo>(0)?;
         let client = self.port().input::<SpotClientKind>(1)?;
         let tick_stream = self.port().input::<TickStream>(2)?;
+        let allocation = self.port().input::<CapitalAllocation>(3).ok();
         let rx = tick_stream.subscribe();
 
         self.create_grid(&pair_info, &client, &tick_stream).await?;
 
+        // 从检查点恢复时补记停机期间的成交
+        self.reconcile_open_orders(&client, &pair_info.base_asset, &pair_info.quote_asset)
+            .await?;
+
         self.grid()?.start();
+        self.save_runtime_store(&self.store)?;
+
+        let heartbeat = self.heartbeat();
+        // 挂单的后续成交由交易所推送
+        let mut user_data = client.subscribe_user_data();
+
+        loop {
+            let next = tokio::select! {
+                next = tick_stream.next(&rx) => next,
+                event = next_user_data(&mut user_data) => {
+                    let _busy = heartbeat.busy();
+                    self.update_spot_stats_with_user_data(&event).await?;
+                    self.save_runtime_store(&self.store)?;
+                    continue;
+                }
+            };
+
+            let Some((_, _, tick)) = next else {
+                break;
+            };
+
+            let _busy = heartbeat.busy();
+
+            // 自适应网格定期重算间距
+            if self.params.mode == Mode::Adaptive {
+                self.recalculate_adaptive_grid(&tick)?;
+            }
+
+            // 按多策略资金分配调整投资金额
+            if let Some(allocation) = &allocation {
+                self.reallocate(allocation, &tick).await?;
+            }
 
-        while let Ok((_, _, tick)) = rx.recv_async().await {
             let Some(signal) = self.grid_mut()?.evaluate_with_price(tick.price) else {
                 continue;
             };
 
+            self.record_signal(&tick, &signal)?;
+
             match signal {
                 // 买入
                 TradeSignal::Buy { quantity, .. } => {
@@ -233,11 +558,10 @@ impl NodeExecutable for SpotGrid {
                     match order_result {
                         Ok(order) => {
                             self.grid_mut()?.update_with_order(&signal, &order);
-                            tracing::info!("SpotGrid buy order: {:?}", order);
                         }
                         Err(e) => {
                             self.grid()?.unlock();
-                            tracing::error!("SpotGrid buy order failed: {}", e);
+                            self.publish_error(format!("SpotGrid buy order failed: {}", e));
                         }
                     }
                 }
@@ -258,17 +582,18 @@ impl NodeExecutable for SpotGrid {
                     match order_result {
                         Ok(order) => {
                             self.grid_mut()?.update_with_order(&signal, &order);
-                            tracing::info!("SpotGrid sell order: {:?}", order);
                         }
                         Err(e) => {
                             self.grid()?.unlock();
-                            tracing::error!("SpotGrid sell order failed: {}", e);
+                            self.publish_error(format!("SpotGrid sell order failed: {}", e));
                         }
                     }
                 }
 
                 // 止损
                 TradeSignal::StopLoss { sell_all_on_stop } => {
+                    self.publish_stop("stop_loss", tick.price).await?;
+
                     if !sell_all_on_stop {
                         continue;
                     }
@@ -291,17 +616,34 @@ impl NodeExecutable for SpotGrid {
                         Ok(order) => {
                             self.grid_mut()?.update_with_order(&signal, &order);
                             self.grid()?.stop();
-                            tracing::info!("SpotGrid sell all order: {:?}", order);
+
+                            // 核销卖出后无法交易的残余余额
+                            if let Err(e) = self
+                                .write_off_dust(
+                                    &client,
+                                    &pair_info.base_asset,
+                                    &pair_info.quote_asset,
+                                    tick.price,
+                                )
+                                .await
+                            {
+                                self.publish_error(format!(
+                                    "SpotGrid write off dust failed: {}",
+                                    e
+                                ));
+                            }
                         }
                         Err(e) => {
                             self.grid()?.unlock();
-                            tracing::error!("SpotGrid sell all order failed: {}", e);
+                            self.publish_error(format!("SpotGrid sell all order failed: {}", e));
                         }
                     }
                 }
 
                 // 止盈
                 TradeSignal::TakeProfit => {
+                    self.publish_stop("take_profit", tick.price).await?;
+
                     let Ok(balance) = client.get_balance(&pair_info.base_asset).await else {
                         self.grid()?.unlock();
                         continue;
@@ -320,11 +662,26 @@ impl NodeExecutable for SpotGrid {
                         Ok(order) => {
                             self.grid_mut()?.update_with_order(&signal, &order);
                             self.grid()?.stop();
-                            tracing::info!("SpotGrid take profit order: {:?}", order);
+
+                            // 核销卖出后无法交易的残余余额
+                            if let Err(e) = self
+                                .write_off_dust(
+                                    &client,
+                                    &pair_info.base_asset,
+                                    &pair_info.quote_asset,
+                                    tick.price,
+                                )
+                                .await
+                            {
+                                self.publish_error(format!(
+                                    "SpotGrid write off dust failed: {}",
+                                    e
+                                ));
+                            }
                         }
                         Err(e) => {
                             self.grid()?.unlock();
-                            tracing::error!("SpotGrid take profit order failed: {}", e);
+                            self.publish_error(format!("SpotGrid take profit order failed: {}", e));
                         }
                     }
                 }
@@ -336,6 +693,9 @@ impl NodeExecutable for SpotGrid {
             // 更新统计信息
             self.update_spot_stats_with_tick(&exchange, &symbol, &tick)
                 .await?;
+
+            // 成交后保存运行时数据，供工作流检查点使用
+            self.save_runtime_store(&self.store)?;
         }
 
         Ok(())
@@ -350,7 +710,10 @@ impl TradeStats for SpotGrid {
         let exchange_rate = ctx.exchange_rate(&pair.quote_asset, &quote_asset).await?;
         let stats = self.spot_stats_data(&exchange, &symbol)?;
         let price = self.price(&exchange, &Market::Spot, &symbol).await?;
-        let capital = stats.initial_base_balance * price + stats.initial_base_balance;
+        let base_value = ctx
+            .valuation_policy()
+            .value(&pair.base_asset, stats.initial_base_balance * price);
+        let capital = base_value + stats.initial_quote_balance;
 
         Ok(capital * exchange_rate.rate())
     }
@@ -374,7 +737,10 @@ impl TradeStats for SpotGrid {
         let price = self.price(&exchange, &Market::Spot, &symbol).await?;
         let maker_commission_rate = Decimal::ONE - stats.base.maker_commission_rate;
         let cost = stats.base_asset_balance * stats.avg_price;
-        let maybe_sell = stats.base_asset_balance * price * maker_commission_rate;
+        let maybe_sell = ctx.valuation_policy().value(
+            &pair.base_asset,
+            stats.base_asset_balance * price * maker_commission_rate,
+        );
         let unrealized_pnl = maybe_sell - cost;
 
         Ok(unrealized_pnl * exchange_rate.rate())
@@ -383,6 +749,46 @@ impl TradeStats for SpotGrid {
     async fn running_time(&self) -> Result<u128> {
         Ok(self.workflow_context()?.running_time().await)
     }
+
+    async fn time_weighted_return(&self) -> Result<Decimal> {
+        let ctx = self.workflow_context()?;
+        let (exchange, _, symbol) = self.exchange_pair_symbol()?;
+        let stats = self.spot_stats_data(&exchange, &symbol)?;
+
+        Ok(stats.time_weighted_return(ctx.valuation_policy()))
+    }
+
+    async fn money_weighted_return(&self) -> Result<Decimal> {
+        let ctx = self.workflow_context()?;
+        let (exchange, _, symbol) = self.exchange_pair_symbol()?;
+        let stats = self.spot_stats_data(&exchange, &symbol)?;
+
+        Ok(stats
+            .money_weighted_return(ctx.valuation_policy())
+            .unwrap_or_default())
+    }
+
+    async fn equity_curve(&self) -> Result<Vec<(i64, Decimal)>> {
+        let ctx = self.workflow_context()?;
+        let (exchange, pair, symbol) = self.exchange_pair_symbol()?;
+        let quote_asset = ctx.quote_asset().await;
+        let exchange_rate = ctx.exchange_rate(&pair.quote_asset, &quote_asset).await?;
+        let stats = self.spot_stats_data(&exchange, &symbol)?;
+
+        // 历史市值按当前汇率折算
+        Ok(stats
+            .equity_curve
+            .iter()
+            .map(|(timestamp, equity)| (*timestamp, equity * exchange_rate.rate()))
+            .collect())
+    }
+
+    async fn trade_record(&self) -> Result<TradeRecord> {
+        let (exchange, _, symbol) = self.exchange_pair_symbol()?;
+        let stats = self.spot_stats_data(&exchange, &symbol)?;
+
+        Ok(stats.trades.clone())
+    }
 }
 
 impl TryFrom<Node> for SpotGrid {
@@ -415,6 +821,29 @@ pub(crate) struct Params {
     stop_loss: Option<Decimal>,     // 止损价格
     take_profit: Option<Decimal>,   // 止盈价格
     sell_all_on_stop: bool,         // 是否在止损时卖出所有基准币，默认为true
+    #[builder(default)]
+    #[serde(default)]
+    adaptive: AdaptiveConfig, // 自适应网格配置，仅 adaptive 模式使用
+}
+
+// 自适应网格配置，网格间距 = ATR * atr_multiplier，网格数量限制在 2..=grid_rows
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub(crate) struct AdaptiveConfig {
+    atr_interval: String,    // ATR的K线周期，默认 1h
+    atr_period: usize,       // ATR的周期，默认 14
+    atr_multiplier: Decimal, // 间距相对ATR的倍数，默认 1
+    recalc_secs: i64,        // 重算间隔(秒)，默认 3600
+}
+
+impl Default for AdaptiveConfig {
+    fn default() -> Self {
+        AdaptiveConfig {
+            atr_interval: "1h".to_string(),
+            atr_period: 14,
+            atr_multiplier: dec!(1),
+            recalc_secs: 3600,
+        }
+    }
 }
 
 impl TryFrom<&Node> for Params {
@@ -425,7 +854,7 @@ impl TryFrom<&Node> for Params {
             return Err(SpotGridError::PropertyTypeMismatch);
         }
 
-        let [mode, lower_price, upper_price, grid_rows, investment, trigger_price, stop_loss, take_profit, sell_all_on_stop] =
+        let [mode, lower_price, upper_price, grid_rows, investment, trigger_price, stop_loss, take_profit, sell_all_on_stop, adaptive @ ..] =
             node.properties.params.as_slice()
         else {
             return Err(SpotGridError::ParamsFormatError);
@@ -461,6 +890,44 @@ impl TryFrom<&Node> for Params {
 
         let sell_all_on_stop = sell_all_on_stop.as_bool().unwrap_or(true);
 
+        // 可选参数: atr_interval, atr_period, atr_multiplier, recalc_secs
+        let mut adaptive_config = AdaptiveConfig::default();
+
+        if let Some(atr_interval) = adaptive.first().and_then(|v| v.as_str()) {
+            if !atr_interval.is_empty() {
+                if KlineInterval::from(atr_interval).as_ref() != atr_interval {
+                    return Err(SpotGridError::AdaptiveError);
+                }
+
+                adaptive_config.atr_interval = atr_interval.to_string();
+            }
+        }
+
+        if let Some(atr_period) = adaptive.get(1).and_then(|v| v.as_u64()) {
+            if atr_period == 0 {
+                return Err(SpotGridError::AdaptiveError);
+            }
+
+            adaptive_config.atr_period = atr_period as usize;
+        }
+
+        if let Some(atr_multiplier) = adaptive.get(2).and_then(|v| v.as_f64()) {
+            adaptive_config.atr_multiplier =
+                Decimal::from_f64(atr_multiplier).ok_or(SpotGridError::AdaptiveError)?;
+
+            if adaptive_config.atr_multiplier <= Decimal::ZERO {
+                return Err(SpotGridError::AdaptiveError);
+            }
+        }
+
+        if let Some(recalc_secs) = adaptive.get(3).and_then(|v| v.as_i64()) {
+            if recalc_secs <= 0 {
+                return Err(SpotGridError::AdaptiveError);
+            }
+
+            adaptive_config.recalc_secs = recalc_secs;
+        }
+
         if lower_price >= upper_price {
             return Err(SpotGridError::PriceRangeError);
         }
@@ -479,6 +946,7 @@ impl TryFrom<&Node> for Params {
             .maybe_stop_loss(stop_loss)
             .maybe_take_profit(take_profit)
             .sell_all_on_stop(sell_all_on_stop)
+            .adaptive(adaptive_config)
             .build();
 
         Ok(params)
@@ -510,6 +978,9 @@ pub enum SpotGridError {
 
     #[error("Invalid investment")]
     InvestmentError,
+
+    #[error("Invalid adaptive params")]
+    AdaptiveError,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -547,6 +1018,8 @@ pub(crate) enum Mode {
     Arithmetic,
     // 等比
     Geometric,
+    // 根据ATR自适应间距
+    Adaptive,
 }
 
 impl FromStr for Mode {
@@ -556,6 +1029,7 @@ impl FromStr for Mode {
         let mode = match s {
             "arithmetic" => Mode::Arithmetic,
             "geometric" => Mode::Geometric,
+            "adaptive" => Mode::Adaptive,
             _ => anyhow::bail!("Invalid mode: {}", s),
         };
 
@@ -574,6 +1048,33 @@ pub(crate) struct Grid {
     running: AtomicBool,           // 是否运行
     locked: AtomicBool,            // 是否锁定
     trading_config: TradingConfig, // 交易配置
+    #[serde(default)]
+    spec: GridSpec, // 创建网格的参数，用于重建网格
+    #[serde(default)]
+    adaptive: AdaptiveState, // 自适应网格状态
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct GridSpec {
+    investment: Decimal,        // 投资金额
+    base_asset_precision: u32,  // 基础币种小数点位数
+    quote_asset_precision: u32, // 报价币种小数点位数
+    commission_rate: Decimal,   // 手续费
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct AdaptiveState {
+    last_recalc_at: i64,                    // 上次重算时间(秒)
+    recalculations: Vec<GridRecalculation>, // 重算记录
+}
+
+// 自适应网格的重算记录
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub(crate) struct GridRecalculation {
+    timestamp: i64,   // 重算时间(秒)
+    atr: Decimal,     // 平均真实波幅
+    spacing: Decimal, // 平均网格间距
+    rows: usize,      // 网格数量
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -617,33 +1118,15 @@ impl Grid {
         commission_rate: Decimal,            // 手续费
         trading_config: TradingConfig,
     ) -> Self {
-        let grid_investment = (investment / (Decimal::from(grid_prices.len()) - dec!(1)))
-            .round_dp(quote_asset_precision);
-
-        let rows = grid_prices
-            .windows(2)
-            .enumerate()
-            .map(|(i, w)| {
-                let buy_quantity = (grid_investment / w[0]).round_dp(base_asset_precision);
-                let sell_quantity =
-                    (buy_quantity * (dec!(1) - commission_rate)).round_dp(base_asset_precision);
-
-                GridRow::builder()
-                    .index(i)
-                    .buy_price(w[0])
-                    .buy_quantity(buy_quantity)
-                    .sell_price(w[1])
-                    .sell_quantity(sell_quantity)
-                    .buyed(false)
-                    .sold(false)
-                    .build()
-            })
-            .collect::<Vec<_>>();
+        let spec = GridSpec {
+            investment,
+            base_asset_precision,
+            quote_asset_precision,
+            commission_rate,
+        };
 
-        let cursor = rows
-            .iter()
-            .position(|r| r.buy_price <= current_price && r.sell_price >= current_price)
-            .unwrap_or(0);
+        let rows = spec.grid_rows(&grid_prices);
+        let cursor = locate_cursor(&rows, current_price);
 
         let prev_sell_price = dec!(0);
 
@@ -660,13 +1143,69 @@ impl Grid {
             running,
             locked,
             trading_config,
+            spec,
+            adaptive: AdaptiveState::default(),
         }
     }
 
+    // 网格价格
+    fn grid_prices(&self) -> Vec<Decimal> {
+        self.rows
+            .iter()
+            .map(|row| row.buy_price)
+            .chain(self.rows.last().map(|row| row.sell_price))
+            .collect()
+    }
+
+    // 是否有已买入未卖出的格子
+    fn has_position(&self) -> bool {
+        self.rows.iter().any(|row| row.buyed)
+    }
+
+    // 按新的网格价格重建网格，调用前需确认没有持仓
+    fn rebuild(&mut self, grid_prices: Vec<Decimal>, current_price: Decimal) {
+        self.rows = self.spec.grid_rows(&grid_prices);
+        self.cursor = locate_cursor(&self.rows, current_price);
+        self.prev_sell_price = dec!(0);
+    }
+
+    // 按新的投资金额重建网格，网格价格不变，调用前需确认没有持仓
+    fn reallocate(&mut self, investment: Decimal, current_price: Decimal) {
+        self.spec.investment = investment;
+        self.rebuild(self.grid_prices(), current_price);
+    }
+
+    // 记录自适应网格的重算
+    fn record_recalculation(&mut self, timestamp: i64, atr: Decimal) -> GridRecalculation {
+        let rows = self.rows.len();
+        let spacing = match (self.rows.first(), self.rows.last()) {
+            (Some(first), Some(last)) => (last.sell_price - first.buy_price) / Decimal::from(rows),
+            _ => Decimal::ZERO,
+        };
+
+        let recalculation = GridRecalculation {
+            timestamp,
+            atr,
+            spacing,
+            rows,
+        };
+
+        let state = &mut self.adaptive;
+        state.last_recalc_at = timestamp;
+        state.recalculations.push(recalculation.clone());
+
+        if state.recalculations.len() > MAX_RECALCULATIONS {
+            state.recalculations.remove(0);
+        }
+
+        recalculation
+    }
+
     fn should_buy(&self, price: Decimal, tolerance: Decimal) -> bool {
         let grid_row = self.current_grid_row();
 
         !grid_row.buyed // 当前格子未买入
+            && grid_row.buy_quantity > dec!(0) // 当前格子分配了资金
             && self.prev_sell_price != grid_row.buy_price // 上一次的卖出价格不等于当前的买入价格
             && price <= grid_row.buy_price
             && price > grid_row.buy_price * (dec!(1) - tolerance)
@@ -856,6 +1395,41 @@ pub(crate) struct GridRow {
     sold: bool,             // 是否已卖出
 }
 
+impl GridSpec {
+    // 根据网格价格计算每格的买卖数量
+    fn grid_rows(&self, grid_prices: &[Decimal]) -> Vec<GridRow> {
+        let grid_investment = (self.investment / (Decimal::from(grid_prices.len()) - dec!(1)))
+            .round_dp(self.quote_asset_precision);
+
+        grid_prices
+            .windows(2)
+            .enumerate()
+            .map(|(i, w)| {
+                let buy_quantity = (grid_investment / w[0]).round_dp(self.base_asset_precision);
+                let sell_quantity = (buy_quantity * (dec!(1) - self.commission_rate))
+                    .round_dp(self.base_asset_precision);
+
+                GridRow::builder()
+                    .index(i)
+                    .buy_price(w[0])
+                    .buy_quantity(buy_quantity)
+                    .sell_price(w[1])
+                    .sell_quantity(sell_quantity)
+                    .buyed(false)
+                    .sold(false)
+                    .build()
+            })
+            .collect()
+    }
+}
+
+// 当前价格所在的格子
+fn locate_cursor(rows: &[GridRow], current_price: Decimal) -> usize {
+    rows.iter()
+        .position(|r| r.buy_price <= current_price && r.sell_price >= current_price)
+        .unwrap_or(0)
+}
+
 // 计算网格价格
 fn calc_grid_prices(
     mode: Mode,                 // 网格模式
@@ -865,7 +1439,8 @@ fn calc_grid_prices(
     quote_asset_precision: u32, // 小数点位数
 ) -> Vec<Decimal> {
     match mode {
-        Mode::Arithmetic => {
+        // 自适应网格在ATR不可用时按等差网格计算
+        Mode::Arithmetic | Mode::Adaptive => {
             let step = (upper_price - lower_price) / Decimal::from(grid_rows);
             (0..=grid_rows)
                 .map(|i| (lower_price + step * Decimal::from(i)).round_dp(quote_asset_precision))
@@ -881,6 +1456,33 @@ fn calc_grid_prices(
     }
 }
 
+// 根据目标间距计算等差网格价格，网格数量限制在 2..=max_rows
+fn calc_adaptive_grid_prices(
+    lower_price: Decimal,       // 网格下界
+    upper_price: Decimal,       // 网格上界
+    spacing: Decimal,           // 目标间距
+    max_rows: u64,              // 最大网格数量
+    quote_asset_precision: u32, // 小数点位数
+) -> Vec<Decimal> {
+    let grid_rows = if spacing > Decimal::ZERO {
+        ((upper_price - lower_price) / spacing)
+            .floor()
+            .to_u64()
+            .unwrap_or(max_rows)
+            .clamp(2, max_rows)
+    } else {
+        max_rows
+    };
+
+    calc_grid_prices(
+        Mode::Arithmetic,
+        lower_price,
+        upper_price,
+        grid_rows,
+        quote_asset_precision,
+    )
+}
+
 #[derive(Debug, PartialEq, Clone)]
 #[allow(unused)]
 enum GridProfitRate {
@@ -893,40 +1495,42 @@ enum GridProfitRate {
     },
 }
 
-// 计算网格的每格利润率
+// 计算网格的每格利润率，零手续费交易对传入的手续费为0
 // 参考资料：https://www.binance.com/zh-CN/support/faq/币安现货网格交易的参数说明-688ff6ff08734848915de76a07b953dd
-// #[allow(unused)]
-// fn calculate_grid_profit(
-//     mode: Mode,                // 网格模式
-//     lower_price: Decimal,      // 网格下界
-//     upper_price: Decimal,      // 网格上界
-//     taker_commission: Decimal, // 手续费
-//     grid_rows: u64,            // 网格数量
-// ) -> GridProfitRate {
-//     match mode {
-//         Mode::Arithmetic => {
-//             let step = (upper_price - lower_price) / grid_rows as Decimal;
-//             let max_profit_rate =
-//                 (1. - taker_commission) * step / lower_price - 2. * taker_commission;
-//             let min_profit_rate = (upper_price * (1. - taker_commission)) / (upper_price - step)
-//                 - 1.
-//                 - taker_commission;
-
-//             GridProfitRate::Arithmetic {
-//                 min_rate: floor_to(min_profit_rate, 4),
-//                 max_rate: floor_to(max_profit_rate, 4),
-//             }
-//         }
-//         Mode::Geometric => {
-//             let step = (upper_price / lower_price).powf(1.0 / grid_rows as Decimal);
-//             let profit_rate = (1. - taker_commission) * step - 1. - taker_commission;
-
-//             GridProfitRate::Geometric {
-//                 rate: floor_to(profit_rate, 4),
-//             }
-//         }
-//     }
-// }
+fn calculate_grid_profit(
+    mode: Mode,               // 网格模式
+    lower_price: Decimal,     // 网格下界
+    upper_price: Decimal,     // 网格上界
+    commission_rate: Decimal, // 手续费
+    grid_rows: u64,           // 网格数量
+) -> GridProfitRate {
+    let floor_to =
+        |rate: Decimal| rate.round_dp_with_strategy(4, RoundingStrategy::ToNegativeInfinity);
+
+    match mode {
+        Mode::Arithmetic | Mode::Adaptive => {
+            let step = (upper_price - lower_price) / Decimal::from(grid_rows);
+            let max_rate =
+                (dec!(1) - commission_rate) * step / lower_price - dec!(2) * commission_rate;
+            let min_rate = upper_price * (dec!(1) - commission_rate) / (upper_price - step)
+                - dec!(1)
+                - commission_rate;
+
+            GridProfitRate::Arithmetic {
+                min_rate: floor_to(min_rate),
+                max_rate: floor_to(max_rate),
+            }
+        }
+        Mode::Geometric => {
+            let step = (upper_price / lower_price).powf(1. / grid_rows as f64);
+            let rate = (dec!(1) - commission_rate) * step - dec!(1) - commission_rate;
+
+            GridProfitRate::Geometric {
+                rate: floor_to(rate),
+            }
+        }
+    }
+}
 
 #[allow(unused)]
 fn calculate_minimum_investment(
@@ -1041,26 +1645,156 @@ mod tests {
         let grid_prices = calc_grid_prices(Mode::Geometric, dec!(4.0), dec!(20.0), 2, 3);
         assert_eq!(grid_prices, vec![dec!(4.0), dec!(8.944), dec!(20.0)]);
 
+        // 有手续费时卖出数量扣除买入手续费，零手续费交易对全部卖出
+        let mut spec = GridSpec {
+            investment: dec!(100),
+            base_asset_precision: 3,
+            quote_asset_precision: 3,
+            commission_rate: dec!(0.001),
+        };
+        let rows = spec.grid_rows(&grid_prices);
+        assert_eq!(rows[0].buy_quantity, dec!(12.5));
+        assert_eq!(rows[0].sell_quantity, dec!(12.488));
+
+        spec.commission_rate = dec!(0);
+        let rows = spec.grid_rows(&grid_prices);
+        assert!(rows.iter().all(|r| r.sell_quantity == r.buy_quantity));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_adaptive_grid() -> Result<()> {
+        let json_str = r#"{"id":4,"type":"交易策略/网格(现货)","pos":[367,125],"order":1,"mode":0,"properties":{"type":"strategy.SpotGrid","params":["adaptive",4,20,10,1000,"","","",true,"4h",20,1.5,600]}}"#;
+        let node: Node = serde_json::from_str(json_str)?;
+        let params = Params::try_from(&node)?;
+
+        assert_eq!(params.mode, Mode::Adaptive);
+        assert_eq!(
+            params.adaptive,
+            AdaptiveConfig {
+                atr_interval: "4h".to_string(),
+                atr_period: 20,
+                atr_multiplier: dec!(1.5),
+                recalc_secs: 600,
+            }
+        );
+
+        let json_str = r#"{"id":4,"type":"交易策略/网格(现货)","pos":[367,125],"order":1,"mode":0,"properties":{"type":"strategy.SpotGrid","params":["adaptive",4,20,10,1000,"","","",true,"2x"]}}"#;
+        let node: Node = serde_json::from_str(json_str)?;
+        assert!(Params::try_from(&node).is_err());
+
+        // 间距 4，网格数量 4
+        let grid_prices = calc_adaptive_grid_prices(dec!(4), dec!(20), dec!(4), 10, 3);
+        assert_eq!(
+            grid_prices,
+            vec![dec!(4), dec!(8), dec!(12), dec!(16), dec!(20)]
+        );
+
+        // 网格数量限制在 2..=max_rows
+        assert_eq!(
+            calc_adaptive_grid_prices(dec!(4), dec!(20), dec!(0.1), 10, 3).len(),
+            11
+        );
+        assert_eq!(
+            calc_adaptive_grid_prices(dec!(4), dec!(20), dec!(100), 10, 3).len(),
+            3
+        );
+
+        let mut grid = Grid::builder()
+            .exchange("Test")
+            .investment(params.investment)
+            .grid_prices(calc_grid_prices(params.mode, dec!(4), dec!(20), 10, 3))
+            .base_asset_precision(2)
+            .quote_asset_precision(3)
+            .current_price(dec!(9))
+            .commission_rate(dec!(0.001))
+            .trading_config((&params).into())
+            .build();
+
+        assert_eq!(grid.rows.len(), 10);
+        assert!(!grid.has_position());
+
+        grid.rebuild(grid_prices.clone(), dec!(9));
+        let recalculation = grid.record_recalculation(3600, dec!(2.5));
+
+        assert_eq!(grid.grid_prices(), grid_prices);
+        assert_eq!(grid.cursor, 1);
+        assert_eq!(grid.current_grid_row().buy_quantity, dec!(31.25));
+        assert_eq!(recalculation.spacing, dec!(4));
+        assert_eq!(recalculation.rows, 4);
+        assert_eq!(grid.adaptive.last_recalc_at, 3600);
+        assert_eq!(grid.adaptive.recalculations, vec![recalculation]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_grid_reallocate() -> Result<()> {
+        let params = Params::builder()
+            .mode(Mode::Arithmetic)
+            .lower_price(dec!(90))
+            .upper_price(dec!(110))
+            .grid_rows(4)
+            .investment(dec!(1000))
+            .sell_all_on_stop(true)
+            .build();
+
+        let mut grid = Grid::builder()
+            .exchange("Test")
+            .investment(params.investment)
+            .grid_prices(vec![dec!(90), dec!(95), dec!(100), dec!(105), dec!(110)])
+            .base_asset_precision(4)
+            .quote_asset_precision(2)
+            .current_price(dec!(100))
+            .commission_rate(dec!(0))
+            .trading_config((&params).into())
+            .build();
+
+        assert_eq!(grid.rows[0].buy_quantity, dec!(2.7778));
+
+        // 网格价格不变，每格数量按新的投资金额计算
+        grid.reallocate(dec!(2000), dec!(100));
+        assert_eq!(grid.grid_prices().len(), 5);
+        assert_eq!(grid.rows[0].buy_quantity, dec!(5.5556));
+        assert_eq!(grid.cursor, 1);
+
+        // 预算为0时不再买入
+        grid.reallocate(dec!(0), dec!(100));
+        grid.start();
+        assert_eq!(grid.evaluate_with_price(dec!(100)), None);
+        assert_eq!(grid.evaluate_with_price(dec!(95)), None);
+
         Ok(())
     }
 
-    // #[test]
-    // fn test_calculate_grid_profit() -> Result<()> {
-    //     let profit =
-    //         calculate_grid_profit(Mode::Arithmetic, dec!(4.0), dec!(20.0), dec!(0.001), 10);
-    //     assert_eq!(
-    //         profit,
-    //         GridProfitRate::Arithmetic {
-    //             min_rate: 0.0848,
-    //             max_rate: 0.3976
-    //         }
-    //     );
-
-    //     let profit = calculate_grid_profit(Mode::Geometric, 4.0, 20.0, 0.001, 10);
-    //     assert_eq!(profit, GridProfitRate::Geometric { rate: 0.1724 });
-
-    //     Ok(())
-    // }
+    #[test]
+    fn test_calculate_grid_profit() -> Result<()> {
+        let profit =
+            calculate_grid_profit(Mode::Arithmetic, dec!(4.0), dec!(20.0), dec!(0.001), 10);
+        assert_eq!(
+            profit,
+            GridProfitRate::Arithmetic {
+                min_rate: dec!(0.0848),
+                max_rate: dec!(0.3976)
+            }
+        );
+
+        let profit = calculate_grid_profit(Mode::Geometric, dec!(4.0), dec!(20.0), dec!(0.001), 10);
+        assert_eq!(profit, GridProfitRate::Geometric { rate: dec!(0.1724) });
+
+        // 零手续费交易对
+        let profit = calculate_grid_profit(Mode::Arithmetic, dec!(4.0), dec!(20.0), dec!(0), 10);
+        assert_eq!(
+            profit,
+            GridProfitRate::Arithmetic {
+                min_rate: dec!(0.0869),
+                max_rate: dec!(0.4)
+            }
+        );
+
+        Ok(())
+    }
 
     #[test]
     fn test_grid_logic() -> Result<()> {
@@ -0,0 +1,225 @@
+use crate::{
+    node_core::{
+        AllocationMethod, CapitalAllocator, NodeCategory, NodeCore, NodeCoreExt, NodeExecutable,
+        NodeInfra, NodeMeta, NodeMetadata, Slot, CAPITAL_ALLOCATION,
+    },
+    node_io::CapitalAllocation,
+    workflow::Node,
+};
+use anyhow::Result;
+use bon::Builder;
+use rust_decimal::Decimal;
+use std::sync::Arc;
+
+/// 多策略资金分配
+/// outputs:
+///      0: CapitalAllocation
+#[derive(Debug)]
+pub(crate) struct StrategyAllocator {
+    params: Params,   // 参数
+    infra: NodeInfra, // 节点基础设施
+}
+
+impl NodeMeta for StrategyAllocator {
+    const METADATA: NodeMetadata = NodeMetadata {
+        prop_type: "strategy.StrategyAllocator",
+        display_name: "多策略资金分配",
+        category: NodeCategory::Strategy,
+        inputs: &[],
+        outputs: &[CAPITAL_ALLOCATION],
+        icon: "pie-chart",
+    };
+}
+
+impl NodeCore for StrategyAllocator {
+    fn node_infra(&self) -> &NodeInfra {
+        &self.infra
+    }
+
+    fn node_infra_mut(&mut self) -> &mut NodeInfra {
+        &mut self.infra
+    }
+}
+
+impl StrategyAllocator {
+    pub(crate) fn try_new(node: Node) -> Result<Self> {
+        let params = Params::try_from(&node)?;
+        let infra = NodeInfra::new(node);
+
+        Ok(StrategyAllocator { params, infra })
+    }
+}
+
+// 子策略通过输出的资金分配通道上报权益并领取预算，再分配由子策略的tick驱动
+impl NodeExecutable for StrategyAllocator {
+    async fn setup(&mut self) -> Result<()> {
+        let allocator = CapitalAllocator::builder()
+            .method(self.params.method)
+            .rebalance_secs(self.params.rebalance_secs)
+            .lookback(self.params.lookback)
+            .min_weight(self.params.min_weight)
+            .build();
+        let allocation_slot = Arc::new(Slot::<CapitalAllocation>::new(CapitalAllocation::new(
+            allocator,
+        )));
+
+        self.port_mut().set_output(0, allocation_slot)?;
+
+        Ok(())
+    }
+}
+
+impl TryFrom<Node> for StrategyAllocator {
+    type Error = anyhow::Error;
+
+    fn try_from(node: Node) -> Result<Self> {
+        StrategyAllocator::try_new(node)
+    }
+}
+
+impl TryFrom<&StrategyAllocator> for Node {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &StrategyAllocator) -> Result<Self> {
+        Ok(value.node().clone())
+    }
+}
+
+#[derive(Builder, Debug, Clone)]
+pub(crate) struct Params {
+    method: AllocationMethod, // 分配方法: equal_weight、risk_parity 或 momentum
+    #[builder(default = 3600)]
+    rebalance_secs: i64, // 再分配间隔(秒)
+    #[builder(default = 30)]
+    lookback: usize, // 回看的权益采样数量
+    #[builder(default)]
+    min_weight: Decimal, // 每个子策略的最低权重
+}
+
+impl TryFrom<&Node> for Params {
+    type Error = StrategyAllocatorError;
+
+    fn try_from(node: &Node) -> Result<Self, Self::Error> {
+        if node.properties.prop_type != "strategy.StrategyAllocator" {
+            return Err(StrategyAllocatorError::PropertyTypeMismatch);
+        }
+
+        // 分配方法为必填参数，其余参数可选
+        let [method, optional_params @ ..] = node.properties.params.as_slice() else {
+            return Err(StrategyAllocatorError::ParamsFormatError);
+        };
+
+        if optional_params.len() > 3 {
+            return Err(StrategyAllocatorError::ParamsFormatError);
+        }
+
+        let rebalance_secs = optional_params.first();
+        let lookback = optional_params.get(1);
+        let min_weight = optional_params.get(2);
+
+        let method = method
+            .as_str()
+            .and_then(|method| method.parse::<AllocationMethod>().ok())
+            .ok_or(StrategyAllocatorError::MethodError)?;
+
+        let rebalance_secs = rebalance_secs
+            .filter(|rebalance_secs| !rebalance_secs.is_null())
+            .map(|rebalance_secs| {
+                rebalance_secs
+                    .as_i64()
+                    .filter(|rebalance_secs| *rebalance_secs > 0)
+                    .ok_or(StrategyAllocatorError::RebalanceSecsError)
+            })
+            .transpose()?;
+
+        // 再分配需要至少两个采样才能计算收益率
+        let lookback = lookback
+            .filter(|lookback| !lookback.is_null())
+            .map(|lookback| {
+                lookback
+                    .as_u64()
+                    .filter(|lookback| *lookback >= 2)
+                    .and_then(|lookback| usize::try_from(lookback).ok())
+                    .ok_or(StrategyAllocatorError::LookbackError)
+            })
+            .transpose()?;
+
+        let min_weight = min_weight
+            .filter(|min_weight| !min_weight.is_null())
+            .map(|min_weight| {
+                min_weight
+                    .as_f64()
+                    .filter(|min_weight| (0. ..1.).contains(min_weight))
+                    .and_then(|min_weight| Decimal::try_from(min_weight).ok())
+                    .ok_or(StrategyAllocatorError::MinWeightError)
+            })
+            .transpose()?;
+
+        let params = Params::builder()
+            .method(method)
+            .maybe_rebalance_secs(rebalance_secs)
+            .maybe_lookback(lookback)
+            .maybe_min_weight(min_weight)
+            .build();
+
+        Ok(params)
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum StrategyAllocatorError {
+    #[error("Invalid property type, expected 'strategy.StrategyAllocator'")]
+    PropertyTypeMismatch,
+
+    #[error("Invalid parameters format")]
+    ParamsFormatError,
+
+    #[error("Invalid allocation method, expected 'equal_weight', 'risk_parity' or 'momentum'")]
+    MethodError,
+
+    #[error("Invalid rebalance seconds")]
+    RebalanceSecsError,
+
+    #[error("Invalid lookback, expected at least 2")]
+    LookbackError,
+
+    #[error("Invalid min weight, expected between 0 and 1")]
+    MinWeightError,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_try_from_node_to_strategy_allocator() -> Result<()> {
+        let json_str = r#"{"id":5,"type":"策略/多策略资金分配","pos":[199,74],"size":{"0":210,"1":310},"flags":{},"order":0,"mode":0,"outputs":[{"name":"资金分配","type":"CapitalAllocation","links":[4],"slot_index":0}],"properties":{"type":"strategy.StrategyAllocator","params":["risk_parity", 86400, null, 0.1]}}"#;
+
+        let node: Node = serde_json::from_str(json_str)?;
+        let allocator = StrategyAllocator::try_from(node)?;
+
+        assert_eq!(allocator.params.method, AllocationMethod::RiskParity);
+        assert_eq!(allocator.params.rebalance_secs, 86400);
+        assert_eq!(allocator.params.lookback, 30);
+        assert_eq!(allocator.params.min_weight, dec!(0.1));
+
+        let json_str = r#"{"id":5,"type":"策略/多策略资金分配","pos":[199,74],"size":{"0":210,"1":310},"flags":{},"order":0,"mode":0,"properties":{"type":"strategy.StrategyAllocator","params":["kelly"]}}"#;
+
+        let node: Node = serde_json::from_str(json_str)?;
+        assert_eq!(
+            StrategyAllocator::try_from(node).unwrap_err().to_string(),
+            "Invalid allocation method, expected 'equal_weight', 'risk_parity' or 'momentum'"
+        );
+
+        let json_str = r#"{"id":5,"type":"策略/多策略资金分配","pos":[199,74],"size":{"0":210,"1":310},"flags":{},"order":0,"mode":0,"properties":{"type":"strategy.StrategyAllocator","params":["momentum", 3600, 1]}}"#;
+
+        let node: Node = serde_json::from_str(json_str)?;
+        assert_eq!(
+            StrategyAllocator::try_from(node).unwrap_err().to_string(),
+            "Invalid lookback, expected at least 2"
+        );
+
+        Ok(())
+    }
+}
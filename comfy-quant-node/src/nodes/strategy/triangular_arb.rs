@@ -0,0 +1,854 @@
+use crate::{
+    node_core::{
+        next_user_data, NodeCategory, NodeCore, NodeCoreExt, NodeError, NodeExecutable, NodeInfra,
+        NodeMeta, NodeMetadata, NodeSpotStats, NodeSpotStatsExt, OrderRules, Slot,
+        SpotClientService, SpotTradeable, SymbolRules, Tick, TradeStats, TradeStatsExt,
+        SPOT_CLIENT, TICK_STREAM,
+    },
+    node_io::TickStream,
+    stats::{SpotStats, TradeRecord},
+    workflow::Node,
+};
+use anyhow::{anyhow, Result};
+use bon::Builder;
+use comfy_quant_base::{Exchange, Market, Symbol};
+use comfy_quant_exchange::client::{
+    spot_client::base::{Order, OrderIntent, OrderSide},
+    spot_client_kind::{SpotClientExecutable, SpotClientKind, SpotclientExecutableExt},
+};
+use futures::{stream, StreamExt};
+use rust_decimal::{
+    prelude::{FromPrimitive, ToPrimitive},
+    Decimal,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+/// 三角套利(现货)
+/// 同一交易所的三个交易对构成闭环(如 BTC/USDT、ETH/BTC、ETH/USDT)，
+/// 起始资产沿闭环兑换一周，扣除手续费后的收益率超过阈值时同时提交三个订单
+/// inputs:
+///     0: SpotClientKind
+///     1: TickStream
+///     2: TickStream (可选，交易对的tick来自不同的tick流时连接)
+///     3: TickStream (可选)
+#[derive(Debug)]
+#[allow(unused)]
+pub(crate) struct TriangularArb {
+    params: Params,
+    store: RuntimeStore,
+    pairs: Vec<PairSpec>, // 交易对的下单规则和手续费，启动时加载，不持久化
+    prices: HashMap<Symbol, Decimal>, // 各交易对的最新价格
+    infra: NodeInfra,
+}
+
+impl NodeMeta for TriangularArb {
+    const METADATA: NodeMetadata = NodeMetadata {
+        prop_type: "strategy.TriangularArb",
+        display_name: "三角套利(现货)",
+        category: NodeCategory::Strategy,
+        inputs: &[
+            SPOT_CLIENT,
+            TICK_STREAM,
+            TICK_STREAM.optional(),
+            TICK_STREAM.optional(),
+        ],
+        outputs: &[],
+        icon: "triangle",
+    };
+}
+
+impl NodeCore for TriangularArb {
+    fn node_infra(&self) -> &NodeInfra {
+        &self.infra
+    }
+
+    fn node_infra_mut(&mut self) -> &mut NodeInfra {
+        &mut self.infra
+    }
+}
+
+impl NodeSpotStats for TriangularArb {
+    fn spot_stats(&self) -> &SpotStats {
+        &self.store.stats
+    }
+
+    fn spot_stats_mut(&mut self) -> &mut SpotStats {
+        &mut self.store.stats
+    }
+}
+
+impl TriangularArb {
+    pub(crate) fn try_new(node: Node) -> Result<Self> {
+        let params = Params::try_from(&node)?;
+        let store = RuntimeStore::try_from(&node)?;
+        let infra = NodeInfra::new(node);
+
+        Ok(Self {
+            params,
+            store,
+            pairs: vec![],
+            prices: HashMap::new(),
+            infra,
+        })
+    }
+
+    // 加载交易对的精度和手续费，首次运行时检查余额并记录投入的起始资产
+    async fn prepare(&mut self, client: &SpotClientKind) -> Result<()> {
+        let ctx = self.node_context()?;
+        let symbol_rules =
+            SymbolRules::load(ctx.db(), &client.account_id(), &client.exchange()).await?;
+        ctx.resource_meter()
+            .add_db_rows_read(symbol_rules.len() as u64);
+
+        let mut spot_client_service = SpotClientService::builder()
+            .client(client)
+            .retry_max_retries(3)
+            .retry_wait_secs(3)
+            .timeout_secs(10)
+            .symbol_rules(symbol_rules)
+            .build();
+
+        let account = spot_client_service.get_account().await?;
+        let mut pairs = Vec::with_capacity(self.params.pairs.len());
+
+        for (base_asset, quote_asset) in &self.params.pairs {
+            spot_client_service.ensure_symbol_allowed(base_asset, quote_asset)?;
+
+            let symbol_info = spot_client_service
+                .get_symbol_info(base_asset, quote_asset)
+                .await?;
+            let (_, commission_rate) = symbol_info.commission_rates(&account);
+
+            pairs.push(PairSpec {
+                base_asset: base_asset.clone(),
+                quote_asset: quote_asset.clone(),
+                symbol: client.symbol(base_asset, quote_asset),
+                rules: (&symbol_info).into(),
+                commission_rate,
+            });
+        }
+
+        self.pairs = pairs;
+
+        // 如果已经初始化，则跳过
+        if self.store.initialized {
+            return Ok(());
+        }
+
+        let balance = spot_client_service
+            .get_balance(&self.params.start_asset)
+            .await?;
+
+        if balance.free.parse::<Decimal>()? < self.params.investment {
+            anyhow::bail!("Insufficient free balance");
+        }
+
+        self.store
+            .holdings
+            .insert(self.params.start_asset.clone(), self.params.investment);
+        self.store.initialized = true;
+
+        Ok(())
+    }
+
+    // 更新交易对的最新价格和统计信息，首个tick初始化该交易对的统计
+    async fn update_with_tick(&mut self, exchange: &Exchange, tick: &Tick) -> Result<()> {
+        let Some(pair) = self
+            .pairs
+            .iter()
+            .find(|pair| pair.symbol == tick.symbol)
+            .cloned()
+        else {
+            return Ok(());
+        };
+
+        self.prices.insert(tick.symbol.clone(), tick.price);
+
+        if self.store.stats.get(exchange, &pair.symbol).is_none() {
+            let ctx = self.node_context()?;
+
+            self.store
+                .stats
+                .setup(exchange, &pair.symbol, &pair.base_asset, &pair.quote_asset);
+            self.store
+                .stats
+                .initialize_balance(
+                    &ctx,
+                    exchange,
+                    &pair.symbol,
+                    &Decimal::ZERO,
+                    &Decimal::ZERO,
+                    tick,
+                )
+                .await?;
+        }
+
+        self.update_spot_stats_with_tick(exchange, &pair.symbol, tick)
+            .await
+    }
+
+    // 收益率最高且超过阈值的兑换方向，返回兑换路径和每个订单的数量
+    fn opportunity(&self) -> Option<(Route, Vec<Decimal>)> {
+        let prices = self
+            .pairs
+            .iter()
+            .map(|pair| self.prices.get(&pair.symbol).copied())
+            .collect::<Option<Vec<_>>>()?;
+
+        // 每次投入不超过投资金额，之前套利的亏损不再追加
+        let amount = self
+            .store
+            .holdings
+            .get(&self.params.start_asset)
+            .copied()
+            .unwrap_or_default()
+            .min(self.params.investment);
+
+        routes(&self.params.start_asset, &self.params.pairs)?
+            .into_iter()
+            .filter_map(|route| {
+                let (quantities, output) = simulate(&route, &self.pairs, &prices, amount)?;
+                let profit_rate = output / amount - Decimal::ONE;
+
+                (profit_rate > self.params.threshold).then_some((route, quantities, profit_rate))
+            })
+            .max_by_key(|(_, _, profit_rate)| *profit_rate)
+            .map(|(route, quantities, _)| (route, quantities))
+    }
+
+    // 同时提交闭环中的所有订单，按成交结果更新持有资产
+    async fn arbitrage(
+        &mut self,
+        client: &SpotClientKind,
+        route: &Route,
+        quantities: &[Decimal],
+    ) -> Result<()> {
+        let intents = route
+            .iter()
+            .zip(quantities)
+            .map(|(leg, qty)| {
+                let pair = &self.pairs[leg.pair];
+                let qty = qty
+                    .to_f64()
+                    .ok_or_else(|| anyhow!("Failed to convert quantity to f64"))?;
+
+                let intent = match leg.side {
+                    OrderSide::Buy => {
+                        OrderIntent::market_buy(&pair.base_asset, &pair.quote_asset, qty)
+                    }
+                    OrderSide::Sell => {
+                        OrderIntent::market_sell(&pair.base_asset, &pair.quote_asset, qty)
+                    }
+                };
+
+                Ok(intent)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let results = self.submit_batch(client, intents).await?;
+        let mut filled = 0;
+
+        for (leg, result) in route.iter().zip(&results) {
+            if let Ok(order) = result {
+                let pair = self.pairs[leg.pair].clone();
+                self.store.update_holdings(&pair, order)?;
+                filled += 1;
+            }
+        }
+
+        self.store.arbitrages += 1;
+
+        tracing::info!(
+            monotonic_counter.triangular_arb_executed = 1_u64,
+            legs = route.len(),
+            filled,
+            "TriangularArb executed"
+        );
+
+        // 部分订单失败时，中间资产留在持有资产中，按最新价格计入未实现盈亏
+        if filled < route.len() {
+            self.publish_error(format!(
+                "TriangularArb only {} of {} orders filled",
+                filled,
+                route.len()
+            ));
+        }
+
+        Ok(())
+    }
+
+    // 起始资产之外的持有资产按最新价格折算为起始资产
+    async fn holdings_value(&self) -> Result<Decimal> {
+        let client = self.port().input::<SpotClientKind>(0)?;
+        let exchange = client.exchange();
+        let start_asset = &self.params.start_asset;
+        let mut value = Decimal::ZERO;
+
+        for (asset, amount) in &self.store.holdings {
+            if asset == start_asset || amount.is_zero() {
+                continue;
+            }
+
+            let Some((base_asset, quote_asset)) = self.params.pairs.iter().find(|(base, quote)| {
+                (base == asset && quote == start_asset) || (base == start_asset && quote == asset)
+            }) else {
+                continue;
+            };
+
+            let symbol = client.symbol(base_asset, quote_asset);
+            let price = self.price(&exchange, &Market::Spot, &symbol).await?;
+
+            if base_asset == asset {
+                value += amount * price;
+            } else if !price.is_zero() {
+                value += amount / price;
+            }
+        }
+
+        Ok(value)
+    }
+}
+
+// 节点执行
+impl NodeExecutable for TriangularArb {
+    async fn execute(&mut self) -> Result<(), NodeError> {
+        Ok(self.run().await?)
+    }
+
+    async fn shutdown(&mut self, cancel_orders: bool) -> Result<()> {
+        if cancel_orders {
+            let client = self.port().input::<SpotClientKind>(0)?;
+
+            for pair in self.pairs.clone() {
+                self.cancel_open_orders(&client, &pair.base_asset, &pair.quote_asset)
+                    .await?;
+            }
+        }
+
+        self.save_runtime_store(&self.store)
+    }
+}
+
+impl TriangularArb {
+    async fn run(&mut self) -> Result<()> {
+        // 获取输入
+        let client = self.port().input::<SpotClientKind>(0)?;
+        let exchange = client.exchange();
+
+        // 同一个tick流可以连接到多个输入，只订阅一次
+        let mut tick_streams: Vec<Arc<Slot<TickStream>>> = vec![];
+
+        for index in 1..=3 {
+            if let Ok(tick_stream) = self.port().input::<TickStream>(index) {
+                if !tick_streams.iter().any(|s| Arc::ptr_eq(s, &tick_stream)) {
+                    tick_streams.push(tick_stream);
+                }
+            }
+        }
+
+        anyhow::ensure!(!tick_streams.is_empty(), "TriangularArb has no tick stream");
+
+        self.prepare(&client).await?;
+
+        // 从检查点恢复时补记停机期间的成交
+        for pair in self.pairs.clone() {
+            self.reconcile_open_orders(&client, &pair.base_asset, &pair.quote_asset)
+                .await?;
+        }
+
+        self.save_runtime_store(&self.store)?;
+
+        // 合并多个tick流，只接收闭环中的交易对
+        let symbols = self
+            .pairs
+            .iter()
+            .map(|pair| pair.symbol.clone())
+            .collect::<Vec<_>>();
+        let mut ticks = stream::select_all(tick_streams.into_iter().map(|tick_stream| {
+            let rx = tick_stream.subscribe_symbols(symbols.clone());
+
+            stream::unfold((tick_stream, rx), |(tick_stream, rx)| async move {
+                let tick = tick_stream.next_for(&rx).await?;
+                Some((tick, (tick_stream, rx)))
+            })
+            .boxed()
+        }));
+
+        let heartbeat = self.heartbeat();
+        let mut user_data = client.subscribe_user_data();
+
+        loop {
+            let next = tokio::select! {
+                next = ticks.next() => next,
+                event = next_user_data(&mut user_data) => {
+                    let _busy = heartbeat.busy();
+                    self.update_spot_stats_with_user_data(&event).await?;
+                    self.save_runtime_store(&self.store)?;
+                    continue;
+                }
+            };
+
+            let Some((_, _, tick)) = next else {
+                break;
+            };
+
+            let _busy = heartbeat.busy();
+
+            self.update_with_tick(&exchange, &tick).await?;
+
+            if let Some((route, quantities)) = self.opportunity() {
+                self.arbitrage(&client, &route, &quantities).await?;
+            }
+
+            // 保存运行时数据，供工作流检查点使用
+            self.save_runtime_store(&self.store)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl TradeStats for TriangularArb {
+    async fn initial_capital(&self) -> Result<Decimal> {
+        let ctx = self.workflow_context()?;
+        let quote_asset = ctx.quote_asset().await;
+        let exchange_rate = ctx
+            .exchange_rate(&self.params.start_asset, &quote_asset)
+            .await?;
+
+        Ok(self.params.investment * exchange_rate.rate())
+    }
+
+    async fn realized_pnl(&self) -> Result<Decimal> {
+        let ctx = self.workflow_context()?;
+        let quote_asset = ctx.quote_asset().await;
+        let exchange_rate = ctx
+            .exchange_rate(&self.params.start_asset, &quote_asset)
+            .await?;
+        let start_balance = self
+            .store
+            .holdings
+            .get(&self.params.start_asset)
+            .copied()
+            .unwrap_or_default();
+
+        Ok((start_balance - self.params.investment) * exchange_rate.rate())
+    }
+
+    async fn unrealized_pnl(&self) -> Result<Decimal> {
+        let ctx = self.workflow_context()?;
+        let quote_asset = ctx.quote_asset().await;
+        let exchange_rate = ctx
+            .exchange_rate(&self.params.start_asset, &quote_asset)
+            .await?;
+
+        Ok(self.holdings_value().await? * exchange_rate.rate())
+    }
+
+    async fn running_time(&self) -> Result<u128> {
+        Ok(self.workflow_context()?.running_time().await)
+    }
+
+    // 运行期间没有追加或提取资金，时间加权收益率即总收益率
+    async fn time_weighted_return(&self) -> Result<Decimal> {
+        self.total_return().await
+    }
+
+    async fn money_weighted_return(&self) -> Result<Decimal> {
+        self.annualized_return().await
+    }
+
+    // 持有多种资产，不记录净值序列
+    async fn equity_curve(&self) -> Result<Vec<(i64, Decimal)>> {
+        Ok(vec![])
+    }
+
+    async fn trade_record(&self) -> Result<TradeRecord> {
+        Ok(self.spot_stats().trade_record())
+    }
+}
+
+impl TryFrom<Node> for TriangularArb {
+    type Error = anyhow::Error;
+
+    fn try_from(node: Node) -> Result<Self> {
+        TriangularArb::try_new(node)
+    }
+}
+
+impl TryFrom<&TriangularArb> for Node {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &TriangularArb) -> Result<Self> {
+        let mut node = value.node().clone();
+        node.runtime_store = Some(serde_json::to_string(&value.store)?);
+        Ok(node)
+    }
+}
+
+// 交易对的下单规则
+#[derive(Debug, Clone)]
+struct PairSpec {
+    base_asset: String,
+    quote_asset: String,
+    symbol: Symbol,
+    rules: OrderRules,        // 数量步长、精度和最小下单限制
+    commission_rate: Decimal, // 吃单手续费率
+}
+
+// 闭环中的一次兑换
+#[derive(Debug, Clone, PartialEq)]
+struct Leg {
+    pair: usize,     // 交易对序号
+    side: OrderSide, // 买入时用计价资产换基础资产，卖出时相反
+}
+
+type Route = Vec<Leg>;
+
+// 从起始资产出发的两个兑换方向，交易对不构成包含起始资产的闭环时返回None
+fn routes(start_asset: &str, pairs: &[(String, String)]) -> Option<[Route; 2]> {
+    let route = |first: usize| {
+        let mut asset = start_asset;
+        let mut route = Route::with_capacity(pairs.len());
+
+        for step in 0..pairs.len() {
+            let pair = if step == 0 {
+                first
+            } else {
+                (0..pairs.len()).find(|index| {
+                    !route.iter().any(|leg: &Leg| leg.pair == *index)
+                        && (pairs[*index].0 == asset || pairs[*index].1 == asset)
+                })?
+            };
+
+            let (base_asset, quote_asset) = &pairs[pair];
+
+            let side = if quote_asset == asset {
+                asset = base_asset;
+                OrderSide::Buy
+            } else if base_asset == asset {
+                asset = quote_asset;
+                OrderSide::Sell
+            } else {
+                return None;
+            };
+
+            route.push(Leg { pair, side });
+        }
+
+        (asset == start_asset).then_some(route)
+    };
+
+    let mut firsts = pairs
+        .iter()
+        .enumerate()
+        .filter(|(_, (base_asset, quote_asset))| {
+            base_asset == start_asset || quote_asset == start_asset
+        })
+        .map(|(index, _)| index);
+
+    let (Some(a), Some(b), None) = (firsts.next(), firsts.next(), firsts.next()) else {
+        return None;
+    };
+
+    Some([route(a)?, route(b)?])
+}
+
+// 按当前价格模拟兑换一周，返回每个订单的数量和最终得到的起始资产数量。
+// 数量按交易对规则向下取整，手续费从获得的资产中扣除，任一订单低于最小下单限制时返回None
+fn simulate(
+    route: &Route,
+    pairs: &[PairSpec],
+    prices: &[Decimal],
+    amount: Decimal,
+) -> Option<(Vec<Decimal>, Decimal)> {
+    let mut amount = amount;
+    let mut quantities = Vec::with_capacity(route.len());
+
+    for leg in route {
+        let pair = pairs.get(leg.pair)?;
+        let price = prices
+            .get(leg.pair)
+            .copied()
+            .filter(|price| *price > Decimal::ZERO)?;
+        let fee = Decimal::ONE - pair.commission_rate;
+
+        let qty = pair.rules.round_qty(match leg.side {
+            OrderSide::Buy => amount / price,
+            OrderSide::Sell => amount,
+        });
+
+        if !pair.rules.is_tradable(qty, price) {
+            return None;
+        }
+
+        amount = match leg.side {
+            OrderSide::Buy => qty * fee,
+            OrderSide::Sell => qty * price * fee,
+        };
+
+        quantities.push(qty);
+    }
+
+    Some((quantities, amount))
+}
+
+#[derive(Builder, Serialize, Deserialize, Debug, Clone)]
+#[builder(on(String, into))]
+pub(crate) struct Params {
+    start_asset: String,          // 起始资产，盈亏以该资产计算
+    pairs: Vec<(String, String)>, // 构成闭环的交易对: 基础货币，计价货币
+    threshold: Decimal,           // 扣除手续费后的最小套利收益率
+    investment: Decimal,          // 投资金额(起始资产)
+}
+
+impl TryFrom<&Node> for Params {
+    type Error = TriangularArbError;
+
+    fn try_from(node: &Node) -> Result<Self, Self::Error> {
+        if node.properties.prop_type != "strategy.TriangularArb" {
+            return Err(TriangularArbError::PropertyTypeMismatch);
+        }
+
+        let [start_asset, pairs, threshold, investment] = node.properties.params.as_slice() else {
+            return Err(TriangularArbError::ParamsFormatError);
+        };
+
+        let start_asset = start_asset
+            .as_str()
+            .filter(|asset| !asset.is_empty())
+            .map(str::to_uppercase)
+            .ok_or(TriangularArbError::StartAssetError)?;
+
+        let pairs = pairs
+            .as_array()
+            .ok_or(TriangularArbError::PairsError)?
+            .iter()
+            .map(|pair| {
+                let pair = pair.as_array()?;
+                let base_asset = pair.first()?.as_str()?.to_uppercase();
+                let quote_asset = pair.get(1)?.as_str()?.to_uppercase();
+                Some((base_asset, quote_asset))
+            })
+            .collect::<Option<Vec<(String, String)>>>()
+            .filter(|pairs| pairs.len() == 3)
+            .ok_or(TriangularArbError::PairsError)?;
+
+        if routes(&start_asset, &pairs).is_none() {
+            return Err(TriangularArbError::PairsError);
+        }
+
+        let threshold = threshold
+            .as_f64()
+            .and_then(Decimal::from_f64)
+            .filter(|threshold| *threshold >= Decimal::ZERO)
+            .ok_or(TriangularArbError::ThresholdError)?;
+
+        let investment = investment
+            .as_f64()
+            .and_then(Decimal::from_f64)
+            .filter(|investment| *investment > Decimal::ZERO)
+            .ok_or(TriangularArbError::InvestmentError)?;
+
+        let params = Params::builder()
+            .start_asset(start_asset)
+            .pairs(pairs)
+            .threshold(threshold)
+            .investment(investment)
+            .build();
+
+        Ok(params)
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum TriangularArbError {
+    #[error("Invalid property type, expected 'strategy.TriangularArb'")]
+    PropertyTypeMismatch,
+
+    #[error("Invalid parameters format")]
+    ParamsFormatError,
+
+    #[error("Invalid start_asset")]
+    StartAssetError,
+
+    #[error("Invalid pairs, expected three [base asset, quote asset] forming a cycle through start_asset")]
+    PairsError,
+
+    #[error("Invalid threshold")]
+    ThresholdError,
+
+    #[error("Invalid investment")]
+    InvestmentError,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub(crate) struct RuntimeStore {
+    stats: SpotStats,
+    holdings: BTreeMap<String, Decimal>, // 套利持有的资产，部分订单失败时包含中间资产
+    arbitrages: u64,                     // 套利次数
+    initialized: bool,
+}
+
+impl RuntimeStore {
+    fn new() -> Self {
+        Self {
+            stats: SpotStats::new(),
+            holdings: BTreeMap::new(),
+            arbitrages: 0,
+            initialized: false,
+        }
+    }
+
+    // 按订单的成交数量更新持有资产，手续费从获得的资产中扣除
+    fn update_holdings(&mut self, pair: &PairSpec, order: &Order) -> Result<()> {
+        let base_amount = order.base_asset_amount()?;
+        let quote_amount = order.quote_asset_amount()?;
+        let base_commission = order.base_commission(&pair.commission_rate)?;
+        let quote_commission = order.quote_commission(&pair.commission_rate)?;
+
+        let (base_delta, quote_delta) = match order.order_side {
+            OrderSide::Buy => (base_amount - base_commission, -quote_amount),
+            OrderSide::Sell => (-base_amount, quote_amount - quote_commission),
+        };
+
+        *self.holdings.entry(pair.base_asset.clone()).or_default() += base_delta;
+        *self.holdings.entry(pair.quote_asset.clone()).or_default() += quote_delta;
+
+        Ok(())
+    }
+}
+
+impl TryFrom<&Node> for RuntimeStore {
+    type Error = anyhow::Error;
+
+    fn try_from(node: &Node) -> Result<Self> {
+        if let Some(runtime_store) = &node.runtime_store {
+            Ok(serde_json::from_str(runtime_store)?)
+        } else {
+            Ok(Self::new())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn pairs() -> Vec<(String, String)> {
+        [("BTC", "USDT"), ("ETH", "BTC"), ("ETH", "USDT")]
+            .into_iter()
+            .map(|(base, quote)| (base.to_string(), quote.to_string()))
+            .collect()
+    }
+
+    fn pair_specs() -> Vec<PairSpec> {
+        pairs()
+            .into_iter()
+            .map(|(base_asset, quote_asset)| PairSpec {
+                symbol: format!("{}{}", base_asset, quote_asset).into(),
+                base_asset,
+                quote_asset,
+                rules: OrderRules::builder().base_asset_precision(4).build(),
+                commission_rate: dec!(0.001),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_try_from_node_to_triangular_arb() -> Result<()> {
+        let json_str = r#"{"id":4,"type":"交易策略/三角套利(现货)","pos":[367,125],"size":{"0":210,"1":310},"flags":{},"order":1,"mode":0,"inputs":[],"properties":{"type":"strategy.TriangularArb","params":["usdt",[["BTC","USDT"],["ETH","BTC"],["ETH","USDT"]],0.001,100]}}"#;
+
+        let node: Node = serde_json::from_str(json_str)?;
+        let arb = TriangularArb::try_from(node)?;
+
+        assert_eq!(arb.params.start_asset, "USDT");
+        assert_eq!(arb.params.pairs, pairs());
+        assert_eq!(arb.params.threshold, dec!(0.001));
+        assert_eq!(arb.params.investment, dec!(100));
+
+        let node = Node::try_from(&arb)?;
+        assert_eq!(
+            node.runtime_store,
+            Some(
+                r#"{"stats":{"data":{}},"holdings":{},"arbitrages":0,"initialized":false}"#
+                    .to_string()
+            )
+        );
+
+        // 交易对不构成经过起始资产的闭环
+        let json_str = r#"{"id":4,"type":"交易策略/三角套利(现货)","pos":[367,125],"size":{"0":210,"1":310},"flags":{},"order":1,"mode":0,"inputs":[],"properties":{"type":"strategy.TriangularArb","params":["USDT",[["BTC","USDT"],["ETH","BTC"],["SOL","USDT"]],0.001,100]}}"#;
+
+        let node: Node = serde_json::from_str(json_str)?;
+        assert!(matches!(
+            Params::try_from(&node),
+            Err(TriangularArbError::PairsError)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_routes() {
+        let buy = |pair| Leg {
+            pair,
+            side: OrderSide::Buy,
+        };
+        let sell = |pair| Leg {
+            pair,
+            side: OrderSide::Sell,
+        };
+
+        // USDT -> BTC -> ETH -> USDT，USDT -> ETH -> BTC -> USDT
+        assert_eq!(
+            routes("USDT", &pairs()),
+            Some([
+                vec![buy(0), buy(1), sell(2)],
+                vec![buy(2), sell(1), sell(0)]
+            ])
+        );
+
+        // BTC -> USDT -> ETH -> BTC，BTC -> ETH -> USDT -> BTC
+        assert_eq!(
+            routes("BTC", &pairs()),
+            Some([
+                vec![sell(0), buy(2), sell(1)],
+                vec![buy(1), sell(2), buy(0)]
+            ])
+        );
+
+        assert_eq!(routes("SOL", &pairs()), None);
+        assert_eq!(routes("USDT", &pairs()[..2]), None);
+    }
+
+    #[test]
+    fn test_simulate() {
+        let [forward, backward] = routes("USDT", &pairs()).unwrap();
+        let pairs = pair_specs();
+
+        // ETH/USDT 相对 BTC/USDT * ETH/BTC 偏高 1%，先买BTC再换ETH最后卖出ETH
+        let prices = [dec!(50000), dec!(0.04), dec!(2020)];
+
+        let (quantities, output) = simulate(&forward, &pairs, &prices, dec!(1000)).unwrap();
+        assert_eq!(quantities, vec![dec!(0.02), dec!(0.4995), dec!(0.4990)]);
+        assert!(output > dec!(1006) && output < dec!(1008));
+
+        let (_, output) = simulate(&backward, &pairs, &prices, dec!(1000)).unwrap();
+        assert!(output < dec!(1000));
+
+        // 价格缺失或数量取整后为0
+        assert!(simulate(
+            &forward,
+            &pairs,
+            &[dec!(0), dec!(0.04), dec!(2020)],
+            dec!(1000)
+        )
+        .is_none());
+        assert!(simulate(&forward, &pairs, &prices, dec!(0.0001)).is_none());
+    }
+}
@@ -0,0 +1,207 @@
+use crate::{
+    node_core::{
+        NodeCategory, NodeCore, NodeCoreExt, NodeExecutable, NodeInfra, NodeMeta, NodeMetadata,
+    },
+    stats::{AssertMetric, AssertionResult},
+    workflow::Node,
+};
+use anyhow::Result;
+use bon::Builder;
+use rust_decimal::{prelude::FromPrimitive, Decimal};
+
+// 回测断言，工作流结束时检查指标是否在范围内，用于策略回归测试
+#[derive(Debug)]
+pub(crate) struct Assert {
+    params: Params,
+    infra: NodeInfra,
+}
+
+impl NodeMeta for Assert {
+    const METADATA: NodeMetadata = NodeMetadata {
+        prop_type: "test.Assert",
+        display_name: "断言",
+        category: NodeCategory::Test,
+        inputs: &[],
+        outputs: &[],
+        icon: "check-circle",
+    };
+}
+
+impl NodeCore for Assert {
+    fn node_infra(&self) -> &NodeInfra {
+        &self.infra
+    }
+
+    fn node_infra_mut(&mut self) -> &mut NodeInfra {
+        &mut self.infra
+    }
+}
+
+impl Assert {
+    pub(crate) fn try_new(node: Node) -> Result<Self> {
+        let params = Params::try_from(&node)?;
+        let infra = NodeInfra::new(node);
+
+        Ok(Assert { params, infra })
+    }
+
+    pub(crate) fn metric(&self) -> AssertMetric {
+        self.params.metric
+    }
+
+    // 用工作流结束时的指标值检查断言
+    pub(crate) fn check(&self, value: Decimal) -> AssertionResult {
+        AssertionResult::new(
+            self.node().id,
+            self.params.metric,
+            value,
+            self.params.min,
+            self.params.max,
+        )
+    }
+}
+
+// 断言在工作流结束后统一检查，执行阶段无需处理
+impl NodeExecutable for Assert {}
+
+impl TryFrom<Node> for Assert {
+    type Error = anyhow::Error;
+
+    fn try_from(node: Node) -> Result<Self> {
+        Assert::try_new(node)
+    }
+}
+
+impl TryFrom<&Assert> for Node {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &Assert) -> Result<Self> {
+        Ok(value.node().clone())
+    }
+}
+
+#[derive(Builder, Debug, Clone)]
+pub(crate) struct Params {
+    metric: AssertMetric, // 指标
+    min: Option<Decimal>, // 下限(含)，为空则不限制
+    max: Option<Decimal>, // 上限(含)，为空则不限制
+}
+
+impl TryFrom<&Node> for Params {
+    type Error = AssertError;
+
+    fn try_from(node: &Node) -> Result<Self, Self::Error> {
+        if node.properties.prop_type != "test.Assert" {
+            return Err(AssertError::PropertyTypeMismatch);
+        }
+
+        let [metric, min, max] = node.properties.params.as_slice() else {
+            return Err(AssertError::ParamsFormatError);
+        };
+
+        let metric = metric
+            .as_str()
+            .and_then(|metric| AssertMetric::try_from(metric).ok())
+            .ok_or(AssertError::MetricError)?;
+
+        let bound = |value: &serde_json::Value| {
+            if value.is_null() {
+                return Ok(None);
+            }
+
+            value
+                .as_f64()
+                .and_then(Decimal::from_f64)
+                .map(Some)
+                .ok_or(AssertError::BoundError)
+        };
+
+        let min = bound(min)?;
+        let max = bound(max)?;
+
+        if min.is_none() && max.is_none() {
+            return Err(AssertError::BoundError);
+        }
+
+        if let (Some(min), Some(max)) = (min, max) {
+            if min > max {
+                return Err(AssertError::RangeError);
+            }
+        }
+
+        let params = Params::builder()
+            .metric(metric)
+            .maybe_min(min)
+            .maybe_max(max)
+            .build();
+
+        Ok(params)
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum AssertError {
+    #[error("Invalid property type, expected 'test.Assert'")]
+    PropertyTypeMismatch,
+
+    #[error("Invalid parameters format")]
+    ParamsFormatError,
+
+    #[error("Invalid metric")]
+    MetricError,
+
+    #[error("Invalid bound, at least one of min and max must be a number")]
+    BoundError,
+
+    #[error("Min must be less than or equal to max")]
+    RangeError,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_try_from_node_to_assert() -> Result<()> {
+        let json_str = r#"{"id":5,"type":"测试/断言","pos":[0,0],"size":[240,120],"flags":{},"order":3,"mode":0,"properties":{"type":"test.Assert","params":["total_trades",1,10]}}"#;
+        let node: Node = serde_json::from_str(json_str)?;
+        let assert = Assert::try_from(node)?;
+
+        assert_eq!(assert.metric(), AssertMetric::TotalTrades);
+        assert!(assert.check(dec!(5)).passed);
+        assert!(!assert.check(dec!(11)).passed);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_assert_open_bound() -> Result<()> {
+        let json_str = r#"{"id":5,"type":"测试/断言","pos":[0,0],"size":[240,120],"flags":{},"order":3,"mode":0,"properties":{"type":"test.Assert","params":["max_drawdown",null,0.2]}}"#;
+        let node: Node = serde_json::from_str(json_str)?;
+        let assert = Assert::try_from(node)?;
+
+        assert!(assert.check(dec!(0.15)).passed);
+        assert!(!assert.check(dec!(0.3)).passed);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_assert_invalid_params() {
+        let json_str = r#"{"id":5,"type":"测试/断言","pos":[0,0],"size":[240,120],"flags":{},"order":3,"mode":0,"properties":{"type":"test.Assert","params":["sharpe",1,null]}}"#;
+        let node: Node = serde_json::from_str(json_str).unwrap();
+        let result = Assert::try_from(node);
+
+        assert_eq!(result.unwrap_err().to_string(), "Invalid metric");
+
+        let json_str = r#"{"id":5,"type":"测试/断言","pos":[0,0],"size":[240,120],"flags":{},"order":3,"mode":0,"properties":{"type":"test.Assert","params":["realized_pnl",10,1]}}"#;
+        let node: Node = serde_json::from_str(json_str).unwrap();
+        let result = Assert::try_from(node);
+
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Min must be less than or equal to max"
+        );
+    }
+}
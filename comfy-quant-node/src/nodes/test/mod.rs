@@ -0,0 +1,3 @@
+mod assert;
+
+pub(crate) use assert::Assert;
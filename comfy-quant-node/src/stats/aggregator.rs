@@ -0,0 +1,224 @@
+use comfy_quant_base::{Exchange, Symbol};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, OnceLock},
+};
+use tokio::sync::watch;
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+// 进程内所有工作流共享的统计聚合服务
+static STATS_AGGREGATOR: OnceLock<StatsAggregator> = OnceLock::new();
+
+// 单个策略交易对的最新统计快照，金额以交易对的计价资产计
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct StrategySnapshot {
+    pub workflow_id: String,     // 工作流ID
+    pub node_id: i16,            // 策略节点ID
+    pub exchange: Exchange,      // 交易所
+    pub symbol: Symbol,          // 交易对
+    pub quote_asset: String,     // 计价资产
+    pub equity: Decimal,         // 持仓市值
+    pub realized_pnl: Decimal,   // 已实现盈亏
+    pub unrealized_pnl: Decimal, // 未实现盈亏
+    pub exposure: Decimal,       // 基础资产敞口市值
+    pub timestamp: i64,          // 快照时间(秒)
+}
+
+impl StrategySnapshot {
+    fn key(&self) -> SnapshotKey {
+        (
+            self.workflow_id.clone(),
+            self.node_id,
+            self.exchange.clone(),
+            self.symbol.clone(),
+        )
+    }
+
+    fn pnl(&self) -> Decimal {
+        self.realized_pnl + self.unrealized_pnl
+    }
+}
+
+// 同一计价资产下的聚合数据
+#[derive(Serialize, Debug, Default, Clone, PartialEq)]
+pub struct AggregateStats {
+    pub total_equity: Decimal,  // 总市值
+    pub daily_pnl: Decimal,     // 当日盈亏
+    pub open_exposure: Decimal, // 未平仓敞口
+    pub strategies: usize,      // 策略交易对数量
+}
+
+// 所有运行中工作流的聚合结果，按计价资产分组
+#[derive(Serialize, Debug, Default, Clone, PartialEq)]
+pub struct StatsAggregate {
+    pub quote_assets: HashMap<String, AggregateStats>,
+    pub updated_at: i64, // 最近一次快照时间(秒)
+}
+
+type SnapshotKey = (String, i16, Exchange, Symbol);
+
+#[derive(Debug)]
+struct Entry {
+    snapshot: StrategySnapshot,
+    day: i64,               // 快照所在日期(距纪元天数)
+    day_start_pnl: Decimal, // 当日开始时的累计盈亏
+}
+
+// 统计聚合服务，策略节点每次更新统计时推送快照，
+// 聚合结果常驻内存并通过 watch 通道推送，看板无需逐个查询数据库
+#[derive(Debug)]
+pub struct StatsAggregator {
+    entries: Mutex<HashMap<SnapshotKey, Entry>>,
+    sender: watch::Sender<Arc<StatsAggregate>>,
+}
+
+impl Default for StatsAggregator {
+    fn default() -> Self {
+        StatsAggregator {
+            entries: Mutex::new(HashMap::new()),
+            sender: watch::Sender::new(Arc::new(StatsAggregate::default())),
+        }
+    }
+}
+
+impl StatsAggregator {
+    pub fn new() -> Self {
+        StatsAggregator::default()
+    }
+
+    pub fn global() -> &'static StatsAggregator {
+        STATS_AGGREGATOR.get_or_init(StatsAggregator::new)
+    }
+
+    // 更新策略快照
+    pub fn update(&self, snapshot: StrategySnapshot) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let day = snapshot.timestamp.div_euclid(SECONDS_PER_DAY);
+
+        match entries.get_mut(&snapshot.key()) {
+            Some(entry) => {
+                // 跨日时以上一个快照的累计盈亏作为当日起点
+                if day != entry.day {
+                    entry.day = day;
+                    entry.day_start_pnl = entry.snapshot.pnl();
+                }
+
+                entry.snapshot = snapshot;
+            }
+            None => {
+                let entry = Entry {
+                    day,
+                    day_start_pnl: snapshot.pnl(),
+                    snapshot,
+                };
+
+                entries.insert(entry.snapshot.key(), entry);
+            }
+        }
+
+        self.publish(&entries);
+    }
+
+    // 工作流停止后移除其快照
+    pub fn remove_workflow(&self, workflow_id: &str) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let len = entries.len();
+
+        entries.retain(|(id, ..), _| id != workflow_id);
+
+        if entries.len() != len {
+            self.publish(&entries);
+        }
+    }
+
+    // 当前聚合结果
+    pub fn current(&self) -> Arc<StatsAggregate> {
+        Arc::clone(&self.sender.borrow())
+    }
+
+    // 订阅聚合结果的变化
+    pub fn subscribe(&self) -> watch::Receiver<Arc<StatsAggregate>> {
+        self.sender.subscribe()
+    }
+
+    fn publish(&self, entries: &HashMap<SnapshotKey, Entry>) {
+        let mut aggregate = StatsAggregate::default();
+
+        for entry in entries.values() {
+            let snapshot = &entry.snapshot;
+            let stats = aggregate
+                .quote_assets
+                .entry(snapshot.quote_asset.clone())
+                .or_default();
+
+            stats.total_equity += snapshot.equity;
+            stats.daily_pnl += snapshot.pnl() - entry.day_start_pnl;
+            stats.open_exposure += snapshot.exposure;
+            stats.strategies += 1;
+
+            aggregate.updated_at = aggregate.updated_at.max(snapshot.timestamp);
+        }
+
+        self.sender.send_replace(Arc::new(aggregate));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn snapshot(
+        workflow_id: &str,
+        equity: Decimal,
+        pnl: Decimal,
+        timestamp: i64,
+    ) -> StrategySnapshot {
+        StrategySnapshot {
+            workflow_id: workflow_id.to_string(),
+            node_id: 1,
+            exchange: Exchange::Binance,
+            symbol: "BTCUSDT".into(),
+            quote_asset: "USDT".to_string(),
+            equity,
+            realized_pnl: pnl,
+            unrealized_pnl: dec!(0),
+            exposure: equity / dec!(2),
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn test_stats_aggregator() {
+        let aggregator = StatsAggregator::new();
+        let mut rx = aggregator.subscribe();
+
+        aggregator.update(snapshot("a", dec!(1000), dec!(0), 0));
+        aggregator.update(snapshot("b", dec!(2000), dec!(10), 0));
+        aggregator.update(snapshot("a", dec!(1050), dec!(50), 100));
+
+        assert!(rx.has_changed().unwrap());
+        let aggregate = rx.borrow_and_update().clone();
+        let stats = &aggregate.quote_assets["USDT"];
+        assert_eq!(stats.total_equity, dec!(3050));
+        assert_eq!(stats.daily_pnl, dec!(50));
+        assert_eq!(stats.open_exposure, dec!(1525));
+        assert_eq!(stats.strategies, 2);
+        assert_eq!(aggregate.updated_at, 100);
+
+        // 跨日后当日盈亏重新计算
+        aggregator.update(snapshot("a", dec!(1080), dec!(80), SECONDS_PER_DAY));
+        assert_eq!(
+            aggregator.current().quote_assets["USDT"].daily_pnl,
+            dec!(30)
+        );
+
+        aggregator.remove_workflow("a");
+        let stats = &aggregator.current().quote_assets["USDT"];
+        assert_eq!(stats.total_equity, dec!(2000));
+        assert_eq!(stats.strategies, 1);
+    }
+}
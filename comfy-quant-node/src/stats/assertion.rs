@@ -0,0 +1,126 @@
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::fmt;
+
+// 断言的指标
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AssertMetric {
+    RealizedPnl,   // 已实现盈亏
+    UnrealizedPnl, // 未实现盈亏
+    TotalPnl,      // 总盈亏
+    TotalReturn,   // 总收益率
+    MaxDrawdown,   // 最大回撤比例
+    TotalTrades,   // 总交易次数
+}
+
+impl TryFrom<&str> for AssertMetric {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let metric = match value {
+            "realized_pnl" => AssertMetric::RealizedPnl,
+            "unrealized_pnl" => AssertMetric::UnrealizedPnl,
+            "total_pnl" => AssertMetric::TotalPnl,
+            "total_return" => AssertMetric::TotalReturn,
+            "max_drawdown" => AssertMetric::MaxDrawdown,
+            "total_trades" => AssertMetric::TotalTrades,
+            _ => anyhow::bail!("Invalid assert metric: {}", value),
+        };
+
+        Ok(metric)
+    }
+}
+
+impl AsRef<str> for AssertMetric {
+    fn as_ref(&self) -> &str {
+        match self {
+            AssertMetric::RealizedPnl => "realized_pnl",
+            AssertMetric::UnrealizedPnl => "unrealized_pnl",
+            AssertMetric::TotalPnl => "total_pnl",
+            AssertMetric::TotalReturn => "total_return",
+            AssertMetric::MaxDrawdown => "max_drawdown",
+            AssertMetric::TotalTrades => "total_trades",
+        }
+    }
+}
+
+// 工作流结束时的断言结果
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct AssertionResult {
+    pub node_id: u32,         // 断言节点ID
+    pub metric: AssertMetric, // 指标
+    pub value: Decimal,       // 实际值
+    pub min: Option<Decimal>, // 下限(含)
+    pub max: Option<Decimal>, // 上限(含)
+    pub passed: bool,         // 是否通过
+}
+
+impl AssertionResult {
+    pub fn new(
+        node_id: u32,
+        metric: AssertMetric,
+        value: Decimal,
+        min: Option<Decimal>,
+        max: Option<Decimal>,
+    ) -> Self {
+        let passed = min.is_none_or(|min| value >= min) && max.is_none_or(|max| value <= max);
+
+        AssertionResult {
+            node_id,
+            metric,
+            value,
+            min,
+            max,
+            passed,
+        }
+    }
+}
+
+impl fmt::Display for AssertionResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let fmt_opt = |value: Option<Decimal>| value.map_or("-".to_string(), |v| v.to_string());
+
+        write!(
+            f,
+            "[{}] node {} {} = {} (min {}, max {})",
+            if self.passed { "PASS" } else { "FAIL" },
+            self.node_id,
+            self.metric.as_ref(),
+            self.value,
+            fmt_opt(self.min),
+            fmt_opt(self.max),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_assertion_result() {
+        let result = AssertionResult::new(
+            1,
+            AssertMetric::RealizedPnl,
+            dec!(100),
+            Some(dec!(50)),
+            None,
+        );
+        assert!(result.passed);
+
+        let result = AssertionResult::new(
+            1,
+            AssertMetric::TotalTrades,
+            dec!(12),
+            Some(dec!(1)),
+            Some(dec!(10)),
+        );
+        assert!(!result.passed);
+        assert_eq!(
+            result.to_string(),
+            "[FAIL] node 1 total_trades = 12 (min 1, max 10)"
+        );
+    }
+}
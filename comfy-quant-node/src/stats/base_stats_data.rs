@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use comfy_quant_base::{Exchange, Symbol};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
@@ -21,4 +22,8 @@ pub struct BaseStatsData {
     pub total_quote_volume: Decimal,     // 总报价资产交易量
     pub realized_pnl: Decimal,           // 已实现盈亏
     pub unrealized_pnl: Decimal,         // 未实现盈亏
+    #[serde(default)]
+    pub first_trade_at: Option<DateTime<Utc>>, // 第一笔交易时间，回测时为模拟时间
+    #[serde(default)]
+    pub last_trade_at: Option<DateTime<Utc>>, // 最后一笔交易时间，回测时为模拟时间
 }
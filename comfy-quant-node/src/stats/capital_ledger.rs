@@ -0,0 +1,97 @@
+use comfy_quant_base::CapitalFlowKind;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+// 一笔资金变动，金额以计价资产计，流入为正，流出为负
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CapitalFlow {
+    pub kind: CapitalFlowKind, // 变动类型
+    pub amount: Decimal,       // 变动金额
+    pub timestamp: i64,        // 发生时间(秒)
+    #[serde(default)]
+    pub value_before: Decimal, // 变动前的持仓市值
+}
+
+// 策略资金账本，记录影响策略资金的每一笔变动
+// 外部资金(初始投入、追加、提取)用于计算时间加权和资金加权收益率，
+// 手续费和已实现盈亏仅用于对账
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct CapitalLedger {
+    flows: Vec<CapitalFlow>,
+}
+
+impl CapitalLedger {
+    pub fn new() -> Self {
+        CapitalLedger::default()
+    }
+
+    pub fn record(
+        &mut self,
+        kind: CapitalFlowKind,
+        amount: Decimal,
+        timestamp: i64,
+        value_before: Decimal,
+    ) -> Option<&CapitalFlow> {
+        if amount.is_zero() {
+            return None;
+        }
+
+        self.flows.push(CapitalFlow {
+            kind,
+            amount,
+            timestamp,
+            value_before,
+        });
+
+        self.flows.last()
+    }
+
+    pub fn flows(&self) -> &[CapitalFlow] {
+        &self.flows
+    }
+
+    // 外部资金变动
+    pub fn external_flows(&self) -> impl Iterator<Item = &CapitalFlow> {
+        self.flows.iter().filter(|flow| flow.kind.is_external())
+    }
+
+    // 净投入资金
+    pub fn net_contribution(&self) -> Decimal {
+        self.external_flows().map(|flow| flow.amount).sum()
+    }
+
+    // 某类变动的合计
+    pub fn total(&self, kind: CapitalFlowKind) -> Decimal {
+        self.flows
+            .iter()
+            .filter(|flow| flow.kind == kind)
+            .map(|flow| flow.amount)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_capital_ledger() {
+        let mut ledger = CapitalLedger::new();
+        ledger.record(CapitalFlowKind::Allocation, dec!(1000), 1, dec!(0));
+        ledger.record(CapitalFlowKind::Fee, dec!(-1), 2, dec!(0));
+        ledger.record(CapitalFlowKind::Pnl, dec!(50), 2, dec!(0));
+        ledger.record(CapitalFlowKind::Deposit, dec!(500), 3, dec!(0));
+        ledger.record(CapitalFlowKind::Withdrawal, dec!(-200), 4, dec!(0));
+
+        // 金额为0不记录
+        assert!(ledger
+            .record(CapitalFlowKind::Fee, dec!(0), 5, dec!(0))
+            .is_none());
+
+        assert_eq!(ledger.flows().len(), 5);
+        assert_eq!(ledger.external_flows().count(), 3);
+        assert_eq!(ledger.net_contribution(), dec!(1300));
+        assert_eq!(ledger.total(CapitalFlowKind::Pnl), dec!(50));
+    }
+}
@@ -0,0 +1,177 @@
+use anyhow::Result;
+use bon::Builder;
+use polars::{
+    df,
+    prelude::{DataFrame, IpcWriter, ParquetWriter, SerWriter},
+};
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+use std::{
+    fs::File,
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+};
+
+// 事件类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    Tick,   // 策略消费的行情
+    Signal, // 策略产生的交易信号
+    Order,  // 提交的订单
+    Fill,   // 订单成交
+    Stats,  // 订单更新后的统计快照
+}
+
+impl EventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventKind::Tick => "tick",
+            EventKind::Signal => "signal",
+            EventKind::Order => "order",
+            EventKind::Fill => "fill",
+            EventKind::Stats => "stats",
+        }
+    }
+}
+
+// 回测事件，字段展平以便按列导出
+#[derive(Debug, Clone, Builder, PartialEq)]
+#[builder(on(String, into))]
+pub struct Event {
+    pub time: i64, // 事件时间(毫秒)，回测中为模拟时间
+    pub kind: EventKind,
+    pub node_id: i16,
+    pub exchange: String,
+    pub symbol: String,
+    pub side: Option<String>,   // 订单方向
+    pub price: Option<Decimal>, // 行情价格、订单价格或成交均价
+    pub qty: Option<Decimal>,   // 订单数量或成交数量
+    pub detail: Option<String>, // 附加信息，统计快照为JSON
+}
+
+// 事件日志，工作流内所有节点共享，开启后才记录，避免实盘中占用内存
+#[derive(Debug, Default)]
+pub struct EventLog {
+    enabled: AtomicBool,
+    events: Mutex<Vec<Event>>,
+}
+
+impl EventLog {
+    pub fn enable(&self) {
+        self.enabled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn record(&self, event: Event) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        if let Ok(mut events) = self.events.lock() {
+            events.push(event);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.lock().map_or(0, |events| events.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // 按记录顺序转换为列式数据
+    pub fn to_data_frame(&self) -> Result<DataFrame> {
+        let events = self
+            .events
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Event log lock poisoned: {}", e))?;
+        let to_f64 = |value: Option<Decimal>| value.and_then(|value| value.to_f64());
+
+        let df = df!(
+            "time" => events.iter().map(|event| event.time).collect::<Vec<_>>(),
+            "kind" => events.iter().map(|event| event.kind.as_str()).collect::<Vec<_>>(),
+            "node_id" => events.iter().map(|event| event.node_id as i32).collect::<Vec<_>>(),
+            "exchange" => events.iter().map(|event| event.exchange.as_str()).collect::<Vec<_>>(),
+            "symbol" => events.iter().map(|event| event.symbol.as_str()).collect::<Vec<_>>(),
+            "side" => events.iter().map(|event| event.side.clone()).collect::<Vec<_>>(),
+            "price" => events.iter().map(|event| to_f64(event.price)).collect::<Vec<_>>(),
+            "qty" => events.iter().map(|event| to_f64(event.qty)).collect::<Vec<_>>(),
+            "detail" => events.iter().map(|event| event.detail.clone()).collect::<Vec<_>>(),
+        )?;
+
+        Ok(df)
+    }
+
+    // 导出为Arrow IPC文件，扩展名为.parquet时导出为Parquet文件
+    pub fn export(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let mut df = self.to_data_frame()?;
+        let mut file = File::create(path)?;
+
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("parquet") => {
+                ParquetWriter::new(&mut file).finish(&mut df)?;
+            }
+            _ => IpcWriter::new(&mut file).finish(&mut df)?,
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn tick_event(time: i64) -> Event {
+        Event::builder()
+            .time(time)
+            .kind(EventKind::Tick)
+            .node_id(1)
+            .exchange("binance")
+            .symbol("BTCUSDT")
+            .price(dec!(30000))
+            .build()
+    }
+
+    #[test]
+    fn test_event_log() -> Result<()> {
+        let event_log = EventLog::default();
+
+        // 未开启时不记录
+        event_log.record(tick_event(1000));
+        assert!(event_log.is_empty());
+
+        event_log.enable();
+        event_log.record(tick_event(2000));
+        event_log.record(
+            Event::builder()
+                .time(2000)
+                .kind(EventKind::Fill)
+                .node_id(2)
+                .exchange("binance")
+                .symbol("BTCUSDT")
+                .side("BUY")
+                .price(dec!(30000))
+                .qty(dec!(0.1))
+                .build(),
+        );
+        assert_eq!(event_log.len(), 2);
+
+        let df = event_log.to_data_frame()?;
+        assert_eq!(df.shape(), (2, 9));
+        let kinds = df.column("kind")?.as_materialized_series().str()?.clone();
+        assert_eq!(kinds.get(1), Some("fill"));
+        let qtys = df.column("qty")?.as_materialized_series().f64()?.clone();
+        assert_eq!(qtys.get(0), None);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,192 @@
+use crate::node_core::Tick;
+use anyhow::Result;
+use comfy_quant_base::{Exchange, Symbol};
+use comfy_quant_exchange::client::spot_client::base::{Order, OrderSide};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+// 执行质量基准，用市场到达价格、区间VWAP、TWAP衡量策略成交价格
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct ExecutionBenchmark {
+    market_volume: Decimal,       // 市场成交量
+    market_quote_volume: Decimal, // 市场成交额，按tick价格 * 成交量估算
+    price_sum: Decimal,           // tick价格累计，用于TWAP
+    ticks: u64,                   // tick数量
+    last_price: Option<Decimal>,  // 最新价格，作为下一笔订单的到达价格
+    buy_qty: Decimal,             // 策略买入数量
+    buy_quote_qty: Decimal,       // 策略买入金额
+    sell_qty: Decimal,            // 策略卖出数量
+    sell_quote_qty: Decimal,      // 策略卖出金额
+    arrival_cost: Decimal,        // 相对到达价格的执行成本(计价货币)，正数表示不利
+}
+
+// 执行质量报告
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct ExecutionReport {
+    pub exchange: Exchange,                  // 交易所
+    pub symbol: Symbol,                      // 交易对
+    pub vwap: Option<Decimal>,               // 区间成交量加权均价
+    pub twap: Option<Decimal>,               // 区间时间加权均价
+    pub avg_buy_price: Option<Decimal>,      // 策略买入均价
+    pub avg_sell_price: Option<Decimal>,     // 策略卖出均价
+    pub buy_vs_vwap_bps: Option<Decimal>,    // 买入均价相对VWAP(基点)，正数表示买贵
+    pub sell_vs_vwap_bps: Option<Decimal>,   // 卖出均价相对VWAP(基点)，正数表示卖便宜
+    pub arrival_cost: Decimal,               // 相对到达价格的执行成本
+    pub participation_rate: Option<Decimal>, // 策略成交量占市场成交量的比例
+}
+
+impl ExecutionBenchmark {
+    pub fn update_with_tick(&mut self, tick: &Tick) {
+        self.market_volume += tick.volume;
+        self.market_quote_volume += tick.price * tick.volume;
+        self.price_sum += tick.price;
+        self.ticks += 1;
+        self.last_price = Some(tick.price);
+    }
+
+    pub fn update_with_order(&mut self, order: &Order) -> Result<()> {
+        let qty = order.base_asset_amount()?;
+        let quote_qty = order.quote_asset_amount()?;
+
+        if qty.is_zero() {
+            return Ok(());
+        }
+
+        match order.order_side {
+            OrderSide::Buy => {
+                self.buy_qty += qty;
+                self.buy_quote_qty += quote_qty;
+            }
+            OrderSide::Sell => {
+                self.sell_qty += qty;
+                self.sell_quote_qty += quote_qty;
+            }
+        }
+
+        // 买入价格高于到达价格、卖出价格低于到达价格都是成本
+        if let Some(arrival_price) = self.last_price {
+            let cost = quote_qty - qty * arrival_price;
+
+            self.arrival_cost += match order.order_side {
+                OrderSide::Buy => cost,
+                OrderSide::Sell => -cost,
+            };
+        }
+
+        Ok(())
+    }
+
+    pub fn vwap(&self) -> Option<Decimal> {
+        (!self.market_volume.is_zero()).then(|| self.market_quote_volume / self.market_volume)
+    }
+
+    pub fn twap(&self) -> Option<Decimal> {
+        (self.ticks > 0).then(|| self.price_sum / Decimal::from(self.ticks))
+    }
+
+    pub fn participation_rate(&self) -> Option<Decimal> {
+        (!self.market_volume.is_zero()).then(|| (self.buy_qty + self.sell_qty) / self.market_volume)
+    }
+
+    pub fn report(&self, exchange: &Exchange, symbol: &Symbol) -> ExecutionReport {
+        let avg_price =
+            |qty: Decimal, quote_qty: Decimal| (!qty.is_zero()).then(|| quote_qty / qty);
+        let avg_buy_price = avg_price(self.buy_qty, self.buy_quote_qty);
+        let avg_sell_price = avg_price(self.sell_qty, self.sell_quote_qty);
+        let vwap = self.vwap();
+
+        let vs_vwap_bps = |diff: Decimal, vwap: Decimal| diff / vwap * dec!(10000);
+        let buy_vs_vwap_bps = avg_buy_price
+            .zip(vwap)
+            .map(|(price, vwap)| vs_vwap_bps(price - vwap, vwap));
+        let sell_vs_vwap_bps = avg_sell_price
+            .zip(vwap)
+            .map(|(price, vwap)| vs_vwap_bps(vwap - price, vwap));
+
+        ExecutionReport {
+            exchange: exchange.clone(),
+            symbol: symbol.clone(),
+            vwap,
+            twap: self.twap(),
+            avg_buy_price,
+            avg_sell_price,
+            buy_vs_vwap_bps,
+            sell_vs_vwap_bps,
+            arrival_cost: self.arrival_cost,
+            participation_rate: self.participation_rate(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use comfy_quant_exchange::client::spot_client::base::{OrderStatus, OrderType};
+
+    fn tick(price: Decimal, volume: Decimal) -> Tick {
+        Tick::builder()
+            .timestamp(0)
+            .symbol("BTCUSDT".into())
+            .price(price)
+            .volume(volume)
+            .build()
+    }
+
+    fn order(side: OrderSide, price: Decimal, qty: Decimal) -> Order {
+        Order::builder()
+            .exchange(Exchange::Binance)
+            .symbol("BTCUSDT")
+            .order_id("1")
+            .price(price.to_string())
+            .avg_price(price.to_string())
+            .orig_qty(qty.to_string())
+            .executed_qty(qty.to_string())
+            .cumulative_quote_qty((price * qty).to_string())
+            .order_type(OrderType::Market)
+            .order_side(side)
+            .order_status(OrderStatus::Filled)
+            .time(0)
+            .update_time(0)
+            .build()
+    }
+
+    #[test]
+    fn test_execution_benchmark() -> Result<()> {
+        let mut benchmark = ExecutionBenchmark::default();
+
+        benchmark.update_with_tick(&tick(dec!(100), dec!(10)));
+        benchmark.update_with_order(&order(OrderSide::Buy, dec!(101), dec!(1)))?;
+        benchmark.update_with_tick(&tick(dec!(110), dec!(30)));
+        benchmark.update_with_order(&order(OrderSide::Sell, dec!(109), dec!(1)))?;
+
+        let report = benchmark.report(&Exchange::Binance, &"BTCUSDT".into());
+
+        // (100 * 10 + 110 * 30) / 40
+        assert_eq!(report.vwap, Some(dec!(107.5)));
+        assert_eq!(report.twap, Some(dec!(105)));
+        assert_eq!(report.avg_buy_price, Some(dec!(101)));
+        assert_eq!(report.avg_sell_price, Some(dec!(109)));
+        assert_eq!(
+            report.sell_vs_vwap_bps.map(|bps| bps.round_dp(2)),
+            Some(dec!(-139.53))
+        );
+        // 买入比到达价格贵1，卖出比到达价格便宜1
+        assert_eq!(report.arrival_cost, dec!(2));
+        assert_eq!(report.participation_rate, Some(dec!(0.05)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_execution_benchmark_without_volume() {
+        let mut benchmark = ExecutionBenchmark::default();
+        benchmark.update_with_tick(&tick(dec!(100), dec!(0)));
+
+        let report = benchmark.report(&Exchange::Binance, &"BTCUSDT".into());
+
+        assert_eq!(report.vwap, None);
+        assert_eq!(report.twap, Some(dec!(100)));
+        assert_eq!(report.participation_rate, None);
+    }
+}
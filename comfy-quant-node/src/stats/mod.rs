@@ -1,7 +1,26 @@
+mod aggregator;
+mod assertion;
 mod base_stats_data;
+mod capital_ledger;
+mod event_log;
+mod execution_benchmark;
 mod futures_stats_data;
+mod performance;
+mod resource_usage;
 mod spot_stats;
 mod spot_stats_data;
+mod write_buffer;
 
+pub use aggregator::{AggregateStats, StatsAggregate, StatsAggregator, StrategySnapshot};
+pub use assertion::{AssertMetric, AssertionResult};
+pub use capital_ledger::{CapitalFlow, CapitalLedger};
+pub use event_log::{Event, EventKind, EventLog};
+pub use execution_benchmark::{ExecutionBenchmark, ExecutionReport};
+pub use performance::{
+    merge_equity_curves, money_weighted_return, time_weighted_return, PerformanceReport,
+    TradeRecord,
+};
+pub use resource_usage::{ResourceMeter, ResourceUsage};
 pub use spot_stats::SpotStats;
 pub use spot_stats_data::SpotStatsData;
+pub(crate) use write_buffer::{PendingWrite, WriteBuffer};
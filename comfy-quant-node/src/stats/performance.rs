@@ -0,0 +1,447 @@
+use super::CapitalFlow;
+use rust_decimal::{
+    prelude::{FromPrimitive, ToPrimitive},
+    Decimal,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+const SECONDS_PER_YEAR: f64 = 365.0 * 86_400.0;
+const EQUITY_SAMPLE_SECS: i64 = 3600; // 净值序列的采样周期，每小时保留最后一个值
+
+// 净值序列按小时采样，同一小时内只保留最后一个值
+pub(crate) fn sample_equity(curve: &mut Vec<(i64, Decimal)>, timestamp: i64, equity: Decimal) {
+    let bucket = timestamp.div_euclid(EQUITY_SAMPLE_SECS);
+
+    match curve.last_mut() {
+        Some(last) if last.0.div_euclid(EQUITY_SAMPLE_SECS) == bucket => {
+            *last = (timestamp, equity)
+        }
+        _ => curve.push((timestamp, equity)),
+    }
+}
+
+// 合并多个节点的净值序列，按小时对齐后求和，节点在某小时没有采样时沿用其上一个值
+pub fn merge_equity_curves(curves: &[Vec<(i64, Decimal)>]) -> Vec<(i64, Decimal)> {
+    let mut buckets = BTreeMap::<i64, (i64, Vec<Option<Decimal>>)>::new();
+
+    for (i, curve) in curves.iter().enumerate() {
+        for (timestamp, equity) in curve {
+            let (latest, values) = buckets
+                .entry(timestamp.div_euclid(EQUITY_SAMPLE_SECS))
+                .or_insert_with(|| (*timestamp, vec![None; curves.len()]));
+
+            *latest = (*latest).max(*timestamp);
+            values[i] = Some(*equity);
+        }
+    }
+
+    let mut last_values = vec![None; curves.len()];
+
+    buckets
+        .into_values()
+        .map(|(timestamp, values)| {
+            for (last, value) in last_values.iter_mut().zip(values) {
+                if value.is_some() {
+                    *last = value;
+                }
+            }
+
+            (timestamp, last_values.iter().flatten().sum())
+        })
+        .collect()
+}
+
+// 交易记录汇总，用于计算盈亏比和持仓时间
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct TradeRecord {
+    pub gross_profit: Decimal,  // 盈利卖出的盈利合计
+    pub gross_loss: Decimal,    // 亏损卖出的亏损合计(正数)
+    pub win_trades: u64,        // 盈利交易次数
+    pub loss_trades: u64,       // 亏损交易次数
+    pub holding_secs: i64,      // 已结束的持仓周期时长合计(秒)
+    pub round_trips: u64,       // 已结束的持仓周期数量，从开仓到清仓为一个周期
+    pub opened_at: Option<i64>, // 当前持仓周期的开始时间(秒)
+}
+
+impl TradeRecord {
+    // 记录一笔卖出的盈亏，部分成交的订单只在第一笔成交时计入交易次数
+    pub fn record_pnl(&mut self, pnl: Decimal, new_trade: bool) {
+        if pnl > Decimal::ZERO {
+            self.gross_profit += pnl;
+        } else {
+            self.gross_loss -= pnl;
+        }
+
+        if new_trade {
+            if pnl > Decimal::ZERO {
+                self.win_trades += 1;
+            } else {
+                self.loss_trades += 1;
+            }
+        }
+    }
+
+    // 持仓变化后更新持仓周期
+    pub fn update_position(&mut self, has_position: bool, timestamp: i64) {
+        match (has_position, self.opened_at) {
+            (true, None) => self.opened_at = Some(timestamp),
+            (false, Some(opened_at)) => {
+                self.holding_secs += (timestamp - opened_at).max(0);
+                self.round_trips += 1;
+                self.opened_at = None;
+            }
+            _ => {}
+        }
+    }
+
+    // 截至某时间的持仓时长合计，包括未结束的持仓周期
+    pub fn exposure_secs(&self, timestamp: i64) -> i64 {
+        self.holding_secs
+            + self
+                .opened_at
+                .map_or(0, |opened_at| (timestamp - opened_at).max(0))
+    }
+
+    // 合并多个节点的交易记录
+    pub fn merge(&mut self, other: &TradeRecord) {
+        self.gross_profit += other.gross_profit;
+        self.gross_loss += other.gross_loss;
+        self.win_trades += other.win_trades;
+        self.loss_trades += other.loss_trades;
+        self.holding_secs += other.holding_secs;
+        self.round_trips += other.round_trips;
+        self.opened_at = match (self.opened_at, other.opened_at) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+    }
+}
+
+// 绩效报告，由净值序列和交易记录计算，无风险利率按0计。
+// 净值序列包含追加、提取资金的影响，有资金进出时收益类指标仅供参考
+#[derive(Serialize, Debug, Default, Clone, PartialEq)]
+pub struct PerformanceReport {
+    pub total_return: Decimal,              // 净值序列的总收益率
+    pub annualized_return: Option<Decimal>, // 年化收益率，不足一天时为空
+    pub volatility: Option<Decimal>,        // 年化波动率
+    pub sharpe_ratio: Option<Decimal>,      // 夏普比率
+    pub sortino_ratio: Option<Decimal>,     // 索提诺比率，只计下行波动
+    pub calmar_ratio: Option<Decimal>,      // 卡玛比率，年化收益率 / 最大回撤
+    pub max_drawdown: Decimal,              // 最大回撤比例
+    pub profit_factor: Option<Decimal>,     // 盈利因子，盈利合计 / 亏损合计
+    pub payoff_ratio: Option<Decimal>,      // 盈亏比，平均盈利 / 平均亏损
+    pub win_rate: Decimal,                  // 胜率
+    pub exposure_time: Decimal,             // 持仓时间占比，多个节点合并时上限为1
+    pub avg_trade_duration: Option<i64>,    // 已结束持仓周期的平均时长(秒)
+}
+
+impl PerformanceReport {
+    pub fn new(equity_curve: &[(i64, Decimal)], trades: &TradeRecord) -> Self {
+        let to_decimal = |value: f64| Decimal::from_f64(value).map(|value| value.round_dp(8));
+
+        let points = equity_curve
+            .iter()
+            .filter_map(|(timestamp, equity)| Some((*timestamp, equity.to_f64()?)))
+            .collect::<Vec<_>>();
+
+        let returns = points
+            .windows(2)
+            .filter(|w| w[0].1 > 0.0)
+            .map(|w| w[1].1 / w[0].1 - 1.0)
+            .collect::<Vec<_>>();
+
+        let (start, end) = match (points.first(), points.last()) {
+            (Some(first), Some(last)) => (*first, *last),
+            _ => ((0, 0.0), (0, 0.0)),
+        };
+        let span = (end.0 - start.0) as f64;
+
+        let total_return = if start.1 > 0.0 {
+            end.1 / start.1 - 1.0
+        } else {
+            0.0
+        };
+
+        let annualized_return = (span >= 86_400.0 && total_return > -1.0)
+            .then(|| (1.0 + total_return).powf(SECONDS_PER_YEAR / span) - 1.0);
+
+        // 按平均采样间隔年化
+        let periods_per_year = (!returns.is_empty() && span > 0.0)
+            .then(|| SECONDS_PER_YEAR / (span / returns.len() as f64));
+
+        let n = returns.len() as f64;
+        let mean = returns.iter().sum::<f64>() / n;
+        let std = (returns.len() >= 2)
+            .then(|| (returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (n - 1.0)).sqrt());
+        let downside = (!returns.is_empty())
+            .then(|| (returns.iter().map(|r| r.min(0.0).powi(2)).sum::<f64>() / n).sqrt());
+
+        let ratio = |deviation: Option<f64>| {
+            let deviation = deviation.filter(|deviation| *deviation > 0.0)?;
+            to_decimal(mean / deviation * periods_per_year?.sqrt())
+        };
+
+        let mut peak = f64::MIN;
+        let max_drawdown = points.iter().fold(0.0_f64, |max_drawdown, (_, equity)| {
+            peak = peak.max(*equity);
+
+            if peak > 0.0 {
+                max_drawdown.max(1.0 - equity / peak)
+            } else {
+                max_drawdown
+            }
+        });
+
+        let decided_trades = trades.win_trades + trades.loss_trades;
+
+        PerformanceReport {
+            total_return: to_decimal(total_return).unwrap_or_default(),
+            annualized_return: annualized_return.and_then(to_decimal),
+            volatility: std
+                .zip(periods_per_year)
+                .and_then(|(std, periods)| to_decimal(std * periods.sqrt())),
+            sharpe_ratio: ratio(std),
+            sortino_ratio: ratio(downside),
+            calmar_ratio: annualized_return
+                .filter(|_| max_drawdown > 0.0)
+                .and_then(|annualized_return| to_decimal(annualized_return / max_drawdown)),
+            max_drawdown: to_decimal(max_drawdown).unwrap_or_default(),
+            profit_factor: (!trades.gross_loss.is_zero())
+                .then(|| trades.gross_profit / trades.gross_loss),
+            payoff_ratio: (trades.win_trades > 0
+                && trades.loss_trades > 0
+                && !trades.gross_loss.is_zero())
+            .then(|| {
+                (trades.gross_profit / Decimal::from(trades.win_trades))
+                    / (trades.gross_loss / Decimal::from(trades.loss_trades))
+            }),
+            win_rate: match decided_trades {
+                0 => Decimal::ZERO,
+                _ => Decimal::from(trades.win_trades) / Decimal::from(decided_trades),
+            },
+            exposure_time: if span > 0.0 {
+                (Decimal::from(trades.exposure_secs(end.0)) / Decimal::from(end.0 - start.0))
+                    .min(Decimal::ONE)
+            } else {
+                Decimal::ZERO
+            },
+            avg_trade_duration: (trades.round_trips > 0)
+                .then(|| trades.holding_secs / trades.round_trips as i64),
+        }
+    }
+}
+
+// 时间加权收益率(TWR)
+// 以每笔外部资金变动为界划分子区间，各子区间收益率连乘，剔除资金进出时点和金额的影响
+pub fn time_weighted_return<'a>(
+    flows: impl IntoIterator<Item = &'a CapitalFlow>,
+    end_value: Decimal,
+) -> Decimal {
+    let mut growth = Decimal::ONE;
+    let mut start_value = None;
+
+    for flow in flows {
+        if let Some(start_value) = start_value.filter(|value: &Decimal| !value.is_zero()) {
+            growth *= flow.value_before / start_value;
+        }
+
+        start_value = Some(flow.value_before + flow.amount);
+    }
+
+    match start_value {
+        Some(start_value) if !start_value.is_zero() => {
+            growth * end_value / start_value - Decimal::ONE
+        }
+        _ => Decimal::ZERO,
+    }
+}
+
+// 资金加权收益率(IRR，年化)
+// 求解收益率 r，使每笔外部资金按 r 复利到期末的终值之和等于期末市值
+// 资金变动跨度不足一天或无解时返回 None
+pub fn money_weighted_return<'a>(
+    flows: impl IntoIterator<Item = &'a CapitalFlow>,
+    end_timestamp: i64,
+    end_value: Decimal,
+) -> Option<Decimal> {
+    let flows = flows
+        .into_iter()
+        .map(|flow| {
+            let years = (end_timestamp - flow.timestamp) as f64 / SECONDS_PER_YEAR;
+            Some((years, flow.amount.to_f64()?))
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let end_value = end_value.to_f64()?;
+
+    let span = flows.iter().map(|(years, _)| *years).fold(0.0, f64::max);
+    if span * 365.0 < 1.0 {
+        return None;
+    }
+
+    // 期末终值差额
+    let npv = |rate: f64| {
+        flows
+            .iter()
+            .map(|(years, amount)| amount * (1.0 + rate).powf(*years))
+            .sum::<f64>()
+            - end_value
+    };
+
+    // 二分法求解，先扩大上界直到区间内有解
+    let mut low = -0.9999;
+    let mut high = 1.0;
+
+    while npv(low).signum() == npv(high).signum() {
+        high *= 10.0;
+
+        if high > 1e6 {
+            return None;
+        }
+    }
+
+    for _ in 0..200 {
+        let mid = (low + high) / 2.0;
+
+        if npv(mid).signum() == npv(low).signum() {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+
+    Decimal::from_f64((low + high) / 2.0).map(|rate| rate.round_dp(8))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use comfy_quant_base::CapitalFlowKind;
+    use rust_decimal_macros::dec;
+
+    const DAY: i64 = 86_400;
+
+    fn flow(
+        kind: CapitalFlowKind,
+        amount: Decimal,
+        timestamp: i64,
+        value_before: Decimal,
+    ) -> CapitalFlow {
+        CapitalFlow {
+            kind,
+            amount,
+            timestamp,
+            value_before,
+        }
+    }
+
+    #[test]
+    fn test_time_weighted_return() {
+        // 1000 涨到 1100(+10%)，追加 900 后 2000 跌到 1800(-10%)
+        let flows = [
+            flow(CapitalFlowKind::Allocation, dec!(1000), 0, dec!(0)),
+            flow(CapitalFlowKind::Deposit, dec!(900), DAY, dec!(1100)),
+        ];
+
+        assert_eq!(time_weighted_return(&flows, dec!(1800)), dec!(-0.01));
+        assert_eq!(time_weighted_return(&[], dec!(1800)), dec!(0));
+    }
+
+    #[test]
+    fn test_money_weighted_return() {
+        // 投入 1000，一年后市值 1100
+        let flows = [flow(CapitalFlowKind::Allocation, dec!(1000), 0, dec!(0))];
+        let irr = money_weighted_return(&flows, 365 * DAY, dec!(1100)).unwrap();
+        assert!((irr - dec!(0.1)).abs() < dec!(0.0001));
+
+        // 半年后追加 1000，年末市值 2100，大部分收益来自初始资金
+        let flows = [
+            flow(CapitalFlowKind::Allocation, dec!(1000), 0, dec!(0)),
+            flow(
+                CapitalFlowKind::Deposit,
+                dec!(1000),
+                365 * DAY / 2,
+                dec!(1100),
+            ),
+        ];
+        let irr = money_weighted_return(&flows, 365 * DAY, dec!(2100)).unwrap();
+        assert!(irr > dec!(0.06) && irr < dec!(0.07));
+
+        // 跨度不足一天
+        assert_eq!(money_weighted_return(&flows[..1], 100, dec!(1100)), None);
+    }
+
+    #[test]
+    fn test_sample_equity() {
+        let mut curve = vec![];
+
+        sample_equity(&mut curve, 0, dec!(100));
+        sample_equity(&mut curve, 1800, dec!(101));
+        sample_equity(&mut curve, 3600, dec!(102));
+
+        assert_eq!(curve, vec![(1800, dec!(101)), (3600, dec!(102))]);
+
+        // 第二个节点在第二个小时没有采样，沿用上一个值
+        let merged = merge_equity_curves(&[curve, vec![(60, dec!(50))]]);
+        assert_eq!(merged, vec![(1800, dec!(151)), (3600, dec!(152))]);
+    }
+
+    #[test]
+    fn test_trade_record() {
+        let mut trades = TradeRecord::default();
+
+        trades.update_position(true, 0);
+        trades.record_pnl(dec!(30), true);
+        trades.record_pnl(dec!(10), false);
+        trades.record_pnl(dec!(-20), true);
+        trades.update_position(false, 3600);
+        trades.update_position(true, 7200);
+
+        assert_eq!(trades.gross_profit, dec!(40));
+        assert_eq!(trades.gross_loss, dec!(20));
+        assert_eq!(trades.win_trades, 1);
+        assert_eq!(trades.loss_trades, 1);
+        assert_eq!(trades.round_trips, 1);
+        assert_eq!(trades.exposure_secs(9000), 5400);
+    }
+
+    #[test]
+    fn test_performance_report() {
+        // 两天内每小时净值交替涨跌，整体上涨
+        let curve = (0..=48)
+            .map(|i| {
+                let base = if i % 2 == 0 { dec!(100) } else { dec!(98) };
+                (i * 3600, base + Decimal::from(i))
+            })
+            .collect::<Vec<_>>();
+
+        let trades = TradeRecord {
+            gross_profit: dec!(60),
+            gross_loss: dec!(20),
+            win_trades: 3,
+            loss_trades: 2,
+            holding_secs: 3 * DAY / 2,
+            round_trips: 3,
+            opened_at: None,
+        };
+
+        let report = PerformanceReport::new(&curve, &trades);
+
+        assert_eq!(report.total_return, dec!(0.48));
+        assert!(report.annualized_return.unwrap() > dec!(0));
+        assert!(report.volatility.unwrap() > dec!(0));
+        assert!(report.sharpe_ratio.unwrap() > dec!(0));
+        assert!(report.sortino_ratio.unwrap() > report.sharpe_ratio.unwrap());
+        assert!(report.calmar_ratio.unwrap() > dec!(0));
+        assert_eq!(report.max_drawdown, dec!(0.01));
+        assert_eq!(report.profit_factor, Some(dec!(3)));
+        assert_eq!(report.payoff_ratio, Some(dec!(2)));
+        assert_eq!(report.win_rate, dec!(0.6));
+        assert_eq!(report.exposure_time, dec!(0.75));
+        assert_eq!(report.avg_trade_duration, Some(DAY / 2));
+
+        // 没有净值和交易
+        let report = PerformanceReport::new(&[], &TradeRecord::default());
+        assert_eq!(report, PerformanceReport::default());
+    }
+}
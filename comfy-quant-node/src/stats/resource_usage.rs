@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+// 工作流执行的资源消耗，用于分析回测慢的原因
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResourceUsage {
+    pub db_rows_read: u64,      // 读取的数据库行数
+    pub db_rows_written: u64,   // 写入的数据库行数
+    pub exchange_requests: u64, // 交易接口请求次数，回测中为模拟账户的请求
+}
+
+impl ResourceUsage {
+    // 相对基准的增量，用于计算单次执行的资源消耗
+    pub fn since(&self, baseline: &ResourceUsage) -> ResourceUsage {
+        ResourceUsage {
+            db_rows_read: self.db_rows_read.saturating_sub(baseline.db_rows_read),
+            db_rows_written: self
+                .db_rows_written
+                .saturating_sub(baseline.db_rows_written),
+            exchange_requests: self
+                .exchange_requests
+                .saturating_sub(baseline.exchange_requests),
+        }
+    }
+}
+
+// 资源计数器，工作流内所有节点共享
+#[derive(Debug, Default)]
+pub struct ResourceMeter {
+    db_rows_read: AtomicU64,
+    db_rows_written: AtomicU64,
+    exchange_requests: AtomicU64,
+}
+
+impl ResourceMeter {
+    pub(crate) fn add_db_rows_read(&self, rows: u64) {
+        self.db_rows_read.fetch_add(rows, Ordering::Relaxed);
+    }
+
+    pub(crate) fn add_db_rows_written(&self, rows: u64) {
+        self.db_rows_written.fetch_add(rows, Ordering::Relaxed);
+    }
+
+    pub(crate) fn add_exchange_requests(&self, requests: u64) {
+        self.exchange_requests
+            .fetch_add(requests, Ordering::Relaxed);
+    }
+
+    // 累计的资源消耗
+    pub fn usage(&self) -> ResourceUsage {
+        ResourceUsage {
+            db_rows_read: self.db_rows_read.load(Ordering::Relaxed),
+            db_rows_written: self.db_rows_written.load(Ordering::Relaxed),
+            exchange_requests: self.exchange_requests.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resource_meter() {
+        let meter = ResourceMeter::default();
+        meter.add_db_rows_read(100);
+        meter.add_exchange_requests(2);
+
+        let baseline = meter.usage();
+        meter.add_db_rows_read(50);
+        meter.add_db_rows_written(3);
+        meter.add_exchange_requests(1);
+
+        assert_eq!(
+            meter.usage(),
+            ResourceUsage {
+                db_rows_read: 150,
+                db_rows_written: 3,
+                exchange_requests: 3,
+            }
+        );
+        assert_eq!(
+            meter.usage().since(&baseline),
+            ResourceUsage {
+                db_rows_read: 50,
+                db_rows_written: 3,
+                exchange_requests: 1,
+            }
+        );
+    }
+}
@@ -1,8 +1,11 @@
-use super::spot_stats_data::SpotStatsData;
+use super::{
+    spot_stats_data::SpotStatsData, ExecutionReport, PendingWrite, StatsAggregator, TradeRecord,
+};
 use crate::node_core::{NodeContext, Tick};
 use anyhow::Result;
-use comfy_quant_base::{Exchange, ExchangeSymbolKey, Symbol};
-use comfy_quant_exchange::client::spot_client::base::Order;
+use comfy_quant_base::{millis_to_datetime, Exchange, ExchangeSymbolKey, Symbol};
+use comfy_quant_database::order::{CreateOrderParams, UpdateOrderParams};
+use comfy_quant_exchange::client::spot_client::base::{Order, OrderUpdate, SymbolInformation};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -12,6 +15,8 @@ type SpotStatsDataMap = HashMap<ExchangeSymbolKey, SpotStatsData>;
 #[derive(Serialize, Deserialize, Debug, Default)]
 pub struct SpotStats {
     data: SpotStatsDataMap,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    open_orders: HashMap<String, Decimal>, // 未完结订单已计入统计的成交数量，推送的成交只统计新增部分
 }
 
 impl AsRef<SpotStatsDataMap> for SpotStats {
@@ -30,6 +35,7 @@ impl SpotStats {
     pub fn new() -> Self {
         SpotStats {
             data: SpotStatsDataMap::new(),
+            open_orders: HashMap::new(),
         }
     }
 
@@ -65,10 +71,10 @@ impl SpotStats {
         symbol: &Symbol,
         initial_base: &Decimal,
         initial_quote: &Decimal,
-        initial_price: &Decimal,
+        initial_tick: &Tick,
     ) -> Result<()> {
         self.get_or_insert(exchange, symbol)
-            .initialize_balance(ctx, initial_base, initial_quote, initial_price)
+            .initialize_balance(ctx, initial_base, initial_quote, initial_tick)
             .await?;
         Ok(())
     }
@@ -80,9 +86,11 @@ impl SpotStats {
         symbol: &Symbol,
         tick: &Tick,
     ) -> Result<()> {
-        self.get_or_insert(exchange, symbol)
-            .update_with_tick(ctx, tick)
-            .await?;
+        let data = self.get_or_insert(exchange, symbol);
+        data.update_with_tick(ctx, tick).await?;
+
+        StatsAggregator::global().update(data.snapshot(ctx));
+
         Ok(())
     }
 
@@ -99,13 +107,195 @@ impl SpotStats {
 
         Ok(())
     }
+
+    // 记录下单返回的订单，未完结的订单等待推送后续成交
+    pub fn track_order(&mut self, order: &Order) -> Result<()> {
+        if order.order_status.is_open() {
+            self.open_orders
+                .insert(order.order_id.clone(), order.executed_qty.parse()?);
+        } else {
+            self.open_orders.remove(&order.order_id);
+        }
+
+        Ok(())
+    }
+
+    // 保存下单返回的订单，成交记录可追溯
+    pub async fn save_order(&self, ctx: &NodeContext, order: &Order) -> Result<()> {
+        let data = CreateOrderParams::builder()
+            .workflow_id(ctx.workflow_id())
+            .node_id(ctx.node_id())
+            .exchange(order.exchange.clone())
+            .symbol(order.symbol.clone())
+            .order_id(order.order_id.clone())
+            .maybe_client_order_id(order.client_order_id.clone())
+            .order_type(order.order_type.as_ref())
+            .order_side(order.order_side.as_ref())
+            .order_status(order.order_status.as_ref())
+            .price(order.price.parse::<Decimal>()?)
+            .avg_price(order.avg_price.parse::<Decimal>()?)
+            .orig_qty(order.orig_qty.parse::<Decimal>()?)
+            .executed_qty(order.executed_qty.parse::<Decimal>()?)
+            .cumulative_quote_qty(order.cumulative_quote_qty.parse::<Decimal>()?)
+            .order_time(millis_to_datetime(order.time)?)
+            .update_time(millis_to_datetime(order.update_time)?)
+            .build();
+
+        ctx.write_buffer()
+            .write(ctx.db(), PendingWrite::Order(data))
+            .await
+    }
+
+    // 保存推送的订单更新，只处理本节点跟踪的订单
+    pub async fn save_order_update(&self, ctx: &NodeContext, update: &OrderUpdate) -> Result<()> {
+        if !self.open_orders.contains_key(&update.order_id) {
+            return Ok(());
+        }
+
+        let data = UpdateOrderParams::builder()
+            .workflow_id(ctx.workflow_id())
+            .node_id(ctx.node_id())
+            .exchange(update.exchange.clone())
+            .order_id(update.order_id.clone())
+            .order_status(update.order_status.as_ref())
+            .executed_qty(update.executed_qty)
+            .last_quote_qty(update.last_qty * update.last_price)
+            .update_time(millis_to_datetime(update.update_time)?)
+            .build();
+
+        ctx.write_buffer()
+            .write(ctx.db(), PendingWrite::OrderUpdate(data))
+            .await
+    }
+
+    // 跟踪中的未完结订单，按订单ID排序
+    pub fn open_order_ids(&self) -> Vec<String> {
+        let mut order_ids = self.open_orders.keys().cloned().collect::<Vec<_>>();
+        order_ids.sort();
+        order_ids
+    }
+
+    // 推送的订单更新中尚未计入统计的成交，只处理本节点跟踪的订单
+    pub fn order_fill(&mut self, update: &OrderUpdate) -> Option<Order> {
+        let filled_qty = self.open_orders.get(&update.order_id).copied()?;
+
+        if update.order_status.is_open() {
+            self.open_orders
+                .insert(update.order_id.clone(), filled_qty.max(update.executed_qty));
+        } else {
+            self.open_orders.remove(&update.order_id);
+        }
+
+        let data = self.get(&update.exchange, &update.symbol)?;
+
+        update.fill(&filled_qty, &data.base.base_asset, &data.base.quote_asset)
+    }
+
+    pub async fn write_off_dust(
+        &mut self,
+        ctx: &NodeContext,
+        exchange: &Exchange,
+        symbol: &Symbol,
+        price: Decimal,
+        symbol_info: &SymbolInformation,
+    ) -> Result<Option<Decimal>> {
+        let data = self.get_or_insert(exchange, symbol);
+        let recorded = data.ledger.flows().len();
+
+        let Some(dust) = data.write_off_dust(ctx.valuation_policy(), price, symbol_info) else {
+            return Ok(None);
+        };
+
+        data.save_all(ctx).await?;
+        data.save_net_value(ctx).await?;
+
+        for flow in &data.ledger.flows()[recorded..] {
+            data.save_capital_flow(ctx, flow).await?;
+        }
+
+        Ok(Some(dust))
+    }
+
+    // 追加资金
+    pub async fn deposit(
+        &mut self,
+        ctx: &NodeContext,
+        exchange: &Exchange,
+        symbol: &Symbol,
+        amount: Decimal,
+    ) -> Result<()> {
+        self.get_or_insert(exchange, symbol)
+            .deposit(ctx, amount)
+            .await
+    }
+
+    // 提取资金
+    pub async fn withdraw(
+        &mut self,
+        ctx: &NodeContext,
+        exchange: &Exchange,
+        symbol: &Symbol,
+        amount: Decimal,
+    ) -> Result<()> {
+        self.get_or_insert(exchange, symbol)
+            .withdraw(ctx, amount)
+            .await
+    }
+
+    // 所有交易对的总交易次数
+    pub fn total_trades(&self) -> u64 {
+        self.data.values().map(|data| data.base.total_trades).sum()
+    }
+
+    // 所有交易对的卖出交易次数
+    pub fn sell_trades(&self) -> u64 {
+        self.data.values().map(|data| data.base.sell_trades).sum()
+    }
+
+    // 所有交易对的盈利交易次数，只有卖出交易会计为盈利
+    pub fn win_trades(&self) -> u64 {
+        self.data.values().map(|data| data.base.win_trades).sum()
+    }
+
+    // 各交易对的交易记录汇总
+    pub fn trade_record(&self) -> TradeRecord {
+        self.data
+            .values()
+            .fold(TradeRecord::default(), |mut record, data| {
+                record.merge(&data.trades);
+                record
+            })
+    }
+
+    // 各交易对中最大的回撤比例
+    pub fn max_drawdown(&self) -> Decimal {
+        self.data
+            .values()
+            .map(|data| data.max_drawdown)
+            .max()
+            .unwrap_or_default()
+    }
+
+    // 各交易对的执行质量报告
+    pub fn execution_reports(&self) -> Vec<ExecutionReport> {
+        self.data
+            .values()
+            .map(|data| {
+                data.execution
+                    .report(&data.base.exchange, &data.base.symbol)
+            })
+            .collect()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::node_core::ValuationPolicy;
+    use comfy_quant_base::{secs_to_datetime, CapitalFlowKind};
+    use comfy_quant_database::strategy_net_value;
     use comfy_quant_exchange::client::spot_client::base::{
-        Order, OrderSide, OrderStatus, OrderType,
+        Order, OrderSide, OrderStatus, OrderType, OrderUpdate,
     };
     use rust_decimal_macros::dec;
     use sqlx::PgPool;
@@ -146,6 +336,64 @@ mod tests {
         assert_eq!(data.base.quote_asset, "USDT");
     }
 
+    #[test]
+    fn test_spot_stats_order_fill() -> anyhow::Result<()> {
+        let exchange = Exchange::Binance;
+        let symbol: Symbol = "BTC/USDT".into();
+        let mut stats = SpotStats::new();
+        stats.setup(&exchange, &symbol, "BTC", "USDT");
+
+        // 挂单部分成交
+        let mut order = create_test_order(OrderSide::Buy, "50000", "0.1");
+        order.order_status = OrderStatus::PartiallyFilled;
+        stats.track_order(&order)?;
+
+        let update = |status: OrderStatus, executed_qty: Decimal, last_qty: Decimal| {
+            OrderUpdate::builder()
+                .exchange(exchange.clone())
+                .symbol(symbol.clone())
+                .order_id("test_order")
+                .order_type(OrderType::Limit)
+                .order_side(OrderSide::Buy)
+                .order_status(status)
+                .orig_qty(dec!(0.3))
+                .executed_qty(executed_qty)
+                .last_qty(last_qty)
+                .last_price(dec!(49000))
+                .commission(dec!(0))
+                .update_time(0)
+                .build()
+        };
+
+        // 已计入统计的成交不重复计算
+        let fill = stats.order_fill(&update(OrderStatus::PartiallyFilled, dec!(0.1), dec!(0.1)));
+        assert!(fill.is_none());
+
+        let fill = stats
+            .order_fill(&update(OrderStatus::PartiallyFilled, dec!(0.2), dec!(0.1)))
+            .ok_or_else(|| anyhow::anyhow!("missing fill"))?;
+        assert_eq!(fill.base_asset_amount()?, dec!(0.1));
+        assert_eq!(fill.quote_asset_amount()?, dec!(4900));
+
+        let fill = stats
+            .order_fill(&update(OrderStatus::Filled, dec!(0.3), dec!(0.1)))
+            .ok_or_else(|| anyhow::anyhow!("missing fill"))?;
+        assert_eq!(fill.base_asset_amount()?, dec!(0.1));
+
+        // 订单完结后不再跟踪
+        assert!(stats
+            .order_fill(&update(OrderStatus::Filled, dec!(0.4), dec!(0.1)))
+            .is_none());
+
+        // 完全成交的订单不跟踪
+        stats.track_order(&create_test_order(OrderSide::Buy, "50000", "0.1"))?;
+        assert!(stats
+            .order_fill(&update(OrderStatus::Filled, dec!(0.2), dec!(0.1)))
+            .is_none());
+
+        Ok(())
+    }
+
     #[sqlx::test(migrator = "comfy_quant_database::MIGRATOR")]
     async fn test_spot_stats_data_update_with_buy_order(db: PgPool) {
         let mut data = SpotStatsData::new();
@@ -209,6 +457,39 @@ mod tests {
         assert_eq!(data.base.win_trades, 1);
     }
 
+    #[sqlx::test(migrator = "comfy_quant_database::MIGRATOR")]
+    async fn test_spot_stats_data_update_with_partial_fills(db: PgPool) -> anyhow::Result<()> {
+        let mut data = SpotStatsData::new();
+        data.setup(&Exchange::Binance, &"BTC/USDT".into(), "BTC", "USDT");
+        data.quote_asset_balance = dec!(10000);
+
+        let ctx = NodeContext::new(Arc::new(db), "test_workflow", 1, "test_node");
+
+        // 未成交的订单不计入统计
+        let mut order = create_test_order(OrderSide::Buy, "50000", "0");
+        order.order_status = OrderStatus::New;
+        data.update_with_order(&ctx, &order).await?;
+        assert_eq!(data.base.total_trades, 0);
+        assert_eq!(data.avg_price, dec!(0));
+
+        // 分两次成交，每次传入新增的成交
+        let mut order = create_test_order(OrderSide::Buy, "50000", "0.06");
+        order.order_status = OrderStatus::PartiallyFilled;
+        data.update_with_order(&ctx, &order).await?;
+
+        let order = create_test_order(OrderSide::Buy, "40000", "0.04");
+        data.update_with_order(&ctx, &order).await?;
+
+        assert_eq!(data.base.total_trades, 1);
+        assert_eq!(data.base.buy_trades, 1);
+        assert_eq!(data.base_asset_balance, dec!(0.1));
+        assert_eq!(data.quote_asset_balance, dec!(5400)); // 10000 - 3000 - 1600
+        assert_eq!(data.avg_price, dec!(46000));
+        assert!(data.partial_orders.is_empty());
+
+        Ok(())
+    }
+
     #[test]
     fn test_spot_stats_get_or_insert() {
         let exchange = Exchange::Binance;
@@ -225,4 +506,176 @@ mod tests {
         let data2 = stats.get_or_insert(&exchange, &symbol);
         assert_eq!(data2.base.total_trades, 0);
     }
+
+    #[sqlx::test(migrator = "comfy_quant_database::MIGRATOR")]
+    async fn test_spot_stats_data_max_drawdown(db: PgPool) -> anyhow::Result<()> {
+        let mut data = SpotStatsData::new();
+        data.setup(&Exchange::Binance, &"BTCUSDT".into(), "BTC", "USDT");
+        data.base_asset_balance = dec!(1);
+
+        let ctx = NodeContext::new(Arc::new(db), "test_workflow", 1, "test_node");
+
+        for price in [dec!(100), dec!(120), dec!(90), dec!(130), dec!(117)] {
+            let tick = Tick::builder()
+                .timestamp(0)
+                .symbol("BTCUSDT".into())
+                .price(price)
+                .build();
+
+            data.update_with_tick(&ctx, &tick).await?;
+        }
+
+        // 120 -> 90
+        assert_eq!(data.peak_value, dec!(130));
+        assert_eq!(data.max_drawdown, dec!(0.25));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_spot_stats_data_equity_with_valuation_policy() -> anyhow::Result<()> {
+        let mut data = SpotStatsData::new();
+        data.setup(&Exchange::Binance, &"PEPEUSDT".into(), "PEPE", "USDT");
+        data.base_asset_balance = dec!(1000);
+        data.quote_asset_balance = dec!(500);
+        data.last_price = dec!(1);
+
+        assert_eq!(data.equity(&ValuationPolicy::new()), dec!(1500));
+
+        let policy = ValuationPolicy::new().with_haircut("PEPE", dec!(0.3))?;
+        assert_eq!(data.exposure(&policy), dec!(700));
+        assert_eq!(data.equity(&policy), dec!(1200));
+
+        // 无法估值的资产不计入市值
+        let policy = ValuationPolicy::new().with_unpriceable("PEPE");
+        assert_eq!(data.equity(&policy), dec!(500));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_spot_stats_data_write_off_dust() {
+        let policy = ValuationPolicy::new();
+        let mut data = SpotStatsData::new();
+        data.setup(&Exchange::Binance, &"BTCUSDT".into(), "BTC", "USDT");
+        data.base_asset_balance = dec!(0.0001);
+        data.avg_price = dec!(50000);
+        let symbol_info = SymbolInformation::builder()
+            .symbol("BTCUSDT".into())
+            .base_asset("BTC")
+            .quote_asset("USDT")
+            .base_asset_precision(8)
+            .quote_asset_precision(8)
+            .min_notional(dec!(5))
+            .build();
+
+        // 名义价值 6 >= 5，不是残余
+        assert_eq!(
+            data.write_off_dust(&policy, dec!(60000), &symbol_info),
+            None
+        );
+
+        // 名义价值 4 < 5，核销
+        assert_eq!(
+            data.write_off_dust(&policy, dec!(40000), &symbol_info),
+            Some(dec!(0.0001))
+        );
+        assert_eq!(data.base_asset_balance, dec!(0));
+        assert_eq!(data.dust_base_balance, dec!(0.0001));
+        assert_eq!(data.base.realized_pnl, dec!(-5));
+        assert_eq!(data.ledger.total(CapitalFlowKind::Pnl), dec!(-5));
+
+        // 余额为0时不再核销
+        assert_eq!(
+            data.write_off_dust(&policy, dec!(40000), &symbol_info),
+            None
+        );
+
+        // 没有最小名义价值的交易所不核销
+        data.base_asset_balance = dec!(0.0001);
+        let symbol_info = SymbolInformation::builder()
+            .symbol("BTC-USDT".into())
+            .base_asset("BTC")
+            .quote_asset("USDT")
+            .base_asset_precision(8)
+            .quote_asset_precision(8)
+            .build();
+        assert_eq!(
+            data.write_off_dust(&policy, dec!(40000), &symbol_info),
+            None
+        );
+    }
+
+    #[sqlx::test(migrator = "comfy_quant_database::MIGRATOR")]
+    async fn test_spot_stats_data_capital_ledger(db: PgPool) -> anyhow::Result<()> {
+        let mut data = SpotStatsData::new();
+        data.setup(&Exchange::Binance, &"BTCUSDT".into(), "BTC", "USDT");
+
+        let ctx = NodeContext::new(Arc::new(db), "test_workflow", 1, "test_node");
+        let tick = Tick::builder()
+            .timestamp(100)
+            .symbol("BTCUSDT".into())
+            .price(dec!(50000))
+            .build();
+
+        data.initialize_balance(&ctx, &dec!(0.1), &dec!(1000), &tick)
+            .await?;
+        data.deposit(&ctx, dec!(500)).await?;
+        data.withdraw(&ctx, dec!(200)).await?;
+
+        // 提取超过空闲余额
+        assert!(data.withdraw(&ctx, dec!(2000)).await.is_err());
+
+        assert_eq!(data.quote_asset_balance, dec!(1300));
+        assert_eq!(data.ledger.external_flows().count(), 3);
+        assert_eq!(data.ledger.net_contribution(), dec!(6300));
+        assert_eq!(data.ledger.flows()[0].timestamp, 100);
+        assert_eq!(data.ledger.flows()[1].value_before, dec!(6000));
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrator = "comfy_quant_database::MIGRATOR")]
+    async fn test_spot_stats_data_save_net_value(db: PgPool) -> anyhow::Result<()> {
+        let mut data = SpotStatsData::new();
+        data.setup(&Exchange::Binance, &"BTCUSDT".into(), "BTC", "USDT");
+
+        let db = Arc::new(db);
+        let ctx = NodeContext::new(Arc::clone(&db), "test_workflow", 1, "test_node");
+        let tick = |timestamp: i64, price: Decimal| {
+            Tick::builder()
+                .timestamp(timestamp)
+                .symbol("BTCUSDT".into())
+                .price(price)
+                .build()
+        };
+
+        data.initialize_balance(&ctx, &dec!(0.1), &dec!(1000), &tick(100, dec!(50000)))
+            .await?;
+
+        // 距上次快照不足间隔时不保存
+        data.update_with_tick(&ctx, &tick(130, dec!(60000))).await?;
+        data.update_with_tick(&ctx, &tick(160, dec!(40000))).await?;
+
+        let net_values = strategy_net_value::list(
+            &db,
+            "test_workflow",
+            1,
+            &Exchange::Binance,
+            &"BTCUSDT".into(),
+            &secs_to_datetime(0)?,
+            &secs_to_datetime(1000)?,
+        )
+        .await?;
+
+        assert_eq!(net_values.len(), 2);
+        assert_eq!(net_values[0].value, dec!(6000));
+        assert_eq!(net_values[0].net_value, dec!(1));
+        assert_eq!(net_values[1].snapshot_at, secs_to_datetime(160)?);
+        assert_eq!(net_values[1].value, dec!(5000));
+        assert_eq!(net_values[1].drawdown.round_dp(6), dec!(0.285714));
+        assert_eq!(data.last_net_value_at, 160);
+
+        Ok(())
+    }
 }
@@ -1,17 +1,23 @@
-use super::base_stats_data::BaseStatsData;
-use crate::node_core::{NodeContext, Tick};
+use super::{
+    base_stats_data::BaseStatsData, money_weighted_return, performance::sample_equity,
+    time_weighted_return, CapitalFlow, CapitalLedger, ExecutionBenchmark, PendingWrite,
+    StrategySnapshot, TradeRecord,
+};
+use crate::node_core::{NodeContext, Tick, ValuationPolicy};
 use anyhow::Result;
-use chrono::Utc;
-use comfy_quant_base::{Exchange, Symbol};
+use comfy_quant_base::{secs_to_datetime, CapitalFlowKind, Exchange, Symbol};
 use comfy_quant_database::{
-    strategy_spot_position::{self, CreateSpotPositionParams},
-    strategy_spot_stats::{self, CreateSpotStatsParams},
+    strategy_capital_flow::CreateCapitalFlowParams, strategy_net_value::SaveNetValueParams,
+    strategy_spot_position::CreateSpotPositionParams, strategy_spot_stats::CreateSpotStatsParams,
     SpotStatsQuery,
 };
-use comfy_quant_exchange::client::spot_client::base::{Order, OrderSide};
+use comfy_quant_exchange::client::spot_client::base::{Order, OrderSide, SymbolInformation};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
-use sqlx::PgPool;
+use std::collections::HashSet;
+
+// 净值快照的保存间隔(秒)，成交、出入金时额外保存
+const NET_VALUE_INTERVAL_SECS: i64 = 60;
 
 // 现货统计
 #[derive(Serialize, Deserialize, Debug, Default)]
@@ -26,6 +32,29 @@ pub struct SpotStatsData {
     pub base_asset_balance: Decimal,    // 基础资产余额
     pub quote_asset_balance: Decimal,   // 报价资产余额
     pub avg_price: Decimal,             // 平均价格
+
+    #[serde(default)]
+    pub execution: ExecutionBenchmark, // 执行质量基准
+    #[serde(default)]
+    pub peak_value: Decimal, // 持仓市值(计价资产)的历史最高值
+    #[serde(default)]
+    pub max_drawdown: Decimal, // 最大回撤比例
+    #[serde(default)]
+    pub dust_base_balance: Decimal, // 已核销的残余基础资产(低于最小名义价值，无法卖出)
+    #[serde(default)]
+    pub ledger: CapitalLedger, // 资金账本
+    #[serde(default)]
+    pub last_timestamp: i64, // 最后一个tick的时间(秒)，资金变动以此为发生时间
+    #[serde(default)]
+    pub last_price: Decimal, // 最后一个tick的价格，用于估算持仓市值
+    #[serde(default, skip_serializing_if = "HashSet::is_empty")]
+    pub partial_orders: HashSet<String>, // 已部分成交、尚未完结的订单，后续成交不再计入交易次数
+    #[serde(default)]
+    pub trades: TradeRecord, // 交易记录汇总
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub equity_curve: Vec<(i64, Decimal)>, // 按小时采样的持仓市值(时间戳秒, 市值)
+    #[serde(default)]
+    pub last_net_value_at: i64, // 最近一次保存净值快照的时间(秒)
 }
 
 #[allow(unused)]
@@ -61,45 +90,109 @@ impl SpotStatsData {
         ctx: &NodeContext,
         initial_base: &Decimal,
         initial_quote: &Decimal,
-        initial_price: &Decimal,
+        initial_tick: &Tick,
     ) -> Result<()> {
         self.initial_base_balance = initial_base.to_owned();
         self.initial_quote_balance = initial_quote.to_owned();
-        self.initial_price = initial_price.to_owned();
+        self.initial_price = initial_tick.price;
+        self.last_timestamp = initial_tick.timestamp;
+        self.last_price = initial_tick.price;
+
+        // 记录初始投入，需在更新余额之前记录
+        let allocation = initial_quote + initial_base * initial_tick.price;
+        self.record_capital_flow(ctx, CapitalFlowKind::Allocation, allocation)
+            .await?;
+
         self.base_asset_balance = initial_base.to_owned();
         self.quote_asset_balance = initial_quote.to_owned();
 
+        sample_equity(
+            &mut self.equity_curve,
+            initial_tick.timestamp,
+            self.equity(ctx.valuation_policy()),
+        );
+        self.trades.update_position(
+            self.base_asset_balance > Decimal::ZERO,
+            initial_tick.timestamp,
+        );
+
         self.save_strategy_spot_stats(
-            ctx.db(),
+            ctx,
             ctx.node_name(),
             &self.base.base_asset,
             &self.base.quote_asset,
             &self.params(ctx.workflow_id(), ctx.node_id()),
         )
         .await?;
+        self.save_net_value(ctx).await?;
 
         Ok(())
     }
 
-    pub async fn update_with_tick(&mut self, _ctx: &NodeContext, tick: &Tick) -> Result<()> {
+    pub async fn update_with_tick(&mut self, ctx: &NodeContext, tick: &Tick) -> Result<()> {
+        let policy = ctx.valuation_policy();
+
+        self.execution.update_with_tick(tick);
+        self.last_timestamp = tick.timestamp;
+        self.last_price = tick.price;
+
         // 更新未实现盈亏
-        self.base.unrealized_pnl = self.base_asset_balance * (tick.price - self.avg_price);
+        self.base.unrealized_pnl = self.exposure(policy) - self.base_asset_balance * self.avg_price;
+
+        // 更新最大回撤
+        let value = self.equity(policy);
+
+        if value > self.peak_value {
+            self.peak_value = value;
+        } else if !self.peak_value.is_zero() {
+            let drawdown = (self.peak_value - value) / self.peak_value;
+            self.max_drawdown = self.max_drawdown.max(drawdown);
+        }
+
+        sample_equity(&mut self.equity_curve, tick.timestamp, value);
+
+        if tick.timestamp - self.last_net_value_at >= NET_VALUE_INTERVAL_SECS {
+            self.save_net_value(ctx).await?;
+        }
 
         Ok(())
     }
 
+    // 按订单的成交数量更新统计，部分成交的订单每次传入新增的成交，
+    // 同一订单只在第一次成交时计入交易次数
     pub async fn update_with_order(&mut self, ctx: &NodeContext, order: &Order) -> Result<()> {
-        let now = Utc::now();
         let base_asset_amount = order.base_asset_amount()?;
+
+        // 未成交的订单(如挂单、延迟成交的市价单)不影响统计
+        if base_asset_amount <= Decimal::ZERO {
+            return Ok(());
+        }
+
+        let now = ctx.now();
         let quote_asset_amount = order.quote_asset_amount()?;
         let base_commission = order.base_commission(&self.base.maker_commission_rate)?;
         let quote_commission = order.quote_commission(&self.base.maker_commission_rate)?;
         let order_avg_price = order.avg_price.parse::<Decimal>()?;
 
-        self.base.total_trades += 1;
+        self.execution.update_with_order(order)?;
+
+        let new_trade = if order.order_status.is_open() {
+            self.partial_orders.insert(order.order_id.clone())
+        } else {
+            !self.partial_orders.remove(&order.order_id)
+        };
+
+        if new_trade {
+            self.base.total_trades += 1;
+        }
+
         self.base.total_base_volume += base_asset_amount;
         self.base.total_quote_volume += quote_asset_amount;
 
+        // 更新第一笔交易时间和最后一笔交易时间
+        self.base.first_trade_at.get_or_insert(now);
+        self.base.last_trade_at = Some(now);
+
         match order.order_side {
             OrderSide::Buy => {
                 // 扣除手续费后实际获得
@@ -109,7 +202,10 @@ impl SpotStatsData {
                     + base_amount * order_avg_price)
                     / (self.base_asset_balance + base_amount);
 
-                self.base.buy_trades += 1;
+                if new_trade {
+                    self.base.buy_trades += 1;
+                }
+
                 self.base_asset_balance += base_amount;
                 self.avg_price = avg_price;
                 self.quote_asset_balance -= quote_asset_amount;
@@ -121,25 +217,173 @@ impl SpotStatsData {
                 // 成本
                 let cost = base_asset_amount * self.avg_price;
 
-                self.base.sell_trades += 1;
                 self.base_asset_balance -= base_asset_amount;
                 self.quote_asset_balance += quote_amount;
                 self.base.total_quote_commission += quote_commission;
 
-                // 卖出所得大于成本，则确定为一次盈利交易
-                if quote_amount > cost {
-                    self.base.win_trades += 1;
+                // 卖出所得大于成本，则确定为一次盈利交易，部分成交的订单按第一笔成交判断
+                if new_trade {
+                    self.base.sell_trades += 1;
+
+                    if quote_amount > cost {
+                        self.base.win_trades += 1;
+                    }
                 }
 
+                self.trades.record_pnl(quote_amount - cost, new_trade);
+
                 // 已实现总盈亏
                 self.base.realized_pnl += quote_amount - cost;
+                self.record_capital_flow(ctx, CapitalFlowKind::Pnl, quote_amount - cost)
+                    .await?;
             }
         }
 
+        self.trades
+            .update_position(self.base_asset_balance > Decimal::ZERO, self.last_timestamp);
+
+        // 手续费折算为计价资产
+        let fee = base_commission * order_avg_price + quote_commission;
+        self.record_capital_flow(ctx, CapitalFlowKind::Fee, -fee)
+            .await?;
+
+        self.save_all(ctx).await?;
+        self.save_net_value(ctx).await?;
+
+        Ok(())
+    }
+
+    // 核销残余基础资产：名义价值低于交易对最小名义价值的余额无法卖出，
+    // 从持仓中移出并按持仓成本计入已实现亏损，使统计余额与可交易余额一致
+    // 返回核销的数量
+    pub fn write_off_dust(
+        &mut self,
+        policy: &ValuationPolicy,
+        price: Decimal,
+        symbol_info: &SymbolInformation,
+    ) -> Option<Decimal> {
+        let dust = self.base_asset_balance;
+
+        if !symbol_info.is_dust(dust, price) {
+            return None;
+        }
+
+        self.ledger.record(
+            CapitalFlowKind::Pnl,
+            -dust * self.avg_price,
+            self.last_timestamp,
+            self.equity(policy),
+        );
+        self.base.realized_pnl -= dust * self.avg_price;
+        self.base.unrealized_pnl = Decimal::ZERO;
+        self.base_asset_balance = Decimal::ZERO;
+        self.dust_base_balance += dust;
+        self.trades.update_position(false, self.last_timestamp);
+
+        Some(dust)
+    }
+
+    // 追加资金
+    pub async fn deposit(&mut self, ctx: &NodeContext, amount: Decimal) -> Result<()> {
+        anyhow::ensure!(amount > Decimal::ZERO, "Deposit amount must be positive");
+
+        self.record_capital_flow(ctx, CapitalFlowKind::Deposit, amount)
+            .await?;
+        self.quote_asset_balance += amount;
+        self.save_all(ctx).await?;
+        self.save_net_value(ctx).await?;
+
+        Ok(())
+    }
+
+    // 提取资金，只能提取空闲的计价资产
+    pub async fn withdraw(&mut self, ctx: &NodeContext, amount: Decimal) -> Result<()> {
+        anyhow::ensure!(amount > Decimal::ZERO, "Withdrawal amount must be positive");
+        anyhow::ensure!(
+            amount <= self.quote_asset_balance,
+            "Insufficient quote balance to withdraw"
+        );
+
+        self.record_capital_flow(ctx, CapitalFlowKind::Withdrawal, -amount)
+            .await?;
+        self.quote_asset_balance -= amount;
+        self.save_all(ctx).await?;
+        self.save_net_value(ctx).await?;
+
+        Ok(())
+    }
+
+    // 按最新价格和估值策略估算的持仓市值
+    pub fn equity(&self, policy: &ValuationPolicy) -> Decimal {
+        self.quote_asset_balance + self.exposure(policy)
+    }
+
+    // 基础资产敞口市值，按估值策略折扣
+    pub fn exposure(&self, policy: &ValuationPolicy) -> Decimal {
+        policy.value(
+            &self.base.base_asset,
+            self.base_asset_balance * self.last_price,
+        )
+    }
+
+    // 统计快照，推送给统计聚合服务
+    pub fn snapshot(&self, ctx: &NodeContext) -> StrategySnapshot {
+        let policy = ctx.valuation_policy();
+
+        StrategySnapshot {
+            workflow_id: ctx.workflow_id().to_string(),
+            node_id: ctx.node_id(),
+            exchange: self.base.exchange.clone(),
+            symbol: self.base.symbol.clone(),
+            quote_asset: self.base.quote_asset.clone(),
+            equity: self.equity(policy),
+            realized_pnl: self.base.realized_pnl,
+            unrealized_pnl: self.base.unrealized_pnl,
+            exposure: self.exposure(policy),
+            timestamp: self.last_timestamp,
+        }
+    }
+
+    // 时间加权收益率
+    pub fn time_weighted_return(&self, policy: &ValuationPolicy) -> Decimal {
+        time_weighted_return(self.ledger.external_flows(), self.equity(policy))
+    }
+
+    // 资金加权收益率(年化)，运行不足一天时为 None
+    pub fn money_weighted_return(&self, policy: &ValuationPolicy) -> Option<Decimal> {
+        money_weighted_return(
+            self.ledger.external_flows(),
+            self.last_timestamp,
+            self.equity(policy),
+        )
+    }
+
+    // 记录资金变动并保存，外部资金变动需在变动余额之前调用
+    async fn record_capital_flow(
+        &mut self,
+        ctx: &NodeContext,
+        kind: CapitalFlowKind,
+        amount: Decimal,
+    ) -> Result<()> {
+        let value_before = self.equity(ctx.valuation_policy());
+
+        let Some(flow) = self
+            .ledger
+            .record(kind, amount, self.last_timestamp, value_before)
+        else {
+            return Ok(());
+        };
+
+        let flow = flow.clone();
+        self.save_capital_flow(ctx, &flow).await
+    }
+
+    // 保存统计和持仓
+    pub(super) async fn save_all(&self, ctx: &NodeContext) -> Result<()> {
         let params = self.params(ctx.workflow_id(), ctx.node_id());
 
         self.save_strategy_spot_stats(
-            ctx.db(),
+            ctx,
             ctx.node_name(),
             &self.base.base_asset,
             &self.base.quote_asset,
@@ -147,7 +391,7 @@ impl SpotStatsData {
         )
         .await?;
         self.save_strategy_spot_position(
-            ctx.db(),
+            ctx,
             ctx.node_name(),
             &self.base.base_asset,
             &self.base.quote_asset,
@@ -158,10 +402,66 @@ impl SpotStatsData {
         Ok(())
     }
 
+    // 保存资金流水
+    pub(super) async fn save_capital_flow(
+        &self,
+        ctx: &NodeContext,
+        flow: &CapitalFlow,
+    ) -> Result<()> {
+        let data = CreateCapitalFlowParams::builder()
+            .workflow_id(ctx.workflow_id())
+            .node_id(ctx.node_id())
+            .node_name(ctx.node_name())
+            .exchange(self.base.exchange.clone())
+            .symbol(self.base.symbol.clone())
+            .asset(self.base.quote_asset.clone())
+            .kind(flow.kind)
+            .amount(flow.amount)
+            .occurred_at(secs_to_datetime(flow.timestamp)?)
+            .build();
+
+        ctx.write_buffer()
+            .write(ctx.db(), PendingWrite::CapitalFlow(data))
+            .await?;
+
+        Ok(())
+    }
+
+    // 保存净值快照，净值按时间加权收益率计算，不受出入金影响
+    pub(super) async fn save_net_value(&mut self, ctx: &NodeContext) -> Result<()> {
+        let policy = ctx.valuation_policy();
+        let value = self.equity(policy);
+        let drawdown = if self.peak_value > value {
+            (self.peak_value - value) / self.peak_value
+        } else {
+            Decimal::ZERO
+        };
+
+        let data = SaveNetValueParams::builder()
+            .workflow_id(ctx.workflow_id())
+            .node_id(ctx.node_id())
+            .node_name(ctx.node_name())
+            .exchange(self.base.exchange.clone())
+            .symbol(self.base.symbol.clone())
+            .quote_asset(self.base.quote_asset.clone())
+            .value(value)
+            .net_value(Decimal::ONE + self.time_weighted_return(policy))
+            .drawdown(drawdown)
+            .snapshot_at(secs_to_datetime(self.last_timestamp)?)
+            .build();
+
+        ctx.write_buffer()
+            .write(ctx.db(), PendingWrite::NetValue(data))
+            .await?;
+        self.last_net_value_at = self.last_timestamp;
+
+        Ok(())
+    }
+
     // 保存策略持仓
     pub async fn save_strategy_spot_position(
         &self,
-        db: &PgPool,
+        ctx: &NodeContext,
         node_name: &str,
         base_asset: &str,
         quote_asset: &str,
@@ -180,7 +480,9 @@ impl SpotStatsData {
             .realized_pnl(self.base.realized_pnl)
             .build();
 
-        strategy_spot_position::create(db, data).await?;
+        ctx.write_buffer()
+            .write(ctx.db(), PendingWrite::SpotPosition(data))
+            .await?;
 
         Ok(())
     }
@@ -188,7 +490,7 @@ impl SpotStatsData {
     // 保存策略统计数据
     pub async fn save_strategy_spot_stats(
         &self,
-        db: &PgPool,
+        ctx: &NodeContext,
         node_name: &str,
         base_asset: &str,
         quote_asset: &str,
@@ -221,7 +523,9 @@ impl SpotStatsData {
             .win_trades(self.base.win_trades as i64)
             .build();
 
-        strategy_spot_stats::create_or_update(db, data).await?;
+        ctx.write_buffer()
+            .write(ctx.db(), PendingWrite::SpotStats(data))
+            .await?;
 
         Ok(())
     }
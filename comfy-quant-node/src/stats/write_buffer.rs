@@ -0,0 +1,199 @@
+use super::ResourceMeter;
+use anyhow::Result;
+use comfy_quant_database::{
+    order::{self, CreateOrderParams, UpdateOrderParams},
+    strategy_capital_flow::{self, CreateCapitalFlowParams},
+    strategy_net_value::{self, SaveNetValueParams},
+    strategy_spot_position::{self, CreateSpotPositionParams},
+    strategy_spot_stats::{self, CreateSpotStatsParams},
+};
+use sqlx::PgPool;
+use std::{
+    collections::VecDeque,
+    fmt,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+use tokio::sync::Mutex;
+
+const DEFAULT_CAPACITY: usize = 10000;
+
+// 待写入数据库的数据
+#[derive(Clone)]
+pub(crate) enum PendingWrite {
+    SpotStats(CreateSpotStatsParams),       // 策略统计
+    SpotPosition(CreateSpotPositionParams), // 策略持仓
+    CapitalFlow(CreateCapitalFlowParams),   // 资金流水
+    NetValue(SaveNetValueParams),           // 净值快照
+    Order(CreateOrderParams),               // 订单
+    OrderUpdate(UpdateOrderParams),         // 订单更新
+}
+
+impl PendingWrite {
+    async fn execute(&self, db: &PgPool) -> Result<()> {
+        match self {
+            PendingWrite::SpotStats(data) => {
+                strategy_spot_stats::create_or_update(db, data.clone()).await?;
+            }
+            PendingWrite::SpotPosition(data) => {
+                strategy_spot_position::create(db, data.clone()).await?;
+            }
+            PendingWrite::CapitalFlow(data) => {
+                strategy_capital_flow::create(db, data.clone()).await?;
+            }
+            PendingWrite::NetValue(data) => {
+                strategy_net_value::save(db, data.clone()).await?;
+            }
+            PendingWrite::Order(data) => {
+                order::create(db, data.clone()).await?;
+            }
+            PendingWrite::OrderUpdate(data) => {
+                order::update(db, data.clone()).await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for PendingWrite {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PendingWrite::SpotStats(_) => write!(f, "SpotStats"),
+            PendingWrite::SpotPosition(_) => write!(f, "SpotPosition"),
+            PendingWrite::CapitalFlow(_) => write!(f, "CapitalFlow"),
+            PendingWrite::NetValue(_) => write!(f, "NetValue"),
+            PendingWrite::Order(data) => write!(f, "Order({})", data.order_id),
+            PendingWrite::OrderUpdate(data) => write!(f, "OrderUpdate({})", data.order_id),
+        }
+    }
+}
+
+// 数据库写缓冲，数据库短暂不可用时暂存写入，恢复后按顺序补写，避免影响订单处理
+#[derive(Debug)]
+pub(crate) struct WriteBuffer {
+    queue: Mutex<VecDeque<PendingWrite>>, // 积压的写入
+    capacity: usize,                      // 最大积压数量
+    dropped: AtomicU64,                   // 溢出丢弃的数量
+    resource_meter: Arc<ResourceMeter>,   // 资源计数器，统计写入的行数
+}
+
+impl WriteBuffer {
+    pub(crate) fn new(capacity: usize) -> Self {
+        WriteBuffer {
+            queue: Mutex::new(VecDeque::new()),
+            capacity,
+            dropped: AtomicU64::new(0),
+            resource_meter: Arc::new(ResourceMeter::default()),
+        }
+    }
+
+    // 共享工作流的资源计数器
+    pub(crate) fn with_resource_meter(mut self, resource_meter: Arc<ResourceMeter>) -> Self {
+        self.resource_meter = resource_meter;
+        self
+    }
+
+    // 写入数据库，失败时暂存到队列
+    pub(crate) async fn write(&self, db: &PgPool, write: PendingWrite) -> Result<()> {
+        let mut queue = self.queue.lock().await;
+
+        // 先补写积压的数据，保证写入顺序
+        if let Err(e) = self.flush_queue(db, &mut queue).await {
+            tracing::warn!("Database unavailable, buffering write: {}", e);
+            self.push(&mut queue, write);
+            return Ok(());
+        }
+
+        match write.execute(db).await {
+            Ok(()) => self.resource_meter.add_db_rows_written(1),
+            Err(e) => {
+                tracing::warn!("Database unavailable, buffering write: {}", e);
+                self.push(&mut queue, write);
+            }
+        }
+
+        Ok(())
+    }
+
+    // 补写积压的数据
+    pub(crate) async fn flush(&self, db: &PgPool) -> Result<()> {
+        let mut queue = self.queue.lock().await;
+        self.flush_queue(db, &mut queue).await
+    }
+
+    async fn flush_queue(&self, db: &PgPool, queue: &mut VecDeque<PendingWrite>) -> Result<()> {
+        while let Some(write) = queue.front() {
+            write.execute(db).await?;
+            queue.pop_front();
+            self.resource_meter.add_db_rows_written(1);
+        }
+
+        Ok(())
+    }
+
+    fn push(&self, queue: &mut VecDeque<PendingWrite>, write: PendingWrite) {
+        if queue.len() >= self.capacity {
+            let dropped = queue.pop_front();
+            let total_dropped = self.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+            tracing::error!(
+                "Write buffer overflow (capacity {}), dropped oldest write: {:?}, total dropped: {}",
+                self.capacity,
+                dropped,
+                total_dropped
+            );
+        }
+
+        queue.push_back(write);
+    }
+}
+
+impl Default for WriteBuffer {
+    fn default() -> Self {
+        WriteBuffer::new(DEFAULT_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use comfy_quant_base::Exchange;
+    use rust_decimal_macros::dec;
+
+    fn position() -> PendingWrite {
+        let data = CreateSpotPositionParams::builder()
+            .workflow_id("jEnbRDqQu4UN6y7cgQgp6")
+            .node_id(1_i16)
+            .node_name("SpotGrid")
+            .exchange(Exchange::Binance)
+            .symbol("BTCUSDT")
+            .base_asset("BTC")
+            .quote_asset("USDT")
+            .base_asset_balance(dec!(1))
+            .quote_asset_balance(dec!(1000))
+            .realized_pnl(dec!(0))
+            .build();
+
+        PendingWrite::SpotPosition(data)
+    }
+
+    #[sqlx::test]
+    async fn test_write_buffer_buffer_and_flush(db: PgPool) -> Result<()> {
+        let buffer = WriteBuffer::new(2);
+
+        db.close().await;
+
+        buffer.write(&db, position()).await?;
+        buffer.write(&db, position()).await?;
+        buffer.write(&db, position()).await?;
+
+        assert_eq!(buffer.queue.lock().await.len(), 2);
+        assert_eq!(buffer.dropped.load(Ordering::Relaxed), 1);
+        assert_eq!(buffer.resource_meter.usage().db_rows_written, 0);
+        assert!(buffer.flush(&db).await.is_err());
+
+        Ok(())
+    }
+}
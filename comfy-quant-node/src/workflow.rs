@@ -1,21 +1,61 @@
 use crate::{
-    node_core::{ExchangeRate, ExchangeRateManager, NodeCoreExt, NodeExecutable, TradeStats},
-    node_io::{SpotPairInfo, TickStream},
-    nodes::node_kind::NodeKind,
+    migration,
+    node_core::{
+        Diagnostic, ErrorAction, EventBus, ExchangeRate, ExchangeRateManager, GraphError,
+        NodeCoreExt, NodeError, NodeExecutable, NodeGraph, NodeHealth, RestartPolicy,
+        SimulatedClock, Supervisor, TickRecorder, TradeStats, TradeStatsExt, ValidationError,
+        ValuationPolicy, Watchdog, WorkflowEvent, RESTART_RESET_AFTER,
+    },
+    node_io::{AnnouncementStream, CapitalAllocation, KlineStream, SpotPairInfo, TickStream},
+    nodes::{node_kind::NodeKind, node_registry},
+    stats::{
+        merge_equity_curves, AssertMetric, AssertionResult, EventLog, ExecutionReport,
+        ResourceMeter, ResourceUsage, StatsAggregator, TradeRecord, WriteBuffer,
+    },
 };
 use anyhow::{anyhow, Result};
 use async_lock::RwLock;
 use chrono::{DateTime, Utc};
 use comfy_quant_base::{arc_rwlock, generate_workflow_id, vec_arc_rwlock};
-use comfy_quant_exchange::{client::spot_client_kind::SpotClientKind, store::PriceStore};
+use comfy_quant_database::workflow_run::{self, SaveCheckpointParams, WorkflowRunStatus};
+use comfy_quant_exchange::{
+    client::{
+        spot_client::backtest_spot_client::BacktestSpotClient, spot_client_kind::SpotClientKind,
+    },
+    store::PriceStore,
+};
+use dashmap::DashMap;
+use futures::FutureExt;
 use itertools::Itertools;
 use rust_decimal::Decimal;
-use serde::{ser::SerializeStruct, Deserialize, Serialize};
+use serde::{de, ser::SerializeStruct, Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
 use sqlx::PgPool;
-use std::{collections::HashMap, future::Future, sync::Arc, time::Instant};
+use std::{
+    any::Any,
+    collections::{HashMap, HashSet},
+    future::Future,
+    panic::AssertUnwindSafe,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+use tokio::{
+    sync::broadcast,
+    task::{AbortHandle, JoinHandle},
+};
 use tokio_util::sync::CancellationToken;
 
+// 优雅停止时等待节点退出的最长时间，超时后强制中止
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
+
+// 反序列化时先把旧版本的工作流定义升级到当前版本，见 migration
 #[derive(Deserialize, Debug)]
+#[serde(remote = "Self")]
 pub struct Workflow {
     last_node_id: u32,
     last_link_id: u32,
@@ -32,12 +72,22 @@ pub struct Workflow {
     #[serde(default, with = "arc_rwlock")]
     running_time: Arc<RwLock<u128>>, // 运行持续时间(微妙)
 
+    #[serde(skip)]
+    workflow_id: Option<String>, // 从检查点恢复时沿用原工作流ID
+    #[serde(skip)]
+    owner: Option<String>, // 运行工作流的实例ID，启用租约时检查点只由持有租约的实例保存
     #[serde(skip)]
     deserialized_nodes: HashMap<u32, Arc<RwLock<NodeKind>>>, // 反序列化节点
     #[serde(skip)]
     context: Option<Arc<WorkflowContext>>, // 上下文
     #[serde(skip)]
     token: CancellationToken, // 取消令牌
+    #[serde(skip)]
+    node_handles: Vec<JoinHandle<()>>, // 节点执行任务
+    #[serde(skip)]
+    runtime_handles: Vec<JoinHandle<()>>, // 运行时间统计任务，停止时记录最终的执行记录
+    #[serde(skip)]
+    supervisor: Arc<Supervisor>, // 节点运行状态
 }
 
 impl Workflow {
@@ -48,13 +98,30 @@ impl Workflow {
         exchange_rate_manager: Arc<RwLock<ExchangeRateManager>>, // 汇率管理器
         quote_asset: impl Into<QuoteAsset>,                      // 报价资产
     ) -> Result<()> {
+        // 连接图有环或悬空连接时无法确定执行顺序
+        self.check_graph()?;
+
         let quote_asset = Arc::new(RwLock::new(quote_asset.into()));
-        let context = Arc::new(WorkflowContext::new(
+        let valuation_policy = ValuationPolicy::from_config(&self.config)?;
+        let mut context = WorkflowContext::new(
             db,
             Arc::clone(&quote_asset),
             exchange_rate_manager,
             Arc::clone(&self.running_time),
-        ));
+        )
+        .with_valuation_policy(valuation_policy)
+        .with_checkpoint_interval(checkpoint_interval(&self.config))
+        .with_tick_record_dir(self.config.get("tick_record_dir").map(PathBuf::from));
+
+        if let Some(workflow_id) = &self.workflow_id {
+            context = context.with_workflow_id(workflow_id);
+        }
+
+        if let Some(owner) = &self.owner {
+            context = context.with_owner(owner);
+        }
+
+        let context = Arc::new(context);
 
         self.quote_asset = Arc::clone(&quote_asset);
         self.context = Some(Arc::clone(&context));
@@ -63,10 +130,23 @@ impl Workflow {
             node.context = Some(Arc::clone(&context));
         }
 
-        // 反序列化节点
-        for node in &self.nodes {
+        // 按执行顺序反序列化节点，上游节点已初始化的连接在 setup 之前建立，
+        // 节点可在 setup 中读取输入，如风控节点包装上游的账户客户端
+        let nodes = self.sorted_nodes().into_iter().cloned().collect::<Vec<_>>();
+        let mut connected_links = HashSet::new();
+
+        for node in nodes {
             let node_id = node.id;
-            let mut node_kind = NodeKind::try_from(node.clone())?;
+            let mut node_kind = NodeKind::try_from(node)?;
+
+            for link in self.links.iter().filter(|link| link.target_id == node_id) {
+                let Some(origin_node) = self.deserialized_nodes.get(&link.origin_id) else {
+                    continue;
+                };
+
+                self.make_connection(&*origin_node.read().await, &mut node_kind, link)?;
+                connected_links.insert(link.link_id);
+            }
 
             node_kind.setup().await?;
 
@@ -77,8 +157,12 @@ impl Workflow {
 
         tracing::info!("Workflow deserialized nodes");
 
-        // 建立连接
-        for link in &self.links {
+        // 建立其余连接
+        for link in self
+            .links
+            .iter()
+            .filter(|link| !connected_links.contains(&link.link_id))
+        {
             let origin_node = self
                 .deserialized_nodes
                 .get(&link.origin_id)
@@ -101,18 +185,415 @@ impl Workflow {
         Ok(())
     }
 
+    // 沿用指定的工作流ID，需在 setup 之前调用，如按部署的逻辑工作流ID运行
+    pub fn with_workflow_id(mut self, workflow_id: impl Into<String>) -> Self {
+        self.workflow_id = Some(workflow_id.into());
+        self
+    }
+
+    // 以指定实例的身份运行，需在 setup 之前调用，用于主备实例之间的故障转移
+    pub fn with_owner(mut self, owner: impl Into<String>) -> Self {
+        self.owner = Some(owner.into());
+        self
+    }
+
+    // 工作流定义中的节点，按执行顺序排列
+    pub fn nodes(&self) -> Vec<&Node> {
+        self.sorted_nodes()
+    }
+
+    // 校验连接图、节点类型和参数、连接两端的端口类型以及必需的输入，
+    // 不需要数据库和上下文，用于提交前检查；返回全部问题供前端在对应节点上提示
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let mut diagnostics = match self.check_graph() {
+            Ok(()) => vec![],
+            Err(e) => e
+                .issues
+                .iter()
+                .flat_map(Diagnostic::from_graph_issue)
+                .collect(),
+        };
+
+        let registry = node_registry()
+            .into_iter()
+            .map(|metadata| (metadata.prop_type, metadata))
+            .collect::<HashMap<_, _>>();
+        let mut node_metadata = HashMap::new(); // 节点ID -> 元数据，类型未知的节点不检查连接
+        let node_ids = self
+            .nodes
+            .iter()
+            .map(|node| node.id)
+            .collect::<HashSet<_>>();
+
+        for node in &self.nodes {
+            let prop_type = node.properties.prop_type.as_str();
+            let Some(metadata) = registry.get(prop_type) else {
+                diagnostics.push(Diagnostic::node(
+                    node.id,
+                    "properties.type",
+                    format!("Unknown node type '{}'", prop_type),
+                ));
+                continue;
+            };
+
+            node_metadata.insert(node.id, metadata);
+
+            // 参数错误时无法确定必需的输入(如多账户的账户个数)，只报告参数错误
+            let node_kind = match NodeKind::try_from(node.clone()) {
+                Ok(node_kind) => node_kind,
+                Err(e) => {
+                    diagnostics.push(Diagnostic::node(
+                        node.id,
+                        "properties.params",
+                        e.to_string(),
+                    ));
+                    continue;
+                }
+            };
+
+            for slot in node_kind.required_inputs() {
+                let connected = self.links.iter().any(|link| {
+                    link.target_id == node.id
+                        && link.target_slot == slot
+                        && node_ids.contains(&link.origin_id)
+                });
+
+                if !connected {
+                    diagnostics.push(Diagnostic::node(
+                        node.id,
+                        format!("inputs[{}]", slot),
+                        format!(
+                            "Required input '{}' is not connected",
+                            metadata.inputs.get(slot).map_or("", |port| port.name)
+                        ),
+                    ));
+                }
+            }
+        }
+
+        let mut connected_inputs = HashSet::new();
+
+        for link in &self.links {
+            // 悬空连接已由连接图检查报告
+            let (Some(origin), Some(target)) = (
+                node_metadata.get(&link.origin_id),
+                node_metadata.get(&link.target_id),
+            ) else {
+                continue;
+            };
+
+            let output = origin.outputs.get(link.origin_slot);
+            let input = target.inputs.get(link.target_slot);
+
+            if output.is_none() {
+                diagnostics.push(Diagnostic::node(
+                    link.origin_id,
+                    format!("outputs[{}]", link.origin_slot),
+                    format!("Link {} uses nonexistent output slot", link.link_id),
+                ));
+            }
+
+            if input.is_none() {
+                diagnostics.push(Diagnostic::node(
+                    link.target_id,
+                    format!("inputs[{}]", link.target_slot),
+                    format!("Link {} uses nonexistent input slot", link.link_id),
+                ));
+            }
+
+            if let (Some(output), Some(input)) = (output, input) {
+                if output.port_type != input.port_type {
+                    diagnostics.push(Diagnostic::node(
+                        link.target_id,
+                        format!("inputs[{}]", link.target_slot),
+                        format!(
+                            "Link {} connects {} output to {} input",
+                            link.link_id, output.port_type, input.port_type
+                        ),
+                    ));
+                }
+            }
+
+            if input.is_some() && !connected_inputs.insert((link.target_id, link.target_slot)) {
+                diagnostics.push(Diagnostic::node(
+                    link.target_id,
+                    format!("inputs[{}]", link.target_slot),
+                    format!(
+                        "Link {} connects an input that is already connected",
+                        link.link_id
+                    ),
+                ));
+            }
+        }
+
+        if diagnostics.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationError { diagnostics })
+        }
+    }
+
+    // 检查连接图中的重复节点、悬空连接和循环依赖
+    pub fn check_graph(&self) -> Result<(), GraphError> {
+        self.graph().check()
+    }
+
+    fn graph(&self) -> NodeGraph {
+        NodeGraph::new(
+            self.nodes.iter().map(|node| (node.id, node.order)),
+            self.links
+                .iter()
+                .map(|link| (link.link_id, link.origin_id, link.target_id)),
+        )
+    }
+
+    // 计价资产，未 setup 时为工作流定义中的设置
+    pub async fn quote_asset(&self) -> QuoteAsset {
+        self.quote_asset.read().await.clone()
+    }
+
     pub async fn update_quote_asset(&mut self, quote_asset: impl Into<QuoteAsset>) -> Result<()> {
         *self.context()?.quote_asset.write().await = quote_asset.into();
         Ok(())
     }
 
     // 按照 order 排序
+    // 按连接拓扑排序，上游节点在前；前端给出的 order 只决定没有依赖关系的节点的先后
     fn sorted_nodes(&self) -> Vec<&Node> {
+        let rank = self
+            .graph()
+            .sorted()
+            .into_iter()
+            .enumerate()
+            .map(|(rank, node_id)| (node_id, rank))
+            .collect::<HashMap<_, _>>();
+
         let mut nodes_vec = self.nodes.iter().collect::<Vec<_>>();
-        nodes_vec.sort_by_key(|node| node.order);
+        nodes_vec.sort_by_key(|node| rank.get(&node.id));
         nodes_vec
     }
 
+    // 等待所有节点执行结束(如回测数据回放完毕)
+    pub async fn wait(&mut self) -> Result<()> {
+        for handle in self.node_handles.drain(..) {
+            handle.await?;
+        }
+
+        // 节点自然结束时标记为已完成，之后不再保存检查点；被停止时由检查点任务标记
+        let Some(context) = &self.context else {
+            return Ok(());
+        };
+
+        if context.checkpoint_interval.is_some() && !self.token.is_cancelled() {
+            context.finished.store(true, Ordering::Relaxed);
+            self.checkpointer()?.save(self.run_status()).await?;
+        }
+
+        Ok(())
+    }
+
+    // 各节点的运行状态和重启次数
+    pub fn node_health(&self) -> Vec<NodeHealth> {
+        self.supervisor.health()
+    }
+
+    // 工作流运行状态: 有节点失败且不再重启时为失败，节点全部结束时为已完成
+    pub fn run_status(&self) -> WorkflowRunStatus {
+        run_status(&self.supervisor, &self.token)
+    }
+
+    // 停止所有节点
+    pub fn stop(&self) {
+        self.token.cancel();
+    }
+
+    // 设置回测数据节点的时间范围
+    pub fn set_backtest_time_range(&mut self, start_datetime: &str, end_datetime: &str) {
+        for node in &mut self.nodes {
+            let params = node.properties.params.as_mut_slice();
+
+            let (start, end) = match (node.properties.prop_type.as_str(), params) {
+                (
+                    "data.BacktestSpotTicker" | "data.BacktestSpotKlines" | "data.BybitSpotTicker",
+                    [_, _, start, end, ..],
+                ) => (start, end),
+                // 多交易对行情的第一个参数为交易对列表
+                ("data.BacktestMultiSpotTicker", [_, start, end, ..]) => (start, end),
+                _ => continue,
+            };
+
+            *start = start_datetime.into();
+            *end = end_datetime.into();
+        }
+    }
+
+    // 将实盘行情节点替换为相同交易对的回测行情节点，用于以近期数据回测实盘工作流；
+    // 没有对应回测行情节点的实盘行情不会结束，返回错误
+    pub fn use_backtest_data(&mut self, start_datetime: &str, end_datetime: &str) -> Result<()> {
+        for node in &mut self.nodes {
+            match node.properties.prop_type.as_str() {
+                // 两者的输出相同，参数在交易对之后追加时间范围
+                "data.BinanceSpotTicker" => {
+                    node.properties.prop_type = "data.BacktestSpotTicker".to_string();
+                    node.properties
+                        .params
+                        .extend([start_datetime.into(), end_datetime.into()]);
+                }
+                "data.OkxSpotTicker" => {
+                    anyhow::bail!("Node {} data.OkxSpotTicker can not be backtested", node.id)
+                }
+                // 按已同步的Bybit历史K线回放，时间范围在下面统一设置
+                "data.BybitSpotTicker" => {}
+                _ => {}
+            }
+        }
+
+        self.set_backtest_time_range(start_datetime, end_datetime);
+
+        Ok(())
+    }
+
+    // 设置节点的参数，用于参数优化时覆盖工作流中的参数
+    pub fn set_node_param(
+        &mut self,
+        node_id: u32,
+        index: usize,
+        value: impl Into<serde_json::Value>,
+    ) -> Result<()> {
+        let node = self
+            .nodes
+            .iter_mut()
+            .find(|node| node.id == node_id)
+            .ok_or_else(|| anyhow!("Node not found: {}", node_id))?;
+
+        let param = node
+            .properties
+            .params
+            .get_mut(index)
+            .ok_or_else(|| anyhow!("Node {} param index out of range: {}", node_id, index))?;
+
+        *param = value.into();
+
+        Ok(())
+    }
+
+    // 复制工作流，节点和连接按执行顺序重新编号，清空运行时数据和执行记录。
+    // 新工作流在 setup 时生成新的工作流ID，不会与原工作流共用统计数据
+    pub fn clone_with_new_ids(&self) -> Workflow {
+        let nodes = self.sorted_nodes();
+
+        let node_ids = nodes
+            .iter()
+            .zip(1..)
+            .map(|(node, id)| (node.id, id))
+            .collect::<HashMap<_, _>>();
+
+        let links = self
+            .links
+            .iter()
+            .filter(|link| {
+                node_ids.contains_key(&link.origin_id) && node_ids.contains_key(&link.target_id)
+            })
+            .collect::<Vec<_>>();
+
+        let link_ids = links
+            .iter()
+            .zip(1..)
+            .map(|(link, id)| (link.link_id, id))
+            .collect::<HashMap<_, _>>();
+
+        let nodes = nodes
+            .into_iter()
+            .map(|node| node.remap_ids(&node_ids, &link_ids))
+            .collect::<Vec<_>>();
+
+        let links = links
+            .into_iter()
+            .map(|link| Link {
+                link_id: link_ids[&link.link_id],
+                origin_id: node_ids[&link.origin_id],
+                target_id: node_ids[&link.target_id],
+                ..link.clone()
+            })
+            .collect::<Vec<_>>();
+
+        let quote_asset = self.quote_asset.read_blocking().clone();
+
+        Workflow {
+            last_node_id: nodes.len() as u32,
+            last_link_id: links.len() as u32,
+            nodes,
+            links,
+            groups: self.groups.clone(),
+            config: self.config.clone(),
+            extra: self.extra.clone(),
+            version: self.version,
+            quote_asset: Arc::new(RwLock::new(quote_asset)),
+            execution_history: Vec::new(),
+            running_time: Arc::new(RwLock::new(0)),
+            workflow_id: None,
+            owner: None,
+            deserialized_nodes: HashMap::new(),
+            context: None,
+            token: CancellationToken::new(),
+            node_handles: Vec::new(),
+            runtime_handles: Vec::new(),
+            supervisor: Arc::default(),
+        }
+    }
+
+    // 工作流定义的内容哈希(SHA-256)，忽略节点位置、编号、运行时数据和执行记录，
+    // 只是在编辑器中移动或重新编号的策略图得到相同的哈希，用于部署时去重
+    pub fn content_hash(&self) -> Result<String> {
+        let mut value = serde_json::to_value(self.clone_with_new_ids())?;
+
+        if let Some(workflow) = value.as_object_mut() {
+            workflow.remove("groups");
+            workflow.remove("extra");
+        }
+
+        if let Some(nodes) = value.get_mut("nodes").and_then(Value::as_array_mut) {
+            for node in nodes.iter_mut().filter_map(Value::as_object_mut) {
+                node.remove("pos");
+            }
+        }
+
+        let bytes = serde_json::to_vec(&canonicalize(value))?;
+
+        Ok(format!("{:x}", Sha256::digest(bytes)))
+    }
+
+    // 只执行选中的节点及其上游依赖节点，用于调试大型工作流(如只预取数据)
+    pub async fn execute_subgraph(&mut self, node_ids: &[u32]) -> Result<()> {
+        let subgraph = self.subgraph_node_ids(node_ids)?;
+        self.execute_nodes(|node| subgraph.contains(&node.id)).await
+    }
+
+    // 选中的节点及其所有上游依赖节点
+    fn subgraph_node_ids(&self, node_ids: &[u32]) -> Result<HashSet<u32>> {
+        let mut subgraph = HashSet::new();
+        let mut pending = node_ids.to_vec();
+
+        while let Some(node_id) = pending.pop() {
+            if !self.nodes.iter().any(|node| node.id == node_id) {
+                anyhow::bail!("Node not found: {}", node_id);
+            }
+
+            if !subgraph.insert(node_id) {
+                continue;
+            }
+
+            pending.extend(
+                self.links
+                    .iter()
+                    .filter(|link| link.target_id == node_id)
+                    .map(|link| link.origin_id),
+            );
+        }
+
+        Ok(subgraph)
+    }
+
     // 建立连接
     fn make_connection(&self, origin: &NodeKind, target: &mut NodeKind, link: &Link) -> Result<()> {
         match link.link_type.as_str() {
@@ -122,9 +603,22 @@ impl Workflow {
             "TickStream" => {
                 origin.connection::<TickStream>(target, link.origin_slot, link.target_slot)?
             }
+            "KlineStream" => {
+                origin.connection::<KlineStream>(target, link.origin_slot, link.target_slot)?
+            }
             "SpotClient" => {
                 origin.connection::<SpotClientKind>(target, link.origin_slot, link.target_slot)?
             }
+            "AnnouncementStream" => origin.connection::<AnnouncementStream>(
+                target,
+                link.origin_slot,
+                link.target_slot,
+            )?,
+            "CapitalAllocation" => origin.connection::<CapitalAllocation>(
+                target,
+                link.origin_slot,
+                link.target_slot,
+            )?,
             _ => anyhow::bail!("Invalid link type: {}", link.link_type),
         }
 
@@ -137,6 +631,178 @@ impl Workflow {
             .ok_or_else(|| anyhow!("Context not set"))
     }
 
+    // 工作流ID，需在 setup 之后调用
+    pub fn workflow_id(&self) -> Result<&str> {
+        Ok(self.context()?.workflow_id())
+    }
+
+    fn checkpointer(&self) -> Result<Checkpointer> {
+        Ok(Checkpointer {
+            last_node_id: self.last_node_id,
+            last_link_id: self.last_link_id,
+            nodes: self.nodes.clone(),
+            links: self.links.clone(),
+            groups: self.groups.clone(),
+            config: self.config.clone(),
+            extra: self.extra.clone(),
+            version: self.version,
+            quote_asset: Arc::clone(&self.quote_asset),
+            execution_history: self.execution_history.clone(),
+            running_time: Arc::clone(&self.running_time),
+            context: Arc::clone(self.context()?),
+        })
+    }
+
+    // 保存检查点，包含各节点最近的运行时数据，需在 setup 之后调用
+    pub async fn save(&self, status: WorkflowRunStatus) -> Result<()> {
+        self.checkpointer()?.save(status).await
+    }
+
+    // 从最近一次检查点恢复工作流，setup 后沿用原工作流ID和各节点的运行时数据
+    pub async fn load(db: &PgPool, workflow_id: &str) -> Result<Option<Workflow>> {
+        let Some(run) = workflow_run::get(db, workflow_id).await? else {
+            return Ok(None);
+        };
+
+        let mut workflow: Workflow = serde_json::from_str(&run.checkpoint)?;
+        workflow.workflow_id = Some(run.workflow_id);
+
+        tracing::info!(
+            monotonic_counter.workflow_resumed = 1_u64,
+            workflow_id = %workflow_id,
+            checkpointed_at = %run.checkpointed_at,
+            "Workflow loaded from checkpoint"
+        );
+
+        Ok(Some(workflow))
+    }
+
+    // 工作流加载后累计的资源消耗，每次执行的消耗记录在执行记录中
+    pub fn resource_usage(&self) -> Result<ResourceUsage> {
+        Ok(self.context()?.resource_meter.usage())
+    }
+
+    // 回测事件日志，需在执行前开启才会记录
+    pub fn event_log(&self) -> Result<Arc<EventLog>> {
+        Ok(self.context()?.cloned_event_log())
+    }
+
+    // 订阅运行时事件，只能收到订阅之后发布的事件
+    pub fn subscribe_events(&self) -> Result<broadcast::Receiver<WorkflowEvent>> {
+        Ok(self.context()?.event_bus.subscribe())
+    }
+
+    // 所有策略节点的执行质量报告
+    pub async fn execution_reports(&self) -> Vec<ExecutionReport> {
+        let mut reports = vec![];
+
+        for node in self.deserialized_nodes.values() {
+            reports.extend(node.read().await.execution_reports());
+        }
+
+        reports
+    }
+
+    // 检查所有断言节点，需在节点执行结束后调用
+    pub async fn assertions(&self) -> Result<Vec<AssertionResult>> {
+        let mut results = vec![];
+
+        for node in self.sorted_nodes() {
+            let node_kind = self
+                .deserialized_nodes
+                .get(&node.id)
+                .ok_or_else(|| anyhow!("Node not found: {}", node.id))?
+                .read()
+                .await;
+
+            if let NodeKind::Assert(assert) = &*node_kind {
+                let value = self.assert_metric_value(assert.metric()).await?;
+                results.push(assert.check(value));
+            }
+        }
+
+        Ok(results)
+    }
+
+    async fn assert_metric_value(&self, metric: AssertMetric) -> Result<Decimal> {
+        let value = match metric {
+            AssertMetric::RealizedPnl => self.realized_pnl().await?,
+            AssertMetric::UnrealizedPnl => self.unrealized_pnl().await?,
+            AssertMetric::TotalPnl => self.total_pnl().await?,
+            AssertMetric::TotalReturn => self.total_return().await?,
+            AssertMetric::MaxDrawdown => self.max_drawdown().await,
+            AssertMetric::TotalTrades => Decimal::from(self.total_trades().await),
+        };
+
+        Ok(value)
+    }
+
+    // 各策略节点中最大的回撤比例
+    pub async fn max_drawdown(&self) -> Decimal {
+        let mut max_drawdown = Decimal::ZERO;
+
+        for node in self.deserialized_nodes.values() {
+            max_drawdown = max_drawdown.max(node.read().await.max_drawdown());
+        }
+
+        max_drawdown
+    }
+
+    // 所有策略节点的总交易次数
+    pub async fn total_trades(&self) -> u64 {
+        let mut total_trades = 0;
+
+        for node in self.deserialized_nodes.values() {
+            total_trades += node.read().await.total_trades();
+        }
+
+        total_trades
+    }
+
+    // 胜率，盈利的卖出交易占卖出交易的比例，没有卖出时为0
+    pub async fn win_rate(&self) -> Decimal {
+        let mut win_trades = 0;
+        let mut sell_trades = 0;
+
+        for node in self.deserialized_nodes.values() {
+            let node = node.read().await;
+            win_trades += node.win_trades();
+            sell_trades += node.sell_trades();
+        }
+
+        match sell_trades {
+            0 => Decimal::ZERO,
+            _ => Decimal::from(win_trades) / Decimal::from(sell_trades),
+        }
+    }
+
+    // 按各节点初始资金加权平均
+    async fn capital_weighted<F, Fut>(&self, f: F) -> Result<Decimal>
+    where
+        F: Fn(Arc<RwLock<NodeKind>>) -> Fut,
+        Fut: Future<Output = Result<Decimal>>,
+    {
+        let mut total_capital = Decimal::ZERO;
+        let mut weighted = Decimal::ZERO;
+
+        for node in self.deserialized_nodes.values() {
+            let capital = node.read().await.initial_capital().await?;
+
+            if capital.is_zero() {
+                continue;
+            }
+
+            total_capital += capital;
+            weighted += capital * f(Arc::clone(node)).await?;
+        }
+
+        if total_capital.is_zero() {
+            return Ok(Decimal::ZERO);
+        }
+
+        Ok(weighted / total_capital)
+    }
+
     // 计算资产金额
     async fn calculate_asset_amount<F, Fut>(&self, f: F) -> Result<Decimal>
     where
@@ -154,18 +820,80 @@ impl Workflow {
 }
 
 impl NodeExecutable for Workflow {
-    async fn execute(&mut self) -> Result<()> {
+    async fn execute(&mut self) -> Result<(), NodeError> {
+        Ok(self.execute_nodes(|_| true).await?)
+    }
+
+    // 优雅停止: 通知节点停止并等待退出，各节点收尾(保存运行时数据，可选撤销挂单)，
+    // 记录最终的执行记录并补写积压数据。之后由调用方保存检查点
+    async fn shutdown(&mut self, cancel_orders: bool) -> Result<()> {
+        self.token.cancel();
+
+        let node_handles = self.node_handles.drain(..).collect::<Vec<_>>();
+        let abort_handles = node_handles
+            .iter()
+            .map(JoinHandle::abort_handle)
+            .collect::<Vec<_>>();
+        let mut joined = std::pin::pin!(futures::future::join_all(node_handles));
+
+        if tokio::time::timeout(SHUTDOWN_TIMEOUT, joined.as_mut())
+            .await
+            .is_err()
+        {
+            tracing::warn!(
+                monotonic_counter.workflow_shutdown_timeout = 1_u64,
+                "Workflow nodes did not stop within {:?}, aborting",
+                SHUTDOWN_TIMEOUT
+            );
+            abort_handles.iter().for_each(AbortHandle::abort);
+            joined.await;
+        }
+
+        // 节点已退出执行，收尾失败不影响其他节点
+        for node in self.deserialized_nodes.values() {
+            let mut node = node.write().await;
+
+            if let Err(e) = node.shutdown(cancel_orders).await {
+                tracing::warn!("Node {:?} shutdown failed: {}", *node, e);
+            }
+        }
+
+        for handle in self.runtime_handles.drain(..) {
+            handle.await?;
+        }
+
+        if let Some(context) = &self.context {
+            context.flush_write_buffer().await?;
+        }
+
+        tracing::info!(cancel_orders, "Workflow shut down");
+
+        Ok(())
+    }
+}
+
+impl Workflow {
+    // 执行满足条件的节点
+    async fn execute_nodes(&mut self, filter: impl Fn(&Node) -> bool) -> Result<()> {
         let start_at = Instant::now();
         let execute_time = Arc::new(RwLock::new(ExecutionRecord::new()));
         let cloned_execute_time = Arc::clone(&execute_time);
         let cloned_running_time = self.running_time.clone();
         let cloned_token = self.token.clone();
+        let cloned_context = Arc::clone(self.context()?);
         let running_time = *cloned_running_time.read().await;
+        let resource_meter = cloned_context.cloned_resource_meter();
+        let baseline_usage = resource_meter.usage();
+        let clock = cloned_context.cloned_clock();
 
         self.execution_history.push(execute_time);
 
+        let checkpoint_interval = cloned_context.checkpoint_interval;
+        let checkpointer = self.checkpointer()?;
+        let cloned_supervisor = Arc::clone(&self.supervisor);
+
         // 计算运行时间
-        tokio::spawn(async move {
+        let runtime_handle = tokio::spawn(async move {
             let update_times = || async {
                 let mut execute_time_write = cloned_execute_time.write().await;
                 let mut running_time_write = cloned_running_time.write().await;
@@ -174,24 +902,63 @@ impl NodeExecutable for Workflow {
                 let time = running_time + elapsed;
                 *running_time_write = time;
                 execute_time_write.running_time = elapsed;
-                execute_time_write.stop_at = Utc::now();
+                execute_time_write.stop_at = clock.now();
+                // 回测的执行记录使用模拟时间
+                if let Some(started_at) = clock.started_at() {
+                    execute_time_write.start_at = started_at;
+                }
+                execute_time_write.resource_usage = resource_meter.usage().since(&baseline_usage);
             };
 
+            let checkpointer = &checkpointer;
+            let save_checkpoint = |status| async move {
+                if checkpoint_interval.is_none()
+                    || checkpointer.context.finished.load(Ordering::Relaxed)
+                {
+                    return;
+                }
+
+                if let Err(e) = checkpointer.save(status).await {
+                    tracing::warn!("Save workflow checkpoint failed: {}", e);
+                }
+            };
+
+            save_checkpoint(WorkflowRunStatus::Running).await;
+
             tokio::select! {
                 _ = async {
+                    let mut elapsed_secs = 0;
+
                     loop {
                         tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
                         update_times().await;
+
+                        if let Err(e) = cloned_context.flush_write_buffer().await {
+                            tracing::warn!("Flush write buffer failed: {}", e);
+                        }
+
+                        elapsed_secs += 1;
+
+                        if checkpoint_interval.is_some_and(|secs| elapsed_secs % secs == 0) {
+                            save_checkpoint(WorkflowRunStatus::Running).await;
+                        }
                     }
                 } => {}
                 _ = cloned_token.cancelled() => {
                     update_times().await;
+                    // 节点失败终止工作流时记录为失败
+                    save_checkpoint(run_status(&cloned_supervisor, &cloned_token)).await;
                 }
             }
         });
 
+        self.runtime_handles.push(runtime_handle);
+
+        let mut node_handles = vec![];
+        let watchdog = Watchdog::from_config(&self.config);
+
         // 按顺序从前至后执行节点
-        for node in self.sorted_nodes().into_iter() {
+        for node in self.sorted_nodes().into_iter().filter(|node| filter(node)) {
             let node_id = node.id;
 
             let mut node_kind = self
@@ -202,23 +969,135 @@ impl NodeExecutable for Workflow {
                 .await;
 
             let cloned_token = self.token.clone();
+            let watchdog = watchdog.clone();
+            let heartbeat = node_kind.heartbeat();
+            let node_name = node.properties.prop_type.clone();
+            let event_bus = self.context()?.cloned_event_bus();
+            let supervisor = Arc::clone(&self.supervisor);
+            let policy = RestartPolicy::from_config(&self.config, node_id)?;
 
             // 在单独的线程中执行节点
-            tokio::spawn(async move {
-                tokio::select! {
-                    _ = async {
-                        node_kind.execute().await?;
-                        Ok::<(), anyhow::Error>(())
-                    } => {
-                        tracing::info!("Node {:?} finished", node_kind);
-                    },
-                    _ = cloned_token.cancelled() => {
-                        tracing::info!("Node {:?} cancelled", node_kind);
+            let handle = tokio::spawn(async move {
+                event_bus.publish(WorkflowEvent::NodeStarted {
+                    node_id,
+                    node_type: node_name.clone(),
+                });
+
+                let finish = |error: Option<&NodeError>| {
+                    event_bus.publish(WorkflowEvent::NodeFinished {
+                        node_id,
+                        node_type: node_name.clone(),
+                        error: error.map(ToString::to_string),
+                    })
+                };
+                let mut attempt = 0;
+
+                loop {
+                    supervisor.started(node_id, &node_name);
+                    let started_at = Instant::now();
+
+                    // 返回 Some 时等待后重新执行节点
+                    let restart = tokio::select! {
+                        result = AssertUnwindSafe(node_kind.execute()).catch_unwind() => {
+                            let error = match result {
+                                Ok(Ok(()) | Err(NodeError::Cancelled)) => None,
+                                Ok(Err(e)) => Some(e),
+                                // panic 视为内部错误，按重启策略处理
+                                Err(panic) => Some(NodeError::Internal(anyhow!(
+                                    "Node panicked: {}",
+                                    panic_message(&*panic)
+                                ))),
+                            };
+
+                            match error {
+                                None => {
+                                    supervisor.finished(node_id);
+                                    finish(None);
+                                    None
+                                }
+                                // 配置错误或交易所拒绝时其他节点继续运行没有意义，终止整个工作流
+                                Some(e) if e.action() == ErrorAction::Abort => {
+                                    tracing::error!(
+                                        monotonic_counter.workflow_aborted = 1_u64,
+                                        node_id,
+                                        kind = e.kind(),
+                                        "Node {:?} failed, abort workflow: {}",
+                                        node_kind,
+                                        e
+                                    );
+                                    supervisor.failed(node_id, e.to_string());
+                                    finish(Some(&e));
+                                    cloned_token.cancel();
+                                    None
+                                }
+                                Some(e) => {
+                                    // 稳定运行一段时间后才失败，不计入之前的连续重启
+                                    if started_at.elapsed() >= RESTART_RESET_AFTER {
+                                        attempt = 0;
+                                    }
+
+                                    attempt += 1;
+
+                                    match policy.delay(attempt) {
+                                        Some(delay) => Some((delay, Some(e.to_string()))),
+                                        None => {
+                                            supervisor.failed(node_id, e.to_string());
+                                            finish(Some(&e));
+                                            None
+                                        }
+                                    }
+                                }
+                            }
+                        },
+                        _ = async {
+                            match &watchdog {
+                                Some(watchdog) => watchdog.watch(&node_name, &heartbeat).await,
+                                None => std::future::pending().await,
+                            }
+                        } => {
+                            tracing::warn!(
+                                monotonic_counter.node_watchdog_restart = 1_u64,
+                                "Node {:?} restarting",
+                                node_kind
+                            );
+                            Some((Duration::ZERO, None))
+                        },
+                        _ = cloned_token.cancelled() => {
+                            tracing::info!("Node {:?} cancelled", node_kind);
+                            supervisor.cancelled(node_id);
+                            finish(None);
+                            None
+                        }
+                    };
+
+                    let Some((delay, error)) = restart else {
+                        break;
+                    };
+
+                    event_bus.publish(WorkflowEvent::NodeRestarting {
+                        node_id,
+                        node_type: node_name.clone(),
+                        attempt: supervisor.restarting(node_id, error.clone()),
+                        delay_ms: delay.as_millis() as u64,
+                        error,
+                    });
+
+                    tokio::select! {
+                        _ = tokio::time::sleep(delay) => {}
+                        _ = cloned_token.cancelled() => {
+                            supervisor.cancelled(node_id);
+                            finish(None);
+                            break;
+                        }
                     }
                 }
             });
+
+            node_handles.push(handle);
         }
 
+        self.node_handles.extend(node_handles);
+
         tracing::info!("Workflow nodes execute");
 
         Ok(())
@@ -248,6 +1127,70 @@ impl TradeStats for Workflow {
     async fn running_time(&self) -> Result<u128> {
         Ok(self.context()?.running_time().await)
     }
+
+    // 时间加权收益率，按各策略节点初始资金加权
+    async fn time_weighted_return(&self) -> Result<Decimal> {
+        self.capital_weighted(|node| async move { node.read().await.time_weighted_return().await })
+            .await
+    }
+
+    // 资金加权收益率，按各策略节点初始资金加权
+    async fn money_weighted_return(&self) -> Result<Decimal> {
+        self.capital_weighted(|node| async move { node.read().await.money_weighted_return().await })
+            .await
+    }
+
+    // 各策略节点的净值序列按小时对齐后求和
+    async fn equity_curve(&self) -> Result<Vec<(i64, Decimal)>> {
+        let mut curves = vec![];
+
+        for node in self.deserialized_nodes.values() {
+            let curve = node.read().await.equity_curve().await?;
+
+            if !curve.is_empty() {
+                curves.push(curve);
+            }
+        }
+
+        Ok(merge_equity_curves(&curves))
+    }
+
+    // 各策略节点的交易记录合并
+    async fn trade_record(&self) -> Result<TradeRecord> {
+        let mut record = TradeRecord::default();
+
+        for node in self.deserialized_nodes.values() {
+            record.merge(&node.read().await.trade_record().await?);
+        }
+
+        Ok(record)
+    }
+}
+
+// 对象按键排序，保证相同内容的序列化结果一致
+fn canonicalize(value: Value) -> Value {
+    match value {
+        Value::Object(map) => map
+            .into_iter()
+            .sorted_by(|(a, _), (b, _)| a.cmp(b))
+            .map(|(key, value)| (key, canonicalize(value)))
+            .collect::<serde_json::Map<_, _>>()
+            .into(),
+        Value::Array(values) => values.into_iter().map(canonicalize).collect(),
+        value => value,
+    }
+}
+
+impl<'de> Deserialize<'de> for Workflow {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value =
+            migration::migrate(Value::deserialize(deserializer)?).map_err(de::Error::custom)?;
+
+        Workflow::deserialize(value).map_err(de::Error::custom)
+    }
 }
 
 impl Serialize for Workflow {
@@ -294,9 +1237,142 @@ impl Serialize for Workflow {
     }
 }
 
+// 检查点任务持有的工作流快照，运行中不锁定节点，
+// 节点的运行时数据取自节点最近保存到上下文中的副本
+struct Checkpointer {
+    last_node_id: u32,
+    last_link_id: u32,
+    nodes: Vec<Node>,
+    links: Vec<Link>,
+    groups: Vec<String>,
+    config: HashMap<String, String>,
+    extra: HashMap<String, String>,
+    version: f32,
+    quote_asset: Arc<RwLock<QuoteAsset>>,
+    execution_history: Vec<Arc<RwLock<ExecutionRecord>>>,
+    running_time: Arc<RwLock<u128>>,
+    context: Arc<WorkflowContext>,
+}
+
+// 与 Workflow 的序列化格式一致，可直接反序列化为 Workflow
+#[derive(Serialize)]
+struct Checkpoint<'a> {
+    last_node_id: u32,
+    last_link_id: u32,
+    nodes: Vec<Node>,
+    links: &'a [Link],
+    groups: &'a [String],
+    config: &'a HashMap<String, String>,
+    extra: &'a HashMap<String, String>,
+    version: f32,
+    quote_asset: QuoteAsset,
+    execution_history: Vec<ExecutionRecord>,
+    running_time: u128,
+}
+
+impl Checkpointer {
+    async fn to_json(&self) -> Result<String> {
+        let nodes = self
+            .nodes
+            .iter()
+            .map(|node| Node {
+                runtime_store: self
+                    .context
+                    .runtime_store(node.id)
+                    .or_else(|| node.runtime_store.clone()),
+                ..node.clone()
+            })
+            .sorted_by_key(|node| node.order)
+            .collect::<Vec<_>>();
+
+        let mut execution_history = Vec::with_capacity(self.execution_history.len());
+
+        for record in &self.execution_history {
+            execution_history.push(record.read().await.clone());
+        }
+
+        let checkpoint = Checkpoint {
+            last_node_id: self.last_node_id,
+            last_link_id: self.last_link_id,
+            nodes,
+            links: &self.links,
+            groups: &self.groups,
+            config: &self.config,
+            extra: &self.extra,
+            version: self.version,
+            quote_asset: self.quote_asset.read().await.clone(),
+            execution_history,
+            running_time: *self.running_time.read().await,
+        };
+
+        Ok(serde_json::to_string(&checkpoint)?)
+    }
+
+    async fn save(&self, status: WorkflowRunStatus) -> Result<()> {
+        let data = SaveCheckpointParams::builder()
+            .workflow_id(self.context.workflow_id())
+            .status(status)
+            .checkpoint(self.to_json().await?)
+            .owner(self.context.owner())
+            .build();
+
+        // 租约已被其他实例接管，本实例不能再覆盖检查点
+        if workflow_run::save_checkpoint(&self.context.db, data)
+            .await?
+            .is_none()
+        {
+            anyhow::bail!(
+                "Workflow {} is owned by another instance",
+                self.context.workflow_id()
+            );
+        }
+
+        tracing::debug!(
+            monotonic_counter.workflow_checkpoint = 1_u64,
+            workflow_id = %self.context.workflow_id(),
+            "Workflow checkpoint saved"
+        );
+
+        Ok(())
+    }
+}
+
+// 有节点失败时为失败，否则被停止时为已停止、节点全部结束时为已完成
+fn run_status(supervisor: &Supervisor, token: &CancellationToken) -> WorkflowRunStatus {
+    if supervisor.has_failed() {
+        WorkflowRunStatus::Failed
+    } else if token.is_cancelled() {
+        WorkflowRunStatus::Stopped
+    } else if supervisor.all_terminated() {
+        WorkflowRunStatus::Finished
+    } else {
+        WorkflowRunStatus::Running
+    }
+}
+
+fn panic_message(panic: &(dyn Any + Send)) -> &str {
+    panic
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown panic")
+}
+
+// 检查点间隔(秒)，未配置时不保存检查点
+fn checkpoint_interval(config: &HashMap<String, String>) -> Option<u64> {
+    config
+        .get("checkpoint_interval_secs")
+        .and_then(|secs| secs.parse::<u64>().ok())
+        .filter(|secs| *secs > 0)
+}
+
 impl Drop for Workflow {
     fn drop(&mut self) {
         self.token.cancel();
+
+        if let Some(context) = &self.context {
+            StatsAggregator::global().remove_workflow(context.workflow_id());
+        }
     }
 }
 
@@ -374,6 +1450,43 @@ impl Node {
             .as_ref()
             .ok_or_else(|| anyhow!("Context not set"))
     }
+
+    // 按新的编号复制节点，不保留运行时数据
+    fn remap_ids(&self, node_ids: &HashMap<u32, u32>, link_ids: &HashMap<u32, u32>) -> Node {
+        let inputs = self.inputs.as_ref().map(|inputs| {
+            inputs
+                .iter()
+                .map(|input| Input {
+                    link: input.link.and_then(|link| link_ids.get(&link).copied()),
+                    ..input.clone()
+                })
+                .collect()
+        });
+
+        let outputs = self.outputs.as_ref().map(|outputs| {
+            outputs
+                .iter()
+                .map(|output| Output {
+                    links: output.links.as_ref().map(|links| {
+                        links
+                            .iter()
+                            .filter_map(|link| link_ids.get(link).copied())
+                            .collect()
+                    }),
+                    ..output.clone()
+                })
+                .collect()
+        });
+
+        Node {
+            id: node_ids[&self.id],
+            inputs,
+            outputs,
+            runtime_store: None,
+            context: None,
+            ..self.clone()
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -406,6 +1519,8 @@ pub struct ExecutionRecord {
     start_at: DateTime<Utc>, // 开始时间
     stop_at: DateTime<Utc>,  // 结束时间
     running_time: u128,      // 运行持续时间(微妙)
+    #[serde(default)]
+    resource_usage: ResourceUsage, // 本次执行的资源消耗
 }
 
 impl ExecutionRecord {
@@ -414,6 +1529,7 @@ impl ExecutionRecord {
             start_at: Utc::now(),
             stop_at: Utc::now(),
             running_time: 0,
+            resource_usage: ResourceUsage::default(),
         }
     }
 }
@@ -428,11 +1544,23 @@ impl Default for ExecutionRecord {
 #[derive(Debug)]
 pub struct WorkflowContext {
     id: String,                                              // 工作流ID
+    owner: String,                                           // 运行工作流的实例ID
     db: Arc<PgPool>,                                         // 数据库
     quote_asset: Arc<RwLock<QuoteAsset>>,                    // 计价货币
     price_store: Arc<RwLock<PriceStore>>,                    // 价格存储
     exchange_rate_manager: Arc<RwLock<ExchangeRateManager>>, // 汇率管理器
     running_time: Arc<RwLock<u128>>,                         // 运行持续时间(微妙)
+    write_buffer: Arc<WriteBuffer>,                          // 数据库写缓冲
+    valuation_policy: Arc<ValuationPolicy>,                  // 估值策略
+    resource_meter: Arc<ResourceMeter>,                      // 资源计数器
+    event_log: Arc<EventLog>,                                // 回测事件日志
+    runtime_stores: DashMap<u32, String>,                    // 各节点最近保存的运行时数据
+    backtest_clients: DashMap<u32, BacktestSpotClient>, // 回测和模拟盘账户，行情节点每个tick撮合其挂单
+    checkpoint_interval: Option<u64>,                   // 检查点间隔(秒)
+    finished: AtomicBool,                               // 节点是否已全部执行完毕
+    tick_record_dir: Option<PathBuf>,                   // 实盘tick录制目录，未配置时不录制
+    event_bus: Arc<EventBus>,                           // 运行时事件总线
+    clock: Arc<SimulatedClock>,                         // 工作流时钟，回测时由tick时间驱动
 }
 
 #[allow(unused)]
@@ -445,21 +1573,72 @@ impl WorkflowContext {
     ) -> Self {
         let id = generate_workflow_id();
         let price_store = Arc::new(RwLock::new(PriceStore::new()));
+        let resource_meter = Arc::new(ResourceMeter::default());
+        let write_buffer =
+            Arc::new(WriteBuffer::default().with_resource_meter(Arc::clone(&resource_meter)));
 
         Self {
             id,
+            owner: String::new(),
             db,
             quote_asset,
             price_store,
             exchange_rate_manager,
             running_time,
+            write_buffer,
+            valuation_policy: Arc::new(ValuationPolicy::default()),
+            resource_meter,
+            event_log: Arc::new(EventLog::default()),
+            runtime_stores: DashMap::new(),
+            backtest_clients: DashMap::new(),
+            checkpoint_interval: None,
+            finished: AtomicBool::new(false),
+            tick_record_dir: None,
+            event_bus: Arc::new(EventBus::default()),
+            clock: Arc::new(SimulatedClock::default()),
         }
     }
 
+    pub(crate) fn with_valuation_policy(mut self, valuation_policy: ValuationPolicy) -> Self {
+        self.valuation_policy = Arc::new(valuation_policy);
+        self
+    }
+
+    pub(crate) fn with_workflow_id(mut self, workflow_id: impl Into<String>) -> Self {
+        self.id = workflow_id.into();
+        self
+    }
+
+    pub(crate) fn with_owner(mut self, owner: impl Into<String>) -> Self {
+        self.owner = owner.into();
+        self
+    }
+
+    pub(crate) fn with_checkpoint_interval(mut self, checkpoint_interval: Option<u64>) -> Self {
+        self.checkpoint_interval = checkpoint_interval;
+        self
+    }
+
+    pub(crate) fn with_tick_record_dir(mut self, tick_record_dir: Option<PathBuf>) -> Self {
+        self.tick_record_dir = tick_record_dir;
+        self
+    }
+
+    // 数据源节点的tick录制，按工作流ID分目录
+    pub(crate) fn tick_recorder(&self, node_id: u32) -> Option<TickRecorder> {
+        self.tick_record_dir
+            .as_ref()
+            .map(|dir| TickRecorder::new(dir, &self.id, node_id))
+    }
+
     pub(crate) fn workflow_id(&self) -> &str {
         &self.id
     }
 
+    pub(crate) fn owner(&self) -> &str {
+        &self.owner
+    }
+
     pub(crate) fn cloned_db(&self) -> Arc<PgPool> {
         Arc::clone(&self.db)
     }
@@ -468,20 +1647,109 @@ impl WorkflowContext {
         Arc::clone(&self.price_store)
     }
 
+    pub(crate) fn cloned_write_buffer(&self) -> Arc<WriteBuffer> {
+        Arc::clone(&self.write_buffer)
+    }
+
+    pub(crate) fn cloned_valuation_policy(&self) -> Arc<ValuationPolicy> {
+        Arc::clone(&self.valuation_policy)
+    }
+
+    pub(crate) fn cloned_resource_meter(&self) -> Arc<ResourceMeter> {
+        Arc::clone(&self.resource_meter)
+    }
+
+    pub(crate) fn cloned_event_log(&self) -> Arc<EventLog> {
+        Arc::clone(&self.event_log)
+    }
+
+    pub(crate) fn cloned_event_bus(&self) -> Arc<EventBus> {
+        Arc::clone(&self.event_bus)
+    }
+
+    pub(crate) fn cloned_clock(&self) -> Arc<SimulatedClock> {
+        Arc::clone(&self.clock)
+    }
+
+    // 保存节点的运行时数据，未开启检查点时忽略
+    pub(crate) fn save_runtime_store(
+        &self,
+        node_id: u32,
+        runtime_store: impl FnOnce() -> Result<String>,
+    ) -> Result<()> {
+        if self.checkpoint_interval.is_some() {
+            self.runtime_stores.insert(node_id, runtime_store()?);
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn runtime_store(&self, node_id: u32) -> Option<String> {
+        self.runtime_stores.get(&node_id).map(|store| store.clone())
+    }
+
+    // 登记账户节点的模拟撮合客户端，节点重启时覆盖
+    pub(crate) fn register_backtest_client(&self, node_id: u32, client: BacktestSpotClient) {
+        self.backtest_clients.insert(node_id, client);
+    }
+
+    // 行情节点保存tick价格后调用，按该tick的价格撮合所有模拟账户的挂单
+    pub(crate) async fn match_backtest_orders(&self) -> Result<()> {
+        let clients = self
+            .backtest_clients
+            .iter()
+            .map(|client| client.value().clone())
+            .collect::<Vec<_>>();
+
+        for client in clients {
+            client.on_tick().await?;
+        }
+
+        Ok(())
+    }
+
+    // 补写数据库不可用期间积压的数据
+    pub(crate) async fn flush_write_buffer(&self) -> Result<()> {
+        self.write_buffer.flush(&self.db).await
+    }
+
     pub async fn exchange_rate(
         &self,
         base_asset: impl AsRef<str>,
         quote_asset: impl AsRef<str>,
     ) -> Result<ExchangeRate> {
-        self.exchange_rate_manager
-            .write()
-            .await
-            .get_rate(base_asset, quote_asset)
-            .ok_or_else(|| anyhow!(""))
+        let (base_asset, quote_asset) = (base_asset.as_ref(), quote_asset.as_ref());
+        let mut manager = self.exchange_rate_manager.write().await;
+
+        manager.get_rate(base_asset, quote_asset).ok_or_else(|| {
+            let diagnostic = manager
+                .diagnostic(base_asset, quote_asset)
+                .cloned()
+                .unwrap_or_default();
+            anyhow!(
+                "Exchange rate not found: {}/{}, {}",
+                base_asset,
+                quote_asset,
+                diagnostic
+            )
+        })
     }
 
+    // 运行持续时间(微秒)，回测时为模拟时间的跨度
     pub async fn running_time(&self) -> u128 {
-        *self.running_time.read().await
+        match self.clock.elapsed_micros() {
+            Some(elapsed) => elapsed,
+            None => *self.running_time.read().await,
+        }
+    }
+
+    // 当前时间，回测时为模拟时间
+    pub fn now(&self) -> DateTime<Utc> {
+        self.clock.now()
+    }
+
+    pub fn valuation_policy(&self) -> &ValuationPolicy {
+        &self.valuation_policy
     }
 
     pub async fn quote_asset(&self) -> QuoteAsset {
@@ -493,6 +1761,7 @@ impl WorkflowContext {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{migration::WORKFLOW_VERSION, node_core::GraphIssue};
 
     fn default_context(db: PgPool) -> Arc<WorkflowContext> {
         Arc::new(WorkflowContext::new(
@@ -547,7 +1816,7 @@ mod tests {
 
     #[sqlx::test]
     fn test_workflow_deserialize(db: PgPool) -> Result<()> {
-        let json_str = r#"{"last_node_id":3,"last_link_id":3,"nodes":[{"id":2,"type":"加密货币交易所/币安现货(Ticker Mock)","pos":[210,58],"size":[240,150],"flags":{},"order":0,"mode":0,"outputs":[{"name":"现货交易对","type":"SpotPairInfo","links":[1],"slot_index":0},{"name":"Tick数据流","type":"TickStream","links":[2],"slot_index":1}],"properties":{"type":"data.BacktestSpotTicker","params":["BTC","USDT","2024-01-01 00:00:00","2024-01-02 00:00:00"]}},{"id":1,"type":"账户/币安账户(Mock)","pos":[224,295],"size":{"0":210,"1":106},"flags":{},"order":1,"mode":0,"outputs":[{"name":"现货账户客户端","type":"SpotClient","links":[3],"slot_index":0}],"properties":{"type":"client.BacktestSpotClient","params":[0.001, [["USDT",1000]]]}},{"id":3,"type":"交易策略/网格(现货)","pos":[520,93],"size":{"0":210,"1":290},"flags":{},"order":2,"mode":0,"inputs":[{"name":"现货交易对","type":"SpotPairInfo","link":1},{"name":"现货账户客户端","type":"SpotClient","link":3},{"name":"Tick数据流","type":"TickStream","link":2}],"properties":{"type":"strategy.SpotGrid","params":["arithmetic",1,1.1,8,1,"","","",true]}}],"links":[[1,2,0,3,0,"SpotPairInfo"],[2,2,1,3,2,"TickStream"],[3,1,0,3,1,"SpotClient"]],"groups":[],"config":{},"extra":{},"version":0.4}"#;
+        let json_str = r#"{"last_node_id":3,"last_link_id":3,"nodes":[{"id":2,"type":"加密货币交易所/币安现货(Ticker Mock)","pos":[210,58],"size":[240,150],"flags":{},"order":0,"mode":0,"outputs":[{"name":"现货交易对","type":"SpotPairInfo","links":[1],"slot_index":0},{"name":"Tick数据流","type":"TickStream","links":[2],"slot_index":1}],"properties":{"type":"data.BacktestSpotTicker","params":["BTC","USDT","2024-01-01 00:00:00","2024-01-02 00:00:00"]}},{"id":1,"type":"账户/币安账户(Mock)","pos":[224,295],"size":{"0":210,"1":106},"flags":{},"order":1,"mode":0,"outputs":[{"name":"现货账户客户端","type":"SpotClient","links":[3],"slot_index":0}],"properties":{"type":"client.BacktestSpotClient","params":[0.001, [["USDT",1000]]]}},{"id":3,"type":"交易策略/网格(现货)","pos":[520,93],"size":{"0":210,"1":290},"flags":{},"order":2,"mode":0,"inputs":[{"name":"现货交易对","type":"SpotPairInfo","link":1},{"name":"现货账户客户端","type":"SpotClient","link":3},{"name":"Tick数据流","type":"TickStream","link":2}],"properties":{"type":"strategy.SpotGrid","params":["arithmetic",1,1.1,8,1,"","","",true]}}],"links":[[1,2,0,3,0,"SpotPairInfo"],[2,2,1,3,2,"TickStream"],[3,1,0,3,1,"SpotClient"]],"groups":[],"config":{},"extra":{},"version":0.5}"#;
 
         let mut workflow: Workflow = serde_json::from_str(json_str)?;
 
@@ -561,19 +1830,341 @@ mod tests {
         assert_eq!(workflow.nodes.len(), 3);
         assert_eq!(workflow.links.len(), 3);
 
-        assert_eq!(serde_json::to_string(&workflow)?, "{\"last_node_id\":3,\"last_link_id\":3,\"nodes\":[{\"id\":2,\"type\":\"加密货币交易所/币安现货(Ticker Mock)\",\"pos\":[210,58],\"order\":0,\"mode\":0,\"inputs\":null,\"outputs\":[{\"name\":\"现货交易对\",\"type\":\"SpotPairInfo\",\"links\":[1],\"slot_index\":0},{\"name\":\"Tick数据流\",\"type\":\"TickStream\",\"links\":[2],\"slot_index\":1}],\"properties\":{\"type\":\"data.BacktestSpotTicker\",\"params\":[\"BTC\",\"USDT\",\"2024-01-01 00:00:00\",\"2024-01-02 00:00:00\"]},\"runtime_store\":null},{\"id\":1,\"type\":\"账户/币安账户(Mock)\",\"pos\":[224,295],\"order\":1,\"mode\":0,\"inputs\":null,\"outputs\":[{\"name\":\"现货账户客户端\",\"type\":\"SpotClient\",\"links\":[3],\"slot_index\":0}],\"properties\":{\"type\":\"client.BacktestSpotClient\",\"params\":[0.001,[[\"USDT\",1000]]]},\"runtime_store\":null},{\"id\":3,\"type\":\"交易策略/网格(现货)\",\"pos\":[520,93],\"order\":2,\"mode\":0,\"inputs\":[{\"name\":\"现货交易对\",\"type\":\"SpotPairInfo\",\"link\":1},{\"name\":\"现货账户客户端\",\"type\":\"SpotClient\",\"link\":3},{\"name\":\"Tick数据流\",\"type\":\"TickStream\",\"link\":2}],\"outputs\":null,\"properties\":{\"type\":\"strategy.SpotGrid\",\"params\":[\"arithmetic\",1,1.1,8,1,\"\",\"\",\"\",true]},\"runtime_store\":\"{\\\"stats\\\":{\\\"data\\\":{}},\\\"grid\\\":null,\\\"initialized\\\":false}\"}],\"links\":[{\"link_id\":1,\"origin_id\":2,\"origin_slot\":0,\"target_id\":3,\"target_slot\":0,\"link_type\":\"SpotPairInfo\"},{\"link_id\":2,\"origin_id\":2,\"origin_slot\":1,\"target_id\":3,\"target_slot\":2,\"link_type\":\"TickStream\"},{\"link_id\":3,\"origin_id\":1,\"origin_slot\":0,\"target_id\":3,\"target_slot\":1,\"link_type\":\"SpotClient\"}],\"groups\":[],\"config\":{},\"extra\":{},\"version\":0.4,\"quote_asset\":\"USDT\",\"execution_history\":[],\"running_time\":0}");
+        assert_eq!(serde_json::to_string(&workflow)?, "{\"last_node_id\":3,\"last_link_id\":3,\"nodes\":[{\"id\":2,\"type\":\"加密货币交易所/币安现货(Ticker Mock)\",\"pos\":[210,58],\"order\":0,\"mode\":0,\"inputs\":null,\"outputs\":[{\"name\":\"现货交易对\",\"type\":\"SpotPairInfo\",\"links\":[1],\"slot_index\":0},{\"name\":\"Tick数据流\",\"type\":\"TickStream\",\"links\":[2],\"slot_index\":1}],\"properties\":{\"type\":\"data.BacktestSpotTicker\",\"params\":[\"BTC\",\"USDT\",\"2024-01-01 00:00:00\",\"2024-01-02 00:00:00\"]},\"runtime_store\":null},{\"id\":1,\"type\":\"账户/币安账户(Mock)\",\"pos\":[224,295],\"order\":1,\"mode\":0,\"inputs\":null,\"outputs\":[{\"name\":\"现货账户客户端\",\"type\":\"SpotClient\",\"links\":[3],\"slot_index\":0}],\"properties\":{\"type\":\"client.BacktestSpotClient\",\"params\":[0.001,[[\"USDT\",1000]]]},\"runtime_store\":null},{\"id\":3,\"type\":\"交易策略/网格(现货)\",\"pos\":[520,93],\"order\":2,\"mode\":0,\"inputs\":[{\"name\":\"现货交易对\",\"type\":\"SpotPairInfo\",\"link\":1},{\"name\":\"现货账户客户端\",\"type\":\"SpotClient\",\"link\":3},{\"name\":\"Tick数据流\",\"type\":\"TickStream\",\"link\":2}],\"outputs\":null,\"properties\":{\"type\":\"strategy.SpotGrid\",\"params\":[\"arithmetic\",1,1.1,8,1,\"\",\"\",\"\",true]},\"runtime_store\":\"{\\\"stats\\\":{\\\"data\\\":{}},\\\"grid\\\":null,\\\"initialized\\\":false}\"}],\"links\":[{\"link_id\":1,\"origin_id\":2,\"origin_slot\":0,\"target_id\":3,\"target_slot\":0,\"link_type\":\"SpotPairInfo\"},{\"link_id\":2,\"origin_id\":2,\"origin_slot\":1,\"target_id\":3,\"target_slot\":2,\"link_type\":\"TickStream\"},{\"link_id\":3,\"origin_id\":1,\"origin_slot\":0,\"target_id\":3,\"target_slot\":1,\"link_type\":\"SpotClient\"}],\"groups\":[],\"config\":{},\"extra\":{},\"version\":0.5,\"quote_asset\":\"USDT\",\"execution_history\":[],\"running_time\":0}");
 
-        let json_str = r#"{"running_time":100000,"last_node_id":3,"last_link_id":3,"nodes":[],"links":[],"groups":[],"config":{},"extra":{},"version":0.4}"#;
+        let json_str = r#"{"running_time":100000,"last_node_id":3,"last_link_id":3,"nodes":[],"links":[],"groups":[],"config":{},"extra":{},"version":0.5}"#;
 
         let workflow: Workflow = serde_json::from_str(json_str)?;
 
         assert_eq!(*workflow.running_time.as_ref().read_blocking(), 100000);
 
-        assert_eq!(serde_json::to_string(&workflow)?, "{\"last_node_id\":3,\"last_link_id\":3,\"nodes\":[],\"links\":[],\"groups\":[],\"config\":{},\"extra\":{},\"version\":0.4,\"quote_asset\":\"USDT\",\"execution_history\":[],\"running_time\":100000}");
+        assert_eq!(serde_json::to_string(&workflow)?, "{\"last_node_id\":3,\"last_link_id\":3,\"nodes\":[],\"links\":[],\"groups\":[],\"config\":{},\"extra\":{},\"version\":0.5,\"quote_asset\":\"USDT\",\"execution_history\":[],\"running_time\":100000}");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_workflow_subgraph_node_ids() -> Result<()> {
+        let json_str = r#"{"last_node_id":3,"last_link_id":3,"nodes":[{"id":2,"type":"加密货币交易所/币安现货(Ticker Mock)","pos":[210,58],"size":[240,150],"flags":{},"order":0,"mode":0,"outputs":[{"name":"现货交易对","type":"SpotPairInfo","links":[1],"slot_index":0},{"name":"Tick数据流","type":"TickStream","links":[2],"slot_index":1}],"properties":{"type":"data.BacktestSpotTicker","params":["BTC","USDT","2024-01-01 00:00:00","2024-01-02 00:00:00"]}},{"id":1,"type":"账户/币安账户(Mock)","pos":[224,295],"size":{"0":210,"1":106},"flags":{},"order":1,"mode":0,"outputs":[{"name":"现货账户客户端","type":"SpotClient","links":[3],"slot_index":0}],"properties":{"type":"client.BacktestSpotClient","params":[0.001, [["USDT",1000]]]}},{"id":3,"type":"交易策略/网格(现货)","pos":[520,93],"size":{"0":210,"1":290},"flags":{},"order":2,"mode":0,"inputs":[{"name":"现货交易对","type":"SpotPairInfo","link":1},{"name":"现货账户客户端","type":"SpotClient","link":3},{"name":"Tick数据流","type":"TickStream","link":2}],"properties":{"type":"strategy.SpotGrid","params":["arithmetic",1,1.1,8,1,"","","",true]}}],"links":[[1,2,0,3,0,"SpotPairInfo"],[2,2,1,3,2,"TickStream"],[3,1,0,3,1,"SpotClient"]],"groups":[],"config":{},"extra":{},"version":0.4}"#;
+
+        let workflow: Workflow = serde_json::from_str(json_str)?;
+
+        let subgraph = workflow.subgraph_node_ids(&[2])?;
+        assert_eq!(subgraph, HashSet::from([2]));
+
+        let subgraph = workflow.subgraph_node_ids(&[3])?;
+        assert_eq!(subgraph, HashSet::from([1, 2, 3]));
+
+        assert!(workflow.subgraph_node_ids(&[4]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_workflow_validate() -> Result<()> {
+        let json_str = r#"{"last_node_id":3,"last_link_id":3,"nodes":[{"id":2,"type":"加密货币交易所/币安现货(Ticker Mock)","pos":[210,58],"order":0,"mode":0,"outputs":[{"name":"现货交易对","type":"SpotPairInfo","links":[1],"slot_index":0},{"name":"Tick数据流","type":"TickStream","links":[2],"slot_index":1}],"properties":{"type":"data.BacktestSpotTicker","params":["BTC","USDT","2024-01-01 00:00:00","2024-01-02 00:00:00"]}},{"id":1,"type":"账户/币安账户(Mock)","pos":[224,295],"order":1,"mode":0,"outputs":[{"name":"现货账户客户端","type":"SpotClient","links":[3],"slot_index":0}],"properties":{"type":"client.BacktestSpotClient","params":[0.001, [["USDT",1000]]]}},{"id":3,"type":"交易策略/网格(现货)","pos":[520,93],"order":2,"mode":0,"inputs":[{"name":"现货交易对","type":"SpotPairInfo","link":1},{"name":"现货账户客户端","type":"SpotClient","link":3},{"name":"Tick数据流","type":"TickStream","link":2}],"properties":{"type":"strategy.SpotGrid","params":["arithmetic",1,1.1,8,1,"","","",true]}}],"links":[[1,2,0,3,0,"SpotPairInfo"],[2,2,1,3,2,"TickStream"],[3,1,0,3,1,"SpotClient"]],"groups":[],"config":{},"extra":{},"version":0.4}"#;
+
+        let mut workflow: Workflow = serde_json::from_str(json_str)?;
+        workflow.validate()?;
+        assert_eq!(
+            workflow
+                .nodes()
+                .iter()
+                .map(|node| node.id)
+                .collect::<Vec<_>>(),
+            vec![2, 1, 3]
+        );
+
+        let diagnostics = |workflow: &Workflow| {
+            workflow
+                .validate()
+                .err()
+                .map(|e| e.diagnostics)
+                .unwrap_or_default()
+        };
+
+        // 参数错误
+        workflow.set_node_param(3, 0, "unknown")?;
+        let errors = diagnostics(&workflow);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].node_id, Some(3));
+        assert_eq!(errors[0].field, "properties.params");
+
+        // 连接的节点不存在，网格节点缺少交易对输入
+        let mut workflow: Workflow = serde_json::from_str(json_str)?;
+        workflow.links[0].origin_id = 9;
+        assert_eq!(
+            diagnostics(&workflow),
+            vec![
+                Diagnostic::link("link 1 references missing node 9"),
+                Diagnostic::node(
+                    3,
+                    "inputs[0]",
+                    "Required input '现货交易对' is not connected"
+                ),
+            ]
+        );
+
+        // 节点类型未知
+        let mut workflow: Workflow = serde_json::from_str(json_str)?;
+        workflow.nodes[1].properties.prop_type = "client.Unknown".to_string();
+        let errors = diagnostics(&workflow);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].node_id, Some(1));
+        assert_eq!(errors[0].field, "properties.type");
+
+        // 端口类型不一致，同一个输入连接了两次
+        let mut workflow: Workflow = serde_json::from_str(json_str)?;
+        workflow.links[1].target_slot = 1;
+        workflow.links[2].origin_slot = 1;
+        let errors = diagnostics(&workflow);
+        assert_eq!(
+            errors
+                .iter()
+                .map(|diagnostic| diagnostic.to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                "node 3 inputs[2]: Required input 'Tick数据流' is not connected",
+                "node 3 inputs[1]: Link 2 connects TickStream output to SpotClient input",
+                "node 1 outputs[1]: Link 3 uses nonexistent output slot",
+                "node 3 inputs[1]: Link 3 connects an input that is already connected",
+            ]
+        );
+
+        // 前端给出的顺序有误时按连接排序
+        let mut workflow: Workflow = serde_json::from_str(json_str)?;
+        workflow.nodes[0].order = 2;
+        workflow.nodes[2].order = 0;
+        assert_eq!(
+            workflow
+                .nodes()
+                .iter()
+                .map(|node| node.id)
+                .collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+
+        // 循环依赖
+        workflow.links.push(Link {
+            link_id: 4,
+            origin_id: 3,
+            origin_slot: 0,
+            target_id: 2,
+            target_slot: 0,
+            link_type: "SpotPairInfo".to_string(),
+        });
+        assert_eq!(
+            workflow.check_graph().map_err(|e| e.issues),
+            Err(vec![GraphIssue::Cycle {
+                node_ids: vec![2, 3]
+            }])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_workflow_clone_with_new_ids() -> Result<()> {
+        let json_str = r#"{"last_node_id":9,"last_link_id":12,"nodes":[{"id":7,"type":"加密货币交易所/币安现货(Ticker Mock)","pos":[210,58],"order":0,"mode":0,"outputs":[{"name":"现货交易对","type":"SpotPairInfo","links":[10],"slot_index":0},{"name":"Tick数据流","type":"TickStream","links":[11],"slot_index":1}],"properties":{"type":"data.BacktestSpotTicker","params":["BTC","USDT","2024-01-01 00:00:00","2024-01-02 00:00:00"]}},{"id":5,"type":"账户/币安账户(Mock)","pos":[224,295],"order":1,"mode":0,"outputs":[{"name":"现货账户客户端","type":"SpotClient","links":[12],"slot_index":0}],"properties":{"type":"client.BacktestSpotClient","params":[0.001, [["USDT",1000]]]}},{"id":9,"type":"交易策略/网格(现货)","pos":[520,93],"order":2,"mode":0,"inputs":[{"name":"现货交易对","type":"SpotPairInfo","link":10},{"name":"现货账户客户端","type":"SpotClient","link":12},{"name":"Tick数据流","type":"TickStream","link":11}],"properties":{"type":"strategy.SpotGrid","params":["arithmetic",1,1.1,8,1,"","","",true]},"runtime_store":"{}"}],"links":[[10,7,0,9,0,"SpotPairInfo"],[11,7,1,9,2,"TickStream"],[12,5,0,9,1,"SpotClient"]],"groups":[],"config":{},"extra":{},"version":0.4,"running_time":100}"#;
+
+        let workflow: Workflow = serde_json::from_str(json_str)?;
+        let cloned = workflow.clone_with_new_ids();
+
+        assert_eq!(cloned.last_node_id, 3);
+        assert_eq!(cloned.last_link_id, 3);
+        assert_eq!(
+            cloned.nodes.iter().map(|node| node.id).collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+        assert!(cloned.nodes.iter().all(|node| node.runtime_store.is_none()));
+        assert_eq!(*cloned.running_time.read_blocking(), 0);
+
+        let link = &cloned.links[2];
+        assert_eq!((link.link_id, link.origin_id, link.target_id), (3, 2, 3));
+
+        let inputs = cloned.nodes[2].inputs.as_ref().unwrap();
+        assert_eq!(
+            inputs.iter().map(|input| input.link).collect::<Vec<_>>(),
+            vec![Some(1), Some(3), Some(2)]
+        );
+
+        let outputs = cloned.nodes[0].outputs.as_ref().unwrap();
+        assert_eq!(outputs[1].links, Some(vec![2]));
+
+        // 原工作流的子图关系保持不变
+        assert_eq!(cloned.subgraph_node_ids(&[3])?, HashSet::from([1, 2, 3]));
+
+        let mut workflow = cloned;
+        workflow.set_node_param(3, 3, 12)?;
+        assert_eq!(workflow.nodes[2].properties.params[3], 12);
+        assert!(workflow.set_node_param(3, 20, 12).is_err());
+        assert!(workflow.set_node_param(9, 3, 12).is_err());
 
         Ok(())
     }
 
+    #[test]
+    fn test_workflow_use_backtest_data() -> Result<()> {
+        let json_str = r#"{"last_node_id":3,"last_link_id":3,"nodes":[{"id":2,"type":"数据/币安现货行情","pos":[210,58],"order":0,"mode":0,"outputs":[{"name":"现货交易对","type":"SpotPairInfo","links":[1],"slot_index":0},{"name":"Tick数据流","type":"TickStream","links":[2],"slot_index":1}],"properties":{"type":"data.BinanceSpotTicker","params":["BTC","USDT"]}},{"id":1,"type":"账户/币安账户(Mock)","pos":[224,295],"order":1,"mode":0,"outputs":[{"name":"现货账户客户端","type":"SpotClient","links":[3],"slot_index":0}],"properties":{"type":"client.BacktestSpotClient","params":[0.001, [["USDT",1000]]]}},{"id":3,"type":"交易策略/网格(现货)","pos":[520,93],"order":2,"mode":0,"inputs":[{"name":"现货交易对","type":"SpotPairInfo","link":1},{"name":"现货账户客户端","type":"SpotClient","link":3},{"name":"Tick数据流","type":"TickStream","link":2}],"properties":{"type":"strategy.SpotGrid","params":["arithmetic",1,1.1,8,1,"","","",true]}}],"links":[[1,2,0,3,0,"SpotPairInfo"],[2,2,1,3,2,"TickStream"],[3,1,0,3,1,"SpotClient"]],"groups":[],"config":{},"extra":{},"version":0.4}"#;
+
+        let mut workflow: Workflow = serde_json::from_str(json_str)?;
+        workflow.use_backtest_data("2024-01-01 00:00:00", "2024-01-02 00:00:00")?;
+        workflow.validate()?;
+
+        let ticker = workflow
+            .nodes()
+            .into_iter()
+            .find(|node| node.id == 2)
+            .ok_or_else(|| anyhow!("Node not found"))?;
+
+        assert_eq!(ticker.properties.prop_type, "data.BacktestSpotTicker");
+        assert_eq!(
+            ticker.properties.params,
+            vec![
+                Value::from("BTC"),
+                Value::from("USDT"),
+                Value::from("2024-01-01 00:00:00"),
+                Value::from("2024-01-02 00:00:00"),
+            ]
+        );
+
+        // Bybit行情回放指定时间范围的K线
+        let mut workflow: Workflow = serde_json::from_str(
+            &json_str.replace(
+                r#""type":"data.BinanceSpotTicker","params":["BTC","USDT"]"#,
+                r#""type":"data.BybitSpotTicker","params":["BTC","USDT","2023-01-01 00:00:00","2023-02-01 00:00:00"]"#,
+            ),
+        )?;
+        workflow.use_backtest_data("2024-01-01 00:00:00", "2024-01-02 00:00:00")?;
+        workflow.validate()?;
+
+        let ticker = workflow
+            .nodes()
+            .into_iter()
+            .find(|node| node.id == 2)
+            .ok_or_else(|| anyhow!("Node not found"))?;
+
+        assert_eq!(ticker.properties.prop_type, "data.BybitSpotTicker");
+        assert_eq!(
+            ticker.properties.params[2..],
+            [
+                Value::from("2024-01-01 00:00:00"),
+                Value::from("2024-01-02 00:00:00"),
+            ]
+        );
+
+        // OKX行情没有对应的回测行情节点
+        let mut workflow: Workflow = serde_json::from_str(
+            &json_str.replace("data.BinanceSpotTicker", "data.OkxSpotTicker"),
+        )?;
+        assert!(workflow
+            .use_backtest_data("2024-01-01 00:00:00", "2024-01-02 00:00:00")
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_workflow_migrate() -> Result<()> {
+        // 早期编辑器保存的工作流，没有版本号，行情节点类型已改名
+        let json_str = r#"{"last_node_id":3,"last_link_id":3,"nodes":[{"id":2,"type":"加密货币交易所/币安现货(Ticker)","pos":[210,58],"order":0,"mode":0,"outputs":[{"name":"现货交易对","type":"SpotPairInfo","links":[1],"slot_index":0},{"name":"Tick数据流","type":"TickStream","links":[2],"slot_index":1}],"properties":{"type":"ExchangeInfo.binanceSpotTicker","params":["BTC","USDT"]}},{"id":1,"type":"账户/币安账户(Mock)","pos":[224,295],"order":1,"mode":0,"outputs":[{"name":"现货账户客户端","type":"SpotClient","links":[3],"slot_index":0}],"properties":{"type":"client.BacktestSpotClient","params":[0.001, [["USDT",1000]]]}},{"id":3,"type":"交易策略/网格(现货)","pos":[520,93],"order":2,"mode":0,"inputs":[{"name":"现货交易对","type":"SpotPairInfo","link":1},{"name":"现货账户客户端","type":"SpotClient","link":3},{"name":"Tick数据流","type":"TickStream","link":2}],"properties":{"type":"strategy.SpotGrid","params":["arithmetic",1,1.1,8,1,"","","",true]}}],"links":[[1,2,0,3,0,"SpotPairInfo"],[2,2,1,3,2,"TickStream"],[3,1,0,3,1,"SpotClient"]],"groups":[],"config":{},"extra":{}}"#;
+
+        let workflow: Workflow = serde_json::from_str(json_str)?;
+        workflow.validate()?;
+
+        assert_eq!(workflow.version, WORKFLOW_VERSION);
+        assert_eq!(
+            workflow.nodes()[0].properties.prop_type,
+            "data.BinanceSpotTicker"
+        );
+
+        // 更新版本的工作流无法识别
+        let json_str = r#"{"last_node_id":0,"last_link_id":0,"nodes":[],"links":[],"groups":[],"config":{},"extra":{},"version":9.9}"#;
+        assert!(serde_json::from_str::<Workflow>(json_str).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_workflow_content_hash() -> Result<()> {
+        let json_str = r#"{"last_node_id":9,"last_link_id":12,"nodes":[{"id":7,"type":"加密货币交易所/币安现货(Ticker Mock)","pos":[210,58],"order":0,"mode":0,"outputs":[{"name":"现货交易对","type":"SpotPairInfo","links":[10],"slot_index":0},{"name":"Tick数据流","type":"TickStream","links":[11],"slot_index":1}],"properties":{"type":"data.BacktestSpotTicker","params":["BTC","USDT","2024-01-01 00:00:00","2024-01-02 00:00:00"]}},{"id":5,"type":"账户/币安账户(Mock)","pos":[224,295],"order":1,"mode":0,"outputs":[{"name":"现货账户客户端","type":"SpotClient","links":[12],"slot_index":0}],"properties":{"type":"client.BacktestSpotClient","params":[0.001, [["USDT",1000]]]}},{"id":9,"type":"交易策略/网格(现货)","pos":[520,93],"order":2,"mode":0,"inputs":[{"name":"现货交易对","type":"SpotPairInfo","link":10},{"name":"现货账户客户端","type":"SpotClient","link":12},{"name":"Tick数据流","type":"TickStream","link":11}],"properties":{"type":"strategy.SpotGrid","params":["arithmetic",1,1.1,8,1,"","","",true]},"runtime_store":"{}"}],"links":[[10,7,0,9,0,"SpotPairInfo"],[11,7,1,9,2,"TickStream"],[12,5,0,9,1,"SpotClient"]],"groups":[],"config":{},"extra":{},"version":0.4,"running_time":100}"#;
+
+        let workflow: Workflow = serde_json::from_str(json_str)?;
+        let hash = workflow.content_hash()?;
+        assert_eq!(hash.len(), 64);
+
+        // 重新编号、移动节点、清空运行时数据后哈希不变
+        let mut cloned = workflow.clone_with_new_ids();
+        cloned.nodes[0].pos = [0, 0];
+        assert_eq!(cloned.content_hash()?, hash);
+
+        // 修改参数后哈希改变
+        cloned.set_node_param(3, 3, 12)?;
+        assert_ne!(cloned.content_hash()?, hash);
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrator = "comfy_quant_database::MIGRATOR")]
+    async fn test_workflow_checkpoint(db: PgPool) -> Result<()> {
+        let json_str = r#"{"last_node_id":3,"last_link_id":3,"nodes":[{"id":2,"type":"加密货币交易所/币安现货(Ticker Mock)","pos":[210,58],"order":0,"mode":0,"outputs":[{"name":"现货交易对","type":"SpotPairInfo","links":[1],"slot_index":0},{"name":"Tick数据流","type":"TickStream","links":[2],"slot_index":1}],"properties":{"type":"data.BacktestSpotTicker","params":["BTC","USDT","2024-01-01 00:00:00","2024-01-02 00:00:00"]}},{"id":1,"type":"账户/币安账户(Mock)","pos":[224,295],"order":1,"mode":0,"outputs":[{"name":"现货账户客户端","type":"SpotClient","links":[3],"slot_index":0}],"properties":{"type":"client.BacktestSpotClient","params":[0.001, [["USDT",1000]]]}},{"id":3,"type":"交易策略/网格(现货)","pos":[520,93],"order":2,"mode":0,"inputs":[{"name":"现货交易对","type":"SpotPairInfo","link":1},{"name":"现货账户客户端","type":"SpotClient","link":3},{"name":"Tick数据流","type":"TickStream","link":2}],"properties":{"type":"strategy.SpotGrid","params":["arithmetic",1,1.1,8,1,"","","",true]}}],"links":[[1,2,0,3,0,"SpotPairInfo"],[2,2,1,3,2,"TickStream"],[3,1,0,3,1,"SpotClient"]],"groups":[],"config":{"checkpoint_interval_secs":"10"},"extra":{},"version":0.4,"running_time":100}"#;
+        let runtime_store = r#"{"stats":{"data":{}},"grid":null,"initialized":true}"#;
+        let db = Arc::new(db);
+
+        let mut workflow: Workflow = serde_json::from_str(json_str)?;
+        workflow
+            .setup(
+                Arc::clone(&db),
+                Arc::new(RwLock::new(ExchangeRateManager::default())),
+                "USDT",
+            )
+            .await?;
+
+        // 模拟节点运行中保存的运行时数据
+        workflow
+            .context()?
+            .save_runtime_store(3, || Ok(runtime_store.to_string()))?;
+        workflow.save(WorkflowRunStatus::Running).await?;
+
+        let workflow_id = workflow.workflow_id()?.to_string();
+        let run = workflow_run::list_running(&db).await?;
+        assert_eq!(run.len(), 1);
+        assert_eq!(run[0].workflow_id, workflow_id);
+
+        let mut resumed = Workflow::load(&db, &workflow_id)
+            .await?
+            .ok_or_else(|| anyhow!("Checkpoint not found"))?;
+        resumed
+            .setup(
+                Arc::clone(&db),
+                Arc::new(RwLock::new(ExchangeRateManager::default())),
+                "USDT",
+            )
+            .await?;
+
+        // 沿用原工作流ID，节点从检查点中的运行时数据恢复
+        assert_eq!(resumed.workflow_id()?, workflow_id);
+        assert_eq!(*resumed.running_time.read().await, 100);
+        assert_eq!(
+            serde_json::to_value(&resumed)?["nodes"][2]["runtime_store"],
+            runtime_store
+        );
+
+        assert!(Workflow::load(&db, "unknown").await?.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_checkpoint_interval() {
+        let config = HashMap::from([("checkpoint_interval_secs".to_string(), "60".to_string())]);
+        assert_eq!(checkpoint_interval(&config), Some(60));
+
+        let config = HashMap::from([("checkpoint_interval_secs".to_string(), "0".to_string())]);
+        assert_eq!(checkpoint_interval(&config), None);
+        assert_eq!(checkpoint_interval(&HashMap::new()), None);
+    }
+
     #[sqlx::test]
     async fn test_workflow_context(db: PgPool) {
         let context = default_context(db);
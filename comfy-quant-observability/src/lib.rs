@@ -0,0 +1,7 @@
+mod metrics;
+mod options;
+mod subscriber;
+
+pub use metrics::{init_metrics, render_metrics};
+pub use options::{FileOptions, LogFormat, ObservabilityOptions, Rotation};
+pub use subscriber::{init, log_filter, set_log_filter, ObservabilityGuard};
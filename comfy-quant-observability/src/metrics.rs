@@ -0,0 +1,62 @@
+use anyhow::{anyhow, Result};
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
+use std::sync::OnceLock;
+
+// 耗时直方图的分桶(秒)，覆盖本地回测到跨境请求交易所的耗时
+const DURATION_BUCKETS: &[f64] = &[
+    0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+// 全局 Prometheus 指标句柄，用于渲染 /metrics
+static PROMETHEUS_HANDLE: OnceLock<PrometheusHandle> = OnceLock::new();
+
+// 安装全局 metrics recorder，之后 metrics::counter! 等宏记录的指标以 Prometheus 格式导出；
+// 名称以 _duration_seconds 结尾的直方图按 DURATION_BUCKETS 分桶。进程内只能调用一次
+pub fn init_metrics() -> Result<()> {
+    let handle = PrometheusBuilder::new()
+        .set_buckets_for_metric(
+            Matcher::Suffix("_duration_seconds".to_string()),
+            DURATION_BUCKETS,
+        )?
+        .install_recorder()?;
+
+    PROMETHEUS_HANDLE
+        .set(handle)
+        .map_err(|_| anyhow!("Metrics already initialized"))
+}
+
+// 以 Prometheus 文本格式渲染当前指标，未初始化时返回 None
+pub fn render_metrics() -> Option<String> {
+    let handle = PROMETHEUS_HANDLE.get()?;
+
+    // 清理过期的直方图样本，避免长时间运行内存增长
+    handle.run_upkeep();
+
+    Some(handle.render())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_init_and_render_metrics() -> Result<()> {
+        assert!(render_metrics().is_none());
+
+        init_metrics()?;
+
+        metrics::counter!("workflow_ticks_total", "workflow_id" => "wf1").increment(3);
+        metrics::histogram!("exchange_request_duration_seconds", "method" => "get_price")
+            .record(0.02);
+
+        let output = render_metrics().unwrap_or_default();
+        assert!(output.contains(r#"workflow_ticks_total{workflow_id="wf1"} 3"#));
+        assert!(output.contains(
+            r#"exchange_request_duration_seconds_bucket{method="get_price",le="0.025"} 1"#
+        ));
+
+        assert!(init_metrics().is_err());
+
+        Ok(())
+    }
+}
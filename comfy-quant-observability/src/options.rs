@@ -0,0 +1,104 @@
+use bon::Builder;
+use serde::Deserialize;
+use std::path::PathBuf;
+
+// 可观测性配置，可从配置文件反序列化，也可用构建器创建
+#[derive(Builder, Deserialize, Debug, Clone)]
+#[builder(on(String, into))]
+#[serde(default)]
+pub struct ObservabilityOptions {
+    #[builder(default = "comfy-quant".to_string())]
+    pub service_name: String, // 服务名称，用于链路追踪和日志文件名
+    #[builder(default)]
+    pub format: LogFormat, // 控制台日志格式
+    #[builder(default = "info".to_string())]
+    pub filter: String, // 日志过滤规则，如 "info,sqlx=warn,comfy_quant_node=debug"
+    pub otlp_endpoint: Option<String>, // OTLP接收端地址，为空则不上报链路
+    #[builder(default = 1.0)]
+    pub sampling_ratio: f64, // 链路采样比例(0~1)
+    pub file: Option<FileOptions>,     // 文件日志，为空则不写文件
+}
+
+impl Default for ObservabilityOptions {
+    fn default() -> Self {
+        ObservabilityOptions::builder().build()
+    }
+}
+
+// 控制台日志格式
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    #[default]
+    Pretty, // 多行，便于本地开发
+    Compact, // 单行
+    Json,    // JSON，便于日志收集
+}
+
+// 文件日志配置
+#[derive(Builder, Deserialize, Debug, Clone)]
+#[builder(on(String, into))]
+pub struct FileOptions {
+    #[builder(into)]
+    pub directory: PathBuf, // 日志目录
+    #[serde(default)]
+    #[builder(default)]
+    pub rotation: Rotation, // 滚动周期
+    #[serde(default = "default_file_level")]
+    #[builder(default = default_file_level())]
+    pub level: String, // 文件日志的最低级别
+}
+
+fn default_file_level() -> String {
+    "info".to_string()
+}
+
+// 日志文件滚动周期
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Rotation {
+    Minutely,
+    Hourly,
+    #[default]
+    Daily,
+    Never,
+}
+
+impl From<Rotation> for tracing_appender::rolling::Rotation {
+    fn from(value: Rotation) -> Self {
+        match value {
+            Rotation::Minutely => tracing_appender::rolling::Rotation::MINUTELY,
+            Rotation::Hourly => tracing_appender::rolling::Rotation::HOURLY,
+            Rotation::Daily => tracing_appender::rolling::Rotation::DAILY,
+            Rotation::Never => tracing_appender::rolling::Rotation::NEVER,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_options() -> anyhow::Result<()> {
+        let json_str = r#"{"service_name":"comfy-quant-api","format":"json","filter":"info,sqlx=warn","otlp_endpoint":"http://localhost:4317","file":{"directory":"/tmp/logs","rotation":"hourly"}}"#;
+        let options: ObservabilityOptions = serde_json::from_str(json_str)?;
+
+        assert_eq!(options.service_name, "comfy-quant-api");
+        assert_eq!(options.format, LogFormat::Json);
+        assert_eq!(options.filter, "info,sqlx=warn");
+        assert_eq!(options.sampling_ratio, 1.0);
+
+        let file = options.file.unwrap();
+        assert_eq!(file.directory, PathBuf::from("/tmp/logs"));
+        assert_eq!(file.rotation, Rotation::Hourly);
+        assert_eq!(file.level, "info");
+
+        let options: ObservabilityOptions = serde_json::from_str("{}")?;
+        assert_eq!(options.service_name, "comfy-quant");
+        assert_eq!(options.format, LogFormat::Pretty);
+        assert!(options.otlp_endpoint.is_none());
+
+        Ok(())
+    }
+}
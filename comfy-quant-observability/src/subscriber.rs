@@ -0,0 +1,177 @@
+use crate::{LogFormat, ObservabilityOptions};
+use anyhow::{anyhow, Result};
+use opentelemetry::{global, KeyValue};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::{
+    runtime,
+    trace::{self, RandomIdGenerator, Sampler},
+    Resource,
+};
+use std::sync::OnceLock;
+use tracing::level_filters::LevelFilter;
+use tracing_appender::{non_blocking::WorkerGuard, rolling::RollingFileAppender};
+use tracing_subscriber::{
+    fmt::{self, format::FmtSpan},
+    layer::{Layered, SubscriberExt},
+    reload,
+    util::SubscriberInitExt,
+    EnvFilter, Layer, Registry,
+};
+
+type FilterLayer = reload::Layer<EnvFilter, Registry>;
+type FilterHandle = reload::Handle<EnvFilter, Registry>;
+type BoxedLayer = Box<dyn Layer<Layered<FilterLayer, Registry>> + Send + Sync>;
+
+// 全局日志过滤规则句柄，用于运行时调整日志级别
+static FILTER_HANDLE: OnceLock<FilterHandle> = OnceLock::new();
+
+// 初始化全局 tracing subscriber，进程内只能调用一次
+pub fn init(options: &ObservabilityOptions) -> Result<ObservabilityGuard> {
+    let (filter, handle) = reload::Layer::new(EnvFilter::try_new(&options.filter)?);
+    let mut layers: Vec<BoxedLayer> = vec![];
+
+    // console layer for tracing-subscriber
+    let console = fmt::Layer::new().with_span_events(FmtSpan::CLOSE);
+    layers.push(match options.format {
+        LogFormat::Pretty => console.pretty().boxed(),
+        LogFormat::Compact => console.compact().boxed(),
+        LogFormat::Json => console.json().boxed(),
+    });
+
+    // file appender layer for tracing-subscriber
+    let file_guard = match &options.file {
+        Some(file) => {
+            let file_appender = RollingFileAppender::new(
+                file.rotation.into(),
+                &file.directory,
+                format!("{}.log", options.service_name),
+            );
+            let (non_blocking, guard) = tracing_appender::non_blocking(file_appender);
+            let level = file.level.parse::<LevelFilter>()?;
+
+            layers.push(
+                fmt::Layer::new()
+                    .with_writer(non_blocking)
+                    .with_ansi(false)
+                    .with_filter(level)
+                    .boxed(),
+            );
+
+            Some(guard)
+        }
+        None => None,
+    };
+
+    // opentelemetry tracing layer for tracing-subscriber
+    if let Some(endpoint) = &options.otlp_endpoint {
+        anyhow::ensure!(
+            (0.0..=1.0).contains(&options.sampling_ratio),
+            "Sampling ratio must be between 0 and 1"
+        );
+
+        let tracer = opentelemetry_otlp::new_pipeline()
+            .tracing()
+            .with_exporter(
+                opentelemetry_otlp::new_exporter()
+                    .tonic()
+                    .with_endpoint(endpoint),
+            )
+            .with_trace_config(
+                trace::config()
+                    .with_sampler(Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(
+                        options.sampling_ratio,
+                    ))))
+                    .with_id_generator(RandomIdGenerator::default())
+                    .with_max_events_per_span(32)
+                    .with_max_attributes_per_span(64)
+                    .with_resource(Resource::new(vec![KeyValue::new(
+                        "service.name",
+                        options.service_name.clone(),
+                    )])),
+            )
+            .install_batch(runtime::Tokio)?;
+
+        layers.push(tracing_opentelemetry::layer().with_tracer(tracer).boxed());
+    }
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(layers)
+        .try_init()?;
+
+    // subscriber 初始化成功后只会执行一次
+    let _ = FILTER_HANDLE.set(handle);
+
+    Ok(ObservabilityGuard {
+        _file_guard: file_guard,
+        otlp: options.otlp_endpoint.is_some(),
+    })
+}
+
+// 运行时调整日志过滤规则，如 "info,comfy_quant_node=debug"
+pub fn set_log_filter(directives: &str) -> Result<()> {
+    let filter = EnvFilter::try_new(directives)?;
+
+    FILTER_HANDLE
+        .get()
+        .ok_or_else(|| anyhow!("Observability not initialized"))?
+        .reload(filter)?;
+
+    tracing::info!(filter = directives, "Log filter updated");
+
+    Ok(())
+}
+
+// 当前的日志过滤规则
+pub fn log_filter() -> Option<String> {
+    FILTER_HANDLE
+        .get()?
+        .with_current(|filter| filter.to_string())
+        .ok()
+}
+
+// 持有期间保持文件日志写入线程，释放时刷新并关闭链路上报
+pub struct ObservabilityGuard {
+    _file_guard: Option<WorkerGuard>,
+    otlp: bool,
+}
+
+impl Drop for ObservabilityGuard {
+    fn drop(&mut self) {
+        if self.otlp {
+            global::shutdown_tracer_provider();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_init_and_set_log_filter() -> Result<()> {
+        assert!(set_log_filter("debug").is_err());
+        assert!(log_filter().is_none());
+
+        let options = ObservabilityOptions::builder()
+            .format(LogFormat::Compact)
+            .filter("warn")
+            .build();
+        let _guard = init(&options)?;
+
+        assert_eq!(log_filter(), Some("warn".to_string()));
+
+        set_log_filter("info,comfy_quant_node=debug")?;
+        assert_eq!(
+            log_filter(),
+            Some("comfy_quant_node=debug,info".to_string())
+        );
+
+        assert!(set_log_filter("info,=").is_err());
+
+        // 全局 subscriber 只能初始化一次
+        assert!(init(&options).is_err());
+
+        Ok(())
+    }
+}
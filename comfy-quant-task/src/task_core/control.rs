@@ -0,0 +1,224 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, OnceLock,
+    },
+};
+use tokio::sync::watch;
+use tokio_util::sync::CancellationToken;
+
+static NEXT_TASK_ID: AtomicU64 = AtomicU64::new(1);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskState {
+    Running,   // 执行中
+    Paused,    // 已暂停
+    Cancelled, // 已取消，等待任务退出
+}
+
+// 任务快照，用于查询正在执行的任务
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskInfo {
+    pub id: u64,                   // 任务ID
+    pub task_type: String,         // 任务类型
+    pub description: String,       // 任务描述
+    pub state: TaskState,          // 状态
+    pub processed: u64,            // 已处理数量
+    pub total: u64,                // 总数量，未知时为0
+    pub started_at: DateTime<Utc>, // 开始时间
+}
+
+// 任务控制句柄：协作式取消、暂停/恢复和进度，任务在处理每条数据前调用 proceed 检查
+#[derive(Debug)]
+pub struct TaskControl {
+    id: u64,
+    task_type: String,
+    description: String,
+    token: CancellationToken,
+    paused: watch::Sender<bool>,
+    processed: AtomicU64,
+    total: AtomicU64,
+    started_at: DateTime<Utc>,
+}
+
+impl TaskControl {
+    pub fn new(task_type: impl Into<String>, description: impl Into<String>) -> Self {
+        TaskControl {
+            id: NEXT_TASK_ID.fetch_add(1, Ordering::Relaxed),
+            task_type: task_type.into(),
+            description: description.into(),
+            token: CancellationToken::new(),
+            paused: watch::Sender::new(false),
+            processed: AtomicU64::new(0),
+            total: AtomicU64::new(0),
+            started_at: Utc::now(),
+        }
+    }
+
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn cancel(&self) {
+        self.token.cancel();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    pub fn pause(&self) {
+        self.paused.send_replace(true);
+    }
+
+    pub fn resume(&self) {
+        self.paused.send_replace(false);
+    }
+
+    pub fn set_total(&self, total: u64) {
+        self.total.store(total, Ordering::Relaxed);
+    }
+
+    pub fn advance(&self) {
+        self.processed.fetch_add(1, Ordering::Relaxed);
+    }
+
+    // 暂停时等待恢复，返回false表示任务已取消，应保存进度后退出
+    pub async fn proceed(&self) -> bool {
+        let mut paused = self.paused.subscribe();
+
+        tokio::select! {
+            biased;
+            _ = self.token.cancelled() => false,
+            result = paused.wait_for(|paused| !*paused) => result.is_ok(),
+        }
+    }
+
+    pub fn info(&self) -> TaskInfo {
+        let state = if self.is_cancelled() {
+            TaskState::Cancelled
+        } else if *self.paused.borrow() {
+            TaskState::Paused
+        } else {
+            TaskState::Running
+        };
+
+        TaskInfo {
+            id: self.id,
+            task_type: self.task_type.clone(),
+            description: self.description.clone(),
+            state,
+            processed: self.processed.load(Ordering::Relaxed),
+            total: self.total.load(Ordering::Relaxed),
+            started_at: self.started_at,
+        }
+    }
+}
+
+// 正在执行的任务
+#[derive(Debug, Default)]
+pub struct TaskRegistry {
+    tasks: Mutex<BTreeMap<u64, Arc<TaskControl>>>,
+}
+
+impl TaskRegistry {
+    // 登记任务，返回的守卫释放时移除
+    pub fn register(&self, control: Arc<TaskControl>) -> TaskGuard<'_> {
+        let id = control.id();
+
+        if let Ok(mut tasks) = self.tasks.lock() {
+            tasks.insert(id, control);
+        }
+
+        TaskGuard { registry: self, id }
+    }
+
+    pub fn get(&self, id: u64) -> Option<Arc<TaskControl>> {
+        self.tasks.lock().ok()?.get(&id).cloned()
+    }
+
+    pub fn list(&self) -> Vec<TaskInfo> {
+        self.tasks
+            .lock()
+            .map(|tasks| tasks.values().map(|control| control.info()).collect())
+            .unwrap_or_default()
+    }
+
+    fn remove(&self, id: u64) {
+        if let Ok(mut tasks) = self.tasks.lock() {
+            tasks.remove(&id);
+        }
+    }
+}
+
+pub struct TaskGuard<'a> {
+    registry: &'a TaskRegistry,
+    id: u64,
+}
+
+impl Drop for TaskGuard<'_> {
+    fn drop(&mut self) {
+        self.registry.remove(self.id);
+    }
+}
+
+// 进程内的任务登记表，任务在工作流节点内部启动，通过全局登记表供API管理
+pub fn registry() -> &'static TaskRegistry {
+    static REGISTRY: OnceLock<TaskRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(TaskRegistry::default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_task_control() -> Result<()> {
+        let registry = TaskRegistry::default();
+        let control = Arc::new(TaskControl::new("binance_klines", "BTCUSDT 1s"));
+        let id = control.id();
+
+        {
+            let _guard = registry.register(Arc::clone(&control));
+            control.set_total(10);
+            control.advance();
+
+            let tasks = registry.list();
+            assert_eq!(tasks.len(), 1);
+            assert_eq!((tasks[0].processed, tasks[0].total), (1, 10));
+            assert_eq!(tasks[0].state, TaskState::Running);
+            assert!(control.proceed().await);
+
+            // 暂停时等待恢复
+            control.pause();
+            assert_eq!(registry.list()[0].state, TaskState::Paused);
+
+            let waiting = tokio::spawn({
+                let control = Arc::clone(&control);
+                async move { control.proceed().await }
+            });
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            assert!(!waiting.is_finished());
+
+            control.resume();
+            assert!(waiting.await?);
+
+            // 取消后暂停的任务也会退出
+            control.pause();
+            control.cancel();
+            assert!(!control.proceed().await);
+            assert_eq!(registry.list()[0].state, TaskState::Cancelled);
+        }
+
+        assert!(registry.list().is_empty());
+        assert!(registry.get(id).is_none());
+
+        Ok(())
+    }
+}
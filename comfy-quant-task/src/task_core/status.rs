@@ -4,4 +4,5 @@ pub enum TaskStatus<T> {
     Running(T),
     Finished,
     Failed(String),
+    Cancelled,
 }
@@ -1,4 +1,6 @@
+use super::control::TaskControl;
 use anyhow::Result;
+use std::sync::Arc;
 
 #[allow(async_fn_in_trait)]
 pub trait Executable {
@@ -10,4 +12,7 @@ pub trait Executable {
 
     // 执行任务
     async fn execute(&self) -> Result<Self::Output>;
+
+    // 任务控制句柄，用于取消、暂停/恢复和查询进度
+    fn control(&self) -> &Arc<TaskControl>;
 }
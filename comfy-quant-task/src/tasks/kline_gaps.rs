@@ -0,0 +1,163 @@
+use super::klines::KlinesTask;
+use crate::task_core::{
+    control::{self, TaskControl},
+    status::TaskStatus,
+    traits::Executable,
+};
+use anyhow::Result;
+use async_stream::stream;
+use bon::bon;
+use comfy_quant_base::{secs_to_datetime, KlineInterval, Market, Symbol};
+use comfy_quant_database::kline::{self, KlineGap};
+use comfy_quant_exchange::market_data::MarketDataConnector;
+use futures::{stream::BoxStream, StreamExt};
+use sqlx::PgPool;
+use std::{marker::PhantomData, sync::Arc};
+
+// K线缺失检测任务，查找时间范围内缺失的K线区间，开启修复时只重新下载缺失的区间
+pub struct KlineGapsTask<C> {
+    db: Arc<PgPool>,
+    market: Market,
+    symbol: Symbol,
+    interval: KlineInterval,
+    start_timestamp: i64,
+    end_timestamp: i64,
+    heal: bool, // 是否重新下载缺失的区间
+    control: Arc<TaskControl>,
+    _connector: PhantomData<C>,
+}
+
+#[bon]
+impl<C: MarketDataConnector + Default> KlineGapsTask<C> {
+    #[builder]
+    pub fn new(
+        db: Arc<PgPool>,
+        market: Market,
+        symbol: Symbol,
+        interval: KlineInterval,
+        start_timestamp: i64,
+        end_timestamp: i64,
+        #[builder(default)] heal: bool,
+    ) -> Result<Self> {
+        C::default().check_interval(&interval)?;
+
+        let control = Arc::new(TaskControl::new(
+            format!("{}_kline_gaps", C::default().exchange()),
+            format!(
+                "{} {} {} {}-{}",
+                market, symbol, interval, start_timestamp, end_timestamp
+            ),
+        ));
+
+        Ok(KlineGapsTask {
+            db,
+            market,
+            symbol,
+            interval,
+            start_timestamp,
+            end_timestamp,
+            heal,
+            control,
+            _connector: PhantomData,
+        })
+    }
+
+    // 缺失的K线区间
+    pub async fn gaps(&self) -> Result<Vec<KlineGap>> {
+        kline::find_gaps(
+            &self.db,
+            &C::default().exchange(),
+            &self.market,
+            &self.symbol,
+            &self.interval,
+            &secs_to_datetime(self.start_timestamp)?,
+            &secs_to_datetime(self.end_timestamp)?,
+        )
+        .await
+    }
+}
+
+impl<C: MarketDataConnector + Default> Executable for KlineGapsTask<C> {
+    // 每个缺失区间输出一次，开启修复时在该区间下载完成后输出
+    type Output = BoxStream<'static, Result<TaskStatus<KlineGap>>>;
+
+    async fn check_data_complete(&self) -> Result<bool> {
+        Ok(self.gaps().await?.is_empty())
+    }
+
+    async fn execute(&self) -> Result<Self::Output> {
+        let gaps = self.gaps().await?;
+        let db = Arc::clone(&self.db);
+        let market = self.market.clone();
+        let symbol = self.symbol.clone();
+        let interval = self.interval.clone();
+        let heal = self.heal;
+        let control = Arc::clone(&self.control);
+
+        control.set_total(gaps.len() as u64);
+
+        let stream = stream! {
+            let _guard = control::registry().register(Arc::clone(&control));
+
+            yield Ok(TaskStatus::Initializing);
+
+            for gap in gaps {
+                if !control.proceed().await {
+                    yield Ok(TaskStatus::Cancelled);
+                    return;
+                }
+
+                if heal {
+                    tracing::info!(
+                        %symbol,
+                        %interval,
+                        "Heal kline gap {} - {}",
+                        gap.start_time,
+                        gap.end_time
+                    );
+
+                    let task = KlinesTask::<C>::builder()
+                        .db(Arc::clone(&db))
+                        .market(market.clone())
+                        .symbol(symbol.clone())
+                        .interval(interval.clone())
+                        .start_timestamp(gap.start_time.timestamp())
+                        .end_timestamp(gap.end_time.timestamp())
+                        .build()?;
+                    let mut statuses = task.execute().await?;
+
+                    while let Some(status) = statuses.next().await {
+                        // 取消时转交给正在执行的下载任务，由它保存进度后退出
+                        if control.is_cancelled() {
+                            task.control().cancel();
+                        }
+
+                        match status? {
+                            TaskStatus::Cancelled => {
+                                yield Ok(TaskStatus::Cancelled);
+                                return;
+                            }
+                            TaskStatus::Failed(e) => {
+                                yield Ok(TaskStatus::Failed(e));
+                                return;
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+
+                control.advance();
+
+                yield Ok(TaskStatus::Running(gap));
+            }
+
+            yield Ok(TaskStatus::Finished);
+        };
+
+        Ok(Box::pin(stream))
+    }
+
+    fn control(&self) -> &Arc<TaskControl> {
+        &self.control
+    }
+}
@@ -0,0 +1,409 @@
+use crate::task_core::{
+    control::{self, TaskControl},
+    status::TaskStatus,
+    traits::Executable,
+};
+use anyhow::Result;
+use async_stream::stream;
+use bon::{bon, Builder};
+use chrono::{DateTime, Utc};
+use comfy_quant_base::{
+    millis_to_datetime, secs_to_datetime, Exchange, KlineInterval, Market, Symbol,
+};
+use comfy_quant_database::{
+    kline::{self, Kline, KlineSource, ReconciliationReport, UpsertKlineParams},
+    kline_sync_state::{self, SaveKlineSyncStateParams},
+    kline_task::{self, CreateKlineTaskParams, KlineTask, KlineTaskStatus},
+};
+use comfy_quant_exchange::{
+    kline_stream::calc_time_range_kline_count, market_data::MarketDataConnector,
+};
+use futures::{stream::BoxStream, StreamExt};
+use sqlx::PgPool;
+use std::{sync::Arc, time::Duration};
+
+// 每批写入的K线数量上限，每批写入后保存一次任务进度
+const WRITE_BATCH_SIZE: usize = 1000;
+// 下载失败后的最大重试次数，成功写入一批K线后重新计数
+const MAX_RETRIES: u32 = 6;
+// 重试的初始等待时间，每次重试翻倍
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(1);
+
+#[derive(Builder, Clone, Debug)]
+#[builder(on(_, into))]
+struct TaskParams {
+    market: Market,          // 市场
+    symbol: Symbol,          // 交易对
+    interval: KlineInterval, // 时间间隔
+    start_timestamp: i64,    // 开始时间
+    end_timestamp: i64,      // 结束时间
+}
+
+// K线同步任务，按交易所的行情接入下载K线并保存到 klines 表
+pub struct KlinesTask<C> {
+    db: Arc<PgPool>,
+    connector: Arc<C>,
+    params: TaskParams,
+    control: Arc<TaskControl>,
+}
+
+#[bon]
+impl<C: MarketDataConnector + Default> KlinesTask<C> {
+    #[builder]
+    pub fn new(
+        db: Arc<PgPool>,
+        #[builder(default)] connector: C,
+        market: Market,
+        symbol: Symbol,
+        interval: KlineInterval,
+        start_timestamp: i64,
+        end_timestamp: i64,
+    ) -> Result<Self> {
+        connector.check_interval(&interval)?;
+
+        let params = TaskParams::builder()
+            .market(market)
+            .symbol(symbol)
+            .interval(interval)
+            .start_timestamp(start_timestamp)
+            .end_timestamp(end_timestamp)
+            .build();
+        let control = Arc::new(TaskControl::new(
+            format!("{}_klines", connector.exchange()),
+            format!(
+                "{} {} {} {}-{}",
+                params.market, params.symbol, params.interval, start_timestamp, end_timestamp
+            ),
+        ));
+
+        Ok(KlinesTask {
+            db,
+            connector: Arc::new(connector),
+            params,
+            control,
+        })
+    }
+
+    // 获取该交易所未完成的任务，用于进程重启后继续下载
+    pub async fn unfinished(db: Arc<PgPool>) -> Result<Vec<Self>> {
+        let exchange = C::default().exchange();
+
+        let tasks = kline_task::list_unfinished(&db)
+            .await?
+            .into_iter()
+            .filter(|task| task.exchange == exchange)
+            .map(|task| {
+                KlinesTask::builder()
+                    .db(Arc::clone(&db))
+                    .market(task.market)
+                    .symbol(task.symbol)
+                    .interval(task.interval)
+                    .start_timestamp(task.start_time.timestamp())
+                    .end_timestamp(task.end_time.timestamp())
+                    .build()
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(tasks)
+    }
+
+    // 在后台继续执行该交易所未完成的任务，返回恢复的任务数量
+    pub async fn resume_unfinished(db: Arc<PgPool>) -> Result<usize> {
+        let mut resumed = 0;
+
+        for task in Self::unfinished(db).await? {
+            let symbol = task.params.symbol.clone();
+            let mut statuses = match task.execute().await {
+                Ok(statuses) => statuses,
+                Err(e) => {
+                    tracing::error!(%symbol, "Resume klines task failed: {}", e);
+                    continue;
+                }
+            };
+
+            tokio::spawn(async move {
+                while let Some(status) = statuses.next().await {
+                    match status {
+                        Ok(TaskStatus::Failed(e)) => {
+                            tracing::error!(%symbol, "Resumed klines task failed: {}", e)
+                        }
+                        Err(e) => tracing::error!(%symbol, "Resumed klines task failed: {}", e),
+                        _ => {}
+                    }
+                }
+            });
+
+            resumed += 1;
+        }
+
+        Ok(resumed)
+    }
+}
+
+impl<C: MarketDataConnector> KlinesTask<C> {
+    fn task_params(&self) -> Result<CreateKlineTaskParams> {
+        let params = CreateKlineTaskParams::builder()
+            .exchange(self.connector.exchange())
+            .market(self.params.market.clone())
+            .symbol(self.params.symbol.clone())
+            .interval(self.params.interval.clone())
+            .start_time(secs_to_datetime(self.params.start_timestamp)?)
+            .end_time(secs_to_datetime(self.params.end_timestamp)?)
+            .build();
+
+        Ok(params)
+    }
+}
+
+impl TaskParams {
+    // 同步状态覆盖开始时间时从同步状态续传，否则从任务进度续传
+    async fn resume_from(
+        &self,
+        db: &PgPool,
+        exchange: &Exchange,
+        task: &KlineTask,
+    ) -> Result<DateTime<Utc>> {
+        let resume_from = task.resume_from();
+
+        if task.status == KlineTaskStatus::Finished {
+            return Ok(resume_from);
+        }
+
+        let sync_resume_from =
+            kline_sync_state::get(db, exchange, &self.market, &self.symbol, &self.interval)
+                .await?
+                .and_then(|state| state.resume_from(&task.start_time))
+                .map(|time| time.min(task.end_time));
+
+        Ok(sync_resume_from.map_or(resume_from, |time| time.max(resume_from)))
+    }
+
+    // 保存任务进度和同步状态，中断后从该K线继续下载
+    async fn save_progress(
+        &self,
+        db: &PgPool,
+        exchange: &Exchange,
+        task: &KlineTask,
+        last_open_time: &DateTime<Utc>,
+    ) -> Result<()> {
+        kline_task::update_progress(db, task.id, last_open_time).await?;
+
+        let data = SaveKlineSyncStateParams::builder()
+            .exchange(exchange.clone())
+            .market(self.market.clone())
+            .symbol(self.symbol.clone())
+            .interval(self.interval.clone())
+            .start_time(task.start_time)
+            .last_open_time(*last_open_time)
+            .build();
+        kline_sync_state::save(db, data).await?;
+
+        Ok(())
+    }
+}
+
+// 指数退避，第n次重试等待 1s * 2^(n-1)
+fn retry_delay(retries: u32) -> Duration {
+    RETRY_BASE_DELAY * 2_u32.pow(retries.saturating_sub(1))
+}
+
+impl<C: MarketDataConnector> Executable for KlinesTask<C> {
+    type Output = BoxStream<'static, Result<TaskStatus<Kline>>>;
+
+    async fn check_data_complete(&self) -> Result<bool> {
+        let start_datetime = secs_to_datetime(self.params.start_timestamp)?;
+        let end_datetime = secs_to_datetime(self.params.end_timestamp)?;
+
+        let store_kline_count = kline::time_range_klines_count(
+            &self.db,
+            &self.connector.exchange(),
+            &self.params.market,
+            &self.params.symbol,
+            &self.params.interval,
+            &start_datetime,
+            &end_datetime,
+        )
+        .await?;
+
+        let kline_count_expect = calc_time_range_kline_count(
+            self.params.interval.as_ref(),
+            self.params.start_timestamp,
+            self.params.end_timestamp,
+        );
+
+        Ok(store_kline_count == kline_count_expect)
+    }
+
+    async fn execute(&self) -> Result<Self::Output> {
+        let is_data_complete = self.check_data_complete().await?;
+        let params = self.params.clone();
+        let db = Arc::clone(&self.db);
+        let connector = Arc::clone(&self.connector);
+        let exchange = connector.exchange();
+
+        // 记录任务，如果上次下载中断，则从中断处继续
+        let task = kline_task::create_or_get(&db, self.task_params()?).await?;
+        let mut resume_timestamp = params.resume_from(&db, &exchange, &task).await?.timestamp();
+        let control = Arc::clone(&self.control);
+
+        let stream = stream! {
+            // 执行期间登记到任务列表，流结束或被丢弃时移除
+            let _guard = control::registry().register(Arc::clone(&control));
+
+            yield Ok(TaskStatus::Initializing);
+
+            if is_data_complete {
+                kline_task::update_status(&db, task.id, &KlineTaskStatus::Finished).await?;
+
+                yield Ok(TaskStatus::Finished);
+                return;
+            } else {
+                kline_task::update_status(&db, task.id, &KlineTaskStatus::Running).await?;
+                kline::ensure_partitions(
+                    &db,
+                    &secs_to_datetime(resume_timestamp)?,
+                    &secs_to_datetime(params.end_timestamp)?,
+                )
+                .await?;
+
+                let mut last_open_time = None;
+                let mut report = ReconciliationReport::default();
+                let mut retries = 0;
+
+                control.set_total(calc_time_range_kline_count(
+                    params.interval.as_ref(),
+                    resume_timestamp,
+                    params.end_timestamp,
+                ) as u64);
+
+                'fetch: loop {
+                    // 已下载的K线按批写入，每批保存一次进度
+                    let mut klines_stream = connector
+                        .fetch_klines(
+                            &params.market,
+                            &params.symbol,
+                            &params.interval,
+                            resume_timestamp,
+                            params.end_timestamp,
+                        )
+                        .ready_chunks(WRITE_BATCH_SIZE);
+
+                    while let Some(market_klines) = klines_stream.next().await {
+                        // 取消时保存进度，下次从中断处继续
+                        if !control.proceed().await {
+                            if let Some(open_time) = &last_open_time {
+                                params.save_progress(&db, &exchange, &task, open_time).await?;
+                            }
+
+                            tracing::info!(%exchange, symbol = %params.symbol, "Klines task cancelled");
+
+                            yield Ok(TaskStatus::Cancelled);
+                            return;
+                        }
+
+                        let mut batch = Vec::with_capacity(market_klines.len());
+                        let mut error = None;
+
+                        for market_kline in market_klines {
+                            let market_kline = match market_kline {
+                                Ok(market_kline) => market_kline,
+                                Err(e) => {
+                                    error = Some(e);
+                                    break;
+                                }
+                            };
+
+                            // 回填到当前时间时最后一根K线可能尚未收盘
+                            let is_closed = market_kline.close_time < Utc::now().timestamp_millis();
+
+                            let data = UpsertKlineParams::builder()
+                                .exchange(exchange.clone())
+                                .market(params.market.clone())
+                                .symbol(params.symbol.clone())
+                                .interval(params.interval.clone())
+                                .open_time(millis_to_datetime(market_kline.open_time)?)
+                                .open_price(market_kline.open)
+                                .high_price(market_kline.high)
+                                .low_price(market_kline.low)
+                                .close_price(market_kline.close)
+                                .volume(market_kline.volume)
+                                .taker_buy_volume(market_kline.taker_buy_volume)
+                                .source(KlineSource::Backfill)
+                                .is_closed(is_closed)
+                                .build();
+
+                            batch.push(data);
+                        }
+
+                        if !batch.is_empty() {
+                            retries = 0;
+
+                            for (outcome, kline) in kline::upsert_many(&db, &batch).await? {
+                                report.record(outcome);
+                                control.advance();
+                                last_open_time = Some(kline.open_time);
+
+                                yield Ok(TaskStatus::Running(kline));
+                            }
+
+                            if let Some(open_time) = &last_open_time {
+                                params.save_progress(&db, &exchange, &task, open_time).await?;
+                            }
+                        }
+
+                        let Some(e) = error else {
+                            continue;
+                        };
+
+                        // 网络错误、限流等下载失败，退避重试，从最后一根K线继续
+                        if retries < MAX_RETRIES {
+                            retries += 1;
+                            let delay = retry_delay(retries);
+
+                            tracing::warn!(
+                                %exchange,
+                                symbol = %params.symbol,
+                                "Fetch klines failed, retry {}/{} in {:?}: {}",
+                                retries,
+                                MAX_RETRIES,
+                                delay,
+                                e
+                            );
+
+                            if let Some(open_time) = &last_open_time {
+                                resume_timestamp = open_time.timestamp();
+                            }
+
+                            tokio::time::sleep(delay).await;
+                            continue 'fetch;
+                        }
+
+                        yield Err(e);
+                        return;
+                    }
+
+                    break;
+                }
+
+                tracing::info!(
+                    monotonic_counter.kline_backfill_rejected = report.rejected,
+                    %exchange,
+                    symbol = %params.symbol,
+                    interval = %params.interval,
+                    "Kline backfill reconciliation: {}",
+                    report
+                );
+
+                kline_task::update_status(&db, task.id, &KlineTaskStatus::Finished).await?;
+            }
+
+            yield Ok(TaskStatus::Finished);
+        };
+
+        Ok(Box::pin(stream))
+    }
+
+    fn control(&self) -> &Arc<TaskControl> {
+        &self.control
+    }
+}
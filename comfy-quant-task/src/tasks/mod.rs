@@ -1 +1,5 @@
 pub mod binance_klines;
+pub mod bybit_klines;
+pub mod kline_gaps;
+pub mod klines;
+pub mod okx_klines;
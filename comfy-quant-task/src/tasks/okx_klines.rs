@@ -0,0 +1,4 @@
+use super::klines::KlinesTask;
+use comfy_quant_exchange::market_data::OkxConnector;
+
+pub type OkxKlinesTask = KlinesTask<OkxConnector>;
@@ -0,0 +1,81 @@
+use crate::WorkflowHandle;
+use anyhow::Result;
+use async_lock::RwLock;
+use bon::bon;
+use comfy_quant_node::{node_core::ExchangeRateManager, workflow::Workflow};
+use sqlx::{postgres::PgPoolOptions, PgPool};
+use std::{fs, path::Path, sync::Arc};
+
+// 嵌入式引擎，封装数据库、汇率和工作流的加载与执行，
+// 其他 Rust 应用可直接在进程内运行回测或实盘，无需启动 HTTP 服务
+#[derive(Debug, Clone)]
+pub struct Engine {
+    db: Arc<PgPool>,                                         // 数据库
+    exchange_rate_manager: Arc<RwLock<ExchangeRateManager>>, // 汇率管理器，所有工作流共享
+    quote_asset: String,                                     // 默认计价资产
+}
+
+#[bon]
+impl Engine {
+    // 连接数据库并创建引擎
+    #[builder(on(String, into), finish_fn = connect)]
+    pub async fn new(
+        database_url: String,                                         // 数据库连接地址
+        #[builder(default = 20)] max_connections: u32,                // 最大连接数
+        #[builder(default = "USDT".to_string())] quote_asset: String, // 默认计价资产
+        #[builder(default = true)] migrate: bool,                     // 是否执行数据库迁移
+    ) -> Result<Self> {
+        let db = PgPoolOptions::new()
+            .max_connections(max_connections)
+            .connect(&database_url)
+            .await?;
+
+        if migrate {
+            comfy_quant_database::MIGRATOR.run(&db).await?;
+        }
+
+        Ok(Engine::with_pool(db, quote_asset))
+    }
+
+    // 使用已有的数据库连接池创建引擎
+    pub fn with_pool(db: PgPool, quote_asset: impl Into<String>) -> Self {
+        Engine {
+            db: Arc::new(db),
+            exchange_rate_manager: Arc::new(RwLock::new(ExchangeRateManager::default())),
+            quote_asset: quote_asset.into(),
+        }
+    }
+
+    pub fn db(&self) -> &PgPool {
+        &self.db
+    }
+
+    pub fn quote_asset(&self) -> &str {
+        &self.quote_asset
+    }
+
+    // 从 JSON 加载工作流，完成节点初始化和连接，返回的工作流尚未开始执行
+    pub async fn load(&self, json_str: &str) -> Result<WorkflowHandle> {
+        let workflow: Workflow = serde_json::from_str(json_str)?;
+        self.load_workflow(workflow).await
+    }
+
+    // 从文件加载工作流
+    pub async fn load_file(&self, path: impl AsRef<Path>) -> Result<WorkflowHandle> {
+        let json_str = fs::read_to_string(path)?;
+        self.load(&json_str).await
+    }
+
+    // 加载已反序列化的工作流，可在加载前修改(如设置回测时间范围)
+    pub async fn load_workflow(&self, mut workflow: Workflow) -> Result<WorkflowHandle> {
+        workflow
+            .setup(
+                Arc::clone(&self.db),
+                Arc::clone(&self.exchange_rate_manager),
+                self.quote_asset.as_str(),
+            )
+            .await?;
+
+        Ok(WorkflowHandle::new(workflow))
+    }
+}
@@ -0,0 +1,11 @@
+// comfy-quant: 嵌入式引擎接口
+mod engine;
+mod workflow_handle;
+
+pub use engine::{Engine, EngineBuilder};
+pub use workflow_handle::{WorkflowHandle, WorkflowStats};
+
+pub use comfy_quant_node::{
+    stats::{AssertionResult, ExecutionReport, ResourceUsage, StatsAggregate, StatsAggregator},
+    workflow::Workflow,
+};
@@ -0,0 +1,127 @@
+use anyhow::Result;
+use comfy_quant_node::{
+    node_core::{NodeExecutable, TradeStats, TradeStatsExt},
+    stats::{AssertionResult, ExecutionReport, PerformanceReport, ResourceUsage},
+    workflow::Workflow,
+};
+use rust_decimal::Decimal;
+use serde::Serialize;
+
+// 工作流统计
+#[derive(Serialize, Debug, Clone)]
+pub struct WorkflowStats {
+    pub initial_capital: Decimal,         // 初始资金
+    pub realized_pnl: Decimal,            // 已实现盈亏
+    pub unrealized_pnl: Decimal,          // 未实现盈亏
+    pub total_pnl: Decimal,               // 总盈亏
+    pub total_return: Decimal,            // 总收益率
+    pub annualized_return: Decimal,       // 年化收益率
+    pub time_weighted_return: Decimal,    // 时间加权收益率
+    pub money_weighted_return: Decimal,   // 资金加权收益率(年化)
+    pub performance: PerformanceReport,   // 绩效报告
+    pub running_time: u128,               // 运行持续时间(微妙)
+    pub resource_usage: ResourceUsage,    // 资源消耗
+    pub execution: Vec<ExecutionReport>,  // 各策略交易对的执行质量
+    pub assertions: Vec<AssertionResult>, // 断言节点的检查结果
+}
+
+// 已加载的工作流，释放时停止所有节点
+#[derive(Debug)]
+pub struct WorkflowHandle {
+    workflow: Workflow,
+}
+
+impl WorkflowHandle {
+    pub(crate) fn new(workflow: Workflow) -> Self {
+        WorkflowHandle { workflow }
+    }
+
+    // 启动所有节点，立即返回
+    pub async fn start(&mut self) -> Result<()> {
+        Ok(self.workflow.execute().await?)
+    }
+
+    // 等待所有节点执行结束(如回测数据回放完毕)
+    pub async fn wait(&mut self) -> Result<()> {
+        self.workflow.wait().await
+    }
+
+    // 执行到结束并返回统计，用于回测
+    pub async fn run(&mut self) -> Result<WorkflowStats> {
+        self.start().await?;
+        self.wait().await?;
+        self.stats().await
+    }
+
+    // 停止所有节点
+    pub fn stop(&self) {
+        self.workflow.stop();
+    }
+
+    // 停止所有节点并等待退出，保存运行时数据，cancel_orders 为 true 时撤销策略的挂单
+    pub async fn shutdown(&mut self, cancel_orders: bool) -> Result<()> {
+        self.workflow.shutdown(cancel_orders).await
+    }
+
+    // 当前统计，运行中也可调用
+    pub async fn stats(&self) -> Result<WorkflowStats> {
+        let workflow = &self.workflow;
+
+        Ok(WorkflowStats {
+            initial_capital: workflow.initial_capital().await?,
+            realized_pnl: workflow.realized_pnl().await?,
+            unrealized_pnl: workflow.unrealized_pnl().await?,
+            total_pnl: workflow.total_pnl().await?,
+            total_return: workflow.total_return().await?,
+            annualized_return: workflow.annualized_return().await?,
+            time_weighted_return: workflow.time_weighted_return().await?,
+            money_weighted_return: workflow.money_weighted_return().await?,
+            performance: workflow.performance_report().await?,
+            running_time: workflow.running_time().await?,
+            resource_usage: workflow.resource_usage()?,
+            execution: workflow.execution_reports().await,
+            assertions: workflow.assertions().await?,
+        })
+    }
+
+    // 序列化工作流，包含节点运行时状态
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string(&self.workflow)?)
+    }
+
+    // 底层工作流，用于访问未封装的功能
+    pub fn workflow(&self) -> &Workflow {
+        &self.workflow
+    }
+
+    pub fn into_inner(self) -> Workflow {
+        self.workflow
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Engine;
+    use anyhow::Result;
+    use rust_decimal::Decimal;
+    use sqlx::PgPool;
+
+    #[sqlx::test(migrator = "comfy_quant_database::MIGRATOR")]
+    async fn test_engine_run_workflow(db: PgPool) -> Result<()> {
+        let engine = Engine::with_pool(db, "USDT");
+
+        assert!(engine.load("{}").await.is_err());
+
+        let json_str = r#"{"last_node_id":0,"last_link_id":0,"nodes":[],"links":[],"groups":[],"config":{},"extra":{},"version":0.4}"#;
+        let mut handle = engine.load(json_str).await?;
+        let stats = handle.run().await?;
+
+        assert_eq!(stats.initial_capital, Decimal::ZERO);
+        assert_eq!(stats.total_return, Decimal::ZERO);
+        assert!(stats.assertions.is_empty());
+        assert_eq!(stats.resource_usage.db_rows_read, 0);
+        assert!(handle.to_json()?.contains("\"quote_asset\":\"USDT\""));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS kline_tasks;
+DROP INDEX IF EXISTS idx_kline_tasks_unique;
@@ -0,0 +1,34 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS kline_tasks (
+    id SERIAL PRIMARY KEY,
+    exchange VARCHAR(20) NOT NULL,
+    market VARCHAR(20) NOT NULL,
+    symbol VARCHAR(20) NOT NULL,
+    interval VARCHAR(10) NOT NULL,
+    start_time TIMESTAMPTZ NOT NULL,
+    end_time TIMESTAMPTZ NOT NULL,
+    last_open_time TIMESTAMPTZ,
+    status VARCHAR(20) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- 创建索引（缩短索引名称）
+CREATE UNIQUE INDEX IF NOT EXISTS idx_kline_tasks_unique
+ON kline_tasks (exchange, market, symbol, interval, start_time, end_time);
+
+-- 添加表注释
+COMMENT ON TABLE kline_tasks IS 'K线下载任务';
+
+-- 添加字段注释
+COMMENT ON COLUMN kline_tasks.id IS 'ID';
+COMMENT ON COLUMN kline_tasks.exchange IS '交易所';
+COMMENT ON COLUMN kline_tasks.market IS '市场';
+COMMENT ON COLUMN kline_tasks.symbol IS '交易对';
+COMMENT ON COLUMN kline_tasks.interval IS '时间间隔';
+COMMENT ON COLUMN kline_tasks.start_time IS '开始时间';
+COMMENT ON COLUMN kline_tasks.end_time IS '结束时间';
+COMMENT ON COLUMN kline_tasks.last_open_time IS '最后一根已下载K线的开盘时间';
+COMMENT ON COLUMN kline_tasks.status IS '状态';
+COMMENT ON COLUMN kline_tasks.created_at IS '创建时间';
+COMMENT ON COLUMN kline_tasks.updated_at IS '更新时间';
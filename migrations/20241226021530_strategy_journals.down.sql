@@ -0,0 +1,4 @@
+-- Add down migration script here
+-- 策略交易日志
+DROP TABLE IF EXISTS strategy_journals;
+DROP INDEX IF EXISTS idx_strategy_journals_lookup;
@@ -0,0 +1,33 @@
+-- Add up migration script here
+-- 策略交易日志
+CREATE TABLE IF NOT EXISTS strategy_journals (
+    id SERIAL PRIMARY KEY,
+    workflow_id VARCHAR(21) NOT NULL,
+    node_id SMALLINT NOT NULL,
+    order_id VARCHAR(64),
+    start_time TIMESTAMP WITH TIME ZONE,
+    end_time TIMESTAMP WITH TIME ZONE,
+    content TEXT NOT NULL,
+    tags TEXT[] NOT NULL DEFAULT '{}',
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+-- 创建索引
+CREATE INDEX IF NOT EXISTS idx_strategy_journals_lookup
+ON strategy_journals (workflow_id, node_id);
+
+-- 添加表注释
+COMMENT ON TABLE strategy_journals IS '策略交易日志';
+
+-- 添加字段注释
+COMMENT ON COLUMN strategy_journals.id IS 'ID';
+COMMENT ON COLUMN strategy_journals.workflow_id IS '工作流ID';
+COMMENT ON COLUMN strategy_journals.node_id IS '策略节点ID';
+COMMENT ON COLUMN strategy_journals.order_id IS '关联的订单ID';
+COMMENT ON COLUMN strategy_journals.start_time IS '关联时间段的开始时间';
+COMMENT ON COLUMN strategy_journals.end_time IS '关联时间段的结束时间';
+COMMENT ON COLUMN strategy_journals.content IS '日志内容';
+COMMENT ON COLUMN strategy_journals.tags IS '标签';
+COMMENT ON COLUMN strategy_journals.created_at IS '创建时间';
+COMMENT ON COLUMN strategy_journals.updated_at IS '更新时间';
@@ -0,0 +1,3 @@
+-- Add down migration script here
+-- K线主动买入成交量
+ALTER TABLE klines DROP COLUMN IF EXISTS taker_buy_volume;
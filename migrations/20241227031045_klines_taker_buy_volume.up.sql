@@ -0,0 +1,6 @@
+-- Add up migration script here
+-- K线主动买入成交量，旧数据默认为0
+ALTER TABLE klines ADD COLUMN IF NOT EXISTS taker_buy_volume NUMERIC(30,8) NOT NULL DEFAULT 0;
+
+-- 添加字段注释
+COMMENT ON COLUMN klines.taker_buy_volume IS '主动买入成交量';
@@ -0,0 +1,5 @@
+-- Add down migration script here
+-- 交易对更名记录
+DROP TABLE IF EXISTS symbol_aliases;
+DROP INDEX IF EXISTS idx_symbol_aliases_unique;
+DROP INDEX IF EXISTS idx_symbol_aliases_new_symbol;
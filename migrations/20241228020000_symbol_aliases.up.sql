@@ -0,0 +1,31 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS symbol_aliases (
+    id SERIAL PRIMARY KEY,
+    exchange VARCHAR(20) NOT NULL,
+    market VARCHAR(20) NOT NULL,
+    old_symbol VARCHAR(20) NOT NULL,
+    new_symbol VARCHAR(20) NOT NULL,
+    renamed_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- 创建索引（缩短索引名称）
+CREATE UNIQUE INDEX IF NOT EXISTS idx_symbol_aliases_unique
+ON symbol_aliases (exchange, market, old_symbol);
+
+CREATE INDEX IF NOT EXISTS idx_symbol_aliases_new_symbol
+ON symbol_aliases (exchange, market, new_symbol);
+
+-- 添加表注释
+COMMENT ON TABLE symbol_aliases IS '交易对更名记录';
+
+-- 添加字段注释
+COMMENT ON COLUMN symbol_aliases.id IS 'ID';
+COMMENT ON COLUMN symbol_aliases.exchange IS '交易所';
+COMMENT ON COLUMN symbol_aliases.market IS '市场';
+COMMENT ON COLUMN symbol_aliases.old_symbol IS '旧交易对';
+COMMENT ON COLUMN symbol_aliases.new_symbol IS '新交易对';
+COMMENT ON COLUMN symbol_aliases.renamed_at IS '更名时间';
+COMMENT ON COLUMN symbol_aliases.created_at IS '创建时间';
+COMMENT ON COLUMN symbol_aliases.updated_at IS '更新时间';
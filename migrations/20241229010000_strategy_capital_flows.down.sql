@@ -0,0 +1,4 @@
+-- Add down migration script here
+-- 策略资金流水
+DROP TABLE IF EXISTS strategy_capital_flows;
+DROP INDEX IF EXISTS idx_strategy_capital_flows_lookup;
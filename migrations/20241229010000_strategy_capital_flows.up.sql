@@ -0,0 +1,35 @@
+-- Add up migration script here
+-- 策略资金流水
+CREATE TABLE IF NOT EXISTS strategy_capital_flows (
+    id SERIAL PRIMARY KEY,
+    workflow_id VARCHAR(21) NOT NULL,
+    node_id SMALLINT NOT NULL,
+    node_name VARCHAR(20) NOT NULL,
+    exchange VARCHAR(20) NOT NULL,
+    symbol VARCHAR(20) NOT NULL,
+    asset VARCHAR(20) NOT NULL,
+    kind VARCHAR(20) NOT NULL,
+    amount NUMERIC NOT NULL,
+    occurred_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+-- 创建索引（缩短索引名称）
+CREATE INDEX IF NOT EXISTS idx_strategy_capital_flows_lookup
+ON strategy_capital_flows (workflow_id, node_id, exchange, symbol, occurred_at);
+
+-- 添加表注释
+COMMENT ON TABLE strategy_capital_flows IS '策略资金流水';
+
+-- 添加字段注释
+COMMENT ON COLUMN strategy_capital_flows.id IS 'ID';
+COMMENT ON COLUMN strategy_capital_flows.workflow_id IS '工作流ID';
+COMMENT ON COLUMN strategy_capital_flows.node_id IS '策略节点ID';
+COMMENT ON COLUMN strategy_capital_flows.node_name IS '策略节点名称';
+COMMENT ON COLUMN strategy_capital_flows.exchange IS '交易所';
+COMMENT ON COLUMN strategy_capital_flows.symbol IS '交易对';
+COMMENT ON COLUMN strategy_capital_flows.asset IS '计价资产';
+COMMENT ON COLUMN strategy_capital_flows.kind IS '变动类型: allocation/deposit/withdrawal/fee/pnl';
+COMMENT ON COLUMN strategy_capital_flows.amount IS '变动金额，流入为正，流出为负';
+COMMENT ON COLUMN strategy_capital_flows.occurred_at IS '发生时间';
+COMMENT ON COLUMN strategy_capital_flows.created_at IS '创建时间';
@@ -0,0 +1,5 @@
+-- Add down migration script here
+-- 现货交易对下单规则
+ALTER TABLE spot_pairs DROP COLUMN IF EXISTS tick_size;
+ALTER TABLE spot_pairs DROP COLUMN IF EXISTS step_size;
+ALTER TABLE spot_pairs DROP COLUMN IF EXISTS min_notional;
@@ -0,0 +1,10 @@
+-- Add up migration script here
+-- 现货交易对下单规则
+ALTER TABLE spot_pairs ADD COLUMN IF NOT EXISTS tick_size NUMERIC;
+ALTER TABLE spot_pairs ADD COLUMN IF NOT EXISTS step_size NUMERIC;
+ALTER TABLE spot_pairs ADD COLUMN IF NOT EXISTS min_notional NUMERIC;
+
+-- 添加字段注释
+COMMENT ON COLUMN spot_pairs.tick_size IS '价格步长';
+COMMENT ON COLUMN spot_pairs.step_size IS '数量步长';
+COMMENT ON COLUMN spot_pairs.min_notional IS '最小名义价值';
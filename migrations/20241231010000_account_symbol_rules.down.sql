@@ -0,0 +1,4 @@
+-- Add down migration script here
+-- 账户交易对黑白名单
+DROP TABLE IF EXISTS account_symbol_rules;
+DROP INDEX IF EXISTS idx_account_symbol_rules_unique;
@@ -0,0 +1,28 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS account_symbol_rules (
+    id SERIAL PRIMARY KEY,
+    account VARCHAR(100) NOT NULL,
+    exchange VARCHAR(20) NOT NULL,
+    symbol VARCHAR(20) NOT NULL,
+    rule VARCHAR(10) NOT NULL CHECK (rule IN ('allow', 'deny')),
+    reason TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- 创建索引（缩短索引名称）
+CREATE UNIQUE INDEX IF NOT EXISTS idx_account_symbol_rules_unique
+ON account_symbol_rules (account, exchange, symbol);
+
+-- 添加表注释
+COMMENT ON TABLE account_symbol_rules IS '账户交易对黑白名单';
+
+-- 添加字段注释
+COMMENT ON COLUMN account_symbol_rules.id IS 'ID';
+COMMENT ON COLUMN account_symbol_rules.account IS '账户标识，*表示所有账户';
+COMMENT ON COLUMN account_symbol_rules.exchange IS '交易所';
+COMMENT ON COLUMN account_symbol_rules.symbol IS '交易对';
+COMMENT ON COLUMN account_symbol_rules.rule IS '规则: allow 白名单, deny 黑名单';
+COMMENT ON COLUMN account_symbol_rules.reason IS '原因，如流动性不足、监管限制';
+COMMENT ON COLUMN account_symbol_rules.created_at IS '创建时间';
+COMMENT ON COLUMN account_symbol_rules.updated_at IS '更新时间';
@@ -0,0 +1,6 @@
+-- Add down migration script here
+-- 工作流部署版本
+DROP TABLE IF EXISTS workflow_deployments;
+DROP INDEX IF EXISTS idx_workflow_deployments_version;
+DROP INDEX IF EXISTS idx_workflow_deployments_hash;
+DROP INDEX IF EXISTS idx_workflow_deployments_content_hash;
@@ -0,0 +1,31 @@
+-- Add up migration script here
+-- 工作流部署版本
+CREATE TABLE IF NOT EXISTS workflow_deployments (
+    id SERIAL PRIMARY KEY,
+    workflow_id VARCHAR(21) NOT NULL,
+    version INTEGER NOT NULL,
+    content_hash VARCHAR(64) NOT NULL,
+    definition TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- 创建索引（缩短索引名称）
+CREATE UNIQUE INDEX IF NOT EXISTS idx_workflow_deployments_version
+ON workflow_deployments (workflow_id, version);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_workflow_deployments_hash
+ON workflow_deployments (workflow_id, content_hash);
+
+CREATE INDEX IF NOT EXISTS idx_workflow_deployments_content_hash
+ON workflow_deployments (content_hash);
+
+-- 添加表注释
+COMMENT ON TABLE workflow_deployments IS '工作流部署版本';
+
+-- 添加字段注释
+COMMENT ON COLUMN workflow_deployments.id IS 'ID';
+COMMENT ON COLUMN workflow_deployments.workflow_id IS '逻辑工作流ID，同一工作流的各个版本共用';
+COMMENT ON COLUMN workflow_deployments.version IS '版本号，从1开始递增';
+COMMENT ON COLUMN workflow_deployments.content_hash IS '规范化后的工作流定义的SHA-256哈希';
+COMMENT ON COLUMN workflow_deployments.definition IS '部署时提交的工作流定义(JSON)';
+COMMENT ON COLUMN workflow_deployments.created_at IS '创建时间';
@@ -0,0 +1,4 @@
+-- Add down migration script here
+ALTER TABLE klines DROP COLUMN IF EXISTS revision;
+ALTER TABLE klines DROP COLUMN IF EXISTS is_closed;
+ALTER TABLE klines DROP COLUMN IF EXISTS source;
@@ -0,0 +1,10 @@
+-- Add up migration script here
+-- K线数据来源、是否已收盘和修订次数，用于实时采集与历史回填同时写入时的冲突处理，旧数据视为已收盘的回填数据
+ALTER TABLE klines ADD COLUMN IF NOT EXISTS source VARCHAR(20) NOT NULL DEFAULT 'backfill';
+ALTER TABLE klines ADD COLUMN IF NOT EXISTS is_closed BOOLEAN NOT NULL DEFAULT TRUE;
+ALTER TABLE klines ADD COLUMN IF NOT EXISTS revision INT NOT NULL DEFAULT 1;
+
+-- 添加字段注释
+COMMENT ON COLUMN klines.source IS '数据来源: backfill 历史回填, live 实时采集';
+COMMENT ON COLUMN klines.is_closed IS '是否已收盘';
+COMMENT ON COLUMN klines.revision IS '修订次数，数据每次变化加1';
@@ -0,0 +1,5 @@
+-- Add down migration script here
+-- 工作流运行检查点
+DROP TABLE IF EXISTS workflow_runs;
+DROP INDEX IF EXISTS idx_workflow_runs_workflow_id;
+DROP INDEX IF EXISTS idx_workflow_runs_status;
@@ -0,0 +1,30 @@
+-- Add up migration script here
+-- 工作流运行检查点
+CREATE TABLE IF NOT EXISTS workflow_runs (
+    id SERIAL PRIMARY KEY,
+    workflow_id VARCHAR(21) NOT NULL,
+    status VARCHAR(20) NOT NULL,
+    checkpoint TEXT NOT NULL,
+    checkpointed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- 创建索引（缩短索引名称）
+CREATE UNIQUE INDEX IF NOT EXISTS idx_workflow_runs_workflow_id
+ON workflow_runs (workflow_id);
+
+CREATE INDEX IF NOT EXISTS idx_workflow_runs_status
+ON workflow_runs (status);
+
+-- 添加表注释
+COMMENT ON TABLE workflow_runs IS '工作流运行检查点';
+
+-- 添加字段注释
+COMMENT ON COLUMN workflow_runs.id IS 'ID';
+COMMENT ON COLUMN workflow_runs.workflow_id IS '工作流ID';
+COMMENT ON COLUMN workflow_runs.status IS '状态';
+COMMENT ON COLUMN workflow_runs.checkpoint IS '最近一次检查点，包含各节点运行时数据的工作流(JSON)';
+COMMENT ON COLUMN workflow_runs.checkpointed_at IS '最近一次检查点时间';
+COMMENT ON COLUMN workflow_runs.created_at IS '创建时间';
+COMMENT ON COLUMN workflow_runs.updated_at IS '更新时间';
@@ -0,0 +1,4 @@
+-- Add down migration script here
+ALTER TABLE klines DROP COLUMN IF EXISTS close_time;
+
+COMMENT ON COLUMN klines.interval IS '时间间隔';
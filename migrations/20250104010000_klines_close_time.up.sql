@@ -0,0 +1,7 @@
+-- Add up migration script here
+-- 按成交笔数聚合的K线没有固定时长，需要记录收盘时间；开盘时间为 TIMESTAMPTZ，已支持毫秒精度的亚秒级K线
+ALTER TABLE klines ADD COLUMN IF NOT EXISTS close_time TIMESTAMPTZ;
+
+-- 添加字段注释
+COMMENT ON COLUMN klines.close_time IS '收盘时间，按成交笔数聚合的K线为最后一笔成交的时间，按时间聚合的K线为空';
+COMMENT ON COLUMN klines.interval IS '时间间隔，如 100ms、1m，按成交笔数聚合时如 100t';
@@ -0,0 +1,3 @@
+-- Add down migration script here
+ALTER TABLE workflow_runs DROP COLUMN IF EXISTS owner;
+ALTER TABLE workflow_runs DROP COLUMN IF EXISTS heartbeat_at;
@@ -0,0 +1,8 @@
+-- Add up migration script here
+-- 工作流运行租约：持有租约的实例定期续期心跳，心跳超时后由备用实例接管
+ALTER TABLE workflow_runs ADD COLUMN IF NOT EXISTS owner VARCHAR(64) NOT NULL DEFAULT '';
+ALTER TABLE workflow_runs ADD COLUMN IF NOT EXISTS heartbeat_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+
+-- 添加字段注释
+COMMENT ON COLUMN workflow_runs.owner IS '运行工作流的实例ID，为空时表示未启用租约';
+COMMENT ON COLUMN workflow_runs.heartbeat_at IS '最近一次心跳时间';
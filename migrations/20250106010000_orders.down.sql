@@ -0,0 +1,5 @@
+-- Add down migration script here
+-- 策略订单
+DROP TABLE IF EXISTS orders;
+DROP INDEX IF EXISTS idx_orders_order_id;
+DROP INDEX IF EXISTS idx_orders_lookup;
@@ -0,0 +1,54 @@
+-- Add up migration script here
+-- 策略订单
+CREATE TABLE IF NOT EXISTS orders (
+    id SERIAL PRIMARY KEY,
+    workflow_id VARCHAR(21) NOT NULL,
+    node_id SMALLINT NOT NULL,
+    exchange VARCHAR(20) NOT NULL,
+    symbol VARCHAR(20) NOT NULL,
+    order_id VARCHAR(64) NOT NULL,
+    client_order_id VARCHAR(64),
+    order_type VARCHAR(20) NOT NULL,
+    order_side VARCHAR(10) NOT NULL,
+    order_status VARCHAR(20) NOT NULL,
+    price NUMERIC NOT NULL,
+    avg_price NUMERIC NOT NULL,
+    orig_qty NUMERIC NOT NULL,
+    executed_qty NUMERIC NOT NULL,
+    cumulative_quote_qty NUMERIC NOT NULL,
+    order_time TIMESTAMP WITH TIME ZONE NOT NULL,
+    update_time TIMESTAMP WITH TIME ZONE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+-- 创建索引（缩短索引名称）
+CREATE UNIQUE INDEX IF NOT EXISTS idx_orders_order_id
+ON orders (workflow_id, node_id, exchange, order_id);
+
+CREATE INDEX IF NOT EXISTS idx_orders_lookup
+ON orders (workflow_id, node_id, exchange, symbol, order_time);
+
+-- 添加表注释
+COMMENT ON TABLE orders IS '策略订单';
+
+-- 添加字段注释
+COMMENT ON COLUMN orders.id IS 'ID';
+COMMENT ON COLUMN orders.workflow_id IS '工作流ID';
+COMMENT ON COLUMN orders.node_id IS '策略节点ID';
+COMMENT ON COLUMN orders.exchange IS '交易所';
+COMMENT ON COLUMN orders.symbol IS '交易对';
+COMMENT ON COLUMN orders.order_id IS '交易所订单ID';
+COMMENT ON COLUMN orders.client_order_id IS '用户自己设置的ID';
+COMMENT ON COLUMN orders.order_type IS '订单类型: MARKET/LIMIT/STOP_LOSS_LIMIT/LIMIT_MAKER';
+COMMENT ON COLUMN orders.order_side IS '订单方向: BUY/SELL';
+COMMENT ON COLUMN orders.order_status IS '订单状态: NEW/PARTIALLY_FILLED/FILLED/CANCELED/PENDING_CANCEL/REJECTED/EXPIRED';
+COMMENT ON COLUMN orders.price IS '订单价格';
+COMMENT ON COLUMN orders.avg_price IS '平均成交价格';
+COMMENT ON COLUMN orders.orig_qty IS '原始订单数量';
+COMMENT ON COLUMN orders.executed_qty IS '累计成交数量';
+COMMENT ON COLUMN orders.cumulative_quote_qty IS '累计成交金额';
+COMMENT ON COLUMN orders.order_time IS '下单时间';
+COMMENT ON COLUMN orders.update_time IS '交易所最后更新时间';
+COMMENT ON COLUMN orders.created_at IS '创建时间';
+COMMENT ON COLUMN orders.updated_at IS '更新时间';
@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS kline_sync_states;
+DROP INDEX IF EXISTS idx_kline_sync_states_unique;
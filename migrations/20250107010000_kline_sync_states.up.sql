@@ -0,0 +1,31 @@
+-- Add up migration script here
+-- K线同步状态
+CREATE TABLE IF NOT EXISTS kline_sync_states (
+    id SERIAL PRIMARY KEY,
+    exchange VARCHAR(20) NOT NULL,
+    market VARCHAR(20) NOT NULL,
+    symbol VARCHAR(20) NOT NULL,
+    interval VARCHAR(10) NOT NULL,
+    start_time TIMESTAMPTZ NOT NULL,
+    last_open_time TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- 创建索引（缩短索引名称）
+CREATE UNIQUE INDEX IF NOT EXISTS idx_kline_sync_states_unique
+ON kline_sync_states (exchange, market, symbol, interval);
+
+-- 添加表注释
+COMMENT ON TABLE kline_sync_states IS 'K线同步状态，记录已连续同步的时间区间';
+
+-- 添加字段注释
+COMMENT ON COLUMN kline_sync_states.id IS 'ID';
+COMMENT ON COLUMN kline_sync_states.exchange IS '交易所';
+COMMENT ON COLUMN kline_sync_states.market IS '市场';
+COMMENT ON COLUMN kline_sync_states.symbol IS '交易对';
+COMMENT ON COLUMN kline_sync_states.interval IS '时间间隔';
+COMMENT ON COLUMN kline_sync_states.start_time IS '已同步区间的开始时间';
+COMMENT ON COLUMN kline_sync_states.last_open_time IS '最后一根已同步K线的开盘时间';
+COMMENT ON COLUMN kline_sync_states.created_at IS '创建时间';
+COMMENT ON COLUMN kline_sync_states.updated_at IS '更新时间';
@@ -0,0 +1,39 @@
+-- Add down migration script here
+-- 恢复为不分区的K线表
+ALTER TABLE klines RENAME TO klines_partitioned;
+ALTER INDEX idx_klines_unique RENAME TO idx_klines_partitioned_unique;
+
+CREATE TABLE klines (
+    id INTEGER NOT NULL DEFAULT nextval('klines_id_seq') PRIMARY KEY,
+    exchange VARCHAR(20) NOT NULL,
+    market VARCHAR(20) NOT NULL,
+    symbol VARCHAR(20) NOT NULL,
+    interval VARCHAR(10) NOT NULL,
+    open_time TIMESTAMPTZ NOT NULL,
+    open_price NUMERIC(20,8) NOT NULL,
+    high_price NUMERIC(20,8) NOT NULL,
+    low_price NUMERIC(20,8) NOT NULL,
+    close_price NUMERIC(20,8) NOT NULL,
+    volume NUMERIC(30,8) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    taker_buy_volume NUMERIC(30,8) NOT NULL DEFAULT 0,
+    source VARCHAR(20) NOT NULL DEFAULT 'backfill',
+    is_closed BOOLEAN NOT NULL DEFAULT TRUE,
+    revision INT NOT NULL DEFAULT 1,
+    close_time TIMESTAMPTZ
+);
+
+ALTER SEQUENCE klines_id_seq OWNED BY klines.id;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_klines_unique
+ON klines (exchange, market, symbol, interval, open_time);
+
+INSERT INTO klines (id, exchange, market, symbol, interval, open_time, open_price, high_price, low_price, close_price, volume, created_at, updated_at, taker_buy_volume, source, is_closed, revision, close_time)
+SELECT id, exchange, market, symbol, interval, open_time, open_price, high_price, low_price, close_price, volume, created_at, updated_at, taker_buy_volume, source, is_closed, revision, close_time
+FROM klines_partitioned;
+
+DROP TABLE klines_partitioned;
+DROP FUNCTION IF EXISTS create_klines_partition(DATE);
+
+COMMENT ON TABLE klines IS '历史K线数据';
@@ -0,0 +1,111 @@
+-- Add up migration script here
+-- K线表按开盘时间分区，每月一个分区，按时间范围查询时只扫描相关月份的分区。
+-- 没有对应分区的数据写入默认分区，创建分区时从默认分区移入
+ALTER TABLE klines RENAME TO klines_legacy;
+ALTER TABLE klines_legacy RENAME CONSTRAINT klines_pkey TO klines_legacy_pkey;
+ALTER INDEX idx_klines_unique RENAME TO idx_klines_legacy_unique;
+
+CREATE TABLE klines (
+    id INTEGER NOT NULL DEFAULT nextval('klines_id_seq'),
+    exchange VARCHAR(20) NOT NULL,
+    market VARCHAR(20) NOT NULL,
+    symbol VARCHAR(20) NOT NULL,
+    interval VARCHAR(10) NOT NULL,
+    open_time TIMESTAMPTZ NOT NULL,
+    open_price NUMERIC(20,8) NOT NULL,
+    high_price NUMERIC(20,8) NOT NULL,
+    low_price NUMERIC(20,8) NOT NULL,
+    close_price NUMERIC(20,8) NOT NULL,
+    volume NUMERIC(30,8) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    taker_buy_volume NUMERIC(30,8) NOT NULL DEFAULT 0,
+    source VARCHAR(20) NOT NULL DEFAULT 'backfill',
+    is_closed BOOLEAN NOT NULL DEFAULT TRUE,
+    revision INT NOT NULL DEFAULT 1,
+    close_time TIMESTAMPTZ,
+    PRIMARY KEY (id, open_time)
+) PARTITION BY RANGE (open_time);
+
+ALTER SEQUENCE klines_id_seq OWNED BY klines.id;
+
+-- 分区表的唯一索引需要包含分区键
+CREATE UNIQUE INDEX IF NOT EXISTS idx_klines_unique
+ON klines (exchange, market, symbol, interval, open_time);
+
+CREATE TABLE IF NOT EXISTS klines_default PARTITION OF klines DEFAULT;
+
+-- 创建指定月份的分区(UTC)，已存在时跳过
+CREATE OR REPLACE FUNCTION create_klines_partition(month_start DATE) RETURNS VOID AS $$
+DECLARE
+    partition_name TEXT := format('klines_%s', to_char(month_start, 'YYYYMM'));
+    range_start TIMESTAMPTZ := date_trunc('month', month_start::TIMESTAMP) AT TIME ZONE 'UTC';
+    range_end TIMESTAMPTZ := (date_trunc('month', month_start::TIMESTAMP) + INTERVAL '1 month') AT TIME ZONE 'UTC';
+BEGIN
+    -- 避免并发创建同一个分区
+    PERFORM pg_advisory_xact_lock(hashtext('create_klines_partition'));
+
+    IF to_regclass(partition_name) IS NOT NULL THEN
+        RETURN;
+    END IF;
+
+    EXECUTE format('CREATE TABLE %I (LIKE klines INCLUDING DEFAULTS)', partition_name);
+
+    -- 默认分区中属于该月的数据移入新分区，否则无法挂载
+    EXECUTE format(
+        'WITH moved AS (DELETE FROM klines_default WHERE open_time >= $1 AND open_time < $2 RETURNING *) INSERT INTO %I SELECT * FROM moved',
+        partition_name
+    ) USING range_start, range_end;
+
+    EXECUTE format(
+        'ALTER TABLE klines ATTACH PARTITION %I FOR VALUES FROM (%L) TO (%L)',
+        partition_name,
+        range_start,
+        range_end
+    );
+END;
+$$ LANGUAGE plpgsql;
+
+-- 按已有数据的月份和当前月份创建分区，再迁移数据
+DO $$
+DECLARE
+    month_start DATE;
+BEGIN
+    FOR month_start IN
+        SELECT DISTINCT date_trunc('month', open_time AT TIME ZONE 'UTC')::DATE FROM klines_legacy
+        UNION
+        SELECT date_trunc('month', NOW() AT TIME ZONE 'UTC')::DATE
+    LOOP
+        PERFORM create_klines_partition(month_start);
+    END LOOP;
+END;
+$$;
+
+INSERT INTO klines (id, exchange, market, symbol, interval, open_time, open_price, high_price, low_price, close_price, volume, created_at, updated_at, taker_buy_volume, source, is_closed, revision, close_time)
+SELECT id, exchange, market, symbol, interval, open_time, open_price, high_price, low_price, close_price, volume, created_at, updated_at, taker_buy_volume, source, is_closed, revision, close_time
+FROM klines_legacy;
+
+DROP TABLE klines_legacy;
+
+-- 添加表注释
+COMMENT ON TABLE klines IS '历史K线数据，按开盘时间每月分区';
+
+-- 添加字段注释
+COMMENT ON COLUMN klines.id IS 'ID';
+COMMENT ON COLUMN klines.exchange IS '交易所';
+COMMENT ON COLUMN klines.market IS '市场';
+COMMENT ON COLUMN klines.symbol IS '交易对';
+COMMENT ON COLUMN klines.interval IS '时间间隔，如 100ms、1m，按成交笔数聚合时如 100t';
+COMMENT ON COLUMN klines.open_time IS '开盘时间';
+COMMENT ON COLUMN klines.open_price IS '开盘价格';
+COMMENT ON COLUMN klines.high_price IS '最高价格';
+COMMENT ON COLUMN klines.low_price IS '最低价格';
+COMMENT ON COLUMN klines.close_price IS '收盘价格';
+COMMENT ON COLUMN klines.volume IS '成交量';
+COMMENT ON COLUMN klines.created_at IS '创建时间';
+COMMENT ON COLUMN klines.updated_at IS '更新时间';
+COMMENT ON COLUMN klines.taker_buy_volume IS '主动买入成交量';
+COMMENT ON COLUMN klines.source IS '数据来源: backfill 历史回填, live 实时采集';
+COMMENT ON COLUMN klines.is_closed IS '是否已收盘';
+COMMENT ON COLUMN klines.revision IS '修订次数，数据每次变化加1';
+COMMENT ON COLUMN klines.close_time IS '收盘时间，按成交笔数聚合的K线为最后一笔成交的时间，按时间聚合的K线为空';
@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS optimization_runs;
+DROP INDEX IF EXISTS idx_optimization_runs_unique;
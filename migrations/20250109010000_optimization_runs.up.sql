@@ -0,0 +1,41 @@
+-- Add up migration script here
+-- 参数扫描结果
+CREATE TABLE IF NOT EXISTS optimization_runs (
+    id SERIAL PRIMARY KEY,
+    optimization_id VARCHAR(64) NOT NULL,
+    params TEXT NOT NULL,
+    start_time TIMESTAMPTZ NOT NULL,
+    end_time TIMESTAMPTZ NOT NULL,
+    status VARCHAR(20) NOT NULL,
+    total_return NUMERIC(30,18),
+    total_pnl NUMERIC(30,8),
+    max_drawdown NUMERIC(30,18),
+    win_rate NUMERIC(30,18),
+    total_trades BIGINT,
+    error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- 创建索引（缩短索引名称）
+CREATE UNIQUE INDEX IF NOT EXISTS idx_optimization_runs_unique
+ON optimization_runs (optimization_id, params, start_time, end_time);
+
+-- 添加表注释
+COMMENT ON TABLE optimization_runs IS '参数扫描结果，每个参数组合一条回测记录';
+
+-- 添加字段注释
+COMMENT ON COLUMN optimization_runs.id IS 'ID';
+COMMENT ON COLUMN optimization_runs.optimization_id IS '参数扫描ID，默认为工作流模板的内容哈希';
+COMMENT ON COLUMN optimization_runs.params IS '参数组合(JSON)，键为 节点ID:参数位置';
+COMMENT ON COLUMN optimization_runs.start_time IS '回测开始时间';
+COMMENT ON COLUMN optimization_runs.end_time IS '回测结束时间';
+COMMENT ON COLUMN optimization_runs.status IS '状态';
+COMMENT ON COLUMN optimization_runs.total_return IS '总收益率';
+COMMENT ON COLUMN optimization_runs.total_pnl IS '总盈亏';
+COMMENT ON COLUMN optimization_runs.max_drawdown IS '最大回撤比例';
+COMMENT ON COLUMN optimization_runs.win_rate IS '胜率';
+COMMENT ON COLUMN optimization_runs.total_trades IS '总交易次数';
+COMMENT ON COLUMN optimization_runs.error IS '回测失败的原因';
+COMMENT ON COLUMN optimization_runs.created_at IS '创建时间';
+COMMENT ON COLUMN optimization_runs.updated_at IS '更新时间';
@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS strategy_net_values;
+DROP INDEX IF EXISTS idx_strategy_net_values_unique;
@@ -0,0 +1,37 @@
+-- Add up migration script here
+-- 策略净值快照
+CREATE TABLE IF NOT EXISTS strategy_net_values (
+    id SERIAL PRIMARY KEY,
+    workflow_id VARCHAR(21) NOT NULL,
+    node_id SMALLINT NOT NULL,
+    node_name VARCHAR(20) NOT NULL,
+    exchange VARCHAR(20) NOT NULL,
+    symbol VARCHAR(20) NOT NULL,
+    quote_asset VARCHAR(20) NOT NULL,
+    value NUMERIC NOT NULL,
+    net_value NUMERIC NOT NULL,
+    drawdown NUMERIC NOT NULL,
+    snapshot_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+-- 创建索引（缩短索引名称）
+CREATE UNIQUE INDEX IF NOT EXISTS idx_strategy_net_values_unique
+ON strategy_net_values (workflow_id, node_id, exchange, symbol, snapshot_at);
+
+-- 添加表注释
+COMMENT ON TABLE strategy_net_values IS '策略净值快照，用于绘制净值曲线';
+
+-- 添加字段注释
+COMMENT ON COLUMN strategy_net_values.id IS 'ID';
+COMMENT ON COLUMN strategy_net_values.workflow_id IS '工作流ID';
+COMMENT ON COLUMN strategy_net_values.node_id IS '策略节点ID';
+COMMENT ON COLUMN strategy_net_values.node_name IS '策略节点名称';
+COMMENT ON COLUMN strategy_net_values.exchange IS '交易所';
+COMMENT ON COLUMN strategy_net_values.symbol IS '交易对';
+COMMENT ON COLUMN strategy_net_values.quote_asset IS '计价资产';
+COMMENT ON COLUMN strategy_net_values.value IS '持仓市值(计价资产)';
+COMMENT ON COLUMN strategy_net_values.net_value IS '净值，扣除外部资金变动的影响';
+COMMENT ON COLUMN strategy_net_values.drawdown IS '相对历史最高市值的回撤';
+COMMENT ON COLUMN strategy_net_values.snapshot_at IS '快照时间，回测时为模拟时间';
+COMMENT ON COLUMN strategy_net_values.created_at IS '创建时间';
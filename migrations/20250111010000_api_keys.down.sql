@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS api_keys;
+DROP INDEX IF EXISTS idx_api_keys_name;
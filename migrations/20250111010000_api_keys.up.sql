@@ -0,0 +1,26 @@
+-- Add up migration script here
+-- 交易所API密钥，密钥使用主密钥加密后保存
+CREATE TABLE IF NOT EXISTS api_keys (
+    id SERIAL PRIMARY KEY,
+    name VARCHAR(50) NOT NULL,
+    exchange VARCHAR(20) NOT NULL,
+    api_key TEXT NOT NULL,
+    secret_key TEXT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+-- 创建索引
+CREATE UNIQUE INDEX IF NOT EXISTS idx_api_keys_name ON api_keys (name);
+
+-- 添加表注释
+COMMENT ON TABLE api_keys IS '交易所API密钥，账户节点按名称引用';
+
+-- 添加字段注释
+COMMENT ON COLUMN api_keys.id IS 'ID';
+COMMENT ON COLUMN api_keys.name IS '密钥名称，工作流中按名称引用';
+COMMENT ON COLUMN api_keys.exchange IS '交易所';
+COMMENT ON COLUMN api_keys.api_key IS 'API Key 密文';
+COMMENT ON COLUMN api_keys.secret_key IS 'Secret Key 密文';
+COMMENT ON COLUMN api_keys.created_at IS '创建时间';
+COMMENT ON COLUMN api_keys.updated_at IS '更新时间';